@@ -34,6 +34,8 @@ pub enum BinanceFuturesError {
     InstrumentNotFound,
     #[error("InvalidRequest")]
     InvalidRequest,
+    #[error("InvalidArg: {0}")]
+    InvalidArg(&'static str),
     #[error("ListenKeyExpired")]
     ListenKeyExpired,
     #[error("ConnectionInterrupted")]
@@ -59,6 +61,7 @@ impl From<BinanceFuturesError> for Value {
         match value {
             BinanceFuturesError::InstrumentNotFound => Value::String(value.to_string()),
             BinanceFuturesError::InvalidRequest => Value::String(value.to_string()),
+            BinanceFuturesError::InvalidArg(_) => Value::String(value.to_string()),
             BinanceFuturesError::ReqError(error) => {
                 let mut map = HashMap::new();
                 if let Some(code) = error.status() {
@@ -255,6 +258,7 @@ impl Connector for BinanceFutures {
                             order.qty,
                             order.order_type,
                             order.time_in_force,
+                            order.display_qty,
                         )
                         .await;
                     match result {
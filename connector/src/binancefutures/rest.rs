@@ -168,7 +168,16 @@ impl BinanceFuturesClient {
         qty: f64,
         order_type: OrdType,
         time_in_force: TimeInForce,
+        display_qty: f64,
     ) -> Result<OrderResponse, BinanceFuturesError> {
+        // Stop orders would need a `stopPrice`, which is not sent.
+        if order_type.is_stop() {
+            return Err(BinanceFuturesError::InvalidArg("order_type"));
+        }
+        // Iceberg orders are not supported, so they are not sent as fully displayed orders.
+        if display_qty > 0.0 && display_qty < qty {
+            return Err(BinanceFuturesError::InvalidArg("display_qty"));
+        }
         let mut body = String::with_capacity(200);
         body.push_str("newClientOrderId=");
         body.push_str(client_order_id);
@@ -202,6 +211,10 @@ impl BinanceFuturesClient {
         if orders.len() > 5 {
             return Err(BinanceFuturesError::InvalidRequest);
         }
+        // Stop orders would need a `stopPrice`, which is not sent.
+        if orders.iter().any(|order| order.6.is_stop()) {
+            return Err(BinanceFuturesError::InvalidArg("order_type"));
+        }
         let mut body = String::with_capacity(2000 * orders.len());
         body.push_str("{\"batchOrders\":[");
         for (i, order) in orders.iter().enumerate() {
@@ -35,6 +35,8 @@ pub enum BinanceSpotError {
     InstrumentNotFound,
     #[error("InvalidRequest")]
     InvalidRequest,
+    #[error("InvalidArg: {0}")]
+    InvalidArg(&'static str),
     #[error("ListenKeyExpired")]
     ListenKeyExpired,
     #[error("ConnectionInterrupted")]
@@ -60,6 +62,7 @@ impl From<BinanceSpotError> for Value {
         match value {
             BinanceSpotError::InstrumentNotFound => Value::String(value.to_string()),
             BinanceSpotError::InvalidRequest => Value::String(value.to_string()),
+            BinanceSpotError::InvalidArg(_) => Value::String(value.to_string()),
             BinanceSpotError::ReqError(error) => {
                 let mut map = HashMap::new();
                 if let Some(code) = error.status() {
@@ -258,6 +261,7 @@ impl Connector for BinanceSpot {
                             order.qty,
                             order.order_type,
                             order.time_in_force,
+                            order.display_qty,
                         )
                         .await;
                     match result {
@@ -190,6 +190,7 @@ impl BinanceSpotClient {
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub async fn submit_order(
         &self,
         client_order_id: &str,
@@ -200,7 +201,16 @@ impl BinanceSpotClient {
         qty: f64,
         order_type: OrdType,
         time_in_force: TimeInForce,
+        display_qty: f64,
     ) -> Result<OrderResponse, BinanceSpotError> {
+        // Stop orders would need a `stopPrice`, which is not sent.
+        if order_type.is_stop() {
+            return Err(BinanceSpotError::InvalidArg("order_type"));
+        }
+        // Iceberg orders are not supported, so they are not sent as fully displayed orders.
+        if display_qty > 0.0 && display_qty < qty {
+            return Err(BinanceSpotError::InvalidArg("display_qty"));
+        }
         let mut body = String::with_capacity(200);
         body.push_str("newClientOrderId=");
         body.push_str(client_order_id);
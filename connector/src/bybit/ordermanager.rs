@@ -98,6 +98,9 @@ impl OrderManager {
         category: &str,
         order: Order,
     ) -> Result<BybitOrder, BybitError> {
+        if order.is_iceberg() {
+            return Err(BybitError::InvalidArg("display_qty"));
+        }
         let price_prec = get_precision(order.tick_size);
         let order_link_id = format!("{}{}", self.prefix, generate_rand_string(16));
         let bybit_order = BybitOrder {
@@ -113,7 +116,9 @@ impl OrderManager {
                 match order.order_type {
                     OrdType::Limit => "Limit".to_string(),
                     OrdType::Market => "Market".to_string(),
-                    OrdType::Unsupported => return Err(BybitError::InvalidArg("order_type")),
+                    OrdType::StopMarket | OrdType::StopLimit | OrdType::Unsupported => {
+                        return Err(BybitError::InvalidArg("order_type"));
+                    }
                 }
             }),
             qty: Some(format!("{:.5}", order.qty)),
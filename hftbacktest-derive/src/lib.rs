@@ -216,7 +216,7 @@ pub fn build_asset(input: TokenStream) -> TokenStream {
                             }
                         } else {
                             quote! {
-                                #qm_ident::new();
+                                #qm_ident::new(#(#qm_args.clone()),*);
                             }
                         };
 
@@ -232,6 +232,27 @@ pub fn build_asset(input: TokenStream) -> TokenStream {
                             (Ident::new("Local", Span::call_site()), em_ident.clone())
                         };
 
+                        let stop_orders = if l3 {
+                            quote! {}
+                        } else {
+                            quote! {
+                                .stop_orders(StopOrders::new(
+                                    #asset.trigger_source,
+                                    #asset.stop_slippage_ticks,
+                                ))
+                                .post_only(#asset.post_only)
+                                .self_trade_prevention(#asset.self_trade_prevention)
+                            }
+                        };
+
+                        let fill_ratio = if !l3 && em_ident == "PartialFillExchange" {
+                            quote! {
+                                .fill_ratio(#asset.fill_ratio)
+                            }
+                        } else {
+                            quote! {}
+                        };
+
                         let depth_construct = match marketdepth.to_string().as_str() {
                             "HashMapMarketDepth" => {
                                 quote! {
@@ -297,7 +318,7 @@ pub fn build_asset(input: TokenStream) -> TokenStream {
                                 State::new(asset_type.clone(), fee_model.clone()),
                                 #asset.last_trades_cap,
                                 order_l2e,
-                            ));
+                            ).funding(#asset.funding.clone()));
 
                             let mut market_depth = #depth_construct;
                             match #asset.initial_snapshot.as_ref() {
@@ -318,7 +339,7 @@ pub fn build_asset(input: TokenStream) -> TokenStream {
                                 State::new(asset_type, fee_model.clone()),
                                 queue_model,
                                 order_e2l,
-                            ));
+                            )#stop_orders #fill_ratio);
 
                             Asset {
                                 local,
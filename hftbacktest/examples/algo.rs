@@ -108,6 +108,8 @@ where
                     order_qty,
                     TimeInForce::GTX,
                     OrdType::Limit,
+                    0.0,
+                    0.0,
                     false,
                 )
                 .unwrap();
@@ -154,6 +156,8 @@ where
                     order_qty,
                     TimeInForce::GTX,
                     OrdType::Limit,
+                    0.0,
+                    0.0,
                     false,
                 )
                 .unwrap();
@@ -53,6 +53,8 @@ where
         qty: f64,
         order_type: OrdType,
         time_in_force: TimeInForce,
+        trigger_price: f64,
+        display_qty: f64,
         current_timestamp: i64,
     ) -> Result<(), BacktestError> {
         self.local.submit_order(
@@ -62,6 +64,8 @@ where
             qty,
             order_type,
             time_in_force,
+            trigger_price,
+            display_qty,
             current_timestamp,
         )
     }
@@ -10,7 +10,16 @@ use std::{
     slice::SliceIndex,
 };
 
-pub use npy::{Field, NpyDTyped, NpyHeader, read_npy_file, read_npz_file, write_npy};
+pub use npy::{
+    Field,
+    NpyDTyped,
+    NpyHeader,
+    read_npy_file,
+    read_npy_header,
+    read_npz_file,
+    write_npy,
+    write_npy_header,
+};
 pub use reader::{Cache, DataPreprocess, DataSource, FeedLatencyAdjustment, Reader, ReaderBuilder};
 
 use crate::utils::{AlignedArray, CACHE_LINE_SIZE};
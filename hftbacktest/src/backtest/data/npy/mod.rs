@@ -358,11 +358,20 @@ pub fn read_npz_file<D: NpyDTyped + Clone>(filepath: &str, name: &str) -> std::i
 }
 
 pub fn write_npy<W: Write, T: NpyDTyped>(write: &mut W, data: &[T]) -> std::io::Result<()> {
+    write_npy_header::<W, T>(write, data.len())?;
+    write.write_all(vec_as_bytes(data))?;
+    Ok(())
+}
+
+/// Writes only the header of a one-dimensional `.npy` array of `len` elements of `T`. The caller
+/// must write exactly `len` elements in their in-memory representation right after it. This
+/// allows writing an array that is not held in memory at once.
+pub fn write_npy_header<W: Write, T: NpyDTyped>(write: &mut W, len: usize) -> std::io::Result<()> {
     let descr = T::descr();
     let header = NpyHeader {
         descr,
         fortran_order: false,
-        shape: vec![data.len()],
+        shape: vec![len],
     };
 
     write.write_all(b"\x93NUMPY\x01\x00")?;
@@ -370,10 +379,58 @@ pub fn write_npy<W: Write, T: NpyDTyped>(write: &mut W, data: &[T]) -> std::io::
     let len = header_str.len() as u16;
     write.write_all(&len.to_le_bytes())?;
     write.write_all(header_str.as_bytes())?;
-    write.write_all(vec_as_bytes(data))?;
     Ok(())
 }
 
+/// Reads only the header of a one-dimensional `.npy` array of `T` and returns the number of
+/// elements, which follow right after it in their in-memory representation. Together with
+/// [`write_npy_header`], this allows processing an array that is not held in memory at once.
+pub fn read_npy_header<R: Read, T: NpyDTyped>(read: &mut R) -> std::io::Result<usize> {
+    let mut preamble = [0u8; 10];
+    read.read_exact(&mut preamble)?;
+    if &preamble[0..6] != b"\x93NUMPY" {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            "must start with \\x93NUMPY",
+        ));
+    }
+    if &preamble[6..8] != b"\x01\x00" {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            "support only version 1.0",
+        ));
+    }
+    let header_len = u16::from_le_bytes([preamble[8], preamble[9]]) as usize;
+    let mut header = vec![0u8; header_len];
+    read.read_exact(&mut header)?;
+    let header = String::from_utf8(header)
+        .map_err(|err| Error::new(ErrorKind::InvalidData, err.to_string()))?;
+    let header = NpyHeader::from_header(&header)?;
+
+    if header.fortran_order {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            "fortran order is unsupported",
+        ));
+    }
+    if T::descr().len() != header.descr.len() {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!(
+                "expected {} fields, found {}",
+                T::descr().len(),
+                header.descr.len()
+            ),
+        ));
+    }
+    check_field_consistency(&T::descr(), &header.descr)
+        .map_err(|err| Error::new(ErrorKind::InvalidData, err))?;
+    match header.shape[..] {
+        [len] => Ok(len),
+        _ => Err(Error::new(ErrorKind::InvalidData, "only 1-d is supported")),
+    }
+}
+
 fn vec_as_bytes<T>(vec: &[T]) -> &[u8] {
     let len = std::mem::size_of_val(vec);
     let ptr = vec.as_ptr() as *const u8;
@@ -18,9 +18,19 @@ use crate::{
         assettype::AssetType,
         data::{Data, FeedLatencyAdjustment, NpyDTyped},
         evs::{EventIntentKind, EventSet},
-        models::{LatencyModel, QueueModel},
+        models::{FundingFeeModel, LatencyModel, QueueModel},
         order::order_bus,
-        proc::{Local, LocalProcessor, NoPartialFillExchange, PartialFillExchange, Processor},
+        proc::{
+            Local,
+            LocalProcessor,
+            NoPartialFillExchange,
+            PartialFillExchange,
+            PostOnly,
+            Processor,
+            SelfTradePrevention,
+            StopOrders,
+            TriggerSource,
+        },
         state::State,
     },
     depth::{L2MarketDepth, L3MarketDepth, MarketDepth},
@@ -142,6 +152,12 @@ pub struct L2AssetBuilder<LM, AT, QM, MD, FM> {
     last_trades_cap: usize,
     queue_model: Option<QM>,
     depth_builder: Option<Box<dyn Fn() -> MD>>,
+    trigger_source: TriggerSource,
+    stop_slippage_ticks: i64,
+    fill_ratio: f64,
+    post_only: PostOnly,
+    self_trade_prevention: SelfTradePrevention,
+    funding: FundingFeeModel,
 }
 
 impl<LM, AT, QM, MD, FM> L2AssetBuilder<LM, AT, QM, MD, FM>
@@ -165,6 +181,12 @@ where
             last_trades_cap: 0,
             queue_model: None,
             depth_builder: None,
+            trigger_source: TriggerSource::LastTrade,
+            stop_slippage_ticks: 0,
+            fill_ratio: 1.0,
+            post_only: PostOnly::Expire,
+            self_trade_prevention: SelfTradePrevention::None,
+            funding: Default::default(),
         }
     }
 
@@ -222,6 +244,52 @@ where
         Self { exch_kind, ..self }
     }
 
+    /// Sets the price that triggers stop orders. The default value is
+    /// [`TriggerSource::LastTrade`].
+    pub fn trigger_source(self, trigger_source: TriggerSource) -> Self {
+        Self {
+            trigger_source,
+            ..self
+        }
+    }
+
+    /// Sets the slippage, in ticks, with which a triggered stop market order is filled beyond the
+    /// best price. The default value is `0`.
+    pub fn stop_slippage(self, slippage_ticks: i64) -> Self {
+        Self {
+            stop_slippage_ticks: slippage_ticks,
+            ..self
+        }
+    }
+
+    /// Sets the share of the traded quantity that a resting order gets once its queue position is
+    /// consumed, with [`ExchangeKind::PartialFillExchange`]. See
+    /// [`PartialFillExchange::fill_ratio`]. The default value is `1.0`.
+    pub fn fill_ratio(self, fill_ratio: f64) -> Self {
+        Self { fill_ratio, ..self }
+    }
+
+    /// Sets how a post-only order that would cross the opposite best at its arrival is handled.
+    /// The default value is [`PostOnly::Expire`].
+    pub fn post_only(self, post_only: PostOnly) -> Self {
+        Self { post_only, ..self }
+    }
+
+    /// Sets how an order that would take liquidity on arrival and trade against our own resting
+    /// orders is handled. The default value is [`SelfTradePrevention::None`].
+    pub fn self_trade_prevention(self, self_trade_prevention: SelfTradePrevention) -> Self {
+        Self {
+            self_trade_prevention,
+            ..self
+        }
+    }
+
+    /// Sets the [`FundingFeeModel`] that charges the funding payments of a perpetual swap. By
+    /// default, no funding is charged.
+    pub fn funding(self, funding: FundingFeeModel) -> Self {
+        Self { funding, ..self }
+    }
+
     /// Sets the initial capacity of the vector storing the last market trades.
     /// The default value is `0`, indicating that no last trades are stored.
     pub fn last_trades_capacity(self, capacity: usize) -> Self {
@@ -291,7 +359,8 @@ where
             State::new(asset_type, fee_model),
             self.last_trades_cap,
             order_l2e,
-        );
+        )
+        .funding(self.funding);
 
         let queue_model = self
             .queue_model
@@ -305,6 +374,10 @@ where
             .clone()
             .ok_or(BuildError::BuilderIncomplete("fee_model"))?;
 
+        if !(self.fill_ratio > 0.0 && self.fill_ratio <= 1.0) {
+            return Err(BuildError::InvalidArgument("fill_ratio must be in (0, 1]"));
+        }
+
         match self.exch_kind {
             ExchangeKind::NoPartialFillExchange => {
                 let exch = NoPartialFillExchange::new(
@@ -312,7 +385,13 @@ where
                     State::new(asset_type, fee_model),
                     queue_model,
                     order_e2l,
-                );
+                )
+                .stop_orders(StopOrders::new(
+                    self.trigger_source,
+                    self.stop_slippage_ticks,
+                ))
+                .post_only(self.post_only)
+                .self_trade_prevention(self.self_trade_prevention);
 
                 Ok(Asset {
                     local: Box::new(local),
@@ -326,7 +405,14 @@ where
                     State::new(asset_type, fee_model),
                     queue_model,
                     order_e2l,
-                );
+                )
+                .stop_orders(StopOrders::new(
+                    self.trigger_source,
+                    self.stop_slippage_ticks,
+                ))
+                .post_only(self.post_only)
+                .self_trade_prevention(self.self_trade_prevention)
+                .fill_ratio(self.fill_ratio);
 
                 Ok(Asset {
                     local: Box::new(local),
@@ -363,6 +449,7 @@ pub struct L3AssetBuilder<LM, AT, QM, MD, FM> {
     last_trades_cap: usize,
     queue_model: Option<QM>,
     depth_builder: Option<Box<dyn Fn() -> MD>>,
+    funding: FundingFeeModel,
 }
 
 impl<LM, AT, QM, MD, FM> L3AssetBuilder<LM, AT, QM, MD, FM>
@@ -387,6 +474,7 @@ where
             last_trades_cap: 0,
             queue_model: None,
             depth_builder: None,
+            funding: Default::default(),
         }
     }
 
@@ -444,6 +532,12 @@ where
         Self { exch_kind, ..self }
     }
 
+    /// Sets the [`FundingFeeModel`] that charges the funding payments of a perpetual swap. By
+    /// default, no funding is charged.
+    pub fn funding(self, funding: FundingFeeModel) -> Self {
+        Self { funding, ..self }
+    }
+
     /// Sets the initial capacity of the vector storing the last market trades.
     /// The default value is `0`, indicating that no last trades are stored.
     pub fn last_trades_capacity(self, capacity: usize) -> Self {
@@ -513,7 +607,8 @@ where
             State::new(asset_type, fee_model),
             self.last_trades_cap,
             order_l2e,
-        );
+        )
+        .funding(self.funding);
 
         let queue_model = self
             .queue_model
@@ -926,6 +1021,8 @@ where
         qty: f64,
         time_in_force: TimeInForce,
         order_type: OrdType,
+        trigger_price: f64,
+        display_qty: f64,
         wait: bool,
     ) -> Result<ElapseResult, Self::Error> {
         let local = self.local.get_mut(asset_no).unwrap();
@@ -936,6 +1033,8 @@ where
             qty,
             order_type,
             time_in_force,
+            trigger_price,
+            display_qty,
             self.cur_ts,
         )?;
 
@@ -957,6 +1056,8 @@ where
         qty: f64,
         time_in_force: TimeInForce,
         order_type: OrdType,
+        trigger_price: f64,
+        display_qty: f64,
         wait: bool,
     ) -> Result<ElapseResult, Self::Error> {
         let local = self.local.get_mut(asset_no).unwrap();
@@ -967,6 +1068,8 @@ where
             qty,
             order_type,
             time_in_force,
+            trigger_price,
+            display_qty,
             self.cur_ts,
         )?;
 
@@ -993,6 +1096,8 @@ where
             order.qty,
             order.order_type,
             order.time_in_force,
+            order.trigger_price,
+            order.display_qty,
             self.cur_ts,
         )?;
 
@@ -1154,16 +1259,44 @@ mod test {
             models::{
                 CommonFees,
                 ConstantLatency,
+                FundingFeeModel,
                 PowerProbQueueFunc3,
                 ProbQueueModel,
+                RiskAdverseQueueModel,
+                TieredFeeModel,
                 TradingValueFeeModel,
             },
+            proc::PostOnly,
         },
         depth::HashMapMarketDepth,
         prelude::{Bot, Event},
-        types::{EXCH_EVENT, LOCAL_EVENT},
+        types::{
+            EXCH_ASK_DEPTH_EVENT,
+            EXCH_BID_DEPTH_EVENT,
+            EXCH_EVENT,
+            EXCH_SELL_TRADE_EVENT,
+            LOCAL_ASK_DEPTH_EVENT,
+            LOCAL_BID_DEPTH_EVENT,
+            LOCAL_EVENT,
+            OrdType,
+            Status,
+            TimeInForce,
+        },
     };
 
+    fn event(ev: u64, ts: i64, px: f64, qty: f64) -> Event {
+        Event {
+            ev,
+            exch_ts: ts,
+            local_ts: ts,
+            px,
+            qty,
+            order_id: 0,
+            ival: 0,
+            fval: 0.0,
+        }
+    }
+
     #[test]
     fn skips_unseen_events() -> Result<(), Box<dyn Error>> {
         let data = Data::from_data(&[
@@ -1238,4 +1371,257 @@ mod test {
 
         Ok(())
     }
+
+    #[test]
+    fn counts_post_only_rejects() -> Result<(), Box<dyn Error>> {
+        let data = Data::from_data(&[
+            event(EXCH_BID_DEPTH_EVENT | LOCAL_BID_DEPTH_EVENT, 1, 99.0, 10.0),
+            event(EXCH_ASK_DEPTH_EVENT | LOCAL_ASK_DEPTH_EVENT, 1, 101.0, 10.0),
+            event(
+                EXCH_BID_DEPTH_EVENT | LOCAL_BID_DEPTH_EVENT,
+                100,
+                99.0,
+                10.0,
+            ),
+        ]);
+
+        let mut backtester = Backtest::builder()
+            .add_asset(
+                L2AssetBuilder::default()
+                    .data(vec![DataSource::Data(data)])
+                    .latency_model(ConstantLatency::new(1, 1))
+                    .asset_type(LinearAsset::new(1.0))
+                    .fee_model(TradingValueFeeModel::new(CommonFees::new(0.0, 0.0)))
+                    .queue_model(RiskAdverseQueueModel::new())
+                    .exchange(NoPartialFillExchange)
+                    .post_only(PostOnly::Reject)
+                    .depth(|| HashMapMarketDepth::new(1.0, 1.0))
+                    .build()?,
+            )
+            .build()?;
+
+        backtester.elapse(5)?;
+        // This order would take the best ask, and the exchange rejects it.
+        backtester.submit_buy_order(
+            0,
+            1,
+            101.0,
+            1.0,
+            TimeInForce::GTX,
+            OrdType::Limit,
+            0.0,
+            0.0,
+            false,
+        )?;
+        backtester.submit_buy_order(
+            0,
+            2,
+            100.0,
+            1.0,
+            TimeInForce::GTX,
+            OrdType::Limit,
+            0.0,
+            0.0,
+            false,
+        )?;
+        backtester.elapse(10)?;
+
+        assert_eq!(backtester.orders(0)[&1].status, Status::Rejected);
+        assert_eq!(backtester.orders(0)[&2].status, Status::New);
+        assert_eq!(backtester.state_values(0).num_post_only_rejects, 1);
+        assert_eq!(backtester.state_values(0).num_trades, 0);
+
+        backtester.clear_inactive_orders(Some(0));
+        assert!(!backtester.orders(0).contains_key(&1));
+
+        Ok(())
+    }
+
+    #[test]
+    fn charges_funding() -> Result<(), Box<dyn Error>> {
+        let bid = |ts| event(EXCH_BID_DEPTH_EVENT | LOCAL_BID_DEPTH_EVENT, ts, 99.0, 10.0);
+        let data = Data::from_data(&[
+            bid(10),
+            event(
+                EXCH_ASK_DEPTH_EVENT | LOCAL_ASK_DEPTH_EVENT,
+                10,
+                101.0,
+                10.0,
+            ),
+            bid(100),
+            bid(200),
+            bid(300),
+        ]);
+
+        let mut backtester = Backtest::builder()
+            .add_asset(
+                L2AssetBuilder::default()
+                    .data(vec![DataSource::Data(data)])
+                    .latency_model(ConstantLatency::new(1, 1))
+                    .asset_type(LinearAsset::new(1.0))
+                    .fee_model(TradingValueFeeModel::new(CommonFees::new(0.0, 0.0)))
+                    .queue_model(RiskAdverseQueueModel::new())
+                    .exchange(NoPartialFillExchange)
+                    .funding(
+                        // The funding timestamps before the start and after the end of the data
+                        // are not charged.
+                        FundingFeeModel::new(vec![
+                            (0, 0.01),
+                            (150, 0.01),
+                            (250, -0.02),
+                            (1000, 0.05),
+                        ]),
+                    )
+                    .depth(|| HashMapMarketDepth::new(1.0, 1.0))
+                    .build()?,
+            )
+            .build()?;
+
+        backtester.elapse(20)?;
+        backtester.submit_buy_order(
+            0,
+            1,
+            101.0,
+            2.0,
+            TimeInForce::GTC,
+            OrdType::Market,
+            0.0,
+            0.0,
+            false,
+        )?;
+        backtester.elapse(180)?;
+        assert_eq!(backtester.state_values(0).position, 2.0);
+        assert_eq!(backtester.state_values(0).funding, 2.0);
+
+        backtester.elapse(2000)?;
+        assert_eq!(backtester.state_values(0).funding, 2.0 - 4.0);
+        assert_eq!(backtester.state_values(0).fee, 0.0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn steps_down_fee_tier() -> Result<(), Box<dyn Error>> {
+        let data = Data::from_data(&[
+            event(EXCH_BID_DEPTH_EVENT | LOCAL_BID_DEPTH_EVENT, 10, 99.0, 10.0),
+            event(
+                EXCH_ASK_DEPTH_EVENT | LOCAL_ASK_DEPTH_EVENT,
+                10,
+                101.0,
+                10.0,
+            ),
+            event(
+                EXCH_BID_DEPTH_EVENT | LOCAL_BID_DEPTH_EVENT,
+                100,
+                99.0,
+                10.0,
+            ),
+        ]);
+
+        let mut backtester = Backtest::builder()
+            .add_asset(
+                L2AssetBuilder::default()
+                    .data(vec![DataSource::Data(data)])
+                    .latency_model(ConstantLatency::new(1, 1))
+                    .asset_type(LinearAsset::new(1.0))
+                    .fee_model(TieredFeeModel::new(
+                        vec![(0.0, 2.0, 5.0), (300.0, 1.0, 3.0)],
+                        1_000_000,
+                    ))
+                    .queue_model(RiskAdverseQueueModel::new())
+                    .exchange(NoPartialFillExchange)
+                    .depth(|| HashMapMarketDepth::new(1.0, 1.0))
+                    .build()?,
+            )
+            .build()?;
+
+        backtester.elapse(20)?;
+        let mut buy = |order_id, qty| {
+            backtester.submit_buy_order(
+                0,
+                order_id,
+                101.0,
+                qty,
+                TimeInForce::GTC,
+                OrdType::Market,
+                0.0,
+                0.0,
+                true,
+            )
+        };
+        buy(1, 2.0)?;
+        // The second fill takes the rolling volume from 202 to 303, over the threshold.
+        buy(2, 1.0)?;
+        let fee = backtester.state_values(0).fee;
+        assert!((fee - 303.0 * 5e-4).abs() < 1e-12);
+
+        backtester.submit_buy_order(
+            0,
+            3,
+            101.0,
+            1.0,
+            TimeInForce::GTC,
+            OrdType::Market,
+            0.0,
+            0.0,
+            true,
+        )?;
+        assert!((backtester.state_values(0).fee - fee - 101.0 * 3e-4).abs() < 1e-12);
+
+        Ok(())
+    }
+
+    #[test]
+    fn records_iceberg_clip_fills() -> Result<(), Box<dyn Error>> {
+        let data = Data::from_data(&[
+            event(EXCH_BID_DEPTH_EVENT | LOCAL_BID_DEPTH_EVENT, 1, 100.0, 2.0),
+            event(EXCH_ASK_DEPTH_EVENT | LOCAL_ASK_DEPTH_EVENT, 1, 101.0, 10.0),
+            event(EXCH_SELL_TRADE_EVENT, 10, 100.0, 3.0),
+            event(EXCH_SELL_TRADE_EVENT, 20, 100.0, 3.0),
+            event(EXCH_SELL_TRADE_EVENT, 30, 100.0, 3.0),
+        ]);
+
+        let mut backtester = Backtest::builder()
+            .add_asset(
+                L2AssetBuilder::default()
+                    .data(vec![DataSource::Data(data)])
+                    .latency_model(ConstantLatency::new(1, 1))
+                    .asset_type(LinearAsset::new(1.0))
+                    .fee_model(TradingValueFeeModel::new(CommonFees::new(0.0, 0.0)))
+                    .queue_model(RiskAdverseQueueModel::new())
+                    .exchange(NoPartialFillExchange)
+                    .depth(|| HashMapMarketDepth::new(1.0, 1.0))
+                    .build()?,
+            )
+            .build()?;
+
+        backtester.elapse(5)?;
+        backtester.submit_buy_order(
+            0,
+            1,
+            100.0,
+            3.0,
+            TimeInForce::GTC,
+            OrdType::Limit,
+            0.0,
+            1.0,
+            false,
+        )?;
+
+        // Each clip is filled separately under the same order ID, and all of them are reflected in
+        // the recorded state.
+        backtester.elapse(10)?;
+        assert_eq!(backtester.orders(0)[&1].status, Status::PartiallyFilled);
+        assert_eq!(backtester.orders(0)[&1].leaves_qty, 2.0);
+        assert_eq!(backtester.state_values(0).trading_volume, 1.0);
+
+        backtester.elapse(30)?;
+        assert_eq!(backtester.orders(0)[&1].status, Status::Filled);
+        let state_values = backtester.state_values(0);
+        assert_eq!(state_values.num_trades, 3);
+        assert_eq!(state_values.trading_volume, 3.0);
+        assert_eq!(state_values.position, 3.0);
+
+        Ok(())
+    }
 }
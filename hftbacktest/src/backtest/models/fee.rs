@@ -1,3 +1,8 @@
+use std::{
+    cell::{Cell, RefCell},
+    collections::VecDeque,
+};
+
 use crate::{prelude::Side, types::Order};
 
 /// Common transaction fees
@@ -151,3 +156,105 @@ impl FeeModel for FlatPerTradeFeeModel<CommonFees> {
         }
     }
 }
+
+/// Fee based on the transaction value, with maker and taker rates that step down as the traded
+/// value over a rolling window grows, like the VIP tiers of crypto exchanges.
+///
+/// Each tier is `(volume_threshold, maker_bps, taker_bps)`, with the rates in basis points of the
+/// transaction value; a negative rate represents rebates. A fill is charged at the tier with the
+/// highest threshold not above the value traded within `window` before it, so once a fill takes
+/// the rolling volume over a threshold, the fees change from the next fill onward, and revert as
+/// the fills age out of the window. Below the lowest threshold, the lowest tier applies. Without
+/// any tier, no fee is charged.
+#[derive(Clone)]
+pub struct TieredFeeModel {
+    tiers: Vec<(f64, f64, f64)>,
+    window: i64,
+    fills: RefCell<VecDeque<(i64, f64)>>,
+    volume: Cell<f64>,
+}
+
+impl TieredFeeModel {
+    /// Constructs `TieredFeeModel` with `(volume_threshold, maker_bps, taker_bps)` tiers in any
+    /// order and the length of the rolling volume window in nanoseconds, such as
+    /// `30 * 24 * 60 * 60 * 1_000_000_000` for 30 days.
+    pub fn new(mut tiers: Vec<(f64, f64, f64)>, window: i64) -> Self {
+        tiers.sort_by(|a, b| a.0.total_cmp(&b.0));
+        Self {
+            tiers,
+            window,
+            fills: Default::default(),
+            volume: Cell::new(0.0),
+        }
+    }
+}
+
+impl FeeModel for TieredFeeModel {
+    fn amount(&self, order: &Order, amount: f64) -> f64 {
+        let mut fills = self.fills.borrow_mut();
+        let mut volume = self.volume.get();
+        while let Some(&(timestamp, value)) = fills.front() {
+            if timestamp > order.exch_timestamp - self.window {
+                break;
+            }
+            fills.pop_front();
+            volume -= value;
+        }
+        if fills.is_empty() {
+            // Resets the rounding errors accumulated by the subtractions.
+            volume = 0.0;
+        }
+
+        let fee = self
+            .tiers
+            .iter()
+            .rev()
+            .find(|(threshold, _, _)| volume >= *threshold)
+            .or(self.tiers.first())
+            .map(|(_, maker_bps, taker_bps)| {
+                let bps = if order.maker { maker_bps } else { taker_bps };
+                bps * 1e-4 * amount
+            })
+            .unwrap_or(0.0);
+
+        fills.push_back((order.exch_timestamp, amount));
+        self.volume.set(volume + amount);
+        fee
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{OrdType, TimeInForce};
+
+    #[test]
+    fn test_tiered_fee_model() {
+        let fee_model = TieredFeeModel::new(vec![(1000.0, 1.0, 4.0), (0.0, 2.0, 5.0)], 100);
+        let fill = |timestamp, maker| {
+            let mut order = Order::new(
+                1,
+                100,
+                1.0,
+                1.0,
+                Side::Buy,
+                OrdType::Limit,
+                TimeInForce::GTC,
+            );
+            order.exch_timestamp = timestamp;
+            order.maker = maker;
+            order
+        };
+
+        assert!((fee_model.amount(&fill(0, false), 600.0) - 0.3).abs() < 1e-12);
+        assert!((fee_model.amount(&fill(10, true), 600.0) - 0.12).abs() < 1e-12);
+        // The rolling volume reached 1200, so the lower tier applies from here.
+        assert!((fee_model.amount(&fill(20, false), 100.0) - 0.04).abs() < 1e-12);
+        assert!((fee_model.amount(&fill(30, true), 100.0) - 0.01).abs() < 1e-12);
+        // The fills at 0 and 10 left the window, leaving 200 in it.
+        assert!((fee_model.amount(&fill(110, false), 100.0) - 0.05).abs() < 1e-12);
+
+        let fee_model = TieredFeeModel::new(vec![], 100);
+        assert_eq!(fee_model.amount(&fill(0, false), 100.0), 0.0);
+    }
+}
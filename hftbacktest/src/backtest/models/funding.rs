@@ -0,0 +1,89 @@
+/// Funding payments of a perpetual swap.
+///
+/// At each funding timestamp, the position held is charged `position * mark_price * rate` for a
+/// linear asset, or `position / mark_price * rate` for an inverse asset, scaled by the contract
+/// size: longs pay and shorts receive when the rate is positive. The payments are accumulated in
+/// [`StateValues::funding`](crate::types::StateValues::funding), separately from trading fees.
+///
+/// A funding timestamp is settled once the local processes the first feed event at or after it,
+/// so the payment is made on the position and market depth as they were at the funding timestamp.
+/// Funding timestamps before the start of the data are skipped, as the backtest starts without a
+/// position, and those after its end are never settled, so a backtest window that starts or ends
+/// partway through a funding interval is only charged for the funding timestamps it contains.
+///
+/// The mark price is the mid-price at the funding timestamp, unless a mark price series is given
+/// with [`mark_prices`](Self::mark_prices).
+#[derive(Clone, Debug, Default)]
+pub struct FundingFeeModel {
+    funding_rates: Vec<(i64, f64)>,
+    mark_prices: Vec<(i64, f64)>,
+    next_funding: usize,
+    next_mark_price: usize,
+}
+
+impl FundingFeeModel {
+    /// Constructs `FundingFeeModel` with `(timestamp, rate)` pairs, with timestamps in nanoseconds
+    /// like the feed.
+    pub fn new(mut funding_rates: Vec<(i64, f64)>) -> Self {
+        funding_rates.sort_by_key(|(timestamp, _)| *timestamp);
+        Self {
+            funding_rates,
+            ..Default::default()
+        }
+    }
+
+    /// Sets the `(timestamp, mark_price)` series that funding payments are valued at, with
+    /// timestamps in nanoseconds. Each funding timestamp takes the latest mark price at or before
+    /// it, and falls back to the mid-price if there is none.
+    pub fn mark_prices(mut self, mut mark_prices: Vec<(i64, f64)>) -> Self {
+        mark_prices.sort_by_key(|(timestamp, _)| *timestamp);
+        self.mark_prices = mark_prices;
+        self
+    }
+
+    /// Returns the rate and the mark price of the next funding timestamp at or before `timestamp`,
+    /// and moves past it, or `None` if there is none. `mid` is the mid-price at `timestamp`.
+    pub fn next_funding(&mut self, timestamp: i64, mid: f64) -> Option<(f64, f64)> {
+        let (funding_timestamp, rate) = *self.funding_rates.get(self.next_funding)?;
+        if funding_timestamp > timestamp {
+            return None;
+        }
+        self.next_funding += 1;
+
+        while self
+            .mark_prices
+            .get(self.next_mark_price)
+            .is_some_and(|(mark_timestamp, _)| *mark_timestamp <= funding_timestamp)
+        {
+            self.next_mark_price += 1;
+        }
+        let mark_price = match self.next_mark_price {
+            0 => mid,
+            i => self.mark_prices[i - 1].1,
+        };
+        Some((rate, mark_price))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_next_funding() {
+        let mut funding = FundingFeeModel::new(vec![(300, 0.0003), (100, 0.0001), (200, -0.0002)])
+            .mark_prices(vec![(150, 10.0), (250, 20.0)]);
+
+        assert_eq!(funding.next_funding(50, 1.0), None);
+        // Without a mark price at or before the funding timestamp, the mid-price is used.
+        assert_eq!(funding.next_funding(100, 1.0), Some((0.0001, 1.0)));
+        assert_eq!(funding.next_funding(100, 1.0), None);
+        // Several funding timestamps can be settled at once.
+        assert_eq!(funding.next_funding(1000, 1.0), Some((-0.0002, 10.0)));
+        assert_eq!(funding.next_funding(1000, 1.0), Some((0.0003, 20.0)));
+        assert_eq!(funding.next_funding(1000, 1.0), None);
+
+        let mut funding = FundingFeeModel::default();
+        assert_eq!(funding.next_funding(i64::MAX, 1.0), None);
+    }
+}
@@ -1,13 +1,24 @@
-use std::{io::Error as IoError, mem};
+use std::{
+    collections::HashMap,
+    io::{Error as IoError, ErrorKind},
+    mem,
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+};
 
 use hftbacktest_derive::NpyDTyped;
+use rand::{Rng, SeedableRng, rngs::StdRng};
+use rand_distr::{Distribution, Gamma, LogNormal};
+use tracing::warn;
 
 use crate::{
     backtest::{
         BacktestError,
         data::{Data, DataPreprocess, DataSource, POD, Reader},
     },
-    types::Order,
+    types::{Order, OrderId},
 };
 
 /// Provides the order entry latency and the order response latency.
@@ -85,6 +96,12 @@ unsafe impl POD for OrderLatencyRow {}
 /// exchange, and its value represents the latency that the local experiences when receiving the
 /// rejection notification.
 ///
+/// Captured data may still contain rows out of order or with a timestamp earlier than the one
+/// before it in the round trip. Such rows are counted and reported as they are loaded, and can be
+/// dropped with [build_validated()](Self::build_validated()). Latencies below the minimum latency,
+/// zero by default, are clamped to it, and before the first or after the last row, the latency of
+/// the nearest row is used rather than extrapolating.
+///
 /// **Example**
 /// ```
 /// use hftbacktest::backtest::{DataSource, models::IntpOrderLatency};
@@ -101,6 +118,9 @@ pub struct IntpOrderLatency {
     reader: Reader<OrderLatencyRow>,
     data: Data<OrderLatencyRow>,
     next_data: Data<OrderLatencyRow>,
+    min_latency: i64,
+    clamped: usize,
+    validation: Arc<OrderLatencyValidation>,
 }
 
 impl IntpOrderLatency {
@@ -110,18 +130,32 @@ impl IntpOrderLatency {
         parallel_load: bool,
         latency_offset: i64,
     ) -> Result<Self, BacktestError> {
-        let mut reader = if latency_offset == 0 {
-            Reader::builder()
-                .parallel_load(parallel_load)
-                .data(data)
-                .build()?
-        } else {
-            Reader::builder()
-                .parallel_load(parallel_load)
-                .data(data)
-                .preprocessor(OrderLatencyAdjustment::new(latency_offset))
-                .build()?
-        };
+        Self::build_validated(data, parallel_load, latency_offset, false)
+    }
+
+    /// Constructs an `IntpOrderLatency` with options, dropping the invalid rows of the data if
+    /// `drop_invalid_rows` is set, rather than only reporting them. A row is invalid if its
+    /// request or exchange timestamp is earlier than the previous valid row's, or if its timestamps
+    /// are out of order within the round trip. Rows rejected by the exchange, with an exchange
+    /// timestamp of zero, are kept.
+    pub fn build_validated(
+        data: Vec<DataSource<OrderLatencyRow>>,
+        parallel_load: bool,
+        latency_offset: i64,
+        drop_invalid_rows: bool,
+    ) -> Result<Self, BacktestError> {
+        let validation = Arc::new(OrderLatencyValidation {
+            drop_invalid_rows,
+            ..Default::default()
+        });
+        let mut reader = Reader::builder()
+            .parallel_load(parallel_load)
+            .data(data)
+            .preprocessor(OrderLatencyAdjustment {
+                latency_offset,
+                validation: validation.clone(),
+            })
+            .build()?;
         let data = match reader.next_data() {
             Ok(data) => data,
             Err(BacktestError::EndOfData) => Data::empty(),
@@ -138,6 +172,9 @@ impl IntpOrderLatency {
             reader,
             data,
             next_data,
+            min_latency: 0,
+            clamped: 0,
+            validation,
         })
     }
 
@@ -146,6 +183,55 @@ impl IntpOrderLatency {
         Self::build(data, true, latency_offset).unwrap()
     }
 
+    /// Sets the minimum latency, zero by default. Lower latencies, interpolated or not, are
+    /// clamped to it, as is the magnitude of the latency of a rejection.
+    pub fn min_latency(self, min_latency: i64) -> Self {
+        Self {
+            min_latency,
+            ..self
+        }
+    }
+
+    /// Returns the number of invalid rows found in the data loaded so far.
+    pub fn invalid_rows(&self) -> usize {
+        self.validation.invalid_rows.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of invalid rows dropped from the data loaded so far.
+    pub fn dropped_rows(&self) -> usize {
+        self.validation.dropped_rows.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of latencies clamped to the minimum latency.
+    pub fn clamped(&self) -> usize {
+        self.clamped
+    }
+
+    fn clamp(&mut self, latency: i64) -> i64 {
+        if latency < self.min_latency {
+            if self.clamped == 0 {
+                warn!(
+                    latency,
+                    min_latency = self.min_latency,
+                    "order latency clamped to the minimum, further clamps are only counted"
+                );
+            }
+            self.clamped += 1;
+            self.min_latency
+        } else {
+            latency
+        }
+    }
+
+    /// Returns the entry latency of `row`, negative if the exchange rejected the request.
+    fn row_entry_latency(&mut self, row: &OrderLatencyRow) -> i64 {
+        if row.exch_ts <= 0 {
+            -self.clamp(row.resp_ts - row.req_ts)
+        } else {
+            self.clamp(row.exch_ts - row.req_ts)
+        }
+    }
+
     fn intp(&self, x: i64, x1: i64, y1: i64, x2: i64, y2: i64) -> i64 {
         (((y2 - y1) as f64) / ((x2 - x1) as f64) * ((x - x1) as f64)) as i64 + y1
     }
@@ -171,7 +257,8 @@ impl LatencyModel for IntpOrderLatency {
     fn entry(&mut self, timestamp: i64, _order: &Order) -> i64 {
         let first_row = &self.data[0];
         if timestamp < first_row.req_ts {
-            return first_row.exch_ts - first_row.req_ts;
+            let first_row = first_row.clone();
+            return self.row_entry_latency(&first_row);
         }
 
         loop {
@@ -181,8 +268,8 @@ impl LatencyModel for IntpOrderLatency {
             } else if !self.next_data.is_empty() {
                 &self.next_data[0]
             } else {
-                let last_row = &self.data[self.data.len() - 1];
-                return last_row.exch_ts - last_row.req_ts;
+                let last_row = self.data[self.data.len() - 1].clone();
+                return self.row_entry_latency(&last_row);
             };
 
             let req_local_timestamp = row.req_ts;
@@ -205,24 +292,26 @@ impl LatencyModel for IntpOrderLatency {
                     // Negative latency indicates that the order is rejected for technical
                     // reasons, and its value represents the latency that the local experiences
                     // when receiving the rejection notification
-                    return -self.intp(
+                    let lat = self.intp(
                         timestamp,
                         req_local_timestamp,
                         lat1,
                         next_req_local_timestamp,
                         lat2,
                     );
+                    return -self.clamp(lat);
                 }
 
                 let lat1 = exch_timestamp - req_local_timestamp;
                 let lat2 = next_exch_timestamp - next_req_local_timestamp;
-                return self.intp(
+                let lat = self.intp(
                     timestamp,
                     req_local_timestamp,
                     lat1,
                     next_req_local_timestamp,
                     lat2,
                 );
+                return self.clamp(lat);
             } else if self.entry_rn == self.data.len() - 1 {
                 if self.next_data().unwrap() {
                     self.entry_rn = 0;
@@ -236,7 +325,8 @@ impl LatencyModel for IntpOrderLatency {
     fn response(&mut self, timestamp: i64, _order: &Order) -> i64 {
         let first_row = &self.data[0];
         if timestamp < first_row.exch_ts {
-            return first_row.resp_ts - first_row.exch_ts;
+            let lat = first_row.resp_ts - first_row.exch_ts;
+            return self.clamp(lat);
         }
 
         loop {
@@ -247,7 +337,8 @@ impl LatencyModel for IntpOrderLatency {
                 &self.next_data[0]
             } else {
                 let last_row = &self.data[self.data.len() - 1];
-                return last_row.resp_ts - last_row.exch_ts;
+                let lat = last_row.resp_ts - last_row.exch_ts;
+                return self.clamp(lat);
             };
 
             let exch_timestamp = row.exch_ts;
@@ -260,8 +351,7 @@ impl LatencyModel for IntpOrderLatency {
                 let lat2 = next_resp_local_timestamp - next_exch_timestamp;
 
                 let lat = self.intp(timestamp, exch_timestamp, lat1, next_exch_timestamp, lat2);
-                assert!(lat >= 0);
-                return lat;
+                return self.clamp(lat);
             } else if self.resp_rn == self.data.len() - 1 {
                 if self.next_data().unwrap() {
                     self.resp_rn = 0;
@@ -273,23 +363,344 @@ impl LatencyModel for IntpOrderLatency {
     }
 }
 
+/// A distribution of order latencies, in the time unit of the data's timestamps.
+#[derive(Clone, Copy, Debug)]
+pub enum LatencyDistribution {
+    /// Lognormal distribution, whose logarithm is normally distributed with mean `mu` and standard
+    /// deviation `sigma`. For example, `mu = (1_000_000f64).ln()` centers the latencies around one
+    /// millisecond in nanoseconds.
+    LogNormal { mu: f64, sigma: f64 },
+    /// Gamma distribution with the given `shape` and `scale`, whose mean is `shape * scale`.
+    Gamma { shape: f64, scale: f64 },
+}
+
+impl LatencyDistribution {
+    /// Returns `true` if the parameters define a distribution: a finite `mu` and a finite,
+    /// non-negative `sigma`, or a positive, finite `shape` and `scale`.
+    pub fn is_valid(&self) -> bool {
+        self.sampler().is_some()
+    }
+
+    fn sampler(&self) -> Option<LatencySampler> {
+        match *self {
+            LatencyDistribution::LogNormal { mu, sigma } => LogNormal::new(mu, sigma)
+                .ok()
+                .filter(|_| mu.is_finite() && sigma.is_finite() && sigma >= 0.0)
+                .map(LatencySampler::LogNormal),
+            LatencyDistribution::Gamma { shape, scale } => Gamma::new(shape, scale)
+                .ok()
+                .filter(|_| shape > 0.0 && scale > 0.0 && shape.is_finite() && scale.is_finite())
+                .map(LatencySampler::Gamma),
+        }
+    }
+}
+
 #[derive(Clone)]
-struct OrderLatencyAdjustment {
-    latency_offset: i64,
+enum LatencySampler {
+    LogNormal(LogNormal<f64>),
+    Gamma(Gamma<f64>),
+}
+
+impl LatencySampler {
+    /// Draws a latency, rounded and at least one so that it is always positive.
+    fn sample<R: Rng>(&self, rng: &mut R) -> i64 {
+        let latency = match self {
+            LatencySampler::LogNormal(dist) => dist.sample(rng),
+            LatencySampler::Gamma(dist) => dist.sample(rng),
+        };
+        (latency.round() as i64).max(1)
+    }
+}
+
+/// Provides order latency drawn from an analytic distribution, for when no historical order
+/// latency data is available.
+///
+/// The entry and response latencies are drawn from their own distributions, each with its own
+/// random number generator seeded from `seed`, so a backtest with the same seed is reproducible.
+/// Generated latencies are always positive, so orders are never rejected, and the responses for an
+/// order are received locally in the order the exchange sent them: a response drawn to arrive
+/// before an earlier response for the same order is delayed to arrive with it.
+///
+/// **Example**
+/// ```
+/// use hftbacktest::backtest::models::{LatencyDistribution, StochasticLatency};
+///
+/// let latency_model = StochasticLatency::new(
+///     LatencyDistribution::LogNormal { mu: (1_000_000f64).ln(), sigma: 0.5 },
+///     LatencyDistribution::Gamma { shape: 4.0, scale: 250_000.0 },
+///     42,
+/// );
+/// ```
+#[derive(Clone)]
+pub struct StochasticLatency {
+    entry: LatencySampler,
+    response: LatencySampler,
+    entry_rng: StdRng,
+    response_rng: StdRng,
+    last_local_recv: HashMap<OrderId, i64>,
+}
+
+impl StochasticLatency {
+    /// Constructs an instance of `StochasticLatency`.
+    ///
+    /// # Panics
+    /// Panics if either distribution is invalid, as reported by
+    /// [`LatencyDistribution::is_valid`].
+    pub fn new(entry: LatencyDistribution, response: LatencyDistribution, seed: u64) -> Self {
+        Self {
+            entry: entry.sampler().expect("invalid entry latency distribution"),
+            response: response
+                .sampler()
+                .expect("invalid response latency distribution"),
+            entry_rng: StdRng::seed_from_u64(seed),
+            // A separate stream, so the entry and response latencies are not drawn from the same
+            // random numbers, as the exchange and the local each hold a clone of the model.
+            response_rng: StdRng::seed_from_u64(seed.wrapping_add(0x9e37_79b9_7f4a_7c15)),
+            last_local_recv: HashMap::new(),
+        }
+    }
+}
+
+impl LatencyModel for StochasticLatency {
+    fn entry(&mut self, _timestamp: i64, _order: &Order) -> i64 {
+        self.entry.sample(&mut self.entry_rng)
+    }
+
+    fn response(&mut self, timestamp: i64, order: &Order) -> i64 {
+        let mut latency = self.response.sample(&mut self.response_rng);
+        if let Some(&last_local_recv) = self.last_local_recv.get(&order.order_id) {
+            latency = latency.max(last_local_recv - timestamp);
+        }
+        if order.active() {
+            self.last_local_recv
+                .insert(order.order_id, timestamp + latency);
+        } else {
+            self.last_local_recv.remove(&order.order_id);
+        }
+        latency
+    }
+}
+
+/// Counts of the invalid rows found, and dropped if `drop_invalid_rows` is set, while loading the
+/// data, shared with the loading threads.
+#[derive(Default)]
+struct OrderLatencyValidation {
+    drop_invalid_rows: bool,
+    invalid_rows: AtomicUsize,
+    dropped_rows: AtomicUsize,
 }
 
-impl OrderLatencyAdjustment {
-    pub fn new(latency_offset: i64) -> Self {
-        Self { latency_offset }
+impl OrderLatencyValidation {
+    /// Reports the invalid rows of `data` and drops them if `drop_invalid_rows` is set. Since each
+    /// file is loaded on its own, the order is only checked within a file.
+    fn validate(&self, data: &mut Data<OrderLatencyRow>) -> Result<(), IoError> {
+        let mut valid = Vec::with_capacity(data.len());
+        let mut last_req_ts = i64::MIN;
+        let mut last_exch_ts = i64::MIN;
+        for i in 0..data.len() {
+            let row = &data[i];
+            let rejected = row.exch_ts <= 0;
+            let is_valid = row.req_ts >= last_req_ts
+                && row.resp_ts >= row.req_ts
+                && (rejected
+                    || (row.exch_ts >= last_exch_ts
+                        && row.req_ts <= row.exch_ts
+                        && row.exch_ts <= row.resp_ts));
+            if is_valid {
+                last_req_ts = row.req_ts;
+                if !rejected {
+                    last_exch_ts = row.exch_ts;
+                }
+            }
+            valid.push(is_valid);
+        }
+
+        let invalid_rows = valid.iter().filter(|is_valid| !**is_valid).count();
+        if invalid_rows == 0 {
+            return Ok(());
+        }
+        self.invalid_rows.fetch_add(invalid_rows, Ordering::Relaxed);
+        warn!(
+            invalid_rows,
+            rows = data.len(),
+            dropped = self.drop_invalid_rows,
+            "order latency data has rows out of order or with negative latencies"
+        );
+        if self.drop_invalid_rows {
+            if invalid_rows == data.len() {
+                return Err(IoError::new(
+                    ErrorKind::InvalidData,
+                    "every row of the order latency data is invalid",
+                ));
+            }
+            let rows: Vec<_> = (0..data.len())
+                .filter(|&i| valid[i])
+                .map(|i| data[i].clone())
+                .collect();
+            *data = Data::from_data(&rows);
+            self.dropped_rows.fetch_add(invalid_rows, Ordering::Relaxed);
+        }
+        Ok(())
     }
 }
 
+#[derive(Clone)]
+struct OrderLatencyAdjustment {
+    latency_offset: i64,
+    validation: Arc<OrderLatencyValidation>,
+}
+
 impl DataPreprocess<OrderLatencyRow> for OrderLatencyAdjustment {
     fn preprocess(&self, data: &mut Data<OrderLatencyRow>) -> Result<(), IoError> {
-        for i in 0..data.len() {
-            data[i].exch_ts += self.latency_offset;
-            data[i].resp_ts += self.latency_offset + self.latency_offset;
+        self.validation.validate(data)?;
+        if self.latency_offset != 0 {
+            for i in 0..data.len() {
+                data[i].exch_ts += self.latency_offset;
+                data[i].resp_ts += self.latency_offset + self.latency_offset;
+            }
         }
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{OrdType, Side, Status, TimeInForce};
+
+    fn corrupted_data() -> Vec<DataSource<OrderLatencyRow>> {
+        let row = |req_ts, exch_ts, resp_ts| OrderLatencyRow {
+            req_ts,
+            exch_ts,
+            resp_ts,
+            _padding: 0,
+        };
+        vec![DataSource::Data(Data::from_data(&[
+            row(100, 110, 120),
+            row(200, 215, 230),
+            // Out of order.
+            row(150, 160, 170),
+            // The exchange timestamp is earlier than the request timestamp.
+            row(300, 290, 320),
+            // The response timestamp is earlier than the exchange timestamp.
+            row(400, 420, 410),
+            row(500, 510, 530),
+        ]))]
+    }
+
+    #[test]
+    fn test_drops_invalid_rows() {
+        let order = Order::new(
+            1,
+            100,
+            1.0,
+            1.0,
+            Side::Buy,
+            OrdType::Limit,
+            TimeInForce::GTC,
+        );
+        let mut latency =
+            IntpOrderLatency::build_validated(corrupted_data(), false, 0, true).unwrap();
+        assert_eq!(latency.invalid_rows(), 3);
+        assert_eq!(latency.dropped_rows(), 3);
+
+        // Before the first and after the last row, the nearest row's latency is used.
+        assert_eq!(latency.entry(50, &order), 10);
+        assert_eq!(latency.entry(350, &order), 13);
+        assert_eq!(latency.entry(600, &order), 10);
+        assert_eq!(latency.response(600, &order), 20);
+        assert_eq!(latency.clamped(), 0);
+    }
+
+    #[test]
+    fn test_clamps_negative_latencies() {
+        let order = Order::new(
+            1,
+            100,
+            1.0,
+            1.0,
+            Side::Buy,
+            OrdType::Limit,
+            TimeInForce::GTC,
+        );
+        let mut latency = IntpOrderLatency::build(corrupted_data(), false, 0).unwrap();
+        assert_eq!(latency.invalid_rows(), 3);
+        assert_eq!(latency.dropped_rows(), 0);
+
+        // Interpolated between the out-of-order row and the one with a negative entry latency.
+        assert_eq!(latency.entry(250, &order), 0);
+        // Interpolated toward the row with a negative response latency.
+        assert_eq!(latency.response(415, &order), 0);
+        assert_eq!(latency.clamped(), 2);
+
+        let mut latency = IntpOrderLatency::build(corrupted_data(), false, 0)
+            .unwrap()
+            .min_latency(5);
+        assert_eq!(latency.entry(250, &order), 5);
+        assert_eq!(latency.clamped(), 1);
+    }
+
+    #[test]
+    fn test_stochastic_latency() {
+        let lognormal = LatencyDistribution::LogNormal {
+            mu: 1000f64.ln(),
+            sigma: 1.0,
+        };
+        let gamma = LatencyDistribution::Gamma {
+            shape: 2.0,
+            scale: 500.0,
+        };
+        assert!(
+            !LatencyDistribution::LogNormal {
+                mu: 0.0,
+                sigma: -1.0
+            }
+            .is_valid()
+        );
+        assert!(
+            !LatencyDistribution::Gamma {
+                shape: 0.0,
+                scale: 1.0
+            }
+            .is_valid()
+        );
+
+        let mut order = Order::new(
+            1,
+            100,
+            1.0,
+            1.0,
+            Side::Buy,
+            OrdType::Limit,
+            TimeInForce::GTC,
+        );
+        order.status = Status::New;
+        let draw = |seed| {
+            let mut latency = StochasticLatency::new(lognormal, gamma, seed);
+            (0..1000)
+                .map(|i| (latency.entry(i, &order), latency.response(i, &order)))
+                .collect::<Vec<_>>()
+        };
+        let latencies = draw(1);
+        assert_eq!(latencies, draw(1));
+        assert_ne!(latencies, draw(2));
+        assert!(
+            latencies
+                .iter()
+                .all(|&(entry, response)| entry > 0 && response > 0)
+        );
+        // Each response for the order is received no earlier than the one before it.
+        let local_recv: Vec<_> = (0..)
+            .zip(&latencies)
+            .map(|(i, &(_, resp))| i + resp)
+            .collect();
+        assert!(local_recv.windows(2).all(|w| w[1] >= w[0]));
+
+        // Once the order is done, the next order with its id starts afresh.
+        let mut latency = StochasticLatency::new(lognormal, gamma, 1);
+        latency.response(0, &order);
+        order.status = Status::Filled;
+        latency.response(0, &order);
+        assert!(latency.last_local_recv.is_empty());
+    }
+}
@@ -4,6 +4,7 @@
 //! * [Latency Models](https://hftbacktest.readthedocs.io/en/latest/latency_models.html)
 //! * [Order Fill](https://hftbacktest.readthedocs.io/en/latest/order_fill.html)
 mod fee;
+mod funding;
 mod latency;
 mod queue;
 
@@ -12,11 +13,21 @@ pub use fee::{
     DirectionalFees,
     FeeModel,
     FlatPerTradeFeeModel,
+    TieredFeeModel,
     TradingQtyFeeModel,
     TradingValueFeeModel,
 };
-pub use latency::{ConstantLatency, IntpOrderLatency, LatencyModel, OrderLatencyRow};
+pub use funding::FundingFeeModel;
+pub use latency::{
+    ConstantLatency,
+    IntpOrderLatency,
+    LatencyDistribution,
+    LatencyModel,
+    OrderLatencyRow,
+    StochasticLatency,
+};
 pub use queue::{
+    EmpiricalQueueModel,
     L3FIFOQueueModel,
     L3QueueModel,
     LogProbQueueFunc,
@@ -28,5 +39,8 @@ pub use queue::{
     Probability,
     QueueModel,
     QueuePos,
+    QueueProfile,
+    QueueProfileRow,
     RiskAdverseQueueModel,
+    estimate_queue_profile,
 };
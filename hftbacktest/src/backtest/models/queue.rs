@@ -1,16 +1,28 @@
 use std::{
     any::Any,
-    collections::{HashMap, HashSet, VecDeque, hash_map::Entry},
+    collections::{BTreeMap, HashMap, HashSet, VecDeque, hash_map::Entry},
+    io::{Error as IoError, ErrorKind},
     marker::PhantomData,
 };
 
+use hftbacktest_derive::NpyDTyped;
+
 use crate::{
-    backtest::BacktestError,
+    backtest::{
+        BacktestError,
+        data::{POD, read_npz_file},
+    },
     depth::{INVALID_MAX, INVALID_MIN, MarketDepth},
     types::{
+        ADD_ORDER_EVENT,
         AnyClone,
         BUY_EVENT,
+        CANCEL_ORDER_EVENT,
+        DEPTH_CLEAR_EVENT,
+        EXCH_EVENT,
         Event,
+        FILL_EVENT,
+        MODIFY_ORDER_EVENT,
         OrdType,
         Order,
         OrderId,
@@ -121,6 +133,68 @@ impl Default for QueuePos {
     }
 }
 
+impl QueuePos {
+    /// Places a new order at the back of the queue at its price level.
+    fn new_order<MD: MarketDepth>(order: &mut Order, depth: &MD) {
+        let mut q = QueuePos::default();
+        if order.side == Side::Buy {
+            q.front_q_qty = depth.bid_qty_at_tick(order.price_tick);
+        } else {
+            q.front_q_qty = depth.ask_qty_at_tick(order.price_tick);
+        }
+        order.q = Box::new(q);
+    }
+
+    fn trade(order: &mut Order, qty: f64) {
+        let q = order.q.as_any_mut().downcast_mut::<QueuePos>().unwrap();
+        q.front_q_qty -= qty;
+        q.cum_trade_qty += qty;
+    }
+
+    /// Advances the queue position by the part of a decrease of the level quantity, net of the
+    /// trades since the last change, that `prob(front, back)`, the probability of the decrease
+    /// being behind the order, puts ahead of it.
+    fn depth<F>(order: &mut Order, prev_qty: f64, new_qty: f64, prob: F)
+    where
+        F: FnOnce(f64, f64) -> f64,
+    {
+        let mut chg = prev_qty - new_qty;
+        // In order to avoid duplicate order queue position adjustment, subtract queue position
+        // change by trades.
+        let q = order.q.as_any_mut().downcast_mut::<QueuePos>().unwrap();
+        chg -= q.cum_trade_qty;
+        // Reset, as quantity change by trade should be already reflected in qty.
+        q.cum_trade_qty = 0.0;
+        // For an increase of the quantity, front queue doesn't change by the quantity change.
+        if chg < 0.0 {
+            q.front_q_qty = q.front_q_qty.min(new_qty);
+            return;
+        }
+
+        let front = q.front_q_qty;
+        let back = prev_qty - front;
+
+        let mut prob = prob(front, back);
+        if prob.is_infinite() {
+            prob = 1.0;
+        }
+
+        let est_front = front - (1.0 - prob) * chg + (back - prob * chg).min(0.0);
+        q.front_q_qty = est_front.min(new_qty);
+    }
+
+    fn is_filled<MD: MarketDepth>(order: &mut Order, depth: &MD) -> f64 {
+        let q = order.q.as_any_mut().downcast_mut::<QueuePos>().unwrap();
+        let exec = (-q.front_q_qty / depth.lot_size()).round() as i64;
+        if exec > 0 {
+            q.front_q_qty = 0.0;
+            (exec as f64) * depth.lot_size()
+        } else {
+            0.0
+        }
+    }
+}
+
 /// Provides the probability of a decrease behind the order's queue position.
 pub trait Probability {
     /// Returns the probability based on the quantity ahead and behind the order.
@@ -163,56 +237,21 @@ where
     MD: MarketDepth,
 {
     fn new_order(&self, order: &mut Order, depth: &MD) {
-        let mut q = QueuePos::default();
-        if order.side == Side::Buy {
-            q.front_q_qty = depth.bid_qty_at_tick(order.price_tick);
-        } else {
-            q.front_q_qty = depth.ask_qty_at_tick(order.price_tick);
-        }
-        order.q = Box::new(q);
+        QueuePos::new_order(order, depth);
     }
 
     fn trade(&self, order: &mut Order, qty: f64, _depth: &MD) {
-        let q = order.q.as_any_mut().downcast_mut::<QueuePos>().unwrap();
-        q.front_q_qty -= qty;
-        q.cum_trade_qty += qty;
+        QueuePos::trade(order, qty);
     }
 
     fn depth(&self, order: &mut Order, prev_qty: f64, new_qty: f64, _depth: &MD) {
-        let mut chg = prev_qty - new_qty;
-        // In order to avoid duplicate order queue position adjustment, subtract queue position
-        // change by trades.
-        let q = order.q.as_any_mut().downcast_mut::<QueuePos>().unwrap();
-        chg -= q.cum_trade_qty;
-        // Reset, as quantity change by trade should be already reflected in qty.
-        q.cum_trade_qty = 0.0;
-        // For an increase of the quantity, front queue doesn't change by the quantity change.
-        if chg < 0.0 {
-            q.front_q_qty = q.front_q_qty.min(new_qty);
-            return;
-        }
-
-        let front = q.front_q_qty;
-        let back = prev_qty - front;
-
-        let mut prob = self.prob.prob(front, back);
-        if prob.is_infinite() {
-            prob = 1.0;
-        }
-
-        let est_front = front - (1.0 - prob) * chg + (back - prob * chg).min(0.0);
-        q.front_q_qty = est_front.min(new_qty);
+        QueuePos::depth(order, prev_qty, new_qty, |front, back| {
+            self.prob.prob(front, back)
+        });
     }
 
     fn is_filled(&self, order: &mut Order, depth: &MD) -> f64 {
-        let q = order.q.as_any_mut().downcast_mut::<QueuePos>().unwrap();
-        let exec = (-q.front_q_qty / depth.lot_size()).round() as i64;
-        if exec > 0 {
-            q.front_q_qty = 0.0;
-            (exec as f64) * depth.lot_size()
-        } else {
-            0.0
-        }
+        QueuePos::is_filled(order, depth)
     }
 }
 
@@ -329,6 +368,340 @@ impl Probability for PowerProbQueueFunc3 {
     }
 }
 
+/// A point of a [`QueueProfile`], as stored in the `data` array of a queue profile `.npz` file.
+#[repr(C)]
+#[derive(Clone, Debug, NpyDTyped)]
+pub struct QueueProfileRow {
+    /// Distance of the price level from the best price on its side, in ticks.
+    pub level: i64,
+    /// Relative queue position, the fraction of the other orders' quantity at the level that is
+    /// ahead, from 0 at the front to 1 at the back.
+    pub position: f64,
+    /// Probability that a cancel at the level is ahead of an order at `position`.
+    pub prob_ahead: f64,
+}
+
+unsafe impl POD for QueueProfileRow {}
+
+/// The probability that a cancel is ahead of an order, given the distance of its price level from
+/// the best price and its relative queue position, estimated from data by
+/// [`estimate_queue_profile`].
+///
+/// Between the positions of a level, the probability is interpolated linearly; outside them, the
+/// nearest one is used. A level missing from the profile uses the closest level below it, and
+/// levels beyond the last one use the last one.
+#[derive(Clone, Debug)]
+pub struct QueueProfile {
+    levels: BTreeMap<i64, Vec<(f64, f64)>>,
+}
+
+impl QueueProfile {
+    /// Constructs a `QueueProfile` from its points, in any order. Fails if there is none, or if a
+    /// position or probability is outside `[0, 1]`.
+    pub fn new(rows: &[QueueProfileRow]) -> Result<Self, IoError> {
+        if rows.is_empty() {
+            return Err(IoError::new(
+                ErrorKind::InvalidData,
+                "the queue profile is empty",
+            ));
+        }
+        let mut levels: BTreeMap<i64, Vec<(f64, f64)>> = BTreeMap::new();
+        for row in rows {
+            if !(0.0..=1.0).contains(&row.position) || !(0.0..=1.0).contains(&row.prob_ahead) {
+                return Err(IoError::new(
+                    ErrorKind::InvalidData,
+                    format!("the queue profile point {row:?} is outside [0, 1]"),
+                ));
+            }
+            levels
+                .entry(row.level)
+                .or_default()
+                .push((row.position, row.prob_ahead));
+        }
+        for points in levels.values_mut() {
+            points.sort_by(|a, b| a.0.total_cmp(&b.0));
+        }
+        Ok(Self { levels })
+    }
+
+    /// Loads a `QueueProfile` from the `data` array of an `.npz` file.
+    pub fn from_file(filepath: &str) -> Result<Self, IoError> {
+        let data = read_npz_file::<QueueProfileRow>(filepath, "data")?;
+        let rows: Vec<_> = (0..data.len()).map(|i| data[i].clone()).collect();
+        Self::new(&rows)
+    }
+
+    /// Returns the probability that a cancel at `level` ticks from the best price is ahead of an
+    /// order at the relative queue `position`.
+    pub fn prob_ahead(&self, level: i64, position: f64) -> f64 {
+        let points = self
+            .levels
+            .range(..=level)
+            .next_back()
+            .or_else(|| self.levels.first_key_value())
+            .map(|(_, points)| points)
+            .unwrap();
+        let i = points.partition_point(|&(x, _)| x <= position);
+        if i == 0 {
+            return points[0].1;
+        }
+        if i == points.len() {
+            return points[i - 1].1;
+        }
+        let (x1, y1) = points[i - 1];
+        let (x2, y2) = points[i];
+        y1 + (y2 - y1) * (position - x1) / (x2 - x1)
+    }
+}
+
+/// Provides a queue position model within the [`ProbQueueModel`] framework, where the probability
+/// of a decrease in the level quantity being ahead of the order comes from a [`QueueProfile`]
+/// estimated from data instead of a fixed function.
+///
+/// As in [`ProbQueueModel`], trades at the level advance the order, and the remaining decrease,
+/// the cancels, is split between ahead of and behind the order by the profile, at the distance of
+/// the order's price from the best price and its relative queue position. A profile of `x ** n`
+/// at every level reproduces [`PowerProbQueueFunc3`].
+///
+/// **Example**
+/// ```no_run
+/// use hftbacktest::{
+///     backtest::models::{EmpiricalQueueModel, QueueProfile},
+///     depth::HashMapMarketDepth,
+/// };
+///
+/// let queue_model = EmpiricalQueueModel::<HashMapMarketDepth>::new(
+///     QueueProfile::from_file("queue_profile_20240215.npz").unwrap(),
+/// );
+/// ```
+pub struct EmpiricalQueueModel<MD> {
+    profile: QueueProfile,
+    _md_marker: PhantomData<MD>,
+}
+
+impl<MD> EmpiricalQueueModel<MD> {
+    /// Constructs an instance of `EmpiricalQueueModel`.
+    pub fn new(profile: QueueProfile) -> Self {
+        Self {
+            profile,
+            _md_marker: Default::default(),
+        }
+    }
+}
+
+impl<MD> QueueModel<MD> for EmpiricalQueueModel<MD>
+where
+    MD: MarketDepth,
+{
+    fn new_order(&self, order: &mut Order, depth: &MD) {
+        QueuePos::new_order(order, depth);
+    }
+
+    fn trade(&self, order: &mut Order, qty: f64, _depth: &MD) {
+        QueuePos::trade(order, qty);
+    }
+
+    fn depth(&self, order: &mut Order, prev_qty: f64, new_qty: f64, depth: &MD) {
+        let level = if order.side == Side::Buy {
+            let best_bid_tick = depth.best_bid_tick();
+            if best_bid_tick == INVALID_MIN {
+                0
+            } else {
+                best_bid_tick - order.price_tick
+            }
+        } else {
+            let best_ask_tick = depth.best_ask_tick();
+            if best_ask_tick == INVALID_MAX {
+                0
+            } else {
+                order.price_tick - best_ask_tick
+            }
+        };
+        QueuePos::depth(order, prev_qty, new_qty, |front, back| {
+            1.0 - self
+                .profile
+                .prob_ahead(level.max(0), front / (front + back))
+        });
+    }
+
+    fn is_filled(&self, order: &mut Order, depth: &MD) -> f64 {
+        QueuePos::is_filled(order, depth)
+    }
+}
+
+/// Estimates a [`QueueProfile`] for [`EmpiricalQueueModel`] from Level 3 Market-By-Order events,
+/// returning its points for `levels` price levels from the best price, each at `bins` evenly
+/// spaced relative queue positions from 0 to 1.
+///
+/// The book is rebuilt from the exchange-side add, modify, cancel, fill and clear events. For each
+/// cancel, the fraction of the other orders' quantity at the level ahead of the canceled order is
+/// recorded, weighted by its quantity, so that the probability of a cancel being ahead of an order
+/// at position `x` is the share of canceled quantity recorded below `x`. A modify that moves the
+/// price or increases the quantity sends the order to the back of the queue. Levels without any
+/// cancel get the uniform profile, `prob_ahead = position`.
+pub fn estimate_queue_profile(
+    events: &[Event],
+    tick_size: f64,
+    levels: usize,
+    bins: usize,
+) -> Vec<QueueProfileRow> {
+    let bins = bins.max(2);
+    let mut book = L3Book::default();
+    let mut cancels: Vec<Vec<(f64, f64)>> = vec![Vec::new(); levels];
+    for event in events.iter().filter(|event| event.ev & EXCH_EVENT != 0) {
+        let side = if event.ev & BUY_EVENT != 0 {
+            Side::Buy
+        } else if event.ev & SELL_EVENT != 0 {
+            Side::Sell
+        } else {
+            Side::None
+        };
+        let price_tick = (event.px / tick_size).round() as i64;
+        match event.ev & 0xff {
+            ADD_ORDER_EVENT if side != Side::None => {
+                book.add(event.order_id, side, price_tick, event.qty);
+            }
+            CANCEL_ORDER_EVENT => {
+                if let Some((level, position, qty)) = book.cancel_position(event.order_id)
+                    && let Some(cancels) = cancels.get_mut(level as usize)
+                {
+                    cancels.push((position, qty));
+                }
+                book.remove(event.order_id);
+            }
+            MODIFY_ORDER_EVENT => book.modify(event.order_id, price_tick, event.qty),
+            FILL_EVENT => book.fill(event.order_id, event.qty),
+            DEPTH_CLEAR_EVENT => book.clear(side),
+            _ => {}
+        }
+    }
+
+    let mut rows = Vec::with_capacity(levels * bins);
+    for (level, cancels) in cancels.iter().enumerate() {
+        let total: f64 = cancels.iter().map(|(_, qty)| qty).sum();
+        for i in 0..bins {
+            let position = i as f64 / (bins - 1) as f64;
+            let prob_ahead = if i == bins - 1 {
+                1.0
+            } else if total > 0.0 {
+                cancels
+                    .iter()
+                    .filter(|(x, _)| *x < position)
+                    .map(|(_, qty)| qty)
+                    .sum::<f64>()
+                    / total
+            } else {
+                position
+            };
+            rows.push(QueueProfileRow {
+                level: level as i64,
+                position,
+                prob_ahead,
+            });
+        }
+    }
+    rows
+}
+
+/// A Market-By-Order book with FIFO queues, rebuilt by [`estimate_queue_profile`].
+#[derive(Default)]
+struct L3Book {
+    bids: BTreeMap<i64, VecDeque<(OrderId, f64)>>,
+    asks: BTreeMap<i64, VecDeque<(OrderId, f64)>>,
+    orders: HashMap<OrderId, (Side, i64)>,
+}
+
+impl L3Book {
+    fn side_mut(&mut self, side: Side) -> &mut BTreeMap<i64, VecDeque<(OrderId, f64)>> {
+        if side == Side::Buy {
+            &mut self.bids
+        } else {
+            &mut self.asks
+        }
+    }
+
+    fn add(&mut self, order_id: OrderId, side: Side, price_tick: i64, qty: f64) {
+        self.remove(order_id);
+        self.side_mut(side)
+            .entry(price_tick)
+            .or_default()
+            .push_back((order_id, qty));
+        self.orders.insert(order_id, (side, price_tick));
+    }
+
+    fn remove(&mut self, order_id: OrderId) -> Option<(Side, i64, f64)> {
+        let (side, price_tick) = self.orders.remove(&order_id)?;
+        let queues = self.side_mut(side);
+        let queue = queues.get_mut(&price_tick)?;
+        let i = queue.iter().position(|(id, _)| *id == order_id)?;
+        let (_, qty) = queue.remove(i)?;
+        if queue.is_empty() {
+            queues.remove(&price_tick);
+        }
+        Some((side, price_tick, qty))
+    }
+
+    /// Returns the distance of the order's level from the best price in ticks, its relative
+    /// queue position and its quantity, or `None` if it is unknown or alone at its level.
+    fn cancel_position(&self, order_id: OrderId) -> Option<(i64, f64, f64)> {
+        let &(side, price_tick) = self.orders.get(&order_id)?;
+        let (queues, level) = if side == Side::Buy {
+            (&self.bids, *self.bids.last_key_value()?.0 - price_tick)
+        } else {
+            (&self.asks, price_tick - *self.asks.first_key_value()?.0)
+        };
+        let queue = queues.get(&price_tick)?;
+        let i = queue.iter().position(|(id, _)| *id == order_id)?;
+        let ahead: f64 = queue.iter().take(i).map(|(_, qty)| qty).sum();
+        let others: f64 = queue.iter().map(|(_, qty)| qty).sum::<f64>() - queue[i].1;
+        (others > 0.0).then(|| (level, ahead / others, queue[i].1))
+    }
+
+    fn modify(&mut self, order_id: OrderId, price_tick: i64, qty: f64) {
+        let Some(&(side, prev_price_tick)) = self.orders.get(&order_id) else {
+            return;
+        };
+        if let Some(queue) = self.side_mut(side).get_mut(&prev_price_tick)
+            && let Some(entry) = queue.iter_mut().find(|(id, _)| *id == order_id)
+            && price_tick == prev_price_tick
+            && qty <= entry.1
+        {
+            // Reducing the quantity keeps the queue priority.
+            entry.1 = qty;
+            return;
+        }
+        self.add(order_id, side, price_tick, qty);
+    }
+
+    fn fill(&mut self, order_id: OrderId, qty: f64) {
+        let Some(&(side, price_tick)) = self.orders.get(&order_id) else {
+            return;
+        };
+        let remaining = self
+            .side_mut(side)
+            .get_mut(&price_tick)
+            .and_then(|queue| queue.iter_mut().find(|(id, _)| *id == order_id))
+            .map(|entry| {
+                entry.1 -= qty;
+                entry.1
+            });
+        if remaining.is_some_and(|remaining| remaining <= 0.0) {
+            self.remove(order_id);
+        }
+    }
+
+    fn clear(&mut self, side: Side) {
+        if side != Side::Sell {
+            self.bids.clear();
+        }
+        if side != Side::Buy {
+            self.asks.clear();
+        }
+        self.orders
+            .retain(|_, (order_side, _)| side != Side::None && *order_side != side);
+    }
+}
+
 /// Represents the order source for the Level 3 Market-By-Order queue model, which is stored in
 /// [`order.q`](crate::types::Order::q)
 #[derive(Copy, Clone, Eq, PartialEq)]
@@ -663,8 +1036,10 @@ where
 
         queue.push_back(Order {
             qty: order.qty,
+            display_qty: 0.0,
             leaves_qty: order.qty,
             price_tick: order_price_tick,
+            trigger_price_tick: 0,
             exch_timestamp: order.exch_ts,
             q: Box::new(L3OrderSource::MarketFeed),
             tick_size,
@@ -1176,10 +1551,12 @@ mod l3_tests {
         qm.add_backtest_order(
             Order {
                 qty: 1.0,
+                display_qty: 0.0,
                 leaves_qty: 0.0,
                 exec_qty: 0.0,
                 exec_price_tick: 0,
                 price_tick: 100,
+                trigger_price_tick: 0,
                 tick_size: 1.0,
                 exch_timestamp: 0,
                 local_timestamp: 0,
@@ -1210,10 +1587,12 @@ mod l3_tests {
         qm.add_backtest_order(
             Order {
                 qty: 1.0,
+                display_qty: 0.0,
                 leaves_qty: 0.0,
                 exec_qty: 0.0,
                 exec_price_tick: 0,
                 price_tick: 101,
+                trigger_price_tick: 0,
                 tick_size: 1.0,
                 exch_timestamp: 0,
                 local_timestamp: 0,
@@ -1266,10 +1645,12 @@ mod l3_tests {
         qm.add_backtest_order(
             Order {
                 qty: 1.0,
+                display_qty: 0.0,
                 leaves_qty: 0.0,
                 exec_qty: 0.0,
                 exec_price_tick: 0,
                 price_tick: 100,
+                trigger_price_tick: 0,
                 tick_size: 1.0,
                 exch_timestamp: 0,
                 local_timestamp: 0,
@@ -1356,3 +1737,168 @@ mod l3_tests {
         );
     }
 }
+
+#[cfg(test)]
+mod empirical_tests {
+    use crate::{
+        backtest::models::{
+            EmpiricalQueueModel,
+            PowerProbQueueFunc3,
+            ProbQueueModel,
+            QueueModel,
+            QueuePos,
+            QueueProfile,
+            QueueProfileRow,
+            estimate_queue_profile,
+        },
+        prelude::{
+            Event,
+            HashMapMarketDepth,
+            L2MarketDepth,
+            OrdType,
+            Order,
+            Side,
+            Status,
+            TimeInForce,
+        },
+        types::{ADD_ORDER_EVENT, BUY_EVENT, CANCEL_ORDER_EVENT, EXCH_EVENT},
+    };
+
+    fn buy_order(price_tick: i64) -> Order {
+        Order {
+            qty: 1.0,
+            display_qty: 0.0,
+            leaves_qty: 1.0,
+            exec_qty: 0.0,
+            exec_price_tick: 0,
+            price_tick,
+            trigger_price_tick: 0,
+            tick_size: 1.0,
+            exch_timestamp: 0,
+            local_timestamp: 0,
+            order_id: 1,
+            q: Box::new(()),
+            maker: false,
+            order_type: OrdType::Limit,
+            req: Status::None,
+            status: Status::None,
+            side: Side::Buy,
+            time_in_force: TimeInForce::GTC,
+        }
+    }
+
+    fn front_q_qty(order: &Order) -> f64 {
+        order
+            .q
+            .as_any()
+            .downcast_ref::<QueuePos>()
+            .unwrap()
+            .front_q_qty
+    }
+
+    #[test]
+    fn degenerates_to_power_model() {
+        let n = 2.0;
+        let rows: Vec<_> = (0..2)
+            .flat_map(|level| {
+                (0..=100).map(move |i| {
+                    let position = i as f64 / 100.0;
+                    QueueProfileRow {
+                        level,
+                        position,
+                        prob_ahead: position.powf(n),
+                    }
+                })
+            })
+            .collect();
+        let empirical = EmpiricalQueueModel::new(QueueProfile::new(&rows).unwrap());
+        let power = ProbQueueModel::new(PowerProbQueueFunc3::new(n));
+
+        let mut depth = HashMapMarketDepth::new(1.0, 1.0);
+        depth.update_bid_depth(100.0, 50.0, 0);
+        depth.update_bid_depth(99.0, 50.0, 0);
+
+        for price_tick in [100, 99] {
+            let mut order_e = buy_order(price_tick);
+            let mut order_p = buy_order(price_tick);
+            empirical.new_order(&mut order_e, &depth);
+            power.new_order(&mut order_p, &depth);
+
+            let price = price_tick as f64;
+            let mut prev_qty = 50.0;
+            depth.update_bid_depth(price, 70.0, 0);
+            for (qty, trade) in [
+                (70.0, 0.0),
+                (61.0, 3.0),
+                (40.0, 0.0),
+                (33.0, 5.0),
+                (12.0, 0.0),
+            ] {
+                if trade > 0.0 {
+                    empirical.trade(&mut order_e, trade, &depth);
+                    power.trade(&mut order_p, trade, &depth);
+                }
+                depth.update_bid_depth(price, qty, 0);
+                empirical.depth(&mut order_e, prev_qty, qty, &depth);
+                power.depth(&mut order_p, prev_qty, qty, &depth);
+                prev_qty = qty;
+
+                let (front_e, front_p) = (front_q_qty(&order_e), front_q_qty(&order_p));
+                assert!(
+                    (front_e - front_p).abs() < 1e-3,
+                    "{front_e} != {front_p} at {price_tick}"
+                );
+            }
+            assert!(front_q_qty(&order_e) < 50.0);
+        }
+    }
+
+    #[test]
+    fn estimates_queue_profile() {
+        let add = |order_id, px| Event {
+            ev: EXCH_EVENT | BUY_EVENT | ADD_ORDER_EVENT,
+            exch_ts: 0,
+            local_ts: 0,
+            px,
+            qty: 1.0,
+            order_id,
+            ival: 0,
+            fval: 0.0,
+        };
+        let cancel = |order_id| Event {
+            ev: EXCH_EVENT | CANCEL_ORDER_EVENT,
+            exch_ts: 0,
+            local_ts: 0,
+            px: 0.0,
+            qty: 0.0,
+            order_id,
+            ival: 0,
+            fval: 0.0,
+        };
+        let events = [
+            add(1, 100.0),
+            add(2, 100.0),
+            add(3, 100.0),
+            add(4, 100.0),
+            add(5, 99.0),
+            // Two of the three other orders are ahead.
+            cancel(3),
+            // At the front.
+            cancel(1),
+            // Alone at its level, so it tells nothing about the queue.
+            cancel(5),
+        ];
+
+        let rows = estimate_queue_profile(&events, 1.0, 2, 4);
+        let probs: Vec<_> = rows.iter().map(|row| row.prob_ahead).collect();
+        let positions: Vec<_> = rows.iter().map(|row| row.position).collect();
+        assert_eq!(&positions[..4], &[0.0, 1.0 / 3.0, 2.0 / 3.0, 1.0]);
+        assert_eq!(&probs[..4], &[0.0, 0.5, 0.5, 1.0]);
+        assert_eq!(&probs[4..], &positions[4..]);
+
+        let profile = QueueProfile::new(&rows).unwrap();
+        assert_eq!(profile.prob_ahead(0, 0.5), 0.5);
+        assert_eq!(profile.prob_ahead(5, 0.5), 0.5);
+        assert!(QueueProfile::new(&[]).is_err());
+    }
+}
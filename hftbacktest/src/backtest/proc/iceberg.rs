@@ -0,0 +1,56 @@
+use crate::types::Order;
+
+/// Returns the quantity of the clip currently displayed for the order, which is all that can be
+/// filled before the order has to rejoin the queue. For an order that is not an iceberg order, this
+/// is its whole leaves quantity.
+///
+/// Clips are counted from the order's original quantity, so the displayed clip is replenished
+/// only after it has been fully filled, and the last clip can be smaller than the display quantity.
+pub(crate) fn clip_qty(order: &Order, lot_size: f64) -> f64 {
+    if !order.is_iceberg() {
+        return order.leaves_qty;
+    }
+    let display_lots = ((order.display_qty / lot_size).round() as i64).max(1);
+    let filled_lots = ((order.qty - order.leaves_qty) / lot_size).round() as i64;
+    let clip_lots = display_lots - filled_lots % display_lots;
+    ((clip_lots as f64) * lot_size).min(order.leaves_qty)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{OrdType, Side, TimeInForce};
+
+    fn order(qty: f64, display_qty: f64, leaves_qty: f64) -> Order {
+        let mut order = Order::new(
+            1,
+            100,
+            1.0,
+            qty,
+            Side::Buy,
+            OrdType::Limit,
+            TimeInForce::GTC,
+        );
+        order.display_qty = display_qty;
+        order.leaves_qty = leaves_qty;
+        order
+    }
+
+    #[test]
+    fn test_clip_qty() {
+        // Not an iceberg order.
+        assert_eq!(clip_qty(&order(10.0, 0.0, 7.0), 1.0), 7.0);
+        assert_eq!(clip_qty(&order(10.0, 10.0, 7.0), 1.0), 7.0);
+
+        assert_eq!(clip_qty(&order(10.0, 3.0, 10.0), 1.0), 3.0);
+        // Partially filled within the first clip.
+        assert_eq!(clip_qty(&order(10.0, 3.0, 8.0), 1.0), 1.0);
+        // The second clip.
+        assert_eq!(clip_qty(&order(10.0, 3.0, 7.0), 1.0), 3.0);
+        // The last clip is what is left.
+        assert_eq!(clip_qty(&order(10.0, 3.0, 1.0), 1.0), 1.0);
+
+        assert!((clip_qty(&order(1.0, 0.3, 0.7), 0.1) - 0.3).abs() < 1e-9);
+        assert!((clip_qty(&order(1.0, 0.3, 0.5), 0.1) - 0.1).abs() < 1e-9);
+    }
+}
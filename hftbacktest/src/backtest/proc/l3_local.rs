@@ -4,7 +4,7 @@ use crate::{
     backtest::{
         BacktestError,
         assettype::AssetType,
-        models::{FeeModel, LatencyModel},
+        models::{FeeModel, FundingFeeModel, LatencyModel},
         order::LocalToExch,
         proc::{LocalProcessor, Processor},
         state::State,
@@ -46,6 +46,7 @@ where
     trades: Vec<Event>,
     last_feed_latency: Option<(i64, i64)>,
     last_order_latency: Option<(i64, i64, i64)>,
+    funding: FundingFeeModel,
 }
 
 impl<AT, LM, MD, FM> L3Local<AT, LM, MD, FM>
@@ -70,8 +71,16 @@ where
             trades: Vec::with_capacity(trade_len),
             last_feed_latency: None,
             last_order_latency: None,
+            funding: Default::default(),
         }
     }
+
+    /// Sets the [`FundingFeeModel`] that charges the funding payments of a perpetual swap. By
+    /// default, no funding is charged.
+    pub fn funding(mut self, funding: FundingFeeModel) -> Self {
+        self.funding = funding;
+        self
+    }
 }
 
 impl<AT, LM, MD, FM> LocalProcessor<MD> for L3Local<AT, LM, MD, FM>
@@ -90,6 +99,8 @@ where
         qty: f64,
         order_type: OrdType,
         time_in_force: TimeInForce,
+        trigger_price: f64,
+        display_qty: f64,
         current_timestamp: i64,
     ) -> Result<(), BacktestError> {
         if self.orders.contains_key(&order_id) {
@@ -106,6 +117,10 @@ where
             order_type,
             time_in_force,
         );
+        if order_type.is_stop() {
+            order.trigger_price_tick = (trigger_price / self.depth.tick_size()).round() as i64;
+        }
+        order.display_qty = display_qty;
         order.req = Status::New;
         order.local_timestamp = current_timestamp;
         self.orders.insert(order.order_id, order.clone());
@@ -175,6 +190,7 @@ where
     fn clear_inactive_orders(&mut self) {
         self.orders.retain(|_, order| {
             order.status != Status::Expired
+                && order.status != Status::Rejected
                 && order.status != Status::Filled
                 && order.status != Status::Canceled
         })
@@ -226,6 +242,12 @@ where
     }
 
     fn process(&mut self, ev: &Event) -> Result<(), BacktestError> {
+        // Settles the funding due by this event before it changes the market depth.
+        let mid = (self.depth.best_bid() + self.depth.best_ask()) / 2.0;
+        while let Some((rate, mark_price)) = self.funding.next_funding(ev.exch_ts, mid) {
+            self.state.apply_funding(rate, mark_price);
+        }
+
         // Processes a depth event
         if ev.is(LOCAL_BID_DEPTH_CLEAR_EVENT) {
             self.depth.clear_orders(Side::Buy);
@@ -279,8 +301,13 @@ where
             }
 
             // Processes receiving order response.
-            if order.status == Status::Filled {
+            if order.status == Status::Filled || order.status == Status::PartiallyFilled {
                 self.state.apply_fill(&order);
+            } else if order.time_in_force == TimeInForce::GTX
+                && (order.status == Status::Expired || order.status == Status::Rejected)
+            {
+                // The post-only order would have taken liquidity.
+                self.state.apply_post_only_reject();
             }
             // Applies the received order response to the local orders.
             match self.orders.entry(order.order_id) {
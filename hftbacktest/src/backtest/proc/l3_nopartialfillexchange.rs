@@ -174,6 +174,12 @@ where
             return Err(BacktestError::OrderIdExist);
         }
 
+        // Iceberg orders are not supported since the displayed clip would need its own position in
+        // the market-by-order queue.
+        if order.is_iceberg() {
+            return Err(BacktestError::InvalidOrderRequest);
+        }
+
         if order.side == Side::Buy {
             match order.order_type {
                 OrdType::Limit => {
@@ -222,7 +228,9 @@ where
                     // Takes the market.
                     self.fill::<false>(order, timestamp, false, self.depth.best_ask_tick())
                 }
-                OrdType::Unsupported => Err(BacktestError::InvalidOrderRequest),
+                OrdType::StopMarket | OrdType::StopLimit | OrdType::Unsupported => {
+                    Err(BacktestError::InvalidOrderRequest)
+                }
             }
         } else {
             match order.order_type {
@@ -272,7 +280,9 @@ where
                     // Takes the market.
                     self.fill::<false>(order, timestamp, false, self.depth.best_bid_tick())
                 }
-                OrdType::Unsupported => Err(BacktestError::InvalidOrderRequest),
+                OrdType::StopMarket | OrdType::StopLimit | OrdType::Unsupported => {
+                    Err(BacktestError::InvalidOrderRequest)
+                }
             }
         }
     }
@@ -0,0 +1,84 @@
+use crate::{
+    depth::MarketDepth,
+    types::{Order, Side},
+};
+
+/// Returns how much of the order's leaves quantity can be taken on arrival from the opposite side
+/// of the book, walking the levels from the best price to the order's price. This is what an
+/// [`TimeInForce::IOC`](crate::types::TimeInForce::IOC) order fills, and a
+/// [`TimeInForce::FOK`](crate::types::TimeInForce::FOK) order is only executed if it is the whole
+/// leaves quantity.
+///
+/// The walk stops as soon as the leaves quantity is covered or past the last level of the book,
+/// so it does not depend on how far through the book the order's price is.
+pub(crate) fn takeable_qty<MD: MarketDepth>(depth: &MD, order: &Order) -> f64 {
+    let lot_size = depth.lot_size();
+    let leaves_lots = (order.leaves_qty / lot_size).round();
+    let mut cum_qty = 0.0;
+    let mut take = |qty: f64| {
+        cum_qty += qty;
+        (cum_qty / lot_size).round() >= leaves_lots
+    };
+    if order.side == Side::Buy {
+        for t in depth.best_ask_tick()..=order.price_tick.min(depth.high_ask_tick()) {
+            if take(depth.ask_qty_at_tick(t)) {
+                break;
+            }
+        }
+    } else {
+        for t in (order.price_tick.max(depth.low_bid_tick())..=depth.best_bid_tick()).rev() {
+            if take(depth.bid_qty_at_tick(t)) {
+                break;
+            }
+        }
+    }
+    cum_qty.min(order.leaves_qty)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        depth::{HashMapMarketDepth, L2MarketDepth},
+        types::{OrdType, TimeInForce},
+    };
+
+    fn order(side: Side, price_tick: i64, qty: f64) -> Order {
+        Order::new(
+            1,
+            price_tick,
+            1.0,
+            qty,
+            side,
+            OrdType::Limit,
+            TimeInForce::IOC,
+        )
+    }
+
+    #[test]
+    fn test_takeable_qty() {
+        let mut depth = HashMapMarketDepth::new(1.0, 1.0);
+        depth.update_bid_depth(99.0, 2.0, 0);
+        depth.update_bid_depth(98.0, 3.0, 0);
+        depth.update_ask_depth(100.0, 2.0, 0);
+        depth.update_ask_depth(102.0, 3.0, 0);
+
+        assert_eq!(takeable_qty(&depth, &order(Side::Buy, 99, 1.0)), 0.0);
+        assert_eq!(takeable_qty(&depth, &order(Side::Buy, 100, 1.0)), 1.0);
+        assert_eq!(takeable_qty(&depth, &order(Side::Buy, 101, 4.0)), 2.0);
+        assert_eq!(takeable_qty(&depth, &order(Side::Buy, 102, 4.0)), 4.0);
+        assert_eq!(takeable_qty(&depth, &order(Side::Buy, 110, 10.0)), 5.0);
+
+        assert_eq!(takeable_qty(&depth, &order(Side::Sell, 100, 1.0)), 0.0);
+        assert_eq!(takeable_qty(&depth, &order(Side::Sell, 99, 4.0)), 2.0);
+        assert_eq!(takeable_qty(&depth, &order(Side::Sell, 98, 4.0)), 4.0);
+        assert_eq!(takeable_qty(&depth, &order(Side::Sell, 90, 10.0)), 5.0);
+
+        // A limit price far through the book stops at its last level.
+        assert_eq!(takeable_qty(&depth, &order(Side::Buy, i64::MAX, 10.0)), 5.0);
+        assert_eq!(
+            takeable_qty(&depth, &order(Side::Sell, i64::MIN, 10.0)),
+            5.0
+        );
+    }
+}
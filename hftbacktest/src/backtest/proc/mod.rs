@@ -1,12 +1,20 @@
+mod iceberg;
+mod liquidity;
 mod local;
 mod nopartialfillexchange;
 mod partialfillexchange;
+mod postonly;
+mod selftrade;
+mod stoporders;
 
 use std::collections::HashMap;
 
 pub use local::Local;
 pub use nopartialfillexchange::NoPartialFillExchange;
 pub use partialfillexchange::PartialFillExchange;
+pub use postonly::PostOnly;
+pub use selftrade::SelfTradePrevention;
+pub use stoporders::{StopOrders, TriggerSource};
 
 mod l3_local;
 
@@ -36,6 +44,9 @@ where
     ///   the exchange model for details.
     /// * `time_in_force` - Available [`TimeInForce`] options vary depending on the exchange model.
     ///   See to the exchange model for details.
+    /// * `trigger_price` - Trigger price of a stop order. Ignored for other order types.
+    /// * `display_qty` - Displayed quantity of an iceberg order, or `0` to display the full
+    ///   quantity.
     /// * `current_timestamp` - The current backtesting timestamp.
     #[allow(clippy::too_many_arguments)]
     fn submit_order(
@@ -46,6 +57,8 @@ where
         qty: f64,
         order_type: OrdType,
         time_in_force: TimeInForce,
+        trigger_price: f64,
+        display_qty: f64,
         current_timestamp: i64,
     ) -> Result<(), BacktestError>;
 
@@ -11,7 +11,7 @@ use crate::{
         assettype::AssetType,
         models::{FeeModel, LatencyModel, QueueModel},
         order::ExchToLocal,
-        proc::Processor,
+        proc::{PostOnly, Processor, SelfTradePrevention, StopOrders, iceberg, liquidity},
         state::State,
     },
     depth::{INVALID_MAX, INVALID_MIN, L2MarketDepth, MarketDepth},
@@ -38,8 +38,15 @@ use crate::{
 
 /// The exchange model without partial fills.
 ///
-/// Support order types: [OrdType::Limit](crate::types::OrdType::Limit)
-/// Support time-in-force: [`TimeInForce::GTC`], [`TimeInForce::GTX`]
+/// Support order types: [OrdType::Limit](crate::types::OrdType::Limit),
+/// [OrdType::Market](crate::types::OrdType::Market),
+/// [OrdType::StopMarket](crate::types::OrdType::StopMarket),
+/// [OrdType::StopLimit](crate::types::OrdType::StopLimit). See [`StopOrders`] for how stop orders
+/// are triggered and filled.
+/// Support time-in-force: [`TimeInForce::GTC`], [`TimeInForce::GTX`], [`TimeInForce::IOC`],
+/// [`TimeInForce::FOK`]. See [`PostOnly`] for how a [`TimeInForce::GTX`] order that would take
+/// liquidity is handled. See [`SelfTradePrevention`] for how an order that would take liquidity
+/// and trade against our own resting orders is handled.
 ///
 /// **Conditions for Full Execution**
 ///
@@ -55,12 +62,24 @@ use crate::{
 /// - Your order price < buy trade price
 /// - Your order is at the front of the queue && your order price == buy trade price
 ///
+/// **Iceberg Orders**
+///
+/// An order with a [`display_qty`](crate::types::Order::display_qty) smaller than its quantity is
+/// an iceberg order. Reaching the front of the queue fills only its displayed clip, after which the
+/// next clip is put at the back of the queue, so the order is partially filled clip by clip. Fills
+/// from crossing prices still fill the whole order.
+///
 /// **Liquidity-Taking Order**
 ///
 /// Regardless of the quantity at the best, liquidity-taking orders will be fully executed at the
 /// best. Be aware that this may cause unrealistic fill simulations if you attempt to execute a
 /// large quantity.
 ///
+/// The exceptions are [`TimeInForce::IOC`] and [`TimeInForce::FOK`] orders, which are checked
+/// against the quantity resting from the best up to their price. An IOC order is filled at the
+/// best for at most that quantity and its remainder is [`Status::Canceled`], and a FOK order is
+/// [`Status::Expired`] unless that quantity covers the whole order.
+///
 pub struct NoPartialFillExchange<AT, LM, QM, MD, FM>
 where
     AT: AssetType,
@@ -82,6 +101,12 @@ where
     queue_model: QM,
 
     filled_orders: Vec<OrderId>,
+
+    stop_orders: StopOrders,
+
+    post_only: PostOnly,
+
+    self_trade_prevention: SelfTradePrevention,
 }
 
 impl<AT, LM, QM, MD, FM> NoPartialFillExchange<AT, LM, QM, MD, FM>
@@ -108,6 +133,33 @@ where
             state,
             queue_model,
             filled_orders: Default::default(),
+            stop_orders: Default::default(),
+            post_only: Default::default(),
+            self_trade_prevention: Default::default(),
+        }
+    }
+
+    /// Sets how stop orders are triggered and filled. By default, they are triggered by the last
+    /// trade price and stop market orders are filled at the best price without slippage.
+    pub fn stop_orders(self, stop_orders: StopOrders) -> Self {
+        Self {
+            stop_orders,
+            ..self
+        }
+    }
+
+    /// Sets how a post-only order that would cross the opposite best at its arrival is handled.
+    /// The default value is [`PostOnly::Expire`].
+    pub fn post_only(self, post_only: PostOnly) -> Self {
+        Self { post_only, ..self }
+    }
+
+    /// Sets how an order that would take liquidity on arrival and trade against our own resting
+    /// orders is handled. The default value is [`SelfTradePrevention::None`].
+    pub fn self_trade_prevention(self, self_trade_prevention: SelfTradePrevention) -> Self {
+        Self {
+            self_trade_prevention,
+            ..self
         }
     }
 
@@ -128,6 +180,9 @@ where
                 // Updates the order's queue position.
                 self.queue_model.trade(order, qty, &self.depth);
                 if self.queue_model.is_filled(order, &self.depth) > 0.0 {
+                    if self.fill_clip(order, timestamp)? {
+                        return Ok(());
+                    }
                     self.filled_orders.push(order.order_id);
                     return self.fill::<true>(order, timestamp, true, order.price_tick);
                 }
@@ -153,6 +208,9 @@ where
                 // Updates the order's queue position.
                 self.queue_model.trade(order, qty, &self.depth);
                 if self.queue_model.is_filled(order, &self.depth) > 0.0 {
+                    if self.fill_clip(order, timestamp)? {
+                        return Ok(());
+                    }
                     self.filled_orders.push(order.order_id);
                     return self.fill::<true>(order, timestamp, true, order.price_tick);
                 }
@@ -195,6 +253,66 @@ where
         Ok(())
     }
 
+    /// Fills only the displayed clip of an iceberg order that has reached the front of the queue,
+    /// and then puts the order at the back of the queue with its next clip. Returns `false`,
+    /// without filling, if the order is not an iceberg order or the displayed clip is its last.
+    fn fill_clip(&mut self, order: &mut Order, timestamp: i64) -> Result<bool, BacktestError> {
+        let lot_size = self.depth.lot_size();
+        let clip_qty = iceberg::clip_qty(order, lot_size);
+        if ((order.leaves_qty - clip_qty) / lot_size).round() <= 0.0 {
+            return Ok(false);
+        }
+
+        order.maker = true;
+        order.exec_price_tick = order.price_tick;
+        order.exec_qty = clip_qty;
+        order.leaves_qty -= clip_qty;
+        order.status = Status::PartiallyFilled;
+        order.exch_timestamp = timestamp;
+
+        self.state.apply_fill(order);
+        self.order_e2l.respond(order.clone());
+
+        self.queue_model.new_order(order, &self.depth);
+        Ok(true)
+    }
+
+    /// Executes a [`TimeInForce::IOC`] or [`TimeInForce::FOK`] order that crosses the book against
+    /// the quantity resting up to its price, at `exec_price_tick` like any other liquidity-taking
+    /// order. An IOC order takes what is there and its remainder is canceled, while a FOK order
+    /// expires unless it can be filled in full.
+    fn take_visible(
+        &mut self,
+        order: &mut Order,
+        timestamp: i64,
+        exec_price_tick: i64,
+    ) -> Result<(), BacktestError> {
+        let lot_size = self.depth.lot_size();
+        let exec_lots = (liquidity::takeable_qty(&self.depth, order) / lot_size).round();
+        if exec_lots >= (order.leaves_qty / lot_size).round() {
+            return self.fill::<false>(order, timestamp, false, exec_price_tick);
+        }
+
+        if order.time_in_force == TimeInForce::IOC {
+            if exec_lots > 0.0 {
+                order.maker = false;
+                order.exec_price_tick = exec_price_tick;
+                order.exec_qty = exec_lots * lot_size;
+                order.leaves_qty -= order.exec_qty;
+                order.status = Status::PartiallyFilled;
+                order.exch_timestamp = timestamp;
+
+                self.state.apply_fill(order);
+                self.order_e2l.respond(order.clone());
+            }
+            order.status = Status::Canceled;
+        } else {
+            order.status = Status::Expired;
+        }
+        order.exch_timestamp = timestamp;
+        Ok(())
+    }
+
     fn remove_filled_orders(&mut self) {
         if !self.filled_orders.is_empty() {
             let mut orders = self.orders.borrow_mut();
@@ -312,24 +430,60 @@ where
     }
 
     fn ack_new(&mut self, order: &mut Order, timestamp: i64) -> Result<(), BacktestError> {
-        if self.orders.borrow().contains_key(&order.order_id) {
+        if self.orders.borrow().contains_key(&order.order_id)
+            || self.stop_orders.contains(order.order_id)
+        {
             return Err(BacktestError::OrderIdExist);
         }
 
+        if order.order_type.is_stop() {
+            if order.time_in_force == TimeInForce::Unsupported {
+                return Err(BacktestError::InvalidOrderRequest);
+            }
+            // The exchange accepts this order and holds it until its trigger price is reached.
+            order.status = Status::New;
+            order.exch_timestamp = timestamp;
+            self.stop_orders.insert(order.clone());
+            return Ok(());
+        }
+        self.place(order, timestamp)
+    }
+
+    /// Executes the order against the book or rests it in the book. A stop order reaches here
+    /// only once it has been triggered.
+    fn place(&mut self, order: &mut Order, timestamp: i64) -> Result<(), BacktestError> {
+        let (canceled, canceled_taker) = self.self_trade_prevention.prevent(
+            &self.depth,
+            order,
+            &mut self.orders.borrow_mut(),
+            &mut self.buy_orders,
+            &mut self.sell_orders,
+            timestamp,
+        );
+        for maker in canceled {
+            self.order_e2l.respond(maker);
+        }
+        if canceled_taker {
+            return Ok(());
+        }
+
         if order.side == Side::Buy {
             match order.order_type {
-                OrdType::Limit => {
+                OrdType::Limit | OrdType::StopLimit => {
+                    if order.time_in_force == TimeInForce::GTX {
+                        order.price_tick = self
+                            .post_only
+                            .buy_price_tick(order.price_tick, self.depth.best_ask_tick());
+                    }
                     // Checks if the buy order price is greater than or equal to the current best ask.
                     if order.price_tick >= self.depth.best_ask_tick() {
                         match order.time_in_force {
                             TimeInForce::GTX => {
-                                order.status = Status::Expired;
+                                order.status = self.post_only.rejected_status();
                                 order.exch_timestamp = timestamp;
                                 Ok(())
                             }
-                            TimeInForce::GTC | TimeInForce::FOK | TimeInForce::IOC => {
-                                // Since this always fills the full quantity, both FOK and IOC
-                                // orders are also fully filled at the best price.
+                            TimeInForce::GTC => {
                                 // Takes the market.
                                 self.fill::<false>(
                                     order,
@@ -338,6 +492,10 @@ where
                                     self.depth.best_ask_tick(),
                                 )
                             }
+                            TimeInForce::FOK | TimeInForce::IOC => {
+                                let exec_price_tick = self.depth.best_ask_tick();
+                                self.take_visible(order, timestamp, exec_price_tick)
+                            }
                             TimeInForce::Unsupported => Err(BacktestError::InvalidOrderRequest),
                         }
                     } else {
@@ -358,11 +516,16 @@ where
                                     .insert(order.order_id, order.clone());
                                 Ok(())
                             }
-                            TimeInForce::FOK | TimeInForce::IOC => {
+                            TimeInForce::FOK => {
                                 order.status = Status::Expired;
                                 order.exch_timestamp = timestamp;
                                 Ok(())
                             }
+                            TimeInForce::IOC => {
+                                order.status = Status::Canceled;
+                                order.exch_timestamp = timestamp;
+                                Ok(())
+                            }
                             TimeInForce::Unsupported => Err(BacktestError::InvalidOrderRequest),
                         }
                     }
@@ -371,22 +534,37 @@ where
                     // Takes the market.
                     self.fill::<false>(order, timestamp, false, self.depth.best_ask_tick())
                 }
+                OrdType::StopMarket => {
+                    // Takes the market at the best price with the modeled slippage.
+                    let best_ask_tick = self.depth.best_ask_tick();
+                    if best_ask_tick == INVALID_MAX {
+                        order.status = Status::Expired;
+                        order.exch_timestamp = timestamp;
+                        Ok(())
+                    } else {
+                        let exec_price_tick = best_ask_tick + self.stop_orders.slippage_ticks();
+                        self.fill::<false>(order, timestamp, false, exec_price_tick)
+                    }
+                }
                 OrdType::Unsupported => Err(BacktestError::InvalidOrderRequest),
             }
         } else {
             match order.order_type {
-                OrdType::Limit => {
+                OrdType::Limit | OrdType::StopLimit => {
+                    if order.time_in_force == TimeInForce::GTX {
+                        order.price_tick = self
+                            .post_only
+                            .sell_price_tick(order.price_tick, self.depth.best_bid_tick());
+                    }
                     // Checks if the sell order price is less than or equal to the current best bid.
                     if order.price_tick <= self.depth.best_bid_tick() {
                         match order.time_in_force {
                             TimeInForce::GTX => {
-                                order.status = Status::Expired;
+                                order.status = self.post_only.rejected_status();
                                 order.exch_timestamp = timestamp;
                                 Ok(())
                             }
-                            TimeInForce::GTC | TimeInForce::FOK | TimeInForce::IOC => {
-                                // Since this always fills the full quantity, both FOK and IOC
-                                // orders are also fully filled at the best price.
+                            TimeInForce::GTC => {
                                 // Takes the market.
                                 self.fill::<false>(
                                     order,
@@ -395,6 +573,10 @@ where
                                     self.depth.best_bid_tick(),
                                 )
                             }
+                            TimeInForce::FOK | TimeInForce::IOC => {
+                                let exec_price_tick = self.depth.best_bid_tick();
+                                self.take_visible(order, timestamp, exec_price_tick)
+                            }
                             TimeInForce::Unsupported => Err(BacktestError::InvalidOrderRequest),
                         }
                     } else {
@@ -415,11 +597,16 @@ where
                                     .insert(order.order_id, order.clone());
                                 Ok(())
                             }
-                            TimeInForce::FOK | TimeInForce::IOC => {
+                            TimeInForce::FOK => {
                                 order.status = Status::Expired;
                                 order.exch_timestamp = timestamp;
                                 Ok(())
                             }
+                            TimeInForce::IOC => {
+                                order.status = Status::Canceled;
+                                order.exch_timestamp = timestamp;
+                                Ok(())
+                            }
                             TimeInForce::Unsupported => Err(BacktestError::InvalidOrderRequest),
                         }
                     }
@@ -428,12 +615,31 @@ where
                     // Takes the market.
                     self.fill::<false>(order, timestamp, false, self.depth.best_bid_tick())
                 }
+                OrdType::StopMarket => {
+                    // Takes the market at the best price with the modeled slippage.
+                    let best_bid_tick = self.depth.best_bid_tick();
+                    if best_bid_tick == INVALID_MIN {
+                        order.status = Status::Expired;
+                        order.exch_timestamp = timestamp;
+                        Ok(())
+                    } else {
+                        let exec_price_tick = best_bid_tick - self.stop_orders.slippage_ticks();
+                        self.fill::<false>(order, timestamp, false, exec_price_tick)
+                    }
+                }
                 OrdType::Unsupported => Err(BacktestError::InvalidOrderRequest),
             }
         }
     }
 
     fn ack_cancel(&mut self, order: &mut Order, timestamp: i64) -> Result<(), BacktestError> {
+        if let Some(stop_order) = self.stop_orders.remove(order.order_id) {
+            let _ = std::mem::replace(order, stop_order);
+            order.status = Status::Canceled;
+            order.exch_timestamp = timestamp;
+            return Ok(());
+        }
+
         let exch_order = {
             let mut order_borrowed = self.orders.borrow_mut();
             order_borrowed.remove(&order.order_id)
@@ -470,7 +676,7 @@ where
         order: &mut Order,
         timestamp: i64,
     ) -> Result<(), BacktestError> {
-        let (prev_order_price_tick, prev_leaves_qty) = {
+        let (prev_order_price_tick, prev_qty, prev_exec_qty) = {
             let order_borrowed = self.orders.borrow();
             let exch_order = order_borrowed.get(&order.order_id);
 
@@ -482,31 +688,61 @@ where
             }
 
             let exch_order = exch_order.unwrap();
-            (exch_order.price_tick, exch_order.leaves_qty)
+            (
+                exch_order.price_tick,
+                exch_order.qty,
+                exch_order.qty - exch_order.leaves_qty,
+            )
         };
 
+        // An iceberg order can already be filled clip by clip, so the modified quantity is the new
+        // total quantity of the order, including what has been filled.
+        let leaves_qty = order.qty - prev_exec_qty;
+        if (leaves_qty / self.depth.lot_size()).round() <= 0.0 {
+            order.req = Status::Rejected;
+            order.exch_timestamp = timestamp;
+            return Ok(());
+        }
+
         // The initialization of the order queue position may not occur when the modified quantity
         // is smaller than the previous quantity, depending on the exchanges. It may need to
         // implement exchange-specific specialization.
-        if RESET_QUEUE_POS
-            || prev_order_price_tick != order.price_tick
-            || order.qty > prev_leaves_qty
-        {
+        if RESET_QUEUE_POS || prev_order_price_tick != order.price_tick || order.qty > prev_qty {
+            let (price_tick, qty) = (order.price_tick, order.qty);
             self.ack_cancel(order, timestamp)?;
-            self.ack_new(order, timestamp)?;
+            order.price_tick = price_tick;
+            order.qty = qty;
+            order.leaves_qty = leaves_qty;
+            self.place(order, timestamp)?;
         } else {
             let mut order_borrowed = self.orders.borrow_mut();
             let exch_order = order_borrowed.get_mut(&order.order_id);
             let exch_order = exch_order.unwrap();
 
             exch_order.qty = order.qty;
-            exch_order.leaves_qty = order.qty;
+            exch_order.leaves_qty = leaves_qty;
             exch_order.exch_timestamp = timestamp;
-            order.leaves_qty = order.qty;
+            order.leaves_qty = leaves_qty;
             order.exch_timestamp = timestamp;
         }
         Ok(())
     }
+
+    fn trigger_stop_orders(&mut self, event: &Event) -> Result<(), BacktestError> {
+        let triggered = self
+            .stop_orders
+            .take_triggered(event, self.depth.tick_size());
+        for mut order in triggered {
+            // Reports the trigger first, then the outcome of the order it releases.
+            order.status = Status::Triggered;
+            order.exch_timestamp = event.exch_ts;
+            self.order_e2l.respond(order.clone());
+
+            self.place(&mut order, event.exch_ts)?;
+            self.order_e2l.respond(order);
+        }
+        Ok(())
+    }
 }
 
 impl<AT, LM, QM, MD, FM> Processor for NoPartialFillExchange<AT, LM, QM, MD, FM>
@@ -598,7 +834,7 @@ where
             self.remove_filled_orders();
         }
 
-        Ok(())
+        self.trigger_stop_orders(event)
     }
 
     fn process_recv_order(
@@ -642,3 +878,491 @@ where
             .unwrap_or(i64::MAX)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        backtest::{
+            assettype::LinearAsset,
+            models::{CommonFees, ConstantLatency, RiskAdverseQueueModel, TradingValueFeeModel},
+            order::{LocalToExch, order_bus},
+            proc::TriggerSource,
+        },
+        depth::HashMapMarketDepth,
+    };
+
+    type TestExchange = NoPartialFillExchange<
+        LinearAsset,
+        ConstantLatency,
+        RiskAdverseQueueModel<HashMapMarketDepth>,
+        HashMapMarketDepth,
+        TradingValueFeeModel<CommonFees>,
+    >;
+
+    fn setup() -> (TestExchange, LocalToExch<ConstantLatency>) {
+        let (order_e2l, order_l2e) = order_bus(ConstantLatency::new(0, 0));
+        let exch = NoPartialFillExchange::new(
+            HashMapMarketDepth::new(1.0, 1.0),
+            State::new(
+                LinearAsset::new(1.0),
+                TradingValueFeeModel::new(CommonFees::new(0.0, 0.0)),
+            ),
+            RiskAdverseQueueModel::new(),
+            order_e2l,
+        )
+        .stop_orders(StopOrders::new(TriggerSource::LastTrade, 1));
+        (exch, order_l2e)
+    }
+
+    fn event(ev: u64, exch_ts: i64, px: f64, qty: f64) -> Event {
+        Event {
+            ev,
+            exch_ts,
+            local_ts: exch_ts,
+            px,
+            qty,
+            order_id: 0,
+            ival: 0,
+            fval: 0.0,
+        }
+    }
+
+    fn submit(
+        exch: &mut TestExchange,
+        order_l2e: &mut LocalToExch<ConstantLatency>,
+        mut order: Order,
+        timestamp: i64,
+    ) {
+        order.req = Status::New;
+        order.local_timestamp = timestamp;
+        order_l2e.request(order, |_| {});
+        exch.process_recv_order(timestamp, None).unwrap();
+    }
+
+    fn stop(
+        order_id: OrderId,
+        side: Side,
+        order_type: OrdType,
+        price_tick: i64,
+        trigger_price_tick: i64,
+    ) -> Order {
+        let mut order = Order::new(
+            order_id,
+            price_tick,
+            1.0,
+            1.0,
+            side,
+            order_type,
+            TimeInForce::GTC,
+        );
+        order.trigger_price_tick = trigger_price_tick;
+        order
+    }
+
+    fn receive_all(order_l2e: &mut LocalToExch<ConstantLatency>, timestamp: i64) -> Vec<Order> {
+        let mut responses = Vec::new();
+        while let Some(order) = order_l2e.receive(timestamp) {
+            responses.push(order);
+        }
+        responses
+    }
+
+    #[test]
+    fn test_stop_orders() {
+        let (mut exch, mut order_l2e) = setup();
+        exch.process(&event(EXCH_BID_DEPTH_EVENT, 1, 99.0, 10.0))
+            .unwrap();
+        exch.process(&event(EXCH_ASK_DEPTH_EVENT, 1, 101.0, 10.0))
+            .unwrap();
+
+        submit(
+            &mut exch,
+            &mut order_l2e,
+            stop(1, Side::Buy, OrdType::StopMarket, 0, 103),
+            10,
+        );
+        submit(
+            &mut exch,
+            &mut order_l2e,
+            stop(2, Side::Sell, OrdType::StopLimit, 100, 98),
+            10,
+        );
+        let responses = receive_all(&mut order_l2e, 10);
+        assert_eq!(responses.len(), 2);
+        assert!(responses.iter().all(|order| order.status == Status::New));
+
+        // Neither trigger price is reached.
+        exch.process(&event(EXCH_SELL_TRADE_EVENT, 20, 100.0, 1.0))
+            .unwrap();
+        assert!(receive_all(&mut order_l2e, 20).is_empty());
+
+        // The stop market order takes the new best ask with a tick of slippage.
+        exch.process(&event(EXCH_ASK_DEPTH_EVENT, 30, 104.0, 5.0))
+            .unwrap();
+        exch.process(&event(EXCH_ASK_DEPTH_EVENT, 30, 101.0, 0.0))
+            .unwrap();
+        exch.process(&event(EXCH_BUY_TRADE_EVENT, 40, 104.0, 1.0))
+            .unwrap();
+        let responses = receive_all(&mut order_l2e, 40);
+        assert_eq!(responses.len(), 2);
+        assert_eq!(responses[0].status, Status::Triggered);
+        assert_eq!(responses[1].status, Status::Filled);
+        assert_eq!(responses[1].exec_price_tick, 105);
+        assert!(!responses[1].maker);
+
+        // The stop limit order joins the book at its limit price and is filled there as a maker.
+        exch.process(&event(EXCH_SELL_TRADE_EVENT, 50, 98.0, 1.0))
+            .unwrap();
+        let responses = receive_all(&mut order_l2e, 50);
+        assert_eq!(responses.len(), 2);
+        assert_eq!(responses[0].status, Status::Triggered);
+        assert_eq!(responses[1].status, Status::New);
+        assert!(exch.orders.borrow().contains_key(&2));
+
+        exch.process(&event(EXCH_BID_DEPTH_EVENT, 60, 100.0, 3.0))
+            .unwrap();
+        let responses = receive_all(&mut order_l2e, 60);
+        assert_eq!(responses.len(), 1);
+        assert_eq!(responses[0].status, Status::Filled);
+        assert_eq!(responses[0].order_type, OrdType::StopLimit);
+        assert_eq!(responses[0].exec_price_tick, 100);
+        assert!(responses[0].maker);
+    }
+
+    fn iceberg(order_id: OrderId, price_tick: i64, qty: f64, display_qty: f64) -> Order {
+        let mut order = Order::new(
+            order_id,
+            price_tick,
+            1.0,
+            qty,
+            Side::Buy,
+            OrdType::Limit,
+            TimeInForce::GTC,
+        );
+        order.display_qty = display_qty;
+        order
+    }
+
+    #[test]
+    fn test_iceberg_clip_rotation() {
+        let (mut exch, mut order_l2e) = setup();
+        exch.process(&event(EXCH_BID_DEPTH_EVENT, 1, 100.0, 2.0))
+            .unwrap();
+        exch.process(&event(EXCH_ASK_DEPTH_EVENT, 1, 101.0, 10.0))
+            .unwrap();
+
+        submit(&mut exch, &mut order_l2e, iceberg(1, 100, 3.0, 1.0), 10);
+        assert_eq!(receive_all(&mut order_l2e, 10)[0].status, Status::New);
+
+        // Once the order reaches the front of the queue, only the displayed clip is filled and the
+        // next clip goes to the back of the queue behind the 2.0 resting in the book.
+        for (timestamp, status) in [
+            (20, Status::PartiallyFilled),
+            (30, Status::PartiallyFilled),
+            (40, Status::Filled),
+        ] {
+            exch.process(&event(EXCH_SELL_TRADE_EVENT, timestamp, 100.0, 2.0))
+                .unwrap();
+            assert!(receive_all(&mut order_l2e, timestamp).is_empty());
+
+            exch.process(&event(EXCH_SELL_TRADE_EVENT, timestamp + 1, 100.0, 1.0))
+                .unwrap();
+            let responses = receive_all(&mut order_l2e, timestamp + 1);
+            assert_eq!(responses.len(), 1);
+            assert_eq!(responses[0].order_id, 1);
+            assert_eq!(responses[0].status, status);
+            assert_eq!(responses[0].exec_qty, 1.0);
+            assert!(responses[0].maker);
+        }
+        assert!(!exch.orders.borrow().contains_key(&1));
+        assert_eq!(exch.state.values().trading_volume, 3.0);
+    }
+
+    #[test]
+    fn test_cancel_iceberg_order() {
+        let (mut exch, mut order_l2e) = setup();
+        exch.process(&event(EXCH_BID_DEPTH_EVENT, 1, 100.0, 2.0))
+            .unwrap();
+        exch.process(&event(EXCH_ASK_DEPTH_EVENT, 1, 101.0, 10.0))
+            .unwrap();
+
+        let order = iceberg(1, 100, 3.0, 1.0);
+        submit(&mut exch, &mut order_l2e, order.clone(), 10);
+        receive_all(&mut order_l2e, 10);
+        exch.process(&event(EXCH_SELL_TRADE_EVENT, 20, 100.0, 3.0))
+            .unwrap();
+        assert_eq!(receive_all(&mut order_l2e, 20)[0].leaves_qty, 2.0);
+
+        // Canceling the order also cancels its hidden quantity.
+        let mut cancel = order;
+        cancel.req = Status::Canceled;
+        cancel.local_timestamp = 30;
+        order_l2e.request(cancel, |_| {});
+        exch.process_recv_order(30, None).unwrap();
+        let responses = receive_all(&mut order_l2e, 30);
+        assert_eq!(responses[0].status, Status::Canceled);
+        assert_eq!(responses[0].leaves_qty, 2.0);
+        assert!(!exch.orders.borrow().contains_key(&1));
+
+        exch.process(&event(EXCH_SELL_TRADE_EVENT, 40, 100.0, 10.0))
+            .unwrap();
+        assert!(receive_all(&mut order_l2e, 40).is_empty());
+        assert_eq!(exch.state.values().trading_volume, 1.0);
+    }
+
+    #[test]
+    fn test_modify_iceberg_order() {
+        let (mut exch, mut order_l2e) = setup();
+        exch.process(&event(EXCH_BID_DEPTH_EVENT, 1, 100.0, 2.0))
+            .unwrap();
+        exch.process(&event(EXCH_ASK_DEPTH_EVENT, 1, 101.0, 10.0))
+            .unwrap();
+
+        let order = iceberg(1, 100, 3.0, 1.0);
+        submit(&mut exch, &mut order_l2e, order.clone(), 10);
+        receive_all(&mut order_l2e, 10);
+        exch.process(&event(EXCH_SELL_TRADE_EVENT, 20, 100.0, 3.0))
+            .unwrap();
+        assert_eq!(receive_all(&mut order_l2e, 20)[0].leaves_qty, 2.0);
+
+        let mut modify = |qty, timestamp| {
+            let mut modify = order.clone();
+            modify.qty = qty;
+            modify.req = Status::Replaced;
+            modify.local_timestamp = timestamp;
+            order_l2e.request(modify, |_| {});
+            exch.process_recv_order(timestamp, None).unwrap();
+            receive_all(&mut order_l2e, timestamp).pop().unwrap()
+        };
+
+        // The filled clip counts toward the modified quantity.
+        let response = modify(2.0, 30);
+        assert_eq!(response.req, Status::None);
+        assert_eq!(response.qty, 2.0);
+        assert_eq!(response.leaves_qty, 1.0);
+
+        // Nothing would be left to fill.
+        let response = modify(1.0, 40);
+        assert_eq!(response.req, Status::Rejected);
+
+        // Increasing the quantity puts the order at the back of the queue with the new remainder.
+        let response = modify(4.0, 50);
+        assert_eq!(response.status, Status::New);
+        assert_eq!(response.leaves_qty, 3.0);
+        let orders = exch.orders.borrow();
+        assert_eq!(orders[&1].qty, 4.0);
+        assert_eq!(orders[&1].leaves_qty, 3.0);
+    }
+
+    #[test]
+    fn test_post_only() {
+        for (post_only, status, buy_price_tick, sell_price_tick) in [
+            (PostOnly::Expire, Status::Expired, 101, 99),
+            (PostOnly::Reject, Status::Rejected, 101, 99),
+            (PostOnly::Reprice, Status::New, 100, 100),
+        ] {
+            let (exch, mut order_l2e) = setup();
+            let mut exch = exch.post_only(post_only);
+            exch.process(&event(EXCH_BID_DEPTH_EVENT, 1, 99.0, 10.0))
+                .unwrap();
+            exch.process(&event(EXCH_ASK_DEPTH_EVENT, 1, 101.0, 10.0))
+                .unwrap();
+
+            // Both orders would take liquidity at their prices.
+            for (order_id, side, price_tick) in [(1, Side::Buy, 101), (2, Side::Sell, 99)] {
+                let order = Order::new(
+                    order_id,
+                    price_tick,
+                    1.0,
+                    1.0,
+                    side,
+                    OrdType::Limit,
+                    TimeInForce::GTX,
+                );
+                submit(&mut exch, &mut order_l2e, order, 10);
+            }
+            let responses = receive_all(&mut order_l2e, 10);
+            assert_eq!(responses.len(), 2);
+            assert!(responses.iter().all(|order| order.status == status));
+            assert!(
+                responses
+                    .iter()
+                    .all(|order| !order.maker && order.exec_qty == 0.0)
+            );
+            assert_eq!(responses[0].price_tick, buy_price_tick);
+            assert_eq!(responses[1].price_tick, sell_price_tick);
+            assert_eq!(
+                exch.orders.borrow().len(),
+                if post_only == PostOnly::Reprice { 2 } else { 0 }
+            );
+            assert_eq!(exch.state.values().num_trades, 0);
+        }
+    }
+
+    #[test]
+    fn test_self_trade_prevention() {
+        for (stp, statuses, resting) in [
+            (
+                SelfTradePrevention::None,
+                vec![(3, Status::Filled)],
+                vec![1, 2],
+            ),
+            (
+                SelfTradePrevention::CancelMaker,
+                vec![(1, Status::Canceled), (3, Status::Filled)],
+                vec![2],
+            ),
+            (
+                SelfTradePrevention::CancelTaker,
+                vec![(3, Status::Canceled)],
+                vec![1, 2],
+            ),
+            (
+                SelfTradePrevention::CancelBoth,
+                vec![(1, Status::Canceled), (3, Status::Canceled)],
+                vec![2],
+            ),
+        ] {
+            let (exch, mut order_l2e) = setup();
+            let mut exch = exch.self_trade_prevention(stp);
+            exch.process(&event(EXCH_BID_DEPTH_EVENT, 1, 99.0, 10.0))
+                .unwrap();
+            exch.process(&event(EXCH_ASK_DEPTH_EVENT, 1, 102.0, 10.0))
+                .unwrap();
+
+            // Our sell order at 101 would be traded against by our buy order at 102, but the one at
+            // 103 would not.
+            for (order_id, price_tick) in [(1, 101), (2, 103)] {
+                let order = Order::new(
+                    order_id,
+                    price_tick,
+                    1.0,
+                    1.0,
+                    Side::Sell,
+                    OrdType::Limit,
+                    TimeInForce::GTC,
+                );
+                submit(&mut exch, &mut order_l2e, order, 10);
+            }
+            assert_eq!(receive_all(&mut order_l2e, 10).len(), 2);
+
+            let order = Order::new(
+                3,
+                102,
+                1.0,
+                1.0,
+                Side::Buy,
+                OrdType::Limit,
+                TimeInForce::GTC,
+            );
+            submit(&mut exch, &mut order_l2e, order, 20);
+            let responses: Vec<_> = receive_all(&mut order_l2e, 20)
+                .iter()
+                .map(|order| (order.order_id, order.status))
+                .collect();
+            assert_eq!(responses, statuses);
+
+            let mut order_ids: Vec<_> = exch.orders.borrow().keys().copied().collect();
+            order_ids.sort();
+            assert_eq!(order_ids, resting);
+            assert_eq!(exch.sell_orders[&101].is_empty(), !resting.contains(&1));
+        }
+    }
+
+    #[test]
+    fn test_ioc_and_fok() {
+        let (mut exch, mut order_l2e) = setup();
+        exch.process(&event(EXCH_BID_DEPTH_EVENT, 1, 100.0, 2.0))
+            .unwrap();
+        exch.process(&event(EXCH_BID_DEPTH_EVENT, 1, 99.0, 3.0))
+            .unwrap();
+        exch.process(&event(EXCH_ASK_DEPTH_EVENT, 1, 101.0, 10.0))
+            .unwrap();
+
+        let order = |order_id, qty, time_in_force| {
+            Order::new(
+                order_id,
+                99,
+                1.0,
+                qty,
+                Side::Sell,
+                OrdType::Limit,
+                time_in_force,
+            )
+        };
+
+        // The IOC order is filled at the best for the quantity resting up to its price, and the
+        // rest is canceled.
+        submit(
+            &mut exch,
+            &mut order_l2e,
+            order(1, 6.0, TimeInForce::IOC),
+            10,
+        );
+        let responses = receive_all(&mut order_l2e, 10);
+        let statuses: Vec<_> = responses.iter().map(|order| order.status).collect();
+        assert_eq!(statuses, vec![Status::PartiallyFilled, Status::Canceled]);
+        assert_eq!(responses[0].exec_price_tick, 100);
+        assert_eq!(responses[0].exec_qty, 5.0);
+        assert_eq!(responses[1].leaves_qty, 1.0);
+
+        // Not enough quantity rests up to its price, so the FOK order is not filled at all.
+        submit(
+            &mut exch,
+            &mut order_l2e,
+            order(2, 6.0, TimeInForce::FOK),
+            20,
+        );
+        let responses = receive_all(&mut order_l2e, 20);
+        assert_eq!(responses.len(), 1);
+        assert_eq!(responses[0].status, Status::Expired);
+        assert_eq!(responses[0].leaves_qty, 6.0);
+
+        submit(
+            &mut exch,
+            &mut order_l2e,
+            order(3, 5.0, TimeInForce::FOK),
+            30,
+        );
+        let responses = receive_all(&mut order_l2e, 30);
+        assert_eq!(responses.len(), 1);
+        assert_eq!(responses[0].status, Status::Filled);
+        assert_eq!(responses[0].exec_qty, 5.0);
+
+        // An IOC order that does not cross is canceled without being filled.
+        let mut order = order(4, 1.0, TimeInForce::IOC);
+        order.price_tick = 101;
+        submit(&mut exch, &mut order_l2e, order, 40);
+        let responses = receive_all(&mut order_l2e, 40);
+        assert_eq!(responses.len(), 1);
+        assert_eq!(responses[0].status, Status::Canceled);
+        assert_eq!(responses[0].exec_qty, 0.0);
+
+        assert_eq!(exch.state.values().trading_volume, 10.0);
+    }
+
+    #[test]
+    fn test_cancel_stop_order() {
+        let (mut exch, mut order_l2e) = setup();
+        exch.process(&event(EXCH_BID_DEPTH_EVENT, 1, 99.0, 10.0))
+            .unwrap();
+        exch.process(&event(EXCH_ASK_DEPTH_EVENT, 1, 101.0, 10.0))
+            .unwrap();
+
+        let order = stop(1, Side::Buy, OrdType::StopMarket, 0, 103);
+        submit(&mut exch, &mut order_l2e, order.clone(), 10);
+        assert_eq!(receive_all(&mut order_l2e, 10)[0].status, Status::New);
+
+        let mut cancel = order;
+        cancel.req = Status::Canceled;
+        cancel.local_timestamp = 20;
+        order_l2e.request(cancel, |_| {});
+        exch.process_recv_order(20, None).unwrap();
+        assert_eq!(receive_all(&mut order_l2e, 20)[0].status, Status::Canceled);
+
+        exch.process(&event(EXCH_BUY_TRADE_EVENT, 30, 110.0, 1.0))
+            .unwrap();
+        assert!(receive_all(&mut order_l2e, 30).is_empty());
+    }
+}
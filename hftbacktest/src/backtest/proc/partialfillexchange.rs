@@ -11,7 +11,7 @@ use crate::{
         assettype::AssetType,
         models::{FeeModel, LatencyModel, QueueModel},
         order::ExchToLocal,
-        proc::Processor,
+        proc::{PostOnly, Processor, SelfTradePrevention, StopOrders, iceberg, liquidity},
         state::State,
     },
     depth::{INVALID_MAX, INVALID_MIN, L2MarketDepth, MarketDepth},
@@ -38,9 +38,18 @@ use crate::{
 
 /// The exchange model with partial fills.
 ///
-/// * Support order types: [OrdType::Limit](crate::types::OrdType::Limit)
+/// * Support order types: [OrdType::Limit](crate::types::OrdType::Limit),
+///   [OrdType::Market](crate::types::OrdType::Market),
+///   [OrdType::StopMarket](crate::types::OrdType::StopMarket),
+///   [OrdType::StopLimit](crate::types::OrdType::StopLimit). See [`StopOrders`] for how stop
+///   orders are triggered and filled.
 /// * Support time-in-force: [`TimeInForce::GTC`], [`TimeInForce::FOK`], [`TimeInForce::IOC`],
-///   [`TimeInForce::GTX`]
+///   [`TimeInForce::GTX`]. See [`PostOnly`] for how a [`TimeInForce::GTX`] order that would take
+///   liquidity is handled. An [`TimeInForce::IOC`] order takes the levels up to its price and its
+///   remainder is [`Status::Canceled`]. A [`TimeInForce::FOK`] order is [`Status::Expired`] unless
+///   those levels hold its whole quantity.
+/// * See [`SelfTradePrevention`] for how an order that would take liquidity and trade against our
+///   own resting orders is handled.
 ///
 /// **Conditions for Full Execution**
 /// Buy order in the order book
@@ -64,6 +73,15 @@ use crate::{
 /// - Filled by (remaining) buy trade quantity: your order is at the front of the queue && your
 ///   order price == buy trade price
 ///
+/// Only [`fill_ratio`](Self::fill_ratio) of the remaining trade quantity goes to your order, so an
+/// order can be filled over several trades, with an execution report for each fill.
+///
+/// **Iceberg Orders**
+/// An order with a [`display_qty`](crate::types::Order::display_qty) smaller than its quantity is
+/// an iceberg order. Trades at its price fill at most its displayed clip, and once a clip is fully
+/// filled, the next clip is put at the back of the queue. Fills from crossing prices are not
+/// limited by the clip.
+///
 /// **Liquidity-Taking Order**
 /// Liquidity-taking orders will be executed based on the quantity of the order book, even though
 /// the best price and quantity do not change due to your execution. Be aware that this may cause
@@ -96,6 +114,14 @@ where
     queue_model: QM,
 
     filled_orders: Vec<OrderId>,
+
+    stop_orders: StopOrders,
+
+    post_only: PostOnly,
+
+    self_trade_prevention: SelfTradePrevention,
+
+    fill_ratio: f64,
 }
 
 impl<AT, LM, QM, MD, FM> PartialFillExchange<AT, LM, QM, MD, FM>
@@ -122,9 +148,58 @@ where
             state,
             queue_model,
             filled_orders: Default::default(),
+            stop_orders: Default::default(),
+            post_only: Default::default(),
+            self_trade_prevention: Default::default(),
+            fill_ratio: 1.0,
+        }
+    }
+
+    /// Sets how stop orders are triggered and filled. By default, they are triggered by the last
+    /// trade price and stop market orders are filled at the best price without slippage.
+    pub fn stop_orders(self, stop_orders: StopOrders) -> Self {
+        Self {
+            stop_orders,
+            ..self
+        }
+    }
+
+    /// Sets how a post-only order that would cross the opposite best at its arrival is handled.
+    /// The default value is [`PostOnly::Expire`].
+    pub fn post_only(self, post_only: PostOnly) -> Self {
+        Self { post_only, ..self }
+    }
+
+    /// Sets how an order that would take liquidity on arrival and trade against our own resting
+    /// orders is handled. The default value is [`SelfTradePrevention::None`].
+    pub fn self_trade_prevention(self, self_trade_prevention: SelfTradePrevention) -> Self {
+        Self {
+            self_trade_prevention,
+            ..self
         }
     }
 
+    /// Sets the share of the traded quantity that a resting order gets once the queue ahead of it
+    /// is consumed by trades at its price. A trade of `qty` then fills the order for
+    /// `min(leaves_qty, qty * fill_ratio)`, rounded to the lot size. The default value is `1.0`,
+    /// which gives the order the whole quantity.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `fill_ratio` is not in `(0, 1]`.
+    pub fn fill_ratio(self, fill_ratio: f64) -> Self {
+        assert!(
+            fill_ratio > 0.0 && fill_ratio <= 1.0,
+            "fill_ratio must be in (0, 1]"
+        );
+        Self { fill_ratio, ..self }
+    }
+
+    fn share_of(&self, filled_qty: f64) -> f64 {
+        let lot_size = self.depth.lot_size();
+        ((filled_qty * self.fill_ratio) / lot_size).round() * lot_size
+    }
+
     fn check_if_sell_filled(
         &mut self,
         order: &mut Order,
@@ -148,17 +223,27 @@ where
                 // Updates the order's queue position.
                 self.queue_model.trade(order, qty, &self.depth);
                 let filled_qty = self.queue_model.is_filled(order, &self.depth);
-                if filled_qty > 0.0 {
-                    // q_ahead is negative since is_filled is true and its value represents the
-                    // executable quantity of this order after execution in the queue ahead of this
-                    // order.
-                    let exec_qty = if filled_qty > order.leaves_qty {
-                        self.filled_orders.push(order.order_id);
-                        order.leaves_qty
-                    } else {
-                        filled_qty
-                    };
-                    return self.fill::<true>(order, timestamp, true, order.price_tick, exec_qty);
+                // filled_qty is the executable quantity of this order after execution in the queue
+                // ahead of this order, of which the order takes its share.
+                let exec_qty = self.share_of(filled_qty);
+                if exec_qty > 0.0 {
+                    // An iceberg order can only be filled up to its displayed clip.
+                    let clip_qty = iceberg::clip_qty(order, self.depth.lot_size());
+                    let exec_qty = exec_qty.min(clip_qty);
+                    let exec_qty =
+                        if ((order.leaves_qty - exec_qty) / self.depth.lot_size()).round() <= 0.0 {
+                            self.filled_orders.push(order.order_id);
+                            order.leaves_qty
+                        } else {
+                            exec_qty
+                        };
+                    self.fill::<true>(order, timestamp, true, order.price_tick, exec_qty)?;
+                    if order.status == Status::PartiallyFilled
+                        && ((clip_qty - exec_qty) / self.depth.lot_size()).round() <= 0.0
+                    {
+                        // The next clip is displayed at the back of the queue.
+                        self.queue_model.new_order(order, &self.depth);
+                    }
                 }
             }
         }
@@ -188,17 +273,27 @@ where
                 // Updates the order's queue position.
                 self.queue_model.trade(order, qty, &self.depth);
                 let filled_qty = self.queue_model.is_filled(order, &self.depth);
-                if filled_qty > 0.0 {
-                    // q_ahead is negative since is_filled is true and its value represents the
-                    // executable quantity of this order after execution in the queue ahead of this
-                    // order.
-                    let exec_qty = if filled_qty > order.leaves_qty {
-                        self.filled_orders.push(order.order_id);
-                        order.leaves_qty
-                    } else {
-                        filled_qty
-                    };
-                    return self.fill::<true>(order, timestamp, true, order.price_tick, exec_qty);
+                // filled_qty is the executable quantity of this order after execution in the queue
+                // ahead of this order, of which the order takes its share.
+                let exec_qty = self.share_of(filled_qty);
+                if exec_qty > 0.0 {
+                    // An iceberg order can only be filled up to its displayed clip.
+                    let clip_qty = iceberg::clip_qty(order, self.depth.lot_size());
+                    let exec_qty = exec_qty.min(clip_qty);
+                    let exec_qty =
+                        if ((order.leaves_qty - exec_qty) / self.depth.lot_size()).round() <= 0.0 {
+                            self.filled_orders.push(order.order_id);
+                            order.leaves_qty
+                        } else {
+                            exec_qty
+                        };
+                    self.fill::<true>(order, timestamp, true, order.price_tick, exec_qty)?;
+                    if order.status == Status::PartiallyFilled
+                        && ((clip_qty - exec_qty) / self.depth.lot_size()).round() <= 0.0
+                    {
+                        // The next clip is displayed at the back of the queue.
+                        self.queue_model.new_order(order, &self.depth);
+                    }
                 }
             }
         }
@@ -244,6 +339,30 @@ where
         Ok(())
     }
 
+    /// Fills the order as it takes `exec_qty` at a level of the opposite side of the book. Unless
+    /// the order is filled in full, the fill is responded right away, so that the local sees every
+    /// level an order sweeping the book is filled at; the final status is responded by the caller.
+    fn take(
+        &mut self,
+        order: &mut Order,
+        timestamp: i64,
+        exec_price_tick: i64,
+        exec_qty: f64,
+    ) -> Result<(), BacktestError> {
+        self.fill::<false>(order, timestamp, false, exec_price_tick, exec_qty)?;
+        if order.status == Status::PartiallyFilled {
+            self.order_e2l.respond(order.clone());
+        }
+        Ok(())
+    }
+
+    /// Returns `true` if the order's whole leaves quantity can be taken from the book on arrival.
+    fn is_takeable(&self, order: &Order) -> bool {
+        let lot_size = self.depth.lot_size();
+        (liquidity::takeable_qty(&self.depth, order) / lot_size).round()
+            >= (order.leaves_qty / lot_size).round()
+    }
+
     fn remove_filled_orders(&mut self) {
         if !self.filled_orders.is_empty() {
             let mut orders = self.orders.borrow_mut();
@@ -385,68 +504,97 @@ where
     }
 
     fn ack_new(&mut self, order: &mut Order, timestamp: i64) -> Result<(), BacktestError> {
-        if self.orders.borrow().contains_key(&order.order_id) {
+        if self.orders.borrow().contains_key(&order.order_id)
+            || self.stop_orders.contains(order.order_id)
+        {
             return Err(BacktestError::OrderIdExist);
         }
 
+        if order.order_type.is_stop() {
+            if order.time_in_force == TimeInForce::Unsupported {
+                return Err(BacktestError::InvalidOrderRequest);
+            }
+            // The exchange accepts this order and holds it until its trigger price is reached.
+            order.status = Status::New;
+            order.exch_timestamp = timestamp;
+            self.stop_orders.insert(order.clone());
+            return Ok(());
+        }
+        self.place(order, timestamp)
+    }
+
+    /// Executes the order against the book or rests it in the book. A stop order reaches here
+    /// only once it has been triggered.
+    fn place(&mut self, order: &mut Order, timestamp: i64) -> Result<(), BacktestError> {
+        let (canceled, canceled_taker) = self.self_trade_prevention.prevent(
+            &self.depth,
+            order,
+            &mut self.orders.borrow_mut(),
+            &mut self.buy_orders,
+            &mut self.sell_orders,
+            timestamp,
+        );
+        for maker in canceled {
+            self.order_e2l.respond(maker);
+        }
+        if canceled_taker {
+            return Ok(());
+        }
+
         if order.side == Side::Buy {
             match order.order_type {
-                OrdType::Limit => {
+                OrdType::Limit | OrdType::StopLimit => {
+                    if order.time_in_force == TimeInForce::GTX {
+                        order.price_tick = self
+                            .post_only
+                            .buy_price_tick(order.price_tick, self.depth.best_ask_tick());
+                    }
                     // Checks if the buy order price is greater than or equal to the current best ask.
                     if order.price_tick >= self.depth.best_ask_tick() {
                         match order.time_in_force {
                             TimeInForce::GTX => {
-                                order.status = Status::Expired;
+                                order.status = self.post_only.rejected_status();
                                 order.exch_timestamp = timestamp;
                                 Ok(())
                             }
                             TimeInForce::FOK => {
                                 // The order must be executed immediately in its entirety; otherwise, the
                                 // entire order will be cancelled.
-                                let mut execute = false;
-                                let mut cum_qty = 0f64;
-                                for t in self.depth.best_ask_tick()..=order.price_tick {
-                                    cum_qty += self.depth.ask_qty_at_tick(t);
-                                    if (cum_qty / self.depth.lot_size()).round()
-                                        >= (order.qty / self.depth.lot_size()).round()
-                                    {
-                                        execute = true;
-                                        break;
-                                    }
+                                if !self.is_takeable(order) {
+                                    order.status = Status::Expired;
+                                    order.exch_timestamp = timestamp;
+                                    return Ok(());
                                 }
-                                if execute {
-                                    for t in self.depth.best_ask_tick()..=order.price_tick {
-                                        let qty = self.depth.ask_qty_at_tick(t);
-                                        if qty > 0.0 {
-                                            let exec_qty = qty.min(order.leaves_qty);
-                                            self.fill::<false>(
-                                                order, timestamp, false, t, exec_qty,
-                                            )?;
-                                            if order.status == Status::Filled {
-                                                return Ok(());
-                                            }
+                                for t in self.depth.best_ask_tick()
+                                    ..=order.price_tick.min(self.depth.high_ask_tick())
+                                {
+                                    let qty = self.depth.ask_qty_at_tick(t);
+                                    if qty > 0.0 {
+                                        let exec_qty = qty.min(order.leaves_qty);
+                                        self.take(order, timestamp, t, exec_qty)?;
+                                        if order.status == Status::Filled {
+                                            return Ok(());
                                         }
                                     }
-                                    unreachable!();
-                                } else {
-                                    order.status = Status::Expired;
-                                    order.exch_timestamp = timestamp;
-                                    Ok(())
                                 }
+                                unreachable!();
                             }
                             TimeInForce::IOC => {
-                                // The order must be executed immediately.
-                                for t in self.depth.best_ask_tick()..=order.price_tick {
+                                // The order must be executed immediately, and the remainder that
+                                // cannot be is canceled.
+                                for t in self.depth.best_ask_tick()
+                                    ..=order.price_tick.min(self.depth.high_ask_tick())
+                                {
                                     let qty = self.depth.ask_qty_at_tick(t);
                                     if qty > 0.0 {
                                         let exec_qty = qty.min(order.leaves_qty);
-                                        self.fill::<false>(order, timestamp, false, t, exec_qty)?;
+                                        self.take(order, timestamp, t, exec_qty)?;
                                     }
                                     if order.status == Status::Filled {
                                         return Ok(());
                                     }
                                 }
-                                order.status = Status::Expired;
+                                order.status = Status::Canceled;
                                 order.exch_timestamp = timestamp;
                                 Ok(())
                             }
@@ -456,7 +604,7 @@ where
                                     let qty = self.depth.ask_qty_at_tick(t);
                                     if qty > 0.0 {
                                         let exec_qty = qty.min(order.leaves_qty);
-                                        self.fill::<false>(order, timestamp, false, t, exec_qty)?;
+                                        self.take(order, timestamp, t, exec_qty)?;
                                     }
                                     if order.status == Status::Filled {
                                         return Ok(());
@@ -490,11 +638,16 @@ where
                                     .insert(order.order_id, order.clone());
                                 Ok(())
                             }
-                            TimeInForce::FOK | TimeInForce::IOC => {
+                            TimeInForce::FOK => {
                                 order.status = Status::Expired;
                                 order.exch_timestamp = timestamp;
                                 Ok(())
                             }
+                            TimeInForce::IOC => {
+                                order.status = Status::Canceled;
+                                order.exch_timestamp = timestamp;
+                                Ok(())
+                            }
                             TimeInForce::Unsupported => Err(BacktestError::InvalidOrderRequest),
                         }
                     }
@@ -505,7 +658,7 @@ where
                         let qty = self.depth.ask_qty_at_tick(t);
                         if qty > 0.0 {
                             let exec_qty = qty.min(order.leaves_qty);
-                            self.fill::<false>(order, timestamp, false, t, exec_qty)?;
+                            self.take(order, timestamp, t, exec_qty)?;
                         }
                         if order.status == Status::Filled {
                             return Ok(());
@@ -515,66 +668,82 @@ where
                     order.exch_timestamp = timestamp;
                     Ok(())
                 }
+                OrdType::StopMarket => {
+                    // Takes the market at the best price with the modeled slippage.
+                    let best_ask_tick = self.depth.best_ask_tick();
+                    if best_ask_tick == INVALID_MAX {
+                        order.status = Status::Expired;
+                        order.exch_timestamp = timestamp;
+                        Ok(())
+                    } else {
+                        let exec_price_tick = best_ask_tick + self.stop_orders.slippage_ticks();
+                        self.fill::<false>(
+                            order,
+                            timestamp,
+                            false,
+                            exec_price_tick,
+                            order.leaves_qty,
+                        )
+                    }
+                }
                 OrdType::Unsupported => Err(BacktestError::InvalidOrderRequest),
             }
         } else {
             match order.order_type {
-                OrdType::Limit => {
+                OrdType::Limit | OrdType::StopLimit => {
+                    if order.time_in_force == TimeInForce::GTX {
+                        order.price_tick = self
+                            .post_only
+                            .sell_price_tick(order.price_tick, self.depth.best_bid_tick());
+                    }
                     // Checks if the sell order price is less than or equal to the current best bid.
                     if order.price_tick <= self.depth.best_bid_tick() {
                         match order.time_in_force {
                             TimeInForce::GTX => {
-                                order.status = Status::Expired;
+                                order.status = self.post_only.rejected_status();
                                 order.exch_timestamp = timestamp;
                                 Ok(())
                             }
                             TimeInForce::FOK => {
                                 // The order must be executed immediately in its entirety; otherwise, the
                                 // entire order will be cancelled.
-                                let mut execute = false;
-                                let mut cum_qty = 0f64;
-                                for t in (order.price_tick..=self.depth.best_bid_tick()).rev() {
-                                    cum_qty += self.depth.bid_qty_at_tick(t);
-                                    if (cum_qty / self.depth.lot_size()).round()
-                                        >= (order.qty / self.depth.lot_size()).round()
-                                    {
-                                        execute = true;
-                                        break;
-                                    }
+                                if !self.is_takeable(order) {
+                                    order.status = Status::Expired;
+                                    order.exch_timestamp = timestamp;
+                                    return Ok(());
                                 }
-                                if execute {
-                                    for t in (order.price_tick..=self.depth.best_bid_tick()).rev() {
-                                        let qty = self.depth.bid_qty_at_tick(t);
-                                        if qty > 0.0 {
-                                            let exec_qty = qty.min(order.leaves_qty);
-                                            self.fill::<false>(
-                                                order, timestamp, false, t, exec_qty,
-                                            )?;
-                                            if order.status == Status::Filled {
-                                                return Ok(());
-                                            }
+                                for t in (order.price_tick.max(self.depth.low_bid_tick())
+                                    ..=self.depth.best_bid_tick())
+                                    .rev()
+                                {
+                                    let qty = self.depth.bid_qty_at_tick(t);
+                                    if qty > 0.0 {
+                                        let exec_qty = qty.min(order.leaves_qty);
+                                        self.take(order, timestamp, t, exec_qty)?;
+                                        if order.status == Status::Filled {
+                                            return Ok(());
                                         }
                                     }
-                                    unreachable!();
-                                } else {
-                                    order.status = Status::Expired;
-                                    order.exch_timestamp = timestamp;
-                                    Ok(())
                                 }
+                                unreachable!();
                             }
                             TimeInForce::IOC => {
-                                // The order must be executed immediately.
-                                for t in (order.price_tick..=self.depth.best_bid_tick()).rev() {
+                                // The order must be executed immediately, and the remainder that
+                                // cannot be is canceled.
+                                for t in (order.price_tick.max(self.depth.low_bid_tick())
+                                    ..=self.depth.best_bid_tick())
+                                    .rev()
+                                {
                                     let qty = self.depth.bid_qty_at_tick(t);
                                     if qty > 0.0 {
                                         let exec_qty = qty.min(order.leaves_qty);
-                                        self.fill::<false>(order, timestamp, false, t, exec_qty)?;
+                                        self.take(order, timestamp, t, exec_qty)?;
                                     }
                                     if order.status == Status::Filled {
                                         return Ok(());
                                     }
                                 }
-                                order.status = Status::Expired;
+                                order.status = Status::Canceled;
                                 order.exch_timestamp = timestamp;
                                 Ok(())
                             }
@@ -584,7 +753,7 @@ where
                                     let qty = self.depth.bid_qty_at_tick(t);
                                     if qty > 0.0 {
                                         let exec_qty = qty.min(order.leaves_qty);
-                                        self.fill::<false>(order, timestamp, false, t, exec_qty)?;
+                                        self.take(order, timestamp, t, exec_qty)?;
                                     }
                                     if order.status == Status::Filled {
                                         return Ok(());
@@ -598,9 +767,7 @@ where
                                 let (price_tick, leaves_qty) = (order.price_tick, order.leaves_qty);
                                 self.fill::<false>(order, timestamp, false, price_tick, leaves_qty)
                             }
-                            _ => {
-                                unreachable!();
-                            }
+                            TimeInForce::Unsupported => Err(BacktestError::InvalidOrderRequest),
                         }
                     } else {
                         match order.time_in_force {
@@ -620,11 +787,16 @@ where
                                     .insert(order.order_id, order.clone());
                                 Ok(())
                             }
-                            TimeInForce::FOK | TimeInForce::IOC => {
+                            TimeInForce::FOK => {
                                 order.status = Status::Expired;
                                 order.exch_timestamp = timestamp;
                                 Ok(())
                             }
+                            TimeInForce::IOC => {
+                                order.status = Status::Canceled;
+                                order.exch_timestamp = timestamp;
+                                Ok(())
+                            }
                             TimeInForce::Unsupported => Err(BacktestError::InvalidOrderRequest),
                         }
                     }
@@ -636,7 +808,7 @@ where
                         let qty = self.depth.bid_qty_at_tick(t);
                         if qty > 0.0 {
                             let exec_qty = qty.min(order.leaves_qty);
-                            self.fill::<false>(order, timestamp, false, t, exec_qty)?;
+                            self.take(order, timestamp, t, exec_qty)?;
                         }
                         if order.status == Status::Filled {
                             return Ok(());
@@ -646,12 +818,37 @@ where
                     order.exch_timestamp = timestamp;
                     Ok(())
                 }
+                OrdType::StopMarket => {
+                    // Takes the market at the best price with the modeled slippage.
+                    let best_bid_tick = self.depth.best_bid_tick();
+                    if best_bid_tick == INVALID_MIN {
+                        order.status = Status::Expired;
+                        order.exch_timestamp = timestamp;
+                        Ok(())
+                    } else {
+                        let exec_price_tick = best_bid_tick - self.stop_orders.slippage_ticks();
+                        self.fill::<false>(
+                            order,
+                            timestamp,
+                            false,
+                            exec_price_tick,
+                            order.leaves_qty,
+                        )
+                    }
+                }
                 OrdType::Unsupported => Err(BacktestError::InvalidOrderRequest),
             }
         }
     }
 
     fn ack_cancel(&mut self, order: &mut Order, timestamp: i64) -> Result<(), BacktestError> {
+        if let Some(stop_order) = self.stop_orders.remove(order.order_id) {
+            let _ = std::mem::replace(order, stop_order);
+            order.status = Status::Canceled;
+            order.exch_timestamp = timestamp;
+            return Ok(());
+        }
+
         let exch_order = {
             let mut order_borrowed = self.orders.borrow_mut();
             order_borrowed.remove(&order.order_id)
@@ -688,7 +885,7 @@ where
         order: &mut Order,
         timestamp: i64,
     ) -> Result<(), BacktestError> {
-        let (prev_order_price_tick, prev_leaves_qty) = {
+        let (prev_order_price_tick, prev_qty, prev_exec_qty) = {
             let order_borrowed = self.orders.borrow();
             let exch_order = order_borrowed.get(&order.order_id);
 
@@ -700,31 +897,61 @@ where
             }
 
             let exch_order = exch_order.unwrap();
-            (exch_order.price_tick, exch_order.leaves_qty)
+            (
+                exch_order.price_tick,
+                exch_order.qty,
+                exch_order.qty - exch_order.leaves_qty,
+            )
         };
 
+        // A partially filled order keeps what has been filled, so the modified quantity is the new
+        // total quantity of the order, including the executed quantity.
+        let leaves_qty = order.qty - prev_exec_qty;
+        if (leaves_qty / self.depth.lot_size()).round() <= 0.0 {
+            order.req = Status::Rejected;
+            order.exch_timestamp = timestamp;
+            return Ok(());
+        }
+
         // The initialization of the order queue position may not occur when the modified quantity
         // is smaller than the previous quantity, depending on the exchanges. It may need to
         // implement exchange-specific specialization.
-        if RESET_QUEUE_POS
-            || prev_order_price_tick != order.price_tick
-            || order.qty > prev_leaves_qty
-        {
+        if RESET_QUEUE_POS || prev_order_price_tick != order.price_tick || order.qty > prev_qty {
+            let (price_tick, qty) = (order.price_tick, order.qty);
             self.ack_cancel(order, timestamp)?;
-            self.ack_new(order, timestamp)?;
+            order.price_tick = price_tick;
+            order.qty = qty;
+            order.leaves_qty = leaves_qty;
+            self.place(order, timestamp)?;
         } else {
             let mut order_borrowed = self.orders.borrow_mut();
             let exch_order = order_borrowed.get_mut(&order.order_id);
             let exch_order = exch_order.unwrap();
 
             exch_order.qty = order.qty;
-            exch_order.leaves_qty = order.qty;
+            exch_order.leaves_qty = leaves_qty;
             exch_order.exch_timestamp = timestamp;
-            order.leaves_qty = order.qty;
+            order.leaves_qty = leaves_qty;
             order.exch_timestamp = timestamp;
         }
         Ok(())
     }
+
+    fn trigger_stop_orders(&mut self, event: &Event) -> Result<(), BacktestError> {
+        let triggered = self
+            .stop_orders
+            .take_triggered(event, self.depth.tick_size());
+        for mut order in triggered {
+            // Reports the trigger first, then the outcome of the order it releases.
+            order.status = Status::Triggered;
+            order.exch_timestamp = event.exch_ts;
+            self.order_e2l.respond(order.clone());
+
+            self.place(&mut order, event.exch_ts)?;
+            self.order_e2l.respond(order);
+        }
+        Ok(())
+    }
 }
 
 impl<AT, LM, QM, MD, FM> Processor for PartialFillExchange<AT, LM, QM, MD, FM>
@@ -816,7 +1043,7 @@ where
             self.remove_filled_orders();
         }
 
-        Ok(())
+        self.trigger_stop_orders(event)
     }
 
     fn process_recv_order(
@@ -860,3 +1087,348 @@ where
             .unwrap_or(i64::MAX)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        backtest::{
+            assettype::LinearAsset,
+            models::{CommonFees, ConstantLatency, RiskAdverseQueueModel, TradingValueFeeModel},
+            order::{LocalToExch, order_bus},
+        },
+        depth::HashMapMarketDepth,
+    };
+
+    type TestExchange = PartialFillExchange<
+        LinearAsset,
+        ConstantLatency,
+        RiskAdverseQueueModel<HashMapMarketDepth>,
+        HashMapMarketDepth,
+        TradingValueFeeModel<CommonFees>,
+    >;
+
+    fn setup(fill_ratio: f64) -> (TestExchange, LocalToExch<ConstantLatency>) {
+        let (order_e2l, order_l2e) = order_bus(ConstantLatency::new(0, 0));
+        let exch = PartialFillExchange::new(
+            HashMapMarketDepth::new(1.0, 0.1),
+            State::new(
+                LinearAsset::new(1.0),
+                TradingValueFeeModel::new(CommonFees::new(0.0, 0.0)),
+            ),
+            RiskAdverseQueueModel::new(),
+            order_e2l,
+        )
+        .fill_ratio(fill_ratio);
+        (exch, order_l2e)
+    }
+
+    fn event(ev: u64, exch_ts: i64, px: f64, qty: f64) -> Event {
+        Event {
+            ev,
+            exch_ts,
+            local_ts: exch_ts,
+            px,
+            qty,
+            order_id: 0,
+            ival: 0,
+            fval: 0.0,
+        }
+    }
+
+    fn receive_all(order_l2e: &mut LocalToExch<ConstantLatency>, timestamp: i64) -> Vec<Order> {
+        let mut responses = Vec::new();
+        while let Some(order) = order_l2e.receive(timestamp) {
+            responses.push(order);
+        }
+        responses
+    }
+
+    #[test]
+    fn test_fill_ratio() {
+        let (mut exch, mut order_l2e) = setup(0.5);
+        exch.process(&event(EXCH_BID_DEPTH_EVENT, 1, 100.0, 2.0))
+            .unwrap();
+        exch.process(&event(EXCH_ASK_DEPTH_EVENT, 1, 101.0, 10.0))
+            .unwrap();
+
+        let mut order = Order::new(
+            1,
+            100,
+            1.0,
+            4.0,
+            Side::Buy,
+            OrdType::Limit,
+            TimeInForce::GTC,
+        );
+        order.req = Status::New;
+        order.local_timestamp = 10;
+        order_l2e.request(order, |_| {});
+        exch.process_recv_order(10, None).unwrap();
+        assert_eq!(receive_all(&mut order_l2e, 10)[0].status, Status::New);
+
+        // The first trade consumes the 2.0 ahead of the order in the queue, and the order takes
+        // half of the rest. The next trades at its price are shared the same way.
+        let mut cum_qty = 0.0;
+        for (i, trade_qty) in [4.0, 2.0, 2.0].into_iter().enumerate() {
+            let timestamp = 20 + i as i64;
+            exch.process(&event(EXCH_SELL_TRADE_EVENT, timestamp, 100.0, trade_qty))
+                .unwrap();
+            let responses = receive_all(&mut order_l2e, timestamp);
+            assert_eq!(responses.len(), 1);
+            assert_eq!(responses[0].status, Status::PartiallyFilled);
+            assert!((responses[0].exec_qty - 1.0).abs() < 1e-9);
+            cum_qty += responses[0].exec_qty;
+            assert!((responses[0].qty - responses[0].leaves_qty - cum_qty).abs() < 1e-9);
+            assert!(exch.orders.borrow().contains_key(&1));
+        }
+
+        // Half of this trade exceeds the leaves quantity, which completes the order.
+        exch.process(&event(EXCH_SELL_TRADE_EVENT, 30, 100.0, 4.0))
+            .unwrap();
+        let responses = receive_all(&mut order_l2e, 30);
+        assert_eq!(responses.len(), 1);
+        assert_eq!(responses[0].status, Status::Filled);
+        assert!((responses[0].exec_qty - 1.0).abs() < 1e-9);
+        assert!(responses[0].maker);
+        assert!(!exch.orders.borrow().contains_key(&1));
+        assert!(exch.buy_orders[&100].is_empty());
+    }
+
+    #[test]
+    fn test_modify_partially_filled_order() {
+        let (mut exch, mut order_l2e) = setup(0.5);
+        exch.process(&event(EXCH_BID_DEPTH_EVENT, 1, 100.0, 2.0))
+            .unwrap();
+        exch.process(&event(EXCH_ASK_DEPTH_EVENT, 1, 101.0, 10.0))
+            .unwrap();
+
+        let mut order = Order::new(
+            1,
+            100,
+            1.0,
+            4.0,
+            Side::Buy,
+            OrdType::Limit,
+            TimeInForce::GTC,
+        );
+        order.req = Status::New;
+        order.local_timestamp = 10;
+        order_l2e.request(order.clone(), |_| {});
+        exch.process_recv_order(10, None).unwrap();
+        receive_all(&mut order_l2e, 10);
+        exch.process(&event(EXCH_SELL_TRADE_EVENT, 20, 100.0, 4.0))
+            .unwrap();
+        let responses = receive_all(&mut order_l2e, 20);
+        assert_eq!(responses[0].status, Status::PartiallyFilled);
+        assert!((responses[0].leaves_qty - 3.0).abs() < 1e-9);
+
+        let mut modify = |qty, timestamp| {
+            let mut modify = order.clone();
+            modify.qty = qty;
+            modify.req = Status::Replaced;
+            modify.local_timestamp = timestamp;
+            order_l2e.request(modify, |_| {});
+            exch.process_recv_order(timestamp, None).unwrap();
+            receive_all(&mut order_l2e, timestamp).pop().unwrap()
+        };
+
+        // The filled quantity counts toward the modified quantity.
+        let response = modify(3.0, 30);
+        assert_eq!(response.req, Status::None);
+        assert_eq!(response.qty, 3.0);
+        assert!((response.leaves_qty - 2.0).abs() < 1e-9);
+
+        // Nothing would be left to fill.
+        let response = modify(1.0, 40);
+        assert_eq!(response.req, Status::Rejected);
+
+        // Increasing the quantity puts the order at the back of the queue with the new remainder.
+        let response = modify(5.0, 50);
+        assert_eq!(response.status, Status::New);
+        assert!((response.leaves_qty - 4.0).abs() < 1e-9);
+        let orders = exch.orders.borrow();
+        assert_eq!(orders[&1].qty, 5.0);
+        assert!((orders[&1].leaves_qty - 4.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_iceberg_clip_rotation() {
+        let (mut exch, mut order_l2e) = setup(1.0);
+        exch.process(&event(EXCH_BID_DEPTH_EVENT, 1, 100.0, 2.0))
+            .unwrap();
+        exch.process(&event(EXCH_ASK_DEPTH_EVENT, 1, 101.0, 10.0))
+            .unwrap();
+
+        let mut order = Order::new(
+            1,
+            100,
+            1.0,
+            3.0,
+            Side::Buy,
+            OrdType::Limit,
+            TimeInForce::GTC,
+        );
+        order.display_qty = 1.0;
+        order.req = Status::New;
+        order.local_timestamp = 10;
+        order_l2e.request(order, |_| {});
+        exch.process_recv_order(10, None).unwrap();
+        assert_eq!(receive_all(&mut order_l2e, 10)[0].status, Status::New);
+
+        // Only the displayed clip is filled even though the trade leaves 2.0 for the order, and
+        // the next clip goes to the back of the queue behind the 2.0 resting in the book.
+        exch.process(&event(EXCH_SELL_TRADE_EVENT, 20, 100.0, 4.0))
+            .unwrap();
+        let responses = receive_all(&mut order_l2e, 20);
+        assert_eq!(responses.len(), 1);
+        assert_eq!(responses[0].status, Status::PartiallyFilled);
+        assert!((responses[0].exec_qty - 1.0).abs() < 1e-9);
+
+        exch.process(&event(EXCH_SELL_TRADE_EVENT, 30, 100.0, 2.0))
+            .unwrap();
+        assert!(receive_all(&mut order_l2e, 30).is_empty());
+
+        // Each clip waits behind the book again, and the last one completes the order.
+        for (timestamp, status) in [(40, Status::PartiallyFilled), (50, Status::Filled)] {
+            exch.process(&event(EXCH_SELL_TRADE_EVENT, timestamp, 100.0, 3.0))
+                .unwrap();
+            let responses = receive_all(&mut order_l2e, timestamp);
+            assert_eq!(responses.len(), 1);
+            assert_eq!(responses[0].order_id, 1);
+            assert_eq!(responses[0].status, status);
+            assert!((responses[0].exec_qty - 1.0).abs() < 1e-9);
+        }
+        assert!(!exch.orders.borrow().contains_key(&1));
+        assert!((exch.state.values().trading_volume - 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_self_trade_prevention() {
+        for (stp, statuses, resting) in [
+            (
+                SelfTradePrevention::None,
+                vec![(3, Status::Filled)],
+                vec![1, 2],
+            ),
+            (
+                SelfTradePrevention::CancelMaker,
+                vec![(1, Status::Canceled), (3, Status::Filled)],
+                vec![2],
+            ),
+            (
+                SelfTradePrevention::CancelTaker,
+                vec![(3, Status::Canceled)],
+                vec![1, 2],
+            ),
+            (
+                SelfTradePrevention::CancelBoth,
+                vec![(1, Status::Canceled), (3, Status::Canceled)],
+                vec![2],
+            ),
+        ] {
+            let (exch, mut order_l2e) = setup(1.0);
+            let mut exch = exch.self_trade_prevention(stp);
+            exch.process(&event(EXCH_BID_DEPTH_EVENT, 1, 100.0, 2.0))
+                .unwrap();
+            exch.process(&event(EXCH_ASK_DEPTH_EVENT, 1, 103.0, 2.0))
+                .unwrap();
+
+            let mut submit = |order_id, side, price_tick, time_in_force, timestamp| {
+                let mut order = Order::new(
+                    order_id,
+                    price_tick,
+                    1.0,
+                    1.0,
+                    side,
+                    OrdType::Limit,
+                    time_in_force,
+                );
+                order.req = Status::New;
+                order.local_timestamp = timestamp;
+                order_l2e.request(order, |_| {});
+                exch.process_recv_order(timestamp, None).unwrap();
+                receive_all(&mut order_l2e, timestamp)
+            };
+
+            // Our buy order at 101 would be traded against by our sell order at 100, but the one
+            // at 99 would not.
+            assert_eq!(submit(1, Side::Buy, 101, TimeInForce::GTC, 10).len(), 1);
+            assert_eq!(submit(2, Side::Buy, 99, TimeInForce::GTC, 10).len(), 1);
+
+            let responses: Vec<_> = submit(3, Side::Sell, 100, TimeInForce::IOC, 20)
+                .iter()
+                .map(|order| (order.order_id, order.status))
+                .collect();
+            assert_eq!(responses, statuses);
+
+            let mut order_ids: Vec<_> = exch.orders.borrow().keys().copied().collect();
+            order_ids.sort();
+            assert_eq!(order_ids, resting);
+        }
+    }
+
+    #[test]
+    fn test_ioc_and_fok() {
+        let (mut exch, mut order_l2e) = setup(1.0);
+        exch.process(&event(EXCH_BID_DEPTH_EVENT, 1, 100.0, 2.0))
+            .unwrap();
+        exch.process(&event(EXCH_ASK_DEPTH_EVENT, 1, 101.0, 2.0))
+            .unwrap();
+        exch.process(&event(EXCH_ASK_DEPTH_EVENT, 1, 102.0, 3.0))
+            .unwrap();
+
+        let mut submit = |order_id, qty, time_in_force, timestamp| {
+            let mut order = Order::new(
+                order_id,
+                102,
+                1.0,
+                qty,
+                Side::Buy,
+                OrdType::Limit,
+                time_in_force,
+            );
+            order.req = Status::New;
+            order.local_timestamp = timestamp;
+            order_l2e.request(order, |_| {});
+            exch.process_recv_order(timestamp, None).unwrap();
+            receive_all(&mut order_l2e, timestamp)
+        };
+
+        // The IOC order takes both levels up to its price, and the rest is canceled.
+        let responses = submit(1, 6.0, TimeInForce::IOC, 10);
+        let statuses: Vec<_> = responses
+            .iter()
+            .map(|order| (order.status, order.exec_price_tick))
+            .collect();
+        assert_eq!(
+            statuses,
+            vec![
+                (Status::PartiallyFilled, 101),
+                (Status::PartiallyFilled, 102),
+                (Status::Canceled, 102),
+            ]
+        );
+        assert!((responses[0].exec_qty - 2.0).abs() < 1e-9);
+        assert!((responses[1].exec_qty - 3.0).abs() < 1e-9);
+        assert!((responses[2].leaves_qty - 1.0).abs() < 1e-9);
+
+        // Not enough quantity rests up to its price, so the FOK order is not filled at all.
+        let responses = submit(2, 6.0, TimeInForce::FOK, 20);
+        assert_eq!(responses.len(), 1);
+        assert_eq!(responses[0].status, Status::Expired);
+        assert!((responses[0].leaves_qty - 6.0).abs() < 1e-9);
+
+        let responses = submit(3, 5.0, TimeInForce::FOK, 30);
+        let statuses: Vec<_> = responses.iter().map(|order| order.status).collect();
+        assert_eq!(statuses, vec![Status::PartiallyFilled, Status::Filled]);
+
+        assert!((exch.state.values().trading_volume - 10.0).abs() < 1e-9);
+    }
+
+    #[test]
+    #[should_panic(expected = "fill_ratio must be in (0, 1]")]
+    fn test_invalid_fill_ratio() {
+        setup(1.5);
+    }
+}
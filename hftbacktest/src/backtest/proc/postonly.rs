@@ -0,0 +1,47 @@
+use crate::types::Status;
+
+/// How the exchange handles a post-only order, [`TimeInForce::GTX`](crate::types::TimeInForce::GTX),
+/// whose price would cross the opposite best when it arrives at the exchange.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PostOnly {
+    /// Expires the order with [`Status::Expired`], as Binance does with GTX orders.
+    #[default]
+    Expire,
+    /// Rejects the order with [`Status::Rejected`], as Bybit does with PostOnly orders that would
+    /// take liquidity.
+    Reject,
+    /// Reprices the order one tick away from the opposite best, so that it rests in the book as a
+    /// maker order instead.
+    Reprice,
+}
+
+impl PostOnly {
+    /// Returns the status of a post-only order that is not accepted because it would take
+    /// liquidity.
+    pub(crate) fn rejected_status(&self) -> Status {
+        match self {
+            PostOnly::Reject => Status::Rejected,
+            PostOnly::Expire | PostOnly::Reprice => Status::Expired,
+        }
+    }
+
+    /// Returns the price tick at which a post-only buy order rests in the book, given the current
+    /// best ask.
+    pub(crate) fn buy_price_tick(&self, price_tick: i64, best_ask_tick: i64) -> i64 {
+        if *self == PostOnly::Reprice && price_tick >= best_ask_tick {
+            best_ask_tick - 1
+        } else {
+            price_tick
+        }
+    }
+
+    /// Returns the price tick at which a post-only sell order rests in the book, given the current
+    /// best bid.
+    pub(crate) fn sell_price_tick(&self, price_tick: i64, best_bid_tick: i64) -> i64 {
+        if *self == PostOnly::Reprice && price_tick <= best_bid_tick {
+            best_bid_tick + 1
+        } else {
+            price_tick
+        }
+    }
+}
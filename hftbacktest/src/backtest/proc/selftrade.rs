@@ -0,0 +1,298 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    depth::{INVALID_MAX, INVALID_MIN, MarketDepth},
+    types::{OrdType, Order, OrderId, Side, Status, TimeInForce},
+};
+
+/// How the exchange handles an order that would take liquidity on arrival and trade against a
+/// resting order of our own, which is called self-trade prevention.
+///
+/// Our resting orders are not part of the replayed market depth, so without self-trade prevention
+/// both the taking order and our resting order it crosses are filled against the market.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SelfTradePrevention {
+    /// Does not prevent self-trades.
+    #[default]
+    None,
+    /// Cancels our resting orders that the taking order would trade against with
+    /// [`Status::Canceled`](crate::types::Status::Canceled), and then executes the taking order.
+    /// This is `EXPIRE_MAKER` on Binance and `CancelMaker` on Bybit.
+    CancelMaker,
+    /// Cancels the taking order with [`Status::Canceled`](crate::types::Status::Canceled), leaving
+    /// our resting orders in the book. This is `EXPIRE_TAKER` on Binance and `CancelTaker` on
+    /// Bybit.
+    CancelTaker,
+    /// Cancels both the taking order and our resting orders that it would trade against. This is
+    /// `EXPIRE_BOTH` on Binance and `CancelBoth` on Bybit.
+    CancelBoth,
+}
+
+impl SelfTradePrevention {
+    /// Returns `true` if our resting orders that would be traded against are canceled.
+    pub(crate) fn cancels_maker(&self) -> bool {
+        matches!(
+            self,
+            SelfTradePrevention::CancelMaker | SelfTradePrevention::CancelBoth
+        )
+    }
+
+    /// Returns `true` if the taking order is canceled.
+    pub(crate) fn cancels_taker(&self) -> bool {
+        matches!(
+            self,
+            SelfTradePrevention::CancelTaker | SelfTradePrevention::CancelBoth
+        )
+    }
+
+    /// Applies self-trade prevention to an order about to be executed or rested. Returns our
+    /// resting orders that are canceled, which are removed from `orders`, `buy_orders` and
+    /// `sell_orders` and set to [`Status::Canceled`] to be responded to, and `true` if the order
+    /// itself is canceled, in which case its status is set to [`Status::Canceled`].
+    ///
+    /// * `orders` - Our resting orders by ID.
+    /// * `buy_orders`, `sell_orders` - Our resting order IDs by price tick.
+    pub(crate) fn prevent<MD: MarketDepth>(
+        &self,
+        depth: &MD,
+        order: &mut Order,
+        orders: &mut HashMap<OrderId, Order>,
+        buy_orders: &mut HashMap<i64, HashSet<OrderId>>,
+        sell_orders: &mut HashMap<i64, HashSet<OrderId>>,
+        timestamp: i64,
+    ) -> (Vec<Order>, bool) {
+        if *self == SelfTradePrevention::None {
+            return (Vec::new(), false);
+        }
+        let crossed = crossed_orders(depth, order, buy_orders, sell_orders);
+        if crossed.is_empty() {
+            return (Vec::new(), false);
+        }
+
+        let mut canceled = Vec::new();
+        if self.cancels_maker() {
+            for order_id in crossed {
+                let mut maker = orders.remove(&order_id).unwrap();
+                if maker.side == Side::Buy {
+                    buy_orders
+                        .get_mut(&maker.price_tick)
+                        .unwrap()
+                        .remove(&order_id);
+                } else {
+                    sell_orders
+                        .get_mut(&maker.price_tick)
+                        .unwrap()
+                        .remove(&order_id);
+                }
+                maker.status = Status::Canceled;
+                maker.exch_timestamp = timestamp;
+                canceled.push(maker);
+            }
+        }
+
+        if self.cancels_taker() {
+            order.status = Status::Canceled;
+            order.exch_timestamp = timestamp;
+            return (canceled, true);
+        }
+        (canceled, false)
+    }
+}
+
+/// Returns the IDs of our resting orders that the order would trade against on arrival, ordered
+/// from the best price. These are the opposite-side orders priced at or better than the order's
+/// price, or all of them for a market order. The result is empty if the order does not take
+/// liquidity.
+///
+/// * `buy_orders`, `sell_orders` - Our resting order IDs by price tick.
+pub(crate) fn crossed_orders<MD: MarketDepth>(
+    depth: &MD,
+    order: &Order,
+    buy_orders: &HashMap<i64, HashSet<OrderId>>,
+    sell_orders: &HashMap<i64, HashSet<OrderId>>,
+) -> Vec<OrderId> {
+    let limit_price_tick = match order.order_type {
+        OrdType::Limit | OrdType::StopLimit => {
+            if matches!(
+                order.time_in_force,
+                TimeInForce::GTX | TimeInForce::Unsupported
+            ) {
+                return Vec::new();
+            }
+            Some(order.price_tick)
+        }
+        OrdType::Market | OrdType::StopMarket => None,
+        OrdType::Unsupported => return Vec::new(),
+    };
+
+    let mut crossed: Vec<(i64, OrderId)> = if order.side == Side::Buy {
+        let best_ask_tick = depth.best_ask_tick();
+        let limit_price_tick = limit_price_tick.unwrap_or(INVALID_MAX);
+        if best_ask_tick == INVALID_MAX || limit_price_tick < best_ask_tick {
+            return Vec::new();
+        }
+        sell_orders
+            .iter()
+            .filter(|(price_tick, _)| **price_tick <= limit_price_tick)
+            .flat_map(|(price_tick, order_ids)| {
+                order_ids.iter().map(|order_id| (*price_tick, *order_id))
+            })
+            .collect()
+    } else {
+        let best_bid_tick = depth.best_bid_tick();
+        let limit_price_tick = limit_price_tick.unwrap_or(INVALID_MIN);
+        if best_bid_tick == INVALID_MIN || limit_price_tick > best_bid_tick {
+            return Vec::new();
+        }
+        buy_orders
+            .iter()
+            .filter(|(price_tick, _)| **price_tick >= limit_price_tick)
+            .flat_map(|(price_tick, order_ids)| {
+                order_ids.iter().map(|order_id| (-*price_tick, *order_id))
+            })
+            .collect()
+    };
+    crossed.sort_unstable();
+    crossed.into_iter().map(|(_, order_id)| order_id).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::depth::{HashMapMarketDepth, L2MarketDepth};
+
+    type PriceOrders = HashMap<i64, HashSet<OrderId>>;
+
+    fn order(
+        order_id: OrderId,
+        side: Side,
+        price_tick: i64,
+        order_type: OrdType,
+        time_in_force: TimeInForce,
+    ) -> Order {
+        Order::new(
+            order_id,
+            price_tick,
+            1.0,
+            1.0,
+            side,
+            order_type,
+            time_in_force,
+        )
+    }
+
+    /// Returns the market depth and our resting orders: buys at 97 and 98, and sells at 101 and
+    /// 103, inside a market of 99 bid and 100 ask.
+    fn setup() -> (
+        HashMapMarketDepth,
+        HashMap<OrderId, Order>,
+        PriceOrders,
+        PriceOrders,
+    ) {
+        let mut depth = HashMapMarketDepth::new(1.0, 1.0);
+        depth.update_bid_depth(99.0, 1.0, 0);
+        depth.update_ask_depth(100.0, 1.0, 0);
+
+        let mut orders = HashMap::new();
+        let mut buy_orders: PriceOrders = HashMap::new();
+        let mut sell_orders: PriceOrders = HashMap::new();
+        for (order_id, side, price_tick) in [
+            (1, Side::Buy, 97),
+            (2, Side::Buy, 98),
+            (3, Side::Sell, 101),
+            (4, Side::Sell, 103),
+        ] {
+            let resting = order(order_id, side, price_tick, OrdType::Limit, TimeInForce::GTC);
+            if side == Side::Buy {
+                buy_orders.entry(price_tick).or_default().insert(order_id);
+            } else {
+                sell_orders.entry(price_tick).or_default().insert(order_id);
+            }
+            orders.insert(order_id, resting);
+        }
+        (depth, orders, buy_orders, sell_orders)
+    }
+
+    #[test]
+    fn test_crossed_orders() {
+        let (depth, _, buy_orders, sell_orders) = setup();
+        let crossed = |side, price_tick, order_type, time_in_force| {
+            crossed_orders(
+                &depth,
+                &order(10, side, price_tick, order_type, time_in_force),
+                &buy_orders,
+                &sell_orders,
+            )
+        };
+
+        // A buy order that does not reach the best ask takes no liquidity.
+        assert!(crossed(Side::Buy, 99, OrdType::Limit, TimeInForce::GTC).is_empty());
+        assert!(crossed(Side::Buy, 100, OrdType::Limit, TimeInForce::GTC).is_empty());
+        assert_eq!(
+            crossed(Side::Buy, 102, OrdType::Limit, TimeInForce::IOC),
+            vec![3]
+        );
+        assert_eq!(
+            crossed(Side::Buy, 103, OrdType::Limit, TimeInForce::GTC),
+            vec![3, 4]
+        );
+        assert_eq!(
+            crossed(Side::Buy, 0, OrdType::Market, TimeInForce::GTC),
+            vec![3, 4]
+        );
+
+        assert!(crossed(Side::Sell, 100, OrdType::Limit, TimeInForce::GTC).is_empty());
+        assert_eq!(
+            crossed(Side::Sell, 98, OrdType::Limit, TimeInForce::GTC),
+            vec![2]
+        );
+        assert_eq!(
+            crossed(Side::Sell, 0, OrdType::Market, TimeInForce::GTC),
+            vec![2, 1]
+        );
+
+        // A post-only order never takes liquidity.
+        assert!(crossed(Side::Buy, 103, OrdType::Limit, TimeInForce::GTX).is_empty());
+    }
+
+    #[test]
+    fn test_prevent() {
+        for (stp, taker_canceled, canceled) in [
+            (SelfTradePrevention::None, false, vec![]),
+            (SelfTradePrevention::CancelMaker, false, vec![3]),
+            (SelfTradePrevention::CancelTaker, true, vec![]),
+            (SelfTradePrevention::CancelBoth, true, vec![3]),
+        ] {
+            let (depth, mut orders, mut buy_orders, mut sell_orders) = setup();
+            let mut taker = order(10, Side::Buy, 102, OrdType::Limit, TimeInForce::GTC);
+            taker.status = Status::New;
+
+            let (makers, canceled_taker) = stp.prevent(
+                &depth,
+                &mut taker,
+                &mut orders,
+                &mut buy_orders,
+                &mut sell_orders,
+                5,
+            );
+
+            assert_eq!(canceled_taker, taker_canceled);
+            assert_eq!(taker.status == Status::Canceled, taker_canceled);
+            assert_eq!(
+                makers
+                    .iter()
+                    .map(|maker| (maker.order_id, maker.status, maker.exch_timestamp))
+                    .collect::<Vec<_>>(),
+                canceled
+                    .iter()
+                    .map(|order_id| (*order_id, Status::Canceled, 5))
+                    .collect::<Vec<_>>()
+            );
+            assert_eq!(orders.contains_key(&3), canceled.is_empty());
+            assert_eq!(sell_orders[&101].is_empty(), !canceled.is_empty());
+            // Our orders the taker would not trade against are kept.
+            assert!(orders.contains_key(&4));
+            assert!(sell_orders[&103].contains(&4));
+        }
+    }
+}
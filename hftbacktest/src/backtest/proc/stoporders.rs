@@ -0,0 +1,191 @@
+use crate::types::{
+    EXCH_INDEX_PRICE_EVENT,
+    EXCH_MARK_PRICE_EVENT,
+    EXCH_TRADE_EVENT,
+    Event,
+    Order,
+    OrderId,
+    Side,
+};
+
+/// The price that triggers stop orders.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TriggerSource {
+    /// The price of market trades, [`EXCH_TRADE_EVENT`].
+    #[default]
+    LastTrade,
+    /// The mark price, [`EXCH_MARK_PRICE_EVENT`]. The feed needs to contain these events.
+    MarkPrice,
+    /// The index price, [`EXCH_INDEX_PRICE_EVENT`]. The feed needs to contain these events.
+    IndexPrice,
+}
+
+impl TriggerSource {
+    /// Returns the price carried by the event if it is from this source.
+    fn price(&self, event: &Event) -> Option<f64> {
+        let is_source = match self {
+            TriggerSource::LastTrade => event.is(EXCH_TRADE_EVENT),
+            TriggerSource::MarkPrice => event.is(EXCH_MARK_PRICE_EVENT),
+            TriggerSource::IndexPrice => event.is(EXCH_INDEX_PRICE_EVENT),
+        };
+        is_source.then_some(event.px)
+    }
+}
+
+/// Holds the stop orders that an exchange model has accepted but whose trigger price has not been
+/// reached yet.
+///
+/// A buy stop order is triggered once the trigger source reaches a price greater than or equal to
+/// its trigger price, and a sell stop order once it reaches a price less than or equal to its
+/// trigger price. A triggered [`OrdType::StopMarket`](crate::types::OrdType::StopMarket) order
+/// takes the market at the best price plus `slippage_ticks` against it, and a triggered
+/// [`OrdType::StopLimit`](crate::types::OrdType::StopLimit) order is handled like a new limit
+/// order at that point.
+pub struct StopOrders {
+    trigger_source: TriggerSource,
+    slippage_ticks: i64,
+    // Kept in the order of acceptance so that orders triggered by the same price are released in
+    // that order.
+    orders: Vec<Order>,
+}
+
+impl StopOrders {
+    /// Constructs an instance of `StopOrders`.
+    pub fn new(trigger_source: TriggerSource, slippage_ticks: i64) -> Self {
+        Self {
+            trigger_source,
+            slippage_ticks,
+            orders: Vec::new(),
+        }
+    }
+
+    /// Returns the slippage, in ticks, applied to a triggered stop market order.
+    pub fn slippage_ticks(&self) -> i64 {
+        self.slippage_ticks
+    }
+
+    pub(crate) fn contains(&self, order_id: OrderId) -> bool {
+        self.orders.iter().any(|order| order.order_id == order_id)
+    }
+
+    pub(crate) fn insert(&mut self, order: Order) {
+        self.orders.push(order);
+    }
+
+    pub(crate) fn remove(&mut self, order_id: OrderId) -> Option<Order> {
+        let pos = self
+            .orders
+            .iter()
+            .position(|order| order.order_id == order_id)?;
+        Some(self.orders.remove(pos))
+    }
+
+    /// Removes and returns the orders triggered by the event.
+    pub(crate) fn take_triggered(&mut self, event: &Event, tick_size: f64) -> Vec<Order> {
+        if self.orders.is_empty() {
+            return Vec::new();
+        }
+        let Some(price) = self.trigger_source.price(event) else {
+            return Vec::new();
+        };
+        let price_tick = (price / tick_size).round() as i64;
+        let (triggered, pending) = self.orders.drain(..).partition(|order| match order.side {
+            Side::Buy => price_tick >= order.trigger_price_tick,
+            _ => price_tick <= order.trigger_price_tick,
+        });
+        self.orders = pending;
+        triggered
+    }
+}
+
+impl Default for StopOrders {
+    fn default() -> Self {
+        Self::new(TriggerSource::default(), 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{EXCH_BUY_TRADE_EVENT, EXCH_SELL_TRADE_EVENT, OrdType, TimeInForce};
+
+    fn event(ev: u64, px: f64) -> Event {
+        Event {
+            ev,
+            exch_ts: 0,
+            local_ts: 0,
+            px,
+            qty: 1.0,
+            order_id: 0,
+            ival: 0,
+            fval: 0.0,
+        }
+    }
+
+    fn stop(order_id: OrderId, side: Side, trigger_price_tick: i64) -> Order {
+        let mut order = Order::new(
+            order_id,
+            0,
+            1.0,
+            1.0,
+            side,
+            OrdType::StopMarket,
+            TimeInForce::GTC,
+        );
+        order.trigger_price_tick = trigger_price_tick;
+        order
+    }
+
+    #[test]
+    fn test_take_triggered() {
+        let mut stop_orders = StopOrders::default();
+        stop_orders.insert(stop(1, Side::Buy, 105));
+        stop_orders.insert(stop(2, Side::Sell, 95));
+        stop_orders.insert(stop(3, Side::Buy, 103));
+
+        assert!(
+            stop_orders
+                .take_triggered(&event(EXCH_MARK_PRICE_EVENT, 110.0), 1.0)
+                .is_empty()
+        );
+        assert!(
+            stop_orders
+                .take_triggered(&event(EXCH_SELL_TRADE_EVENT, 100.0), 1.0)
+                .is_empty()
+        );
+
+        let triggered = stop_orders.take_triggered(&event(EXCH_BUY_TRADE_EVENT, 105.0), 1.0);
+        let order_ids: Vec<_> = triggered.iter().map(|order| order.order_id).collect();
+        assert_eq!(order_ids, vec![1, 3]);
+        assert!(!stop_orders.contains(1));
+        assert!(stop_orders.contains(2));
+
+        let triggered = stop_orders.take_triggered(&event(EXCH_SELL_TRADE_EVENT, 95.0), 1.0);
+        assert_eq!(triggered.len(), 1);
+        assert_eq!(triggered[0].order_id, 2);
+    }
+
+    #[test]
+    fn test_mark_price_source() {
+        let mut stop_orders = StopOrders::new(TriggerSource::MarkPrice, 0);
+        stop_orders.insert(stop(1, Side::Sell, 95));
+
+        assert!(
+            stop_orders
+                .take_triggered(&event(EXCH_SELL_TRADE_EVENT, 90.0), 1.0)
+                .is_empty()
+        );
+        assert!(
+            stop_orders
+                .take_triggered(&event(EXCH_INDEX_PRICE_EVENT, 90.0), 1.0)
+                .is_empty()
+        );
+        assert_eq!(
+            stop_orders
+                .take_triggered(&event(EXCH_MARK_PRICE_EVENT, 94.0), 1.0)
+                .len(),
+            1
+        );
+        assert!(stop_orders.remove(1).is_none());
+    }
+}
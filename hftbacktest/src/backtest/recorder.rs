@@ -24,6 +24,8 @@ struct Record {
     num_trades: i64,
     trading_volume: f64,
     trading_value: f64,
+    num_post_only_rejects: i64,
+    funding: f64,
 }
 
 unsafe impl POD for Record {}
@@ -57,6 +59,8 @@ impl Recorder for BacktestRecorder {
                 trading_volume: state_values.trading_volume,
                 trading_value: state_values.trading_value,
                 num_trades: state_values.num_trades,
+                num_post_only_rejects: state_values.num_post_only_rejects,
+                funding: state_values.funding,
             });
         }
         Ok(())
@@ -84,7 +88,7 @@ impl BacktestRecorder {
     /// Saves record data into a CSV file at the specified path. It creates a separate CSV file for
     /// each asset, with the filename `{prefix}_{asset_no}.csv`.
     /// The columns are `timestamp`, `mid`, `balance`, `position`, `fee`, `trade_num`,
-    /// `trade_amount`, `trade_qty`.
+    /// `trade_amount`, `trade_qty`, `num_post_only_rejects`, `funding`.
     pub fn to_csv<Prefix, P>(&self, prefix: Prefix, path: P) -> Result<(), Error>
     where
         Prefix: AsRef<str>,
@@ -96,7 +100,7 @@ impl BacktestRecorder {
             let mut file = BufWriter::new(File::create(file_path)?);
             writeln!(
                 file,
-                "timestamp,balance,position,fee,trading_volume,trading_value,num_trades,price",
+                "timestamp,balance,position,fee,trading_volume,trading_value,num_trades,price,num_post_only_rejects,funding",
             )?;
             for Record {
                 timestamp,
@@ -107,11 +111,13 @@ impl BacktestRecorder {
                 trading_value,
                 num_trades,
                 price: mid_price,
+                num_post_only_rejects,
+                funding,
             } in values
             {
                 writeln!(
                     file,
-                    "{timestamp},{balance},{position},{fee},{trading_volume},{trading_value},{num_trades},{mid_price}"
+                    "{timestamp},{balance},{position},{fee},{trading_volume},{trading_value},{num_trades},{mid_price},{num_post_only_rejects},{funding}"
                 )?;
             }
         }
@@ -28,6 +28,8 @@ where
                 num_trades: 0,
                 trading_volume: 0.0,
                 trading_value: 0.0,
+                num_post_only_rejects: 0,
+                funding: 0.0,
             },
             fee_model,
             asset_type,
@@ -45,6 +47,22 @@ where
         self.state_values.trading_value += amount;
     }
 
+    #[inline]
+    pub fn apply_post_only_reject(&mut self) {
+        self.state_values.num_post_only_rejects += 1;
+    }
+
+    /// Charges the funding payment of the current position at `rate`, valued at `mark_price`.
+    #[inline]
+    pub fn apply_funding(&mut self, rate: f64, mark_price: f64) {
+        if self.state_values.position != 0.0 {
+            self.state_values.funding += self
+                .asset_type
+                .amount(mark_price, self.state_values.position)
+                * rate;
+        }
+    }
+
     #[inline]
     pub fn equity(&self, mid: f64) -> f64 {
         self.asset_type.equity(
@@ -52,7 +70,7 @@ where
             self.state_values.balance,
             self.state_values.position,
             self.state_values.fee,
-        )
+        ) - self.state_values.funding
     }
 
     #[inline]
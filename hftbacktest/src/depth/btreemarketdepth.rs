@@ -219,6 +219,22 @@ impl MarketDepth for BTreeMarketDepth {
     fn ask_qty_at_tick(&self, price_tick: i64) -> f64 {
         *self.ask_depth.get(&price_tick).unwrap_or(&0.0)
     }
+
+    #[inline(always)]
+    fn low_bid_tick(&self) -> i64 {
+        self.bid_depth
+            .first_key_value()
+            .map(|(price_tick, _)| *price_tick)
+            .unwrap_or(INVALID_MAX)
+    }
+
+    #[inline(always)]
+    fn high_ask_tick(&self) -> i64 {
+        self.ask_depth
+            .last_key_value()
+            .map(|(price_tick, _)| *price_tick)
+            .unwrap_or(INVALID_MIN)
+    }
 }
 
 impl ApplySnapshot for BTreeMarketDepth {
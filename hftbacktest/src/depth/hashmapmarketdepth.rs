@@ -308,6 +308,16 @@ impl MarketDepth for HashMapMarketDepth {
     fn ask_qty_at_tick(&self, price_tick: i64) -> f64 {
         *self.ask_depth.get(&price_tick).unwrap_or(&0.0)
     }
+
+    #[inline(always)]
+    fn low_bid_tick(&self) -> i64 {
+        self.low_bid_tick
+    }
+
+    #[inline(always)]
+    fn high_ask_tick(&self) -> i64 {
+        self.high_ask_tick
+    }
 }
 
 impl ApplySnapshot for HashMapMarketDepth {
@@ -58,6 +58,18 @@ pub trait MarketDepth {
 
     /// Returns the quantity at the ask market depth for a given price in ticks.
     fn ask_qty_at_tick(&self, price_tick: i64) -> f64;
+
+    /// Returns the price in ticks below which no quantity rests at the bid market depth, so that a
+    /// walk down the bid side can stop there. If it is not tracked, it returns [`INVALID_MIN`].
+    fn low_bid_tick(&self) -> i64 {
+        INVALID_MIN
+    }
+
+    /// Returns the price in ticks above which no quantity rests at the ask market depth, so that a
+    /// walk up the ask side can stop there. If it is not tracked, it returns [`INVALID_MAX`].
+    fn high_ask_tick(&self) -> i64 {
+        INVALID_MAX
+    }
 }
 
 /// Provides Level2-specific market depth functions.
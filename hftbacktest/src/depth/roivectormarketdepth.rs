@@ -459,6 +459,16 @@ impl MarketDepth for ROIVectorMarketDepth {
             }
         }
     }
+
+    #[inline(always)]
+    fn low_bid_tick(&self) -> i64 {
+        self.low_bid_tick
+    }
+
+    #[inline(always)]
+    fn high_ask_tick(&self) -> i64 {
+        self.high_ask_tick
+    }
 }
 
 impl ApplySnapshot for ROIVectorMarketDepth {
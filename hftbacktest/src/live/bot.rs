@@ -365,6 +365,8 @@ where
         qty: f64,
         time_in_force: TimeInForce,
         order_type: OrdType,
+        trigger_price: f64,
+        display_qty: f64,
         wait: bool,
         side: Side,
     ) -> Result<ElapseResult, BotError> {
@@ -380,7 +382,13 @@ where
         let order = Order {
             order_id,
             price_tick: (price / tick_size).round() as i64,
+            trigger_price_tick: if order_type.is_stop() {
+                (trigger_price / tick_size).round() as i64
+            } else {
+                0
+            },
             qty,
+            display_qty,
             leaves_qty: qty,
             tick_size,
             side,
@@ -488,6 +496,8 @@ where
         qty: f64,
         time_in_force: TimeInForce,
         order_type: OrdType,
+        trigger_price: f64,
+        display_qty: f64,
         wait: bool,
     ) -> Result<ElapseResult, Self::Error> {
         self.submit_order(
@@ -497,6 +507,8 @@ where
             qty,
             time_in_force,
             order_type,
+            trigger_price,
+            display_qty,
             wait,
             Side::Buy,
         )
@@ -511,6 +523,8 @@ where
         qty: f64,
         time_in_force: TimeInForce,
         order_type: OrdType,
+        trigger_price: f64,
+        display_qty: f64,
         wait: bool,
     ) -> Result<ElapseResult, Self::Error> {
         self.submit_order(
@@ -520,6 +534,8 @@ where
             qty,
             time_in_force,
             order_type,
+            trigger_price,
+            display_qty,
             wait,
             Side::Sell,
         )
@@ -538,6 +554,8 @@ where
             order.qty,
             order.time_in_force,
             order.order_type,
+            order.trigger_price,
+            order.display_qty,
             wait,
             order.side,
         )
@@ -181,6 +181,12 @@ pub const MODIFY_ORDER_EVENT: u64 = 12;
 /// Indicates that an order in the order book has been filled.
 pub const FILL_EVENT: u64 = 13;
 
+/// Indicates that the mark price is updated. The price is in `px`.
+pub const MARK_PRICE_EVENT: u64 = 14;
+
+/// Indicates that the index price is updated. The price is in `px`.
+pub const INDEX_PRICE_EVENT: u64 = 15;
+
 /// Indicates that it is a valid event to be handled by the exchange processor at the exchange
 /// timestamp.
 pub const EXCH_EVENT: u64 = 1 << 31;
@@ -293,6 +299,12 @@ pub const EXCH_CANCEL_ORDER_EVENT: u64 = EXCH_EVENT | CANCEL_ORDER_EVENT;
 /// Represents a combination of [`EXCH_EVENT`] and [`MODIFY_ORDER_EVENT`].
 pub const EXCH_MODIFY_ORDER_EVENT: u64 = EXCH_EVENT | MODIFY_ORDER_EVENT;
 
+/// Represents a combination of [`EXCH_EVENT`] and [`MARK_PRICE_EVENT`].
+pub const EXCH_MARK_PRICE_EVENT: u64 = EXCH_EVENT | MARK_PRICE_EVENT;
+
+/// Represents a combination of [`EXCH_EVENT`] and [`INDEX_PRICE_EVENT`].
+pub const EXCH_INDEX_PRICE_EVENT: u64 = EXCH_EVENT | INDEX_PRICE_EVENT;
+
 /// Represents a combination of [`EXCH_EVENT`] and [`FILL_EVENT`].
 pub const EXCH_FILL_EVENT: u64 = EXCH_EVENT | FILL_EVENT;
 
@@ -401,6 +413,9 @@ pub enum Status {
     PartiallyFilled = 5,
     Rejected = 6,
     Replaced = 7,
+    /// The trigger price of a stop order has been reached. The order is then released to the
+    /// market as a market or limit order, and a further response reports the outcome.
+    Triggered = 8,
     /// This occurs when the [`Connector`](`crate::connector::Connector`) receives an order status
     /// value that does not have a corresponding enum value.
     Unsupported = 255,
@@ -441,14 +456,27 @@ impl AsRef<str> for TimeInForce {
 pub enum OrdType {
     Limit = 0,
     Market = 1,
+    /// A market order placed once the trigger price is reached.
+    StopMarket = 2,
+    /// A limit order placed once the trigger price is reached.
+    StopLimit = 3,
     Unsupported = 255,
 }
 
+impl OrdType {
+    /// Returns whether this is a stop order, which waits for its trigger price.
+    pub fn is_stop(&self) -> bool {
+        matches!(self, OrdType::StopMarket | OrdType::StopLimit)
+    }
+}
+
 impl AsRef<str> for OrdType {
     fn as_ref(&self) -> &'static str {
         match self {
             OrdType::Limit => "LIMIT",
             OrdType::Market => "MARKET",
+            OrdType::StopMarket => "STOP_MARKET",
+            OrdType::StopLimit => "STOP_LIMIT",
             OrdType::Unsupported => panic!("OrdType::Unsupported"),
         }
     }
@@ -491,6 +519,10 @@ impl AnyClone for () {
 pub struct Order {
     /// Order quantity
     pub qty: f64,
+    /// Quantity displayed in the order book at a time by an iceberg order, `0` for an order that
+    /// displays its full quantity. Once a displayed clip is filled, the next one is placed at the
+    /// back of the queue.
+    pub display_qty: f64,
     /// The quantity of this order that has not yet been executed. It represents the remaining
     /// quantity that is still open or active in the market after any partial fills.
     pub leaves_qty: f64,
@@ -501,6 +533,9 @@ pub struct Order {
     pub exec_price_tick: i64,
     /// Order price in ticks (`price / tick_size`).
     pub price_tick: i64,
+    /// Trigger price in ticks (`trigger_price / tick_size`) of a stop order, `0` for other order
+    /// types.
+    pub trigger_price_tick: i64,
     /// The tick size of the asset associated with this order.
     pub tick_size: f64,
     /// The time at which the exchange processes this order, ideally when the matching engine
@@ -537,8 +572,10 @@ impl Order {
     ) -> Self {
         Self {
             qty,
+            display_qty: 0.0,
             leaves_qty: qty,
             price_tick,
+            trigger_price_tick: 0,
             tick_size,
             side,
             time_in_force,
@@ -565,6 +602,17 @@ impl Order {
         self.exec_price_tick as f64 * self.tick_size
     }
 
+    /// Returns the trigger price, only available for a stop order.
+    pub fn trigger_price(&self) -> f64 {
+        self.trigger_price_tick as f64 * self.tick_size
+    }
+
+    /// Returns whether this is an iceberg order, which displays only `display_qty` of its quantity
+    /// at a time.
+    pub fn is_iceberg(&self) -> bool {
+        self.display_qty > 0.0 && self.display_qty < self.qty
+    }
+
     /// Returns whether this order is cancelable.
     pub fn cancellable(&self) -> bool {
         (self.status == Status::New || self.status == Status::PartiallyFilled)
@@ -598,8 +646,10 @@ impl Order {
         }
 
         self.qty = order.qty;
+        self.display_qty = order.display_qty;
         self.leaves_qty = order.leaves_qty;
         self.price_tick = order.price_tick;
+        self.trigger_price_tick = order.trigger_price_tick;
         self.tick_size = order.tick_size;
         self.side = order.side;
         self.time_in_force = order.time_in_force;
@@ -625,8 +675,10 @@ impl Debug for Order {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Order")
             .field("qty", &self.qty)
+            .field("display_qty", &self.display_qty)
             .field("leaves_qty", &self.leaves_qty)
             .field("price_tick", &self.price_tick)
+            .field("trigger_price_tick", &self.trigger_price_tick)
             .field("tick_size", &self.tick_size)
             .field("side", &self.side)
             .field("time_in_force", &self.time_in_force)
@@ -647,10 +699,12 @@ impl<Context> Decode<Context> for Order {
     fn decode<D: Decoder>(decoder: &mut D) -> Result<Self, DecodeError> {
         Ok(Self {
             qty: Decode::decode(decoder)?,
+            display_qty: Decode::decode(decoder)?,
             leaves_qty: Decode::decode(decoder)?,
             exec_qty: Decode::decode(decoder)?,
             exec_price_tick: Decode::decode(decoder)?,
             price_tick: Decode::decode(decoder)?,
+            trigger_price_tick: Decode::decode(decoder)?,
             tick_size: Decode::decode(decoder)?,
             exch_timestamp: Decode::decode(decoder)?,
             local_timestamp: Decode::decode(decoder)?,
@@ -671,10 +725,12 @@ impl<'de, Context> BorrowDecode<'de, Context> for Order {
     fn borrow_decode<D: BorrowDecoder<'de>>(decoder: &mut D) -> Result<Self, DecodeError> {
         Ok(Self {
             qty: Decode::decode(decoder)?,
+            display_qty: Decode::decode(decoder)?,
             leaves_qty: Decode::decode(decoder)?,
             exec_qty: Decode::decode(decoder)?,
             exec_price_tick: Decode::decode(decoder)?,
             price_tick: Decode::decode(decoder)?,
+            trigger_price_tick: Decode::decode(decoder)?,
             tick_size: Decode::decode(decoder)?,
             exch_timestamp: Decode::decode(decoder)?,
             local_timestamp: Decode::decode(decoder)?,
@@ -694,10 +750,12 @@ impl<'de, Context> BorrowDecode<'de, Context> for Order {
 impl Encode for Order {
     fn encode<E: Encoder>(&self, encoder: &mut E) -> Result<(), EncodeError> {
         self.qty.encode(encoder)?;
+        self.display_qty.encode(encoder)?;
         self.leaves_qty.encode(encoder)?;
         self.exec_qty.encode(encoder)?;
         self.exec_price_tick.encode(encoder)?;
         self.price_tick.encode(encoder)?;
+        self.trigger_price_tick.encode(encoder)?;
         self.tick_size.encode(encoder)?;
         self.exch_timestamp.encode(encoder)?;
         self.local_timestamp.encode(encoder)?;
@@ -746,6 +804,16 @@ pub struct StateValues {
     pub trading_volume: f64,
     /// Backtest only
     pub trading_value: f64,
+    /// The number of post-only orders that were not accepted because they would have taken
+    /// liquidity.
+    ///
+    /// Backtest only
+    pub num_post_only_rejects: i64,
+    /// The cumulative funding payments, positive when paid and negative when received. See
+    /// [`FundingFeeModel`](crate::backtest::models::FundingFeeModel).
+    ///
+    /// Backtest only
+    pub funding: f64,
 }
 
 /// Provides errors that can occur in builders.
@@ -772,6 +840,10 @@ pub struct OrderRequest {
     pub side: Side,
     pub time_in_force: TimeInForce,
     pub order_type: OrdType,
+    /// Trigger price of a stop order, ignored for other order types.
+    pub trigger_price: f64,
+    /// Displayed quantity of an iceberg order, `0` to display the full quantity.
+    pub display_qty: f64,
 }
 
 /// Provides a bot interface for backtesting and live trading.
@@ -830,6 +902,10 @@ where
     /// * `order_type` - Available [`OrdType`] options vary depending on the exchange model. See to
     ///   the exchange model for details.
     ///
+    /// * `trigger_price` - Trigger price of a [`OrdType::StopMarket`] or [`OrdType::StopLimit`]
+    ///   order. Ignored for other order types.
+    /// * `display_qty` - Quantity displayed at a time by an iceberg order, or `0` to display the
+    ///   full quantity. Support varies depending on the exchange model.
     /// * `wait` - If true, wait until the order placement response is received.
     #[allow(clippy::too_many_arguments)]
     fn submit_buy_order(
@@ -840,6 +916,8 @@ where
         qty: f64,
         time_in_force: TimeInForce,
         order_type: OrdType,
+        trigger_price: f64,
+        display_qty: f64,
         wait: bool,
     ) -> Result<ElapseResult, Self::Error>;
 
@@ -856,6 +934,10 @@ where
     /// * `order_type` - Available [`OrdType`] options vary depending on the exchange model. See to
     ///   the exchange model for details.
     ///
+    /// * `trigger_price` - Trigger price of a [`OrdType::StopMarket`] or [`OrdType::StopLimit`]
+    ///   order. Ignored for other order types.
+    /// * `display_qty` - Quantity displayed at a time by an iceberg order, or `0` to display the
+    ///   full quantity. Support varies depending on the exchange model.
     /// * `wait` - If true, wait until the order placement response is received.
     #[allow(clippy::too_many_arguments)]
     fn submit_sell_order(
@@ -866,6 +948,8 @@ where
         qty: f64,
         time_in_force: TimeInForce,
         order_type: OrdType,
+        trigger_price: f64,
+        display_qty: f64,
         wait: bool,
     ) -> Result<ElapseResult, Self::Error>;
 
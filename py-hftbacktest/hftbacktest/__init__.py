@@ -7,6 +7,7 @@ from ._hftbacktest import (
     BacktestAsset as BacktestAsset_,
     build_hashmap_backtest,
     build_roivec_backtest,
+    _estimate_queue_profile,
     LiveInstrument
 )
 from .binding import (
@@ -25,10 +26,14 @@ from .order import (
     EXPIRED,
     FILLED,
     CANCELED,
+    REJECTED,
     GTC,
     GTX,
     LIMIT,
     MARKET,
+    STOP_MARKET,
+    STOP_LIMIT,
+    TRIGGERED,
 )
 from .recorder import Recorder
 from .types import (
@@ -43,10 +48,13 @@ from .types import (
     CANCEL_ORDER_EVENT,
     MODIFY_ORDER_EVENT,
     FILL_EVENT,
+    MARK_PRICE_EVENT,
+    INDEX_PRICE_EVENT,
     EXCH_EVENT,
     LOCAL_EVENT,
     BUY_EVENT,
-    SELL_EVENT
+    SELL_EVENT,
+    queue_profile_dtype
 )
 try:
     from ._hftbacktest import (
@@ -67,6 +75,8 @@ __all__ = (
     'BacktestAsset',
     'HashMapMarketDepthBacktest',
     'ROIVectorMarketDepthBacktest',
+    'estimate_queue_profile',
+    'queue_profile_dtype',
 
     'LiveInstrument',
     'HashMapMarketDepthLiveBot',
@@ -84,6 +94,8 @@ __all__ = (
     'CANCEL_ORDER_EVENT',
     'MODIFY_ORDER_EVENT',
     'FILL_EVENT',
+    'MARK_PRICE_EVENT',
+    'INDEX_PRICE_EVENT',
     'EXCH_EVENT',
     'LOCAL_EVENT',
     'EXCH_EVENT',
@@ -101,6 +113,8 @@ __all__ = (
     'EXPIRED',
     'FILLED',
     'CANCELED',
+    'REJECTED',
+    'TRIGGERED',
 
     # Time-In-Force
     'GTC',
@@ -108,6 +122,8 @@ __all__ = (
 
     'LIMIT',
     'MARKET',
+    'STOP_MARKET',
+    'STOP_LIMIT',
     
     'Recorder'
 )
@@ -116,6 +132,22 @@ __version__ = '2.4.3'
 
 
 class BacktestAsset(BacktestAsset_):
+    @classmethod
+    def l3(cls):
+        """
+        Constructs a backtesting asset for Level-3 Market-By-Order feed data, such as converted by
+        :func:`hftbacktest.data.utils.mbo.convert`. The market depth is rebuilt order by order, and your orders are
+        placed at the back of the actual FIFO queue at their price level, so no queue position model is needed. The
+        strategy code is the same as for a Level-2 asset.
+
+        This selects the :meth:`l3_fifo_queue_model` and the :meth:`no_partial_fill_exchange`, as partial fills aren't
+        supported with Level-3 data.
+
+        Returns:
+            A :class:`BacktestAsset` for Level-3 feed data.
+        """
+        return cls().l3_fifo_queue_model().no_partial_fill_exchange()
+
     def add_data(self, data: EVENT_ARRAY):
         self._add_data_ndarray(data.ctypes.data, len(data))
         return self
@@ -170,6 +202,34 @@ class BacktestAsset(BacktestAsset_):
             raise ValueError
         return self
 
+    def empirical_queue_model(self, profile: str | NDArray):
+        """
+        Uses the `EmpiricalQueueModel` for the queue position model, with a queue profile estimated by
+        :func:`estimate_queue_profile`.
+
+        Please find the details below.
+
+        * `Order Fill - ProbQueueModel <https://hftbacktest.readthedocs.io/en/latest/order_fill.html#probqueuemodel>`_
+        * `EmpiricalQueueModel <https://docs.rs/hftbacktest/latest/hftbacktest/backtest/models/struct.EmpiricalQueueModel.html>`_
+
+        Args:
+            profile: The file path of the queue profile in `npz`, or a NumPy array of the queue profile with
+                     ``queue_profile_dtype``.
+
+        Raises:
+            ValueError: if the queue profile can't be loaded or is invalid.
+        """
+        if isinstance(profile, str):
+            super().empirical_queue_model(profile)
+        elif isinstance(profile, np.ndarray):
+            if profile.dtype != queue_profile_dtype:
+                raise ValueError('profile must have queue_profile_dtype')
+            profile = np.ascontiguousarray(profile)
+            self._empirical_queue_model_ndarray(profile.ctypes.data, len(profile))
+        else:
+            raise ValueError
+        return self
+
     def initial_snapshot(self, data: str | np.ndarray[Any, event_dtype]):
         """
         Sets the initial snapshot.
@@ -218,6 +278,35 @@ def ROIVectorMarketDepthBacktest(
     return ROIVectorMarketDepthBacktest_(ptr)
 
 
+def estimate_queue_profile(
+        events: EVENT_ARRAY,
+        tick_size: float,
+        levels: int = 5,
+        bins: int = 21
+) -> NDArray:
+    """
+    Estimates the queue profile for :meth:`BacktestAsset.empirical_queue_model` from Level 3 Market-By-Order
+    events: the probability that a cancel is ahead of an order, given the distance of its price level from the best
+    price in ticks and its relative queue position, from 0 at the front to 1 at the back. Levels without any cancel
+    get the uniform profile.
+
+    Args:
+        events: Level 3 Market-By-Order feed data, with the exchange-side add, modify, cancel and fill events.
+        tick_size: The tick size of the asset.
+        levels: The number of price levels from the best price to estimate.
+        bins: The number of evenly spaced queue positions from 0 to 1 at each level.
+
+    Returns:
+        A NumPy array of the queue profile with ``queue_profile_dtype``, which can be saved with
+        ``np.savez_compressed(file, data=profile)``.
+    """
+    events = np.ascontiguousarray(events)
+    if events.dtype != event_dtype:
+        raise ValueError('events must have event_dtype')
+    rows = _estimate_queue_profile(events.ctypes.data, len(events), tick_size, levels, bins)
+    return np.array(rows, dtype=queue_profile_dtype)
+
+
 if LIVE_FEATURE:
     def HashMapMarketDepthLiveBot(
             assets: List[LiveInstrument]
@@ -0,0 +1,94 @@
+"""Binance exchange utilities for fetching historical trade data."""
+
+from typing import Dict, List, Optional
+
+try:
+    from .. import _hftbacktest
+except ImportError:
+    _hftbacktest = None
+
+if _hftbacktest is not None:
+    from .._hftbacktest import BinanceError
+else:
+
+    class BinanceError(RuntimeError):
+        """Raised when fetching data from Binance fails."""
+
+
+def fetch_binance_trades(
+    symbol: str,
+    start_time: int,
+    end_time: int,
+    *,
+    limit: int = 1000,
+    max_retries: int = 5,
+    base_backoff_ms: int = 50,
+    max_backoff_ms: int = 10000,
+    base_url: str = "https://fapi.binance.com",
+    side_as_int: bool = False,
+) -> List[Dict]:
+    """
+    Fetch Binance USDⓈ-M Futures aggregate trades between two timestamps.
+
+    This function calls Binance's ``/fapi/v1/aggTrades`` endpoint. The first trade is located
+    with hour-long time windows, since Binance rejects wider ones, and the rest are paged
+    forwards by aggregate trade ID.
+
+    Args:
+        symbol (str): Trading symbol (e.g., "BTCUSDT").
+        start_time (int): Start timestamp in milliseconds.
+        end_time (int): End timestamp in milliseconds (inclusive).
+        limit (int, optional): Number of trades per request. Must be between 1 and 1000.
+            Default: 1000.
+        max_retries (int, optional): Maximum number of retries on HTTP 429. Default: 5.
+        base_backoff_ms (int, optional): Wait before the first retry in milliseconds when
+            Binance sends no ``Retry-After`` header, doubled on each retry. Default: 50.
+        max_backoff_ms (int, optional): Upper bound of the wait between retries in
+            milliseconds. Default: 10000.
+        base_url (str, optional): Binance Futures API base URL.
+            Default: "https://fapi.binance.com".
+        side_as_int (bool, optional): Return ``side`` as 1 for "Buy" and -1 for "Sell" instead
+            of the string, which is cheaper to store and aggregate. A trade with any other side
+            raises ValueError. Default: False.
+
+    Returns:
+        List[Dict]: Trades in the same shape as :func:`hftbacktest.bybit.fetch_trades`:
+            - exec_id (str): Aggregate trade ID
+            - timestamp (int): Trade timestamp in milliseconds
+            - symbol (str): Trading symbol
+            - side (str): Taker side, "Sell" if the buyer was the maker, otherwise "Buy"
+            - size (float): Trade quantity
+            - price (float): Trade price
+            - is_block_trade (bool): Always False
+
+    Raises:
+        BinanceError: If the API request fails, the rate limit is still exceeded after max
+            retries, or the IP is banned (HTTP 418). Derives from RuntimeError.
+
+    See Also:
+        - Binance Futures aggTrades API:
+          https://developers.binance.com/docs/derivatives/usds-margined-futures/market-data/rest-api/Compressed-Aggregate-Trades-List
+    """
+    if _hftbacktest is None:
+        raise ImportError(
+            "hftbacktest extension module not found. "
+            "Please ensure py-hftbacktest is properly installed."
+        )
+
+    return _hftbacktest.fetch_binance_trades(
+        symbol,
+        start_time,
+        end_time,
+        limit=limit,
+        max_retries=max_retries,
+        base_backoff_ms=base_backoff_ms,
+        max_backoff_ms=max_backoff_ms,
+        base_url=base_url,
+        side_as_int=side_as_int,
+    )
+
+
+__all__ = [
+    "BinanceError",
+    "fetch_binance_trades",
+]
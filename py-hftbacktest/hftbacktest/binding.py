@@ -591,6 +591,8 @@ hashmapbt_submit_buy_order.argtypes = [
     c_double,
     c_uint8,
     c_uint8,
+    c_double,
+    c_double,
     c_bool
 ]
 
@@ -604,6 +606,8 @@ hashmapbt_submit_sell_order.argtypes = [
     c_double,
     c_uint8,
     c_uint8,
+    c_double,
+    c_double,
     c_bool
 ]
 
@@ -747,7 +751,9 @@ class HashMapMarketDepthBacktest:
             qty: float64,
             time_in_force: uint8,
             order_type: uint8,
-            wait: bool
+            wait: bool,
+            trigger_price: float64 = 0.0,
+            display_qty: float64 = 0.0
     ) -> int64:
         """
         Submits a buy order.
@@ -769,15 +775,20 @@ class HashMapMarketDepthBacktest:
 
                 * :const:`LIMIT <hftbacktest.order.LIMIT>`
                 * :const:`MARKET <hftbacktest.order.MARKET>`
+                * :const:`STOP_MARKET <hftbacktest.order.STOP_MARKET>`
+                * :const:`STOP_LIMIT <hftbacktest.order.STOP_LIMIT>`
 
             wait: If `True`, wait until the order placement response is received.
+            trigger_price: Trigger price of a stop order, ignored for other order types.
+            display_qty: Displayed quantity of an iceberg order. `0` displays the full quantity. Supported only
+                         by the L2 exchange models.
 
         Returns:
             * `0` when it successfully submits an order.
             * `1` when it reaches the end of the data, if `wait` is `True`.
             * Otherwise, an error occurred.
         """
-        return hashmapbt_submit_buy_order(self.ptr, asset_no, order_id, price, qty, time_in_force, order_type, wait)
+        return hashmapbt_submit_buy_order(self.ptr, asset_no, order_id, price, qty, time_in_force, order_type, trigger_price, display_qty, wait)
 
     def submit_sell_order(
             self,
@@ -787,7 +798,9 @@ class HashMapMarketDepthBacktest:
             qty: float64,
             time_in_force: uint8,
             order_type: uint8,
-            wait: bool
+            wait: bool,
+            trigger_price: float64 = 0.0,
+            display_qty: float64 = 0.0
     ) -> int64:
         """
         Submits a sell order.
@@ -809,15 +822,20 @@ class HashMapMarketDepthBacktest:
 
                 * :const:`LIMIT <hftbacktest.order.LIMIT>`
                 * :const:`MARKET <hftbacktest.order.MARKET>`
+                * :const:`STOP_MARKET <hftbacktest.order.STOP_MARKET>`
+                * :const:`STOP_LIMIT <hftbacktest.order.STOP_LIMIT>`
 
             wait: If `True`, wait until the order placement response is received.
+            trigger_price: Trigger price of a stop order, ignored for other order types.
+            display_qty: Displayed quantity of an iceberg order. `0` displays the full quantity. Supported only
+                         by the L2 exchange models.
 
         Returns:
             * `0` when it successfully submits an order.
             * `1` when it reaches the end of the data, if `wait` is `True`.
             * Otherwise, an error occurred.
         """
-        return hashmapbt_submit_sell_order(self.ptr, asset_no, order_id, price, qty, time_in_force, order_type, wait)
+        return hashmapbt_submit_sell_order(self.ptr, asset_no, order_id, price, qty, time_in_force, order_type, trigger_price, display_qty, wait)
 
     def modify(self, asset_no: uint64, order_id: uint64, price: float, qty: float, wait: bool) -> int64:
         """
@@ -1038,6 +1056,8 @@ roivecbt_submit_buy_order.argtypes = [
     c_double,
     c_uint8,
     c_uint8,
+    c_double,
+    c_double,
     c_bool
 ]
 
@@ -1051,6 +1071,8 @@ roivecbt_submit_sell_order.argtypes = [
     c_double,
     c_uint8,
     c_uint8,
+    c_double,
+    c_double,
     c_bool
 ]
 
@@ -1190,7 +1212,9 @@ class ROIVectorMarketDepthBacktest:
             qty: float64,
             time_in_force: uint8,
             order_type: uint8,
-            wait: bool
+            wait: bool,
+            trigger_price: float64 = 0.0,
+            display_qty: float64 = 0.0
     ) -> int64:
         """
         Submits a buy order.
@@ -1212,15 +1236,20 @@ class ROIVectorMarketDepthBacktest:
 
                 * :const:`LIMIT <hftbacktest.order.LIMIT>`
                 * :const:`MARKET <hftbacktest.order.MARKET>`
+                * :const:`STOP_MARKET <hftbacktest.order.STOP_MARKET>`
+                * :const:`STOP_LIMIT <hftbacktest.order.STOP_LIMIT>`
 
             wait: If `True`, wait until the order placement response is received.
+            trigger_price: Trigger price of a stop order, ignored for other order types.
+            display_qty: Displayed quantity of an iceberg order. `0` displays the full quantity. Supported only
+                         by the L2 exchange models.
 
         Returns:
             * `0` when it successfully submits an order.
             * `1` when it reaches the end of the data, if `wait` is `True`.
             * Otherwise, an error occurred.
         """
-        return roivecbt_submit_buy_order(self.ptr, asset_no, order_id, price, qty, time_in_force, order_type, wait)
+        return roivecbt_submit_buy_order(self.ptr, asset_no, order_id, price, qty, time_in_force, order_type, trigger_price, display_qty, wait)
 
     def submit_sell_order(
             self,
@@ -1230,7 +1259,9 @@ class ROIVectorMarketDepthBacktest:
             qty: float64,
             time_in_force: uint8,
             order_type: uint8,
-            wait: bool
+            wait: bool,
+            trigger_price: float64 = 0.0,
+            display_qty: float64 = 0.0
     ) -> int64:
         """
         Submits a sell order.
@@ -1252,15 +1283,20 @@ class ROIVectorMarketDepthBacktest:
 
                 * :const:`LIMIT <hftbacktest.order.LIMIT>`
                 * :const:`MARKET <hftbacktest.order.MARKET>`
+                * :const:`STOP_MARKET <hftbacktest.order.STOP_MARKET>`
+                * :const:`STOP_LIMIT <hftbacktest.order.STOP_LIMIT>`
 
             wait: If `True`, wait until the order placement response is received.
+            trigger_price: Trigger price of a stop order, ignored for other order types.
+            display_qty: Displayed quantity of an iceberg order. `0` displays the full quantity. Supported only
+                         by the L2 exchange models.
 
         Returns:
             * `0` when it successfully submits an order.
             * `1` when it reaches the end of the data, if `wait` is `True`.
             * Otherwise, an error occurred.
         """
-        return roivecbt_submit_sell_order(self.ptr, asset_no, order_id, price, qty, time_in_force, order_type, wait)
+        return roivecbt_submit_sell_order(self.ptr, asset_no, order_id, price, qty, time_in_force, order_type, trigger_price, display_qty, wait)
 
     def modify(self, asset_no: uint64, order_id: uint64, price: float, qty: float, wait: bool) -> int64:
         """
@@ -1557,6 +1593,8 @@ if LIVE_FEATURE:
         c_double,
         c_uint8,
         c_uint8,
+        c_double,
+        c_double,
         c_bool
     ]
 
@@ -1570,6 +1608,8 @@ if LIVE_FEATURE:
         c_double,
         c_uint8,
         c_uint8,
+        c_double,
+        c_double,
         c_bool
     ]
 
@@ -1709,7 +1749,9 @@ if LIVE_FEATURE:
                 qty: float64,
                 time_in_force: uint8,
                 order_type: uint8,
-                wait: bool
+                wait: bool,
+                trigger_price: float64 = 0.0,
+                display_qty: float64 = 0.0
         ) -> int64:
             """
             Submits a buy order.
@@ -1733,13 +1775,16 @@ if LIVE_FEATURE:
                     * :const:`MARKET <hftLiveBot.order.MARKET>`
 
                 wait: If `True`, wait until the order placement response is received.
+                trigger_price: Trigger price of a stop order, ignored for other order types.
+                display_qty: Displayed quantity of an iceberg order. `0` displays the full quantity. The live
+                             connectors do not support iceberg orders and reject the order otherwise.
 
             Returns:
                 * `0` when it successfully submits an order.
                 * `1` when it reaches the end of the data, if `wait` is `True`.
                 * Otherwise, an error occurred.
             """
-            return hashmaplive_submit_buy_order(self.ptr, asset_no, order_id, price, qty, time_in_force, order_type, wait)
+            return hashmaplive_submit_buy_order(self.ptr, asset_no, order_id, price, qty, time_in_force, order_type, trigger_price, display_qty, wait)
 
         def submit_sell_order(
                 self,
@@ -1749,7 +1794,9 @@ if LIVE_FEATURE:
                 qty: float64,
                 time_in_force: uint8,
                 order_type: uint8,
-                wait: bool
+                wait: bool,
+                trigger_price: float64 = 0.0,
+                display_qty: float64 = 0.0
         ) -> int64:
             """
             Submits a sell order.
@@ -1773,13 +1820,16 @@ if LIVE_FEATURE:
                     * :const:`MARKET <hftLiveBot.order.MARKET>`
 
                 wait: If `True`, wait until the order placement response is received.
+                trigger_price: Trigger price of a stop order, ignored for other order types.
+                display_qty: Displayed quantity of an iceberg order. `0` displays the full quantity. The live
+                             connectors do not support iceberg orders and reject the order otherwise.
 
             Returns:
                 * `0` when it successfully submits an order.
                 * `1` when it reaches the end of the data, if `wait` is `True`.
                 * Otherwise, an error occurred.
             """
-            return hashmaplive_submit_sell_order(self.ptr, asset_no, order_id, price, qty, time_in_force, order_type, wait)
+            return hashmaplive_submit_sell_order(self.ptr, asset_no, order_id, price, qty, time_in_force, order_type, trigger_price, display_qty, wait)
 
         def modify(self, asset_no: uint64, order_id: uint64, price: float, qty: float, wait: bool) -> int64:
             """
@@ -2000,6 +2050,8 @@ if LIVE_FEATURE:
         c_double,
         c_uint8,
         c_uint8,
+        c_double,
+        c_double,
         c_bool
     ]
 
@@ -2013,6 +2065,8 @@ if LIVE_FEATURE:
         c_double,
         c_uint8,
         c_uint8,
+        c_double,
+        c_double,
         c_bool
     ]
 
@@ -2152,7 +2206,9 @@ if LIVE_FEATURE:
                 qty: float64,
                 time_in_force: uint8,
                 order_type: uint8,
-                wait: bool
+                wait: bool,
+                trigger_price: float64 = 0.0,
+                display_qty: float64 = 0.0
         ) -> int64:
             """
             Submits a buy order.
@@ -2176,13 +2232,16 @@ if LIVE_FEATURE:
                     * :const:`MARKET <hftLiveBot.order.MARKET>`
 
                 wait: If `True`, wait until the order placement response is received.
+                trigger_price: Trigger price of a stop order, ignored for other order types.
+                display_qty: Displayed quantity of an iceberg order. `0` displays the full quantity. The live
+                             connectors do not support iceberg orders and reject the order otherwise.
 
             Returns:
                 * `0` when it successfully submits an order.
                 * `1` when it reaches the end of the data, if `wait` is `True`.
                 * Otherwise, an error occurred.
             """
-            return roiveclive_submit_buy_order(self.ptr, asset_no, order_id, price, qty, time_in_force, order_type, wait)
+            return roiveclive_submit_buy_order(self.ptr, asset_no, order_id, price, qty, time_in_force, order_type, trigger_price, display_qty, wait)
 
         def submit_sell_order(
                 self,
@@ -2192,7 +2251,9 @@ if LIVE_FEATURE:
                 qty: float64,
                 time_in_force: uint8,
                 order_type: uint8,
-                wait: bool
+                wait: bool,
+                trigger_price: float64 = 0.0,
+                display_qty: float64 = 0.0
         ) -> int64:
             """
             Submits a sell order.
@@ -2216,13 +2277,16 @@ if LIVE_FEATURE:
                     * :const:`MARKET <hftLiveBot.order.MARKET>`
 
                 wait: If `True`, wait until the order placement response is received.
+                trigger_price: Trigger price of a stop order, ignored for other order types.
+                display_qty: Displayed quantity of an iceberg order. `0` displays the full quantity. The live
+                             connectors do not support iceberg orders and reject the order otherwise.
 
             Returns:
                 * `0` when it successfully submits an order.
                 * `1` when it reaches the end of the data, if `wait` is `True`.
                 * Otherwise, an error occurred.
             """
-            return roiveclive_submit_sell_order(self.ptr, asset_no, order_id, price, qty, time_in_force, order_type, wait)
+            return roiveclive_submit_sell_order(self.ptr, asset_no, order_id, price, qty, time_in_force, order_type, trigger_price, display_qty, wait)
 
         def modify(self, asset_no: uint64, order_id: uint64, price: float, qty: float, wait: bool) -> int64:
             """
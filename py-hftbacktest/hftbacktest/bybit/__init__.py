@@ -1,12 +1,135 @@
 """Bybit exchange utilities for fetching historical trade data."""
 
-from typing import Dict, List, Optional
+import csv
+import datetime
+import inspect
+import os
+from typing import Callable, Dict, List, Optional, Sequence, Tuple, Union
+
+import numpy as np
+import polars as pl
+
+from ..types import (
+    BUY_EVENT,
+    DEPTH_CLEAR_EVENT,
+    DEPTH_SNAPSHOT_EVENT,
+    EXCH_EVENT,
+    LOCAL_EVENT,
+    SELL_EVENT,
+    TRADE_EVENT,
+    event_dtype,
+)
 
 try:
     from .. import _hftbacktest
 except ImportError:
     _hftbacktest = None
 
+if _hftbacktest is not None:
+    from .._hftbacktest import (
+        BybitApiError,
+        BybitError,
+        BybitFetcher,
+        BybitRateLimitError,
+        BybitUnknownSymbolError,
+        FetchConfig,
+        FetchInterrupted,
+        LatencyInjector,
+    )
+else:
+
+    class BybitError(RuntimeError):
+        """Base class for errors raised while fetching data from Bybit."""
+
+    class BybitRateLimitError(BybitError):
+        """Raised when requests are still rate limited after the maximum number of retries."""
+
+    class BybitApiError(BybitError):
+        """Raised when Bybit responds with a non-zero retCode."""
+
+    class BybitUnknownSymbolError(BybitApiError):
+        """
+        Raised when Bybit rejects the symbol as invalid, such as a typo'd ``"BTCUSD"`` for
+        ``"BTCUSDT"``, as opposed to a valid symbol without data in the range.
+        """
+
+    class FetchInterrupted(BybitError):
+        """
+        Raised when a trade fetch stops on a transient error. ``partial`` holds the trades
+        fetched so far and ``cursor`` the cursor to resume from.
+        """
+
+    class BybitFetcher:
+        """
+        A reusable Bybit client that keeps one connection pool across fetches. Its
+        ``fetch_trades`` and ``fetch_trades_numpy`` methods take the same arguments as the free
+        functions, minus the client settings given to the constructor. Pass ``config`` to take
+        the settings from a :class:`FetchConfig`.
+        """
+
+        def __init__(self, **kwargs):
+            raise ImportError(
+                "hftbacktest extension module not found. "
+                "Please ensure py-hftbacktest is properly installed."
+            )
+
+    class FetchConfig:
+        """
+        Settings of a fetch, built once with keyword arguments and passed as ``config`` to any
+        of the fetch functions or to :class:`BybitFetcher`. Settings such as ``page_delay_ms``,
+        ``time_unit`` or ``cache_dir`` are only set here. With ``cache_dir`` set, the trade
+        fetches that return a list or an array are cached on disk.
+        """
+
+        def __init__(self, **kwargs):
+            raise ImportError(
+                "hftbacktest extension module not found. "
+                "Please ensure py-hftbacktest is properly installed."
+            )
+
+    class LatencyInjector:
+        """
+        Produces local timestamps by adding a feed latency to exchange timestamps. Create one
+        with ``LatencyInjector.constant``, ``LatencyInjector.uniform`` or
+        ``LatencyInjector.empirical``.
+        """
+
+        def __init__(self, *args, **kwargs):
+            raise ImportError(
+                "hftbacktest extension module not found. "
+                "Please ensure py-hftbacktest is properly installed."
+            )
+
+        constant = uniform = empirical = __init__
+
+
+def _local_ts(exch_ts: np.ndarray, feed_latency: Union[int, LatencyInjector]) -> np.ndarray:
+    if isinstance(feed_latency, LatencyInjector):
+        return feed_latency.local_ts(np.ascontiguousarray(exch_ts, np.int64))
+    return exch_ts + feed_latency
+
+
+def _explicit_settings(func: Callable, config: Optional[FetchConfig], settings: Dict) -> Dict:
+    """
+    Returns the settings to pass to the extension along with ``config``: all of them without a
+    config, otherwise only those changed from ``func``'s defaults, which override the config.
+    """
+    if config is None:
+        return settings
+    params = inspect.signature(func).parameters
+    return {name: value for name, value in settings.items() if value != params[name].default}
+
+
+class ValidatedTrades(list):
+    """
+    Trades returned by :func:`fetch_trades` with ``validate=True``. ``report`` holds the
+    :class:`TradeReport` from :func:`validate_trades`.
+    """
+
+    def __init__(self, trades: List[Dict], report):
+        super().__init__(trades)
+        self.report = report
+
 
 def fetch_trades(
     symbol: str,
@@ -14,9 +137,40 @@ def fetch_trades(
     end_time: int,
     *,
     limit: int = 1000,
+    category: str = "linear",
     api_key: str = "",
     secret: str = "",
+    recv_window: int = 5000,
+    max_retries: int = 5,
+    base_backoff_ms: int = 50,
+    max_backoff_ms: int = 10000,
+    page_delay_ms: int = 50,
     base_url: str = "https://api.bybit.com",
+    testnet: bool = False,
+    proxy: Optional[str] = None,
+    compress: bool = True,
+    connect_timeout_ms: Optional[int] = None,
+    timeout_ms: int = 10000,
+    tcp_keepalive_ms: Optional[int] = None,
+    max_idle_connections: Optional[int] = None,
+    user_agent: Optional[str] = None,
+    extra_headers: Optional[Dict[str, str]] = None,
+    cursor: Optional[str] = None,
+    progress: Optional[Callable[[Dict], None]] = None,
+    strict_range: bool = False,
+    strict: bool = False,
+    deterministic: bool = False,
+    partial: bool = False,
+    include_block_trades: bool = True,
+    min_size: Optional[float] = None,
+    side_as_int: bool = False,
+    include_turnover: bool = False,
+    raw_dump_path: Optional[str] = None,
+    cache_dir: Optional[str] = None,
+    force_refresh: bool = False,
+    time_unit: Optional[str] = None,
+    config: Optional[FetchConfig] = None,
+    validate: bool = False,
 ) -> List[Dict]:
     """
     Fetch Bybit trade history between two timestamps.
@@ -25,30 +179,147 @@ def fetch_trades(
     It supports authentication via API key/secret when provided, automatic pagination, and rate-limit
     backoff.
 
+    The window is half-open, ``[start_time, end_time)``: trades at ``end_time`` are left out, so
+    adjacent windows can be chained without duplicating the trades on their shared boundary.
+
     Args:
         symbol (str): Trading symbol in Bybit format (e.g., "BTCUSDT", "ETHUSDT").
-        start_time (int): Start timestamp in milliseconds.
-        end_time (int): End timestamp in milliseconds.
+        start_time (int): Start timestamp in milliseconds, or in ``time_unit`` if given
+            (inclusive).
+        end_time (int): End timestamp in milliseconds, or in ``time_unit`` if given
+            (exclusive).
         limit (int, optional): Number of trades per request (default 1000, max 1000).
             Bybit API limit is 1000 per request.
+        category (str, optional): Bybit product type: "linear", "inverse", "spot", or
+            "option". Default: "linear".
         api_key (str, optional): Bybit API key for authenticated requests.
             If empty string, requests are made to the public endpoint. Default: "".
         secret (str, optional): Bybit API secret for authenticated requests.
             Required if api_key is provided. Default: "".
+        recv_window (int, optional): Validity window of a signed request in milliseconds.
+            Default: 5000.
+        max_retries (int, optional): Maximum number of retries on a 429, a 5xx or a network
+            error. Default: 5.
+        base_backoff_ms (int, optional): Wait before the first retry in milliseconds, doubled
+            on each subsequent retry. Default: 50.
+        max_backoff_ms (int, optional): Upper bound of the wait between retries in
+            milliseconds. Default: 10000.
+        page_delay_ms (int, optional): Delay between page requests in milliseconds when
+            Bybit sends no rate-limit headers to pace them by. 0 removes it, which risks
+            429s on standard accounts. Default: 50.
         base_url (str, optional): Base URL for Bybit API.
             Default: "https://api.bybit.com".
+        testnet (bool, optional): Use the Bybit testnet at "https://api-testnet.bybit.com"
+            unless a custom base_url is given. Default: False.
+        proxy (str, optional): Proxy URL to send requests through, with an http, https,
+            socks5 or socks5h scheme. Default: None.
+        compress (bool, optional): Accept gzip and brotli compressed responses. Disable to
+            fetch raw JSON when debugging. Default: True.
+        connect_timeout_ms (int, optional): Timeout for establishing a connection in
+            milliseconds. Default: None (no timeout).
+        timeout_ms (int, optional): Timeout for each request in milliseconds. Default: 10000.
+        tcp_keepalive_ms (int, optional): Interval of TCP keepalive probes in milliseconds.
+            Default: None (disabled).
+        max_idle_connections (int, optional): Maximum number of idle connections kept open.
+            Default: None (unlimited).
+        user_agent (str, optional): User-Agent header sent with every request, instead of
+            reqwest's default. Default: None.
+        extra_headers (Dict[str, str], optional): Headers added to every request, such as those
+            a gateway identifies clients by. The ``X-BAPI-*`` signing headers can't be set.
+            Default: None.
+        cursor (str, optional): Cursor to resume an interrupted fetch from, taken from
+            ``FetchInterrupted.cursor``. The other arguments must match the interrupted call.
+            Default: None.
+        progress (Callable[[Dict], None], optional): Called once after each page with a dict:
+            - count (int): Number of trades fetched so far
+            - last_timestamp (Optional[int]): Timestamp of the last trade received, or None if
+              no trades yet
+            - cursor (Optional[str]): Cursor of the next page, or None on the last page
+            - elapsed (float): Wall-clock seconds since the fetch started
+            If it raises, the fetch is aborted and the exception is propagated. Default: None.
+        strict_range (bool, optional): Raise instead of warning when start_time is older than
+            the trade endpoint's 7-day retention, beyond which trades are not returned.
+            Default: False.
+        strict (bool, optional): Raise instead of sorting when the pages arrive out of
+            timestamp order, which indicates a data quality issue. Default: False.
+        deterministic (bool, optional): Sort trades with the same timestamp by ``exec_id``
+            instead of in execution order. The result then only depends on the trades
+            themselves, not on how the pages were received, so identical inputs always yield the
+            same order, which keeps backtests reproducible. This relies on ``exec_id``, which
+            every trade is fetched with. Default: False.
+        partial (bool, optional): If a page fails with a transient error or a server-side API
+            error (retCode 10000, 10006, 10016 or 10429) after the first page, return the
+            trades fetched so far instead of raising. A ``UserWarning`` reports the retCode,
+            retMsg and the cursor to resume from. Default: False.
+        include_block_trades (bool, optional): Keep block trades. They are negotiated off the
+            order book, so they distort trade size distributions and should not feed
+            queue-position models; set to False to drop them. Default: True.
+        min_size (float, optional): Drop trades smaller than this size, e.g. to keep only
+            the trades that matter for a liquidity analysis. Pages are still fetched in full;
+            only the returned trades are filtered. Default: None (keep all).
+        side_as_int (bool, optional): Return ``side`` as 1 for "Buy" and -1 for "Sell" instead
+            of the string, which is cheaper to store and aggregate. A trade with any other side
+            raises ValueError. Default: False.
+        include_turnover (bool, optional): Add a ``turnover`` key with ``price * size``, the
+            quote volume needed for VWAP, computed once in Rust. It is a convenience derived
+            from the float price and size, not a field reported by Bybit, and carries a
+            relative rounding error below 4e-16. Default: False.
+        raw_dump_path (str, optional): Append the raw body of every response, together with
+            its request URL and receive time in milliseconds, to this file as one
+            zstd-compressed NDJSON record, before it is parsed. A body that fails to parse is
+            dumped as well, which helps debug schema changes. Read it back with
+            :func:`replay_raw_dump` or ``zstd -dc``. Default: None.
+        cache_dir (str, optional): Directory caching each fetched window as a compressed file
+            keyed by ``(symbol, category, start_time, end_time, limit)``. An identical call is
+            then served from disk without any request, which speeds up re-running a notebook.
+            Only complete fetches are cached, never partial, failed or resumed ones. The key
+            doesn't include the host, so use a separate directory for the testnet.
+            Default: None.
+        force_refresh (bool, optional): Fetch even if the window is cached, and replace the
+            cached entry. Default: False.
+        time_unit (str, optional): Unit of ``start_time``, ``end_time`` and the returned
+            timestamps: "s", "ms", "us" or "ns". Requests are still sent to Bybit in
+            milliseconds. Without it, the timestamps are milliseconds and a ``start_time``
+            before 1973, which is most likely in seconds, raises ValueError instead of fetching
+            an empty list. Default: None.
+        config (FetchConfig, optional): Settings built once with :class:`FetchConfig` and
+            reused across symbols. Arguments left at their default take the config's value
+            instead, and arguments passed with any other value override it. Default: None.
+        validate (bool, optional): Run :func:`validate_trades` on the result with the default
+            gap threshold and return a :class:`ValidatedTrades` list carrying the report as
+            ``report``. Default: False.
 
     Returns:
         List[Dict]: List of dictionaries representing trades. Each dict contains:
-            - timestamp (int): Trade timestamp in milliseconds
+            - exec_id (str): Bybit execution ID, usable as a deduplication key
+            - timestamp (int): Trade timestamp in milliseconds, or in ``time_unit`` if given
             - symbol (str): Trading symbol
-            - side (str): Trade side ("Buy" or "Sell"), represents the taker side
+            - side (str): Trade side ("Buy" or "Sell"), represents the taker side, or 1 and
+              -1 with ``side_as_int``
             - size (float): Trade quantity
             - price (float): Trade price
+            - is_block_trade (bool): Whether the trade is a block trade, which does not
+              hit the public order book
+            - turnover (float): ``price * size``, only present with ``include_turnover``
+            - mark_price, index_price, mark_iv, iv (float): Only present for the "option"
+              category
+            The list is sorted by timestamp in ascending order. Trades with the same timestamp
+            are in execution order, the reverse of the newest-first order Bybit returns them in.
 
     Raises:
-        RuntimeError: If the API request fails, returns non-zero status code,
-            or rate limit is exceeded after max retries.
+        ValueError: If ``time_unit`` is invalid, if it is not given and ``start_time`` looks
+            like seconds.
+        FetchInterrupted: If the fetch stops on a transient error (dropped connection,
+            exhausted retries on 429 or 5xx), unless ``partial`` is set and a page was already
+            fetched. ``partial`` holds the trades fetched so far and ``cursor`` the cursor to
+            pass back to resume.
+        BybitRateLimitError: If rate limit is exceeded after max retries.
+        BybitApiError: If the API returns a non-zero status code.
+        BybitError: If start_time is not before end_time, if strict_range is set and
+            start_time is older than the retention, if strict is set and the trades arrived out
+            of order, if Bybit keeps returning empty pages with a next cursor (see
+            ``FetchConfig.max_empty_pages``), or if the API request fails for any other reason.
+            All of the above derive from BybitError, which derives from RuntimeError.
 
     Examples:
         >>> # Fetch trades for BTCUSDT from 2024-01-01 00:00:00 to 2024-01-01 01:00:00
@@ -63,13 +334,18 @@ def fetch_trades(
 
     Notes:
         - Automatic pagination: The function automatically handles pagination to retrieve
-          all trades within the time range.
+          all trades within the time range. Trades repeated across overlapping pages are
+          deduplicated by execution ID.
         - Rate limiting: If Bybit returns a 429 status code (rate limited), the function
-          automatically backs off with exponential backoff (50ms, 100ms, 200ms, 400ms, 800ms)
-          up to 5 retries before raising an error.
+          automatically backs off with exponential backoff (by default 50ms, 100ms, 200ms,
+          400ms, 800ms) up to ``max_retries`` retries before raising an error. Each wait is
+          capped at ``max_backoff_ms``, and drawn at random between zero and the backoff so
+          that concurrent fetchers don't retry in lockstep.
         - Feed latency: Returned timestamps are from Bybit's server and may need latency
           adjustment for realistic backtesting. Consider adding feed latency if using this
           data for backtesting.
+        - Threading: The GIL is released while trades are downloaded, so the function can be
+          driven from a thread pool alongside other Python work.
         - Public vs. Authenticated: The public endpoint is rate-limited differently than
           authenticated endpoints. Use API key/secret for higher rate limits if needed.
 
@@ -82,15 +358,2831 @@ def fetch_trades(
             "Please ensure py-hftbacktest is properly installed."
         )
 
-    return _hftbacktest.fetch_trades(
+    trades = _hftbacktest.fetch_trades(
         symbol,
         start_time,
         end_time,
-        limit=limit,
-        api_key=api_key,
-        secret=secret,
-        base_url=base_url,
+        cursor=cursor,
+        progress=progress,
+        partial=partial,
+        raw_dump_path=raw_dump_path,
+        config=config,
+        **_explicit_settings(
+            fetch_trades,
+            config,
+            dict(
+                limit=limit,
+                category=category,
+                api_key=api_key,
+                secret=secret,
+                recv_window=recv_window,
+                max_retries=max_retries,
+                base_backoff_ms=base_backoff_ms,
+                max_backoff_ms=max_backoff_ms,
+                page_delay_ms=page_delay_ms,
+                base_url=base_url,
+                testnet=testnet,
+                proxy=proxy,
+                compress=compress,
+                connect_timeout_ms=connect_timeout_ms,
+                timeout_ms=timeout_ms,
+                tcp_keepalive_ms=tcp_keepalive_ms,
+                max_idle_connections=max_idle_connections,
+                user_agent=user_agent,
+                extra_headers=extra_headers,
+                strict_range=strict_range,
+                strict=strict,
+                deterministic=deterministic,
+                include_block_trades=include_block_trades,
+                min_size=min_size,
+                side_as_int=side_as_int,
+                include_turnover=include_turnover,
+                cache_dir=cache_dir,
+                force_refresh=force_refresh,
+                time_unit=time_unit,
+            ),
+        ),
+    )
+    if validate:
+        return ValidatedTrades(trades, _hftbacktest.validate_trades(trades))
+    return trades
+
+
+def count_trades(
+    symbol: str,
+    start_time: int,
+    end_time: int,
+    *,
+    limit: int = 1000,
+    category: str = "linear",
+    api_key: str = "",
+    secret: str = "",
+    recv_window: int = 5000,
+    max_retries: int = 5,
+    base_backoff_ms: int = 50,
+    max_backoff_ms: int = 10000,
+    page_delay_ms: int = 50,
+    base_url: str = "https://api.bybit.com",
+    testnet: bool = False,
+    proxy: Optional[str] = None,
+    compress: bool = True,
+    connect_timeout_ms: Optional[int] = None,
+    timeout_ms: int = 10000,
+    tcp_keepalive_ms: Optional[int] = None,
+    max_idle_connections: Optional[int] = None,
+    user_agent: Optional[str] = None,
+    extra_headers: Optional[Dict[str, str]] = None,
+    cursor: Optional[str] = None,
+    progress: Optional[Callable[[Dict], None]] = None,
+    strict_range: bool = False,
+    partial: bool = False,
+    include_block_trades: bool = True,
+    min_size: Optional[float] = None,
+    time_unit: Optional[str] = None,
+    config: Optional[FetchConfig] = None,
+) -> int:
+    """
+    Count the Bybit trades between two timestamps without keeping them.
+
+    Paginates through the window as :func:`fetch_trades` does, with the same retries, backoff
+    and trade filters, but drops each page once counted, so memory stays flat however large the
+    window is. The count matches the length of the list :func:`fetch_trades` returns for the
+    same arguments, which sizes a large download before running it. The cache is bypassed.
+
+    Args:
+        symbol (str): Trading symbol in Bybit format (e.g., "BTCUSDT").
+        start_time (int): Start timestamp in milliseconds, or in ``time_unit`` if given
+            (inclusive).
+        end_time (int): End timestamp in milliseconds, or in ``time_unit`` if given
+            (exclusive).
+        cursor (str, optional): Cursor to resume an interrupted count from. Default: None.
+        progress (Callable[[Dict], None], optional): Called once after each page with the same
+            dict as in :func:`fetch_trades`. Default: None.
+        partial (bool, optional): If a page fails with a transient error or a server-side API
+            error after the first page, return the count so far with a ``UserWarning``
+            carrying the error and the cursor to resume from, instead of raising.
+            Default: False.
+        include_block_trades (bool, optional): Count block trades. Default: True.
+        min_size (float, optional): Leave out trades smaller than this size. Default: None.
+        time_unit (str, optional): Unit of ``start_time`` and ``end_time``: "s", "ms", "us"
+            or "ns". Default: None (milliseconds).
+        config (FetchConfig, optional): Settings built once with :class:`FetchConfig` and
+            reused across symbols. Arguments left at their default take the config's value
+            instead, and arguments passed with any other value override it. Default: None.
+
+        The remaining arguments are the same as in :func:`fetch_trades`.
+
+    Returns:
+        int: The number of trades in the window.
+
+    Raises:
+        ValueError: If ``time_unit`` is invalid, or if it is not given and ``start_time``
+            looks like seconds.
+        BybitError: If start_time is not before end_time, if strict_range is set and
+            start_time is older than the retention, or if the API request fails. A transient
+            error raises the underlying error rather than :class:`FetchInterrupted`.
+
+    Examples:
+        >>> from hftbacktest.bybit import count_trades, last_fetch_stats
+        >>> count = count_trades("BTCUSDT", start, end)
+        >>> stats = last_fetch_stats()  # requests and time the count took
+    """
+    if _hftbacktest is None:
+        raise ImportError(
+            "hftbacktest extension module not found. "
+            "Please ensure py-hftbacktest is properly installed."
+        )
+
+    return _hftbacktest.count_trades(
+        symbol,
+        start_time,
+        end_time,
+        cursor=cursor,
+        progress=progress,
+        partial=partial,
+        config=config,
+        **_explicit_settings(
+            count_trades,
+            config,
+            dict(
+                limit=limit,
+                category=category,
+                api_key=api_key,
+                secret=secret,
+                recv_window=recv_window,
+                max_retries=max_retries,
+                base_backoff_ms=base_backoff_ms,
+                max_backoff_ms=max_backoff_ms,
+                page_delay_ms=page_delay_ms,
+                base_url=base_url,
+                testnet=testnet,
+                proxy=proxy,
+                compress=compress,
+                connect_timeout_ms=connect_timeout_ms,
+                timeout_ms=timeout_ms,
+                tcp_keepalive_ms=tcp_keepalive_ms,
+                max_idle_connections=max_idle_connections,
+                user_agent=user_agent,
+                extra_headers=extra_headers,
+                strict_range=strict_range,
+                include_block_trades=include_block_trades,
+                min_size=min_size,
+                time_unit=time_unit,
+            ),
+        ),
+    )
+
+
+def fetch_trades_page(
+    symbol: str,
+    start_time: int,
+    end_time: int,
+    cursor: Optional[str] = None,
+    *,
+    limit: int = 1000,
+    category: str = "linear",
+    api_key: str = "",
+    secret: str = "",
+    recv_window: int = 5000,
+    max_retries: int = 5,
+    base_backoff_ms: int = 50,
+    max_backoff_ms: int = 10000,
+    base_url: str = "https://api.bybit.com",
+    testnet: bool = False,
+    proxy: Optional[str] = None,
+    compress: bool = True,
+    connect_timeout_ms: Optional[int] = None,
+    timeout_ms: int = 10000,
+    include_block_trades: bool = True,
+    min_size: Optional[float] = None,
+    side_as_int: bool = False,
+    include_turnover: bool = False,
+    time_unit: Optional[str] = None,
+    config: Optional[FetchConfig] = None,
+) -> Tuple[List[Dict], Optional[str]]:
+    """
+    Fetch a single page of Bybit trade history with exactly one request.
+
+    This lets Python drive the pagination of :func:`fetch_trades`: pass the returned cursor back
+    with the same ``symbol``, ``start_time`` and ``end_time`` to fetch the next page, and persist
+    it between runs to resume a long pull where it stopped. Failed requests are still retried
+    with backoff as in :func:`fetch_trades`.
+
+    Args:
+        symbol (str): Trading symbol in Bybit format (e.g., "BTCUSDT", "ETHUSDT").
+        start_time (int): Start timestamp in milliseconds, or in ``time_unit`` if given
+            (inclusive).
+        end_time (int): End timestamp in milliseconds, or in ``time_unit`` if given
+            (exclusive).
+        cursor (str, optional): Cursor of the page to fetch, as returned by the previous call,
+            or None for the first page of the window. Default: None.
+        time_unit (str, optional): Unit of ``start_time``, ``end_time`` and the returned
+            timestamps, as in :func:`fetch_trades`. Default: None.
+        config (FetchConfig, optional): Settings built once with :class:`FetchConfig`, as in
+            :func:`fetch_trades`. Default: None.
+
+        The remaining arguments are the same as in :func:`fetch_trades`.
+
+    Returns:
+        Tuple[List[Dict], Optional[str]]: The trades of the page, in the same format as
+            :func:`fetch_trades` but in the newest-first order Bybit returns them in, and the
+            cursor of the next page, or None if this was the last page.
+
+    Raises:
+        BybitError: If start_time is not before end_time, or if the API request fails.
+
+    Examples:
+        >>> trades, cursor = fetch_trades_page("BTCUSDT", start, end)
+        >>> while cursor is not None:
+        ...     page, cursor = fetch_trades_page("BTCUSDT", start, end, cursor)
+        ...     trades.extend(page)
+        ...     save_checkpoint(cursor)
+
+    Notes:
+        - Nothing is kept between calls, so unlike :func:`fetch_trades` the pages are neither
+          sorted nor deduplicated. A trade repeated at the boundary of two pages appears twice;
+          dedupe by ``exec_id`` once all pages are in.
+    """
+    if _hftbacktest is None:
+        raise ImportError(
+            "hftbacktest extension module not found. "
+            "Please ensure py-hftbacktest is properly installed."
+        )
+
+    return _hftbacktest.fetch_trades_page(
+        symbol,
+        start_time,
+        end_time,
+        cursor,
+        config=config,
+        **_explicit_settings(
+            fetch_trades_page,
+            config,
+            dict(
+                limit=limit,
+                category=category,
+                api_key=api_key,
+                secret=secret,
+                recv_window=recv_window,
+                max_retries=max_retries,
+                base_backoff_ms=base_backoff_ms,
+                max_backoff_ms=max_backoff_ms,
+                base_url=base_url,
+                testnet=testnet,
+                proxy=proxy,
+                compress=compress,
+                connect_timeout_ms=connect_timeout_ms,
+                timeout_ms=timeout_ms,
+                include_block_trades=include_block_trades,
+                min_size=min_size,
+                side_as_int=side_as_int,
+                include_turnover=include_turnover,
+                time_unit=time_unit,
+            ),
+        ),
+    )
+
+
+def replay_raw_dump(path: str, *, side_as_int: bool = False) -> List[Dict]:
+    """
+    Re-parse the trade pages of a raw dump written by :func:`fetch_trades`, offline.
+
+    The pages go through the same parser and deduplication as a live fetch, so a dump can be
+    used to audit a fetch after the fact or to reproduce a response that failed to parse.
+
+    Args:
+        path (str): Dump file written with ``raw_dump_path``.
+        side_as_int (bool, optional): Return ``side`` as 1 for "Buy" and -1 for "Sell" instead
+            of the string. Default: False.
+
+    Returns:
+        List[Dict]: Trades in the same format and order as :func:`fetch_trades`. Responses of
+            other endpoints in the dump are skipped.
+
+    Raises:
+        BybitError: If the file cannot be read or a trade page fails to parse.
+    """
+    if _hftbacktest is None:
+        raise ImportError(
+            "hftbacktest extension module not found. "
+            "Please ensure py-hftbacktest is properly installed."
+        )
+
+    return _hftbacktest.replay_raw_dump(path, side_as_int=side_as_int)
+
+
+async def fetch_trades_async(
+    symbol: str,
+    start_time: int,
+    end_time: int,
+    *,
+    limit: int = 1000,
+    category: str = "linear",
+    api_key: str = "",
+    secret: str = "",
+    recv_window: int = 5000,
+    max_retries: int = 5,
+    base_backoff_ms: int = 50,
+    max_backoff_ms: int = 10000,
+    page_delay_ms: int = 50,
+    base_url: str = "https://api.bybit.com",
+    testnet: bool = False,
+    proxy: Optional[str] = None,
+    compress: bool = True,
+    connect_timeout_ms: Optional[int] = None,
+    timeout_ms: int = 10000,
+    tcp_keepalive_ms: Optional[int] = None,
+    max_idle_connections: Optional[int] = None,
+    user_agent: Optional[str] = None,
+    extra_headers: Optional[Dict[str, str]] = None,
+    strict_range: bool = False,
+    strict: bool = False,
+    side_as_int: bool = False,
+    include_turnover: bool = False,
+    config: Optional[FetchConfig] = None,
+) -> List[Dict]:
+    """
+    Fetch Bybit trade history between two timestamps without blocking the event loop.
+
+    This is the asyncio counterpart of :func:`fetch_trades`: the download runs on the extension's
+    background runtime, so other tasks keep running while it is awaited, and several fetches can
+    be awaited concurrently, e.g. with ``asyncio.gather``. It takes the same arguments except
+    ``cursor``, ``progress``, ``partial``, ``include_block_trades``, ``min_size``,
+    ``raw_dump_path`` and ``validate``, and returns the same list of dicts.
+
+    Cancelling the awaiting task, e.g. with ``Task.cancel()`` or ``asyncio.wait_for``, cancels
+    the fetch itself: the request in flight is aborted, no further page is requested, and the
+    trades fetched so far are discarded. ``CancelledError`` propagates as usual.
+
+    Raises:
+        BybitRateLimitError: If rate limit is exceeded after max retries.
+        BybitApiError: If the API returns a non-zero status code.
+        BybitError: If start_time is not before end_time, if strict_range is set and
+            start_time is older than the retention, if strict is set and the trades arrived out
+            of order, or if the API request fails for any other reason.
+
+    Examples:
+        >>> import asyncio
+        >>> from hftbacktest.bybit import fetch_trades_async
+        >>>
+        >>> async def main():
+        ...     return await asyncio.gather(
+        ...         fetch_trades_async("BTCUSDT", start, end),
+        ...         fetch_trades_async("ETHUSDT", start, end),
+        ...     )
+        >>> btc, eth = asyncio.run(main())
+    """
+    if _hftbacktest is None:
+        raise ImportError(
+            "hftbacktest extension module not found. "
+            "Please ensure py-hftbacktest is properly installed."
+        )
+
+    return await _hftbacktest.fetch_trades_async(
+        symbol,
+        start_time,
+        end_time,
+        config=config,
+        **_explicit_settings(
+            fetch_trades_async,
+            config,
+            dict(
+                limit=limit,
+                category=category,
+                api_key=api_key,
+                secret=secret,
+                recv_window=recv_window,
+                max_retries=max_retries,
+                base_backoff_ms=base_backoff_ms,
+                max_backoff_ms=max_backoff_ms,
+                page_delay_ms=page_delay_ms,
+                base_url=base_url,
+                testnet=testnet,
+                proxy=proxy,
+                compress=compress,
+                connect_timeout_ms=connect_timeout_ms,
+                timeout_ms=timeout_ms,
+                tcp_keepalive_ms=tcp_keepalive_ms,
+                max_idle_connections=max_idle_connections,
+                user_agent=user_agent,
+                extra_headers=extra_headers,
+                strict_range=strict_range,
+                strict=strict,
+                side_as_int=side_as_int,
+                include_turnover=include_turnover,
+            ),
+        ),
+    )
+
+
+def fetch_trades_numpy(
+    symbol: str,
+    start_time: int,
+    end_time: int,
+    *,
+    limit: int = 1000,
+    category: str = "linear",
+    api_key: str = "",
+    secret: str = "",
+    recv_window: int = 5000,
+    max_retries: int = 5,
+    base_backoff_ms: int = 50,
+    max_backoff_ms: int = 10000,
+    page_delay_ms: int = 50,
+    base_url: str = "https://api.bybit.com",
+    testnet: bool = False,
+    proxy: Optional[str] = None,
+    compress: bool = True,
+    cursor: Optional[str] = None,
+    progress: Optional[Callable[[Dict], None]] = None,
+    strict_range: bool = False,
+    strict: bool = False,
+    partial: bool = False,
+    include_block_trades: bool = True,
+    min_size: Optional[float] = None,
+    cache_dir: Optional[str] = None,
+    force_refresh: bool = False,
+    time_unit: Optional[str] = None,
+    config: Optional[FetchConfig] = None,
+) -> np.ndarray:
+    """
+    Fetch Bybit trade history between two timestamps as a NumPy structured array.
+
+    This behaves like :func:`fetch_trades`, but the array is filled directly from Rust without
+    creating a Python object per trade, which keeps memory and time overhead low for high-volume
+    symbols. The symbol is constant per call, so it is not included in the array.
+
+    Args:
+        symbol (str): Trading symbol in Bybit format (e.g., "BTCUSDT", "ETHUSDT").
+        start_time (int): Start timestamp in milliseconds, or in ``time_unit`` if given
+            (inclusive).
+        end_time (int): End timestamp in milliseconds, or in ``time_unit`` if given
+            (exclusive).
+        limit (int, optional): Number of trades per request (default 1000, max 1000).
+        category (str, optional): Bybit product type: "linear", "inverse", "spot", or
+            "option". Default: "linear".
+        api_key (str, optional): Bybit API key for authenticated requests. Default: "".
+        secret (str, optional): Bybit API secret for authenticated requests. Default: "".
+        recv_window (int, optional): Validity window of a signed request in milliseconds.
+            Default: 5000.
+        max_retries (int, optional): Maximum number of retries on a 429, a 5xx or a network
+            error. Default: 5.
+        base_backoff_ms (int, optional): Wait before the first retry in milliseconds, doubled
+            on each subsequent retry. Default: 50.
+        max_backoff_ms (int, optional): Upper bound of the wait between retries in
+            milliseconds. Default: 10000.
+        page_delay_ms (int, optional): Delay between page requests in milliseconds when
+            Bybit sends no rate-limit headers to pace them by. 0 removes it, which risks
+            429s on standard accounts. Default: 50.
+        base_url (str, optional): Base URL for Bybit API.
+            Default: "https://api.bybit.com".
+        testnet (bool, optional): Use the Bybit testnet at "https://api-testnet.bybit.com"
+            unless a custom base_url is given. Default: False.
+        proxy (str, optional): Proxy URL to send requests through, with an http, https,
+            socks5 or socks5h scheme. Default: None.
+        compress (bool, optional): Accept gzip and brotli compressed responses. Disable to
+            fetch raw JSON when debugging. Default: True.
+        cursor (str, optional): Cursor to resume an interrupted fetch from, taken from
+            ``FetchInterrupted.cursor``. The other arguments must match the interrupted call.
+            Default: None.
+        progress (Callable[[Dict], None], optional): Called once after each page with a dict:
+            - count (int): Number of trades fetched so far
+            - last_timestamp (Optional[int]): Timestamp of the last trade received, or None if
+              no trades yet
+            - cursor (Optional[str]): Cursor of the next page, or None on the last page
+            - elapsed (float): Wall-clock seconds since the fetch started
+            If it raises, the fetch is aborted and the exception is propagated. Default: None.
+        strict_range (bool, optional): Raise instead of warning when start_time is older than
+            the trade endpoint's 7-day retention, beyond which trades are not returned.
+            Default: False.
+        strict (bool, optional): Raise instead of sorting when the pages arrive out of
+            timestamp order, which indicates a data quality issue. Default: False.
+        partial (bool, optional): If a page fails with a transient error or a server-side API
+            error (retCode 10000, 10006, 10016 or 10429) after the first page, return the
+            trades fetched so far instead of raising. A ``UserWarning`` reports the retCode,
+            retMsg and the cursor to resume from. Default: False.
+        include_block_trades (bool, optional): Keep block trades. Set to False to drop them.
+            Default: True.
+        min_size (float, optional): Drop trades smaller than this size, e.g. to keep only
+            the trades that matter for a liquidity analysis. Pages are still fetched in full;
+            only the returned trades are filtered. Default: None (keep all).
+        cache_dir (str, optional): Directory caching each fetched window, shared with
+            :func:`fetch_trades`. Default: None.
+        force_refresh (bool, optional): Fetch even if the window is cached, and replace the
+            cached entry. Default: False.
+        time_unit (str, optional): Unit of ``start_time``, ``end_time`` and the returned
+            timestamps: "s", "ms", "us" or "ns". Requests are still sent to Bybit in
+            milliseconds. Without it, the timestamps are milliseconds and a ``start_time``
+            before 1973, which is most likely in seconds, raises ValueError instead of fetching
+            an empty list. Default: None.
+        config (FetchConfig, optional): Settings built once with :class:`FetchConfig` and
+            reused across symbols. Arguments left at their default take the config's value
+            instead, and arguments passed with any other value override it. Default: None.
+
+    Returns:
+        np.ndarray: Structured array with dtype
+            ``[('timestamp', 'i8'), ('price', 'f8'), ('size', 'f8'), ('side', 'u1'),
+            ('is_block_trade', 'u1')]``, sorted by timestamp in ascending order. ``timestamp``
+            is in milliseconds, or in ``time_unit`` if given. ``side`` is the
+            taker side encoded as ``1`` for "Buy" and ``0`` for "Sell", and
+            ``is_block_trade`` is 1 for a block trade and 0 otherwise.
+
+    Raises:
+        ValueError: If ``time_unit`` is invalid, or if it is not given and ``start_time`` looks
+            like seconds.
+        FetchInterrupted: If the fetch stops on a transient error, unless ``partial`` is set
+            and a page was already fetched. ``partial`` holds the trades fetched so far as dicts
+            and ``cursor`` the cursor to pass back to resume.
+        BybitError: If start_time is not before end_time, if strict_range is set and
+            start_time is older than the retention, or if strict is set and the trades arrived
+            out of order.
+        RuntimeError: If the API request fails, returns non-zero status code,
+            or rate limit is exceeded after max retries.
+    """
+    if _hftbacktest is None:
+        raise ImportError(
+            "hftbacktest extension module not found. "
+            "Please ensure py-hftbacktest is properly installed."
+        )
+
+    return _hftbacktest.fetch_trades_numpy(
+        symbol,
+        start_time,
+        end_time,
+        cursor=cursor,
+        progress=progress,
+        partial=partial,
+        config=config,
+        **_explicit_settings(
+            fetch_trades_numpy,
+            config,
+            dict(
+                limit=limit,
+                category=category,
+                api_key=api_key,
+                secret=secret,
+                recv_window=recv_window,
+                max_retries=max_retries,
+                base_backoff_ms=base_backoff_ms,
+                max_backoff_ms=max_backoff_ms,
+                page_delay_ms=page_delay_ms,
+                base_url=base_url,
+                testnet=testnet,
+                proxy=proxy,
+                compress=compress,
+                strict_range=strict_range,
+                strict=strict,
+                include_block_trades=include_block_trades,
+                min_size=min_size,
+                cache_dir=cache_dir,
+                force_refresh=force_refresh,
+                time_unit=time_unit,
+            ),
+        ),
+    )
+
+
+def fetch_trades_polars(
+    symbol: str,
+    start_time: int,
+    end_time: int,
+    *,
+    limit: int = 1000,
+    category: str = "linear",
+    api_key: str = "",
+    secret: str = "",
+    recv_window: int = 5000,
+    max_retries: int = 5,
+    base_backoff_ms: int = 50,
+    max_backoff_ms: int = 10000,
+    page_delay_ms: int = 50,
+    base_url: str = "https://api.bybit.com",
+    testnet: bool = False,
+    proxy: Optional[str] = None,
+    compress: bool = True,
+    cursor: Optional[str] = None,
+    progress: Optional[Callable[[Dict], None]] = None,
+    strict_range: bool = False,
+    strict: bool = False,
+    partial: bool = False,
+    include_block_trades: bool = True,
+    min_size: Optional[float] = None,
+    cache_dir: Optional[str] = None,
+    force_refresh: bool = False,
+    time_unit: Optional[str] = None,
+    config: Optional[FetchConfig] = None,
+) -> pl.DataFrame:
+    """
+    Fetch Bybit trade history between two timestamps as a polars DataFrame.
+
+    It takes the same arguments as :func:`fetch_trades_numpy`. The columns are built in Rust and
+    handed to polars as a whole frame, so no Python object is created per trade, which is much
+    faster than building a DataFrame from the list of dicts of :func:`fetch_trades`.
+
+    Returns:
+        pl.DataFrame: The trades sorted by timestamp in ascending order, with the schema
+
+            - timestamp (Int64): In milliseconds, or in ``time_unit`` if given
+            - symbol (String): The requested symbol
+            - side (String): Taker side, "Buy" or "Sell"
+            - size (Float64)
+            - price (Float64)
+            - is_block_trade (Boolean): True for a block trade
+
+        The schema is the same when no trade is returned.
+
+    Raises:
+        Same as :func:`fetch_trades_numpy`.
+
+    Examples:
+        >>> import polars as pl
+        >>> from hftbacktest.bybit import fetch_trades_polars
+        >>>
+        >>> df = fetch_trades_polars("BTCUSDT", start, end)
+        >>> df.group_by("side").agg(pl.col("size").sum())
+    """
+    if _hftbacktest is None:
+        raise ImportError(
+            "hftbacktest extension module not found. "
+            "Please ensure py-hftbacktest is properly installed."
+        )
+
+    return _hftbacktest.fetch_trades_polars(
+        symbol,
+        start_time,
+        end_time,
+        cursor=cursor,
+        progress=progress,
+        partial=partial,
+        config=config,
+        **_explicit_settings(
+            fetch_trades_polars,
+            config,
+            dict(
+                limit=limit,
+                category=category,
+                api_key=api_key,
+                secret=secret,
+                recv_window=recv_window,
+                max_retries=max_retries,
+                base_backoff_ms=base_backoff_ms,
+                max_backoff_ms=max_backoff_ms,
+                page_delay_ms=page_delay_ms,
+                base_url=base_url,
+                testnet=testnet,
+                proxy=proxy,
+                compress=compress,
+                strict_range=strict_range,
+                strict=strict,
+                include_block_trades=include_block_trades,
+                min_size=min_size,
+                cache_dir=cache_dir,
+                force_refresh=force_refresh,
+                time_unit=time_unit,
+            ),
+        ),
+    )
+
+
+def fetch_trades_chunked(
+    symbol: str,
+    start_time: int,
+    end_time: int,
+    chunk_ms: int,
+    callback: Callable[[List[Dict]], None],
+    *,
+    limit: int = 1000,
+    category: str = "linear",
+    api_key: str = "",
+    secret: str = "",
+    recv_window: int = 5000,
+    max_retries: int = 5,
+    base_backoff_ms: int = 50,
+    max_backoff_ms: int = 10000,
+    page_delay_ms: int = 50,
+    base_url: str = "https://api.bybit.com",
+    testnet: bool = False,
+    proxy: Optional[str] = None,
+    compress: bool = True,
+    side_as_int: bool = False,
+    time_unit: Optional[str] = None,
+    config: Optional[FetchConfig] = None,
+) -> int:
+    """
+    Fetch Bybit trade history in fixed-size time windows, passing each window to a callback.
+
+    ``[start_time, end_time)`` is split into windows of ``chunk_ms`` milliseconds. Each window is
+    fetched with the same pagination as :func:`fetch_trades` and handed to ``callback`` before the
+    next one is fetched, so only one window is held in memory at a time.
+
+    Args:
+        symbol (str): Trading symbol in Bybit format (e.g., "BTCUSDT", "ETHUSDT").
+        start_time (int): Start timestamp in milliseconds, or in ``time_unit`` if given (inclusive).
+        end_time (int): End timestamp in milliseconds, or in ``time_unit`` if given (exclusive).
+        chunk_ms (int): Window length in milliseconds. The last window may be shorter.
+        callback (Callable[[List[Dict]], None]): Called with the trades of each window, in the
+            same dict format and order as :func:`fetch_trades`. Called for empty windows as well.
+        limit (int, optional): Number of trades per request (default 1000, max 1000).
+        category (str, optional): Bybit product type: "linear", "inverse", "spot", or
+            "option". Default: "linear".
+        api_key (str, optional): Bybit API key for authenticated requests. Default: "".
+        secret (str, optional): Bybit API secret for authenticated requests. Default: "".
+        recv_window (int, optional): Validity window of a signed request in milliseconds.
+            Default: 5000.
+        max_retries (int, optional): Maximum number of retries on a 429, a 5xx or a network
+            error. Default: 5.
+        base_backoff_ms (int, optional): Wait before the first retry in milliseconds, doubled
+            on each subsequent retry. Default: 50.
+        max_backoff_ms (int, optional): Upper bound of the wait between retries in
+            milliseconds. Default: 10000.
+        page_delay_ms (int, optional): Delay between page requests in milliseconds when
+            Bybit sends no rate-limit headers to pace them by. 0 removes it, which risks
+            429s on standard accounts. Default: 50.
+        base_url (str, optional): Base URL for Bybit API.
+            Default: "https://api.bybit.com".
+        testnet (bool, optional): Use the Bybit testnet at "https://api-testnet.bybit.com"
+            unless a custom base_url is given. Default: False.
+        proxy (str, optional): Proxy URL to send requests through, with an http, https,
+            socks5 or socks5h scheme. Default: None.
+        compress (bool, optional): Accept gzip and brotli compressed responses. Disable to
+            fetch raw JSON when debugging. Default: True.
+        side_as_int (bool, optional): Return ``side`` as 1 for "Buy" and -1 for "Sell" instead
+            of the string, which is cheaper to store and aggregate. A trade with any other side
+            raises ValueError. Default: False.
+        time_unit (str, optional): Unit of ``start_time``, ``end_time`` and the returned
+            timestamps: "s", "ms", "us" or "ns". Requests are still sent to Bybit in
+            milliseconds. Without it, the timestamps are milliseconds and a ``start_time``
+            before 1973, which is most likely in seconds, raises ValueError instead of fetching
+            an empty list. Default: None.
+        config (FetchConfig, optional): Settings built once with :class:`FetchConfig` and reused
+            across symbols. Arguments left at their default take the config's value instead, and
+            arguments passed with any other value override it. Default: None.
+
+    Returns:
+        int: Total number of trades delivered to ``callback``.
+
+    Raises:
+        ValueError: If ``time_unit`` is invalid, or if it is not given and ``start_time`` looks
+            like seconds.
+        RuntimeError: If the API request fails, returns non-zero status code,
+            or rate limit is exceeded after max retries.
+        Exception: Any exception raised by ``callback`` stops the fetch and is re-raised.
+
+    Notes:
+        - Boundaries: Windows are half-open, so a trade exactly on a boundary millisecond is
+          delivered in exactly one window.
+    """
+    if _hftbacktest is None:
+        raise ImportError(
+            "hftbacktest extension module not found. "
+            "Please ensure py-hftbacktest is properly installed."
+        )
+
+    return _hftbacktest.fetch_trades_chunked(
+        symbol,
+        start_time,
+        end_time,
+        chunk_ms,
+        callback,
+        config=config,
+        **_explicit_settings(
+            fetch_trades_chunked,
+            config,
+            dict(
+                limit=limit,
+                category=category,
+                api_key=api_key,
+                secret=secret,
+                recv_window=recv_window,
+                max_retries=max_retries,
+                base_backoff_ms=base_backoff_ms,
+                max_backoff_ms=max_backoff_ms,
+                page_delay_ms=page_delay_ms,
+                base_url=base_url,
+                testnet=testnet,
+                proxy=proxy,
+                compress=compress,
+                side_as_int=side_as_int,
+                time_unit=time_unit,
+            ),
+        ),
     )
 
 
-__all__ = ["fetch_trades"]
+def fetch_trades_concurrent(
+    symbol: str,
+    start_time: int,
+    end_time: int,
+    *,
+    num_workers: int = 4,
+    limit: int = 1000,
+    category: str = "linear",
+    api_key: str = "",
+    secret: str = "",
+    recv_window: int = 5000,
+    max_retries: int = 5,
+    base_backoff_ms: int = 50,
+    max_backoff_ms: int = 10000,
+    page_delay_ms: int = 50,
+    base_url: str = "https://api.bybit.com",
+    testnet: bool = False,
+    proxy: Optional[str] = None,
+    compress: bool = True,
+    side_as_int: bool = False,
+    deterministic: bool = False,
+    time_unit: Optional[str] = None,
+    config: Optional[FetchConfig] = None,
+) -> List[Dict]:
+    """
+    Fetch Bybit trade history between two timestamps by fetching sub-windows concurrently.
+
+    The range is split into ``num_workers`` equal sub-windows, each paginated like
+    :func:`fetch_trades` at the same time. This is much faster than a single cursor walk when the
+    range spans days. The merged trades are deduplicated by ``exec_id`` and sorted by timestamp.
+
+    Args:
+        symbol (str): Trading symbol in Bybit format (e.g., "BTCUSDT", "ETHUSDT").
+        start_time (int): Start timestamp in milliseconds, or in ``time_unit`` if given.
+        end_time (int): End timestamp in milliseconds, or in ``time_unit`` if given.
+        num_workers (int, optional): Number of sub-windows fetched concurrently. Clamped to
+            1..8 to stay clear of Bybit's per-IP rate limit. Default: 4.
+        limit (int, optional): Number of trades per request (default 1000, max 1000).
+        category (str, optional): Bybit product type: "linear", "inverse", "spot", or
+            "option". Default: "linear".
+        api_key (str, optional): Bybit API key for authenticated requests. Default: "".
+        secret (str, optional): Bybit API secret for authenticated requests. Default: "".
+        recv_window (int, optional): Validity window of a signed request in milliseconds.
+            Default: 5000.
+        max_retries (int, optional): Maximum number of retries on a 429, a 5xx or a network
+            error. Default: 5.
+        base_backoff_ms (int, optional): Wait before the first retry in milliseconds, doubled
+            on each subsequent retry. Default: 50.
+        max_backoff_ms (int, optional): Upper bound of the wait between retries in
+            milliseconds. Default: 10000.
+        page_delay_ms (int, optional): Delay between page requests in milliseconds when
+            Bybit sends no rate-limit headers to pace them by. 0 removes it, which risks
+            429s on standard accounts. Default: 50.
+        base_url (str, optional): Base URL for Bybit API.
+            Default: "https://api.bybit.com".
+        testnet (bool, optional): Use the Bybit testnet at "https://api-testnet.bybit.com"
+            unless a custom base_url is given. Default: False.
+        proxy (str, optional): Proxy URL to send requests through, with an http, https,
+            socks5 or socks5h scheme. Default: None.
+        compress (bool, optional): Accept gzip and brotli compressed responses. Disable to
+            fetch raw JSON when debugging. Default: True.
+        side_as_int (bool, optional): Return ``side`` as 1 for "Buy" and -1 for "Sell" instead
+            of the string, which is cheaper to store and aggregate. A trade with any other side
+            raises ValueError. Default: False.
+        deterministic (bool, optional): Sort trades with the same timestamp by ``exec_id``, as
+            in :func:`fetch_trades`. Default: False.
+        time_unit (str, optional): Unit of ``start_time``, ``end_time`` and the returned
+            timestamps: "s", "ms", "us" or "ns". Requests are still sent to Bybit in
+            milliseconds. Without it, the timestamps are milliseconds and a ``start_time``
+            before 1973, which is most likely in seconds, raises ValueError instead of fetching
+            an empty list. Default: None.
+        config (FetchConfig, optional): Settings built once with :class:`FetchConfig` and reused
+            across symbols. Arguments left at their default take the config's value instead, and
+            arguments passed with any other value override it. Default: None.
+
+    Returns:
+        List[Dict]: Trades in the same format as :func:`fetch_trades`, sorted by timestamp in
+        ascending order.
+
+    Raises:
+        ValueError: If ``time_unit`` is invalid, or if it is not given and ``start_time`` looks
+            like seconds.
+        RuntimeError: If any sub-window fails, returns non-zero status code, or rate limit is
+            exceeded after max retries.
+    """
+    if _hftbacktest is None:
+        raise ImportError(
+            "hftbacktest extension module not found. "
+            "Please ensure py-hftbacktest is properly installed."
+        )
+
+    return _hftbacktest.fetch_trades_concurrent(
+        symbol,
+        start_time,
+        end_time,
+        num_workers=num_workers,
+        config=config,
+        **_explicit_settings(
+            fetch_trades_concurrent,
+            config,
+            dict(
+                limit=limit,
+                category=category,
+                api_key=api_key,
+                secret=secret,
+                recv_window=recv_window,
+                max_retries=max_retries,
+                base_backoff_ms=base_backoff_ms,
+                max_backoff_ms=max_backoff_ms,
+                page_delay_ms=page_delay_ms,
+                base_url=base_url,
+                testnet=testnet,
+                proxy=proxy,
+                compress=compress,
+                side_as_int=side_as_int,
+                deterministic=deterministic,
+                time_unit=time_unit,
+            ),
+        ),
+    )
+
+
+class MultiFetchResult(dict):
+    """
+    Trades keyed by symbol, returned by :func:`fetch_trades_multi`.
+
+    Only symbols that were fetched successfully are keys. ``errors`` maps every symbol that failed
+    to the exception that stopped its fetch.
+    """
+
+    def __init__(self, trades: Dict[str, List[Dict]], errors: Dict[str, Exception]):
+        super().__init__(trades)
+        self.errors = errors
+
+
+def fetch_trades_multi(
+    symbols: Sequence[str],
+    start_time: int,
+    end_time: int,
+    *,
+    concurrency: int = 4,
+    max_requests_per_sec: float = 10.0,
+    limit: int = 1000,
+    category: str = "linear",
+    api_key: str = "",
+    secret: str = "",
+    recv_window: int = 5000,
+    max_retries: int = 5,
+    base_backoff_ms: int = 50,
+    max_backoff_ms: int = 10000,
+    page_delay_ms: int = 50,
+    base_url: str = "https://api.bybit.com",
+    testnet: bool = False,
+    proxy: Optional[str] = None,
+    compress: bool = True,
+    side_as_int: bool = False,
+    time_unit: Optional[str] = None,
+    config: Optional[FetchConfig] = None,
+) -> MultiFetchResult:
+    """
+    Fetch the trade history of several Bybit symbols concurrently.
+
+    Up to ``concurrency`` symbols are fetched at the same time, each with the same pagination as
+    :func:`fetch_trades`. All fetches share one token bucket, so the aggregate request rate stays
+    within ``max_requests_per_sec`` regardless of ``concurrency``.
+
+    Args:
+        symbols (Sequence[str]): Trading symbols in Bybit format (e.g., ["BTCUSDT", "ETHUSDT"]).
+        start_time (int): Start timestamp in milliseconds, or in ``time_unit`` if given.
+        end_time (int): End timestamp in milliseconds, or in ``time_unit`` if given.
+        concurrency (int, optional): Maximum number of symbols fetched at the same time.
+            Default: 4.
+        max_requests_per_sec (float, optional): Aggregate request budget across all symbols.
+            Default: 10.0.
+        limit (int, optional): Number of trades per request (default 1000, max 1000).
+        category (str, optional): Bybit product type: "linear", "inverse", "spot", or
+            "option". Default: "linear".
+        api_key (str, optional): Bybit API key for authenticated requests. Default: "".
+        secret (str, optional): Bybit API secret for authenticated requests. Default: "".
+        recv_window (int, optional): Validity window of a signed request in milliseconds.
+            Default: 5000.
+        max_retries (int, optional): Maximum number of retries on a 429, a 5xx or a network
+            error. Default: 5.
+        base_backoff_ms (int, optional): Wait before the first retry in milliseconds, doubled
+            on each subsequent retry. Default: 50.
+        max_backoff_ms (int, optional): Upper bound of the wait between retries in
+            milliseconds. Default: 10000.
+        page_delay_ms (int, optional): Delay between page requests in milliseconds when
+            Bybit sends no rate-limit headers to pace them by. 0 removes it, which risks
+            429s on standard accounts. Default: 50.
+        base_url (str, optional): Base URL for Bybit API.
+            Default: "https://api.bybit.com".
+        testnet (bool, optional): Use the Bybit testnet at "https://api-testnet.bybit.com"
+            unless a custom base_url is given. Default: False.
+        proxy (str, optional): Proxy URL to send requests through, with an http, https,
+            socks5 or socks5h scheme. Default: None.
+        compress (bool, optional): Accept gzip and brotli compressed responses. Disable to
+            fetch raw JSON when debugging. Default: True.
+        side_as_int (bool, optional): Return ``side`` as 1 for "Buy" and -1 for "Sell" instead
+            of the string, which is cheaper to store and aggregate. A trade with any other side
+            raises ValueError. Default: False.
+        time_unit (str, optional): Unit of ``start_time``, ``end_time`` and the returned
+            timestamps: "s", "ms", "us" or "ns". Requests are still sent to Bybit in
+            milliseconds. Without it, the timestamps are milliseconds and a ``start_time``
+            before 1973, which is most likely in seconds, raises ValueError instead of fetching
+            an empty list. Default: None.
+        config (FetchConfig, optional): Settings built once with :class:`FetchConfig` and reused
+            across symbols. Arguments left at their default take the config's value instead, and
+            arguments passed with any other value override it. Default: None.
+
+    Returns:
+        MultiFetchResult: Dict mapping each successful symbol to its trades, in the same format
+        as :func:`fetch_trades`. Symbols that failed are listed in ``result.errors`` with their
+        exception.
+
+    Raises:
+        ValueError: If ``time_unit`` is invalid, or if it is not given and ``start_time`` looks
+            like seconds.
+        RuntimeError: If every symbol failed, with the error of the first symbol.
+
+    Example:
+        >>> result = fetch_trades_multi(["BTCUSDT", "ETHUSDT"], start, end)
+        >>> for symbol, error in result.errors.items():
+        ...     print(f"{symbol} failed: {error}")
+    """
+    if _hftbacktest is None:
+        raise ImportError(
+            "hftbacktest extension module not found. "
+            "Please ensure py-hftbacktest is properly installed."
+        )
+
+    trades, errors = _hftbacktest.fetch_trades_multi(
+        list(symbols),
+        start_time,
+        end_time,
+        concurrency=concurrency,
+        max_requests_per_sec=max_requests_per_sec,
+        config=config,
+        **_explicit_settings(
+            fetch_trades_multi,
+            config,
+            dict(
+                limit=limit,
+                category=category,
+                api_key=api_key,
+                secret=secret,
+                recv_window=recv_window,
+                max_retries=max_retries,
+                base_backoff_ms=base_backoff_ms,
+                max_backoff_ms=max_backoff_ms,
+                page_delay_ms=page_delay_ms,
+                base_url=base_url,
+                testnet=testnet,
+                proxy=proxy,
+                compress=compress,
+                side_as_int=side_as_int,
+                time_unit=time_unit,
+            ),
+        ),
+    )
+    return MultiFetchResult(trades, errors)
+
+
+def fetch_trades_to_parquet(
+    symbol: str,
+    start_time: int,
+    end_time: int,
+    path: str,
+    *,
+    compression: str = "snappy",
+    row_group_size: int = 100000,
+    limit: int = 1000,
+    category: str = "linear",
+    api_key: str = "",
+    secret: str = "",
+    recv_window: int = 5000,
+    max_retries: int = 5,
+    base_backoff_ms: int = 50,
+    max_backoff_ms: int = 10000,
+    page_delay_ms: int = 50,
+    base_url: str = "https://api.bybit.com",
+    testnet: bool = False,
+    proxy: Optional[str] = None,
+    compress: bool = True,
+    time_unit: Optional[str] = None,
+    config: Optional[FetchConfig] = None,
+) -> Tuple[int, int]:
+    """
+    Fetch Bybit trade history between two timestamps and write it to a Parquet file.
+
+    Pages are streamed into the writer as they arrive instead of being collected into a Python
+    list, and flushed as a row group every ``row_group_size`` trades, so peak memory stays
+    bounded for multi-day pulls.
+
+    Args:
+        symbol (str): Trading symbol in Bybit format (e.g., "BTCUSDT", "ETHUSDT").
+        start_time (int): Start timestamp in milliseconds, or in ``time_unit`` if given.
+        end_time (int): End timestamp in milliseconds, or in ``time_unit`` if given.
+        path (str): Output Parquet file path. An existing file is overwritten.
+        compression (str, optional): Compression codec: "snappy", "zstd", or "none".
+            Default: "snappy".
+        row_group_size (int, optional): Number of trades buffered before they are flushed as a
+            row group. Default: 100000.
+        limit (int, optional): Number of trades per request (default 1000, max 1000).
+        category (str, optional): Bybit product type: "linear", "inverse", "spot", or
+            "option". Default: "linear".
+        api_key (str, optional): Bybit API key for authenticated requests. Default: "".
+        secret (str, optional): Bybit API secret for authenticated requests. Default: "".
+        recv_window (int, optional): Validity window of a signed request in milliseconds.
+            Default: 5000.
+        max_retries (int, optional): Maximum number of retries on a 429, a 5xx or a network
+            error. Default: 5.
+        base_backoff_ms (int, optional): Wait before the first retry in milliseconds, doubled
+            on each subsequent retry. Default: 50.
+        max_backoff_ms (int, optional): Upper bound of the wait between retries in
+            milliseconds. Default: 10000.
+        page_delay_ms (int, optional): Delay between page requests in milliseconds when
+            Bybit sends no rate-limit headers to pace them by. 0 removes it, which risks
+            429s on standard accounts. Default: 50.
+        base_url (str, optional): Base URL for Bybit API.
+            Default: "https://api.bybit.com".
+        testnet (bool, optional): Use the Bybit testnet at "https://api-testnet.bybit.com"
+            unless a custom base_url is given. Default: False.
+        proxy (str, optional): Proxy URL to send requests through, with an http, https,
+            socks5 or socks5h scheme. Default: None.
+        compress (bool, optional): Accept gzip and brotli compressed responses. Disable to
+            fetch raw JSON when debugging. Default: True.
+        time_unit (str, optional): Unit of ``start_time`` and ``end_time``: "s", "ms", "us" or
+            "ns". The file keeps its own timestamp unit regardless. Without it, the timestamps
+            are milliseconds and a ``start_time`` before 1973, which is most likely in seconds,
+            raises ValueError instead of writing an empty file. Default: None.
+        config (FetchConfig, optional): Settings built once with :class:`FetchConfig` and reused
+            across symbols. Arguments left at their default take the config's value instead, and
+            arguments passed with any other value override it. Default: None.
+
+    Returns:
+        Tuple[int, int]: Number of rows written and the file size in bytes, so callers can
+            validate the write. The file schema is:
+            - timestamp (int64): Trade timestamp in milliseconds
+            - symbol (dictionary-encoded utf8): Trading symbol
+            - side (int8): Taker side, +1 for "Buy" and -1 for "Sell"
+            - price (float64): Trade price
+            - size (float64): Trade quantity
+
+    Raises:
+        ValueError: If ``time_unit`` is invalid, or if it is not given and ``start_time`` looks
+            like seconds.
+        RuntimeError: If the API request fails, returns non-zero status code, rate limit is
+            exceeded after max retries, or the file cannot be written.
+    """
+    if _hftbacktest is None:
+        raise ImportError(
+            "hftbacktest extension module not found. "
+            "Please ensure py-hftbacktest is properly installed."
+        )
+
+    return _hftbacktest.fetch_trades_to_parquet(
+        symbol,
+        start_time,
+        end_time,
+        path,
+        compression=compression,
+        row_group_size=row_group_size,
+        config=config,
+        **_explicit_settings(
+            fetch_trades_to_parquet,
+            config,
+            dict(
+                limit=limit,
+                category=category,
+                api_key=api_key,
+                secret=secret,
+                recv_window=recv_window,
+                max_retries=max_retries,
+                base_backoff_ms=base_backoff_ms,
+                max_backoff_ms=max_backoff_ms,
+                page_delay_ms=page_delay_ms,
+                base_url=base_url,
+                testnet=testnet,
+                proxy=proxy,
+                compress=compress,
+                time_unit=time_unit,
+            ),
+        ),
+    )
+
+
+def fetch_trades_to_csv(
+    symbol: str,
+    start_time: int,
+    end_time: int,
+    path: str,
+    *,
+    append: bool = False,
+    limit: int = 1000,
+    category: str = "linear",
+    api_key: str = "",
+    secret: str = "",
+    recv_window: int = 5000,
+    max_retries: int = 5,
+    base_backoff_ms: int = 50,
+    max_backoff_ms: int = 10000,
+    page_delay_ms: int = 50,
+    base_url: str = "https://api.bybit.com",
+    testnet: bool = False,
+    proxy: Optional[str] = None,
+    compress: bool = True,
+    time_unit: Optional[str] = None,
+    config: Optional[FetchConfig] = None,
+) -> int:
+    """
+    Fetch Bybit trade history between two timestamps and write it to a CSV file.
+
+    Each page is appended to the file as soon as it arrives, which is the simplest format for
+    spot-checking data in a spreadsheet.
+
+    Args:
+        symbol (str): Trading symbol in Bybit format (e.g., "BTCUSDT", "ETHUSDT").
+        start_time (int): Start timestamp in milliseconds, or in ``time_unit`` if given.
+        end_time (int): End timestamp in milliseconds, or in ``time_unit`` if given.
+        path (str): Output CSV file path.
+        append (bool, optional): Append to an existing file instead of overwriting it. The
+            header is only written if the file is new or empty, so several windows can be
+            concatenated into one file. Default: False.
+        limit (int, optional): Number of trades per request (default 1000, max 1000).
+        category (str, optional): Bybit product type: "linear", "inverse", "spot", or
+            "option". Default: "linear".
+        api_key (str, optional): Bybit API key for authenticated requests. Default: "".
+        secret (str, optional): Bybit API secret for authenticated requests. Default: "".
+        recv_window (int, optional): Validity window of a signed request in milliseconds.
+            Default: 5000.
+        max_retries (int, optional): Maximum number of retries on a 429, a 5xx or a network
+            error. Default: 5.
+        base_backoff_ms (int, optional): Wait before the first retry in milliseconds, doubled
+            on each subsequent retry. Default: 50.
+        max_backoff_ms (int, optional): Upper bound of the wait between retries in
+            milliseconds. Default: 10000.
+        page_delay_ms (int, optional): Delay between page requests in milliseconds when
+            Bybit sends no rate-limit headers to pace them by. 0 removes it, which risks
+            429s on standard accounts. Default: 50.
+        base_url (str, optional): Base URL for Bybit API.
+            Default: "https://api.bybit.com".
+        testnet (bool, optional): Use the Bybit testnet at "https://api-testnet.bybit.com"
+            unless a custom base_url is given. Default: False.
+        proxy (str, optional): Proxy URL to send requests through, with an http, https,
+            socks5 or socks5h scheme. Default: None.
+        compress (bool, optional): Accept gzip and brotli compressed responses. Disable to
+            fetch raw JSON when debugging. Default: True.
+        time_unit (str, optional): Unit of ``start_time`` and ``end_time``: "s", "ms", "us" or
+            "ns". The file keeps its own timestamp unit regardless. Without it, the timestamps
+            are milliseconds and a ``start_time`` before 1973, which is most likely in seconds,
+            raises ValueError instead of writing an empty file. Default: None.
+        config (FetchConfig, optional): Settings built once with :class:`FetchConfig` and reused
+            across symbols. Arguments left at their default take the config's value instead, and
+            arguments passed with any other value override it. Default: None.
+
+    Returns:
+        int: Number of rows written, excluding the header. The columns are:
+            - timestamp: Trade timestamp in milliseconds
+            - symbol: Trading symbol
+            - side: Trade side ("Buy" or "Sell")
+            - size: Trade quantity
+            - price: Trade price
+
+    Raises:
+        ValueError: If ``time_unit`` is invalid, or if it is not given and ``start_time`` looks
+            like seconds.
+        RuntimeError: If the API request fails, returns non-zero status code, rate limit is
+            exceeded after max retries, or the file cannot be written.
+    """
+    if _hftbacktest is None:
+        raise ImportError(
+            "hftbacktest extension module not found. "
+            "Please ensure py-hftbacktest is properly installed."
+        )
+
+    return _hftbacktest.fetch_trades_to_csv(
+        symbol,
+        start_time,
+        end_time,
+        path,
+        append=append,
+        config=config,
+        **_explicit_settings(
+            fetch_trades_to_csv,
+            config,
+            dict(
+                limit=limit,
+                category=category,
+                api_key=api_key,
+                secret=secret,
+                recv_window=recv_window,
+                max_retries=max_retries,
+                base_backoff_ms=base_backoff_ms,
+                max_backoff_ms=max_backoff_ms,
+                page_delay_ms=page_delay_ms,
+                base_url=base_url,
+                testnet=testnet,
+                proxy=proxy,
+                compress=compress,
+                time_unit=time_unit,
+            ),
+        ),
+    )
+
+
+def fetch_trades_to_npz(
+    symbol: str,
+    start_time: int,
+    end_time: int,
+    path: str,
+    *,
+    feed_latency: int = 0,
+    latency: Optional[LatencyInjector] = None,
+    tick_size: Optional[float] = None,
+    lot_size: Optional[float] = None,
+    limit: int = 1000,
+    category: str = "linear",
+    api_key: str = "",
+    secret: str = "",
+    recv_window: int = 5000,
+    max_retries: int = 5,
+    base_backoff_ms: int = 50,
+    max_backoff_ms: int = 10000,
+    page_delay_ms: int = 50,
+    base_url: str = "https://api.bybit.com",
+    testnet: bool = False,
+    proxy: Optional[str] = None,
+    compress: bool = True,
+    time_unit: Optional[str] = None,
+    config: Optional[FetchConfig] = None,
+) -> int:
+    """
+    Fetch Bybit trade history and write it as hftbacktest trade events to an ``.npz`` file.
+
+    Each trade is converted into an :data:`hftbacktest.types.event_dtype` row with
+    ``ev = EXCH_EVENT | LOCAL_EVENT | TRADE_EVENT`` plus ``BUY_EVENT`` or ``SELL_EVENT`` for the
+    taker side. Timestamps are converted to nanoseconds. The events are stored under the ``data``
+    key in ascending timestamp order, so the file can be passed to ``BacktestAsset().data(...)``
+    directly. Pages are streamed to disk as they arrive.
+
+    Args:
+        symbol (str): Trading symbol in Bybit format (e.g., "BTCUSDT", "ETHUSDT").
+        start_time (int): Start timestamp in milliseconds, or in ``time_unit`` if given.
+        end_time (int): End timestamp in milliseconds, or in ``time_unit`` if given.
+        path (str): Output ``.npz`` file path. An existing file is overwritten.
+        feed_latency (int, optional): Offset in nanoseconds added to the exchange timestamp to
+            produce the local timestamp. Default: 0.
+        latency (LatencyInjector, optional): Produces the local timestamps instead of
+            ``feed_latency``, for a jittered or empirical feed latency. The local timestamps
+            never go backwards, even when the jitter exceeds the gap between trades.
+            Default: None.
+        tick_size (float, optional): Count each price in ticks from the decimal string Bybit
+            sent and store it as an exact multiple of ``tick_size``, so that
+            ``px / tick_size`` rounds to the intended tick. Default: None, the parsed price.
+        lot_size (float, optional): Same as ``tick_size`` for the size. Default: None, the
+            parsed size.
+        limit (int, optional): Number of trades per request (default 1000, max 1000).
+        category (str, optional): Bybit product type: "linear", "inverse", "spot", or
+            "option". Default: "linear".
+        api_key (str, optional): Bybit API key for authenticated requests. Default: "".
+        secret (str, optional): Bybit API secret for authenticated requests. Default: "".
+        recv_window (int, optional): Validity window of a signed request in milliseconds.
+            Default: 5000.
+        max_retries (int, optional): Maximum number of retries on a 429, a 5xx or a network
+            error. Default: 5.
+        base_backoff_ms (int, optional): Wait before the first retry in milliseconds, doubled
+            on each subsequent retry. Default: 50.
+        max_backoff_ms (int, optional): Upper bound of the wait between retries in
+            milliseconds. Default: 10000.
+        page_delay_ms (int, optional): Delay between page requests in milliseconds when
+            Bybit sends no rate-limit headers to pace them by. 0 removes it, which risks
+            429s on standard accounts. Default: 50.
+        base_url (str, optional): Base URL for Bybit API.
+            Default: "https://api.bybit.com".
+        testnet (bool, optional): Use the Bybit testnet at "https://api-testnet.bybit.com"
+            unless a custom base_url is given. Default: False.
+        proxy (str, optional): Proxy URL to send requests through, with an http, https,
+            socks5 or socks5h scheme. Default: None.
+        compress (bool, optional): Accept gzip and brotli compressed responses. Disable to
+            fetch raw JSON when debugging. Default: True.
+        time_unit (str, optional): Unit of ``start_time`` and ``end_time``: "s", "ms", "us" or
+            "ns". The file keeps its own timestamp unit regardless. Without it, the timestamps
+            are milliseconds and a ``start_time`` before 1973, which is most likely in seconds,
+            raises ValueError instead of writing an empty file. Default: None.
+        config (FetchConfig, optional): Settings built once with :class:`FetchConfig` and reused
+            across symbols. Arguments left at their default take the config's value instead, and
+            arguments passed with any other value override it. Default: None.
+
+    Returns:
+        int: Number of events written.
+
+    Raises:
+        ValueError: If ``time_unit`` is invalid, or if it is not given and ``start_time`` looks
+            like seconds.
+        RuntimeError: If the API request fails, returns non-zero status code, rate limit is
+            exceeded after max retries, a price or size is not a multiple of ``tick_size`` or
+            ``lot_size``, or the file cannot be written.
+    """
+    if _hftbacktest is None:
+        raise ImportError(
+            "hftbacktest extension module not found. "
+            "Please ensure py-hftbacktest is properly installed."
+        )
+
+    return _hftbacktest.fetch_trades_to_npz(
+        symbol,
+        start_time,
+        end_time,
+        path,
+        feed_latency=feed_latency,
+        latency=latency,
+        tick_size=tick_size,
+        lot_size=lot_size,
+        config=config,
+        **_explicit_settings(
+            fetch_trades_to_npz,
+            config,
+            dict(
+                limit=limit,
+                category=category,
+                api_key=api_key,
+                secret=secret,
+                recv_window=recv_window,
+                max_retries=max_retries,
+                base_backoff_ms=base_backoff_ms,
+                max_backoff_ms=max_backoff_ms,
+                page_delay_ms=page_delay_ms,
+                base_url=base_url,
+                testnet=testnet,
+                proxy=proxy,
+                compress=compress,
+                time_unit=time_unit,
+            ),
+        ),
+    )
+
+
+def fetch_trades_daily(
+    symbol: str,
+    start_date: Union[str, datetime.date],
+    end_date: Union[str, datetime.date],
+    out_dir: Union[str, os.PathLike],
+    format: str = "npz",
+    *,
+    overwrite: bool = False,
+    feed_latency: int = 0,
+    config: Optional[FetchConfig] = None,
+) -> List:
+    """
+    Fetch Bybit trade history one UTC day at a time, writing each day to its own file.
+
+    Every day from ``start_date`` to ``end_date``, both inclusive, is fetched with the same
+    pagination as :func:`fetch_trades` and written to ``"{symbol}_{YYYYMMDD}.npz"`` or
+    ``".parquet"`` in ``out_dir``, the one-file-per-symbol-per-day layout used for backtest data.
+    Days are half-open: a trade at 23:59:59.999 belongs to its day and one at 00:00:00.000 to the
+    next.
+
+    Args:
+        symbol (str): Trading symbol in Bybit format (e.g., "BTCUSDT").
+        start_date (str or datetime.date): First UTC day, as a date or "YYYY-MM-DD".
+        end_date (str or datetime.date): Last UTC day, inclusive.
+        out_dir (str or os.PathLike): Output directory, created if missing.
+        format (str, optional): "npz" for trade events as written by
+            :func:`fetch_trades_to_npz`, or "parquet" for trade rows as written by
+            :func:`fetch_trades_to_parquet`. Default: "npz".
+        overwrite (bool, optional): Fetch and rewrite the days whose file already exists.
+            By default they are skipped, so an interrupted run can simply be repeated.
+            Default: False.
+        feed_latency (int, optional): Offset in nanoseconds added to the exchange timestamp to
+            produce the local timestamp of the npz events. Default: 0.
+        config (FetchConfig, optional): Client settings and the ``category``, ``limit``,
+            ``include_block_trades`` and ``min_size`` of the fetches. Default: None.
+
+    Returns:
+        List[DaySummary]: One summary per day, in order, with:
+            - date (str): The UTC day as "YYYY-MM-DD"
+            - path (str): Path of the day's file
+            - skipped (bool): Whether the file already existed and the day was not fetched
+            - trades (int): Number of trades written, 0 for a skipped day
+            - first_timestamp, last_timestamp (Optional[int]): Timestamps of the earliest and
+              latest trade written, None for an empty or skipped day
+
+    Raises:
+        BybitError: If a date is malformed, start_date is after end_date, the format is
+            unknown, the API request fails, or a file cannot be written. A file only gets its
+            final name once complete, so the day that failed is fetched again on the next run,
+            while the days completed before it are skipped.
+    """
+    if _hftbacktest is None:
+        raise ImportError(
+            "hftbacktest extension module not found. "
+            "Please ensure py-hftbacktest is properly installed."
+        )
+
+    def iso(date: Union[str, datetime.date]) -> str:
+        return date if isinstance(date, str) else date.isoformat()
+
+    return _hftbacktest.fetch_trades_daily(
+        symbol,
+        iso(start_date),
+        iso(end_date),
+        os.fspath(out_dir),
+        format,
+        overwrite=overwrite,
+        feed_latency=feed_latency,
+        config=config,
+    )
+
+
+def fetch_trades_aggregated(
+    symbol: str,
+    start_time: int,
+    end_time: int,
+    bucket_ms: int,
+    *,
+    config: Optional[FetchConfig] = None,
+) -> List[Dict]:
+    """
+    Fetch Bybit trade history between two timestamps and aggregate it into fixed time buckets.
+
+    The trades are fetched with the same pagination as :func:`fetch_trades` and aggregated in
+    Rust as each page arrives, so only the buckets are held in memory, which helps when the raw
+    trades are too large to keep. Buckets start at multiples of ``bucket_ms`` since the Unix
+    epoch, and buckets without trades are left out.
+
+    Args:
+        symbol (str): Trading symbol in Bybit format (e.g., "BTCUSDT").
+        start_time (int): Start timestamp in milliseconds (inclusive).
+        end_time (int): End timestamp in milliseconds (exclusive).
+        bucket_ms (int): Length of a bucket in milliseconds.
+        config (FetchConfig, optional): Client settings and the ``category``, ``limit``,
+            ``strict_range``, ``include_block_trades`` and ``min_size`` of the fetch.
+            Default: None.
+
+    Returns:
+        List[Dict]: One dict per bucket, sorted by ``start_ts``, with:
+            - start_ts (int): Start of the bucket in milliseconds
+            - vwap (float): Volume-weighted average price of the trades
+            - volume (float): Total size of the trades
+            - buy_volume (float): Size of the trades initiated by a buyer
+            - sell_volume (float): Size of the trades initiated by a seller
+            - trade_count (int): Number of trades
+
+    Raises:
+        BybitError: If start_time is not before end_time, bucket_ms is not positive,
+            strict_range is set and start_time is older than the retention, or the API request
+            fails.
+    """
+    if _hftbacktest is None:
+        raise ImportError(
+            "hftbacktest extension module not found. "
+            "Please ensure py-hftbacktest is properly installed."
+        )
+
+    return _hftbacktest.fetch_trades_aggregated(
+        symbol,
+        start_time,
+        end_time,
+        bucket_ms,
+        config=config,
+    )
+
+
+def fetch_klines(
+    symbol: str,
+    interval: str,
+    start_time: int,
+    end_time: int,
+    *,
+    limit: int = 1000,
+    category: str = "linear",
+    api_key: str = "",
+    secret: str = "",
+    recv_window: int = 5000,
+    max_retries: int = 5,
+    base_backoff_ms: int = 50,
+    max_backoff_ms: int = 10000,
+    page_delay_ms: int = 50,
+    base_url: str = "https://api.bybit.com",
+    testnet: bool = False,
+    proxy: Optional[str] = None,
+    compress: bool = True,
+    config: Optional[FetchConfig] = None,
+) -> List[Dict]:
+    """
+    Fetch Bybit klines (OHLCV candles) between two timestamps.
+
+    This function calls Bybit's v5 kline REST endpoint. The endpoint has no pagination cursor, so
+    the requested window is walked backwards in pages of up to ``limit`` bars.
+
+    Args:
+        symbol (str): Trading symbol in Bybit format (e.g., "BTCUSDT", "ETHUSDT").
+        interval (str): Kline interval. One of "1", "3", "5", "15", "30", "60", "120", "240",
+            "360", "720" (minutes), "D" (day), "W" (week), or "M" (month).
+        start_time (int): Start timestamp in milliseconds.
+        end_time (int): End timestamp in milliseconds.
+        limit (int, optional): Number of klines per request (default 1000, max 1000).
+        category (str, optional): Bybit product type: "linear", "inverse", or "spot".
+            Default: "linear".
+        api_key (str, optional): Bybit API key for authenticated requests. Default: "".
+        secret (str, optional): Bybit API secret for authenticated requests. Default: "".
+        recv_window (int, optional): Validity window of a signed request in milliseconds.
+            Default: 5000.
+        max_retries (int, optional): Maximum number of retries on a 429, a 5xx or a network
+            error. Default: 5.
+        base_backoff_ms (int, optional): Wait before the first retry in milliseconds, doubled
+            on each subsequent retry. Default: 50.
+        max_backoff_ms (int, optional): Upper bound of the wait between retries in
+            milliseconds. Default: 10000.
+        page_delay_ms (int, optional): Delay between page requests in milliseconds when
+            Bybit sends no rate-limit headers to pace them by. 0 removes it, which risks
+            429s on standard accounts. Default: 50.
+        base_url (str, optional): Base URL for Bybit API.
+            Default: "https://api.bybit.com".
+        testnet (bool, optional): Use the Bybit testnet at "https://api-testnet.bybit.com"
+            unless a custom base_url is given. Default: False.
+        proxy (str, optional): Proxy URL to send requests through, with an http, https,
+            socks5 or socks5h scheme. Default: None.
+        compress (bool, optional): Accept gzip and brotli compressed responses. Disable to
+            fetch raw JSON when debugging. Default: True.
+        config (FetchConfig, optional): Settings built once with :class:`FetchConfig` and reused
+            across symbols. Arguments left at their default take the config's value instead, and
+            arguments passed with any other value override it. Default: None.
+
+    Returns:
+        List[Dict]: List of klines sorted by start time in ascending order. Each dict contains:
+            - start_time (int): Kline open time in milliseconds
+            - open (float): Open price
+            - high (float): High price
+            - low (float): Low price
+            - close (float): Close price
+            - volume (float): Traded volume in base currency
+            - turnover (float): Traded value in quote currency
+
+    Raises:
+        RuntimeError: If the interval is invalid, the API request fails, returns non-zero
+            status code, or rate limit is exceeded after max retries.
+
+    See Also:
+        - Bybit v5 Market Kline API: https://bybit-exchange.github.io/docs/v5/market/kline
+    """
+    if _hftbacktest is None:
+        raise ImportError(
+            "hftbacktest extension module not found. "
+            "Please ensure py-hftbacktest is properly installed."
+        )
+
+    return _hftbacktest.fetch_klines(
+        symbol,
+        interval,
+        start_time,
+        end_time,
+        config=config,
+        **_explicit_settings(
+            fetch_klines,
+            config,
+            dict(
+                limit=limit,
+                category=category,
+                api_key=api_key,
+                secret=secret,
+                recv_window=recv_window,
+                max_retries=max_retries,
+                base_backoff_ms=base_backoff_ms,
+                max_backoff_ms=max_backoff_ms,
+                page_delay_ms=page_delay_ms,
+                base_url=base_url,
+                testnet=testnet,
+                proxy=proxy,
+                compress=compress,
+            ),
+        ),
+    )
+
+
+def fetch_klines_numpy(
+    symbol: str,
+    interval: str,
+    start_time: int,
+    end_time: int,
+    *,
+    limit: int = 1000,
+    category: str = "linear",
+    api_key: str = "",
+    secret: str = "",
+    recv_window: int = 5000,
+    max_retries: int = 5,
+    base_backoff_ms: int = 50,
+    max_backoff_ms: int = 10000,
+    page_delay_ms: int = 50,
+    base_url: str = "https://api.bybit.com",
+    testnet: bool = False,
+    proxy: Optional[str] = None,
+    compress: bool = True,
+    config: Optional[FetchConfig] = None,
+) -> np.ndarray:
+    """
+    Fetch Bybit klines (OHLCV candles) between two timestamps as a NumPy structured array.
+
+    This function calls Bybit's v5 kline REST endpoint. The endpoint has no pagination cursor, so
+    the requested window is walked backwards in pages of up to ``limit`` bars.
+
+    Args:
+        symbol (str): Trading symbol in Bybit format (e.g., "BTCUSDT", "ETHUSDT").
+        interval (str): Kline interval. One of "1", "3", "5", "15", "30", "60", "120", "240",
+            "360", "720" (minutes), "D" (day), "W" (week), or "M" (month).
+        start_time (int): Start timestamp in milliseconds.
+        end_time (int): End timestamp in milliseconds.
+        limit (int, optional): Number of klines per request (default 1000, max 1000).
+        category (str, optional): Bybit product type: "linear", "inverse", or "spot".
+            Default: "linear".
+        api_key (str, optional): Bybit API key for authenticated requests. Default: "".
+        secret (str, optional): Bybit API secret for authenticated requests. Default: "".
+        recv_window (int, optional): Validity window of a signed request in milliseconds.
+            Default: 5000.
+        max_retries (int, optional): Maximum number of retries on a 429, a 5xx or a network
+            error. Default: 5.
+        base_backoff_ms (int, optional): Wait before the first retry in milliseconds, doubled
+            on each subsequent retry. Default: 50.
+        max_backoff_ms (int, optional): Upper bound of the wait between retries in
+            milliseconds. Default: 10000.
+        page_delay_ms (int, optional): Delay between page requests in milliseconds when
+            Bybit sends no rate-limit headers to pace them by. 0 removes it, which risks
+            429s on standard accounts. Default: 50.
+        base_url (str, optional): Base URL for Bybit API.
+            Default: "https://api.bybit.com".
+        testnet (bool, optional): Use the Bybit testnet at "https://api-testnet.bybit.com"
+            unless a custom base_url is given. Default: False.
+        proxy (str, optional): Proxy URL to send requests through, with an http, https,
+            socks5 or socks5h scheme. Default: None.
+        compress (bool, optional): Accept gzip and brotli compressed responses. Disable to
+            fetch raw JSON when debugging. Default: True.
+        config (FetchConfig, optional): Settings built once with :class:`FetchConfig` and reused
+            across symbols. Arguments left at their default take the config's value instead, and
+            arguments passed with any other value override it. Default: None.
+
+    Returns:
+        np.ndarray: Structured array sorted by start time in ascending order, with dtype
+            ``[('start_time', 'i8'), ('open', 'f8'), ('high', 'f8'), ('low', 'f8'),
+            ('close', 'f8'), ('volume', 'f8'), ('turnover', 'f8')]``.
+
+    Raises:
+        RuntimeError: If the interval is invalid, the API request fails, returns non-zero
+            status code, or rate limit is exceeded after max retries.
+
+    See Also:
+        - Bybit v5 Market Kline API: https://bybit-exchange.github.io/docs/v5/market/kline
+    """
+    if _hftbacktest is None:
+        raise ImportError(
+            "hftbacktest extension module not found. "
+            "Please ensure py-hftbacktest is properly installed."
+        )
+
+    return _hftbacktest.fetch_klines_numpy(
+        symbol,
+        interval,
+        start_time,
+        end_time,
+        config=config,
+        **_explicit_settings(
+            fetch_klines_numpy,
+            config,
+            dict(
+                limit=limit,
+                category=category,
+                api_key=api_key,
+                secret=secret,
+                recv_window=recv_window,
+                max_retries=max_retries,
+                base_backoff_ms=base_backoff_ms,
+                max_backoff_ms=max_backoff_ms,
+                page_delay_ms=page_delay_ms,
+                base_url=base_url,
+                testnet=testnet,
+                proxy=proxy,
+                compress=compress,
+            ),
+        ),
+    )
+
+
+def fetch_funding(
+    symbol: str,
+    start_time: int,
+    end_time: int,
+    *,
+    category: str = "linear",
+    api_key: str = "",
+    secret: str = "",
+    recv_window: int = 5000,
+    max_retries: int = 5,
+    base_backoff_ms: int = 50,
+    max_backoff_ms: int = 10000,
+    page_delay_ms: int = 50,
+    base_url: str = "https://api.bybit.com",
+    testnet: bool = False,
+    proxy: Optional[str] = None,
+    compress: bool = True,
+    config: Optional[FetchConfig] = None,
+) -> List[Dict]:
+    """
+    Fetch the funding rate history of a Bybit perpetual contract between two timestamps.
+
+    This function calls Bybit's v5 funding history REST endpoint. The endpoint has no pagination
+    cursor, so the requested window is walked backwards in pages of 200 rows.
+
+    Args:
+        symbol (str): Trading symbol in Bybit format (e.g., "BTCUSDT", "ETHUSDT").
+        start_time (int): Start timestamp in milliseconds.
+        end_time (int): End timestamp in milliseconds.
+        category (str, optional): Bybit product type: "linear" or "inverse". Default: "linear".
+        api_key (str, optional): Bybit API key for authenticated requests. Default: "".
+        secret (str, optional): Bybit API secret for authenticated requests. Default: "".
+        recv_window (int, optional): Validity window of a signed request in milliseconds.
+            Default: 5000.
+        max_retries (int, optional): Maximum number of retries on a 429, a 5xx or a network
+            error. Default: 5.
+        base_backoff_ms (int, optional): Wait before the first retry in milliseconds, doubled
+            on each subsequent retry. Default: 50.
+        max_backoff_ms (int, optional): Upper bound of the wait between retries in
+            milliseconds. Default: 10000.
+        page_delay_ms (int, optional): Delay between page requests in milliseconds when
+            Bybit sends no rate-limit headers to pace them by. 0 removes it, which risks
+            429s on standard accounts. Default: 50.
+        base_url (str, optional): Base URL for Bybit API.
+            Default: "https://api.bybit.com".
+        testnet (bool, optional): Use the Bybit testnet at "https://api-testnet.bybit.com"
+            unless a custom base_url is given. Default: False.
+        proxy (str, optional): Proxy URL to send requests through, with an http, https,
+            socks5 or socks5h scheme. Default: None.
+        compress (bool, optional): Accept gzip and brotli compressed responses. Disable to
+            fetch raw JSON when debugging. Default: True.
+        config (FetchConfig, optional): Settings built once with :class:`FetchConfig` and reused
+            across symbols. Arguments left at their default take the config's value instead, and
+            arguments passed with any other value override it. Default: None.
+
+    Returns:
+        List[Dict]: List of dictionaries representing funding settlements. Each dict contains:
+            - timestamp (int): Funding settlement timestamp in milliseconds
+            - symbol (str): Trading symbol
+            - funding_rate (float): Funding rate of the settlement
+            The list is sorted by timestamp in ascending order.
+
+    Raises:
+        RuntimeError: If the category is invalid, the API request fails, returns non-zero
+            status code, or rate limit is exceeded after max retries.
+
+    See Also:
+        - Bybit v5 Funding Rate History API:
+          https://bybit-exchange.github.io/docs/v5/market/history-fund-rate
+    """
+    if _hftbacktest is None:
+        raise ImportError(
+            "hftbacktest extension module not found. "
+            "Please ensure py-hftbacktest is properly installed."
+        )
+
+    return _hftbacktest.fetch_funding(
+        symbol,
+        start_time,
+        end_time,
+        config=config,
+        **_explicit_settings(
+            fetch_funding,
+            config,
+            dict(
+                category=category,
+                api_key=api_key,
+                secret=secret,
+                recv_window=recv_window,
+                max_retries=max_retries,
+                base_backoff_ms=base_backoff_ms,
+                max_backoff_ms=max_backoff_ms,
+                page_delay_ms=page_delay_ms,
+                base_url=base_url,
+                testnet=testnet,
+                proxy=proxy,
+                compress=compress,
+            ),
+        ),
+    )
+
+
+#: Former name of :func:`fetch_funding`, kept so existing code keeps working.
+fetch_funding_history = fetch_funding
+
+
+def fetch_open_interest(
+    symbol: str,
+    interval_time: str,
+    start_time: int,
+    end_time: int,
+    *,
+    category: str = "linear",
+    api_key: str = "",
+    secret: str = "",
+    recv_window: int = 5000,
+    max_retries: int = 5,
+    base_backoff_ms: int = 50,
+    max_backoff_ms: int = 10000,
+    page_delay_ms: int = 50,
+    base_url: str = "https://api.bybit.com",
+    testnet: bool = False,
+    proxy: Optional[str] = None,
+    compress: bool = True,
+    config: Optional[FetchConfig] = None,
+) -> Tuple[np.ndarray, np.ndarray]:
+    """
+    Fetch the open interest history of a Bybit perpetual or futures contract between two timestamps.
+
+    This function calls Bybit's v5 open interest REST endpoint and follows its pagination
+    cursor in pages of 200 rows.
+
+    Args:
+        symbol (str): Trading symbol in Bybit format (e.g., "BTCUSDT", "ETHUSDT").
+        interval_time (str): Sampling interval: "5min", "15min", "30min", "1h", "4h", or "1d".
+        start_time (int): Start timestamp in milliseconds.
+        end_time (int): End timestamp in milliseconds.
+        category (str, optional): Bybit product type: "linear" or "inverse". Default: "linear".
+        api_key (str, optional): Bybit API key for authenticated requests. Default: "".
+        secret (str, optional): Bybit API secret for authenticated requests. Default: "".
+        recv_window (int, optional): Validity window of a signed request in milliseconds.
+            Default: 5000.
+        max_retries (int, optional): Maximum number of retries on a 429, a 5xx or a network
+            error. Default: 5.
+        base_backoff_ms (int, optional): Wait before the first retry in milliseconds, doubled
+            on each subsequent retry. Default: 50.
+        max_backoff_ms (int, optional): Upper bound of the wait between retries in
+            milliseconds. Default: 10000.
+        page_delay_ms (int, optional): Delay between page requests in milliseconds when
+            Bybit sends no rate-limit headers to pace them by. 0 removes it, which risks
+            429s on standard accounts. Default: 50.
+        base_url (str, optional): Base URL for Bybit API.
+            Default: "https://api.bybit.com".
+        testnet (bool, optional): Use the Bybit testnet at "https://api-testnet.bybit.com"
+            unless a custom base_url is given. Default: False.
+        proxy (str, optional): Proxy URL to send requests through, with an http, https,
+            socks5 or socks5h scheme. Default: None.
+        compress (bool, optional): Accept gzip and brotli compressed responses. Disable to
+            fetch raw JSON when debugging. Default: True.
+        config (FetchConfig, optional): Settings built once with :class:`FetchConfig` and reused
+            across symbols. Arguments left at their default take the config's value instead, and
+            arguments passed with any other value override it. Default: None.
+
+    Returns:
+        Tuple[np.ndarray, np.ndarray]: ``(timestamps, open_interest)`` arrays of dtype int64 and
+        float64, sorted by timestamp in ascending order, with timestamps in milliseconds.
+
+    Raises:
+        RuntimeError: If the interval_time or category is invalid, the API request fails, returns
+            non-zero status code, or rate limit is exceeded after max retries.
+
+    See Also:
+        - Bybit v5 Open Interest API:
+          https://bybit-exchange.github.io/docs/v5/market/open-interest
+    """
+    if _hftbacktest is None:
+        raise ImportError(
+            "hftbacktest extension module not found. "
+            "Please ensure py-hftbacktest is properly installed."
+        )
+
+    return _hftbacktest.fetch_open_interest(
+        symbol,
+        interval_time,
+        start_time,
+        end_time,
+        config=config,
+        **_explicit_settings(
+            fetch_open_interest,
+            config,
+            dict(
+                category=category,
+                api_key=api_key,
+                secret=secret,
+                recv_window=recv_window,
+                max_retries=max_retries,
+                base_backoff_ms=base_backoff_ms,
+                max_backoff_ms=max_backoff_ms,
+                page_delay_ms=page_delay_ms,
+                base_url=base_url,
+                testnet=testnet,
+                proxy=proxy,
+                compress=compress,
+            ),
+        ),
+    )
+
+
+def fetch_long_short_ratio(
+    symbol: str,
+    period: str,
+    start_time: int,
+    end_time: int,
+    *,
+    category: str = "linear",
+    api_key: str = "",
+    secret: str = "",
+    recv_window: int = 5000,
+    max_retries: int = 5,
+    base_backoff_ms: int = 50,
+    max_backoff_ms: int = 10000,
+    page_delay_ms: int = 50,
+    base_url: str = "https://api.bybit.com",
+    testnet: bool = False,
+    proxy: Optional[str] = None,
+    compress: bool = True,
+    config: Optional[FetchConfig] = None,
+) -> Tuple[np.ndarray, np.ndarray]:
+    """
+    Fetch the long/short account ratio history of a Bybit contract between two timestamps.
+
+    The ratio is the share of accounts holding long positions divided by the share holding
+    short positions, from Bybit's v5 long/short ratio REST endpoint, which is followed by its
+    pagination cursor in pages of 500 rows.
+
+    Args:
+        symbol (str): Trading symbol in Bybit format (e.g., "BTCUSDT", "ETHUSDT").
+        period (str): Sampling interval: "5min", "15min", "30min", "1h", "4h", or "1d".
+        start_time (int): Start timestamp in milliseconds.
+        end_time (int): End timestamp in milliseconds.
+        category (str, optional): Bybit product type: "linear" or "inverse". Default: "linear".
+        api_key (str, optional): Bybit API key for authenticated requests. Default: "".
+        secret (str, optional): Bybit API secret for authenticated requests. Default: "".
+        recv_window (int, optional): Validity window of a signed request in milliseconds.
+            Default: 5000.
+        max_retries (int, optional): Maximum number of retries on a 429, a 5xx or a network
+            error. Default: 5.
+        base_backoff_ms (int, optional): Wait before the first retry in milliseconds, doubled
+            on each subsequent retry. Default: 50.
+        max_backoff_ms (int, optional): Upper bound of the wait between retries in
+            milliseconds. Default: 10000.
+        page_delay_ms (int, optional): Delay between page requests in milliseconds when
+            Bybit sends no rate-limit headers to pace them by. 0 removes it, which risks
+            429s on standard accounts. Default: 50.
+        base_url (str, optional): Base URL for Bybit API.
+            Default: "https://api.bybit.com".
+        testnet (bool, optional): Use the Bybit testnet at "https://api-testnet.bybit.com"
+            unless a custom base_url is given. Default: False.
+        proxy (str, optional): Proxy URL to send requests through, with an http, https,
+            socks5 or socks5h scheme. Default: None.
+        compress (bool, optional): Accept gzip and brotli compressed responses. Disable to
+            fetch raw JSON when debugging. Default: True.
+        config (FetchConfig, optional): Settings built once with :class:`FetchConfig` and reused
+            across symbols. Arguments left at their default take the config's value instead, and
+            arguments passed with any other value override it. Default: None.
+
+    Returns:
+        Tuple[np.ndarray, np.ndarray]: ``(timestamps, ratio)`` arrays of dtype int64 and
+        float64, sorted by timestamp in ascending order, with timestamps in milliseconds.
+
+    Raises:
+        RuntimeError: If the period or category is invalid, the API request fails, returns
+            non-zero status code, or rate limit is exceeded after max retries.
+
+    See Also:
+        - Bybit v5 Long Short Ratio API:
+          https://bybit-exchange.github.io/docs/v5/market/long-short-ratio
+    """
+    if _hftbacktest is None:
+        raise ImportError(
+            "hftbacktest extension module not found. "
+            "Please ensure py-hftbacktest is properly installed."
+        )
+
+    return _hftbacktest.fetch_long_short_ratio(
+        symbol,
+        period,
+        start_time,
+        end_time,
+        config=config,
+        **_explicit_settings(
+            fetch_long_short_ratio,
+            config,
+            dict(
+                category=category,
+                api_key=api_key,
+                secret=secret,
+                recv_window=recv_window,
+                max_retries=max_retries,
+                base_backoff_ms=base_backoff_ms,
+                max_backoff_ms=max_backoff_ms,
+                page_delay_ms=page_delay_ms,
+                base_url=base_url,
+                testnet=testnet,
+                proxy=proxy,
+                compress=compress,
+            ),
+        ),
+    )
+
+
+def fetch_instruments(
+    category: str = "linear",
+    *,
+    api_key: str = "",
+    secret: str = "",
+    recv_window: int = 5000,
+    max_retries: int = 5,
+    base_backoff_ms: int = 50,
+    max_backoff_ms: int = 10000,
+    page_delay_ms: int = 50,
+    base_url: str = "https://api.bybit.com",
+    testnet: bool = False,
+    proxy: Optional[str] = None,
+    compress: bool = True,
+    config: Optional[FetchConfig] = None,
+) -> List[Dict]:
+    """
+    Fetch the instruments listed in a Bybit product category.
+
+    Use it to enumerate the tradeable symbols before batch-fetching their trades. The tick and
+    lot sizes go directly into the backtest asset configuration.
+
+    Args:
+        category (str, optional): Bybit product type: "linear", "inverse", "spot", or
+            "option". Default: "linear".
+        api_key (str, optional): Bybit API key for authenticated requests. Default: "".
+        secret (str, optional): Bybit API secret for authenticated requests. Default: "".
+        recv_window (int, optional): Validity window of a signed request in milliseconds.
+            Default: 5000.
+        max_retries (int, optional): Maximum number of retries on a 429, a 5xx or a network
+            error. Default: 5.
+        base_backoff_ms (int, optional): Wait before the first retry in milliseconds, doubled
+            on each subsequent retry. Default: 50.
+        max_backoff_ms (int, optional): Upper bound of the wait between retries in
+            milliseconds. Default: 10000.
+        page_delay_ms (int, optional): Delay between page requests in milliseconds when
+            Bybit sends no rate-limit headers to pace them by. 0 removes it, which risks
+            429s on standard accounts. Default: 50.
+        base_url (str, optional): Base URL for Bybit API.
+            Default: "https://api.bybit.com".
+        testnet (bool, optional): Use the Bybit testnet at "https://api-testnet.bybit.com"
+            unless a custom base_url is given. Default: False.
+        proxy (str, optional): Proxy URL to send requests through, with an http, https,
+            socks5 or socks5h scheme. Default: None.
+        compress (bool, optional): Accept gzip and brotli compressed responses. Disable to
+            fetch raw JSON when debugging. Default: True.
+        config (FetchConfig, optional): Settings built once with :class:`FetchConfig` and reused
+            across symbols. Arguments left at their default take the config's value instead, and
+            arguments passed with any other value override it. Default: None.
+
+    Returns:
+        List[Dict]: One dict per instrument with the following keys:
+            - symbol (str): Trading symbol
+            - base_coin (str): Base coin, e.g. "BTC"
+            - quote_coin (str): Quote coin, e.g. "USDT"
+            - tick_size (float): Minimum price increment
+            - lot_size (float): Minimum quantity increment, ``qtyStep`` for derivatives and
+              ``basePrecision`` for spot
+            - status (str): Trading status, e.g. "Trading" or "PreLaunch"
+
+    Raises:
+        RuntimeError: If the category is invalid, the API request fails, returns non-zero
+            status code, or rate limit is exceeded after max retries.
+
+    See Also:
+        - Bybit v5 Instruments Info API:
+          https://bybit-exchange.github.io/docs/v5/market/instrument
+    """
+    if _hftbacktest is None:
+        raise ImportError(
+            "hftbacktest extension module not found. "
+            "Please ensure py-hftbacktest is properly installed."
+        )
+
+    return _hftbacktest.fetch_instruments(
+        category,
+        config=config,
+        **_explicit_settings(
+            fetch_instruments,
+            config,
+            dict(
+                api_key=api_key,
+                secret=secret,
+                recv_window=recv_window,
+                max_retries=max_retries,
+                base_backoff_ms=base_backoff_ms,
+                max_backoff_ms=max_backoff_ms,
+                page_delay_ms=page_delay_ms,
+                base_url=base_url,
+                testnet=testnet,
+                proxy=proxy,
+                compress=compress,
+            ),
+        ),
+    )
+
+
+#: Custom event ID carrying a funding rate in ``px``, the same ID used by
+#: :func:`hftbacktest.data.utils.binancefutures.convert`.
+FUNDING_RATE_EVENT = 102
+
+
+def funding_to_events(
+    funding: Sequence[Dict],
+    feed_latency: Union[int, LatencyInjector] = 0,
+) -> np.ndarray:
+    """
+    Convert a funding rate series into hftbacktest events.
+
+    Each funding settlement becomes an event with
+    ``ev = FUNDING_RATE_EVENT`` and the rate in ``px``, so the events can be merged into the
+    backtest data and picked up by a custom fee model to charge funding payments.
+
+    Args:
+        funding: Funding settlements with ``timestamp`` in milliseconds and ``funding_rate``,
+            as returned by :func:`fetch_funding`.
+        feed_latency: Offset in nanoseconds added to the exchange timestamp to produce the
+            local timestamp, or a :class:`LatencyInjector`. Default: 0.
+
+    Returns:
+        np.ndarray: Events with ``event_dtype``, with timestamps in nanoseconds.
+    """
+    events = np.zeros(len(funding), event_dtype)
+    for i, row in enumerate(funding):
+        exch_ts = int(row["timestamp"]) * 1_000_000
+        events[i] = (FUNDING_RATE_EVENT, exch_ts, 0, row["funding_rate"], 0, 0, 0, 0)
+    events["local_ts"] = _local_ts(events["exch_ts"], feed_latency)
+    return events
+
+
+def fetch_orderbook(
+    symbol: str,
+    depth_limit: int = 50,
+    *,
+    category: str = "linear",
+    api_key: str = "",
+    secret: str = "",
+    recv_window: int = 5000,
+    max_retries: int = 5,
+    base_backoff_ms: int = 50,
+    max_backoff_ms: int = 10000,
+    base_url: str = "https://api.bybit.com",
+    testnet: bool = False,
+    proxy: Optional[str] = None,
+    compress: bool = True,
+) -> Dict:
+    """
+    Fetch an L2 orderbook snapshot of a Bybit symbol.
+
+    This function calls Bybit's v5 orderbook REST endpoint. Use :func:`snapshot_to_events` to turn
+    the snapshot into events that seed the market depth at the start of a backtest.
+
+    Args:
+        symbol (str): Trading symbol in Bybit format (e.g., "BTCUSDT", "ETHUSDT").
+        depth_limit (int, optional): Number of levels per side: up to 500 for "linear" and
+            "inverse", 200 for "spot", and 25 for "option". Larger values are clamped to the
+            maximum with a ``UserWarning``. Default: 50.
+        category (str, optional): Bybit product type: "linear", "inverse", "spot", or
+            "option". Default: "linear".
+        api_key (str, optional): Bybit API key for authenticated requests. Default: "".
+        secret (str, optional): Bybit API secret for authenticated requests. Default: "".
+        recv_window (int, optional): Validity window of a signed request in milliseconds.
+            Default: 5000.
+        max_retries (int, optional): Maximum number of retries on a 429, a 5xx or a network
+            error. Default: 5.
+        base_backoff_ms (int, optional): Wait before the first retry in milliseconds, doubled
+            on each subsequent retry. Default: 50.
+        max_backoff_ms (int, optional): Upper bound of the wait between retries in
+            milliseconds. Default: 10000.
+        base_url (str, optional): Base URL for Bybit API.
+            Default: "https://api.bybit.com".
+        testnet (bool, optional): Use the Bybit testnet at "https://api-testnet.bybit.com"
+            unless a custom base_url is given. Default: False.
+        proxy (str, optional): Proxy URL to send requests through, with an http, https,
+            socks5 or socks5h scheme. Default: None.
+        compress (bool, optional): Accept gzip and brotli compressed responses. Disable to
+            fetch raw JSON when debugging. Default: True.
+
+    Returns:
+        Dict: Snapshot with the following keys:
+            - symbol (str): Trading symbol
+            - timestamp (int): Snapshot time in milliseconds
+            - update_id (int): Orderbook update ID
+            - bids (List[Tuple[float, float]]): Bid levels as (price, size), best bid first
+            - asks (List[Tuple[float, float]]): Ask levels as (price, size), best ask first
+            - bid_px, bid_qty (np.ndarray): Bid prices and sizes, best bid first
+            - ask_px, ask_qty (np.ndarray): Ask prices and sizes, best ask first
+
+    Raises:
+        RuntimeError: If depth_limit is 0, the API request fails, returns non-zero status code,
+            or rate limit is exceeded after max retries.
+
+    See Also:
+        - Bybit v5 Orderbook API: https://bybit-exchange.github.io/docs/v5/market/orderbook
+    """
+    if _hftbacktest is None:
+        raise ImportError(
+            "hftbacktest extension module not found. "
+            "Please ensure py-hftbacktest is properly installed."
+        )
+
+    return _hftbacktest.fetch_orderbook(
+        symbol,
+        depth_limit,
+        category=category,
+        api_key=api_key,
+        secret=secret,
+        recv_window=recv_window,
+        max_retries=max_retries,
+        base_backoff_ms=base_backoff_ms,
+        max_backoff_ms=max_backoff_ms,
+        base_url=base_url,
+        testnet=testnet,
+        proxy=proxy,
+        compress=compress,
+    )
+
+
+def fetch_recent_trades(
+    symbol: str,
+    n: int,
+    *,
+    category: str = "linear",
+    api_key: str = "",
+    secret: str = "",
+    recv_window: int = 5000,
+    max_retries: int = 5,
+    base_backoff_ms: int = 50,
+    max_backoff_ms: int = 10000,
+    page_delay_ms: int = 50,
+    base_url: str = "https://api.bybit.com",
+    testnet: bool = False,
+    proxy: Optional[str] = None,
+    compress: bool = True,
+    side_as_int: bool = False,
+    config: Optional[FetchConfig] = None,
+) -> List[Dict]:
+    """
+    Fetch the most recent trades of a Bybit symbol.
+
+    No time range is sent, so Bybit starts from the latest trade. Pages are followed backwards
+    through the cursor until ``n`` trades are collected or there are no older trades, so fewer
+    than ``n`` trades may be returned.
+
+    Args:
+        symbol (str): Trading symbol in Bybit format (e.g., "BTCUSDT", "ETHUSDT").
+        n (int): Number of trades to fetch.
+        category (str, optional): Bybit product type: "linear", "inverse", "spot", or
+            "option". Default: "linear".
+        api_key (str, optional): Bybit API key for authenticated requests. Default: "".
+        secret (str, optional): Bybit API secret for authenticated requests. Default: "".
+        recv_window (int, optional): Validity window of a signed request in milliseconds.
+            Default: 5000.
+        max_retries (int, optional): Maximum number of retries on a 429, a 5xx or a network
+            error. Default: 5.
+        base_backoff_ms (int, optional): Wait before the first retry in milliseconds, doubled
+            on each subsequent retry. Default: 50.
+        max_backoff_ms (int, optional): Upper bound of the wait between retries in
+            milliseconds. Default: 10000.
+        page_delay_ms (int, optional): Delay between page requests in milliseconds when
+            Bybit sends no rate-limit headers to pace them by. 0 removes it, which risks
+            429s on standard accounts. Default: 50.
+        base_url (str, optional): Base URL for Bybit API.
+            Default: "https://api.bybit.com".
+        testnet (bool, optional): Use the Bybit testnet at "https://api-testnet.bybit.com"
+            unless a custom base_url is given. Default: False.
+        proxy (str, optional): Proxy URL to send requests through, with an http, https,
+            socks5 or socks5h scheme. Default: None.
+        compress (bool, optional): Accept gzip and brotli compressed responses. Disable to
+            fetch raw JSON when debugging. Default: True.
+        side_as_int (bool, optional): Return ``side`` as 1 for "Buy" and -1 for "Sell" instead
+            of the string, which is cheaper to store and aggregate. A trade with any other side
+            raises ValueError. Default: False.
+        config (FetchConfig, optional): Settings built once with :class:`FetchConfig` and reused
+            across symbols. Arguments left at their default take the config's value instead, and
+            arguments passed with any other value override it. Default: None.
+
+    Returns:
+        List[Dict]: Up to ``n`` trades in the same format as :func:`fetch_trades`, newest first.
+
+    Raises:
+        RuntimeError: If the API request fails, returns non-zero status code, or rate limit is
+            exceeded after max retries.
+    """
+    if _hftbacktest is None:
+        raise ImportError(
+            "hftbacktest extension module not found. "
+            "Please ensure py-hftbacktest is properly installed."
+        )
+
+    return _hftbacktest.fetch_recent_trades(
+        symbol,
+        n,
+        config=config,
+        **_explicit_settings(
+            fetch_recent_trades,
+            config,
+            dict(
+                category=category,
+                api_key=api_key,
+                secret=secret,
+                recv_window=recv_window,
+                max_retries=max_retries,
+                base_backoff_ms=base_backoff_ms,
+                max_backoff_ms=max_backoff_ms,
+                page_delay_ms=page_delay_ms,
+                base_url=base_url,
+                testnet=testnet,
+                proxy=proxy,
+                compress=compress,
+                side_as_int=side_as_int,
+            ),
+        ),
+    )
+
+
+def snapshot_to_events(
+    snapshot: Dict, feed_latency: Union[int, LatencyInjector] = 0
+) -> np.ndarray:
+    """
+    Convert an orderbook snapshot into hftbacktest depth snapshot events.
+
+    For each non-empty side, a ``DEPTH_CLEAR_EVENT`` up to the farthest price in the snapshot is
+    followed by one ``DEPTH_SNAPSHOT_EVENT`` per level, the same layout the Bybit data converter
+    produces. Prepend the events to a trade or depth file to seed the market depth at the start
+    of a backtest.
+
+    Args:
+        snapshot: Snapshot as returned by :func:`fetch_orderbook`.
+        feed_latency: Offset in nanoseconds added to the exchange timestamp to produce the
+            local timestamp, or a :class:`LatencyInjector`. The snapshot arrives as one message,
+            so all events share one local timestamp. Default: 0.
+
+    Returns:
+        np.ndarray: Events with ``event_dtype``, with timestamps in nanoseconds.
+    """
+    exch_ts = int(snapshot["timestamp"]) * 1_000_000
+    local_ts = int(_local_ts(np.array([exch_ts], np.int64), feed_latency)[0])
+    rows = []
+    for side, px, qty in (
+        (BUY_EVENT, snapshot["bid_px"], snapshot["bid_qty"]),
+        (SELL_EVENT, snapshot["ask_px"], snapshot["ask_qty"]),
+    ):
+        if len(px) == 0:
+            continue
+        flags = EXCH_EVENT | LOCAL_EVENT | side
+        # 1: clear the existing market depth up to the prices in the snapshot.
+        rows.append((DEPTH_CLEAR_EVENT | flags, exch_ts, local_ts, float(px[-1]), 0, 0, 0, 0))
+        # 2: insert the snapshot.
+        for level_px, level_qty in zip(px, qty):
+            rows.append(
+                (
+                    DEPTH_SNAPSHOT_EVENT | flags,
+                    exch_ts,
+                    local_ts,
+                    float(level_px),
+                    float(level_qty),
+                    0,
+                    0,
+                    0,
+                )
+            )
+    return np.array(rows, event_dtype)
+
+
+def validate_trades(trades: List[Dict], max_gap_ms: int = 60000):
+    """
+    Check a fetched trade series for gaps and implausible values.
+
+    Exchanges occasionally drop history windows without reporting an error, which otherwise only
+    shows up as odd backtest behaviour.
+
+    Args:
+        trades (List[Dict]): Trades as returned by :func:`fetch_trades`, in either time order,
+            with ``side`` as a string or as an integer from ``side_as_int``.
+        max_gap_ms (int, optional): Longest interval without trades, in milliseconds, that is
+            not reported. Default: 60000.
+
+    Returns:
+        TradeReport: An object with:
+            - gaps (List[Tuple[int, int]]): (start, end) timestamps of intervals longer than
+              ``max_gap_ms`` without trades
+            - non_monotonic (List[int]): Indices of trades out of time order
+            - invalid_values (List[int]): Indices of trades with a zero or negative size or price
+            - invalid_sides (List[int]): Indices of trades whose side is not "Buy" or "Sell"
+            - is_clean() (bool): True if nothing was found
+    """
+    if _hftbacktest is None:
+        raise ImportError(
+            "hftbacktest extension module not found. "
+            "Please ensure py-hftbacktest is properly installed."
+        )
+
+    return _hftbacktest.validate_trades(trades, max_gap_ms)
+
+
+def record_trades(
+    symbols: Sequence[str],
+    output_path: str,
+    duration_secs: float,
+    *,
+    category: str = "linear",
+    testnet: bool = False,
+    flush_interval_ms: int = 1000,
+):
+    """
+    Record the live Bybit trade stream of one or more symbols to a CSV file.
+
+    The REST trade endpoint only serves a short lookback, so longer histories have to be
+    recorded as they happen. This subscribes to the ``publicTrade.<symbol>`` WebSocket topics and
+    appends every trade to ``output_path`` in the layout of :func:`fetch_trades_to_csv`. The call
+    blocks until ``duration_secs`` elapse; Ctrl-C stops it early, keeping what was recorded.
+
+    Dropped connections are re-established and resubscribed automatically, and pings are sent
+    every 20 seconds as Bybit requires. Trades published while disconnected are lost; each such
+    gap emits a ``UserWarning`` with the last and first trade timestamps around the outage.
+
+    Args:
+        symbols (Sequence[str]): Trading symbols in Bybit format (e.g., ["BTCUSDT", "ETHUSDT"]).
+        output_path (str): CSV file to append to, created with a header row if missing.
+        duration_secs (float): How long to record, in seconds.
+        category (str, optional): Bybit product type: "linear", "inverse", "spot", or
+            "option". Default: "linear".
+        testnet (bool, optional): Connect to the Bybit testnet. Default: False.
+        flush_interval_ms (int, optional): How often buffered trades are written to the file,
+            in milliseconds. Default: 1000.
+
+    Returns:
+        RecordSummary: An object with:
+            - rows (int): Number of trades written
+            - reconnects (int): Number of times the connection was re-established
+            - resyncs (int): Always 0 for trades
+            - gaps (List[Tuple[int, int]]): (last, first) trade timestamps in milliseconds
+              around each outage
+
+    Raises:
+        BybitError: If the category is invalid, a symbol is rejected by the subscription, or
+            the file can't be written.
+    """
+    if _hftbacktest is None:
+        raise ImportError(
+            "hftbacktest extension module not found. "
+            "Please ensure py-hftbacktest is properly installed."
+        )
+
+    return _hftbacktest.record_trades(
+        list(symbols),
+        output_path,
+        duration_secs,
+        category=category,
+        testnet=testnet,
+        flush_interval_ms=flush_interval_ms,
+    )
+
+
+def record_liquidations(
+    symbols: Sequence[str],
+    output_path: str,
+    duration_secs: float,
+    *,
+    category: str = "linear",
+    testnet: bool = False,
+    flush_interval_ms: int = 1000,
+):
+    """
+    Record the live Bybit liquidation stream of one or more symbols to a CSV file.
+
+    Subscribes to the ``allLiquidation.<symbol>`` WebSocket topics and appends every liquidation
+    to ``output_path`` in the layout of :func:`fetch_trades_to_csv`, where ``side`` is the side of
+    the liquidated position: "Buy" for a long, "Sell" for a short. Use
+    :func:`liquidations_to_events` to turn the file into events. The call blocks until
+    ``duration_secs`` elapse; Ctrl-C stops it early, keeping what was recorded.
+
+    Connections are kept alive the same way as in :func:`record_trades`. Bybit may re-send
+    liquidations after a reconnection, so one with the same timestamp, price and size as a
+    liquidation already recorded in this call is dropped.
+
+    Args:
+        symbols (Sequence[str]): Trading symbols in Bybit format (e.g., ["BTCUSDT", "ETHUSDT"]).
+        output_path (str): CSV file to append to, created with a header row if missing.
+        duration_secs (float): How long to record, in seconds.
+        category (str, optional): Bybit product type: "linear" or "inverse".
+            Default: "linear".
+        testnet (bool, optional): Connect to the Bybit testnet. Default: False.
+        flush_interval_ms (int, optional): How often buffered liquidations are written to the
+            file, in milliseconds. Default: 1000.
+
+    Returns:
+        RecordSummary: An object with:
+            - rows (int): Number of liquidations written
+            - reconnects (int): Number of times the connection was re-established
+            - resyncs (int): Always 0 for liquidations
+            - duplicates (int): Number of re-sent liquidations dropped
+            - gaps (List[Tuple[int, int]]): (last, first) liquidation timestamps in
+              milliseconds around each outage
+
+    Raises:
+        BybitError: If the category is invalid, a symbol is rejected by the subscription, or
+            the file can't be written.
+    """
+    if _hftbacktest is None:
+        raise ImportError(
+            "hftbacktest extension module not found. "
+            "Please ensure py-hftbacktest is properly installed."
+        )
+
+    return _hftbacktest.record_liquidations(
+        list(symbols),
+        output_path,
+        duration_secs,
+        category=category,
+        testnet=testnet,
+        flush_interval_ms=flush_interval_ms,
+    )
+
+
+#: Flag bit marking a liquidation. Liquidation events are local trade events with this bit set,
+#: so they show up in ``last_trades`` without being matched against orders.
+LIQUIDATION_EVENT = 1 << 20
+
+
+def liquidations_to_events(
+    path: str,
+    feed_latency: Union[int, LatencyInjector] = 0,
+    *,
+    symbol: Optional[str] = None,
+) -> np.ndarray:
+    """
+    Convert liquidations recorded by :func:`record_liquidations` into hftbacktest events.
+
+    Each liquidation becomes an event with ``ev = LOCAL_EVENT | TRADE_EVENT | LIQUIDATION_EVENT``
+    plus ``BUY_EVENT`` for a liquidated long or ``SELL_EVENT`` for a liquidated short, with the
+    price in ``px`` and the size in ``qty``. ``EXCH_EVENT`` is not set: the liquidation orders
+    also appear in the trade stream, so the exchange side must not fill against them twice. The
+    local processor keeps them with the other trades, where a strategy tells them apart with
+    ``ev & LIQUIDATION_EVENT``.
+
+    Args:
+        path (str): CSV file written by :func:`record_liquidations`.
+        feed_latency: Offset in nanoseconds added to the exchange timestamp to produce the
+            local timestamp, or a :class:`LatencyInjector`. Default: 0.
+        symbol (str, optional): Only convert the liquidations of this symbol, for a file
+            recorded from several symbols. Default: None.
+
+    Returns:
+        np.ndarray: Events with ``event_dtype`` in timestamp order, with timestamps in
+        nanoseconds.
+    """
+    with open(path, newline="") as f:
+        rows = [row for row in csv.DictReader(f) if symbol is None or row["symbol"] == symbol]
+    events = np.zeros(len(rows), event_dtype)
+    for i, row in enumerate(rows):
+        side = BUY_EVENT if row["side"] == "Buy" else SELL_EVENT
+        ev = LOCAL_EVENT | TRADE_EVENT | LIQUIDATION_EVENT | side
+        exch_ts = int(row["timestamp"]) * 1_000_000
+        events[i] = (ev, exch_ts, 0, float(row["price"]), float(row["size"]), 0, 0, 0)
+    events = events[np.argsort(events["exch_ts"], kind="stable")]
+    events["local_ts"] = _local_ts(events["exch_ts"], feed_latency)
+    return events
+
+
+def record_depth(
+    symbol: str,
+    level: int,
+    output_path: str,
+    duration_secs: float,
+    *,
+    category: str = "linear",
+    testnet: bool = False,
+):
+    """
+    Record the live Bybit orderbook of a symbol as hftbacktest depth events.
+
+    Subscribes to the ``orderbook.<level>.<symbol>`` WebSocket topic and writes every update to
+    ``output_path`` as an ``.npz`` file that ``BacktestAsset`` loads directly. Each snapshot is
+    written as a ``DEPTH_CLEAR_EVENT`` that clears the whole book followed by
+    ``DEPTH_SNAPSHOT_EVENT`` rows, and each delta as ``DEPTH_EVENT`` rows, where a quantity of 0
+    removes the level. ``exch_ts`` comes from the message and ``local_ts`` from a monotonic clock
+    anchored to the wall clock when recording starts. The call blocks until ``duration_secs``
+    elapse; Ctrl-C stops it early, keeping what was recorded.
+
+    A delta whose update id doesn't follow the previous one means an update was missed. The
+    topic is then resubscribed to get a fresh snapshot, and dropped connections are
+    re-established the same way. Each such gap emits a ``UserWarning``, and the clear event
+    before the next snapshot marks it in the file.
+
+    Args:
+        symbol (str): Trading symbol in Bybit format (e.g., "BTCUSDT").
+        level (int): Orderbook depth: 1, 25, 50, 100, 200, 500, or 1000. Which ones are
+            available depends on the category.
+        output_path (str): ``.npz`` file to write.
+        duration_secs (float): How long to record, in seconds.
+        category (str, optional): Bybit product type: "linear", "inverse", "spot", or
+            "option". Default: "linear".
+        testnet (bool, optional): Connect to the Bybit testnet. Default: False.
+
+    Returns:
+        RecordSummary: An object with:
+            - rows (int): Number of events written
+            - reconnects (int): Number of times the connection was re-established
+            - resyncs (int): Number of times the topic was resubscribed after a missed update
+            - gaps (List[Tuple[int, int]]): (last, first) update timestamps in milliseconds
+              around each gap
+
+    Raises:
+        BybitError: If the category or level is invalid, the subscription is rejected, or the
+            file can't be written.
+    """
+    if _hftbacktest is None:
+        raise ImportError(
+            "hftbacktest extension module not found. "
+            "Please ensure py-hftbacktest is properly installed."
+        )
+
+    return _hftbacktest.record_depth(
+        symbol,
+        level,
+        output_path,
+        duration_secs,
+        category=category,
+        testnet=testnet,
+    )
+
+
+def last_fetch_stats():
+    """
+    Return the request statistics of the last trade fetch made on the calling thread.
+
+    Returns:
+        Optional[FetchStats]: None if no trade fetch has completed yet. Otherwise an object with:
+            - requests (int): Number of HTTP requests sent, including rate-limited ones
+            - rate_limited (int): Number of requests rejected with HTTP 429
+            - retries (int): Number of requests resent after a 429, a 5xx, a timeout or a
+              dropped connection
+            - trades (int): Number of trades delivered
+            - elapsed (datetime.timedelta): Wall-clock time spent fetching
+            - request_rate() (float): Effective number of requests per second
+    """
+    if _hftbacktest is None:
+        raise ImportError(
+            "hftbacktest extension module not found. "
+            "Please ensure py-hftbacktest is properly installed."
+        )
+
+    return _hftbacktest.last_fetch_stats()
+
+
+def init_logging(level: str = "info") -> None:
+    """
+    Write the log events of the fetchers at ``level`` and above to stderr.
+
+    At ``"debug"``, every request is logged with its URL, response status and any backoff before
+    it is retried, and every page with its size and the cursor of the next one. API keys and
+    request signatures are never logged. Calling it again changes the level.
+
+    Args:
+        level (str, optional): One of "trace", "debug", "info", "warn", "error" or "off".
+            Default: "info".
+
+    Raises:
+        ValueError: If ``level`` is not one of the above.
+        RuntimeError: If another logger is already installed in the process.
+
+    Example:
+        >>> init_logging("debug")
+        >>> trades = fetch_trades("BTCUSDT", start, end)  # logs each request to stderr
+    """
+    if _hftbacktest is None:
+        raise ImportError(
+            "hftbacktest extension module not found. "
+            "Please ensure py-hftbacktest is properly installed."
+        )
+
+    _hftbacktest.init_logging(level)
+
+
+__all__ = [
+    "BybitError",
+    "BybitRateLimitError",
+    "BybitApiError",
+    "BybitUnknownSymbolError",
+    "FetchInterrupted",
+    "BybitFetcher",
+    "FetchConfig",
+    "LatencyInjector",
+    "fetch_trades",
+    "count_trades",
+    "fetch_trades_page",
+    "replay_raw_dump",
+    "fetch_trades_async",
+    "ValidatedTrades",
+    "validate_trades",
+    "fetch_trades_numpy",
+    "fetch_trades_polars",
+    "fetch_trades_chunked",
+    "fetch_trades_concurrent",
+    "fetch_trades_multi",
+    "MultiFetchResult",
+    "fetch_trades_to_parquet",
+    "fetch_trades_to_csv",
+    "fetch_trades_to_npz",
+    "fetch_trades_daily",
+    "fetch_trades_aggregated",
+    "fetch_klines",
+    "fetch_klines_numpy",
+    "fetch_funding",
+    "fetch_funding_history",
+    "FUNDING_RATE_EVENT",
+    "funding_to_events",
+    "fetch_open_interest",
+    "fetch_long_short_ratio",
+    "fetch_instruments",
+    "fetch_orderbook",
+    "fetch_recent_trades",
+    "snapshot_to_events",
+    "last_fetch_stats",
+    "init_logging",
+    "record_trades",
+    "record_liquidations",
+    "LIQUIDATION_EVENT",
+    "liquidations_to_events",
+    "record_depth",
+]
@@ -4,10 +4,13 @@ from .validation import (
     validate_event_order
 )
 from ..binding import FuseMarketDepth_ as FuseMarketDepth
+from .._hftbacktest import merge_event_files, MergeSummary
 
 __all__ = (
     'correct_local_timestamp',
     'correct_event_order',
     'validate_event_order',
-    'FuseMarketDepth'
+    'FuseMarketDepth',
+    'merge_event_files',
+    'MergeSummary'
 )
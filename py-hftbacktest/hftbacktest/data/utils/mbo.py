@@ -0,0 +1,115 @@
+import csv
+
+import numpy as np
+from numpy.typing import NDArray
+
+from ..validation import correct_event_order, validate_event_order, correct_local_timestamp
+from ...types import (
+    event_dtype,
+    BUY_EVENT,
+    SELL_EVENT,
+    DEPTH_CLEAR_EVENT,
+    TRADE_EVENT,
+    ADD_ORDER_EVENT,
+    CANCEL_ORDER_EVENT,
+    MODIFY_ORDER_EVENT,
+    FILL_EVENT,
+)
+
+ACTIONS = {
+    'A': ADD_ORDER_EVENT,
+    'M': MODIFY_ORDER_EVENT,
+    'C': CANCEL_ORDER_EVENT,
+    'F': FILL_EVENT,
+    'T': TRADE_EVENT,
+    'R': DEPTH_CLEAR_EVENT,
+}
+
+SIDES = {
+    'B': BUY_EVENT,
+    'S': SELL_EVENT,
+    'N': 0,
+}
+
+
+def convert(
+        input_file: str,
+        output_filename: str | None = None,
+        base_latency: float = 0,
+        timestamp_mul: int = 1
+) -> NDArray:
+    r"""
+    Converts a Level-3 Market-By-Order CSV file into a format compatible with HftBacktest, to be used with
+    :meth:`BacktestAsset.l3 <hftbacktest.BacktestAsset.l3>`.
+
+    The file has a header and the following columns, in any order.
+
+    * exch_ts: The exchange timestamp.
+    * local_ts: The local timestamp. Optional; if missing or empty, the exchange timestamp plus ``base_latency`` is
+      used.
+    * action: ``A`` to add an order, ``M`` to modify it, ``C`` to cancel it, ``F`` for a fill of it, ``T`` for a trade
+      and ``R`` to clear the book.
+    * side: ``B`` for a buy order, ``S`` for a sell order, or ``N`` if there is none, as for a clear. For a trade, the
+      side of the initiator.
+    * price: The price of the order. For a modify, the new price.
+    * qty: The quantity of the order. For a modify, the new quantity, and for a fill, the executed quantity.
+    * order_id: The exchange's order ID, which must be unique among the live orders.
+
+    .. code-block::
+
+        exch_ts,local_ts,action,side,price,qty,order_id
+        1700000000000000000,1700000000000150000,A,B,100.5,2,1001
+        1700000000000200000,1700000000000350000,M,B,100.5,1,1001
+        1700000000000400000,1700000000000550000,F,B,100.5,1,1001
+
+    Args:
+        input_file: The CSV file to convert.
+        output_filename: If provided, the converted data will be saved to the specified filename in ``npz`` format.
+        base_latency: The value to be added to the feed latency.
+                      See :func:`.correct_local_timestamp`.
+        timestamp_mul: The multiplier to convert the timestamps in the file to nanoseconds, such as ``1_000`` for
+                       microseconds.
+
+    Returns:
+        Converted data compatible with HftBacktest.
+    """
+    rows = []
+    with open(input_file, 'r', newline='') as f:
+        for line_num, row in enumerate(csv.DictReader(f), start=2):
+            try:
+                ev = ACTIONS[row['action'].strip().upper()] | SIDES[row['side'].strip().upper()]
+            except KeyError as e:
+                raise ValueError(f'unknown {e} at line {line_num}')
+            exch_ts = int(row['exch_ts']) * timestamp_mul
+            local_ts = row.get('local_ts')
+            local_ts = int(local_ts) * timestamp_mul if local_ts else exch_ts + int(base_latency)
+            rows.append((
+                ev,
+                exch_ts,
+                local_ts,
+                float(row['price']),
+                float(row['qty']),
+                int(row['order_id'] or 0),
+                0,
+                0
+            ))
+
+    tmp = np.array(rows, dtype=event_dtype)
+
+    print('Correcting the latency')
+    tmp = correct_local_timestamp(tmp, base_latency)
+
+    print('Correcting the event order')
+    data = correct_event_order(
+        tmp,
+        np.argsort(tmp['exch_ts'], kind='mergesort'),
+        np.argsort(tmp['local_ts'], kind='mergesort')
+    )
+
+    validate_event_order(data)
+
+    if output_filename is not None:
+        print('Saving to %s' % output_filename)
+        np.savez_compressed(output_filename, data=data)
+
+    return data
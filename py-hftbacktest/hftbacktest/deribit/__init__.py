@@ -0,0 +1,156 @@
+"""Deribit exchange utilities for fetching historical trade data and instruments."""
+
+from typing import Dict, List, Optional
+
+try:
+    from .. import _hftbacktest
+except ImportError:
+    _hftbacktest = None
+
+if _hftbacktest is not None:
+    from .._hftbacktest import DeribitError
+else:
+
+    class DeribitError(RuntimeError):
+        """Raised when fetching data from Deribit fails."""
+
+
+def fetch_deribit_trades(
+    instrument_name: str,
+    start_time: int,
+    end_time: int,
+    *,
+    max_retries: int = 5,
+    base_backoff_ms: int = 50,
+    max_backoff_ms: int = 10000,
+    base_url: str = "https://www.deribit.com",
+    side_as_int: bool = False,
+) -> List[Dict]:
+    """
+    Fetch Deribit public trade history between two timestamps.
+
+    This function calls Deribit's ``/api/v2/public/get_last_trades_by_instrument_and_time``
+    endpoint, which returns at most 1000 trades per page. Pages are requested newest first, and
+    while ``has_more`` is set the next page ends at the oldest timestamp received. Trades repeated
+    across pages are dropped by ``trade_seq``.
+
+    Args:
+        instrument_name (str): Instrument name (e.g., "BTC-PERPETUAL" or
+            "BTC-27DEC24-100000-C").
+        start_time (int): Start timestamp in milliseconds.
+        end_time (int): End timestamp in milliseconds (inclusive).
+        max_retries (int, optional): Maximum number of retries on a rate limit, a 5xx or a
+            network error. Default: 5.
+        base_backoff_ms (int, optional): Wait before the first retry in milliseconds, doubled
+            on each retry. Default: 50.
+        max_backoff_ms (int, optional): Upper bound of the wait between retries in
+            milliseconds. Default: 10000.
+        base_url (str, optional): Deribit API base URL. Default: "https://www.deribit.com".
+        side_as_int (bool, optional): Return ``side`` as 1 for "Buy" and -1 for "Sell" instead
+            of the string, which is cheaper to store and aggregate. A trade with any other side
+            raises ValueError. Default: False.
+
+    Returns:
+        List[Dict]: Trades, newest first, in the same shape as
+            :func:`hftbacktest.bybit.fetch_trades`:
+            - exec_id (str): Trade sequence number, unique within the instrument
+            - timestamp (int): Trade timestamp in milliseconds
+            - symbol (str): Instrument name
+            - side (str): Taker side, "Buy" or "Sell"
+            - size (float): Trade amount, in USD for inverse futures and perpetuals and in the
+              base currency otherwise
+            - price (float): Trade price
+            - is_block_trade (bool): Whether the trade was part of a block trade
+            - mark_price, index_price (float): Prices at the time of the trade
+            - iv (float): Implied volatility in percent, options only
+
+    Raises:
+        DeribitError: If the API request fails or the rate limit is still exceeded after max
+            retries. Derives from RuntimeError.
+
+    See Also:
+        - Deribit trades API:
+          https://docs.deribit.com/#public-get_last_trades_by_instrument_and_time
+    """
+    if _hftbacktest is None:
+        raise ImportError(
+            "hftbacktest extension module not found. "
+            "Please ensure py-hftbacktest is properly installed."
+        )
+
+    return _hftbacktest.fetch_deribit_trades(
+        instrument_name,
+        start_time,
+        end_time,
+        max_retries=max_retries,
+        base_backoff_ms=base_backoff_ms,
+        max_backoff_ms=max_backoff_ms,
+        base_url=base_url,
+        side_as_int=side_as_int,
+    )
+
+
+def fetch_deribit_instruments(
+    currency: str,
+    kind: Optional[str] = None,
+    *,
+    expired: bool = False,
+    max_retries: int = 5,
+    base_backoff_ms: int = 50,
+    max_backoff_ms: int = 10000,
+    base_url: str = "https://www.deribit.com",
+) -> List[Dict]:
+    """
+    Fetch the Deribit instruments of a currency, such as the listed option series.
+
+    Args:
+        currency (str): Currency, e.g. "BTC", "ETH", "USDC" or "any".
+        kind (str, optional): "future", "option", "spot", "future_combo" or "option_combo".
+            Default: None, all kinds.
+        expired (bool, optional): List recently expired instruments instead of active ones.
+            Default: False.
+        max_retries (int, optional): Maximum number of retries on a rate limit, a 5xx or a
+            network error. Default: 5.
+        base_backoff_ms (int, optional): Wait before the first retry in milliseconds, doubled
+            on each retry. Default: 50.
+        max_backoff_ms (int, optional): Upper bound of the wait between retries in
+            milliseconds. Default: 10000.
+        base_url (str, optional): Deribit API base URL. Default: "https://www.deribit.com".
+
+    Returns:
+        List[Dict]: Instruments, each with:
+            - instrument_name, kind, base_currency, quote_currency, settlement_currency (str)
+            - tick_size, min_trade_amount, contract_size (float)
+            - expiration_timestamp (int): Expiration time in milliseconds
+            - strike (float) and option_type (str): None except for options
+            - is_active (bool)
+
+    Raises:
+        DeribitError: If kind is invalid, the API request fails or the rate limit is still
+            exceeded after max retries.
+
+    See Also:
+        - Deribit instruments API: https://docs.deribit.com/#public-get_instruments
+    """
+    if _hftbacktest is None:
+        raise ImportError(
+            "hftbacktest extension module not found. "
+            "Please ensure py-hftbacktest is properly installed."
+        )
+
+    return _hftbacktest.fetch_deribit_instruments(
+        currency,
+        kind,
+        expired=expired,
+        max_retries=max_retries,
+        base_backoff_ms=base_backoff_ms,
+        max_backoff_ms=max_backoff_ms,
+        base_url=base_url,
+    )
+
+
+__all__ = [
+    "DeribitError",
+    "fetch_deribit_instruments",
+    "fetch_deribit_trades",
+]
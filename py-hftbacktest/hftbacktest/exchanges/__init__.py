@@ -0,0 +1,105 @@
+"""Trade history of further exchanges, fetched through one shared pagination driver."""
+
+from typing import Dict, List, Optional
+
+try:
+    from .. import _hftbacktest
+except ImportError:
+    _hftbacktest = None
+
+if _hftbacktest is not None:
+    from .._hftbacktest import ExchangeError
+else:
+
+    class ExchangeError(RuntimeError):
+        """Raised when fetching trades from an exchange with fetch_trades fails."""
+
+
+EXCHANGES = ("coinbase", "kraken_futures")
+
+
+def fetch_trades(
+    exchange: str,
+    symbol: str,
+    start_time: int,
+    end_time: int,
+    *,
+    max_retries: int = 5,
+    base_backoff_ms: int = 50,
+    max_backoff_ms: int = 10000,
+    base_url: Optional[str] = None,
+    side_as_int: bool = False,
+) -> List[Dict]:
+    """
+    Fetch the public trade history of an exchange between two timestamps.
+
+    Every exchange goes through the same pagination, which retries rate-limited and failed
+    requests, paces the pages to the endpoint's rate limit and drops trades repeated across
+    pages.
+
+    - ``"coinbase"``: Coinbase Exchange ``/products/{product_id}/trades``. The endpoint can't be
+      queried by time, so pages are walked from the latest trade backwards with the ``CB-AFTER``
+      header; an old window takes as many requests as there are trades after it.
+    - ``"kraken_futures"``: Kraken Futures ``/api/history/v3/market/{symbol}/executions``,
+      queried by time and walked with the continuation token of each page.
+
+    Args:
+        exchange (str): One of :data:`EXCHANGES`.
+        symbol (str): Coinbase product ID (e.g., "BTC-USD") or Kraken Futures symbol
+            (e.g., "PF_XBTUSD").
+        start_time (int): Start timestamp in milliseconds.
+        end_time (int): End timestamp in milliseconds (inclusive).
+        max_retries (int, optional): Maximum number of retries on a 429, a 5xx or a network
+            error. Default: 5.
+        base_backoff_ms (int, optional): Wait before the first retry in milliseconds, doubled
+            on each retry. Default: 50.
+        max_backoff_ms (int, optional): Upper bound of the wait between retries in
+            milliseconds. Default: 10000.
+        base_url (str, optional): Base URL of the exchange's API. Default: None, the
+            exchange's public host.
+        side_as_int (bool, optional): Return ``side`` as 1 for "Buy" and -1 for "Sell" instead
+            of the string, which is cheaper to store and aggregate. A trade with any other side
+            raises ValueError. Default: False.
+
+    Returns:
+        List[Dict]: Trades sorted by timestamp, in the same shape as
+            :func:`hftbacktest.bybit.fetch_trades`:
+            - exec_id (str): Trade ID
+            - timestamp (int): Trade timestamp in milliseconds
+            - symbol (str): Product ID or symbol
+            - side (str): Taker side, "Buy" or "Sell"
+            - size (float): Trade quantity
+            - price (float): Trade price
+            - is_block_trade (bool): Always False
+
+    Raises:
+        ExchangeError: If the exchange is unknown, the API request fails, or the rate limit is
+            still exceeded after max retries. Derives from RuntimeError.
+
+    Example:
+        >>> trades = fetch_trades("coinbase", "BTC-USD", start, end)
+    """
+    if _hftbacktest is None:
+        raise ImportError(
+            "hftbacktest extension module not found. "
+            "Please ensure py-hftbacktest is properly installed."
+        )
+
+    return _hftbacktest.fetch_exchange_trades(
+        exchange,
+        symbol,
+        start_time,
+        end_time,
+        max_retries=max_retries,
+        base_backoff_ms=base_backoff_ms,
+        max_backoff_ms=max_backoff_ms,
+        base_url=base_url,
+        side_as_int=side_as_int,
+    )
+
+
+__all__ = [
+    "EXCHANGES",
+    "ExchangeError",
+    "fetch_trades",
+]
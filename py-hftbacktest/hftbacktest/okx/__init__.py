@@ -0,0 +1,91 @@
+"""OKX exchange utilities for fetching historical trade data."""
+
+from typing import Dict, List
+
+try:
+    from .. import _hftbacktest
+except ImportError:
+    _hftbacktest = None
+
+if _hftbacktest is not None:
+    from .._hftbacktest import OkxError
+else:
+
+    class OkxError(RuntimeError):
+        """Raised when fetching data from OKX fails."""
+
+
+def fetch_okx_trades(
+    inst_id: str,
+    start_time: int,
+    end_time: int,
+    *,
+    max_retries: int = 5,
+    base_backoff_ms: int = 50,
+    max_backoff_ms: int = 10000,
+    base_url: str = "https://www.okx.com",
+    side_as_int: bool = False,
+) -> List[Dict]:
+    """
+    Fetch OKX public trade history between two timestamps.
+
+    This function calls OKX's ``/api/v5/market/history-trades`` endpoint, which returns at most
+    100 trades per page, newest first. The first page is located by ``end_time`` and the rest
+    are paged backwards by trade ID until ``start_time`` is passed.
+
+    Args:
+        inst_id (str): Instrument ID (e.g., "BTC-USDT-SWAP").
+        start_time (int): Start timestamp in milliseconds.
+        end_time (int): End timestamp in milliseconds (inclusive).
+        max_retries (int, optional): Maximum number of retries on a 429, a 5xx or a network
+            error. Default: 5.
+        base_backoff_ms (int, optional): Wait before the first retry in milliseconds, doubled
+            on each retry. Default: 50.
+        max_backoff_ms (int, optional): Upper bound of the wait between retries in
+            milliseconds. Default: 10000.
+        base_url (str, optional): OKX API base URL. Default: "https://www.okx.com".
+        side_as_int (bool, optional): Return ``side`` as 1 for "Buy" and -1 for "Sell" instead
+            of the string, which is cheaper to store and aggregate. A trade with any other side
+            raises ValueError. Default: False.
+
+    Returns:
+        List[Dict]: Trades, newest first, in the same shape as
+            :func:`hftbacktest.bybit.fetch_trades`:
+            - exec_id (str): Trade ID
+            - timestamp (int): Trade timestamp in milliseconds
+            - symbol (str): Instrument ID
+            - side (str): Taker side, "Buy" or "Sell"
+            - size (float): Trade quantity
+            - price (float): Trade price
+            - is_block_trade (bool): Always False
+
+    Raises:
+        OkxError: If the API request fails or the rate limit is still exceeded after max
+            retries. Derives from RuntimeError.
+
+    See Also:
+        - OKX history trades API:
+          https://www.okx.com/docs-v5/en/#order-book-trading-market-data-get-trades-history
+    """
+    if _hftbacktest is None:
+        raise ImportError(
+            "hftbacktest extension module not found. "
+            "Please ensure py-hftbacktest is properly installed."
+        )
+
+    return _hftbacktest.fetch_okx_trades(
+        inst_id,
+        start_time,
+        end_time,
+        max_retries=max_retries,
+        base_backoff_ms=base_backoff_ms,
+        max_backoff_ms=max_backoff_ms,
+        base_url=base_url,
+        side_as_int=side_as_int,
+    )
+
+
+__all__ = [
+    "OkxError",
+    "fetch_okx_trades",
+]
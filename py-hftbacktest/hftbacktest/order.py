@@ -41,6 +41,9 @@ PARTIALLY_FILLED = 5
 #: REJECTED
 REJECTED = 6
 
+#: TRIGGERED, reported when the trigger price of a stop order is reached
+TRIGGERED = 8
+
 #: Good 'till cancel
 GTC = 0
 
@@ -59,6 +62,12 @@ LIMIT = 0
 #: MARKET
 MARKET = 1
 
+#: STOP_MARKET, a market order placed once the trigger price is reached
+STOP_MARKET = 2
+
+#: STOP_LIMIT, a limit order placed once the trigger price is reached
+STOP_LIMIT = 3
+
 
 class Order:
     arr: from_dtype(order_dtype)[:]
@@ -97,6 +106,13 @@ class Order:
         """
         return self.arr[0].qty
 
+    @property
+    def display_qty(self) -> float64:
+        """
+        Returns the displayed quantity of an iceberg order, or `0` if the full quantity is displayed.
+        """
+        return self.arr[0].display_qty
+
     @property
     def leaves_qty(self) -> float64:
         """
@@ -112,6 +128,22 @@ class Order:
         """
         return self.arr[0].price_tick
 
+    @property
+    def trigger_price(self) -> float64:
+        """
+        Returns the trigger price. This is only valid if :obj:`order_type` is :const:`STOP_MARKET` or
+        :const:`STOP_LIMIT`.
+        """
+        return self.arr[0].trigger_price_tick * self.arr[0].tick_size
+
+    @property
+    def trigger_price_tick(self) -> int64:
+        """
+        Returns the trigger price in ticks. This is only valid if :obj:`order_type` is :const:`STOP_MARKET` or
+        :const:`STOP_LIMIT`.
+        """
+        return self.arr[0].trigger_price_tick
+
     @property
     def tick_size(self) -> float64:
         """
@@ -163,6 +195,8 @@ class Order:
 
             * :const:`MARKET`
             * :const:`LIMIT`
+            * :const:`STOP_MARKET`
+            * :const:`STOP_LIMIT`
         """
         return self.arr[0].order_type
 
@@ -189,6 +223,7 @@ class Order:
             * :const:`FILLED`
             * :const:`CANCELED`
             * :const:`PARTIALLY_FILLED`
+            * :const:`TRIGGERED`
         """
         return self.arr[0].status
 
@@ -55,5 +55,19 @@ class StateValues:
         """
         return self.arr[0].trading_value
 
+    @property
+    def num_post_only_rejects(self) -> int64:
+        """
+        Returns the number of post-only orders that were not accepted because they would have taken liquidity.
+        """
+        return self.arr[0].num_post_only_rejects
+
+    @property
+    def funding(self) -> float64:
+        """
+        Returns the cumulative funding payments, positive when paid and negative when received.
+        """
+        return self.arr[0].funding
+
 
 StateValues_ = jitclass(StateValues)
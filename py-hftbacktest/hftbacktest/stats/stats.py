@@ -423,6 +423,11 @@ class LinearAssetRecord(Record):
                     pl.col('balance') + pl.col('position') * pl.col('price') * self._contract_size
                 ).alias('equity_wo_fee')
             )
+            if 'funding' in self.df:
+                # Funding payments are settled outside of the balance.
+                self.df = self.df.with_columns(
+                    (pl.col('equity_wo_fee') - pl.col('funding')).alias('equity_wo_fee')
+                )
 
         if 'trading_value_' not in self.df:
             if 'trading_value' not in self.df:
@@ -446,6 +451,11 @@ class InverseAssetRecord(Record):
                     -pl.col('balance') - pl.col('position') / pl.col('price') * self._contract_size
                 ).alias('equity_wo_fee')
             )
+            if 'funding' in self.df:
+                # Funding payments are settled outside of the balance.
+                self.df = self.df.with_columns(
+                    (pl.col('equity_wo_fee') - pl.col('funding')).alias('equity_wo_fee')
+                )
 
         if 'trading_value_' not in self.df:
             if 'trading_value' not in self.df:
@@ -32,6 +32,12 @@ MODIFY_ORDER_EVENT = 12
 #: Indicates that an order in the order book has been filled.
 FILL_EVENT = 13
 
+#: Indicates that the mark price is updated. The price is in `px`.
+MARK_PRICE_EVENT = 14
+
+#: Indicates that the index price is updated. The price is in `px`.
+INDEX_PRICE_EVENT = 15
+
 # todo: fix WAIT_ORDER_RESPONSE flags.
 WAIT_ORDER_RESPONSE_NONE = -1
 WAIT_ORDER_RESPONSE_ANY = -2
@@ -66,7 +72,9 @@ state_values_dtype = np.dtype(
         ('fee', 'f8'),
         ('num_trades', 'i8'),
         ('trading_volume', 'f8'),
-        ('trading_value', 'f8')
+        ('trading_value', 'f8'),
+        ('num_post_only_rejects', 'i8'),
+        ('funding', 'f8')
     ],
     align=True
 )
@@ -87,13 +95,24 @@ event_dtype = np.dtype(
 
 EVENT_ARRAY = np.ndarray[Any, event_dtype]
 
+queue_profile_dtype = np.dtype(
+    [
+        ('level', 'i8'),
+        ('position', 'f8'),
+        ('prob_ahead', 'f8')
+    ],
+    align=True
+)
+
 order_dtype = np.dtype(
     [
         ('qty', 'f8'),
+        ('display_qty', 'f8'),
         ('leaves_qty', 'f8'),
         ('exec_qty', 'f8'),
         ('exec_price_tick', 'i8'),
         ('price_tick', 'i8'),
+        ('trigger_price_tick', 'i8'),
         ('tick_size', 'f8'),
         ('exch_timestamp', 'i8'),
         ('local_timestamp', 'i8'),
@@ -119,7 +138,9 @@ record_dtype = np.dtype(
         ('fee', 'f8'),
         ('num_trades', 'i8'),
         ('trading_volume', 'f8'),
-        ('trading_value', 'f8')
+        ('trading_value', 'f8'),
+        ('num_post_only_rejects', 'i8'),
+        ('funding', 'f8')
     ],
     align=True
 )
@@ -136,6 +136,8 @@ pub extern "C" fn hashmapbt_submit_buy_order(
     qty: f64,
     time_in_force: u8,
     order_type: u8,
+    trigger_price: f64,
+    display_qty: f64,
     wait: bool,
 ) -> i64 {
     let hbt = unsafe { &mut *hbt_ptr };
@@ -147,6 +149,8 @@ pub extern "C" fn hashmapbt_submit_buy_order(
         qty,
         tif,
         unsafe { mem::transmute::<u8, OrdType>(order_type) },
+        trigger_price,
+        display_qty,
         wait,
     ))
 }
@@ -160,6 +164,8 @@ pub extern "C" fn hashmapbt_submit_sell_order(
     qty: f64,
     time_in_force: u8,
     order_type: u8,
+    trigger_price: f64,
+    display_qty: f64,
     wait: bool,
 ) -> i64 {
     let hbt = unsafe { &mut *hbt_ptr };
@@ -170,6 +176,8 @@ pub extern "C" fn hashmapbt_submit_sell_order(
         qty,
         unsafe { mem::transmute::<u8, TimeInForce>(time_in_force) },
         unsafe { mem::transmute::<u8, OrdType>(order_type) },
+        trigger_price,
+        display_qty,
         wait,
     ))
 }
@@ -398,6 +406,8 @@ pub extern "C" fn roivecbt_submit_buy_order(
     qty: f64,
     time_in_force: u8,
     order_type: u8,
+    trigger_price: f64,
+    display_qty: f64,
     wait: bool,
 ) -> i64 {
     let hbt = unsafe { &mut *hbt_ptr };
@@ -409,6 +419,8 @@ pub extern "C" fn roivecbt_submit_buy_order(
         qty,
         tif,
         unsafe { mem::transmute::<u8, OrdType>(order_type) },
+        trigger_price,
+        display_qty,
         wait,
     ))
 }
@@ -422,6 +434,8 @@ pub extern "C" fn roivecbt_submit_sell_order(
     qty: f64,
     time_in_force: u8,
     order_type: u8,
+    trigger_price: f64,
+    display_qty: f64,
     wait: bool,
 ) -> i64 {
     let hbt = unsafe { &mut *hbt_ptr };
@@ -432,6 +446,8 @@ pub extern "C" fn roivecbt_submit_sell_order(
         qty,
         unsafe { mem::transmute::<u8, TimeInForce>(time_in_force) },
         unsafe { mem::transmute::<u8, OrdType>(order_type) },
+        trigger_price,
+        display_qty,
         wait,
     ))
 }
@@ -0,0 +1,392 @@
+use std::time::{Duration, Instant};
+
+use pyo3::{prelude::*, types::PyList};
+use reqwest::{Client, StatusCode, header::HeaderMap};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::bybit::{FetchStats, RetryConfig, TradeRow, record_stats, runtime};
+
+pub mod exceptions {
+    use pyo3::{create_exception, exceptions::PyRuntimeError};
+
+    create_exception!(
+        hftbacktest,
+        BinanceError,
+        PyRuntimeError,
+        "Raised when fetching data from Binance fails."
+    );
+}
+
+#[derive(Error, Debug)]
+pub enum BinanceError {
+    #[error("HTTP error: {0}")]
+    Http(StatusCode),
+    #[error("Rate limited: max retries exceeded")]
+    RateLimited,
+    #[error("IP banned for exceeding the request weight limit, retry after {0:?}")]
+    Banned(Option<Duration>),
+    #[error("API error: {code} - {msg}")]
+    Api { code: i64, msg: String },
+    #[error("Failed to parse {0}")]
+    Parse(String),
+    #[error("Request failed: {0}")]
+    Transport(String),
+    #[error("Invalid argument: {0}")]
+    InvalidArg(String),
+}
+
+impl From<BinanceError> for PyErr {
+    fn from(error: BinanceError) -> Self {
+        exceptions::BinanceError::new_err(error.to_string())
+    }
+}
+
+/// Binance USDⓈ-M Futures REST host.
+pub const FUTURES_BASE_URL: &str = "https://fapi.binance.com";
+
+/// Maximum span, in milliseconds, of an aggTrades request bounded by both `startTime` and
+/// `endTime`.
+const MAX_TIME_WINDOW_MS: i64 = 60 * 60 * 1000;
+
+/// Delay between consecutive page requests.
+const PAGE_DELAY: Duration = Duration::from_millis(50);
+
+/// An aggregate trade as returned by `/fapi/v1/aggTrades`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BinanceAggTrade {
+    /// Aggregate trade ID.
+    #[serde(rename = "a")]
+    pub id: i64,
+    #[serde(rename = "p")]
+    pub price: String,
+    #[serde(rename = "q")]
+    pub qty: String,
+    #[serde(rename = "T")]
+    pub time: i64,
+    /// True if the buyer was the maker, that is, the taker sold.
+    #[serde(rename = "m")]
+    pub is_buyer_maker: bool,
+}
+
+impl BinanceAggTrade {
+    pub fn to_row(&self, symbol: &str) -> Result<TradeRow, BinanceError> {
+        Ok(TradeRow {
+            exec_id: self.id.to_string(),
+            timestamp: self.time,
+            symbol: symbol.to_string(),
+            side: if self.is_buyer_maker { "Sell" } else { "Buy" }.to_string(),
+            size: self
+                .qty
+                .parse()
+                .map_err(|_| BinanceError::Parse(format!("qty: {}", self.qty)))?,
+            price: self
+                .price
+                .parse()
+                .map_err(|_| BinanceError::Parse(format!("price: {}", self.price)))?,
+            is_block_trade: false,
+            mark_price: None,
+            index_price: None,
+            mark_iv: None,
+            iv: None,
+            raw_price: None,
+            raw_size: None,
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct BinanceErrorBody {
+    code: i64,
+    msg: String,
+}
+
+/// Reads the `Retry-After` header, which Binance sends in seconds with 429 and 418 responses.
+fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let secs: u64 = headers
+        .get("Retry-After")?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse()
+        .ok()?;
+    Some(Duration::from_secs(secs))
+}
+
+#[derive(Debug, Clone)]
+pub struct BinanceAggTradesFetcher {
+    client: Client,
+    base_url: String,
+    retry: RetryConfig,
+}
+
+impl BinanceAggTradesFetcher {
+    pub fn new(base_url: String) -> Self {
+        Self {
+            client: Client::new(),
+            base_url,
+            retry: RetryConfig::default(),
+        }
+    }
+
+    /// Sets the retry policy applied when requests are rate limited.
+    pub fn with_retry(mut self, retry: RetryConfig) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Sends a GET request to `path`, retrying on HTTP 429 after the `Retry-After` delay, or with
+    /// exponential backoff if the header is missing. HTTP 418 means the IP is banned and is not
+    /// retried.
+    async fn get(
+        &self,
+        path: &str,
+        query_string: &str,
+        stats: &mut FetchStats,
+    ) -> Result<Vec<BinanceAggTrade>, BinanceError> {
+        let url = format!("{}{}?{}", self.base_url, path, query_string);
+        let mut retries = 0;
+
+        loop {
+            let response = self
+                .client
+                .get(&url)
+                .timeout(Duration::from_secs(10))
+                .send()
+                .await
+                .map_err(|e| BinanceError::Transport(e.to_string()))?;
+            stats.requests += 1;
+
+            match response.status() {
+                StatusCode::TOO_MANY_REQUESTS => {
+                    stats.rate_limited += 1;
+                    if retries >= self.retry.max_retries {
+                        return Err(BinanceError::RateLimited);
+                    }
+                    retries += 1;
+                    let delay = retry_after(response.headers())
+                        .unwrap_or_else(|| self.retry.backoff(retries));
+                    tokio::time::sleep(delay).await;
+                    continue;
+                },
+                StatusCode::IM_A_TEAPOT => {
+                    stats.rate_limited += 1;
+                    return Err(BinanceError::Banned(retry_after(response.headers())));
+                },
+                status if !status.is_success() => {
+                    return Err(match response.json::<BinanceErrorBody>().await {
+                        Ok(body) => BinanceError::Api {
+                            code: body.code,
+                            msg: body.msg,
+                        },
+                        Err(_) => BinanceError::Http(status),
+                    });
+                },
+                _ => {},
+            }
+
+            return response
+                .json()
+                .await
+                .map_err(|e| BinanceError::Parse(format!("response: {}", e)));
+        }
+    }
+
+    /// Fetches the aggregate trades in `[start_time, end_time]`, passing each page to `on_page`.
+    ///
+    /// The first trade is located with hour-long `startTime`/`endTime` windows, since Binance
+    /// rejects wider ones; from there pages are walked forwards by `fromId` until a trade past
+    /// `end_time` or the latest trade is reached.
+    pub async fn fetch_agg_trade_pages<F>(
+        &self,
+        symbol: &str,
+        start_time: i64,
+        end_time: i64,
+        limit: u32,
+        mut on_page: F,
+    ) -> Result<FetchStats, BinanceError>
+    where
+        F: FnMut(Vec<TradeRow>) -> Result<(), BinanceError>,
+    {
+        if limit == 0 || limit > 1000 {
+            return Err(BinanceError::InvalidArg(format!(
+                "limit must be in 1..=1000: {}",
+                limit
+            )));
+        }
+
+        let started = Instant::now();
+        let mut stats = FetchStats::default();
+        let mut window_start = start_time;
+        let mut from_id = None;
+
+        loop {
+            let query_string = match from_id {
+                Some(from_id) => format!("symbol={}&fromId={}&limit={}", symbol, from_id, limit),
+                None => {
+                    if window_start > end_time {
+                        break;
+                    }
+                    let window_end = window_start
+                        .saturating_add(MAX_TIME_WINDOW_MS - 1)
+                        .min(end_time);
+                    let query_string = format!(
+                        "symbol={}&startTime={}&endTime={}&limit={}",
+                        symbol, window_start, window_end, limit
+                    );
+                    window_start = window_end + 1;
+                    query_string
+                },
+            };
+
+            let page = self
+                .get("/fapi/v1/aggTrades", &query_string, &mut stats)
+                .await?;
+            let Some(last) = page.last() else {
+                if from_id.is_some() {
+                    break;
+                }
+                tokio::time::sleep(PAGE_DELAY).await;
+                continue;
+            };
+            // A short page walked by ID means the latest trade has been reached.
+            let done = last.time >= end_time || (from_id.is_some() && page.len() < limit as usize);
+            from_id = Some(last.id + 1);
+
+            let rows = page
+                .iter()
+                .filter(|t| t.time >= start_time && t.time <= end_time)
+                .map(|t| t.to_row(symbol))
+                .collect::<Result<Vec<_>, _>>()?;
+            stats.trades += rows.len();
+            on_page(rows)?;
+
+            if done {
+                break;
+            }
+            tokio::time::sleep(PAGE_DELAY).await;
+        }
+
+        stats.elapsed = started.elapsed();
+        Ok(stats)
+    }
+}
+
+/// Fetch Binance USDⓈ-M Futures aggregate trades between two timestamps.
+///
+/// Aggregate trades are returned in the same shape as Bybit trades from fetch_trades, with the
+/// aggregate trade ID as exec_id and the taker side derived from isBuyerMaker.
+///
+/// Args:
+///     symbol: Trading symbol (e.g., "BTCUSDT")
+///     start_time: Start timestamp in milliseconds
+///     end_time: End timestamp in milliseconds (inclusive)
+///     limit: Number of trades per request (default 1000, max 1000)
+///     max_retries: Maximum number of retries when rate limited (default 5)
+///     base_backoff_ms: Wait before the first retry in milliseconds when no Retry-After header is
+///                      sent, doubled on each retry (default 50)
+///     max_backoff_ms: Upper bound of the wait between retries in milliseconds (default 10000)
+///     base_url: Base URL for the Binance Futures API (default "https://fapi.binance.com")
+///     side_as_int: Return side as 1 for Buy and -1 for Sell instead of the string (default False)
+///
+/// Returns:
+///     List of dicts with keys: exec_id, timestamp, symbol, side, size, price, is_block_trade
+///
+/// Raises:
+///     BinanceError: If the API request fails, the rate limit is still exceeded after max
+///                   retries, or the IP is banned
+#[allow(clippy::too_many_arguments)]
+#[pyfunction]
+#[pyo3(
+    text_signature = "(symbol, start_time, end_time, *, limit=1000, max_retries=5, base_backoff_ms=50, max_backoff_ms=10000, base_url='https://fapi.binance.com', side_as_int=False)"
+)]
+pub fn fetch_binance_trades(
+    py: Python,
+    symbol: String,
+    start_time: i64,
+    end_time: i64,
+    limit: Option<u32>,
+    max_retries: Option<u32>,
+    base_backoff_ms: Option<u64>,
+    max_backoff_ms: Option<u64>,
+    base_url: Option<String>,
+    side_as_int: Option<bool>,
+) -> PyResult<PyObject> {
+    if start_time >= end_time {
+        return Err(BinanceError::InvalidArg(format!(
+            "start_time {} must be before end_time {}",
+            start_time, end_time
+        ))
+        .into());
+    }
+    let fetcher =
+        BinanceAggTradesFetcher::new(base_url.unwrap_or_else(|| FUTURES_BASE_URL.to_string()))
+            .with_retry(RetryConfig::new(
+                max_retries,
+                base_backoff_ms,
+                max_backoff_ms,
+            ));
+
+    let rt = runtime()?;
+    let mut trades = Vec::new();
+    let stats = py.allow_threads(|| {
+        rt.block_on(fetcher.fetch_agg_trade_pages(
+            &symbol,
+            start_time,
+            end_time,
+            limit.unwrap_or(1000),
+            |page| {
+                trades.extend(page);
+                Ok(())
+            },
+        ))
+    })?;
+    record_stats(stats);
+
+    let result = trades
+        .iter()
+        .map(|t| t.to_dict(py, side_as_int.unwrap_or(false), false))
+        .collect::<PyResult<Vec<_>>>()?;
+    Ok(PyList::new(py, result)?.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::header::HeaderValue;
+
+    use super::*;
+
+    #[test]
+    fn test_agg_trade_to_row() {
+        let json = r#"[
+            {"a": 26129, "p": "0.01633102", "q": "4.70443515", "f": 27781, "l": 27781,
+             "T": 1498793709153, "m": true},
+            {"a": 26130, "p": "0.01633200", "q": "1.5", "f": 27782, "l": 27783,
+             "T": 1498793709160, "m": false}
+        ]"#;
+        let trades: Vec<BinanceAggTrade> = serde_json::from_str(json).unwrap();
+        let rows = trades
+            .iter()
+            .map(|t| t.to_row("BTCUSDT"))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        assert_eq!(rows[0].exec_id, "26129");
+        assert_eq!(rows[0].timestamp, 1498793709153);
+        assert_eq!(rows[0].symbol, "BTCUSDT");
+        // The buyer was the maker, so the taker sold.
+        assert_eq!(rows[0].side, "Sell");
+        assert_eq!(rows[0].price, 0.01633102);
+        assert_eq!(rows[0].size, 4.70443515);
+        assert_eq!(rows[1].side, "Buy");
+        assert!(!rows[1].is_block_trade);
+    }
+
+    #[test]
+    fn test_retry_after() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(retry_after(&headers), None);
+        headers.insert("Retry-After", HeaderValue::from_static("3"));
+        assert_eq!(retry_after(&headers), Some(Duration::from_secs(3)));
+    }
+}
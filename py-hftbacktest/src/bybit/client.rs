@@ -0,0 +1,1589 @@
+//! The HTTP client shared by every Bybit fetch: request signing, retries with backoff, rate-limit
+//! pacing and the settings it is built from.
+
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    sync::{Arc, Mutex, OnceLock},
+    time::{Duration, Instant},
+};
+
+use chrono::Utc;
+use pyo3::{exceptions::PyRuntimeError, prelude::*};
+use rand::{Rng, SeedableRng, rngs::StdRng};
+use reqwest::{
+    Client, StatusCode,
+    header::{HeaderMap, HeaderName, HeaderValue, USER_AGENT},
+};
+use serde::de::DeserializeOwned;
+use tokio::runtime::Runtime;
+use tracing::debug;
+
+use super::{
+    BybitError, BybitResponse, DEFAULT_MAX_EMPTY_PAGES, DEFAULT_PAGE_DELAY_MS, DEFAULT_RECV_WINDOW,
+    FetchStats, MAINNET_BASE_URL, ResponseStatus, TESTNET_BASE_URL, TradeRow, api_error,
+    export::RawDump,
+    pagination::{retain_min_size, retain_non_block, sort_canonical, sort_trades},
+};
+
+#[derive(Debug, Clone)]
+pub struct BybitHistoryFetcher {
+    client: Client,
+    base_url: String,
+    api_key: String,
+    secret: String,
+    recv_window: u64,
+    pub(super) retry: RetryConfig,
+    rate_limiter: Option<Arc<TokenBucket>>,
+    proxy: Option<String>,
+    compress: bool,
+    http: HttpConfig,
+    headers: HeaderMap,
+    raw_dump: Option<Arc<RawDump>>,
+    max_empty_pages: u32,
+    page_delay_ms: u64,
+}
+
+impl BybitHistoryFetcher {
+    pub fn new(base_url: String, api_key: String, secret: String) -> Self {
+        Self {
+            client: Client::new(),
+            base_url,
+            api_key,
+            secret,
+            recv_window: DEFAULT_RECV_WINDOW,
+            retry: RetryConfig::default(),
+            rate_limiter: None,
+            proxy: None,
+            compress: true,
+            http: HttpConfig::default(),
+            headers: HeaderMap::new(),
+            raw_dump: None,
+            max_empty_pages: DEFAULT_MAX_EMPTY_PAGES,
+            page_delay_ms: DEFAULT_PAGE_DELAY_MS,
+        }
+    }
+
+    /// Creates a fetcher with the client settings of `config`: host, credentials, retry policy,
+    /// stalled pagination limit, proxy, compression, HTTP timeouts and headers.
+    pub fn from_config(config: &FetchConfig) -> Result<Self, BybitError> {
+        Self::new(
+            resolve_base_url(Some(config.base_url.clone()), Some(config.testnet)),
+            config.api_key.clone(),
+            config.secret.clone(),
+        )
+        .with_recv_window(config.recv_window)
+        .with_max_empty_pages(config.max_empty_pages)
+        .with_page_delay_ms(config.page_delay_ms)
+        .with_retry(RetryConfig {
+            jitter: RetryConfig::default().jitter.filter(|_| config.jitter),
+            retry_on_5xx: config.retry_on_5xx,
+            ..RetryConfig::new(
+                Some(config.max_retries),
+                Some(config.base_backoff_ms),
+                Some(config.max_backoff_ms),
+            )
+        })
+        .with_proxy(config.proxy.as_deref())?
+        .with_compression(config.compress)?
+        .with_http_config(HttpConfig::new(
+            config.connect_timeout_ms,
+            Some(config.timeout_ms),
+            config.tcp_keepalive_ms,
+            config.max_idle_connections,
+        ))?
+        .with_headers(config.user_agent.as_deref(), config.extra_headers.as_ref())
+    }
+
+    /// Sets how long, in milliseconds, a signed request stays valid after its timestamp.
+    pub fn with_recv_window(mut self, recv_window: u64) -> Self {
+        self.recv_window = recv_window;
+        self
+    }
+
+    /// Sets how many consecutive empty pages that still carry a next cursor are followed before
+    /// the pagination fails with [`BybitError::StalledPagination`] instead of spinning forever.
+    pub fn with_max_empty_pages(mut self, max_empty_pages: u32) -> Self {
+        self.max_empty_pages = max_empty_pages;
+        self
+    }
+
+    /// Sets the delay between consecutive page requests when the response carries no rate-limit
+    /// headers to pace them by. `0` sends the next page right away.
+    pub fn with_page_delay_ms(mut self, page_delay_ms: u64) -> Self {
+        self.page_delay_ms = page_delay_ms;
+        self
+    }
+
+    /// Sets the retry policy applied when requests are rate limited.
+    pub fn with_retry(mut self, retry: RetryConfig) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Sends all requests through `proxy`, an `http://`, `https://`, `socks5://` or `socks5h://`
+    /// URL. `None` keeps direct connections.
+    pub fn with_proxy(mut self, proxy: Option<&str>) -> Result<Self, BybitError> {
+        let Some(proxy) = proxy else {
+            return Ok(self);
+        };
+        let scheme = proxy.split_once("://").map(|(scheme, _)| scheme);
+        if !matches!(scheme, Some("http" | "https" | "socks5" | "socks5h")) {
+            return Err(BybitError::InvalidArg(format!(
+                "proxy {} (expected an http, https, socks5 or socks5h URL)",
+                proxy
+            )));
+        }
+        self.proxy = Some(proxy.to_string());
+        self.rebuild_client()?;
+        Ok(self)
+    }
+
+    /// Sets whether gzip and brotli compressed responses are accepted, which is the default.
+    /// Disabling it fetches raw JSON, which is easier to inspect when debugging.
+    pub fn with_compression(mut self, compress: bool) -> Result<Self, BybitError> {
+        if compress != self.compress {
+            self.compress = compress;
+            self.rebuild_client()?;
+        }
+        Ok(self)
+    }
+
+    /// Sets the timeouts and connection pooling of the HTTP client.
+    pub fn with_http_config(mut self, http: HttpConfig) -> Result<Self, BybitError> {
+        if http.timeout_ms == 0 || http.connect_timeout_ms == Some(0) {
+            return Err(BybitError::InvalidArg(
+                "timeouts must be positive".to_string(),
+            ));
+        }
+        self.http = http;
+        self.rebuild_client()?;
+        Ok(self)
+    }
+
+    /// Adds `extra_headers` to every request, and sets its `User-Agent` to `user_agent`, which
+    /// takes precedence over one in `extra_headers`. The `X-BAPI-*` headers that sign a request
+    /// can't be overridden.
+    pub fn with_headers(
+        mut self,
+        user_agent: Option<&str>,
+        extra_headers: Option<&HashMap<String, String>>,
+    ) -> Result<Self, BybitError> {
+        if user_agent.is_none() && extra_headers.is_none_or(HashMap::is_empty) {
+            return Ok(self);
+        }
+        let mut headers = HeaderMap::new();
+        for (name, value) in extra_headers.into_iter().flatten() {
+            let name = HeaderName::from_bytes(name.as_bytes())
+                .map_err(|e| BybitError::InvalidArg(format!("header name {:?}: {}", name, e)))?;
+            // `HeaderName` is lowercase.
+            if name.as_str().starts_with("x-bapi-") {
+                return Err(BybitError::InvalidArg(format!(
+                    "header {} (the signing headers can't be overridden)",
+                    name
+                )));
+            }
+            let value = HeaderValue::from_str(value)
+                .map_err(|e| BybitError::InvalidArg(format!("header {}: {}", name, e)))?;
+            headers.insert(name, value);
+        }
+        if let Some(user_agent) = user_agent {
+            let value = HeaderValue::from_str(user_agent)
+                .map_err(|e| BybitError::InvalidArg(format!("user_agent: {}", e)))?;
+            headers.insert(USER_AGENT, value);
+        }
+        self.headers = headers;
+        self.rebuild_client()?;
+        Ok(self)
+    }
+
+    fn rebuild_client(&mut self) -> Result<(), BybitError> {
+        let mut builder = Client::builder()
+            .default_headers(self.headers.clone())
+            .gzip(self.compress)
+            .brotli(self.compress)
+            .tcp_keepalive(self.http.tcp_keepalive_ms.map(Duration::from_millis));
+        if let Some(connect_timeout_ms) = self.http.connect_timeout_ms {
+            builder = builder.connect_timeout(Duration::from_millis(connect_timeout_ms));
+        }
+        if let Some(max_idle_connections) = self.http.max_idle_connections {
+            builder = builder.pool_max_idle_per_host(max_idle_connections);
+        }
+        if let Some(proxy) = &self.proxy {
+            let proxy = reqwest::Proxy::all(proxy)
+                .map_err(|e| BybitError::InvalidArg(format!("proxy {}: {}", proxy, e)))?;
+            builder = builder.proxy(proxy);
+        }
+        self.client = builder
+            .build()
+            .map_err(|e| BybitError::Transport(format!("HTTP client: {}", e)))?;
+        Ok(())
+    }
+
+    /// Appends the raw body of every successful response to the [`RawDump`] at `path` before it
+    /// is parsed. `None` disables dumping.
+    pub fn with_raw_dump(mut self, path: Option<&str>) -> Result<Self, BybitError> {
+        self.raw_dump = path.map(RawDump::open).transpose()?.map(Arc::new);
+        Ok(self)
+    }
+
+    /// Makes every request take a token from `rate_limiter` first. Clones of the fetcher share the
+    /// bucket, which bounds their combined request rate.
+    pub fn with_rate_limiter(mut self, rate_limiter: Arc<TokenBucket>) -> Self {
+        self.rate_limiter = Some(rate_limiter);
+        self
+    }
+
+    /// Sends a signed GET request to `path`, retrying while rate limited, on a gateway or server
+    /// error (500, 502, 503 or 504), and on a timeout or dropped connection, and returns the
+    /// `result` of the response together with the rate-limit budget reported in its headers. All
+    /// retries share the `max_retries` budget and resend the same URL, so a page's cursor is kept;
+    /// other errors, such as a 4xx, fail at once.
+    pub(super) async fn get<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        query_string: &str,
+        stats: &mut FetchStats,
+    ) -> Result<(T, Option<RateLimitStatus>), BybitError> {
+        let url = format!("{}{}?{}", self.base_url, path, query_string);
+        let mut retries = 0;
+
+        loop {
+            if let Some(rate_limiter) = &self.rate_limiter {
+                rate_limiter.acquire().await;
+            }
+            debug!(url = %redact_url(&url), retry = retries, "sending request");
+
+            let mut request = self
+                .client
+                .get(&url)
+                .timeout(Duration::from_millis(self.http.timeout_ms));
+            // Public endpoints need no signature, so a request without credentials is sent plain.
+            if !self.api_key.is_empty() || !self.secret.is_empty() {
+                let timestamp = Utc::now().timestamp_millis();
+                let signature = sign_request(
+                    &self.api_key,
+                    &self.secret,
+                    self.recv_window,
+                    query_string,
+                    timestamp,
+                )?;
+                request = request
+                    .header("X-BAPI-SIGN", signature)
+                    .header("X-BAPI-API-KEY", &self.api_key)
+                    .header("X-BAPI-TIMESTAMP", timestamp.to_string())
+                    .header("X-BAPI-RECV-WINDOW", self.recv_window.to_string());
+            }
+            let response = request.send().await;
+            stats.requests += 1;
+            let response = match response {
+                Ok(response) => response,
+                Err(e) if self.retry.retries_error(&e) && retries < self.retry.max_retries => {
+                    retries += 1;
+                    stats.retries += 1;
+                    let delay = self.retry.backoff(retries);
+                    debug!(
+                        timeout = e.is_timeout(),
+                        retry = retries,
+                        delay_ms = delay.as_millis() as u64,
+                        "request failed, backing off"
+                    );
+                    tokio::time::sleep(delay).await;
+                    continue;
+                },
+                Err(e) if e.is_timeout() => return Err(BybitError::Timeout(e.to_string())),
+                Err(e) => return Err(BybitError::Transport(e.to_string())),
+            };
+            let rate_limit = RateLimitStatus::from_headers(response.headers());
+            debug!(status = %response.status(), "received response");
+
+            if response.status() == 429 {
+                // Rate limited
+                stats.rate_limited += 1;
+                if retries < self.retry.max_retries {
+                    retries += 1;
+                    stats.retries += 1;
+                    let delay = match &rate_limit {
+                        Some(rate_limit) => rate_limit.delay(Utc::now().timestamp_millis()),
+                        None => self.retry.backoff(retries),
+                    };
+                    debug!(
+                        retry = retries,
+                        delay_ms = delay.as_millis() as u64,
+                        "rate limited, backing off"
+                    );
+                    tokio::time::sleep(delay).await;
+                    continue;
+                } else {
+                    return Err(BybitError::RateLimited);
+                }
+            }
+
+            if self.retry.retries_status(response.status()) && retries < self.retry.max_retries {
+                retries += 1;
+                stats.retries += 1;
+                let delay = self.retry.backoff(retries);
+                debug!(
+                    status = %response.status(),
+                    retry = retries,
+                    delay_ms = delay.as_millis() as u64,
+                    "server error, backing off"
+                );
+                tokio::time::sleep(delay).await;
+                continue;
+            }
+
+            if !response.status().is_success() {
+                return Err(BybitError::Http(response.status()));
+            }
+
+            let body = response.text().await.map_err(|e| {
+                if e.is_timeout() {
+                    BybitError::Timeout(e.to_string())
+                } else {
+                    BybitError::Parse(format!("response: {}", e))
+                }
+            })?;
+            // Dumped before parsing, so a body that no longer matches the schema is kept.
+            if let Some(raw_dump) = &self.raw_dump {
+                raw_dump.write(&url, Utc::now().timestamp_millis(), &body)?;
+            }
+            if let Ok(status) = serde_json::from_str::<ResponseStatus>(&body)
+                && status.ret_code != 0
+            {
+                return Err(api_error(status.ret_code, status.ret_msg, query_string));
+            }
+            let resp_body: BybitResponse<T> = serde_json::from_str(&body).map_err(|e| {
+                BybitError::Parse(format!("response: {}, body: {}", e, body_snippet(&body)))
+            })?;
+
+            return Ok((resp_body.result, rate_limit));
+        }
+    }
+
+    /// Delay before the next page request, paced by the rate-limit headers when available and
+    /// `page_delay_ms` otherwise.
+    pub(super) fn page_delay(&self, rate_limit: &Option<RateLimitStatus>) -> Duration {
+        match rate_limit {
+            Some(rate_limit) => rate_limit.delay(Utc::now().timestamp_millis()),
+            None => Duration::from_millis(self.page_delay_ms),
+        }
+    }
+
+    /// Counts a page that came with a next cursor in `empty_pages`, the number of consecutive
+    /// empty ones, and fails once it reaches the `max_empty_pages` limit.
+    pub(super) fn check_stalled(
+        &self,
+        empty_pages: &mut u32,
+        is_empty: bool,
+    ) -> Result<(), BybitError> {
+        if !is_empty {
+            *empty_pages = 0;
+            return Ok(());
+        }
+        *empty_pages += 1;
+        if *empty_pages >= self.max_empty_pages {
+            return Err(BybitError::StalledPagination(*empty_pages));
+        }
+        Ok(())
+    }
+}
+
+/// Rate-limit budget reported by Bybit in the `X-Bapi-Limit-Status`, `X-Bapi-Limit`, and
+/// `X-Bapi-Limit-Reset-Timestamp` response headers.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RateLimitStatus {
+    /// Requests remaining in the current window.
+    pub remaining: u64,
+    /// Requests allowed per window.
+    pub limit: u64,
+    /// Time in milliseconds at which the budget is restored.
+    pub reset_timestamp: i64,
+}
+
+impl RateLimitStatus {
+    pub fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        fn get<T: std::str::FromStr>(headers: &HeaderMap, name: &str) -> Option<T> {
+            headers.get(name)?.to_str().ok()?.trim().parse().ok()
+        }
+        Some(Self {
+            remaining: get(headers, "X-Bapi-Limit-Status")?,
+            limit: get(headers, "X-Bapi-Limit")?,
+            reset_timestamp: get(headers, "X-Bapi-Limit-Reset-Timestamp")?,
+        })
+    }
+
+    /// Returns how long to wait before the next request so the remaining budget is spread
+    /// evenly until the reset time. Waits until the reset time once the budget is exhausted.
+    pub fn delay(&self, now: i64) -> Duration {
+        let until_reset = (self.reset_timestamp - now).max(0) as u64;
+        Duration::from_millis(
+            until_reset
+                .checked_div(self.remaining)
+                .unwrap_or(until_reset),
+        )
+    }
+}
+
+/// Token bucket shared by concurrent fetches so that their combined request rate stays within a
+/// per-key budget. The bucket holds up to one second's worth of tokens.
+#[derive(Debug)]
+pub struct TokenBucket {
+    requests_per_sec: f64,
+    state: Mutex<(f64, Instant)>,
+}
+
+impl TokenBucket {
+    pub fn new(requests_per_sec: f64) -> Self {
+        Self {
+            requests_per_sec,
+            state: Mutex::new((requests_per_sec.max(1.0), Instant::now())),
+        }
+    }
+
+    /// Waits until a token is available and takes it.
+    pub async fn acquire(&self) {
+        loop {
+            let wait = {
+                let mut state = self.state.lock().unwrap();
+                let (tokens, last_refill) = &mut *state;
+                let now = Instant::now();
+                *tokens = (*tokens
+                    + now.duration_since(*last_refill).as_secs_f64() * self.requests_per_sec)
+                    .min(self.requests_per_sec.max(1.0));
+                *last_refill = now;
+                if *tokens >= 1.0 {
+                    *tokens -= 1.0;
+                    return;
+                }
+                Duration::from_secs_f64((1.0 - *tokens) / self.requests_per_sec)
+            };
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+/// HTTP statuses of transient gateway and server errors, retried like a rate-limited request.
+const RETRYABLE_STATUSES: [StatusCode; 4] = [
+    StatusCode::INTERNAL_SERVER_ERROR,
+    StatusCode::BAD_GATEWAY,
+    StatusCode::SERVICE_UNAVAILABLE,
+    StatusCode::GATEWAY_TIMEOUT,
+];
+
+/// Number of characters of a response body quoted in a parse error.
+const BODY_SNIPPET_CHARS: usize = 256;
+
+/// Returns the start of a response body to quote in an error, so that an HTML error page from a
+/// proxy or CDN can be told apart from a change of the response schema.
+pub(crate) fn body_snippet(body: &str) -> String {
+    match body.char_indices().nth(BODY_SNIPPET_CHARS) {
+        Some((end, _)) => format!("{}...", &body[..end]),
+        None => body.to_string(),
+    }
+}
+
+/// Query parameters whose values are credentials, replaced by [`redact_url`].
+const SECRET_PARAMS: [&str; 5] = ["api_key", "apiKey", "sign", "signature", "secret"];
+
+/// Returns `url` with the values of credential query parameters replaced, so that it can be
+/// logged. Bybit sends the key and signature in headers, which are never logged, but a URL built
+/// for another venue may carry them in the query.
+pub(crate) fn redact_url(url: &str) -> String {
+    let Some((base, query)) = url.split_once('?') else {
+        return url.to_string();
+    };
+    let query = query
+        .split('&')
+        .map(|param| match param.split_once('=') {
+            Some((key, _)) if SECRET_PARAMS.contains(&key) => format!("{}=REDACTED", key),
+            _ => param.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("&");
+    format!("{}?{}", base, query)
+}
+
+/// Retry policy for rate-limited, failed and timed-out requests. The `n`-th retry waits
+/// `min(base_backoff_ms * 2^(n - 1), max_backoff_ms)` milliseconds, or with `jitter`, a random
+/// duration up to that ("full jitter"), so that clients hitting the rate limit together don't
+/// retry in lockstep and trip it again.
+#[derive(Debug, Clone)]
+pub struct RetryConfig {
+    pub max_retries: u32,
+    pub base_backoff_ms: u64,
+    pub max_backoff_ms: u64,
+    /// Source of the jitter, shared between clones so the concurrent workers of a fetch draw
+    /// different waits. `None` waits the full backoff.
+    pub jitter: Option<Arc<Mutex<StdRng>>>,
+    /// Retry a 500, 502, 503 or 504 response. Otherwise it fails the request right away.
+    pub retry_on_5xx: bool,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: 5,
+            base_backoff_ms: 50,
+            max_backoff_ms: 10_000,
+            jitter: Some(Arc::new(Mutex::new(StdRng::from_os_rng()))),
+            retry_on_5xx: true,
+        }
+    }
+}
+
+impl RetryConfig {
+    pub fn new(
+        max_retries: Option<u32>,
+        base_backoff_ms: Option<u64>,
+        max_backoff_ms: Option<u64>,
+    ) -> Self {
+        let default = Self::default();
+        Self {
+            max_retries: max_retries.unwrap_or(default.max_retries),
+            base_backoff_ms: base_backoff_ms.unwrap_or(default.base_backoff_ms),
+            max_backoff_ms: max_backoff_ms.unwrap_or(default.max_backoff_ms),
+            jitter: default.jitter,
+            retry_on_5xx: default.retry_on_5xx,
+        }
+    }
+
+    /// Returns whether a response with `status` is retried.
+    pub fn retries_status(&self, status: StatusCode) -> bool {
+        self.retry_on_5xx && RETRYABLE_STATUSES.contains(&status)
+    }
+
+    /// Returns whether a request that failed to send with `error` is retried: the connection
+    /// could not be made, was reset or timed out. A request that can't be built, such as one with
+    /// an invalid URL, fails the same way every time and is not retried.
+    pub fn retries_error(&self, error: &reqwest::Error) -> bool {
+        !error.is_builder() && (error.is_timeout() || error.is_connect() || error.is_request())
+    }
+
+    /// Seeds the jitter, making the waits reproducible.
+    pub fn with_jitter_seed(mut self, seed: u64) -> Self {
+        self.jitter = Some(Arc::new(Mutex::new(StdRng::seed_from_u64(seed))));
+        self
+    }
+
+    /// Returns the wait before the `retry`-th retry, starting from 1.
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let factor = 1u64
+            .checked_shl(retry.saturating_sub(1))
+            .unwrap_or(u64::MAX);
+        let backoff_ms = self
+            .base_backoff_ms
+            .saturating_mul(factor)
+            .min(self.max_backoff_ms);
+        match &self.jitter {
+            Some(rng) => {
+                let mut rng = rng.lock().unwrap_or_else(|e| e.into_inner());
+                Duration::from_millis(rng.random_range(0..=backoff_ms))
+            },
+            None => Duration::from_millis(backoff_ms),
+        }
+    }
+}
+
+/// Timeouts and connection pooling of the HTTP client. `None` leaves the `reqwest` default in
+/// place: no connect timeout, no TCP keepalive, and no limit on idle connections.
+#[derive(Debug, Clone)]
+pub struct HttpConfig {
+    /// Timeout for establishing a connection.
+    pub connect_timeout_ms: Option<u64>,
+    /// Timeout for a whole request, from connecting until the response body is read.
+    pub timeout_ms: u64,
+    /// Interval of TCP keepalive probes on idle connections.
+    pub tcp_keepalive_ms: Option<u64>,
+    /// Maximum number of idle connections kept open per host.
+    pub max_idle_connections: Option<usize>,
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        Self {
+            connect_timeout_ms: None,
+            timeout_ms: 10_000,
+            tcp_keepalive_ms: None,
+            max_idle_connections: None,
+        }
+    }
+}
+
+impl HttpConfig {
+    pub fn new(
+        connect_timeout_ms: Option<u64>,
+        timeout_ms: Option<u64>,
+        tcp_keepalive_ms: Option<u64>,
+        max_idle_connections: Option<usize>,
+    ) -> Self {
+        Self {
+            connect_timeout_ms,
+            timeout_ms: timeout_ms.unwrap_or(Self::default().timeout_ms),
+            tcp_keepalive_ms,
+            max_idle_connections,
+        }
+    }
+}
+
+/// Settings of a trade fetch, built once and reused across symbols instead of passing the same
+/// keyword arguments to every call. The client settings are applied by
+/// [`BybitHistoryFetcher::from_config`], the rest by each fetch.
+///
+/// Args:
+///     category: Product type: "linear", "inverse", "spot", or "option" (default "linear")
+///     limit: Number of trades per request (default 1000, max 1000)
+///     api_key: Bybit API key (optional for public endpoint)
+///     secret: Bybit API secret (optional for public endpoint)
+///     recv_window: Validity window of a signed request in milliseconds (default 5000)
+///     max_retries: Maximum number of retries on a 429, a 5xx or a network error (default 5)
+///     base_backoff_ms: Wait before the first retry in milliseconds, doubled on each retry (default 50)
+///     max_backoff_ms: Upper bound of the wait between retries in milliseconds (default 10000)
+///     jitter: Wait a random duration up to the backoff instead of the full backoff, so that
+///             concurrent clients don't retry in lockstep (default True)
+///     retry_on_5xx: Retry a 500, 502, 503 or 504 response instead of raising (default True)
+///     base_url: Base URL for Bybit API (default "https://api.bybit.com")
+///     testnet: Use the Bybit testnet at "https://api-testnet.bybit.com" unless a custom base_url is
+///              given (default False)
+///     proxy: Proxy URL to send requests through, with an http, https, socks5 or socks5h scheme
+///            (default None)
+///     compress: Accept gzip and brotli compressed responses (default True)
+///     connect_timeout_ms: Timeout for establishing a connection in milliseconds (default None,
+///                         no timeout)
+///     timeout_ms: Timeout for each request in milliseconds (default 10000)
+///     tcp_keepalive_ms: Interval of TCP keepalive probes in milliseconds (default None, disabled)
+///     max_idle_connections: Maximum number of idle connections kept open (default None,
+///                           unlimited)
+///     user_agent: User-Agent header sent with every request, instead of reqwest's default
+///                 (default None)
+///     extra_headers: Dict of headers added to every request, such as those a gateway identifies
+///                    clients by. The X-BAPI-* signing headers can't be set (default None)
+///     strict_range: Raise instead of warning when start_time is older than the trade endpoint's
+///                   7-day retention (default False)
+///     strict: Raise instead of sorting when the pages arrive out of timestamp order (default
+///             False)
+///     deterministic: Sort the trades by (timestamp, exec_id) so identical inputs always yield
+///                    the same order (default False)
+///     include_block_trades: Keep block trades (default True)
+///     min_size: Drop trades smaller than this size (default None, keep all)
+///     side_as_int: Return side as 1 for Buy and -1 for Sell instead of the string (default False)
+///     include_turnover: Add a turnover key with price * size to each trade (default False)
+///     max_empty_pages: Number of consecutive empty pages with a next cursor after which the
+///                      pagination is considered stalled and fails with BybitError (default 10)
+///     page_delay_ms: Delay between page requests in milliseconds when Bybit sends no rate-limit
+///                    headers to pace them by. 0 removes it, which risks 429s on standard
+///                    accounts (default 50)
+///     cache_dir: Directory caching each fetched window, keyed by symbol, category, start_time,
+///                end_time and limit, so an identical fetch is read from disk (default None)
+///     force_refresh: Fetch even if the window is cached, and replace the entry (default False)
+///     time_unit: Unit of start_time, end_time and the returned timestamps of the trade fetches:
+///                "s", "ms", "us" or "ns". Without it, the timestamps are milliseconds and a
+///                start_time before 1973, which is most likely in seconds, raises ValueError
+///                (default None)
+#[pyclass(module = "hftbacktest", get_all, set_all)]
+#[derive(Debug, Clone)]
+pub struct FetchConfig {
+    pub category: String,
+    pub limit: i32,
+    pub api_key: String,
+    pub secret: String,
+    pub recv_window: u64,
+    pub max_retries: u32,
+    pub base_backoff_ms: u64,
+    pub max_backoff_ms: u64,
+    pub jitter: bool,
+    pub retry_on_5xx: bool,
+    pub base_url: String,
+    pub testnet: bool,
+    pub proxy: Option<String>,
+    pub compress: bool,
+    pub connect_timeout_ms: Option<u64>,
+    pub timeout_ms: u64,
+    pub tcp_keepalive_ms: Option<u64>,
+    pub max_idle_connections: Option<usize>,
+    pub user_agent: Option<String>,
+    pub extra_headers: Option<HashMap<String, String>>,
+    pub strict_range: bool,
+    pub strict: bool,
+    pub deterministic: bool,
+    pub include_block_trades: bool,
+    pub min_size: Option<f64>,
+    pub side_as_int: bool,
+    pub include_turnover: bool,
+    pub max_empty_pages: u32,
+    pub page_delay_ms: u64,
+    pub cache_dir: Option<String>,
+    pub force_refresh: bool,
+    pub time_unit: Option<String>,
+}
+
+impl Default for FetchConfig {
+    fn default() -> Self {
+        let retry = RetryConfig::default();
+        let http = HttpConfig::default();
+        Self {
+            category: "linear".to_string(),
+            limit: 1000,
+            api_key: String::new(),
+            secret: String::new(),
+            recv_window: DEFAULT_RECV_WINDOW,
+            max_retries: retry.max_retries,
+            base_backoff_ms: retry.base_backoff_ms,
+            max_backoff_ms: retry.max_backoff_ms,
+            jitter: retry.jitter.is_some(),
+            retry_on_5xx: retry.retry_on_5xx,
+            base_url: MAINNET_BASE_URL.to_string(),
+            testnet: false,
+            proxy: None,
+            compress: true,
+            connect_timeout_ms: http.connect_timeout_ms,
+            timeout_ms: http.timeout_ms,
+            tcp_keepalive_ms: http.tcp_keepalive_ms,
+            max_idle_connections: http.max_idle_connections,
+            user_agent: None,
+            extra_headers: None,
+            strict_range: false,
+            strict: false,
+            deterministic: false,
+            include_block_trades: true,
+            min_size: None,
+            side_as_int: false,
+            include_turnover: false,
+            max_empty_pages: DEFAULT_MAX_EMPTY_PAGES,
+            page_delay_ms: DEFAULT_PAGE_DELAY_MS,
+            cache_dir: None,
+            force_refresh: false,
+            time_unit: None,
+        }
+    }
+}
+
+impl FetchConfig {
+    /// Drops the trades filtered out by `include_block_trades` and `min_size`.
+    pub(super) fn retain(&self, trades: &mut Vec<TradeRow>) {
+        if !self.include_block_trades {
+            retain_non_block(trades);
+        }
+        if let Some(min_size) = self.min_size {
+            retain_min_size(trades, min_size);
+        }
+    }
+
+    /// Sorts trades received newest first as described in [`sort_trades`], then into the canonical
+    /// order of [`sort_canonical`] with `deterministic`.
+    pub(super) fn sort(&self, trades: &mut [TradeRow]) -> Result<(), BybitError> {
+        sort_trades(trades, self.strict)?;
+        if self.deterministic {
+            sort_canonical(trades);
+        }
+        Ok(())
+    }
+}
+
+/// Picks the API host: an explicitly given base URL other than mainnet wins, otherwise `testnet`
+/// selects between mainnet and testnet.
+pub(super) fn resolve_base_url(base_url: Option<String>, testnet: Option<bool>) -> String {
+    match base_url {
+        Some(base_url) if base_url != MAINNET_BASE_URL => base_url,
+        _ if testnet.unwrap_or(false) => TESTNET_BASE_URL.to_string(),
+        _ => MAINNET_BASE_URL.to_string(),
+    }
+}
+
+/// Signs a GET request as Bybit v5 expects: HMAC-SHA256 over
+/// `timestamp + api_key + recv_window + query_string`, hex encoded.
+fn sign_request(
+    api_key: &str,
+    secret: &str,
+    recv_window: u64,
+    query_string: &str,
+    timestamp: i64,
+) -> Result<String, BybitError> {
+    use hmac::{Hmac, Mac};
+    use sha2::Sha256;
+
+    let sign_body = format!("{}{}{}{}", timestamp, api_key, recv_window, query_string);
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+        .map_err(|_| BybitError::InvalidArg("secret".to_string()))?;
+    mac.update(sign_body.as_bytes());
+    let result = mac.finalize();
+
+    // Convert to hex string manually
+    let bytes = result.into_bytes();
+    let hex_str = bytes
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect::<String>();
+    Ok(hex_str)
+}
+
+thread_local! {
+    pub(super) static LAST_FETCH_STATS: RefCell<Option<FetchStats>> = const { RefCell::new(None) };
+}
+
+pub(crate) fn record_stats(stats: FetchStats) -> FetchStats {
+    LAST_FETCH_STATS.with(|last| *last.borrow_mut() = Some(stats.clone()));
+    stats
+}
+
+static RUNTIME: OnceLock<Runtime> = OnceLock::new();
+
+/// Returns the tokio runtime shared by all fetch calls, creating it on first use.
+pub(crate) fn runtime() -> PyResult<&'static Runtime> {
+    shared_runtime().map_err(|e| PyRuntimeError::new_err(e.to_string()))
+}
+
+fn shared_runtime() -> std::io::Result<&'static Runtime> {
+    if let Some(rt) = RUNTIME.get() {
+        return Ok(rt);
+    }
+    let rt = Runtime::new()?;
+    Ok(RUNTIME.get_or_init(|| rt))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use super::*;
+    use crate::bybit::{
+        exceptions,
+        tests::{serve_once, serve_once_from},
+    };
+
+    #[test]
+    fn test_rate_limit_status() {
+        let mut headers = HeaderMap::new();
+        headers.insert("X-Bapi-Limit-Status", "4".parse().unwrap());
+        headers.insert("X-Bapi-Limit", "10".parse().unwrap());
+        headers.insert(
+            "X-Bapi-Limit-Reset-Timestamp",
+            "1704067201000".parse().unwrap(),
+        );
+
+        let status = RateLimitStatus::from_headers(&headers).unwrap();
+        assert_eq!(
+            status,
+            RateLimitStatus {
+                remaining: 4,
+                limit: 10,
+                reset_timestamp: 1704067201000,
+            }
+        );
+        assert_eq!(status.delay(1704067200000), Duration::from_millis(250));
+        assert_eq!(status.delay(1704067202000), Duration::ZERO);
+
+        let exhausted = RateLimitStatus {
+            remaining: 0,
+            ..status
+        };
+        assert_eq!(exhausted.delay(1704067200400), Duration::from_millis(600));
+
+        headers.remove("X-Bapi-Limit");
+        assert_eq!(RateLimitStatus::from_headers(&headers), None);
+    }
+
+    #[test]
+    fn test_page_delay() {
+        let fetcher = BybitHistoryFetcher::new(String::new(), String::new(), String::new());
+        assert_eq!(fetcher.page_delay(&None), Duration::from_millis(50));
+
+        let fetcher = fetcher.with_page_delay_ms(0);
+        assert_eq!(fetcher.page_delay(&None), Duration::ZERO);
+
+        // The rate-limit headers take precedence over the fixed delay.
+        let exhausted = RateLimitStatus {
+            remaining: 0,
+            limit: 10,
+            reset_timestamp: Utc::now().timestamp_millis() + 60_000,
+        };
+        assert!(fetcher.page_delay(&Some(exhausted)) > Duration::from_secs(50));
+
+        let config = FetchConfig {
+            page_delay_ms: 5,
+            ..Default::default()
+        };
+        let fetcher = BybitHistoryFetcher::from_config(&config).unwrap();
+        assert_eq!(fetcher.page_delay(&None), Duration::from_millis(5));
+    }
+
+    #[test]
+    fn test_resolve_base_url() {
+        assert_eq!(resolve_base_url(None, None), MAINNET_BASE_URL);
+        assert_eq!(resolve_base_url(None, Some(true)), TESTNET_BASE_URL);
+        assert_eq!(
+            resolve_base_url(Some(MAINNET_BASE_URL.to_string()), Some(true)),
+            TESTNET_BASE_URL
+        );
+        assert_eq!(
+            resolve_base_url(Some("http://localhost:8080".to_string()), Some(true)),
+            "http://localhost:8080"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_token_bucket_paces_requests() {
+        let bucket = TokenBucket::new(20.0);
+        let started = Instant::now();
+        for _ in 0..20 {
+            bucket.acquire().await;
+        }
+        assert!(started.elapsed() < Duration::from_millis(40));
+
+        bucket.acquire().await;
+        assert!(started.elapsed() >= Duration::from_millis(40));
+    }
+
+    #[test]
+    fn test_runtime_is_shared() {
+        // Worker threads are only spawned once, so repeated fetches don't grow the thread count.
+        let rt = shared_runtime().unwrap();
+        for _ in 0..10 {
+            let again = shared_runtime().unwrap();
+            assert!(std::ptr::eq(rt, again));
+            again.block_on(async {});
+        }
+    }
+
+    #[tokio::test]
+    async fn test_non_json_body_is_quoted_in_the_error() {
+        let page = format!(
+            "<html><head><title>502 Bad Gateway</title></head><body>{}</body></html>",
+            "cloudflare ".repeat(50)
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { serve_once(listener, &page).await });
+
+        let fetcher = BybitHistoryFetcher::new(base_url, String::new(), String::new());
+        let result = fetcher
+            .fetch_trades("BTCUSDT", 0, i64::MAX, &FetchConfig::default())
+            .await;
+        let Err(BybitError::Parse(msg)) = result else {
+            panic!("expected a parse error");
+        };
+        let (_, snippet) = msg.split_once("body: ").unwrap();
+        assert!(snippet.starts_with("<html><head><title>502 Bad Gateway</title>"));
+        assert_eq!(snippet.chars().count(), BODY_SNIPPET_CHARS + 3);
+
+        assert_eq!(body_snippet("{}"), "{}");
+        assert_eq!(
+            body_snippet(&"é".repeat(300)),
+            format!("{}...", "é".repeat(256))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_compression_is_negotiated() {
+        let body = r#"{"retCode":0,"retMsg":"OK","result":{"list":[{"execId":"1","symbol":"BTCUSDT",
+            "price":"42000.5","size":"0.1","side":"Buy","time":"1704067200000",
+            "isBlockTrade":false}],"nextPageCursor":null}}"#;
+
+        for compress in [true, false] {
+            let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let base_url = format!("http://{}", listener.local_addr().unwrap());
+            let server = tokio::spawn(async move { serve_once(listener, body).await });
+
+            let fetcher = BybitHistoryFetcher::new(base_url, String::new(), String::new())
+                .with_compression(compress)
+                .unwrap();
+            let mut trades = Vec::new();
+            fetcher
+                .fetch_trade_pages("linear", "BTCUSDT", 0, 1, 1000, |page, _| {
+                    trades.extend(page);
+                    Ok(())
+                })
+                .await
+                .unwrap();
+            let request = server.await.unwrap();
+
+            assert_eq!(trades.len(), 1);
+            assert_eq!(trades[0].price, 42000.5);
+            let accept_encoding = request
+                .lines()
+                .find_map(|line| line.strip_prefix("accept-encoding:"))
+                .unwrap_or_default();
+            assert_eq!(accept_encoding.contains("gzip"), compress);
+            assert_eq!(accept_encoding.contains("br"), compress);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_server_errors_are_retried_on_the_same_page() {
+        let page1 = r#"{"retCode":0,"retMsg":"OK","result":{"list":[
+            {"execId":"2","symbol":"BTCUSDT","price":"100","size":"0.1","side":"Buy","time":"1704067200002","isBlockTrade":false}],"nextPageCursor":"page2"}}"#;
+        let page2 = r#"{"retCode":0,"retMsg":"OK","result":{"list":[
+            {"execId":"1","symbol":"BTCUSDT","price":"99","size":"0.1","side":"Sell","time":"1704067200001","isBlockTrade":false}],"nextPageCursor":""}}"#;
+        let responses = [
+            ("200 OK", page1),
+            ("502 Bad Gateway", "bad gateway"),
+            ("503 Service Unavailable", "unavailable"),
+            ("200 OK", page2),
+            ("403 Forbidden", "forbidden"),
+        ];
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+            let mut requests = Vec::new();
+            for (status, body) in responses {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut buf = vec![0; 4096];
+                let n = socket.read(&mut buf).await.unwrap();
+                let response = format!(
+                    "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                );
+                socket.write_all(response.as_bytes()).await.unwrap();
+                requests.push(String::from_utf8_lossy(&buf[..n]).to_string());
+            }
+            requests
+        });
+
+        let fetcher = BybitHistoryFetcher::new(base_url, String::new(), String::new())
+            .with_retry(RetryConfig::new(Some(2), Some(1), None));
+        let mut timestamps = Vec::new();
+        let stats = fetcher
+            .fetch_trade_pages("linear", "BTCUSDT", 0, 1, 1000, |page, _| {
+                timestamps.extend(page.iter().map(|t| t.timestamp));
+                Ok(())
+            })
+            .await
+            .unwrap();
+        assert_eq!(timestamps, vec![1704067200002, 1704067200001]);
+        assert_eq!(stats.requests, 4);
+        assert_eq!(stats.retries, 2);
+
+        // A 4xx is not retried.
+        let result = fetcher
+            .fetch_trade_pages("linear", "BTCUSDT", 0, 1, 1000, |_, _| Ok(()))
+            .await;
+        assert!(matches!(
+            result,
+            Err(BybitError::Http(StatusCode::FORBIDDEN))
+        ));
+
+        let requests = server.await.unwrap();
+        assert!(!requests[0].contains("cursor="));
+        for request in &requests[1..4] {
+            assert!(request.contains("cursor=page2"));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_server_errors_fail_without_retry_on_5xx() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = vec![0; 4096];
+            let _ = socket.read(&mut buf).await.unwrap();
+            socket
+                .write_all(
+                    b"HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                )
+                .await
+                .unwrap();
+        });
+
+        let config = FetchConfig {
+            base_url,
+            jitter: false,
+            retry_on_5xx: false,
+            ..FetchConfig::default()
+        };
+        let fetcher = BybitHistoryFetcher::from_config(&config).unwrap();
+        assert!(fetcher.retry.jitter.is_none());
+        let result = fetcher
+            .fetch_trade_pages("linear", "BTCUSDT", 0, 1, 1000, |_, _| Ok(()))
+            .await;
+        assert!(matches!(
+            result,
+            Err(BybitError::Http(StatusCode::SERVICE_UNAVAILABLE))
+        ));
+        server.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_dropped_connection_is_retried() {
+        let page = r#"{"retCode":0,"retMsg":"OK","result":{"list":[
+            {"execId":"1","symbol":"BTCUSDT","price":"100","size":"0.1","side":"Buy","time":"1704067200001","isBlockTrade":false}],"nextPageCursor":""}}"#;
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+            // Reads the first request and closes the connection without answering.
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = vec![0; 4096];
+            let _ = socket.read(&mut buf).await.unwrap();
+            drop(socket);
+
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let _ = socket.read(&mut buf).await.unwrap();
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                page.len(),
+                page
+            );
+            socket.write_all(response.as_bytes()).await.unwrap();
+        });
+
+        let fetcher = BybitHistoryFetcher::new(base_url, String::new(), String::new())
+            .with_retry(RetryConfig::new(Some(2), Some(1), None));
+        let mut timestamps = Vec::new();
+        let stats = fetcher
+            .fetch_trade_pages("linear", "BTCUSDT", 0, 1, 1000, |page, _| {
+                timestamps.extend(page.iter().map(|t| t.timestamp));
+                Ok(())
+            })
+            .await
+            .unwrap();
+        assert_eq!(timestamps, vec![1704067200001]);
+        assert_eq!(stats.requests, 2);
+        assert_eq!(stats.retries, 1);
+        server.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_failures_map_to_python_exceptions() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = vec![0; 4096];
+            let _ = socket.read(&mut buf).await.unwrap();
+            socket
+                .write_all(
+                    b"HTTP/1.1 429 Too Many Requests\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                )
+                .await
+                .unwrap();
+            serve_once_from(
+                &listener,
+                r#"{"retCode":10002,"retMsg":"invalid request","result":{}}"#,
+            )
+            .await
+        });
+
+        let fetcher = BybitHistoryFetcher::new(base_url, String::new(), String::new())
+            .with_retry(RetryConfig::new(Some(0), Some(1), None));
+        let rate_limited = fetcher
+            .fetch_trade_pages("linear", "BTCUSDT", 0, 1, 1000, |_, _| Ok(()))
+            .await
+            .unwrap_err();
+        assert!(matches!(rate_limited, BybitError::RateLimited));
+        let rejected = fetcher
+            .fetch_trade_pages("linear", "BTCUSDT", 0, 1, 1000, |_, _| Ok(()))
+            .await
+            .unwrap_err();
+        assert!(matches!(rejected, BybitError::Api { code: 10002, .. }));
+        server.await.unwrap();
+
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let err = PyErr::from(rate_limited);
+            assert!(err.is_instance_of::<exceptions::BybitRateLimitError>(py));
+            assert!(!err.is_instance_of::<exceptions::BybitApiError>(py));
+            // Both stay catchable as the base exception.
+            assert!(err.is_instance_of::<exceptions::BybitError>(py));
+
+            let err = PyErr::from(rejected);
+            assert!(err.is_instance_of::<exceptions::BybitApiError>(py));
+            assert!(!err.is_instance_of::<exceptions::BybitRateLimitError>(py));
+            assert!(err.is_instance_of::<exceptions::BybitError>(py));
+            assert!(err.to_string().contains("10002"));
+        });
+    }
+
+    #[tokio::test]
+    async fn test_invalid_url_fails_without_retry() {
+        let fetcher =
+            BybitHistoryFetcher::new("http://[invalid".to_string(), String::new(), String::new())
+                .with_retry(RetryConfig::new(Some(3), Some(1), None));
+        let mut stats = FetchStats::default();
+        let result = fetcher
+            .get::<serde_json::Value>("/v5/market/recent-trade", "category=linear", &mut stats)
+            .await;
+        assert!(matches!(result, Err(BybitError::Transport(_))));
+        assert_eq!(stats.requests, 1);
+        assert_eq!(stats.retries, 0);
+    }
+
+    #[test]
+    fn test_redact_url() {
+        assert_eq!(
+            redact_url("https://api.example.com/v1/trades?symbol=BTC&apiKey=k&signature=s"),
+            "https://api.example.com/v1/trades?symbol=BTC&apiKey=REDACTED&signature=REDACTED"
+        );
+        assert_eq!(
+            redact_url("https://api.bybit.com/v5/market/trades?category=linear&symbol=BTCUSDT"),
+            "https://api.bybit.com/v5/market/trades?category=linear&symbol=BTCUSDT"
+        );
+        assert_eq!(redact_url("https://api.bybit.com"), "https://api.bybit.com");
+    }
+
+    #[derive(Clone, Default)]
+    struct LogBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for LogBuffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_debug_logs_leave_out_credentials() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+            let mut requests = Vec::new();
+            for response in [
+                "HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                    .to_string(),
+                {
+                    let body = r#"{"retCode":0,"retMsg":"OK","result":{"category":"linear","list":[{"execId":"1","symbol":"BTCUSDT","price":"42000","size":"0.1","side":"Buy","time":"1000","isBlockTrade":false}]}}"#;
+                    format!(
+                        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        body.len(),
+                        body
+                    )
+                },
+            ] {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut buf = vec![0; 4096];
+                let n = socket.read(&mut buf).await.unwrap();
+                requests.push(String::from_utf8_lossy(&buf[..n]).to_string());
+                socket.write_all(response.as_bytes()).await.unwrap();
+            }
+            requests
+        });
+
+        let logs = LogBuffer::default();
+        let writer = logs.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::DEBUG)
+            .with_ansi(false)
+            .with_writer(move || writer.clone())
+            .finish();
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let fetcher =
+            BybitHistoryFetcher::new(base_url, "my-api-key".to_string(), "my-secret".to_string())
+                .with_retry(RetryConfig::new(Some(1), Some(1), Some(1)));
+        fetcher
+            .fetch_trade_pages("linear", "BTCUSDT", 0, 2000, 1000, |_, _| Ok(()))
+            .await
+            .unwrap();
+
+        let requests = server.await.unwrap();
+        let signature = requests[1]
+            .lines()
+            .find_map(|line| line.strip_prefix("x-bapi-sign: "))
+            .unwrap()
+            .trim()
+            .to_string();
+        let logs = String::from_utf8(logs.0.lock().unwrap().clone()).unwrap();
+        assert!(logs.contains("fetch_trades"));
+        assert!(logs.contains("/v5/market/trades?category=linear&symbol=BTCUSDT"));
+        assert!(logs.contains("server error, backing off"));
+        assert!(logs.contains("trades=1"));
+        assert!(!logs.contains("my-api-key"));
+        assert!(!logs.contains("my-secret"));
+        assert!(!logs.contains(&signature));
+    }
+
+    #[tokio::test]
+    async fn test_request_timeout() {
+        // Accepts the connection but never answers.
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let mut sockets = Vec::new();
+            for _ in 0..3 {
+                sockets.push(listener.accept().await.unwrap());
+            }
+            sockets
+        });
+
+        let fetcher = BybitHistoryFetcher::new(base_url, String::new(), String::new())
+            .with_retry(RetryConfig::new(Some(2), Some(10), None))
+            .with_http_config(HttpConfig::new(None, Some(100), None, None))
+            .unwrap();
+        let result = fetcher
+            .fetch_trade_pages("linear", "BTCUSDT", 0, 1, 1000, |_, _| Ok(()))
+            .await;
+
+        // The first attempt and both retries time out.
+        assert!(matches!(result, Err(BybitError::Timeout(_))));
+        assert!(BybitError::Timeout(String::new()).is_transient());
+        assert_eq!(server.await.unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_with_http_config() {
+        let fetcher = || BybitHistoryFetcher::new(String::new(), String::new(), String::new());
+
+        assert!(
+            fetcher()
+                .with_http_config(HttpConfig::new(
+                    Some(2_000),
+                    Some(30_000),
+                    Some(60_000),
+                    Some(4)
+                ))
+                .is_ok()
+        );
+        assert!(matches!(
+            fetcher().with_http_config(HttpConfig::new(None, Some(0), None, None)),
+            Err(BybitError::InvalidArg(_))
+        ));
+        assert!(matches!(
+            fetcher().with_http_config(HttpConfig::new(Some(0), None, None, None)),
+            Err(BybitError::InvalidArg(_))
+        ));
+        assert_eq!(HttpConfig::new(None, None, None, None).timeout_ms, 10_000);
+    }
+
+    #[test]
+    fn test_with_proxy() {
+        let fetcher =
+            BybitHistoryFetcher::new(MAINNET_BASE_URL.to_string(), String::new(), String::new());
+        assert!(fetcher.clone().with_proxy(None).is_ok());
+        assert!(
+            fetcher
+                .clone()
+                .with_proxy(Some("http://127.0.0.1:8080"))
+                .is_ok()
+        );
+        assert!(
+            fetcher
+                .clone()
+                .with_proxy(Some("socks5://127.0.0.1:1080"))
+                .is_ok()
+        );
+        let err = fetcher.with_proxy(Some("ftp://127.0.0.1:21")).unwrap_err();
+        assert!(matches!(err, BybitError::InvalidArg(_)));
+        assert!(err.to_string().contains("ftp://127.0.0.1:21"));
+    }
+
+    #[tokio::test]
+    async fn test_sends_extra_headers() {
+        let body = r#"{"retCode":0,"retMsg":"OK","result":{"list":[],"nextPageCursor":""}}"#;
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move { serve_once(listener, body).await });
+        let extra_headers = HashMap::from([
+            ("X-Client-Id".to_string(), "research-1".to_string()),
+            ("User-Agent".to_string(), "overridden".to_string()),
+        ]);
+        BybitHistoryFetcher::new(base_url, String::new(), String::new())
+            .with_headers(Some("hftbacktest-research/1.0"), Some(&extra_headers))
+            .unwrap()
+            .fetch_trades("BTCUSDT", 0, 2000, &FetchConfig::default())
+            .await
+            .unwrap();
+        let request = server.await.unwrap();
+        assert!(request.contains("\r\nx-client-id: research-1\r\n"));
+        assert!(request.contains("\r\nuser-agent: hftbacktest-research/1.0\r\n"));
+        assert!(!request.contains("overridden"));
+
+        let fetcher =
+            BybitHistoryFetcher::new(MAINNET_BASE_URL.to_string(), String::new(), String::new());
+        let signing = HashMap::from([("X-BAPI-SIGN".to_string(), "forged".to_string())]);
+        let err = fetcher
+            .clone()
+            .with_headers(None, Some(&signing))
+            .unwrap_err();
+        assert!(matches!(err, BybitError::InvalidArg(_)));
+        assert!(err.to_string().contains("x-bapi-sign"));
+        let invalid = HashMap::from([("X-Client-Id".to_string(), "a\nb".to_string())]);
+        assert!(fetcher.with_headers(None, Some(&invalid)).is_err());
+    }
+
+    #[test]
+    fn test_retry_backoff_is_capped() {
+        let retry = RetryConfig {
+            jitter: None,
+            ..RetryConfig::new(None, Some(100), Some(1000))
+        };
+        assert_eq!(retry.max_retries, 5);
+        assert_eq!(retry.backoff(1), Duration::from_millis(100));
+        assert_eq!(retry.backoff(2), Duration::from_millis(200));
+        assert_eq!(retry.backoff(4), Duration::from_millis(800));
+        assert_eq!(retry.backoff(5), Duration::from_millis(1000));
+        assert_eq!(retry.backoff(100), Duration::from_millis(1000));
+    }
+
+    #[test]
+    fn test_retry_backoff_jitter() {
+        let retry = RetryConfig::new(None, Some(100), Some(1000)).with_jitter_seed(42);
+        let waits = (1..=200)
+            .map(|retry_no| {
+                let wait = retry.backoff(retry_no % 6 + 1);
+                let cap = 100 << (retry_no % 6);
+                assert!(wait <= Duration::from_millis(cap.min(1000)));
+                wait
+            })
+            .collect::<Vec<_>>();
+        assert!(waits.windows(2).any(|w| w[0] != w[1]));
+
+        let same_seed = RetryConfig::new(None, Some(100), Some(1000)).with_jitter_seed(42);
+        assert_eq!(
+            (1..=200)
+                .map(|retry_no| same_seed.backoff(retry_no % 6 + 1))
+                .collect::<Vec<_>>(),
+            waits
+        );
+    }
+
+    #[tokio::test]
+    async fn test_signs_only_with_credentials() {
+        let body = r#"{"retCode":0,"retMsg":"OK","result":{"list":[],"nextPageCursor":""}}"#;
+        let request = |api_key: &str, secret: &str| {
+            let (api_key, secret) = (api_key.to_string(), secret.to_string());
+            async move {
+                let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+                let base_url = format!("http://{}", listener.local_addr().unwrap());
+                // Read raw rather than through `serve_once`, which lowercases the signed query.
+                let server = tokio::spawn(async move {
+                    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+                    let (mut socket, _) = listener.accept().await.unwrap();
+                    let mut buf = vec![0; 4096];
+                    let n = socket.read(&mut buf).await.unwrap();
+                    let response = format!(
+                        "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        body.len(),
+                        body
+                    );
+                    socket.write_all(response.as_bytes()).await.unwrap();
+                    String::from_utf8_lossy(&buf[..n]).to_string()
+                });
+                BybitHistoryFetcher::new(base_url, api_key, secret)
+                    .fetch_trades("BTCUSDT", 0, 2000, &FetchConfig::default())
+                    .await
+                    .unwrap();
+                server.await.unwrap()
+            }
+        };
+
+        let public = request("", "").await.to_lowercase();
+        assert!(!public.contains("x-bapi-"));
+
+        let signed = request("my-api-key", "my-secret").await;
+        let header = |name: &str| {
+            signed
+                .lines()
+                .find_map(|line| line.strip_prefix(&format!("{}: ", name)))
+                .map(|value| value.trim().to_string())
+                .unwrap()
+        };
+        assert_eq!(header("x-bapi-api-key"), "my-api-key");
+        assert_eq!(
+            header("x-bapi-recv-window"),
+            DEFAULT_RECV_WINDOW.to_string()
+        );
+        let timestamp = header("x-bapi-timestamp").parse().unwrap();
+        let query_string = signed
+            .split_whitespace()
+            .nth(1)
+            .and_then(|target| target.split_once('?'))
+            .map(|(_, query_string)| query_string.to_string())
+            .unwrap();
+        assert_eq!(
+            header("x-bapi-sign"),
+            sign_request(
+                "my-api-key",
+                "my-secret",
+                DEFAULT_RECV_WINDOW,
+                &query_string,
+                timestamp
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_sign_request() {
+        // Pre-sign string: 1658384314791XXXXXXXXXX5000category=option&symbol=BTC-29JUL22-25000-C
+        let signature = sign_request(
+            "XXXXXXXXXX",
+            "secret",
+            5000,
+            "category=option&symbol=BTC-29JUL22-25000-C",
+            1658384314791,
+        )
+        .unwrap();
+        assert_eq!(
+            signature,
+            "02e9182e346177050f199ce1e0703d738589e3763805ed71590ced65539a73a7"
+        );
+    }
+
+    #[test]
+    fn test_sign_request_depends_on_api_key_and_recv_window() {
+        let query_string = "category=linear&symbol=BTCUSDT";
+        let signature = sign_request("key", "secret", 5000, query_string, 1658384314791).unwrap();
+        assert_ne!(
+            signature,
+            sign_request("other", "secret", 5000, query_string, 1658384314791).unwrap()
+        );
+        assert_ne!(
+            signature,
+            sign_request("key", "secret", 10000, query_string, 1658384314791).unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fetch_config() {
+        let page = r#"{"retCode":0,"retMsg":"OK","result":{"list":[
+            {"execId":"c","symbol":"BTCUSDT","price":"100","size":"5.0","side":"Buy","time":"1704067200003","isBlockTrade":true},
+            {"execId":"b","symbol":"BTCUSDT","price":"100","size":"0.1","side":"Sell","time":"1704067200002","isBlockTrade":false},
+            {"execId":"a","symbol":"BTCUSDT","price":"99","size":"1.0","side":"Sell","time":"1704067200001","isBlockTrade":false}],"nextPageCursor":""}}"#;
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let config = FetchConfig {
+            base_url: format!("http://{}", listener.local_addr().unwrap()),
+            include_block_trades: false,
+            min_size: Some(1.0),
+            ..FetchConfig::default()
+        };
+        tokio::spawn(serve_once(listener, page));
+
+        let fetcher = BybitHistoryFetcher::from_config(&config).unwrap();
+        let (trades, _) = fetcher
+            .fetch_trades("BTCUSDT", 0, i64::MAX, &config)
+            .await
+            .unwrap();
+        assert_eq!(
+            trades
+                .iter()
+                .map(|t| t.exec_id.as_str())
+                .collect::<Vec<_>>(),
+            vec!["a"]
+        );
+
+        let config = FetchConfig {
+            proxy: Some("ftp://127.0.0.1:21".to_string()),
+            ..FetchConfig::default()
+        };
+        assert!(matches!(
+            BybitHistoryFetcher::from_config(&config),
+            Err(BybitError::InvalidArg(_))
+        ));
+    }
+}
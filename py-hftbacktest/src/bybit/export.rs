@@ -0,0 +1,1386 @@
+//! Writers turning fetched trades into Parquet, CSV and `.npz` files, the raw response dump, the
+//! on-disk trade cache, and the daily and aggregated fetches built on them.
+
+use std::{
+    collections::{BTreeMap, HashSet},
+    fs::File,
+    io::{BufWriter, Read, Seek, SeekFrom, Write},
+    mem::size_of,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use arrow_array::{
+    ArrayRef, DictionaryArray, Float64Array, Int8Array, Int64Array, RecordBatch, types::Int32Type,
+};
+use arrow_schema::{DataType, Field, Schema};
+use chrono::{NaiveDate, NaiveTime};
+use hftbacktest::{backtest::data::write_npy_header, types::Event};
+use parquet::{
+    arrow::ArrowWriter,
+    basic::{Compression, ZstdLevel},
+    file::properties::WriterProperties,
+};
+use pyo3::prelude::*;
+use serde::{Deserialize, Serialize};
+use zip::{ZipWriter, write::SimpleFileOptions};
+
+use super::{
+    BybitError, BybitResponse, FetchStats, TradeResult, TradeRow,
+    client::{BybitHistoryFetcher, FetchConfig},
+    pagination::{append_page, sort_trades},
+    validate_range,
+};
+use crate::latency::LatencyInjector;
+
+impl BybitHistoryFetcher {
+    /// Fetches the trades of each UTC day from `start_date` to `end_date`, both inclusive, into
+    /// its own `{symbol}_{YYYYMMDD}` file under `out_dir`, with the `category`, `limit` and
+    /// filters of `config`. Days are half-open, so a trade at 23:59:59.999 is written to its day
+    /// and one at 00:00:00.000 to the next.
+    ///
+    /// A day whose file already exists is skipped unless `overwrite` is set. Each file is written
+    /// under a `.part` name and renamed once complete, so a day interrupted by an error is fetched
+    /// again by the next run instead of being skipped; the days completed before the error keep
+    /// their files.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn fetch_trades_daily(
+        &self,
+        symbol: &str,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+        out_dir: &Path,
+        format: DailyFormat,
+        overwrite: bool,
+        feed_latency: i64,
+        config: &FetchConfig,
+    ) -> Result<(Vec<DaySummary>, FetchStats), BybitError> {
+        if start_date > end_date {
+            return Err(BybitError::InvalidArg(format!(
+                "start_date {} is after end_date {}",
+                start_date, end_date
+            )));
+        }
+        std::fs::create_dir_all(out_dir).map_err(|e| {
+            BybitError::Io(format!("failed to create {}: {}", out_dir.display(), e))
+        })?;
+
+        let mut summaries = Vec::new();
+        let mut stats = FetchStats::default();
+        for date in start_date.iter_days().take_while(|date| *date <= end_date) {
+            let path = out_dir
+                .join(format!(
+                    "{}_{}.{}",
+                    symbol,
+                    date.format("%Y%m%d"),
+                    format.extension()
+                ))
+                .to_string_lossy()
+                .into_owned();
+            let mut summary = DaySummary {
+                date: date.to_string(),
+                path: path.clone(),
+                skipped: false,
+                trades: 0,
+                first_timestamp: None,
+                last_timestamp: None,
+            };
+            if !overwrite && Path::new(&path).exists() {
+                summary.skipped = true;
+                summaries.push(summary);
+                continue;
+            }
+
+            let part_path = format!("{}.part", path);
+            let (start_time, end_time) = day_range(date);
+            let result = async {
+                let mut writer = DayWriter::create(&part_path, format, feed_latency)?;
+                let day_stats = self
+                    .fetch_trade_pages(
+                        &config.category,
+                        symbol,
+                        start_time,
+                        end_time,
+                        config.limit,
+                        |mut page, _| {
+                            page.retain(|t| (start_time..end_time).contains(&t.timestamp));
+                            config.retain(&mut page);
+                            for trade in &page {
+                                summary.first_timestamp = Some(
+                                    summary
+                                        .first_timestamp
+                                        .map_or(trade.timestamp, |ts| ts.min(trade.timestamp)),
+                                );
+                                summary.last_timestamp = Some(
+                                    summary
+                                        .last_timestamp
+                                        .map_or(trade.timestamp, |ts| ts.max(trade.timestamp)),
+                                );
+                            }
+                            writer.write(&page)
+                        },
+                    )
+                    .await?;
+                let trades = writer.finish()?;
+                std::fs::rename(&part_path, &path).map_err(|e| {
+                    BybitError::Io(format!("failed to rename {}: {}", part_path, e))
+                })?;
+                Ok::<_, BybitError>((trades, day_stats))
+            }
+            .await;
+            let (trades, day_stats) = result.inspect_err(|_| {
+                let _ = std::fs::remove_file(&part_path);
+            })?;
+            summary.trades = trades;
+            stats.merge(&day_stats);
+            summaries.push(summary);
+        }
+        stats.trades = summaries.iter().map(|summary| summary.trades).sum();
+        Ok((summaries, stats))
+    }
+
+    /// Fetches the trades in the half-open window `[start_time, end_time)` with the `category`,
+    /// `limit` and filters of `config`, and aggregates them into `bucket_ms` buckets as they
+    /// arrive, so that only the buckets are kept in memory. See [`TradeAggregator`].
+    pub async fn fetch_trades_aggregated(
+        &self,
+        symbol: &str,
+        start_time: i64,
+        end_time: i64,
+        bucket_ms: i64,
+        config: &FetchConfig,
+    ) -> Result<(Vec<TradeBucket>, FetchStats), BybitError> {
+        validate_range(start_time, end_time)?;
+        let mut aggregator = TradeAggregator::new(bucket_ms)?;
+        let stats = self
+            .fetch_trade_pages(
+                &config.category,
+                symbol,
+                start_time,
+                end_time,
+                config.limit,
+                |mut page, _| {
+                    page.retain(|t| (start_time..end_time).contains(&t.timestamp));
+                    config.retain(&mut page);
+                    aggregator.add(&page);
+                    Ok(())
+                },
+            )
+            .await?;
+        Ok((aggregator.finish(), stats))
+    }
+}
+
+/// Default number of trades per Parquet row group.
+pub const PARQUET_ROW_GROUP_SIZE: usize = 100_000;
+
+/// Parses the name of a Parquet compression codec: `snappy`, `zstd`, or `none`.
+fn parse_compression(compression: &str) -> Result<Compression, BybitError> {
+    match compression {
+        "snappy" => Ok(Compression::SNAPPY),
+        "zstd" => Ok(Compression::ZSTD(ZstdLevel::default())),
+        "none" => Ok(Compression::UNCOMPRESSED),
+        _ => Err(BybitError::InvalidArg(format!(
+            "compression {} (expected snappy, zstd or none)",
+            compression
+        ))),
+    }
+}
+
+/// Writes [`TradeRow`]s to a Parquet file incrementally. Rows are buffered until
+/// `row_group_size` of them are pending and then flushed as a row group, so memory stays bounded
+/// by the row group size rather than the number of trades.
+pub struct TradeParquetWriter {
+    path: String,
+    writer: ArrowWriter<File>,
+    schema: Arc<Schema>,
+    num_rows: usize,
+}
+
+impl TradeParquetWriter {
+    pub fn create(
+        path: &str,
+        compression: &str,
+        row_group_size: usize,
+    ) -> Result<Self, BybitError> {
+        if row_group_size == 0 {
+            return Err(BybitError::InvalidArg(
+                "row_group_size must be positive".to_string(),
+            ));
+        }
+        let props = WriterProperties::builder()
+            .set_compression(parse_compression(compression)?)
+            .set_max_row_group_size(row_group_size)
+            .build();
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("timestamp", DataType::Int64, false),
+            Field::new(
+                "symbol",
+                DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8)),
+                false,
+            ),
+            Field::new("side", DataType::Int8, false),
+            Field::new("price", DataType::Float64, false),
+            Field::new("size", DataType::Float64, false),
+        ]));
+        let file = File::create(path)
+            .map_err(|e| BybitError::Io(format!("failed to create {}: {}", path, e)))?;
+        let writer = ArrowWriter::try_new(file, schema.clone(), Some(props))
+            .map_err(|e| BybitError::Io(format!("failed to create Parquet writer: {}", e)))?;
+        Ok(Self {
+            path: path.to_string(),
+            writer,
+            schema,
+            num_rows: 0,
+        })
+    }
+
+    pub fn write(&mut self, trades: &[TradeRow]) -> Result<(), BybitError> {
+        if trades.is_empty() {
+            return Ok(());
+        }
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(Int64Array::from_iter_values(
+                trades.iter().map(|t| t.timestamp),
+            )),
+            Arc::new(
+                trades
+                    .iter()
+                    .map(|t| t.symbol.as_str())
+                    .collect::<DictionaryArray<Int32Type>>(),
+            ),
+            Arc::new(Int8Array::from_iter_values(
+                trades.iter().map(TradeRow::side_sign),
+            )),
+            Arc::new(Float64Array::from_iter_values(
+                trades.iter().map(|t| t.price),
+            )),
+            Arc::new(Float64Array::from_iter_values(
+                trades.iter().map(|t| t.size),
+            )),
+        ];
+        let batch = RecordBatch::try_new(self.schema.clone(), columns)
+            .map_err(|e| BybitError::Io(format!("failed to build record batch: {}", e)))?;
+        self.writer
+            .write(&batch)
+            .map_err(|e| BybitError::Io(format!("failed to write Parquet: {}", e)))?;
+        self.num_rows += trades.len();
+        Ok(())
+    }
+
+    /// Flushes the pending rows, writes the file footer and returns the number of rows written
+    /// and the size of the file in bytes.
+    pub fn close(self) -> Result<(usize, u64), BybitError> {
+        self.writer
+            .close()
+            .map_err(|e| BybitError::Io(format!("failed to close Parquet writer: {}", e)))?;
+        let file_size = std::fs::metadata(&self.path)
+            .map_err(|e| BybitError::Io(format!("failed to stat {}: {}", self.path, e)))?
+            .len();
+        Ok((self.num_rows, file_size))
+    }
+}
+
+/// Writes [`TradeRow`]s to a CSV file as `timestamp,symbol,side,size,price` rows, one page at
+/// a time.
+pub struct TradeCsvWriter {
+    writer: csv::Writer<File>,
+    num_rows: usize,
+}
+
+impl TradeCsvWriter {
+    /// Creates the file with a header row, or, if `append` is set, appends to it and only writes
+    /// the header if the file is new or empty.
+    pub fn create(path: &str, append: bool) -> Result<Self, BybitError> {
+        let file = File::options()
+            .create(true)
+            .write(true)
+            .append(append)
+            .truncate(!append)
+            .open(path)
+            .map_err(|e| BybitError::Io(format!("failed to open {}: {}", path, e)))?;
+        let is_empty = file
+            .metadata()
+            .map_err(|e| BybitError::Io(format!("failed to stat {}: {}", path, e)))?
+            .len()
+            == 0;
+        let mut writer = csv::Writer::from_writer(file);
+        if is_empty {
+            writer
+                .write_record(["timestamp", "symbol", "side", "size", "price"])
+                .map_err(|e| BybitError::Io(format!("failed to write CSV: {}", e)))?;
+        }
+        Ok(Self {
+            writer,
+            num_rows: 0,
+        })
+    }
+
+    pub fn write(&mut self, trades: &[TradeRow]) -> Result<(), BybitError> {
+        for trade in trades {
+            self.writer
+                .write_record([
+                    trade.timestamp.to_string(),
+                    trade.symbol.clone(),
+                    trade.side.clone(),
+                    trade.size.to_string(),
+                    trade.price.to_string(),
+                ])
+                .map_err(|e| BybitError::Io(format!("failed to write CSV: {}", e)))?;
+        }
+        self.writer
+            .flush()
+            .map_err(|e| BybitError::Io(format!("failed to write CSV: {}", e)))?;
+        self.num_rows += trades.len();
+        Ok(())
+    }
+
+    /// Returns the number of rows written, excluding the header.
+    pub fn close(mut self) -> Result<usize, BybitError> {
+        self.writer
+            .flush()
+            .map_err(|e| BybitError::Io(format!("failed to write CSV: {}", e)))?;
+        Ok(self.num_rows)
+    }
+}
+
+/// A raw API response as stored in a [`RawDump`].
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RawRecord {
+    /// Request URL, including the query string.
+    pub url: String,
+    /// Time the response was received, in milliseconds.
+    pub received_at: i64,
+    /// Response body as received, which may not be valid JSON.
+    pub body: String,
+}
+
+/// Appends raw API responses to a file as newline-delimited JSON [`RawRecord`]s. Each record is
+/// compressed as a separate zstd frame, so the file stays readable up to the last complete record
+/// if the process dies mid-write, and `zstd -dc` prints it as plain NDJSON.
+#[derive(Debug)]
+pub struct RawDump {
+    path: String,
+    file: Mutex<File>,
+}
+
+impl RawDump {
+    /// Opens `path` for appending, creating it if needed.
+    pub fn open(path: &str) -> Result<Self, BybitError> {
+        let file = File::options()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| BybitError::Io(format!("failed to open {}: {}", path, e)))?;
+        Ok(Self {
+            path: path.to_string(),
+            file: Mutex::new(file),
+        })
+    }
+
+    pub fn write(&self, url: &str, received_at: i64, body: &str) -> Result<(), BybitError> {
+        let mut line = serde_json::to_vec(&RawRecord {
+            url: url.to_string(),
+            received_at,
+            body: body.to_string(),
+        })
+        .map_err(|e| BybitError::Io(format!("failed to encode record: {}", e)))?;
+        line.push(b'\n');
+        let frame = zstd::encode_all(line.as_slice(), 0)
+            .map_err(|e| BybitError::Io(format!("failed to compress record: {}", e)))?;
+        self.file
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .write_all(&frame)
+            .map_err(|e| BybitError::Io(format!("failed to write {}: {}", self.path, e)))
+    }
+
+    /// Reads back all records of the dump at `path`.
+    pub fn read(path: &str) -> Result<Vec<RawRecord>, BybitError> {
+        let file = File::open(path)
+            .map_err(|e| BybitError::Io(format!("failed to open {}: {}", path, e)))?;
+        let data = zstd::decode_all(file)
+            .map_err(|e| BybitError::Io(format!("failed to decompress {}: {}", path, e)))?;
+        data.split(|b| *b == b'\n')
+            .filter(|line| !line.is_empty())
+            .map(|line| {
+                serde_json::from_slice(line)
+                    .map_err(|e| BybitError::Parse(format!("record in {}: {}", path, e)))
+            })
+            .collect()
+    }
+}
+
+/// Re-parses the trade pages of the dump at `path` with the same parser and deduplication as a
+/// live fetch, returning the trades sorted as described in [`sort_trades`]. Records of other
+/// endpoints are skipped.
+pub fn replay_raw_dump_rows(path: &str) -> Result<Vec<TradeRow>, BybitError> {
+    let mut trades = Vec::new();
+    let mut prev_page_exec_ids = HashSet::new();
+    for record in RawDump::read(path)? {
+        if !record.url.contains("/v5/market/trades?") {
+            continue;
+        }
+        let page: BybitResponse<TradeResult> = serde_json::from_str(&record.body).map_err(|e| {
+            BybitError::Parse(format!(
+                "response received at {} from {}: {}",
+                record.received_at, record.url, e
+            ))
+        })?;
+        append_page(&mut trades, &mut prev_page_exec_ids, page.result.list)?;
+    }
+    sort_trades(&mut trades, false)?;
+    Ok(trades)
+}
+
+/// On-disk cache of fetched trade windows. Each window is stored as a zstd-compressed JSON array of
+/// [`TradeRow`]s, keyed by `(symbol, category, start_time, end_time, limit)` in the file name. The
+/// rows are stored sorted and before the per-call filters, so calls that only differ in
+/// `include_block_trades` or `min_size` share an entry.
+pub struct TradeCache {
+    dir: PathBuf,
+}
+
+impl TradeCache {
+    /// Opens the cache in `dir`, creating the directory if needed.
+    pub fn open(dir: &str) -> Result<Self, BybitError> {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| BybitError::Io(format!("failed to create {}: {}", dir, e)))?;
+        Ok(Self {
+            dir: PathBuf::from(dir),
+        })
+    }
+
+    pub fn path(
+        &self,
+        symbol: &str,
+        category: &str,
+        start_time: i64,
+        end_time: i64,
+        limit: i32,
+    ) -> PathBuf {
+        self.dir.join(format!(
+            "{}_{}_{}_{}_{}.json.zst",
+            symbol, category, start_time, end_time, limit
+        ))
+    }
+
+    /// Reads the entry at `path`. A missing entry, or one that fails to decode, such as a file
+    /// truncated by a crash, is a miss.
+    pub fn read(path: &Path) -> Option<Vec<TradeRow>> {
+        let file = File::open(path).ok()?;
+        let data = zstd::decode_all(file).ok()?;
+        serde_json::from_slice(&data).ok()
+    }
+
+    /// Writes the entry at `path` through a temporary file renamed into place, so readers never
+    /// see a partially written entry.
+    pub fn write(path: &Path, trades: &[TradeRow]) -> Result<(), BybitError> {
+        let json = serde_json::to_vec(trades)
+            .map_err(|e| BybitError::Io(format!("failed to encode trades: {}", e)))?;
+        let data = zstd::encode_all(json.as_slice(), 0)
+            .map_err(|e| BybitError::Io(format!("failed to compress trades: {}", e)))?;
+        let part_path = path.with_extension("zst.part");
+        std::fs::write(&part_path, data).map_err(|e| {
+            BybitError::Io(format!("failed to write {}: {}", part_path.display(), e))
+        })?;
+        std::fs::rename(&part_path, path).map_err(|e| {
+            let _ = std::fs::remove_file(&part_path);
+            BybitError::Io(format!("failed to rename {}: {}", part_path.display(), e))
+        })
+    }
+}
+
+/// Writes hftbacktest [`Event`]s to a compressed `.npz` file under the `data` key, which
+/// [`BacktestAsset`](crate::BacktestAsset) loads directly.
+///
+/// The `.npy` header must state the number of events up front, so events are spooled to a
+/// temporary file as they arrive and copied into the archive by [`finish`], in reverse order if
+/// `reverse` is set. A [`LatencyInjector`] given through [`with_latency`] sets the local
+/// timestamps during the copy, when the events are in their final order.
+///
+/// [`with_latency`]: EventNpzWriter::with_latency
+/// [`finish`]: EventNpzWriter::finish
+pub struct EventNpzWriter {
+    path: String,
+    spool_path: String,
+    spool: BufWriter<File>,
+    reverse: bool,
+    latency: Option<LatencyInjector>,
+    num_events: usize,
+}
+
+impl EventNpzWriter {
+    pub fn create(path: &str, reverse: bool) -> Result<Self, BybitError> {
+        let spool_path = format!("{}.spool", path);
+        let spool = File::create(&spool_path)
+            .map_err(|e| BybitError::Io(format!("failed to create {}: {}", spool_path, e)))?;
+        Ok(Self {
+            path: path.to_string(),
+            spool_path,
+            spool: BufWriter::new(spool),
+            reverse,
+            latency: None,
+            num_events: 0,
+        })
+    }
+
+    pub fn with_latency(mut self, latency: LatencyInjector) -> Self {
+        self.latency = Some(latency);
+        self
+    }
+
+    pub fn write(&mut self, events: &[Event]) -> Result<(), BybitError> {
+        self.spool
+            .write_all(events_as_bytes(events))
+            .map_err(|e| BybitError::Io(format!("failed to write spool: {}", e)))?;
+        self.num_events += events.len();
+        Ok(())
+    }
+
+    /// Writes the `.npz` file, removes the spool, and returns the number of events written.
+    pub fn finish(mut self) -> Result<usize, BybitError> {
+        let result = self.write_npz();
+        let _ = std::fs::remove_file(&self.spool_path);
+        result
+    }
+
+    fn write_npz(&mut self) -> Result<usize, BybitError> {
+        const BLOCK_EVENTS: usize = 16384;
+        let event_size = size_of::<Event>();
+
+        let io_err = |e: std::io::Error| BybitError::Io(format!("failed to write npz: {}", e));
+
+        self.spool.flush().map_err(io_err)?;
+        let mut spool = File::open(&self.spool_path).map_err(io_err)?;
+
+        let file = File::create(&self.path).map_err(io_err)?;
+        let mut zip = ZipWriter::new(file);
+        let options = SimpleFileOptions::default()
+            .compression_method(zip::CompressionMethod::DEFLATE)
+            .large_file(self.num_events * event_size > u32::MAX as usize);
+        zip.start_file("data.npy", options)
+            .map_err(|e| BybitError::Io(format!("failed to write npz: {}", e)))?;
+        write_npy_header::<_, Event>(&mut zip, self.num_events).map_err(io_err)?;
+
+        let empty = Event {
+            ev: 0,
+            exch_ts: 0,
+            local_ts: 0,
+            px: 0.0,
+            qty: 0.0,
+            order_id: 0,
+            ival: 0,
+            fval: 0.0,
+        };
+        let mut buf = vec![empty; BLOCK_EVENTS];
+        let mut remaining = self.num_events;
+        while remaining > 0 {
+            let n = remaining.min(BLOCK_EVENTS);
+            remaining -= n;
+            let block = &mut buf[..n];
+            if self.reverse {
+                spool
+                    .seek(SeekFrom::Start((remaining * event_size) as u64))
+                    .map_err(io_err)?;
+                spool
+                    .read_exact(events_as_bytes_mut(block))
+                    .map_err(io_err)?;
+                block.reverse();
+            } else {
+                spool
+                    .read_exact(events_as_bytes_mut(block))
+                    .map_err(io_err)?;
+            }
+            if let Some(latency) = &mut self.latency {
+                latency.inject(block);
+            }
+            zip.write_all(events_as_bytes(block)).map_err(io_err)?;
+        }
+
+        zip.finish()
+            .map_err(|e| BybitError::Io(format!("failed to write npz: {}", e)))?;
+        Ok(self.num_events)
+    }
+}
+
+/// Writes [`TradeRow`]s as trade [`Event`]s to an `.npz` file. Since Bybit returns trades newest
+/// first, the events are written in reverse to produce ascending timestamps. The local timestamps
+/// come from the [`LatencyInjector`].
+pub struct TradeNpzWriter {
+    events: EventNpzWriter,
+    grid: Option<Box<TickGrid>>,
+}
+
+impl TradeNpzWriter {
+    pub fn create(path: &str, latency: LatencyInjector) -> Result<Self, BybitError> {
+        Ok(Self {
+            events: EventNpzWriter::create(path, true)?.with_latency(latency),
+            grid: None,
+        })
+    }
+
+    /// Converts the trades with [`TradeRow::to_event_on_grid`], failing on a trade off the grid.
+    pub fn with_grid(mut self, grid: TickGrid) -> Self {
+        self.grid = Some(Box::new(grid));
+        self
+    }
+
+    pub fn write(&mut self, trades: &[TradeRow]) -> Result<(), BybitError> {
+        let events = trades
+            .iter()
+            .map(|trade| match &self.grid {
+                Some(grid) => trade.to_event_on_grid(0, grid),
+                None => Ok(trade.to_event(0)),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        self.events.write(&events)
+    }
+
+    /// Writes the `.npz` file, removes the spool, and returns the number of events written.
+    pub fn finish(self) -> Result<usize, BybitError> {
+        self.events.finish()
+    }
+}
+
+/// File formats written by [`BybitHistoryFetcher::fetch_trades_daily`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DailyFormat {
+    /// Trade events, as written by [`TradeNpzWriter`].
+    Npz,
+    /// Trade rows, as written by [`TradeParquetWriter`].
+    Parquet,
+}
+
+impl DailyFormat {
+    pub fn parse(format: &str) -> Result<Self, BybitError> {
+        match format {
+            "npz" => Ok(Self::Npz),
+            "parquet" => Ok(Self::Parquet),
+            _ => Err(BybitError::InvalidArg(format!(
+                "format {} (expected npz or parquet)",
+                format
+            ))),
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Npz => "npz",
+            Self::Parquet => "parquet",
+        }
+    }
+}
+
+/// Writer of one day's file in a [`DailyFormat`].
+enum DayWriter {
+    Npz(TradeNpzWriter),
+    Parquet(TradeParquetWriter),
+}
+
+impl DayWriter {
+    fn create(path: &str, format: DailyFormat, feed_latency: i64) -> Result<Self, BybitError> {
+        match format {
+            DailyFormat::Npz => Ok(Self::Npz(TradeNpzWriter::create(
+                path,
+                LatencyInjector::constant(feed_latency),
+            )?)),
+            DailyFormat::Parquet => Ok(Self::Parquet(TradeParquetWriter::create(
+                path,
+                "snappy",
+                PARQUET_ROW_GROUP_SIZE,
+            )?)),
+        }
+    }
+
+    fn write(&mut self, trades: &[TradeRow]) -> Result<(), BybitError> {
+        match self {
+            Self::Npz(writer) => writer.write(trades),
+            Self::Parquet(writer) => writer.write(trades),
+        }
+    }
+
+    /// Completes the file and returns the number of trades written.
+    fn finish(self) -> Result<usize, BybitError> {
+        match self {
+            Self::Npz(writer) => writer.finish(),
+            Self::Parquet(writer) => writer.close().map(|(rows, _)| rows),
+        }
+    }
+}
+
+/// Outcome of one UTC day of a daily fetch.
+#[pyclass(module = "hftbacktest", get_all)]
+#[derive(Debug, Clone)]
+pub struct DaySummary {
+    /// The UTC day, as YYYY-MM-DD.
+    pub date: String,
+    /// Path of the day's file.
+    pub path: String,
+    /// Whether the file already existed, in which case the day was not fetched.
+    pub skipped: bool,
+    /// Number of trades written, 0 for a skipped day.
+    pub trades: usize,
+    /// Timestamp of the earliest trade written, `None` for an empty or skipped day.
+    pub first_timestamp: Option<i64>,
+    /// Timestamp of the latest trade written, `None` for an empty or skipped day.
+    pub last_timestamp: Option<i64>,
+}
+
+#[pymethods]
+impl DaySummary {
+    fn __repr__(&self) -> String {
+        format!(
+            "DaySummary(date={}, skipped={}, trades={}, first_timestamp={:?}, last_timestamp={:?})",
+            self.date, self.skipped, self.trades, self.first_timestamp, self.last_timestamp
+        )
+    }
+}
+
+/// Milliseconds in a UTC day. Unix time has no leap seconds, so every day has exactly this many.
+const DAY_MS: i64 = 24 * 60 * 60 * 1000;
+
+pub(super) fn parse_date(date: &str) -> Result<NaiveDate, BybitError> {
+    NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .map_err(|_| BybitError::InvalidArg(format!("date {} (expected YYYY-MM-DD)", date)))
+}
+
+/// Returns the half-open window `[00:00:00.000, 00:00:00.000 of the next day)` of a UTC day in
+/// milliseconds.
+pub(super) fn day_range(date: NaiveDate) -> (i64, i64) {
+    let start = date.and_time(NaiveTime::MIN).and_utc().timestamp_millis();
+    (start, start + DAY_MS)
+}
+
+/// Trades of one time bucket, aggregated by [`TradeAggregator`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct TradeBucket {
+    /// Start of the bucket, a multiple of the bucket length since the Unix epoch.
+    pub start_ts: i64,
+    /// Volume-weighted average price of the trades.
+    pub vwap: f64,
+    pub volume: f64,
+    /// Volume of the trades initiated by a buyer.
+    pub buy_volume: f64,
+    /// Volume of the trades initiated by a seller.
+    pub sell_volume: f64,
+    pub trade_count: usize,
+}
+
+impl TradeBucket {
+    pub fn to_dict(&self, py: Python) -> PyResult<PyObject> {
+        let dict = pyo3::types::PyDict::new(py);
+        dict.set_item("start_ts", self.start_ts)?;
+        for (key, value) in [
+            ("vwap", self.vwap),
+            ("volume", self.volume),
+            ("buy_volume", self.buy_volume),
+            ("sell_volume", self.sell_volume),
+        ] {
+            dict.set_item(key, value)?;
+        }
+        dict.set_item("trade_count", self.trade_count)?;
+        Ok(dict.into())
+    }
+}
+
+/// Groups trades into fixed buckets of `bucket_ms` aligned to the Unix epoch, in any order, so
+/// that the pages can be added as they arrive. Buckets without trades are left out.
+pub struct TradeAggregator {
+    bucket_ms: i64,
+    /// Turnover, that is the sum of price * size, and the bucket with the VWAP not yet computed.
+    buckets: BTreeMap<i64, (f64, TradeBucket)>,
+}
+
+impl TradeAggregator {
+    pub fn new(bucket_ms: i64) -> Result<Self, BybitError> {
+        if bucket_ms <= 0 {
+            return Err(BybitError::InvalidArg(format!(
+                "bucket_ms {} (must be positive)",
+                bucket_ms
+            )));
+        }
+        Ok(Self {
+            bucket_ms,
+            buckets: BTreeMap::new(),
+        })
+    }
+
+    pub fn add(&mut self, trades: &[TradeRow]) {
+        for trade in trades {
+            let start_ts = trade.timestamp.div_euclid(self.bucket_ms) * self.bucket_ms;
+            let (turnover, bucket) = self.buckets.entry(start_ts).or_insert_with(|| {
+                (
+                    0.0,
+                    TradeBucket {
+                        start_ts,
+                        vwap: 0.0,
+                        volume: 0.0,
+                        buy_volume: 0.0,
+                        sell_volume: 0.0,
+                        trade_count: 0,
+                    },
+                )
+            });
+            *turnover += trade.turnover();
+            bucket.volume += trade.size;
+            match trade.side_sign() {
+                1 => bucket.buy_volume += trade.size,
+                -1 => bucket.sell_volume += trade.size,
+                _ => {},
+            }
+            bucket.trade_count += 1;
+        }
+    }
+
+    /// Returns the buckets in time order.
+    pub fn finish(self) -> Vec<TradeBucket> {
+        self.buckets
+            .into_values()
+            .map(|(turnover, mut bucket)| {
+                bucket.vwap = if bucket.volume > 0.0 {
+                    turnover / bucket.volume
+                } else {
+                    f64::NAN
+                };
+                bucket
+            })
+            .collect()
+    }
+}
+
+fn events_as_bytes(events: &[Event]) -> &[u8] {
+    // Event is a `repr(C)` plain old data struct without padding.
+    unsafe { std::slice::from_raw_parts(events.as_ptr() as *const u8, size_of_val(events)) }
+}
+
+fn events_as_bytes_mut(events: &mut [Event]) -> &mut [u8] {
+    unsafe { std::slice::from_raw_parts_mut(events.as_mut_ptr() as *mut u8, size_of_val(events)) }
+}
+
+/// A positive decimal step, such as a tick or lot size, that decimal strings are counted in.
+#[derive(Debug, Clone, Copy)]
+pub struct DecimalStep {
+    pub(super) value: f64,
+    mantissa: i128,
+    scale: u32,
+}
+
+impl DecimalStep {
+    pub fn new(value: f64, name: &str) -> Result<Self, BybitError> {
+        match parse_decimal(&value.to_string()) {
+            Some((mantissa, scale)) if mantissa > 0 => Ok(Self {
+                value,
+                mantissa,
+                scale,
+            }),
+            _ => Err(BybitError::InvalidArg(format!(
+                "{} {} (expected a positive number)",
+                name, value
+            ))),
+        }
+    }
+
+    /// Returns the number of steps in the decimal string `value`, or `None` if it is not a whole
+    /// number of steps.
+    pub fn steps(&self, value: &str) -> Option<i64> {
+        let (mantissa, scale) = parse_decimal(value)?;
+        // value / step = (mantissa * 10^self.scale) / (self.mantissa * 10^scale)
+        let numerator = mantissa.checked_mul(10i128.checked_pow(self.scale)?)?;
+        let denominator = self.mantissa.checked_mul(10i128.checked_pow(scale)?)?;
+        if numerator % denominator != 0 {
+            return None;
+        }
+        i64::try_from(numerator / denominator).ok()
+    }
+
+    /// Returns `value` as its number of steps times the step, or `None` if it is not a whole
+    /// number of steps.
+    pub fn snap(&self, value: &str) -> Option<f64> {
+        self.steps(value).map(|steps| steps as f64 * self.value)
+    }
+}
+
+/// Parses a plain decimal string, such as "-12.340", into its digits as an integer and the number
+/// of digits after the point, ignoring trailing zeros.
+fn parse_decimal(value: &str) -> Option<(i128, u32)> {
+    let (negative, digits) = match value.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, value.strip_prefix('+').unwrap_or(value)),
+    };
+    let (int, frac) = digits.split_once('.').unwrap_or((digits, ""));
+    if int.is_empty() && frac.is_empty() {
+        return None;
+    }
+    let frac = frac.trim_end_matches('0');
+    let mut mantissa = 0i128;
+    for c in int.chars().chain(frac.chars()) {
+        mantissa = mantissa
+            .checked_mul(10)?
+            .checked_add(c.to_digit(10)? as i128)?;
+    }
+    let scale = u32::try_from(frac.len()).ok()?;
+    Some((if negative { -mantissa } else { mantissa }, scale))
+}
+
+/// Tick and lot size that trade events are converted on by [`TradeRow::to_event_on_grid`]. A
+/// `None` step leaves the price or size as parsed.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TickGrid {
+    pub tick_size: Option<DecimalStep>,
+    pub lot_size: Option<DecimalStep>,
+}
+
+impl TickGrid {
+    pub fn new(tick_size: Option<f64>, lot_size: Option<f64>) -> Result<Self, BybitError> {
+        Ok(Self {
+            tick_size: tick_size
+                .map(|v| DecimalStep::new(v, "tick_size"))
+                .transpose()?,
+            lot_size: lot_size
+                .map(|v| DecimalStep::new(v, "lot_size"))
+                .transpose()?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hftbacktest::types::{BUY_EVENT, EXCH_EVENT, LOCAL_EVENT, SELL_EVENT, TRADE_EVENT};
+
+    use super::*;
+    use crate::bybit::tests::{serve_once, serve_once_from};
+
+    #[tokio::test]
+    async fn test_raw_dump_replays_to_the_same_trades() {
+        let page1 = r#"{"retCode":0,"retMsg":"OK","result":{"list":[
+            {"execId":"c","symbol":"BTCUSDT","price":"100","size":"0.1","side":"Buy","time":"1704067200003","isBlockTrade":false},
+            {"execId":"b","symbol":"BTCUSDT","price":"100","size":"0.2","side":"Sell","time":"1704067200002","isBlockTrade":false}],"nextPageCursor":"page2"}}"#;
+        let page2 = r#"{"retCode":0,"retMsg":"OK","result":{"list":[
+            {"execId":"b","symbol":"BTCUSDT","price":"100","size":"0.2","side":"Sell","time":"1704067200002","isBlockTrade":false},
+            {"execId":"a","symbol":"BTCUSDT","price":"99","size":"0.3","side":"Sell","time":"1704067200001","isBlockTrade":false}],"nextPageCursor":""}}"#;
+        let drifted = r#"{"retCode":0,"retMsg":"OK","result":{"rows":[]}}"#;
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            for body in [page1, page2, drifted] {
+                serve_once_from(&listener, body).await;
+            }
+        });
+        let path = std::env::temp_dir().join(format!("raw_dump_{}.ndjson.zst", std::process::id()));
+        let path = path.to_str().unwrap();
+
+        let fetcher = BybitHistoryFetcher::new(base_url, String::new(), String::new())
+            .with_raw_dump(Some(path))
+            .unwrap();
+        let (trades, _) = fetcher
+            .fetch_trades("BTCUSDT", 0, i64::MAX, &FetchConfig::default())
+            .await
+            .unwrap();
+        let result = fetcher
+            .fetch_trades("BTCUSDT", 0, i64::MAX, &FetchConfig::default())
+            .await;
+        assert!(matches!(result, Err(BybitError::Parse(_))));
+
+        let records = RawDump::read(path).unwrap();
+        assert_eq!(records.len(), 3);
+        assert!(records[1].url.contains("cursor=page2"));
+        // The body that failed to parse is kept for debugging.
+        assert_eq!(records[2].body, drifted);
+        assert!(matches!(
+            replay_raw_dump_rows(path),
+            Err(BybitError::Parse(_))
+        ));
+
+        // Without the drifted record, the replay matches the fetch.
+        std::fs::remove_file(path).unwrap();
+        let dump = RawDump::open(path).unwrap();
+        for record in &records[..2] {
+            dump.write(&record.url, record.received_at, &record.body)
+                .unwrap();
+        }
+        let replayed = replay_raw_dump_rows(path).unwrap();
+        std::fs::remove_file(path).unwrap();
+        assert_eq!(
+            replayed.iter().map(|t| &t.exec_id).collect::<Vec<_>>(),
+            trades.iter().map(|t| &t.exec_id).collect::<Vec<_>>()
+        );
+        assert_eq!(replayed.len(), 3);
+    }
+
+    #[test]
+    fn test_trade_parquet_writer() {
+        use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+        let path = std::env::temp_dir().join("test_trade_parquet_writer.parquet");
+        let path = path.to_str().unwrap();
+        let trade = TradeRow {
+            exec_id: "1".to_string(),
+            timestamp: 1704067200000,
+            symbol: "BTCUSDT".to_string(),
+            side: "Buy".to_string(),
+            size: 0.1,
+            price: 42000.0,
+            is_block_trade: false,
+            mark_price: None,
+            index_price: None,
+            mark_iv: None,
+            iv: None,
+            raw_price: None,
+            raw_size: None,
+        };
+
+        assert!(TradeParquetWriter::create(path, "lz4", 2).is_err());
+
+        let mut writer = TradeParquetWriter::create(path, "zstd", 2).unwrap();
+        writer.write(std::slice::from_ref(&trade)).unwrap();
+        writer.write(&[]).unwrap();
+        writer.write(&[trade.clone(), trade]).unwrap();
+        let (num_rows, file_size) = writer.close().unwrap();
+        assert_eq!(num_rows, 3);
+        assert_eq!(file_size, std::fs::metadata(path).unwrap().len());
+
+        let reader = ParquetRecordBatchReaderBuilder::try_new(File::open(path).unwrap()).unwrap();
+        assert_eq!(reader.metadata().file_metadata().num_rows(), 3);
+        // Row groups are cut every 2 rows regardless of how the pages were split.
+        assert_eq!(reader.metadata().num_row_groups(), 2);
+        let schema = reader.schema().clone();
+        assert_eq!(
+            schema.field_with_name("side").unwrap().data_type(),
+            &DataType::Int8
+        );
+        assert!(matches!(
+            schema.field_with_name("symbol").unwrap().data_type(),
+            DataType::Dictionary(..)
+        ));
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_trade_csv_writer() {
+        let path = std::env::temp_dir().join("test_trade_csv_writer.csv");
+        let path = path.to_str().unwrap();
+        let trade = TradeRow {
+            exec_id: "1".to_string(),
+            timestamp: 1704067200000,
+            symbol: "BTCUSDT".to_string(),
+            side: "Buy".to_string(),
+            size: 0.1,
+            price: 42000.5,
+            is_block_trade: false,
+            mark_price: None,
+            index_price: None,
+            mark_iv: None,
+            iv: None,
+            raw_price: None,
+            raw_size: None,
+        };
+
+        let mut writer = TradeCsvWriter::create(path, false).unwrap();
+        writer.write(std::slice::from_ref(&trade)).unwrap();
+        assert_eq!(writer.close().unwrap(), 1);
+        // Appending skips the header.
+        let mut writer = TradeCsvWriter::create(path, true).unwrap();
+        writer.write(&[trade.clone(), trade.clone()]).unwrap();
+        assert_eq!(writer.close().unwrap(), 2);
+
+        let row = "1704067200000,BTCUSDT,Buy,0.1,42000.5\n";
+        assert_eq!(
+            std::fs::read_to_string(path).unwrap(),
+            format!("timestamp,symbol,side,size,price\n{row}{row}{row}")
+        );
+
+        // Overwriting starts over with a header.
+        let mut writer = TradeCsvWriter::create(path, false).unwrap();
+        writer.write(&[trade]).unwrap();
+        writer.close().unwrap();
+        assert_eq!(
+            std::fs::read_to_string(path).unwrap(),
+            format!("timestamp,symbol,side,size,price\n{row}")
+        );
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_trade_cache() {
+        let dir = std::env::temp_dir().join(format!("test_trade_cache_{}", std::process::id()));
+        let cache = TradeCache::open(dir.to_str().unwrap()).unwrap();
+        let path = cache.path("BTC-26JAN24-45000-C", "option", 1000, 2000, 500);
+        assert_eq!(
+            path.file_name().unwrap(),
+            "BTC-26JAN24-45000-C_option_1000_2000_500.json.zst"
+        );
+        assert!(TradeCache::read(&path).is_none());
+
+        let trade = TradeRow {
+            exec_id: "1".to_string(),
+            timestamp: 1500,
+            symbol: "BTC-26JAN24-45000-C".to_string(),
+            side: "Sell".to_string(),
+            size: 0.5,
+            price: 1200.0,
+            is_block_trade: true,
+            mark_price: Some(1180.5),
+            index_price: None,
+            mark_iv: Some(0.52),
+            iv: None,
+            raw_price: None,
+            raw_size: None,
+        };
+        TradeCache::write(&path, &[trade.clone(), trade]).unwrap();
+        let trades = TradeCache::read(&path).unwrap();
+        assert_eq!(trades.len(), 2);
+        assert_eq!(trades[1].exec_id, "1");
+        assert!(trades[1].is_block_trade);
+        assert_eq!(trades[1].mark_price, Some(1180.5));
+        assert_eq!(trades[1].index_price, None);
+        assert!(!path.with_extension("zst.part").exists());
+
+        // A truncated entry is a miss rather than an error.
+        let data = std::fs::read(&path).unwrap();
+        std::fs::write(&path, &data[..data.len() / 2]).unwrap();
+        assert!(TradeCache::read(&path).is_none());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_trade_npz_writer() {
+        use hftbacktest::backtest::data::read_npz_file;
+
+        let path = std::env::temp_dir().join("test_trade_npz_writer.npz");
+        let path = path.to_str().unwrap();
+        let trade = |exec_id: &str, timestamp: i64, side: &str| TradeRow {
+            exec_id: exec_id.to_string(),
+            timestamp,
+            symbol: "BTCUSDT".to_string(),
+            side: side.to_string(),
+            size: 0.1,
+            price: 42000.0,
+            is_block_trade: false,
+            mark_price: None,
+            index_price: None,
+            mark_iv: None,
+            iv: None,
+            raw_price: None,
+            raw_size: None,
+        };
+
+        // Pages arrive newest first.
+        let mut writer =
+            TradeNpzWriter::create(path, LatencyInjector::constant(5_000_000)).unwrap();
+        writer
+            .write(&[
+                trade("3", 1704067200003, "Buy"),
+                trade("2", 1704067200002, "Sell"),
+            ])
+            .unwrap();
+        writer.write(&[trade("1", 1704067200001, "Buy")]).unwrap();
+        assert_eq!(writer.finish().unwrap(), 3);
+
+        let data = read_npz_file::<Event>(path, "data").unwrap();
+        assert_eq!(data.len(), 3);
+        assert_eq!(data[0].exch_ts, 1704067200001000000);
+        assert_eq!(data[0].local_ts, 1704067200001000000 + 5_000_000);
+        assert_eq!(
+            data[0].ev,
+            EXCH_EVENT | LOCAL_EVENT | TRADE_EVENT | BUY_EVENT
+        );
+        assert_eq!(
+            data[1].ev,
+            EXCH_EVENT | LOCAL_EVENT | TRADE_EVENT | SELL_EVENT
+        );
+        assert_eq!(data[2].exch_ts, 1704067200003000000);
+        assert!(!std::path::Path::new(&format!("{}.spool", path)).exists());
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_decimal_step() {
+        let tick = DecimalStep::new(0.1, "tick_size").unwrap();
+        assert_eq!(tick.steps("0.3"), Some(3));
+        assert_eq!(tick.steps("42000.10"), Some(420001));
+        assert_eq!(tick.steps("-1.2"), Some(-12));
+        assert_eq!(tick.steps("100.05"), None);
+        assert_eq!(tick.steps("1e3"), None);
+        assert_eq!(tick.steps(""), None);
+        // 0.3 / 0.1 is 2.9999999999999996 in f64.
+        assert_eq!((tick.snap("0.3").unwrap() / 0.1).round(), 3.0);
+        assert_eq!((0.3f64 / 0.1).floor(), 2.0);
+
+        let lot = DecimalStep::new(0.001, "lot_size").unwrap();
+        assert_eq!(lot.steps("1.234"), Some(1234));
+        assert_eq!(lot.steps("0.0015"), None);
+        assert_eq!(
+            DecimalStep::new(5.0, "tick_size").unwrap().steps("15"),
+            Some(3)
+        );
+        assert!(DecimalStep::new(0.0, "tick_size").is_err());
+        assert!(DecimalStep::new(-0.5, "tick_size").is_err());
+    }
+
+    #[test]
+    fn test_trade_npz_writer_on_grid() {
+        use hftbacktest::backtest::data::read_npz_file;
+
+        let path = std::env::temp_dir().join("test_trade_npz_writer_on_grid.npz");
+        let path = path.to_str().unwrap();
+        let trade = |price: &str, size: &str| TradeRow {
+            exec_id: "1".to_string(),
+            timestamp: 1704067200001,
+            symbol: "BTCUSDT".to_string(),
+            side: "Buy".to_string(),
+            size: size.parse().unwrap(),
+            price: price.parse().unwrap(),
+            is_block_trade: false,
+            mark_price: None,
+            index_price: None,
+            mark_iv: None,
+            iv: None,
+            raw_price: Some(price.to_string()),
+            raw_size: Some(size.to_string()),
+        };
+        let grid = TickGrid::new(Some(0.1), Some(0.001)).unwrap();
+
+        let mut writer = TradeNpzWriter::create(path, LatencyInjector::constant(0))
+            .unwrap()
+            .with_grid(grid);
+        writer.write(&[trade("0.3", "0.007")]).unwrap();
+        writer.finish().unwrap();
+        let data = read_npz_file::<Event>(path, "data").unwrap();
+        assert_eq!((data[0].px / 0.1).round(), 3.0);
+        assert_eq!(data[0].px, 3.0 * 0.1);
+        assert_eq!(data[0].qty, 7.0 * 0.001);
+        std::fs::remove_file(path).unwrap();
+
+        let err = trade("100.05", "0.1")
+            .to_event_on_grid(0, &grid)
+            .unwrap_err();
+        assert!(matches!(err, BybitError::OffGrid(_)));
+        assert!(err.to_string().contains("price 100.05 of trade 1"));
+        assert!(matches!(
+            trade("100.1", "0.0005").to_event_on_grid(0, &grid),
+            Err(BybitError::OffGrid(_))
+        ));
+
+        // Without the raw strings, the shortest representation of the parsed value is used.
+        let parsed = TradeRow {
+            raw_price: None,
+            raw_size: None,
+            ..trade("0.3", "0.007")
+        };
+        assert_eq!(parsed.to_event_on_grid(0, &grid).unwrap().px, 3.0 * 0.1);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_trades_daily_splits_at_utc_midnight() {
+        // The mock serves the same page for every day: 2024-01-01 23:59:59.999 and 2024-01-02
+        // 00:00:00.000, around midnight, then 2024-01-02 12:00:00.000.
+        let page = r#"{"retCode":0,"retMsg":"OK","result":{"list":[
+            {"execId":"c","symbol":"BTCUSDT","price":"101","size":"0.1","side":"Buy","time":"1704196800000","isBlockTrade":false},
+            {"execId":"b","symbol":"BTCUSDT","price":"100","size":"0.1","side":"Buy","time":"1704153600000","isBlockTrade":false},
+            {"execId":"a","symbol":"BTCUSDT","price":"99","size":"0.1","side":"Sell","time":"1704153599999","isBlockTrade":false}],"nextPageCursor":""}}"#;
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let config = FetchConfig {
+            base_url: format!("http://{}", listener.local_addr().unwrap()),
+            ..FetchConfig::default()
+        };
+        tokio::spawn(async move {
+            loop {
+                serve_once_from(&listener, page).await;
+            }
+        });
+        let out_dir = std::env::temp_dir().join(format!("daily_{}", std::process::id()));
+        let fetcher = BybitHistoryFetcher::from_config(&config).unwrap();
+        let fetch_days = |overwrite| {
+            fetcher.fetch_trades_daily(
+                "BTCUSDT",
+                parse_date("2024-01-01").unwrap(),
+                parse_date("2024-01-03").unwrap(),
+                &out_dir,
+                DailyFormat::Npz,
+                overwrite,
+                0,
+                &config,
+            )
+        };
+
+        let (summaries, stats) = fetch_days(false).await.unwrap();
+        let days = summaries
+            .iter()
+            .map(|s| (s.skipped, s.trades, s.first_timestamp, s.last_timestamp))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            days,
+            [
+                (false, 1, Some(1704153599999), Some(1704153599999)),
+                (false, 2, Some(1704153600000), Some(1704196800000)),
+                (false, 0, None, None),
+            ]
+        );
+        assert_eq!(stats.trades, 3);
+        assert!(summaries[1].path.ends_with("BTCUSDT_20240102.npz"));
+        let data = hftbacktest::backtest::data::read_npz_file::<Event>(&summaries[1].path, "data")
+            .unwrap();
+        assert_eq!(data.len(), 2);
+        assert_eq!(data[0].exch_ts, 1704153600000 * 1_000_000);
+
+        let (summaries, _) = fetch_days(false).await.unwrap();
+        assert!(summaries.iter().all(|s| s.skipped && s.trades == 0));
+        let (summaries, _) = fetch_days(true).await.unwrap();
+        assert!(summaries.iter().all(|s| !s.skipped));
+
+        let files = std::fs::read_dir(&out_dir).unwrap().count();
+        std::fs::remove_dir_all(&out_dir).unwrap();
+        assert_eq!(files, 3);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_trades_aggregated() {
+        // Newest first, as Bybit returns them: one trade in the 00:00:01 bucket, three in the
+        // 00:00:00 one, and one at end_time, which is left out.
+        let page = r#"{"retCode":0,"retMsg":"OK","result":{"list":[
+            {"execId":"e","symbol":"BTCUSDT","price":"105","size":"1","side":"Buy","time":"1704067202000","isBlockTrade":false},
+            {"execId":"d","symbol":"BTCUSDT","price":"104","size":"0.5","side":"Sell","time":"1704067201500","isBlockTrade":false},
+            {"execId":"c","symbol":"BTCUSDT","price":"102","size":"3","side":"Sell","time":"1704067200999","isBlockTrade":false},
+            {"execId":"b","symbol":"BTCUSDT","price":"101","size":"0.5","side":"Buy","time":"1704067200001","isBlockTrade":false},
+            {"execId":"a","symbol":"BTCUSDT","price":"100","size":"0.5","side":"Buy","time":"1704067200000","isBlockTrade":false}],"nextPageCursor":""}}"#;
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let config = FetchConfig {
+            base_url: format!("http://{}", listener.local_addr().unwrap()),
+            ..FetchConfig::default()
+        };
+        tokio::spawn(async move { serve_once(listener, page).await });
+        let (buckets, stats) = BybitHistoryFetcher::from_config(&config)
+            .unwrap()
+            .fetch_trades_aggregated("BTCUSDT", 1704067200000, 1704067202000, 1000, &config)
+            .await
+            .unwrap();
+
+        assert_eq!(stats.trades, 5);
+        assert_eq!(
+            buckets,
+            [
+                TradeBucket {
+                    start_ts: 1704067200000,
+                    vwap: (100.0 * 0.5 + 101.0 * 0.5 + 102.0 * 3.0) / 4.0,
+                    volume: 4.0,
+                    buy_volume: 1.0,
+                    sell_volume: 3.0,
+                    trade_count: 3,
+                },
+                TradeBucket {
+                    start_ts: 1704067201000,
+                    vwap: 104.0,
+                    volume: 0.5,
+                    buy_volume: 0.0,
+                    sell_volume: 0.5,
+                    trade_count: 1,
+                },
+            ]
+        );
+        assert!(matches!(
+            TradeAggregator::new(0),
+            Err(BybitError::InvalidArg(_))
+        ));
+    }
+}
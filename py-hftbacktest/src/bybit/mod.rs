@@ -0,0 +1,1345 @@
+//! Bybit v5 public market data: trade history, klines, funding, open interest, instruments and
+//! order book snapshots. [`client`] sends the requests with signing, retries and rate limiting,
+//! [`pagination`] walks the cursor and time-window pages of each endpoint, [`export`] writes the
+//! results to files, and [`python`] holds the Python bindings.
+
+use std::time::Duration;
+
+use hftbacktest::types::{BUY_EVENT, EXCH_EVENT, Event, LOCAL_EVENT, SELL_EVENT, TRADE_EVENT};
+use numpy::{Element, PyArrayDescr};
+use pyo3::{exceptions::PyValueError, prelude::*, sync::GILOnceCell};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+mod client;
+mod export;
+mod pagination;
+mod python;
+
+pub use client::{FetchConfig, RetryConfig};
+pub(crate) use client::{body_snippet, record_stats, runtime};
+pub use export::{DaySummary, DecimalStep, EventNpzWriter, TickGrid, TradeCsvWriter};
+pub use python::{
+    BybitFetcher, count_trades, fetch_funding, fetch_instruments, fetch_klines, fetch_klines_numpy,
+    fetch_long_short_ratio, fetch_open_interest, fetch_orderbook, fetch_recent_trades,
+    fetch_trades, fetch_trades_aggregated, fetch_trades_async, fetch_trades_chunked,
+    fetch_trades_concurrent, fetch_trades_daily, fetch_trades_multi, fetch_trades_numpy,
+    fetch_trades_page, fetch_trades_polars, fetch_trades_to_csv, fetch_trades_to_npz,
+    fetch_trades_to_parquet, last_fetch_stats, replay_raw_dump, validate_trades,
+};
+
+pub mod exceptions {
+    use pyo3::{create_exception, exceptions::PyRuntimeError};
+
+    create_exception!(
+        hftbacktest,
+        BybitError,
+        PyRuntimeError,
+        "Base class for errors raised while fetching data from Bybit."
+    );
+    create_exception!(
+        hftbacktest,
+        BybitRateLimitError,
+        BybitError,
+        "Raised when requests are still rate limited after the maximum number of retries."
+    );
+    create_exception!(
+        hftbacktest,
+        BybitApiError,
+        BybitError,
+        "Raised when Bybit responds with a non-zero retCode."
+    );
+    create_exception!(
+        hftbacktest,
+        BybitUnknownSymbolError,
+        BybitApiError,
+        "Raised when Bybit rejects the symbol as invalid, such as a typo'd \"BTCUSD\" for \
+         \"BTCUSDT\", as opposed to a valid symbol without data in the range."
+    );
+    create_exception!(
+        hftbacktest,
+        FetchInterrupted,
+        BybitError,
+        "Raised when a trade fetch stops on a transient error. `partial` holds the trades fetched \
+         so far and `cursor` the cursor to resume from."
+    );
+}
+
+#[derive(Error, Debug)]
+pub enum BybitError {
+    #[error("HTTP error: {0}")]
+    Http(StatusCode),
+    #[error("Rate limited: max retries exceeded")]
+    RateLimited,
+    #[error("API error: {code} - {msg}")]
+    Api { code: i32, msg: String },
+    #[error("Unknown symbol: {0}")]
+    UnknownSymbol(String),
+    #[error("Failed to parse {0}")]
+    Parse(String),
+    #[error("Request failed: {0}")]
+    Transport(String),
+    #[error("Request timed out: {0}")]
+    Timeout(String),
+    #[error("Invalid argument: {0}")]
+    InvalidArg(String),
+    #[error("Invalid time range: {0}")]
+    InvalidRange(String),
+    #[error("Trades out of order: {0}")]
+    OutOfOrder(String),
+    #[error("Pagination stalled: {0} consecutive empty pages with a next cursor")]
+    StalledPagination(u32),
+    #[error("Off the tick or lot grid: {0}")]
+    OffGrid(String),
+    #[error("IO error: {0}")]
+    Io(String),
+    #[error("Callback failed")]
+    Callback,
+}
+
+impl BybitError {
+    /// Returns true if the error may go away by retrying later, such as a dropped connection,
+    /// exhausted rate-limit retries or a server error, as opposed to a bad request.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            BybitError::Http(status) => status.is_server_error(),
+            BybitError::RateLimited | BybitError::Transport(_) | BybitError::Timeout(_) => true,
+            _ => false,
+        }
+    }
+
+    /// Returns true if the error does not invalidate the pages fetched before it: a transient
+    /// error, a non-zero retCode Bybit uses for server-side trouble rather than a bad request, or
+    /// stalled pagination.
+    pub fn is_non_fatal(&self) -> bool {
+        match self {
+            BybitError::Api { code, .. } => NON_FATAL_RET_CODES.contains(code),
+            BybitError::StalledPagination(_) => true,
+            error => error.is_transient(),
+        }
+    }
+}
+
+/// retCodes for server timeouts, internal errors and request-frequency protection.
+const NON_FATAL_RET_CODES: [i32; 4] = [10000, 10006, 10016, 10429];
+
+/// retCode for invalid request parameters, which Bybit also uses for a symbol it does not list,
+/// with a retMsg such as "params error: symbol invalid".
+const PARAMS_ERROR_RET_CODE: i32 = 10001;
+
+/// Maps a non-zero retCode to an error, telling an invalid symbol apart from other rejected
+/// parameters so that a typo'd symbol does not pass for an empty range.
+fn api_error(code: i32, msg: String, query_string: &str) -> BybitError {
+    if code == PARAMS_ERROR_RET_CODE && msg.to_lowercase().contains("symbol") {
+        let symbol = query_string
+            .split('&')
+            .find_map(|param| param.strip_prefix("symbol="));
+        if let Some(symbol) = symbol {
+            return BybitError::UnknownSymbol(symbol.to_string());
+        }
+    }
+    BybitError::Api { code, msg }
+}
+
+impl From<BybitError> for PyErr {
+    fn from(error: BybitError) -> Self {
+        match error {
+            BybitError::RateLimited => exceptions::BybitRateLimitError::new_err(error.to_string()),
+            BybitError::Api { .. } => exceptions::BybitApiError::new_err(error.to_string()),
+            BybitError::UnknownSymbol(_) => {
+                exceptions::BybitUnknownSymbolError::new_err(error.to_string())
+            },
+            error => exceptions::BybitError::new_err(error.to_string()),
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct TradeRow {
+    pub exec_id: String,
+    pub timestamp: i64,
+    pub symbol: String,
+    pub side: String,
+    pub size: f64,
+    pub price: f64,
+    pub is_block_trade: bool,
+    /// Option-specific fields, `None` for other categories.
+    pub mark_price: Option<f64>,
+    pub index_price: Option<f64>,
+    pub mark_iv: Option<f64>,
+    pub iv: Option<f64>,
+    /// Price and size as the exchange sent them, kept so that [`to_event_on_grid`] converts them
+    /// exactly. `None` if the row was not parsed from a decimal string.
+    ///
+    /// [`to_event_on_grid`]: TradeRow::to_event_on_grid
+    #[serde(default)]
+    pub raw_price: Option<String>,
+    #[serde(default)]
+    pub raw_size: Option<String>,
+}
+
+impl TradeRow {
+    /// Converts the trade into a dict. With `side_as_int`, `side` is the [`side_sign`] of the
+    /// trade instead of the string, and an unrecognized side is an error. With
+    /// `include_turnover`, the dict also has a `turnover` key with the [`turnover`] of the trade.
+    ///
+    /// [`side_sign`]: TradeRow::side_sign
+    /// [`turnover`]: TradeRow::turnover
+    pub fn to_dict(
+        &self,
+        py: Python,
+        side_as_int: bool,
+        include_turnover: bool,
+    ) -> PyResult<PyObject> {
+        let dict = pyo3::types::PyDict::new(py);
+        dict.set_item("exec_id", self.exec_id.clone())?;
+        dict.set_item("timestamp", self.timestamp)?;
+        dict.set_item("symbol", self.symbol.clone())?;
+        if side_as_int {
+            let side = match self.side_sign() {
+                0 => {
+                    return Err(PyValueError::new_err(format!(
+                        "unrecognized side {:?} of trade {}",
+                        self.side, self.exec_id
+                    )));
+                },
+                side => side,
+            };
+            dict.set_item("side", side)
+        } else {
+            dict.set_item("side", self.side.clone())
+        }?;
+        dict.set_item("size", self.size)?;
+        dict.set_item("price", self.price)?;
+        dict.set_item("is_block_trade", self.is_block_trade)?;
+        if include_turnover {
+            dict.set_item("turnover", self.turnover())?;
+        }
+        for (key, value) in [
+            ("mark_price", self.mark_price),
+            ("index_price", self.index_price),
+            ("mark_iv", self.mark_iv),
+            ("iv", self.iv),
+        ] {
+            if let Some(value) = value {
+                dict.set_item(key, value)?;
+            }
+        }
+        Ok(dict.into())
+    }
+
+    /// Returns the quote volume `price * size` of the trade. It is derived, not reported by Bybit.
+    /// Parsing the price and size and multiplying them each round once, so the result is within
+    /// about 4e-16 relative of the exact decimal product: under a millionth of a quote unit for a
+    /// notional of a billion. That is fine for VWAP, but sums over many trades should not be
+    /// expected to match an exchange-reported turnover to the last digit.
+    pub fn turnover(&self) -> f64 {
+        self.price * self.size
+    }
+
+    /// Encodes the taker side as +1 for `Buy` and -1 for `Sell`, 0 if unrecognized.
+    pub fn side_sign(&self) -> i8 {
+        match self.side.as_str() {
+            "Buy" => 1,
+            "Sell" => -1,
+            _ => 0,
+        }
+    }
+
+    /// Converts the trade into an hftbacktest trade [`Event`]. Bybit timestamps are in
+    /// milliseconds and are converted to nanoseconds; `local_ts` is `exch_ts + feed_latency`.
+    pub fn to_event(&self, feed_latency: i64) -> Event {
+        let side = match self.side_sign() {
+            1 => BUY_EVENT,
+            -1 => SELL_EVENT,
+            _ => 0,
+        };
+        let exch_ts = self.timestamp * 1_000_000;
+        Event {
+            ev: EXCH_EVENT | LOCAL_EVENT | TRADE_EVENT | side,
+            exch_ts,
+            local_ts: exch_ts + feed_latency,
+            px: self.price,
+            qty: self.size,
+            order_id: 0,
+            ival: 0,
+            fval: 0.0,
+        }
+    }
+
+    /// Same as [`to_event`], except that the price and size are an exact number of ticks and lots
+    /// of `grid`, counted from their decimal strings rather than their parsed values, so that
+    /// `px / tick_size` rounds to the intended tick even where the `f64` division does not land on
+    /// it. A price or size that is not a whole number of ticks or lots is an error rather than
+    /// being rounded.
+    ///
+    /// [`to_event`]: TradeRow::to_event
+    pub fn to_event_on_grid(
+        &self,
+        feed_latency: i64,
+        grid: &TickGrid,
+    ) -> Result<Event, BybitError> {
+        let mut event = self.to_event(feed_latency);
+        if let Some(tick_size) = &grid.tick_size {
+            event.px = self.snap(&self.raw_price, self.price, tick_size, "price", "tick_size")?;
+        }
+        if let Some(lot_size) = &grid.lot_size {
+            event.qty = self.snap(&self.raw_size, self.size, lot_size, "size", "lot_size")?;
+        }
+        Ok(event)
+    }
+
+    fn snap(
+        &self,
+        raw: &Option<String>,
+        value: f64,
+        step: &DecimalStep,
+        name: &str,
+        step_name: &str,
+    ) -> Result<f64, BybitError> {
+        // The shortest string that parses back to the value is the decimal the exchange sent,
+        // unless it had more significant digits than an f64 holds.
+        let decimal = raw.clone().unwrap_or_else(|| value.to_string());
+        step.snap(&decimal).ok_or_else(|| {
+            BybitError::OffGrid(format!(
+                "{} {} of trade {} at {} is not a multiple of {} {}",
+                name, decimal, self.exec_id, self.timestamp, step_name, step.value
+            ))
+        })
+    }
+
+    /// Converts the trade into a [`TradeRecord`], failing if the side is neither `Buy` nor
+    /// `Sell` since the record has no encoding for it.
+    pub fn to_record(&self) -> Result<TradeRecord, BybitError> {
+        let side = match self.side_sign() {
+            1 => 1,
+            -1 => 0,
+            _ => {
+                return Err(BybitError::Parse(format!(
+                    "side {:?} of trade {}",
+                    self.side, self.exec_id
+                )));
+            },
+        };
+        Ok(TradeRecord {
+            timestamp: self.timestamp,
+            price: self.price,
+            size: self.size,
+            side,
+            is_block_trade: self.is_block_trade as u8,
+        })
+    }
+}
+
+/// A trade laid out to match the NumPy structured dtype
+/// `[('timestamp', 'i8'), ('price', 'f8'), ('size', 'f8'), ('side', 'u1'), ('is_block_trade', 'u1')]`,
+/// where `side` is the taker side, 1 for `Buy` and 0 for `Sell`.
+#[repr(C, packed)]
+#[derive(Clone, Copy, Debug)]
+pub struct TradeRecord {
+    pub timestamp: i64,
+    pub price: f64,
+    pub size: f64,
+    pub side: u8,
+    pub is_block_trade: u8,
+}
+
+unsafe impl Element for TradeRecord {
+    const IS_COPY: bool = true;
+
+    fn get_dtype(py: Python<'_>) -> Bound<'_, PyArrayDescr> {
+        static DTYPE: GILOnceCell<Py<PyArrayDescr>> = GILOnceCell::new();
+        DTYPE
+            .get_or_init(py, || {
+                PyArrayDescr::new(
+                    py,
+                    vec![
+                        ("timestamp", "i8"),
+                        ("price", "f8"),
+                        ("size", "f8"),
+                        ("side", "u1"),
+                        ("is_block_trade", "u1"),
+                    ],
+                )
+                .expect("trade record dtype must be valid")
+                .unbind()
+            })
+            .bind(py)
+            .clone()
+    }
+
+    fn clone_ref(&self, _py: Python<'_>) -> Self {
+        *self
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BybitTrade {
+    #[serde(rename = "execId")]
+    pub exec_id: String,
+    #[serde(rename = "symbol")]
+    pub symbol: String,
+    #[serde(rename = "price")]
+    pub price: String,
+    #[serde(rename = "size")]
+    pub size: String,
+    #[serde(rename = "side")]
+    pub side: String,
+    #[serde(rename = "time")]
+    pub time: String,
+    #[serde(rename = "isBlockTrade")]
+    pub is_block_trade: bool,
+    /// Mark price, options only.
+    #[serde(rename = "mP", default)]
+    pub mark_price: Option<String>,
+    /// Index price, options only.
+    #[serde(rename = "iP", default)]
+    pub index_price: Option<String>,
+    /// Mark implied volatility, options only.
+    #[serde(rename = "mIv", default)]
+    pub mark_iv: Option<String>,
+    /// Implied volatility of the trade, options only.
+    #[serde(rename = "iv", default)]
+    pub iv: Option<String>,
+}
+
+/// The status fields of the envelope, checked before the `result`, which Bybit leaves empty on an
+/// error.
+#[derive(Deserialize)]
+struct ResponseStatus {
+    #[serde(rename = "retCode")]
+    ret_code: i32,
+    #[serde(rename = "retMsg")]
+    ret_msg: String,
+}
+
+/// Envelope shared by all Bybit v5 responses.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BybitResponse<T> {
+    #[serde(rename = "retCode")]
+    pub ret_code: i32,
+    #[serde(rename = "retMsg")]
+    pub ret_msg: String,
+    pub result: T,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TradeResult {
+    pub list: Vec<BybitTrade>,
+    #[serde(rename = "nextPageCursor")]
+    pub next_page_cursor: Option<String>,
+}
+
+/// Trades of one symbol fetched by [`client::BybitHistoryFetcher::fetch_trades_multi`], or the
+/// error that stopped its fetch.
+pub type SymbolTrades = (String, Result<Vec<TradeRow>, BybitError>);
+
+#[derive(Clone)]
+pub struct KlineRow {
+    pub start_time: i64,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub volume: f64,
+    pub turnover: f64,
+}
+
+impl KlineRow {
+    pub fn to_dict(&self, py: Python) -> PyResult<PyObject> {
+        let dict = pyo3::types::PyDict::new(py);
+        dict.set_item("start_time", self.start_time)?;
+        for (key, value) in [
+            ("open", self.open),
+            ("high", self.high),
+            ("low", self.low),
+            ("close", self.close),
+            ("volume", self.volume),
+            ("turnover", self.turnover),
+        ] {
+            dict.set_item(key, value)?;
+        }
+        Ok(dict.into())
+    }
+
+    pub fn to_record(&self) -> KlineRecord {
+        KlineRecord {
+            start_time: self.start_time,
+            open: self.open,
+            high: self.high,
+            low: self.low,
+            close: self.close,
+            volume: self.volume,
+            turnover: self.turnover,
+        }
+    }
+}
+
+/// A kline laid out to match the NumPy structured dtype
+/// `[('start_time', 'i8'), ('open', 'f8'), ('high', 'f8'), ('low', 'f8'), ('close', 'f8'),
+/// ('volume', 'f8'), ('turnover', 'f8')]`.
+#[repr(C, packed)]
+#[derive(Clone, Copy, Debug)]
+pub struct KlineRecord {
+    pub start_time: i64,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub volume: f64,
+    pub turnover: f64,
+}
+
+unsafe impl Element for KlineRecord {
+    const IS_COPY: bool = true;
+
+    fn get_dtype(py: Python<'_>) -> Bound<'_, PyArrayDescr> {
+        static DTYPE: GILOnceCell<Py<PyArrayDescr>> = GILOnceCell::new();
+        DTYPE
+            .get_or_init(py, || {
+                PyArrayDescr::new(
+                    py,
+                    vec![
+                        ("start_time", "i8"),
+                        ("open", "f8"),
+                        ("high", "f8"),
+                        ("low", "f8"),
+                        ("close", "f8"),
+                        ("volume", "f8"),
+                        ("turnover", "f8"),
+                    ],
+                )
+                .expect("kline record dtype must be valid")
+                .unbind()
+            })
+            .bind(py)
+            .clone()
+    }
+
+    fn clone_ref(&self, _py: Python<'_>) -> Self {
+        *self
+    }
+}
+
+/// Kline intervals accepted by Bybit: minutes as a number, or `D`, `W`, `M`.
+pub const KLINE_INTERVALS: [&str; 13] = [
+    "1", "3", "5", "15", "30", "60", "120", "240", "360", "720", "D", "W", "M",
+];
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct KlineResult {
+    pub symbol: String,
+    /// Each entry is `[startTime, open, high, low, close, volume, turnover]`, newest first.
+    pub list: Vec<[String; 7]>,
+}
+
+fn parse_kline(raw: &[String; 7]) -> Result<KlineRow, BybitError> {
+    let start_time: i64 = raw[0]
+        .parse()
+        .map_err(|_| BybitError::Parse(format!("kline start time: {}", raw[0])))?;
+    let mut values = [0.0; 6];
+    for (value, field) in values.iter_mut().zip(&raw[1..]) {
+        *value = field
+            .parse()
+            .map_err(|_| BybitError::Parse(format!("kline value: {}", field)))?;
+    }
+    let [open, high, low, close, volume, turnover] = values;
+    Ok(KlineRow {
+        start_time,
+        open,
+        high,
+        low,
+        close,
+        volume,
+        turnover,
+    })
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BybitFunding {
+    pub symbol: String,
+    #[serde(rename = "fundingRate")]
+    pub funding_rate: String,
+    #[serde(rename = "fundingRateTimestamp")]
+    pub funding_rate_timestamp: String,
+}
+
+impl BybitFunding {
+    fn to_row(&self) -> Result<FundingRow, BybitError> {
+        Ok(FundingRow {
+            timestamp: self.funding_rate_timestamp.parse().map_err(|_| {
+                BybitError::Parse(format!(
+                    "funding timestamp: {}",
+                    self.funding_rate_timestamp
+                ))
+            })?,
+            symbol: self.symbol.clone(),
+            funding_rate: self
+                .funding_rate
+                .parse()
+                .map_err(|_| BybitError::Parse(format!("funding rate: {}", self.funding_rate)))?,
+        })
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FundingResult {
+    pub category: String,
+    /// Funding rows, newest first.
+    pub list: Vec<BybitFunding>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct FundingRow {
+    /// Funding settlement time in milliseconds.
+    pub timestamp: i64,
+    pub symbol: String,
+    pub funding_rate: f64,
+}
+
+impl FundingRow {
+    pub fn to_dict(&self, py: Python) -> PyResult<PyObject> {
+        let dict = pyo3::types::PyDict::new(py);
+        dict.set_item("timestamp", self.timestamp)?;
+        dict.set_item("symbol", &self.symbol)?;
+        dict.set_item("funding_rate", self.funding_rate)?;
+        Ok(dict.into())
+    }
+}
+
+/// Trading rules of an instrument, as listed by the instruments-info endpoint.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InstrumentRow {
+    pub symbol: String,
+    pub base_coin: String,
+    pub quote_coin: String,
+    /// Minimum price increment.
+    pub tick_size: f64,
+    /// Minimum order quantity increment: `qtyStep` for derivatives, `basePrecision` for spot.
+    pub lot_size: f64,
+    /// Trading status, e.g. "Trading" or "PreLaunch".
+    pub status: String,
+}
+
+impl InstrumentRow {
+    pub fn to_dict(&self, py: Python) -> PyResult<PyObject> {
+        let dict = pyo3::types::PyDict::new(py);
+        for (key, value) in [
+            ("symbol", &self.symbol),
+            ("base_coin", &self.base_coin),
+            ("quote_coin", &self.quote_coin),
+            ("status", &self.status),
+        ] {
+            dict.set_item(key, value)?;
+        }
+        for (key, value) in [("tick_size", self.tick_size), ("lot_size", self.lot_size)] {
+            dict.set_item(key, value)?;
+        }
+        Ok(dict.into())
+    }
+}
+
+/// Sampling intervals accepted by the open interest and long/short ratio endpoints.
+pub const STATS_INTERVALS: [&str; 6] = ["5min", "15min", "30min", "1h", "4h", "1d"];
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BybitOpenInterest {
+    #[serde(rename = "openInterest")]
+    pub open_interest: String,
+    pub timestamp: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OpenInterestResult {
+    /// Open interest samples, newest first.
+    pub list: Vec<BybitOpenInterest>,
+    #[serde(rename = "nextPageCursor", default)]
+    pub next_page_cursor: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BybitPriceFilter {
+    #[serde(rename = "tickSize")]
+    pub tick_size: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BybitLotSizeFilter {
+    /// Quantity step of linear, inverse and option contracts.
+    #[serde(rename = "qtyStep", default)]
+    pub qty_step: Option<String>,
+    /// Quantity step of spot pairs.
+    #[serde(rename = "basePrecision", default)]
+    pub base_precision: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BybitInstrument {
+    pub symbol: String,
+    pub status: String,
+    #[serde(rename = "baseCoin")]
+    pub base_coin: String,
+    #[serde(rename = "quoteCoin")]
+    pub quote_coin: String,
+    #[serde(rename = "priceFilter")]
+    pub price_filter: BybitPriceFilter,
+    #[serde(rename = "lotSizeFilter")]
+    pub lot_size_filter: BybitLotSizeFilter,
+}
+
+impl BybitInstrument {
+    fn to_row(&self) -> Result<InstrumentRow, BybitError> {
+        let lot_size = self
+            .lot_size_filter
+            .qty_step
+            .as_deref()
+            .or(self.lot_size_filter.base_precision.as_deref())
+            .ok_or_else(|| BybitError::Parse(format!("lot size of {}", self.symbol)))?;
+        Ok(InstrumentRow {
+            symbol: self.symbol.clone(),
+            base_coin: self.base_coin.clone(),
+            quote_coin: self.quote_coin.clone(),
+            tick_size: parse_field(&self.price_filter.tick_size, "tick size")?,
+            lot_size: parse_field(lot_size, "lot size")?,
+            status: self.status.clone(),
+        })
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct InstrumentResult {
+    pub list: Vec<BybitInstrument>,
+    #[serde(rename = "nextPageCursor", default)]
+    pub next_page_cursor: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BybitAccountRatio {
+    #[serde(rename = "buyRatio")]
+    pub buy_ratio: String,
+    #[serde(rename = "sellRatio")]
+    pub sell_ratio: String,
+    pub timestamp: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AccountRatioResult {
+    /// Ratio samples, newest first.
+    pub list: Vec<BybitAccountRatio>,
+    #[serde(rename = "nextPageCursor", default)]
+    pub next_page_cursor: Option<String>,
+}
+
+fn parse_field<T: std::str::FromStr>(value: &str, name: &str) -> Result<T, BybitError> {
+    value
+        .parse()
+        .map_err(|_| BybitError::Parse(format!("{}: {}", name, value)))
+}
+
+fn validate_stats_interval(interval: &str) -> Result<(), BybitError> {
+    if !STATS_INTERVALS.contains(&interval) {
+        return Err(BybitError::InvalidArg(format!(
+            "interval {} (expected one of {})",
+            interval,
+            STATS_INTERVALS.join(", ")
+        )));
+    }
+    Ok(())
+}
+
+/// Checks that `category` is one of the derivatives categories, which are the only ones
+/// `endpoint` serves.
+fn validate_derivatives_category(category: &str, endpoint: &str) -> Result<(), BybitError> {
+    validate_category(category)?;
+    if category != "linear" && category != "inverse" {
+        return Err(BybitError::InvalidArg(format!(
+            "category {} ({} is only available for linear and inverse)",
+            category, endpoint
+        )));
+    }
+    Ok(())
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OrderbookResult {
+    #[serde(rename = "s")]
+    pub symbol: String,
+    /// Bid levels as `[price, size]`, best first.
+    #[serde(rename = "b")]
+    pub bids: Vec<[String; 2]>,
+    /// Ask levels as `[price, size]`, best first.
+    #[serde(rename = "a")]
+    pub asks: Vec<[String; 2]>,
+    pub ts: i64,
+    #[serde(rename = "u")]
+    pub update_id: i64,
+}
+
+/// An L2 orderbook snapshot, with levels as `(price, size)` ordered from the best price.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DepthSnapshot {
+    pub symbol: String,
+    /// Snapshot time in milliseconds.
+    pub timestamp: i64,
+    pub update_id: i64,
+    pub bids: Vec<(f64, f64)>,
+    pub asks: Vec<(f64, f64)>,
+}
+
+impl OrderbookResult {
+    fn to_snapshot(&self) -> Result<DepthSnapshot, BybitError> {
+        Ok(DepthSnapshot {
+            symbol: self.symbol.clone(),
+            timestamp: self.ts,
+            update_id: self.update_id,
+            bids: parse_levels(&self.bids)?,
+            asks: parse_levels(&self.asks)?,
+        })
+    }
+}
+
+pub(crate) fn parse_levels(levels: &[[String; 2]]) -> Result<Vec<(f64, f64)>, BybitError> {
+    levels
+        .iter()
+        .map(|[px, qty]| {
+            let px = px
+                .parse()
+                .map_err(|_| BybitError::Parse(format!("orderbook price: {}", px)))?;
+            let qty = qty
+                .parse()
+                .map_err(|_| BybitError::Parse(format!("orderbook size: {}", qty)))?;
+            Ok((px, qty))
+        })
+        .collect()
+}
+
+/// Returns the deepest orderbook snapshot Bybit serves for `category`.
+fn max_orderbook_depth(category: &str) -> usize {
+    match category {
+        "spot" => 200,
+        "option" => 25,
+        _ => 500,
+    }
+}
+
+/// Request statistics of a fetch, used to verify the effective request rate.
+#[pyclass(module = "hftbacktest", get_all)]
+#[derive(Debug, Clone, Default)]
+pub struct FetchStats {
+    /// Number of HTTP requests sent, including rate-limited ones.
+    pub requests: u64,
+    /// Number of requests rejected with HTTP 429.
+    pub rate_limited: u64,
+    /// Number of requests resent after a 429, a 5xx, a timeout or a dropped connection.
+    pub retries: u64,
+    /// Number of trades delivered.
+    pub trades: usize,
+    pub elapsed: Duration,
+}
+
+#[pymethods]
+impl FetchStats {
+    /// Returns the effective number of requests per second.
+    pub fn request_rate(&self) -> f64 {
+        let secs = self.elapsed.as_secs_f64();
+        if secs > 0.0 {
+            self.requests as f64 / secs
+        } else {
+            0.0
+        }
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "FetchStats(requests={}, rate_limited={}, retries={}, trades={}, elapsed={:.3}s, request_rate={:.2}/s)",
+            self.requests,
+            self.rate_limited,
+            self.retries,
+            self.trades,
+            self.elapsed.as_secs_f64(),
+            self.request_rate()
+        )
+    }
+}
+
+impl FetchStats {
+    pub fn merge(&mut self, other: &FetchStats) {
+        self.requests += other.requests;
+        self.rate_limited += other.rate_limited;
+        self.retries += other.retries;
+        self.trades += other.trades;
+        self.elapsed += other.elapsed;
+    }
+}
+
+/// Default threshold, in milliseconds, above which a stretch without trades is reported as a gap.
+pub const DEFAULT_MAX_GAP_MS: i64 = 60_000;
+
+/// Findings of [`validate_rows`] on a trade series. Indices refer to positions in the series.
+#[pyclass(module = "hftbacktest", get_all)]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TradeReport {
+    /// `(start, end)` timestamps of intervals longer than the gap threshold without trades.
+    pub gaps: Vec<(i64, i64)>,
+    /// Trades out of order relative to the direction of the series, which may be ascending or
+    /// descending as Bybit pages are newest first.
+    pub non_monotonic: Vec<usize>,
+    /// Trades with a zero, negative or non-finite size or price.
+    pub invalid_values: Vec<usize>,
+    /// Trades whose side is neither `Buy` nor `Sell`.
+    pub invalid_sides: Vec<usize>,
+}
+
+#[pymethods]
+impl TradeReport {
+    /// Returns true if nothing was found.
+    pub fn is_clean(&self) -> bool {
+        *self == Self::default()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "TradeReport(gaps={}, non_monotonic={}, invalid_values={}, invalid_sides={})",
+            self.gaps.len(),
+            self.non_monotonic.len(),
+            self.invalid_values.len(),
+            self.invalid_sides.len()
+        )
+    }
+}
+
+/// Scans a trade series for gaps longer than `max_gap_ms`, timestamps out of order, and
+/// implausible sizes, prices or sides.
+pub fn validate_rows(trades: &[TradeRow], max_gap_ms: i64) -> TradeReport {
+    let mut report = TradeReport::default();
+    let ascending = match (trades.first(), trades.last()) {
+        (Some(first), Some(last)) => first.timestamp <= last.timestamp,
+        _ => true,
+    };
+    for (i, trade) in trades.iter().enumerate() {
+        if let Some(prev) = i.checked_sub(1).map(|j| &trades[j]) {
+            let in_order = if ascending {
+                prev.timestamp <= trade.timestamp
+            } else {
+                prev.timestamp >= trade.timestamp
+            };
+            if !in_order {
+                report.non_monotonic.push(i);
+            }
+        }
+        let valid = |v: f64| v.is_finite() && v > 0.0;
+        if !valid(trade.size) || !valid(trade.price) {
+            report.invalid_values.push(i);
+        }
+        if trade.side_sign() == 0 {
+            report.invalid_sides.push(i);
+        }
+    }
+
+    let mut timestamps = trades.iter().map(|t| t.timestamp).collect::<Vec<_>>();
+    timestamps.sort_unstable();
+    report.gaps = timestamps
+        .windows(2)
+        .filter(|w| w[1] - w[0] > max_gap_ms)
+        .map(|w| (w[0], w[1]))
+        .collect();
+    report
+}
+
+/// Upper bound on the number of sub-windows fetched concurrently for one symbol, to stay clear of
+/// Bybit's per-IP rate limit.
+pub const MAX_CONCURRENT_WORKERS: usize = 8;
+
+/// Bybit v5 REST hosts.
+pub const MAINNET_BASE_URL: &str = "https://api.bybit.com";
+pub const TESTNET_BASE_URL: &str = "https://api-testnet.bybit.com";
+
+/// Default number of consecutive empty pages with a next cursor after which pagination is
+/// considered stalled.
+pub const DEFAULT_MAX_EMPTY_PAGES: u32 = 10;
+
+/// Default delay, in milliseconds, between consecutive page requests without rate-limit headers.
+pub const DEFAULT_PAGE_DELAY_MS: u64 = 50;
+
+/// Default validity window, in milliseconds, of a signed request.
+pub const DEFAULT_RECV_WINDOW: u64 = 5000;
+
+/// How far back, in milliseconds, the trade endpoint serves history. Older windows come back empty.
+pub const TRADE_RETENTION_MS: i64 = 7 * 24 * 60 * 60 * 1000;
+
+/// Product types accepted by Bybit's v5 market endpoints.
+pub const CATEGORIES: [&str; 4] = ["linear", "inverse", "spot", "option"];
+
+/// Start times below this many milliseconds (1973-03-03) are taken for seconds passed without a
+/// `time_unit`, which Bybit would answer with an empty list rather than an error.
+const MIN_START_TIME_MS: i64 = 100_000_000_000;
+
+/// Unit of the timestamps passed to and returned by the trade fetches. Requests are always sent in
+/// milliseconds, and events always carry nanoseconds regardless of the unit.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TimeUnit {
+    S,
+    Ms,
+    Us,
+    Ns,
+}
+
+impl TimeUnit {
+    /// Parses `time_unit`, one of `"s"`, `"ms"`, `"us"` or `"ns"`. Without one, the timestamps
+    /// are milliseconds, and a `start_time` that looks like seconds is rejected.
+    fn resolve(time_unit: Option<&str>, start_time: i64) -> PyResult<Self> {
+        match time_unit {
+            Some("s") => Ok(Self::S),
+            Some("ms") => Ok(Self::Ms),
+            Some("us") => Ok(Self::Us),
+            Some("ns") => Ok(Self::Ns),
+            Some(unit) => Err(PyValueError::new_err(format!(
+                "time_unit must be 's', 'ms', 'us' or 'ns', got {:?}",
+                unit
+            ))),
+            None if start_time < MIN_START_TIME_MS => Err(PyValueError::new_err(format!(
+                "start_time {} is before 1973 in milliseconds and looks like seconds; pass \
+                 time_unit='s' or multiply it by 1000",
+                start_time
+            ))),
+            None => Ok(Self::Ms),
+        }
+    }
+
+    /// Converts `t` in this unit to milliseconds, rounding up, so that a bound within a
+    /// millisecond leaves out the trades stamped with it.
+    pub fn unit_to_ms(self, t: i64) -> i64 {
+        let div_ceil = |n: i64| t.div_euclid(n) + (t.rem_euclid(n) != 0) as i64;
+        match self {
+            Self::S => t.saturating_mul(1000),
+            Self::Ms => t,
+            Self::Us => div_ceil(1000),
+            Self::Ns => div_ceil(1_000_000),
+        }
+    }
+
+    /// Converts `t` in milliseconds to this unit, truncating to the second for seconds.
+    pub fn ms_to_unit(self, t: i64) -> i64 {
+        match self {
+            Self::S => t.div_euclid(1000),
+            Self::Ms => t,
+            Self::Us => t.saturating_mul(1000),
+            Self::Ns => t.saturating_mul(1_000_000),
+        }
+    }
+
+    /// Converts the timestamps of `trades` from milliseconds to this unit.
+    pub fn convert_trades(self, trades: &mut [TradeRow]) {
+        if self != Self::Ms {
+            for trade in trades {
+                trade.timestamp = self.ms_to_unit(trade.timestamp);
+            }
+        }
+    }
+}
+
+fn validate_range(start_time: i64, end_time: i64) -> Result<(), BybitError> {
+    if start_time >= end_time {
+        return Err(BybitError::InvalidRange(format!(
+            "start_time {} must be before end_time {}",
+            start_time, end_time
+        )));
+    }
+    Ok(())
+}
+
+/// Fails if `start_time` lies beyond the trade endpoint's retention horizon as of `now`, in which
+/// case at least part of the window would silently come back empty.
+fn check_retention(start_time: i64, now: i64) -> Result<(), BybitError> {
+    let horizon = now - TRADE_RETENTION_MS;
+    if start_time < horizon {
+        return Err(BybitError::InvalidRange(format!(
+            "start_time {} is older than the {}-day trade retention (earliest {}); older trades \
+             are not returned",
+            start_time,
+            TRADE_RETENTION_MS / (24 * 60 * 60 * 1000),
+            horizon
+        )));
+    }
+    Ok(())
+}
+
+fn validate_category(category: &str) -> Result<(), BybitError> {
+    if !CATEGORIES.contains(&category) {
+        return Err(BybitError::InvalidArg(format!(
+            "category {} (expected one of {})",
+            category,
+            CATEGORIES.join(", ")
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    pub(super) fn parse_page(body: &str) -> TradeResult {
+        serde_json::from_str::<BybitResponse<TradeResult>>(body)
+            .unwrap()
+            .result
+    }
+
+    #[test]
+    fn test_parse_funding() {
+        let result: FundingResult = serde_json::from_str(
+            r#"{
+                "category": "linear",
+                "list": [
+                    {"symbol": "BTCUSDT", "fundingRate": "-0.000072", "fundingRateTimestamp": "1704096000000"},
+                    {"symbol": "BTCUSDT", "fundingRate": "0.0001", "fundingRateTimestamp": "1704067200000"}
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let rows = result
+            .list
+            .iter()
+            .map(BybitFunding::to_row)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(
+            rows,
+            vec![
+                FundingRow {
+                    timestamp: 1704096000000,
+                    symbol: "BTCUSDT".to_string(),
+                    funding_rate: -0.000072,
+                },
+                FundingRow {
+                    timestamp: 1704067200000,
+                    symbol: "BTCUSDT".to_string(),
+                    funding_rate: 0.0001,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_orderbook() {
+        let result: OrderbookResult = serde_json::from_str(
+            r#"{
+                "s": "BTCUSDT",
+                "b": [["65485.47", "47.081829"], ["65485", "0.001"]],
+                "a": [["65557.7", "16.606555"]],
+                "ts": 1716863719031,
+                "u": 230704,
+                "seq": 1432604333,
+                "cts": 1716863718905
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            result.to_snapshot().unwrap(),
+            DepthSnapshot {
+                symbol: "BTCUSDT".to_string(),
+                timestamp: 1716863719031,
+                update_id: 230704,
+                bids: vec![(65485.47, 47.081829), (65485.0, 0.001)],
+                asks: vec![(65557.7, 16.606555)],
+            }
+        );
+        assert_eq!(max_orderbook_depth("linear"), 500);
+        assert_eq!(max_orderbook_depth("spot"), 200);
+        assert_eq!(max_orderbook_depth("option"), 25);
+    }
+
+    #[test]
+    fn test_validate_range() {
+        assert!(validate_range(1000, 2000).is_ok());
+        let err = validate_range(2000, 1000).unwrap_err();
+        assert!(matches!(err, BybitError::InvalidRange(_)));
+        assert!(err.to_string().contains("2000") && err.to_string().contains("1000"));
+        assert!(validate_range(1000, 1000).is_err());
+
+        let now = 1_700_000_000_000;
+        assert!(check_retention(now - TRADE_RETENTION_MS, now).is_ok());
+        let err = check_retention(now - TRADE_RETENTION_MS - 1, now).unwrap_err();
+        assert!(matches!(err, BybitError::InvalidRange(_)));
+        assert!(
+            err.to_string()
+                .contains(&(now - TRADE_RETENTION_MS - 1).to_string())
+        );
+    }
+
+    /// Answers a single trade-page request with `body` and returns the request it received.
+    pub(super) async fn serve_once(listener: tokio::net::TcpListener, body: &str) -> String {
+        serve_once_from(&listener, body).await
+    }
+
+    /// Same as [`serve_once`], leaving `listener` open for further requests.
+    pub(super) async fn serve_once_from(listener: &tokio::net::TcpListener, body: &str) -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let mut buf = vec![0; 4096];
+        let n = socket.read(&mut buf).await.unwrap();
+        let response = format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            body.len(),
+            body
+        );
+        socket.write_all(response.as_bytes()).await.unwrap();
+        String::from_utf8_lossy(&buf[..n]).to_lowercase()
+    }
+
+    #[test]
+    fn test_time_unit_conversion() {
+        assert_eq!(TimeUnit::S.unit_to_ms(1_704_067_200), 1_704_067_200_000);
+        assert_eq!(
+            TimeUnit::Ms.unit_to_ms(1_704_067_200_123),
+            1_704_067_200_123
+        );
+        assert_eq!(
+            TimeUnit::Us.unit_to_ms(1_704_067_200_123_000),
+            1_704_067_200_123
+        );
+        assert_eq!(
+            TimeUnit::Ns.unit_to_ms(1_704_067_200_123_000_000),
+            1_704_067_200_123
+        );
+        // A bound within a millisecond rounds up, leaving out the trades stamped with it.
+        assert_eq!(
+            TimeUnit::Ns.unit_to_ms(1_704_067_200_123_000_001),
+            1_704_067_200_124
+        );
+        assert_eq!(TimeUnit::Us.unit_to_ms(-1), 0);
+
+        assert_eq!(TimeUnit::S.ms_to_unit(1_704_067_200_999), 1_704_067_200);
+        assert_eq!(
+            TimeUnit::Us.ms_to_unit(1_704_067_200_123),
+            1_704_067_200_123_000
+        );
+        assert_eq!(
+            TimeUnit::Ns.ms_to_unit(1_704_067_200_123),
+            1_704_067_200_123_000_000
+        );
+        for unit in [TimeUnit::S, TimeUnit::Ms, TimeUnit::Us, TimeUnit::Ns] {
+            assert_eq!(
+                unit.unit_to_ms(unit.ms_to_unit(1_704_067_200_000)),
+                1_704_067_200_000
+            );
+        }
+    }
+
+    #[test]
+    fn test_validate_rows() {
+        let trade = |timestamp: i64, side: &str, size: f64, price: f64| TradeRow {
+            exec_id: String::new(),
+            timestamp,
+            symbol: "BTCUSDT".to_string(),
+            side: side.to_string(),
+            size,
+            price,
+            is_block_trade: false,
+            mark_price: None,
+            index_price: None,
+            mark_iv: None,
+            iv: None,
+            raw_price: None,
+            raw_size: None,
+        };
+
+        // Newest first, as fetched.
+        let clean = vec![
+            trade(3000, "Buy", 0.1, 42000.0),
+            trade(2000, "Sell", 0.2, 42001.0),
+            trade(1000, "Buy", 0.3, 42002.0),
+        ];
+        assert!(validate_rows(&clean, 60_000).is_clean());
+        assert!(validate_rows(&[], 60_000).is_clean());
+
+        let trades = vec![
+            trade(1000, "Buy", 0.1, 42000.0),
+            trade(200_000, "Sell", 0.0, 42000.0),
+            trade(150_000, "buy", 0.1, -1.0),
+            trade(210_000, "Buy", 0.1, 42000.0),
+        ];
+        let report = validate_rows(&trades, 60_000);
+        assert_eq!(report.gaps, vec![(1000, 150_000)]);
+        assert_eq!(report.non_monotonic, vec![2]);
+        assert_eq!(report.invalid_values, vec![1, 2]);
+        assert_eq!(report.invalid_sides, vec![2]);
+        assert!(!report.is_clean());
+    }
+
+    #[test]
+    fn test_transient_errors() {
+        assert!(BybitError::Transport("connection reset".to_string()).is_transient());
+        assert!(BybitError::RateLimited.is_transient());
+        assert!(BybitError::Http(StatusCode::BAD_GATEWAY).is_transient());
+        assert!(!BybitError::Http(StatusCode::FORBIDDEN).is_transient());
+        assert!(
+            !BybitError::Api {
+                code: 10001,
+                msg: "params error".to_string(),
+            }
+            .is_transient()
+        );
+        assert!(!BybitError::Parse("response".to_string()).is_transient());
+    }
+
+    #[test]
+    fn test_non_fatal_errors() {
+        assert!(BybitError::Transport("connection reset".to_string()).is_non_fatal());
+        assert!(
+            BybitError::Api {
+                code: 10016,
+                msg: "Internal server error".to_string(),
+            }
+            .is_non_fatal()
+        );
+        assert!(
+            !BybitError::Api {
+                code: 10001,
+                msg: "params error".to_string(),
+            }
+            .is_non_fatal()
+        );
+        assert!(!BybitError::InvalidRange("empty".to_string()).is_non_fatal());
+    }
+
+    #[test]
+    fn test_unknown_symbol_error() {
+        assert!(matches!(
+            api_error(10001, "params error: symbol invalid".to_string(), "category=linear&symbol=BTCUSD"),
+            BybitError::UnknownSymbol(symbol) if symbol == "BTCUSD"
+        ));
+        assert!(matches!(
+            api_error(
+                10001,
+                "params error: limit".to_string(),
+                "category=linear&symbol=BTCUSDT"
+            ),
+            BybitError::Api { code: 10001, .. }
+        ));
+        assert!(!BybitError::UnknownSymbol("BTCUSD".to_string()).is_non_fatal());
+    }
+
+    #[test]
+    fn test_parse_kline() {
+        let resp: BybitResponse<KlineResult> = serde_json::from_str(
+            r#"{"retCode":0,"retMsg":"OK","result":{"symbol":"BTCUSDT","category":"linear","list":[
+                ["1704067260000","42300.5","42350","42280.1","42340","12.5","528750.25"]
+            ]}}"#,
+        )
+        .unwrap();
+
+        let kline = parse_kline(&resp.result.list[0]).unwrap();
+        assert_eq!(kline.start_time, 1704067260000);
+        assert_eq!(kline.open, 42300.5);
+        assert_eq!(kline.high, 42350.0);
+        assert_eq!(kline.low, 42280.1);
+        assert_eq!(kline.close, 42340.0);
+        assert_eq!(kline.volume, 12.5);
+        assert_eq!(kline.turnover, 528750.25);
+    }
+}
@@ -0,0 +1,1868 @@
+//! Pagination of the Bybit endpoints, by cursor or by walking the time window backward, and the
+//! trade filtering, deduplication and sorting applied to the pages.
+
+use std::{collections::HashSet, ffi::CString, ops::ControlFlow, sync::Arc, time::Instant};
+
+use chrono::Utc;
+use pyo3::{exceptions::PyUserWarning, prelude::*, types::PyList};
+use serde::de::DeserializeOwned;
+use tokio::{sync::Semaphore, task::JoinSet};
+use tracing::{Instrument, debug, debug_span};
+
+use super::{
+    AccountRatioResult, BybitError, BybitFunding, BybitTrade, DepthSnapshot, FetchStats,
+    FundingResult, FundingRow, InstrumentResult, InstrumentRow, KLINE_INTERVALS, KlineResult,
+    KlineRow, MAX_CONCURRENT_WORKERS, OpenInterestResult, OrderbookResult, SymbolTrades, TimeUnit,
+    TradeResult, TradeRow, check_retention,
+    client::{BybitHistoryFetcher, FetchConfig, RateLimitStatus, record_stats, runtime},
+    exceptions,
+    export::TradeCache,
+    max_orderbook_depth, parse_field, parse_kline, validate_category,
+    validate_derivatives_category, validate_range, validate_stats_interval,
+};
+
+impl BybitHistoryFetcher {
+    /// Requests the page of a cursor-paginated endpoint at `cursor`, or its first page if `None`.
+    async fn get_page<T: CursorPage>(
+        &self,
+        path: &str,
+        query_string: &str,
+        cursor: Option<&str>,
+        stats: &mut FetchStats,
+    ) -> Result<(T, Option<RateLimitStatus>), BybitError> {
+        match cursor {
+            Some(cursor) => {
+                let query_string = format!("{}&cursor={}", query_string, cursor);
+                self.get::<T>(path, &query_string, stats).await
+            },
+            None => self.get::<T>(path, query_string, stats).await,
+        }
+    }
+
+    /// Walks a cursor-paginated endpoint from `cursor`, or from the first page if `None`, passing
+    /// each page's `result` to `on_page` until a page comes without a next cursor or `on_page`
+    /// breaks. Requests are paced using the rate-limit response headers, and retried as described
+    /// in [`get`](Self::get), so every paginated fetch shares the same rate-limit behavior.
+    async fn get_pages<T, F>(
+        &self,
+        path: &str,
+        query_string: &str,
+        cursor: Option<&str>,
+        stats: &mut FetchStats,
+        mut on_page: F,
+    ) -> Result<(), BybitError>
+    where
+        T: CursorPage,
+        F: FnMut(T) -> Result<ControlFlow<()>, BybitError>,
+    {
+        let mut cursor = cursor.map(str::to_string);
+        let mut empty_pages = 0;
+        loop {
+            let (result, rate_limit) = self
+                .get_page::<T>(path, query_string, cursor.as_deref(), stats)
+                .await?;
+            let next_cursor = result.next_page_cursor().map(str::to_string);
+            debug!(from = ?cursor, to = ?next_cursor, "cursor advanced");
+            cursor = next_cursor;
+            let is_empty = result.is_empty();
+            if on_page(result)?.is_break() || cursor.is_none() {
+                return Ok(());
+            }
+            self.check_stalled(&mut empty_pages, is_empty)?;
+            let delay = self.page_delay(&rate_limit);
+            debug!(delay_ms = delay.as_millis() as u64, "pacing next page");
+            tokio::time::sleep(delay).await;
+        }
+    }
+
+    /// Walks `[start_time, end_time]` of an endpoint without a cursor, which returns the newest
+    /// rows of the requested window first, backwards: `query_string` builds the query of the
+    /// window ending at the given time, `parse_page` parses a page's `result` into rows, and the
+    /// next request ends just before the oldest row received, until a page comes empty. Returns
+    /// the rows in ascending order of `timestamp`, deduplicated by it.
+    async fn get_pages_backward<T, R, Q, P>(
+        &self,
+        path: &str,
+        start_time: i64,
+        end_time: i64,
+        query_string: Q,
+        mut parse_page: P,
+        timestamp: fn(&R) -> i64,
+    ) -> Result<Vec<R>, BybitError>
+    where
+        T: DeserializeOwned,
+        Q: Fn(i64) -> String,
+        P: FnMut(T) -> Result<Vec<R>, BybitError>,
+    {
+        let mut stats = FetchStats::default();
+        let mut rows = Vec::new();
+        let mut end = end_time;
+
+        while end >= start_time {
+            let (result, rate_limit) = self.get::<T>(path, &query_string(end), &mut stats).await?;
+            let page = parse_page(result)?;
+            let Some(oldest) = page.iter().map(timestamp).min() else {
+                break; // No more rows in the window
+            };
+            rows.extend(page);
+            end = oldest - 1;
+            tokio::time::sleep(self.page_delay(&rate_limit)).await;
+        }
+
+        rows.sort_by_key(timestamp);
+        rows.dedup_by_key(|row| timestamp(row));
+        Ok(rows)
+    }
+
+    /// Runs the pagination loop, passing the deduplicated trades of each page to `on_page` as soon
+    /// as the page arrives instead of accumulating them, along with the cursor of the next page, or
+    /// `None` on the last page.
+    ///
+    /// Requests are paced using Bybit's rate-limit response headers when present, falling back to
+    /// the delay of [`with_page_delay_ms`](Self::with_page_delay_ms) between pages and exponential
+    /// backoff on HTTP 429 otherwise.
+    pub async fn fetch_trade_pages<F>(
+        &self,
+        category: &str,
+        symbol: &str,
+        start_time: i64,
+        end_time: i64,
+        limit: i32,
+        on_page: F,
+    ) -> Result<FetchStats, BybitError>
+    where
+        F: FnMut(Vec<TradeRow>, Option<&str>) -> Result<(), BybitError>,
+    {
+        self.fetch_trade_pages_from(category, symbol, start_time, end_time, limit, None, on_page)
+            .await
+    }
+
+    /// Same as [`fetch_trade_pages`](Self::fetch_trade_pages), but starts from `cursor` if given,
+    /// resuming an earlier fetch with the same arguments that was interrupted.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn fetch_trade_pages_from<F>(
+        &self,
+        category: &str,
+        symbol: &str,
+        start_time: i64,
+        end_time: i64,
+        limit: i32,
+        cursor: Option<&str>,
+        mut on_page: F,
+    ) -> Result<FetchStats, BybitError>
+    where
+        F: FnMut(Vec<TradeRow>, Option<&str>) -> Result<(), BybitError>,
+    {
+        validate_category(category)?;
+
+        let started = Instant::now();
+        let mut stats = FetchStats::default();
+        let mut prev_page_exec_ids = HashSet::new();
+        let mut num_trades = 0;
+        let query_string = trades_query(category, symbol, start_time, end_time, limit);
+
+        self.get_pages(
+            "/v5/market/trades",
+            &query_string,
+            cursor,
+            &mut stats,
+            |result: TradeResult| {
+                let next_cursor = result.next_page_cursor().map(str::to_string);
+                let mut page = Vec::with_capacity(result.list.len());
+                append_page(&mut page, &mut prev_page_exec_ids, result.list)?;
+                debug!(trades = page.len(), "received page");
+                num_trades += page.len();
+                on_page(page, next_cursor.as_deref())?;
+                Ok(ControlFlow::Continue(()))
+            },
+        )
+        .instrument(debug_span!(
+            "fetch_trades",
+            category,
+            symbol,
+            start_time,
+            end_time
+        ))
+        .await?;
+
+        stats.trades += num_trades;
+        stats.elapsed = started.elapsed();
+        Ok(stats)
+    }
+
+    /// Fetches the single page of trades at `cursor`, or the first page of the window if `None`,
+    /// with one request, retried like any other. Returns the trades of the page in the order
+    /// Bybit sends them, newest first, and the cursor of the next page, or `None` on the last
+    /// page, so that the caller drives the pagination and can persist the cursor.
+    ///
+    /// Nothing is kept between calls, so unlike [`fetch_trade_pages`](Self::fetch_trade_pages),
+    /// a trade repeated at the boundary of two pages is not dropped.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn fetch_trade_page(
+        &self,
+        category: &str,
+        symbol: &str,
+        start_time: i64,
+        end_time: i64,
+        limit: i32,
+        cursor: Option<&str>,
+        stats: &mut FetchStats,
+    ) -> Result<(Vec<TradeRow>, Option<String>), BybitError> {
+        validate_category(category)?;
+
+        let started = Instant::now();
+        let query_string = trades_query(category, symbol, start_time, end_time, limit);
+        let (result, _) = self
+            .get_page::<TradeResult>("/v5/market/trades", &query_string, cursor, stats)
+            .instrument(debug_span!("fetch_trades_page", category, symbol, cursor))
+            .await?;
+        let next_cursor = result.next_page_cursor().map(str::to_string);
+        let mut page = Vec::with_capacity(result.list.len());
+        append_page(&mut page, &mut HashSet::new(), result.list)?;
+        debug!(trades = page.len(), "received page");
+
+        stats.trades += page.len();
+        stats.elapsed += started.elapsed();
+        Ok((page, next_cursor))
+    }
+
+    /// Fetches all trades in the half-open window `[start_time, end_time)` with the `category`,
+    /// `limit` and filters of `config`, sorted by timestamp as described in [`sort_trades`]. The
+    /// client settings of `config` are not used here; see [`Self::from_config`].
+    pub async fn fetch_trades(
+        &self,
+        symbol: &str,
+        start_time: i64,
+        end_time: i64,
+        config: &FetchConfig,
+    ) -> Result<(Vec<TradeRow>, FetchStats), BybitError> {
+        let mut trades = Vec::new();
+        let mut stats = self
+            .fetch_trade_pages(
+                &config.category,
+                symbol,
+                start_time,
+                end_time,
+                config.limit,
+                |mut page, _| {
+                    config.retain(&mut page);
+                    trades.extend(page);
+                    Ok(())
+                },
+            )
+            .await?;
+        retain_before(&mut trades, end_time);
+        config.sort(&mut trades)?;
+        stats.trades = trades.len();
+        Ok((trades, stats))
+    }
+
+    /// Fetches the `n` most recent trades, newest first. Without a time range, the trade endpoint
+    /// starts from the latest trade, so this walks back through the pages until `n` trades are
+    /// collected or there is no older page.
+    pub async fn fetch_recent_trades(
+        &self,
+        category: &str,
+        symbol: &str,
+        n: usize,
+    ) -> Result<(Vec<TradeRow>, FetchStats), BybitError> {
+        validate_category(category)?;
+
+        let started = Instant::now();
+        let mut stats = FetchStats::default();
+        let mut trades = Vec::with_capacity(n);
+        let mut prev_page_exec_ids = HashSet::new();
+        let query_string = [
+            format!("category={}", category),
+            format!("symbol={}", symbol),
+            format!("limit={}", n.clamp(1, TRADE_PAGE_LIMIT)),
+        ]
+        .join("&");
+        if n > 0 {
+            self.get_pages(
+                "/v5/market/trades",
+                &query_string,
+                None,
+                &mut stats,
+                |result: TradeResult| {
+                    append_page(&mut trades, &mut prev_page_exec_ids, result.list)?;
+                    Ok(if trades.len() >= n {
+                        ControlFlow::Break(())
+                    } else {
+                        ControlFlow::Continue(())
+                    })
+                },
+            )
+            .await?;
+        }
+        trades.truncate(n);
+
+        stats.trades = trades.len();
+        stats.elapsed = started.elapsed();
+        Ok((trades, stats))
+    }
+
+    /// Fetches trades in `[start_time, end_time)` one fixed-size time window at a time, passing
+    /// each completed window to `on_chunk`, sorted as described in [`sort_trades`], before
+    /// fetching the next one, so only a single chunk is held in memory. Windows are half-open, so a trade on a boundary millisecond belongs to
+    /// exactly one chunk. The last window may be shorter than `chunk_ms`.
+    ///
+    /// The returned stats cover all windows; `trades` is the total number of trades delivered.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn fetch_trades_chunked<F>(
+        &self,
+        category: &str,
+        symbol: &str,
+        start_time: i64,
+        end_time: i64,
+        chunk_ms: i64,
+        limit: i32,
+        mut on_chunk: F,
+    ) -> Result<FetchStats, BybitError>
+    where
+        F: FnMut(Vec<TradeRow>) -> Result<(), BybitError>,
+    {
+        if chunk_ms <= 0 {
+            return Err(BybitError::InvalidArg(format!(
+                "chunk_ms must be positive: {}",
+                chunk_ms
+            )));
+        }
+
+        let mut stats = FetchStats::default();
+        let mut window_start = start_time;
+        while window_start < end_time {
+            let window_end = window_start.saturating_add(chunk_ms).min(end_time);
+            let mut chunk = Vec::new();
+            // The trade endpoint treats endTime as inclusive.
+            let window_stats = self
+                .fetch_trade_pages(
+                    category,
+                    symbol,
+                    window_start,
+                    window_end - 1,
+                    limit,
+                    |page, _| {
+                        chunk.extend(page);
+                        Ok(())
+                    },
+                )
+                .await?;
+            stats.merge(&window_stats);
+            sort_trades(&mut chunk, false)?;
+            on_chunk(chunk)?;
+            window_start = window_end;
+        }
+        Ok(stats)
+    }
+
+    /// Fetches the trades of several symbols concurrently on the current runtime, running at most
+    /// `concurrency` pagination loops at a time. A failing symbol does not abort the others; the
+    /// results are returned in the order of `symbols`, along with the combined stats of the
+    /// successful fetches.
+    ///
+    /// To keep the aggregate request rate within budget, set a shared limiter with
+    /// [`with_rate_limiter`](Self::with_rate_limiter) first.
+    pub async fn fetch_trades_multi(
+        &self,
+        category: &str,
+        symbols: &[String],
+        start_time: i64,
+        end_time: i64,
+        limit: i32,
+        concurrency: usize,
+    ) -> (Vec<SymbolTrades>, FetchStats) {
+        let started = Instant::now();
+        let semaphore = Arc::new(Semaphore::new(concurrency.max(1)));
+        let handles = symbols
+            .iter()
+            .map(|symbol| {
+                let fetcher = self.clone();
+                let semaphore = semaphore.clone();
+                let category = category.to_string();
+                let symbol = symbol.clone();
+                tokio::spawn(async move {
+                    let _permit = semaphore
+                        .acquire_owned()
+                        .await
+                        .expect("semaphore is never closed");
+                    let mut trades = Vec::new();
+                    let stats = fetcher
+                        .fetch_trade_pages(
+                            &category,
+                            &symbol,
+                            start_time,
+                            end_time,
+                            limit,
+                            |page, _| {
+                                trades.extend(page);
+                                Ok(())
+                            },
+                        )
+                        .await?;
+                    Ok((trades, stats))
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let mut results = Vec::with_capacity(symbols.len());
+        let mut stats = FetchStats::default();
+        for (symbol, handle) in symbols.iter().zip(handles) {
+            let result = match handle.await {
+                Ok(Ok((trades, symbol_stats))) => {
+                    stats.merge(&symbol_stats);
+                    Ok(trades)
+                },
+                Ok(Err(e)) => Err(e),
+                Err(e) => Err(BybitError::Transport(e.to_string())),
+            };
+            results.push((symbol.clone(), result));
+        }
+        // The fetches overlap, so the elapsed times of the individual symbols don't add up.
+        stats.elapsed = started.elapsed();
+        (results, stats)
+    }
+
+    /// Fetches the trades in `[start_time, end_time]` by splitting the range into `num_workers`
+    /// equal sub-windows and walking them concurrently, instead of one cursor walk over the
+    /// whole range. The merged trades are deduplicated by `exec_id` and sorted by timestamp.
+    ///
+    /// `num_workers` is clamped to `1..=MAX_CONCURRENT_WORKERS`.
+    pub async fn fetch_trades_concurrent(
+        &self,
+        category: &str,
+        symbol: &str,
+        start_time: i64,
+        end_time: i64,
+        limit: i32,
+        num_workers: usize,
+    ) -> Result<(Vec<TradeRow>, FetchStats), BybitError> {
+        validate_category(category)?;
+
+        let started = Instant::now();
+        let windows = split_range(
+            start_time,
+            end_time,
+            num_workers.clamp(1, MAX_CONCURRENT_WORKERS),
+        );
+        let mut tasks = JoinSet::new();
+        for (window_start, window_end) in windows {
+            let fetcher = self.clone();
+            let category = category.to_string();
+            let symbol = symbol.to_string();
+            tasks.spawn(async move {
+                let mut trades = Vec::new();
+                let stats = fetcher
+                    .fetch_trade_pages(
+                        &category,
+                        &symbol,
+                        window_start,
+                        window_end,
+                        limit,
+                        |page, _| {
+                            trades.extend(page);
+                            Ok(())
+                        },
+                    )
+                    .await?;
+                Ok::<_, BybitError>((trades, stats))
+            });
+        }
+
+        let mut all_trades = Vec::new();
+        let mut stats = FetchStats::default();
+        while let Some(joined) = tasks.join_next().await {
+            let (trades, window_stats) =
+                joined.map_err(|e| BybitError::Transport(e.to_string()))??;
+            // Each window arrives newest first; reversed, its same-timestamp trades are in
+            // execution order, which the stable sort below preserves.
+            all_trades.extend(trades.into_iter().rev());
+            stats.merge(&window_stats);
+        }
+
+        // A trade on a sub-window boundary may be returned by both neighbouring windows.
+        let mut seen = HashSet::with_capacity(all_trades.len());
+        all_trades.retain(|t| seen.insert(t.exec_id.clone()));
+        all_trades.sort_by_key(|t| t.timestamp);
+        stats.trades = all_trades.len();
+        stats.elapsed = started.elapsed();
+        Ok((all_trades, stats))
+    }
+
+    /// Fetches klines whose start time falls within `[start_time, end_time]`, in ascending order.
+    ///
+    /// The kline endpoint has no cursor and returns the newest bars of the requested window
+    /// first, so pagination walks the window backwards by moving `end` before the oldest bar
+    /// received.
+    pub async fn fetch_klines(
+        &self,
+        category: &str,
+        symbol: &str,
+        interval: &str,
+        start_time: i64,
+        end_time: i64,
+        limit: i32,
+    ) -> Result<Vec<KlineRow>, BybitError> {
+        validate_category(category)?;
+        if !KLINE_INTERVALS.contains(&interval) {
+            return Err(BybitError::InvalidArg(format!(
+                "interval {} (expected one of {})",
+                interval,
+                KLINE_INTERVALS.join(", ")
+            )));
+        }
+
+        self.get_pages_backward(
+            "/v5/market/kline",
+            start_time,
+            end_time,
+            |end| {
+                [
+                    format!("category={}", category),
+                    format!("symbol={}", symbol),
+                    format!("interval={}", interval),
+                    format!("start={}", start_time),
+                    format!("end={}", end),
+                    format!("limit={}", limit),
+                ]
+                .join("&")
+            },
+            |result: KlineResult| result.list.iter().map(parse_kline).collect(),
+            |kline| kline.start_time,
+        )
+        .await
+    }
+
+    /// Fetches the funding rate history of a perpetual contract within `[start_time, end_time]`,
+    /// in ascending order.
+    ///
+    /// Like klines, the funding endpoint has no cursor and returns the newest rows first, so the
+    /// window is walked backwards in pages of up to 200 rows.
+    pub async fn fetch_funding(
+        &self,
+        category: &str,
+        symbol: &str,
+        start_time: i64,
+        end_time: i64,
+    ) -> Result<Vec<FundingRow>, BybitError> {
+        validate_derivatives_category(category, "funding history")?;
+
+        self.get_pages_backward(
+            "/v5/market/funding/history",
+            start_time,
+            end_time,
+            |end| {
+                [
+                    format!("category={}", category),
+                    format!("symbol={}", symbol),
+                    format!("startTime={}", start_time),
+                    format!("endTime={}", end),
+                    format!("limit={}", FUNDING_PAGE_LIMIT),
+                ]
+                .join("&")
+            },
+            |result: FundingResult| result.list.iter().map(BybitFunding::to_row).collect(),
+            |funding| funding.timestamp,
+        )
+        .await
+    }
+
+    /// Fetches the trading rules of the instruments listed in `category`. Spot pairs come in one
+    /// page; derivatives are paginated through the cursor.
+    pub async fn fetch_instruments(
+        &self,
+        category: &str,
+    ) -> Result<Vec<InstrumentRow>, BybitError> {
+        validate_category(category)?;
+
+        let query_string = [
+            format!("category={}", category),
+            format!("limit={}", INSTRUMENT_PAGE_LIMIT),
+        ]
+        .join("&");
+        let mut instruments = Vec::new();
+        self.get_pages(
+            "/v5/market/instruments-info",
+            &query_string,
+            None,
+            &mut FetchStats::default(),
+            |result: InstrumentResult| {
+                for instrument in &result.list {
+                    instruments.push(instrument.to_row()?);
+                }
+                Ok(ControlFlow::Continue(()))
+            },
+        )
+        .await?;
+        Ok(instruments)
+    }
+
+    /// Fetches the open interest of a perpetual or futures contract within
+    /// `[start_time, end_time]`, sampled every `interval_time`, as `(timestamp, open_interest)`
+    /// pairs in ascending order.
+    pub async fn fetch_open_interest(
+        &self,
+        category: &str,
+        symbol: &str,
+        interval_time: &str,
+        start_time: i64,
+        end_time: i64,
+    ) -> Result<Vec<(i64, f64)>, BybitError> {
+        validate_derivatives_category(category, "open interest")?;
+        validate_stats_interval(interval_time)?;
+
+        let query_string = [
+            format!("category={}", category),
+            format!("symbol={}", symbol),
+            format!("intervalTime={}", interval_time),
+            format!("startTime={}", start_time),
+            format!("endTime={}", end_time),
+            format!("limit={}", OPEN_INTEREST_PAGE_LIMIT),
+        ]
+        .join("&");
+        let mut series = Vec::new();
+        self.get_pages(
+            "/v5/market/open-interest",
+            &query_string,
+            None,
+            &mut FetchStats::default(),
+            |result: OpenInterestResult| {
+                for row in &result.list {
+                    series.push((
+                        parse_field(&row.timestamp, "open interest timestamp")?,
+                        parse_field(&row.open_interest, "open interest")?,
+                    ));
+                }
+                Ok(ControlFlow::Continue(()))
+            },
+        )
+        .await?;
+
+        series.sort_by_key(|(timestamp, _)| *timestamp);
+        Ok(series)
+    }
+
+    /// Fetches the ratio of accounts holding long positions to accounts holding short positions
+    /// within `[start_time, end_time]`, sampled every `period`, as `(timestamp, ratio)` pairs in
+    /// ascending order.
+    pub async fn fetch_long_short_ratio(
+        &self,
+        category: &str,
+        symbol: &str,
+        period: &str,
+        start_time: i64,
+        end_time: i64,
+    ) -> Result<Vec<(i64, f64)>, BybitError> {
+        validate_derivatives_category(category, "long/short ratio")?;
+        validate_stats_interval(period)?;
+
+        let query_string = [
+            format!("category={}", category),
+            format!("symbol={}", symbol),
+            format!("period={}", period),
+            format!("startTime={}", start_time),
+            format!("endTime={}", end_time),
+            format!("limit={}", ACCOUNT_RATIO_PAGE_LIMIT),
+        ]
+        .join("&");
+        let mut series = Vec::new();
+        self.get_pages(
+            "/v5/market/account-ratio",
+            &query_string,
+            None,
+            &mut FetchStats::default(),
+            |result: AccountRatioResult| {
+                for row in &result.list {
+                    let buy_ratio: f64 = parse_field(&row.buy_ratio, "buy ratio")?;
+                    let sell_ratio: f64 = parse_field(&row.sell_ratio, "sell ratio")?;
+                    series.push((
+                        parse_field(&row.timestamp, "account ratio timestamp")?,
+                        buy_ratio / sell_ratio,
+                    ));
+                }
+                Ok(ControlFlow::Continue(()))
+            },
+        )
+        .await?;
+
+        series.sort_by_key(|(timestamp, _)| *timestamp);
+        Ok(series)
+    }
+
+    /// Fetches an L2 orderbook snapshot with up to `depth_limit` levels per side.
+    ///
+    /// The maximum depth depends on the category: 500 for linear and inverse, 200 for spot, and
+    /// 25 for option.
+    pub async fn fetch_orderbook(
+        &self,
+        category: &str,
+        symbol: &str,
+        depth_limit: usize,
+    ) -> Result<DepthSnapshot, BybitError> {
+        validate_category(category)?;
+        let max_depth = max_orderbook_depth(category);
+        if depth_limit == 0 || depth_limit > max_depth {
+            return Err(BybitError::InvalidArg(format!(
+                "depth_limit {} for {} (expected 1-{})",
+                depth_limit, category, max_depth
+            )));
+        }
+
+        let query_string = [
+            format!("category={}", category),
+            format!("symbol={}", symbol),
+            format!("limit={}", depth_limit),
+        ]
+        .join("&");
+        let (result, _) = self
+            .get::<OrderbookResult>(
+                "/v5/market/orderbook",
+                &query_string,
+                &mut FetchStats::default(),
+            )
+            .await?;
+        result.to_snapshot()
+    }
+}
+
+/// Maximum number of trades the trade endpoint returns per request.
+const TRADE_PAGE_LIMIT: usize = 1000;
+
+/// Maximum number of rows the funding history endpoint returns per request.
+const FUNDING_PAGE_LIMIT: i32 = 200;
+
+/// Maximum number of instruments per instruments-info request.
+const INSTRUMENT_PAGE_LIMIT: usize = 1000;
+
+/// Maximum number of rows per open interest request.
+const OPEN_INTEREST_PAGE_LIMIT: usize = 200;
+
+/// Maximum number of rows per long/short ratio request.
+const ACCOUNT_RATIO_PAGE_LIMIT: usize = 500;
+
+/// The `result` of a cursor-paginated endpoint.
+trait CursorPage: DeserializeOwned {
+    /// Returns the cursor of the next page, or `None` on the last page.
+    fn next_page_cursor(&self) -> Option<&str>;
+
+    /// Returns true if the page has no entries.
+    fn is_empty(&self) -> bool;
+}
+
+impl CursorPage for TradeResult {
+    fn next_page_cursor(&self) -> Option<&str> {
+        self.next_page_cursor.as_deref().filter(|c| !c.is_empty())
+    }
+    fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+}
+
+impl CursorPage for OpenInterestResult {
+    fn next_page_cursor(&self) -> Option<&str> {
+        self.next_page_cursor.as_deref().filter(|c| !c.is_empty())
+    }
+    fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+}
+
+impl CursorPage for InstrumentResult {
+    fn next_page_cursor(&self) -> Option<&str> {
+        self.next_page_cursor.as_deref().filter(|c| !c.is_empty())
+    }
+    fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+}
+
+impl CursorPage for AccountRatioResult {
+    fn next_page_cursor(&self) -> Option<&str> {
+        self.next_page_cursor.as_deref().filter(|c| !c.is_empty())
+    }
+    fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+}
+
+/// Splits the inclusive range `[start, end]` into at most `n` contiguous, non-overlapping inclusive
+/// sub-ranges of near-equal length.
+fn split_range(start: i64, end: i64, n: usize) -> Vec<(i64, i64)> {
+    if start > end {
+        return Vec::new();
+    }
+    let span = end - start + 1;
+    let n = (n as i64).clamp(1, span);
+    (0..n)
+        .map(|i| (start + span * i / n, start + span * (i + 1) / n - 1))
+        .collect()
+}
+
+/// Drops the trades at or after `end_time`. The trade endpoint treats `endTime` as inclusive; this
+/// makes a fetch cover the half-open window `[start_time, end_time)`.
+/// Returns the query string of the trade endpoint, without a cursor.
+fn trades_query(
+    category: &str,
+    symbol: &str,
+    start_time: i64,
+    end_time: i64,
+    limit: i32,
+) -> String {
+    [
+        format!("category={}", category),
+        format!("symbol={}", symbol),
+        format!("startTime={}", start_time),
+        format!("endTime={}", end_time),
+        format!("limit={}", limit),
+    ]
+    .join("&")
+}
+
+pub(super) fn retain_before(trades: &mut Vec<TradeRow>, end_time: i64) {
+    trades.retain(|t| t.timestamp < end_time);
+}
+
+/// Drops block trades. They are negotiated off the order book, so they distort trade size
+/// distributions and never consume queue position.
+pub(super) fn retain_non_block(trades: &mut Vec<TradeRow>) {
+    trades.retain(|t| !t.is_block_trade);
+}
+
+/// Drops the trades smaller than `min_size`.
+pub(super) fn retain_min_size(trades: &mut Vec<TradeRow>, min_size: f64) {
+    trades.retain(|t| t.size >= min_size);
+}
+
+/// Sorts trades received newest first into ascending timestamp order. The trades are reversed
+/// before a stable sort, so trades with the same timestamp end up in execution order, the reverse of
+/// the order they were received in. Pages occasionally overlap out of order across the cursor
+/// boundary; with `strict`, any trade newer than the one received before it fails with
+/// [`BybitError::OutOfOrder`] instead.
+pub(super) fn sort_trades(trades: &mut [TradeRow], strict: bool) -> Result<(), BybitError> {
+    if strict
+        && let Some(i) = trades
+            .windows(2)
+            .position(|w| w[1].timestamp > w[0].timestamp)
+    {
+        return Err(BybitError::OutOfOrder(format!(
+            "trade {} at {} was received after trade {} at {}",
+            trades[i + 1].exec_id,
+            trades[i + 1].timestamp,
+            trades[i].exec_id,
+            trades[i].timestamp
+        )));
+    }
+    trades.reverse();
+    trades.sort_by_key(|t| t.timestamp);
+    Ok(())
+}
+
+/// Sorts trades into the total order of `(timestamp, exec_id)`, which, unlike the order of
+/// [`sort_trades`], doesn't depend on how the pages were received, so identical trades always come
+/// out in the same order. Trades with the same timestamp are no longer in execution order, since
+/// `exec_id` doesn't follow it.
+pub(super) fn sort_canonical(trades: &mut [TradeRow]) {
+    trades.sort_by(|a, b| {
+        a.timestamp
+            .cmp(&b.timestamp)
+            .then_with(|| a.exec_id.cmp(&b.exec_id))
+    });
+}
+
+/// Converts a page of trades to [`TradeRow`]s and appends them, skipping any trade whose `execId`
+/// was already seen on the previous page or earlier on the same page. Bybit may return overlapping
+/// pages when the cursor boundary falls on a millisecond with many executions.
+pub(super) fn append_page(
+    all_trades: &mut Vec<TradeRow>,
+    prev_page_exec_ids: &mut HashSet<String>,
+    page: Vec<BybitTrade>,
+) -> Result<(), BybitError> {
+    let mut page_exec_ids = HashSet::with_capacity(page.len());
+    for trade in page {
+        let timestamp: i64 = trade
+            .time
+            .parse()
+            .map_err(|_| BybitError::Parse(format!("timestamp: {}", trade.time)))?;
+
+        let size: f64 = trade
+            .size
+            .parse()
+            .map_err(|_| BybitError::Parse(format!("size: {}", trade.size)))?;
+
+        let price: f64 = trade
+            .price
+            .parse()
+            .map_err(|_| BybitError::Parse(format!("price: {}", trade.price)))?;
+
+        let parse_optional = |value: Option<String>| -> Result<Option<f64>, BybitError> {
+            value
+                .filter(|v| !v.is_empty())
+                .map(|v| {
+                    v.parse()
+                        .map_err(|_| BybitError::Parse(format!("option field: {}", v)))
+                })
+                .transpose()
+        };
+        let mark_price = parse_optional(trade.mark_price)?;
+        let index_price = parse_optional(trade.index_price)?;
+        let mark_iv = parse_optional(trade.mark_iv)?;
+        let iv = parse_optional(trade.iv)?;
+
+        if prev_page_exec_ids.contains(&trade.exec_id)
+            || !page_exec_ids.insert(trade.exec_id.clone())
+        {
+            continue;
+        }
+
+        all_trades.push(TradeRow {
+            exec_id: trade.exec_id,
+            timestamp,
+            symbol: trade.symbol,
+            side: trade.side,
+            size,
+            price,
+            is_block_trade: trade.is_block_trade,
+            mark_price,
+            index_price,
+            mark_iv,
+            iv,
+            raw_price: Some(trade.price),
+            raw_size: Some(trade.size),
+        });
+    }
+    *prev_page_exec_ids = page_exec_ids;
+    Ok(())
+}
+
+/// Fails if the window is empty. A window reaching past the trade endpoint's retention emits a
+/// `UserWarning`, or fails if `strict_range` is set.
+pub(super) fn check_trade_range(
+    py: Python,
+    start_time: i64,
+    end_time: i64,
+    strict_range: Option<bool>,
+) -> PyResult<()> {
+    validate_range(start_time, end_time)?;
+    if let Err(e) = check_retention(start_time, Utc::now().timestamp_millis()) {
+        if strict_range.unwrap_or(false) {
+            return Err(e.into());
+        }
+        PyErr::warn(
+            py,
+            &py.get_type::<PyUserWarning>(),
+            &CString::new(e.to_string())?,
+            1,
+        )?;
+    }
+    Ok(())
+}
+
+/// Runs the pagination loop with the GIL released, so other Python threads can make progress while
+/// waiting on the network. The GIL is only reacquired to report progress after each page, as a dict
+/// with `count`, `last_timestamp`, `cursor` (of the next page) and `elapsed` (seconds). The trades
+/// are sorted once all pages have arrived; see [`sort_trades`].
+///
+/// With `partial`, a [non-fatal](BybitError::is_non_fatal) error after at least one page ends the
+/// fetch with a `UserWarning` carrying the error and the cursor to resume from, and the trades
+/// collected so far are returned instead of being discarded.
+///
+/// With `config.cache_dir`, a fetch of the whole window is served from the [`TradeCache`] unless
+/// `config.force_refresh` is set, and a successful one is written to it. Partial and resumed
+/// fetches bypass the cache.
+#[allow(clippy::too_many_arguments)]
+pub(super) fn fetch_trade_rows(
+    py: Python,
+    fetcher: &BybitHistoryFetcher,
+    symbol: String,
+    start_time: i64,
+    end_time: i64,
+    config: &FetchConfig,
+    cursor: Option<String>,
+    progress: Option<PyObject>,
+    partial: bool,
+) -> PyResult<Vec<TradeRow>> {
+    let time_unit = TimeUnit::resolve(config.time_unit.as_deref(), start_time)?;
+    let (start_time, end_time) = (
+        time_unit.unit_to_ms(start_time),
+        time_unit.unit_to_ms(end_time),
+    );
+    check_trade_range(py, start_time, end_time, Some(config.strict_range))?;
+    if config.min_size.is_some_and(f64::is_nan) {
+        return Err(BybitError::InvalidArg("min_size is NaN".to_string()).into());
+    }
+
+    let cache_path = match (&config.cache_dir, &cursor) {
+        (Some(cache_dir), None) => Some(TradeCache::open(cache_dir)?.path(
+            &symbol,
+            &config.category,
+            start_time,
+            end_time,
+            config.limit,
+        )),
+        _ => None,
+    };
+    if let Some(cache_path) = cache_path.as_deref().filter(|_| !config.force_refresh) {
+        let started = Instant::now();
+        if let Some(mut trades) = py.allow_threads(|| TradeCache::read(cache_path)) {
+            config.retain(&mut trades);
+            // The entry may have been written without deterministic.
+            if config.deterministic {
+                sort_canonical(&mut trades);
+            }
+            record_stats(FetchStats {
+                trades: trades.len(),
+                elapsed: started.elapsed(),
+                ..Default::default()
+            });
+            time_unit.convert_trades(&mut trades);
+            return Ok(trades);
+        }
+    }
+
+    let rt = runtime()?;
+
+    let started = Instant::now();
+    let mut all_trades = Vec::new();
+    let mut pages = 0;
+    let mut resume_cursor = cursor.clone();
+    let mut callback_err = None;
+    let result = py.allow_threads(|| {
+        rt.block_on(fetcher.fetch_trade_pages_from(
+            &config.category,
+            &symbol,
+            start_time,
+            end_time,
+            config.limit,
+            cursor.as_deref(),
+            |mut page, cursor| {
+                retain_before(&mut page, end_time);
+                // The cache keeps the unfiltered trades; they are filtered once complete.
+                if cache_path.is_none() {
+                    config.retain(&mut page);
+                }
+                all_trades.extend(page);
+                pages += 1;
+                resume_cursor = cursor.map(str::to_string);
+                let Some(progress) = &progress else {
+                    return Ok(());
+                };
+                let last_timestamp = all_trades.last().map(|t| time_unit.ms_to_unit(t.timestamp));
+                Python::with_gil(|py| {
+                    let info = pyo3::types::PyDict::new(py);
+                    info.set_item("count", all_trades.len())?;
+                    info.set_item("last_timestamp", last_timestamp)?;
+                    info.set_item("cursor", cursor)?;
+                    info.set_item("elapsed", started.elapsed().as_secs_f64())?;
+                    progress.call1(py, (info,))
+                })
+                .map(|_| ())
+                .map_err(|e| {
+                    callback_err = Some(e);
+                    BybitError::Callback
+                })
+            },
+        ))
+    });
+
+    if result.is_err() && cache_path.is_some() {
+        config.retain(&mut all_trades);
+    }
+
+    match (result, callback_err) {
+        (_, Some(e)) => Err(e),
+        (Ok(stats), None) => {
+            record_stats(stats);
+            config.sort(&mut all_trades)?;
+            if let Some(cache_path) = &cache_path {
+                py.allow_threads(|| TradeCache::write(cache_path, &all_trades))?;
+                config.retain(&mut all_trades);
+            }
+            time_unit.convert_trades(&mut all_trades);
+            Ok(all_trades)
+        },
+        (Err(e), None) if partial && pages > 0 && e.is_non_fatal() => {
+            let message = format!(
+                "{}; returning the {} trades of the first {} pages. Pass cursor={:?} to resume",
+                e,
+                all_trades.len(),
+                pages,
+                resume_cursor.as_deref().unwrap_or_default()
+            );
+            PyErr::warn(
+                py,
+                &py.get_type::<PyUserWarning>(),
+                &CString::new(message)?,
+                1,
+            )?;
+            config.sort(&mut all_trades)?;
+            time_unit.convert_trades(&mut all_trades);
+            Ok(all_trades)
+        },
+        (Err(e), None) if e.is_transient() => {
+            let err = exceptions::FetchInterrupted::new_err(e.to_string());
+            time_unit.convert_trades(&mut all_trades);
+            let partial = all_trades
+                .iter()
+                .map(|t| t.to_dict(py, config.side_as_int, config.include_turnover))
+                .collect::<PyResult<Vec<_>>>()?;
+            err.value(py)
+                .setattr("partial", PyList::new(py, partial)?)?;
+            err.value(py).setattr("cursor", resume_cursor)?;
+            Err(err)
+        },
+        (Err(e), None) => Err(e.into()),
+    }
+}
+
+/// Counts the trades [`fetch_trade_rows`] would return, running the same pagination loop with the
+/// same retries and filters but dropping each page once counted, so memory stays flat however
+/// large the window is. Progress is reported as in `fetch_trade_rows`. The cache is neither read
+/// nor written.
+///
+/// With `partial`, a [non-fatal](BybitError::is_non_fatal) error after at least one page ends the
+/// count with a `UserWarning` carrying the error and the cursor to resume from, and the number of
+/// trades counted so far is returned.
+#[allow(clippy::too_many_arguments)]
+pub(super) fn count_trade_rows(
+    py: Python,
+    fetcher: &BybitHistoryFetcher,
+    symbol: String,
+    start_time: i64,
+    end_time: i64,
+    config: &FetchConfig,
+    cursor: Option<String>,
+    progress: Option<PyObject>,
+    partial: bool,
+) -> PyResult<usize> {
+    let time_unit = TimeUnit::resolve(config.time_unit.as_deref(), start_time)?;
+    let (start_time, end_time) = (
+        time_unit.unit_to_ms(start_time),
+        time_unit.unit_to_ms(end_time),
+    );
+    check_trade_range(py, start_time, end_time, Some(config.strict_range))?;
+    if config.min_size.is_some_and(f64::is_nan) {
+        return Err(BybitError::InvalidArg("min_size is NaN".to_string()).into());
+    }
+
+    let rt = runtime()?;
+
+    let started = Instant::now();
+    let mut count = 0;
+    let mut last_timestamp = None;
+    let mut pages = 0;
+    let mut resume_cursor = cursor.clone();
+    let mut callback_err = None;
+    let result = py.allow_threads(|| {
+        rt.block_on(fetcher.fetch_trade_pages_from(
+            &config.category,
+            &symbol,
+            start_time,
+            end_time,
+            config.limit,
+            cursor.as_deref(),
+            |mut page, cursor| {
+                retain_before(&mut page, end_time);
+                config.retain(&mut page);
+                count += page.len();
+                if let Some(trade) = page.last() {
+                    last_timestamp = Some(time_unit.ms_to_unit(trade.timestamp));
+                }
+                pages += 1;
+                resume_cursor = cursor.map(str::to_string);
+                let Some(progress) = &progress else {
+                    return Ok(());
+                };
+                Python::with_gil(|py| {
+                    let info = pyo3::types::PyDict::new(py);
+                    info.set_item("count", count)?;
+                    info.set_item("last_timestamp", last_timestamp)?;
+                    info.set_item("cursor", cursor)?;
+                    info.set_item("elapsed", started.elapsed().as_secs_f64())?;
+                    progress.call1(py, (info,))
+                })
+                .map(|_| ())
+                .map_err(|e| {
+                    callback_err = Some(e);
+                    BybitError::Callback
+                })
+            },
+        ))
+    });
+
+    match (result, callback_err) {
+        (_, Some(e)) => Err(e),
+        (Ok(stats), None) => {
+            record_stats(stats);
+            Ok(count)
+        },
+        (Err(e), None) if partial && pages > 0 && e.is_non_fatal() => {
+            let message = format!(
+                "{}; returning the count of {} trades in the first {} pages. Pass cursor={:?} to \
+                 resume",
+                e,
+                count,
+                pages,
+                resume_cursor.as_deref().unwrap_or_default()
+            );
+            PyErr::warn(
+                py,
+                &py.get_type::<PyUserWarning>(),
+                &CString::new(message)?,
+                1,
+            )?;
+            Ok(count)
+        },
+        (Err(e), None) => Err(e.into()),
+    }
+}
+
+pub(super) fn fetch_kline_rows(
+    py: Python,
+    symbol: String,
+    interval: String,
+    start_time: i64,
+    end_time: i64,
+    config: &FetchConfig,
+) -> PyResult<Vec<KlineRow>> {
+    let fetcher = BybitHistoryFetcher::from_config(config)?;
+    let rt = runtime()?;
+
+    py.allow_threads(|| {
+        rt.block_on(fetcher.fetch_klines(
+            &config.category,
+            &symbol,
+            &interval,
+            start_time,
+            end_time,
+            config.limit,
+        ))
+    })
+    .map_err(PyErr::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::bybit::tests::{parse_page, serve_once, serve_once_from};
+
+    #[test]
+    fn test_split_range() {
+        assert_eq!(
+            split_range(1000, 1999, 4),
+            vec![(1000, 1249), (1250, 1499), (1500, 1749), (1750, 1999)]
+        );
+        assert_eq!(split_range(0, 9, 3), vec![(0, 2), (3, 5), (6, 9)]);
+        assert_eq!(split_range(5, 6, 4), vec![(5, 5), (6, 6)]);
+        assert_eq!(split_range(7, 6, 4), vec![]);
+    }
+
+    #[tokio::test]
+    async fn test_trades_are_ascending_across_pages() {
+        // Newest first, as Bybit returns them, with two trades in the same millisecond.
+        let page1 = r#"{"retCode":0,"retMsg":"OK","result":{"list":[
+            {"execId":"d","symbol":"BTCUSDT","price":"100","size":"0.1","side":"Buy","time":"1704067200003","isBlockTrade":false},
+            {"execId":"c","symbol":"BTCUSDT","price":"100","size":"0.1","side":"Buy","time":"1704067200002","isBlockTrade":false},
+            {"execId":"b","symbol":"BTCUSDT","price":"100","size":"0.1","side":"Sell","time":"1704067200002","isBlockTrade":false}],"nextPageCursor":"page2"}}"#;
+        let page2 = r#"{"retCode":0,"retMsg":"OK","result":{"list":[
+            {"execId":"a","symbol":"BTCUSDT","price":"99","size":"0.1","side":"Sell","time":"1704067200001","isBlockTrade":false}],"nextPageCursor":""}}"#;
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            loop {
+                serve_once_from(&listener, page1).await;
+                serve_once_from(&listener, page2).await;
+            }
+        });
+        let fetcher = BybitHistoryFetcher::new(base_url, String::new(), String::new());
+        let exec_ids = |trades: &[TradeRow]| {
+            assert!(trades.windows(2).all(|w| w[0].timestamp <= w[1].timestamp));
+            trades.iter().map(|t| t.exec_id.clone()).collect::<Vec<_>>()
+        };
+
+        let (trades, _) = fetcher
+            .fetch_trades("BTCUSDT", 0, i64::MAX, &FetchConfig::default())
+            .await
+            .unwrap();
+        assert_eq!(exec_ids(&trades), ["a", "b", "c", "d"]);
+
+        let mut chunks = Vec::new();
+        fetcher
+            .fetch_trades_chunked("linear", "BTCUSDT", 0, i64::MAX, i64::MAX, 1000, |chunk| {
+                chunks.push(exec_ids(&chunk));
+                Ok(())
+            })
+            .await
+            .unwrap();
+        assert_eq!(chunks, [["a", "b", "c", "d"]]);
+    }
+
+    #[tokio::test]
+    async fn test_dropping_fetch_aborts_in_flight_request() {
+        use tokio::io::AsyncReadExt;
+
+        let page1 = r#"{"retCode":0,"retMsg":"OK","result":{"list":[
+            {"execId":"b","symbol":"BTCUSDT","price":"100","size":"0.1","side":"Buy","time":"1704067200002","isBlockTrade":false}],"nextPageCursor":"page2"}}"#;
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let (closed_tx, closed_rx) = tokio::sync::oneshot::channel();
+        tokio::spawn(async move {
+            serve_once_from(&listener, page1).await;
+            // Hold the second page until the client goes away.
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = vec![0; 4096];
+            while socket.read(&mut buf).await.unwrap_or(0) > 0 {}
+            let _ = closed_tx.send(());
+        });
+        let fetcher = BybitHistoryFetcher::new(base_url, String::new(), String::new());
+
+        // This is what cancelling the awaitable of fetch_trades_async does to the fetch.
+        let result = tokio::time::timeout(
+            Duration::from_millis(200),
+            fetcher.fetch_trades("BTCUSDT", 0, i64::MAX, &FetchConfig::default()),
+        )
+        .await;
+        assert!(result.is_err());
+        tokio::time::timeout(Duration::from_secs(5), closed_rx)
+            .await
+            .expect("the second page request was not aborted")
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_empty_pages_with_cursor_stall_pagination() {
+        let empty = r#"{"retCode":0,"retMsg":"OK","result":{"list":[],"nextPageCursor":"next"}}"#;
+        let trade = r#"{"retCode":0,"retMsg":"OK","result":{"list":[
+            {"execId":"a","symbol":"BTCUSDT","price":"100","size":"0.1","side":"Buy","time":"1704067200001","isBlockTrade":false}],"nextPageCursor":"next"}}"#;
+        let last = r#"{"retCode":0,"retMsg":"OK","result":{"list":[],"nextPageCursor":""}}"#;
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            // A trade resets the count, so the first run finishes; the second never gets a
+            // trade.
+            for body in [empty, empty, trade, empty, empty, last] {
+                serve_once_from(&listener, body).await;
+            }
+            loop {
+                serve_once_from(&listener, empty).await;
+            }
+        });
+        let fetcher = BybitHistoryFetcher::new(base_url, String::new(), String::new())
+            .with_max_empty_pages(3);
+        let config = FetchConfig::default();
+
+        let (trades, stats) = fetcher
+            .fetch_trades("BTCUSDT", 0, i64::MAX, &config)
+            .await
+            .unwrap();
+        assert_eq!(trades.len(), 1);
+        assert_eq!(stats.requests, 6);
+
+        let result = fetcher.fetch_trades("BTCUSDT", 0, i64::MAX, &config).await;
+        assert!(matches!(result, Err(BybitError::StalledPagination(3))));
+    }
+
+    #[tokio::test]
+    async fn test_open_interest_follows_cursor() {
+        let pages = [
+            r#"{"retCode":0,"retMsg":"OK","result":{"list":[
+                {"openInterest":"120.5","timestamp":"1704070800000"},
+                {"openInterest":"110.0","timestamp":"1704067200000"}],"nextPageCursor":"page2"}}"#,
+            r#"{"retCode":0,"retMsg":"OK","result":{"list":[
+                {"openInterest":"100.0","timestamp":"1704063600000"}],"nextPageCursor":""}}"#,
+        ];
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+            let mut requests = Vec::new();
+            for body in pages {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut buf = vec![0; 4096];
+                let n = socket.read(&mut buf).await.unwrap();
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                socket.write_all(response.as_bytes()).await.unwrap();
+                requests.push(String::from_utf8_lossy(&buf[..n]).to_string());
+            }
+            requests
+        });
+
+        let fetcher = BybitHistoryFetcher::new(base_url, String::new(), String::new());
+        let series = fetcher
+            .fetch_open_interest("linear", "BTCUSDT", "1h", 1704063600000, 1704070800000)
+            .await
+            .unwrap();
+        let requests = server.await.unwrap();
+
+        assert_eq!(
+            series,
+            vec![
+                (1704063600000, 100.0),
+                (1704067200000, 110.0),
+                (1704070800000, 120.5)
+            ]
+        );
+        assert!(requests[0].contains("intervalTime=1h"));
+        assert!(!requests[0].contains("cursor="));
+        assert!(requests[1].contains("cursor=page2"));
+    }
+
+    #[tokio::test]
+    async fn test_klines_walk_the_window_backward() {
+        let bar = |start: i64| format!(r#"["{}","1","2","0.5","1.5","10","15"]"#, start);
+        let page = |bars: &[i64]| {
+            let list: Vec<_> = bars.iter().map(|&start| bar(start)).collect();
+            format!(
+                r#"{{"retCode":0,"retMsg":"OK","result":{{"symbol":"BTCUSDT","category":"linear","list":[{}]}}}}"#,
+                list.join(",")
+            )
+        };
+        let pages = [page(&[3000, 2000]), page(&[1999, 1000]), page(&[])];
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let mut requests = Vec::new();
+            for page in &pages {
+                requests.push(serve_once_from(&listener, page).await);
+            }
+            requests
+        });
+        let fetcher =
+            BybitHistoryFetcher::new(base_url, String::new(), String::new()).with_page_delay_ms(0);
+
+        let klines = fetcher
+            .fetch_klines("linear", "BTCUSDT", "1", 0, 3500, 2)
+            .await
+            .unwrap();
+        assert_eq!(
+            klines.iter().map(|k| k.start_time).collect::<Vec<_>>(),
+            [1000, 1999, 2000, 3000]
+        );
+        let requests = server.await.unwrap();
+        assert!(requests[0].contains("&end=3500&"));
+        assert!(requests[1].contains("&end=1999&"));
+        assert!(requests[2].contains("&end=999&"));
+    }
+
+    #[tokio::test]
+    async fn test_recent_trades_stops_at_n() {
+        let pages = [
+            r#"{"retCode":0,"retMsg":"OK","result":{"list":[
+                {"execId":"4","symbol":"BTCUSDT","price":"101","size":"0.1","side":"Buy","time":"1704067200004","isBlockTrade":false},
+                {"execId":"3","symbol":"BTCUSDT","price":"100","size":"0.1","side":"Sell","time":"1704067200003","isBlockTrade":false}],"nextPageCursor":"page2"}}"#,
+            r#"{"retCode":0,"retMsg":"OK","result":{"list":[
+                {"execId":"2","symbol":"BTCUSDT","price":"99","size":"0.1","side":"Buy","time":"1704067200002","isBlockTrade":false},
+                {"execId":"1","symbol":"BTCUSDT","price":"98","size":"0.1","side":"Sell","time":"1704067200001","isBlockTrade":false}],"nextPageCursor":"page3"}}"#,
+        ];
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+            let mut requests = Vec::new();
+            for body in pages {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut buf = vec![0; 4096];
+                let n = socket.read(&mut buf).await.unwrap();
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                socket.write_all(response.as_bytes()).await.unwrap();
+                requests.push(String::from_utf8_lossy(&buf[..n]).to_string());
+            }
+            requests
+        });
+
+        let fetcher = BybitHistoryFetcher::new(base_url, String::new(), String::new());
+        let (trades, stats) = fetcher
+            .fetch_recent_trades("linear", "BTCUSDT", 3)
+            .await
+            .unwrap();
+        let requests = server.await.unwrap();
+
+        assert_eq!(
+            trades.iter().map(|t| t.timestamp).collect::<Vec<_>>(),
+            vec![1704067200004, 1704067200003, 1704067200002]
+        );
+        assert_eq!(stats.requests, 2);
+        assert_eq!(stats.trades, 3);
+        assert!(requests[0].contains("limit=3"));
+        assert!(!requests[0].contains("startTime"));
+        assert!(!requests[0].contains("cursor="));
+        assert!(requests[1].contains("cursor=page2"));
+    }
+
+    #[tokio::test]
+    async fn test_instruments_follow_cursor() {
+        let pages = [
+            r#"{"retCode":0,"retMsg":"OK","result":{"category":"linear","list":[
+                {"symbol":"BTCUSDT","contractType":"LinearPerpetual","status":"Trading","baseCoin":"BTC","quoteCoin":"USDT",
+                 "priceFilter":{"minPrice":"0.10","maxPrice":"199999.80","tickSize":"0.10"},
+                 "lotSizeFilter":{"maxOrderQty":"100.000","minOrderQty":"0.001","qtyStep":"0.001"}}],"nextPageCursor":"page2"}}"#,
+            r#"{"retCode":0,"retMsg":"OK","result":{"category":"linear","list":[
+                {"symbol":"NEWUSDT","contractType":"LinearPerpetual","status":"PreLaunch","baseCoin":"NEW","quoteCoin":"USDT",
+                 "priceFilter":{"tickSize":"0.0001"},
+                 "lotSizeFilter":{"basePrecision":"1"}}],"nextPageCursor":""}}"#,
+        ];
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+            let mut requests = Vec::new();
+            for body in pages {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut buf = vec![0; 4096];
+                let n = socket.read(&mut buf).await.unwrap();
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                socket.write_all(response.as_bytes()).await.unwrap();
+                requests.push(String::from_utf8_lossy(&buf[..n]).to_string());
+            }
+            requests
+        });
+
+        let fetcher = BybitHistoryFetcher::new(base_url, String::new(), String::new());
+        let instruments = fetcher.fetch_instruments("linear").await.unwrap();
+        let requests = server.await.unwrap();
+
+        assert_eq!(
+            instruments,
+            vec![
+                InstrumentRow {
+                    symbol: "BTCUSDT".to_string(),
+                    base_coin: "BTC".to_string(),
+                    quote_coin: "USDT".to_string(),
+                    tick_size: 0.1,
+                    lot_size: 0.001,
+                    status: "Trading".to_string(),
+                },
+                InstrumentRow {
+                    symbol: "NEWUSDT".to_string(),
+                    base_coin: "NEW".to_string(),
+                    quote_coin: "USDT".to_string(),
+                    tick_size: 0.0001,
+                    lot_size: 1.0,
+                    status: "PreLaunch".to_string(),
+                },
+            ]
+        );
+        assert!(requests[0].contains("/v5/market/instruments-info?category=linear&limit=1000"));
+        assert!(requests[1].contains("cursor=page2"));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_trade_page_makes_one_request() {
+        let page1 = r#"{"retCode":0,"retMsg":"OK","result":{"list":[
+            {"execId":"2","symbol":"BTCUSDT","price":"100","size":"0.1","side":"Buy","time":"1704067200002","isBlockTrade":false}],"nextPageCursor":"page2"}}"#;
+        let page2 = r#"{"retCode":0,"retMsg":"OK","result":{"list":[
+            {"execId":"2","symbol":"BTCUSDT","price":"100","size":"0.1","side":"Buy","time":"1704067200002","isBlockTrade":false},
+            {"execId":"1","symbol":"BTCUSDT","price":"99","size":"0.1","side":"Sell","time":"1704067200001","isBlockTrade":false}],"nextPageCursor":""}}"#;
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+            let mut requests = Vec::new();
+            for body in [page1, page2] {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut buf = vec![0; 4096];
+                let n = socket.read(&mut buf).await.unwrap();
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                socket.write_all(response.as_bytes()).await.unwrap();
+                requests.push(String::from_utf8_lossy(&buf[..n]).to_string());
+            }
+            requests
+        });
+
+        let fetcher = BybitHistoryFetcher::new(base_url, String::new(), String::new());
+        let mut stats = FetchStats::default();
+        let (trades, cursor) = fetcher
+            .fetch_trade_page("linear", "BTCUSDT", 0, 1, 1000, None, &mut stats)
+            .await
+            .unwrap();
+        assert_eq!(trades.len(), 1);
+        assert_eq!(cursor.as_deref(), Some("page2"));
+        assert_eq!(stats.requests, 1);
+
+        // The trade repeated from the previous page is not dropped across calls.
+        let (trades, cursor) = fetcher
+            .fetch_trade_page(
+                "linear",
+                "BTCUSDT",
+                0,
+                1,
+                1000,
+                cursor.as_deref(),
+                &mut stats,
+            )
+            .await
+            .unwrap();
+        let exec_ids: Vec<_> = trades.iter().map(|t| t.exec_id.as_str()).collect();
+        assert_eq!(exec_ids, vec!["2", "1"]);
+        assert_eq!(cursor, None);
+        assert_eq!(stats.requests, 2);
+        assert_eq!(stats.trades, 3);
+
+        let requests = server.await.unwrap();
+        assert!(!requests[0].contains("cursor="));
+        assert!(requests[1].contains("cursor=page2"));
+    }
+
+    #[tokio::test]
+    async fn test_stats_interval_is_validated() {
+        let fetcher = BybitHistoryFetcher::new(
+            "http://127.0.0.1:1".to_string(),
+            String::new(),
+            String::new(),
+        );
+
+        assert!(matches!(
+            fetcher
+                .fetch_open_interest("linear", "BTCUSDT", "2h", 0, 1)
+                .await,
+            Err(BybitError::InvalidArg(_))
+        ));
+        assert!(matches!(
+            fetcher
+                .fetch_long_short_ratio("spot", "BTCUSDT", "1h", 0, 1)
+                .await,
+            Err(BybitError::InvalidArg(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_unknown_symbol_is_not_an_empty_range() {
+        let body = r#"{"retCode":10001,"retMsg":"params error: Symbol Is Invalid","result":{},"retExtInfo":{},"time":1704067200000}"#;
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(serve_once(listener, body));
+        let fetcher = BybitHistoryFetcher::new(base_url, String::new(), String::new());
+
+        let result = fetcher
+            .fetch_trades("BTCUSD", 0, i64::MAX, &FetchConfig::default())
+            .await;
+        assert!(matches!(result, Err(BybitError::UnknownSymbol(symbol)) if symbol == "BTCUSD"));
+    }
+
+    #[test]
+    fn test_append_page_parses_option_fields() {
+        let page = parse_page(
+            r#"{"retCode":0,"retMsg":"OK","result":{"list":[
+                {"execId":"1","symbol":"BTC-26JAN24-45000-C","price":"1200","size":"0.5","side":"Buy","time":"1704067200000","isBlockTrade":false,"mP":"1180.5","iP":"42000.1","mIv":"0.52","iv":"0.55"}
+            ],"nextPageCursor":null}}"#,
+        );
+
+        let mut trades = Vec::new();
+        append_page(&mut trades, &mut HashSet::new(), page.list).unwrap();
+        assert_eq!(trades[0].mark_price, Some(1180.5));
+        assert_eq!(trades[0].index_price, Some(42000.1));
+        assert_eq!(trades[0].mark_iv, Some(0.52));
+        assert_eq!(trades[0].iv, Some(0.55));
+    }
+
+    #[test]
+    fn test_block_trade_filter() {
+        let page = parse_page(
+            r#"{"retCode":0,"retMsg":"OK","result":{"list":[
+                {"execId":"3","symbol":"BTCUSDT","price":"100.5","size":"0.1","side":"Buy","time":"1704067200002","isBlockTrade":false},
+                {"execId":"2","symbol":"BTCUSDT","price":"100.0","size":"250","side":"Sell","time":"1704067200001","isBlockTrade":true},
+                {"execId":"1","symbol":"BTCUSDT","price":"100.2","size":"0.3","side":"Buy","time":"1704067200000","isBlockTrade":false}
+            ],"nextPageCursor":""}}"#,
+        );
+        let mut trades = Vec::new();
+        append_page(&mut trades, &mut HashSet::new(), page.list).unwrap();
+
+        let records = trades
+            .iter()
+            .map(TradeRow::to_record)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(
+            records.iter().map(|r| r.is_block_trade).collect::<Vec<_>>(),
+            vec![0, 1, 0]
+        );
+        assert_eq!(
+            records.iter().map(|r| r.side).collect::<Vec<_>>(),
+            vec![1, 0, 1]
+        );
+
+        // include_block_trades=True keeps the page as is.
+        assert_eq!(trades.len(), 3);
+        assert_eq!(trades[1].exec_id, "2");
+        assert!(trades[1].is_block_trade);
+
+        let mut excluded = trades;
+        retain_non_block(&mut excluded);
+        assert_eq!(
+            excluded
+                .iter()
+                .map(|t| t.exec_id.as_str())
+                .collect::<Vec<_>>(),
+            vec!["3", "1"]
+        );
+    }
+
+    #[test]
+    fn test_min_size_filter() {
+        let page = parse_page(
+            r#"{"retCode":0,"retMsg":"OK","result":{"list":[
+                {"execId":"3","symbol":"BTCUSDT","price":"100.5","size":"0.1","side":"Buy","time":"1704067200002","isBlockTrade":false},
+                {"execId":"2","symbol":"BTCUSDT","price":"100.0","size":"2.5","side":"Sell","time":"1704067200001","isBlockTrade":false},
+                {"execId":"1","symbol":"BTCUSDT","price":"100.2","size":"1.0","side":"Buy","time":"1704067200000","isBlockTrade":false}
+            ],"nextPageCursor":""}}"#,
+        );
+        let mut trades = Vec::new();
+        append_page(&mut trades, &mut HashSet::new(), page.list).unwrap();
+
+        // The threshold itself is kept.
+        retain_min_size(&mut trades, 1.0);
+        assert_eq!(
+            trades
+                .iter()
+                .map(|t| t.exec_id.as_str())
+                .collect::<Vec<_>>(),
+            vec!["2", "1"]
+        );
+    }
+
+    #[test]
+    fn test_append_page_dedups_exec_id_across_pages() {
+        let page1 = parse_page(
+            r#"{"retCode":0,"retMsg":"OK","result":{"list":[
+                {"execId":"3","symbol":"BTCUSDT","price":"100.5","size":"0.1","side":"Buy","time":"1704067200002","isBlockTrade":false},
+                {"execId":"2","symbol":"BTCUSDT","price":"100.0","size":"0.2","side":"Sell","time":"1704067200001","isBlockTrade":false}
+            ],"nextPageCursor":"page2"}}"#,
+        );
+        let page2 = parse_page(
+            r#"{"retCode":0,"retMsg":"OK","result":{"list":[
+                {"execId":"2","symbol":"BTCUSDT","price":"100.0","size":"0.2","side":"Sell","time":"1704067200001","isBlockTrade":false},
+                {"execId":"1","symbol":"BTCUSDT","price":"99.5","size":"0.3","side":"Buy","time":"1704067200000","isBlockTrade":false}
+            ],"nextPageCursor":null}}"#,
+        );
+
+        let mut all_trades = Vec::new();
+        let mut prev_page_exec_ids = HashSet::new();
+        append_page(&mut all_trades, &mut prev_page_exec_ids, page1.list).unwrap();
+        append_page(&mut all_trades, &mut prev_page_exec_ids, page2.list).unwrap();
+
+        let exec_ids: Vec<_> = all_trades.iter().map(|t| t.exec_id.as_str()).collect();
+        assert_eq!(exec_ids, vec!["3", "2", "1"]);
+    }
+
+    #[test]
+    fn test_adjacent_windows_do_not_overlap() {
+        // The endpoint includes endTime, so the trade at b = 1704067200001 comes back for both
+        // [a, b) and [b, c).
+        let first = parse_page(
+            r#"{"retCode":0,"retMsg":"OK","result":{"list":[
+                {"execId":"2","symbol":"BTCUSDT","price":"100.0","size":"0.2","side":"Sell","time":"1704067200001","isBlockTrade":false},
+                {"execId":"1","symbol":"BTCUSDT","price":"99.5","size":"0.3","side":"Buy","time":"1704067200000","isBlockTrade":false}
+            ],"nextPageCursor":null}}"#,
+        );
+        let second = parse_page(
+            r#"{"retCode":0,"retMsg":"OK","result":{"list":[
+                {"execId":"3","symbol":"BTCUSDT","price":"100.5","size":"0.1","side":"Buy","time":"1704067200002","isBlockTrade":false},
+                {"execId":"2","symbol":"BTCUSDT","price":"100.0","size":"0.2","side":"Sell","time":"1704067200001","isBlockTrade":false}
+            ],"nextPageCursor":null}}"#,
+        );
+
+        let mut trades = Vec::new();
+        for (page, end_time) in [(first, 1704067200001), (second, 1704067200002)] {
+            let mut window = Vec::new();
+            append_page(&mut window, &mut HashSet::new(), page.list).unwrap();
+            retain_before(&mut window, end_time);
+            trades.extend(window);
+        }
+
+        let mut timestamps: Vec<_> = trades.iter().map(|t| t.timestamp).collect();
+        timestamps.sort();
+        assert_eq!(timestamps, vec![1704067200000, 1704067200001]);
+    }
+
+    #[test]
+    fn test_sort_trades() {
+        // The second page overlaps the first one out of order across the cursor boundary.
+        let page = parse_page(
+            r#"{"retCode":0,"retMsg":"OK","result":{"list":[
+                {"execId":"4","symbol":"BTCUSDT","price":"100.0","size":"0.1","side":"Buy","time":"1704067200002","isBlockTrade":false},
+                {"execId":"3","symbol":"BTCUSDT","price":"100.0","size":"0.2","side":"Buy","time":"1704067200001","isBlockTrade":false},
+                {"execId":"2","symbol":"BTCUSDT","price":"100.0","size":"0.3","side":"Buy","time":"1704067200001","isBlockTrade":false},
+                {"execId":"5","symbol":"BTCUSDT","price":"100.0","size":"0.4","side":"Buy","time":"1704067200003","isBlockTrade":false},
+                {"execId":"1","symbol":"BTCUSDT","price":"100.0","size":"0.5","side":"Sell","time":"1704067200000","isBlockTrade":false}
+            ],"nextPageCursor":null}}"#,
+        );
+        let mut trades = Vec::new();
+        append_page(&mut trades, &mut HashSet::new(), page.list).unwrap();
+
+        let mut strict = trades.clone();
+        assert!(matches!(
+            sort_trades(&mut strict, true),
+            Err(BybitError::OutOfOrder(_))
+        ));
+
+        sort_trades(&mut trades, false).unwrap();
+        let exec_ids: Vec<_> = trades.iter().map(|t| t.exec_id.as_str()).collect();
+        // 3 and 2 share a timestamp and end up in execution order, the reverse of the order they
+        // were received in.
+        assert_eq!(exec_ids, vec!["1", "2", "3", "4", "5"]);
+    }
+
+    #[test]
+    fn test_deterministic_order() {
+        let pages = [
+            r#"{"retCode":0,"retMsg":"OK","result":{"list":[
+                {"execId":"c","symbol":"BTCUSDT","price":"100.0","size":"0.1","side":"Buy","time":"1704067200002","isBlockTrade":false},
+                {"execId":"a","symbol":"BTCUSDT","price":"100.0","size":"0.2","side":"Buy","time":"1704067200001","isBlockTrade":false}
+            ],"nextPageCursor":null}}"#,
+            r#"{"retCode":0,"retMsg":"OK","result":{"list":[
+                {"execId":"d","symbol":"BTCUSDT","price":"100.0","size":"0.3","side":"Sell","time":"1704067200001","isBlockTrade":false},
+                {"execId":"b","symbol":"BTCUSDT","price":"100.0","size":"0.4","side":"Buy","time":"1704067200001","isBlockTrade":false}
+            ],"nextPageCursor":null}}"#,
+            r#"{"retCode":0,"retMsg":"OK","result":{"list":[
+                {"execId":"e","symbol":"BTCUSDT","price":"100.0","size":"0.5","side":"Sell","time":"1704067200000","isBlockTrade":false}
+            ],"nextPageCursor":null}}"#,
+        ];
+        let config = FetchConfig {
+            deterministic: true,
+            ..Default::default()
+        };
+        let orders = [
+            [0, 1, 2],
+            [0, 2, 1],
+            [1, 0, 2],
+            [1, 2, 0],
+            [2, 0, 1],
+            [2, 1, 0],
+        ];
+        for order in orders {
+            let mut trades = Vec::new();
+            for i in order {
+                append_page(&mut trades, &mut HashSet::new(), parse_page(pages[i]).list).unwrap();
+            }
+            config.sort(&mut trades).unwrap();
+            let exec_ids: Vec<_> = trades.iter().map(|t| t.exec_id.as_str()).collect();
+            assert_eq!(
+                exec_ids,
+                vec!["e", "a", "b", "d", "c"],
+                "page order {:?}",
+                order
+            );
+        }
+    }
+}
@@ -0,0 +1,3097 @@
+//! Python bindings of the Bybit fetches: the `fetch_*` functions, [`BybitFetcher`] and the
+//! constructor of [`FetchConfig`].
+
+use std::{collections::HashMap, ffi::CString, path::Path, sync::Arc};
+
+use numpy::PyArray1;
+use polars::prelude::{Column, DataFrame, PolarsResult};
+use pyo3::{
+    exceptions::{PyRuntimeError, PyUserWarning},
+    prelude::*,
+    types::PyList,
+};
+use pyo3_polars::PyDataFrame;
+
+use super::{
+    BybitError, DEFAULT_MAX_GAP_MS, DEFAULT_RECV_WINDOW, FetchStats, KlineRecord, KlineRow,
+    TimeUnit, TradeRecord, TradeReport, TradeRow,
+    client::{
+        BybitHistoryFetcher, FetchConfig, LAST_FETCH_STATS, RetryConfig, TokenBucket, record_stats,
+        resolve_base_url, runtime,
+    },
+    export::{
+        DailyFormat, DaySummary, PARQUET_ROW_GROUP_SIZE, TickGrid, TradeCsvWriter, TradeNpzWriter,
+        TradeParquetWriter, day_range, parse_date, replay_raw_dump_rows,
+    },
+    max_orderbook_depth,
+    pagination::{
+        check_trade_range, count_trade_rows, fetch_kline_rows, fetch_trade_rows, retain_before,
+        sort_canonical,
+    },
+    validate_range, validate_rows,
+};
+use crate::latency::LatencyInjector;
+
+/// Overwrites the fields of a [`FetchConfig`] with the arguments of the same name that were
+/// passed, so that keyword arguments given alongside a config take precedence over it. Fields in
+/// the second list are optional themselves and are only overwritten by a `Some`.
+macro_rules! override_config {
+    ($config:ident, [$($field:ident),* $(,)?], [$($optional:ident),* $(,)?]) => {
+        $(
+            if let Some(value) = $field {
+                $config.$field = value;
+            }
+        )*
+        $(
+            if $optional.is_some() {
+                $config.$optional = $optional;
+            }
+        )*
+    };
+}
+
+#[pymethods]
+impl FetchConfig {
+    #[allow(clippy::too_many_arguments)]
+    #[new]
+    #[pyo3(signature = (
+        *,
+        category = None,
+        limit = None,
+        api_key = None,
+        secret = None,
+        recv_window = None,
+        max_retries = None,
+        base_backoff_ms = None,
+        max_backoff_ms = None,
+        jitter = None,
+        retry_on_5xx = None,
+        base_url = None,
+        testnet = None,
+        proxy = None,
+        compress = None,
+        connect_timeout_ms = None,
+        timeout_ms = None,
+        tcp_keepalive_ms = None,
+        max_idle_connections = None,
+        user_agent = None,
+        extra_headers = None,
+        strict_range = None,
+        strict = None,
+        deterministic = None,
+        include_block_trades = None,
+        min_size = None,
+        side_as_int = None,
+        include_turnover = None,
+        max_empty_pages = None,
+        page_delay_ms = None,
+        cache_dir = None,
+        force_refresh = None,
+        time_unit = None,
+    ))]
+    fn new(
+        category: Option<String>,
+        limit: Option<i32>,
+        api_key: Option<String>,
+        secret: Option<String>,
+        recv_window: Option<u64>,
+        max_retries: Option<u32>,
+        base_backoff_ms: Option<u64>,
+        max_backoff_ms: Option<u64>,
+        jitter: Option<bool>,
+        retry_on_5xx: Option<bool>,
+        base_url: Option<String>,
+        testnet: Option<bool>,
+        proxy: Option<String>,
+        compress: Option<bool>,
+        connect_timeout_ms: Option<u64>,
+        timeout_ms: Option<u64>,
+        tcp_keepalive_ms: Option<u64>,
+        max_idle_connections: Option<usize>,
+        user_agent: Option<String>,
+        extra_headers: Option<HashMap<String, String>>,
+        strict_range: Option<bool>,
+        strict: Option<bool>,
+        deterministic: Option<bool>,
+        include_block_trades: Option<bool>,
+        min_size: Option<f64>,
+        side_as_int: Option<bool>,
+        include_turnover: Option<bool>,
+        max_empty_pages: Option<u32>,
+        page_delay_ms: Option<u64>,
+        cache_dir: Option<String>,
+        force_refresh: Option<bool>,
+        time_unit: Option<String>,
+    ) -> Self {
+        let mut config = Self::default();
+        override_config!(
+            config,
+            [
+                category,
+                limit,
+                api_key,
+                secret,
+                recv_window,
+                max_retries,
+                base_backoff_ms,
+                max_backoff_ms,
+                jitter,
+                retry_on_5xx,
+                base_url,
+                testnet,
+                compress,
+                timeout_ms,
+                strict_range,
+                strict,
+                deterministic,
+                include_block_trades,
+                side_as_int,
+                include_turnover,
+                max_empty_pages,
+                page_delay_ms,
+                force_refresh,
+            ],
+            [
+                proxy,
+                connect_timeout_ms,
+                tcp_keepalive_ms,
+                max_idle_connections,
+                user_agent,
+                extra_headers,
+                min_size,
+                cache_dir,
+                time_unit,
+            ]
+        );
+        config
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "FetchConfig(category={:?}, limit={}, base_url={:?}, testnet={}, max_retries={}, timeout_ms={})",
+            self.category,
+            self.limit,
+            self.base_url,
+            self.testnet,
+            self.max_retries,
+            self.timeout_ms
+        )
+    }
+}
+
+/// Fetch Bybit trade history between two timestamps.
+///
+/// The window is half-open: trades at end_time are left out, so adjacent windows
+/// [a, b) and [b, c) can be chained without duplicating the trades at b.
+///
+/// Args:
+///     symbol: Trading symbol (e.g., "BTCUSDT")
+///     start_time: Start timestamp in milliseconds, or in time_unit if given (inclusive)
+///     end_time: End timestamp in milliseconds, or in time_unit if given (exclusive)
+///     limit: Number of trades per request (default 1000, max 1000)
+///     category: Product type: "linear", "inverse", "spot", or "option" (default "linear")
+///     api_key: Bybit API key (optional for public endpoint)
+///     secret: Bybit API secret (optional for public endpoint)
+///     recv_window: Validity window of a signed request in milliseconds (default 5000)
+///     max_retries: Maximum number of retries on a 429, a 5xx or a network error (default 5)
+///     base_backoff_ms: Wait before the first retry in milliseconds, doubled on each retry (default 50)
+///     max_backoff_ms: Upper bound of the wait between retries in milliseconds (default 10000)
+///     page_delay_ms: Delay between page requests in milliseconds when Bybit sends no rate-limit
+///                    headers to pace them by. 0 removes it, which risks 429s on standard
+///                    accounts (default 50)
+///     base_url: Base URL for Bybit API (default "https://api.bybit.com")
+///     testnet: Use the Bybit testnet at "https://api-testnet.bybit.com" unless a custom base_url is
+///              given (default False)
+///     proxy: Proxy URL to send requests through, with an http, https, socks5 or socks5h scheme
+///            (default None)
+///     compress: Accept gzip and brotli compressed responses (default True)
+///     connect_timeout_ms: Timeout for establishing a connection in milliseconds (default None,
+///                         no timeout)
+///     timeout_ms: Timeout for each request in milliseconds (default 10000)
+///     tcp_keepalive_ms: Interval of TCP keepalive probes in milliseconds (default None, disabled)
+///     max_idle_connections: Maximum number of idle connections kept open (default None,
+///                           unlimited)
+///     user_agent: User-Agent header sent with every request, instead of reqwest's default
+///                 (default None)
+///     extra_headers: Dict of headers added to every request, such as those a gateway identifies
+///                    clients by. The X-BAPI-* signing headers can't be set (default None)
+///     cursor: Cursor to resume an interrupted fetch from, taken from FetchInterrupted.cursor. The
+///             other arguments must match the interrupted call (default None)
+///     progress: Optional callable invoked after each page with a dict of count (trades fetched so
+///               far), last_timestamp (of the last trade received, or None if none yet), cursor
+///               (of the next page, or None on the last page) and elapsed (seconds)
+///     strict_range: Raise instead of warning when start_time is older than the trade endpoint's
+///                   7-day retention (default False)
+///     strict: Raise instead of sorting when the pages arrive out of timestamp order, which
+///             indicates a data quality issue (default False)
+///     deterministic: Sort the trades by (timestamp, exec_id) instead of execution order, so the
+///                    same trades always come out in the same order regardless of how the pages
+///                    interleaved. Relies on exec_id, which every trade is fetched with (default
+///                    False)
+///     partial: On a transient error or a server-side API error after the first page, return the
+///              trades fetched so far with a UserWarning carrying the retCode, retMsg and the
+///              cursor to resume from, instead of raising (default False)
+///     include_block_trades: Keep block trades, which are negotiated off the order book. Set to
+///                           False to drop them (default True)
+///     min_size: Drop trades smaller than this size. Pages are still fetched in full; only the
+///               returned trades are filtered (default None, keep all)
+///     side_as_int: Return side as 1 for Buy and -1 for Sell instead of the string (default False)
+///     include_turnover: Add a turnover key with price * size, computed once in Rust. It is a
+///                       convenience derived from the f64 price and size, not a field reported by
+///                       Bybit, and carries a relative rounding error below 4e-16 (default False)
+///     raw_dump_path: Append the raw body of every response, with its request URL and receive time,
+///                    to this file as zstd-compressed NDJSON before it is parsed. Read it back with
+///                    replay_raw_dump (default None)
+///     cache_dir: Directory caching each fetched window, keyed by symbol, category, start_time,
+///                end_time and limit. An identical fetch is then read from disk without any
+///                request. Only complete fetches are cached, not partial or resumed ones (default
+///                None)
+///     force_refresh: Fetch even if the window is cached, and replace the entry (default False)
+///     time_unit: Unit of start_time, end_time and the returned timestamps: "s", "ms", "us" or
+///                "ns". Without it, the timestamps are milliseconds and a start_time before 1973,
+///                which is most likely in seconds, raises ValueError (default None)
+///     config: FetchConfig supplying the settings above that are not passed explicitly, so they
+///             can be built once and reused across symbols (default None)
+///
+/// Returns:
+///     List of dicts with keys: exec_id, timestamp, symbol, side, size, price, is_block_trade,
+///     sorted by timestamp in ascending order. Trades with the same timestamp are in execution
+///     order, the reverse of the order Bybit returns them in.
+///
+/// Raises:
+///     FetchInterrupted: If the fetch stops on a transient error (dropped connection, exhausted
+///                       rate-limit retries, server error), with the trades fetched so far as
+///                       dicts in `partial` and the cursor to resume from in `cursor`, unless
+///                       partial is set and a page was already fetched
+///     BybitError: If start_time is not before end_time, if strict_range is set and start_time
+///                 is older than the retention, or if strict is set and the trades arrived out of
+///                 order
+///     RuntimeError: If the API request fails
+///     Any exception raised by the progress callback
+#[allow(clippy::too_many_arguments)]
+#[pyfunction]
+#[pyo3(
+    text_signature = "(symbol, start_time, end_time, *, limit=1000, category='linear', api_key='', secret='', recv_window=5000, max_retries=5, base_backoff_ms=50, max_backoff_ms=10000, page_delay_ms=50, base_url='https://api.bybit.com', testnet=False, proxy=None, compress=True, connect_timeout_ms=None, timeout_ms=10000, tcp_keepalive_ms=None, max_idle_connections=None, user_agent=None, extra_headers=None, cursor=None, progress=None, strict_range=False, strict=False, deterministic=False, partial=False, include_block_trades=True, min_size=None, side_as_int=False, include_turnover=False, raw_dump_path=None, cache_dir=None, force_refresh=False, time_unit=None, config=None)"
+)]
+pub fn fetch_trades(
+    py: Python,
+    symbol: String,
+    start_time: i64,
+    end_time: i64,
+    limit: Option<i32>,
+    category: Option<String>,
+    api_key: Option<String>,
+    secret: Option<String>,
+    recv_window: Option<u64>,
+    max_retries: Option<u32>,
+    base_backoff_ms: Option<u64>,
+    max_backoff_ms: Option<u64>,
+    page_delay_ms: Option<u64>,
+    base_url: Option<String>,
+    testnet: Option<bool>,
+    proxy: Option<String>,
+    compress: Option<bool>,
+    connect_timeout_ms: Option<u64>,
+    timeout_ms: Option<u64>,
+    tcp_keepalive_ms: Option<u64>,
+    max_idle_connections: Option<usize>,
+    user_agent: Option<String>,
+    extra_headers: Option<HashMap<String, String>>,
+    cursor: Option<String>,
+    progress: Option<PyObject>,
+    strict_range: Option<bool>,
+    strict: Option<bool>,
+    deterministic: Option<bool>,
+    partial: Option<bool>,
+    include_block_trades: Option<bool>,
+    min_size: Option<f64>,
+    side_as_int: Option<bool>,
+    include_turnover: Option<bool>,
+    raw_dump_path: Option<String>,
+    cache_dir: Option<String>,
+    force_refresh: Option<bool>,
+    time_unit: Option<String>,
+    config: Option<FetchConfig>,
+) -> PyResult<PyObject> {
+    let mut config = config.unwrap_or_default();
+    override_config!(
+        config,
+        [
+            limit,
+            category,
+            api_key,
+            secret,
+            recv_window,
+            max_retries,
+            base_backoff_ms,
+            max_backoff_ms,
+            page_delay_ms,
+            base_url,
+            testnet,
+            compress,
+            timeout_ms,
+            strict_range,
+            strict,
+            deterministic,
+            include_block_trades,
+            side_as_int,
+            include_turnover,
+            force_refresh
+        ],
+        [
+            proxy,
+            connect_timeout_ms,
+            tcp_keepalive_ms,
+            max_idle_connections,
+            user_agent,
+            extra_headers,
+            min_size,
+            cache_dir,
+            time_unit
+        ]
+    );
+    let fetcher =
+        BybitHistoryFetcher::from_config(&config)?.with_raw_dump(raw_dump_path.as_deref())?;
+    let trades = fetch_trade_rows(
+        py,
+        &fetcher,
+        symbol,
+        start_time,
+        end_time,
+        &config,
+        cursor,
+        progress,
+        partial.unwrap_or(false),
+    )?;
+
+    let result = trades
+        .iter()
+        .map(|t| t.to_dict(py, config.side_as_int, config.include_turnover))
+        .collect::<PyResult<Vec<_>>>()?;
+
+    Ok(PyList::new(py, result)?.into())
+}
+
+/// Count the Bybit trades between two timestamps without keeping them.
+///
+/// Paginates through the window as fetch_trades does, with the same retries, backoff and trade
+/// filters, but drops each page once counted, so memory stays flat however large the window is.
+/// The count matches the length of the list fetch_trades returns for the same arguments, which
+/// sizes a large download before running it. The cache is bypassed.
+///
+/// Args:
+///     symbol: Trading symbol (e.g., "BTCUSDT")
+///     start_time: Start timestamp in milliseconds, or in time_unit if given (inclusive)
+///     end_time: End timestamp in milliseconds, or in time_unit if given (exclusive)
+///     limit: Number of trades per request (default 1000, max 1000)
+///     category: Product type: "linear", "inverse", "spot", or "option" (default "linear")
+///     api_key: Bybit API key (optional for public endpoint)
+///     secret: Bybit API secret (optional for public endpoint)
+///     recv_window: Validity window of a signed request in milliseconds (default 5000)
+///     max_retries: Maximum number of retries on a 429, a 5xx or a network error (default 5)
+///     base_backoff_ms: Wait before the first retry in milliseconds, doubled on each retry (default 50)
+///     max_backoff_ms: Upper bound of the wait between retries in milliseconds (default 10000)
+///     page_delay_ms: Delay between page requests in milliseconds when Bybit sends no rate-limit
+///                    headers to pace them by. 0 removes it, which risks 429s on standard
+///                    accounts (default 50)
+///     base_url: Base URL for Bybit API (default "https://api.bybit.com")
+///     testnet: Use the Bybit testnet at "https://api-testnet.bybit.com" unless a custom base_url is
+///              given (default False)
+///     proxy: Proxy URL to send requests through, with an http, https, socks5 or socks5h scheme
+///            (default None)
+///     compress: Accept gzip and brotli compressed responses (default True)
+///     connect_timeout_ms: Timeout for establishing a connection in milliseconds (default None,
+///                         no timeout)
+///     timeout_ms: Timeout for each request in milliseconds (default 10000)
+///     tcp_keepalive_ms: Interval of TCP keepalive probes in milliseconds (default None, disabled)
+///     max_idle_connections: Maximum number of idle connections kept open (default None,
+///                           unlimited)
+///     user_agent: User-Agent header sent with every request, instead of reqwest's default
+///                 (default None)
+///     extra_headers: Dict of headers added to every request, such as those a gateway identifies
+///                    clients by. The X-BAPI-* signing headers can't be set (default None)
+///     cursor: Cursor to resume an interrupted count from (default None)
+///     progress: Optional callable invoked after each page with the same dict as in fetch_trades
+///     strict_range: Raise instead of warning when start_time is older than the trade endpoint's
+///                   7-day retention (default False)
+///     partial: On a transient error or a server-side API error after the first page, return the
+///              count so far with a UserWarning carrying the error and the cursor to resume
+///              from, instead of raising (default False)
+///     include_block_trades: Count block trades, which are negotiated off the order book. Set to
+///                           False to leave them out (default True)
+///     min_size: Leave out trades smaller than this size (default None, count all)
+///     time_unit: Unit of start_time and end_time: "s", "ms", "us" or "ns" (default None,
+///                milliseconds)
+///     config: FetchConfig supplying the settings above that are not passed explicitly, so they
+///             can be built once and reused across symbols (default None)
+///
+/// Returns:
+///     The number of trades in the window.
+///
+/// Raises:
+///     BybitError: If start_time is not before end_time, if strict_range is set and start_time
+///                 is older than the retention, or if a request fails
+///     Any exception raised by the progress callback
+#[allow(clippy::too_many_arguments)]
+#[pyfunction]
+#[pyo3(
+    text_signature = "(symbol, start_time, end_time, *, limit=1000, category='linear', api_key='', secret='', recv_window=5000, max_retries=5, base_backoff_ms=50, max_backoff_ms=10000, page_delay_ms=50, base_url='https://api.bybit.com', testnet=False, proxy=None, compress=True, connect_timeout_ms=None, timeout_ms=10000, tcp_keepalive_ms=None, max_idle_connections=None, user_agent=None, extra_headers=None, cursor=None, progress=None, strict_range=False, partial=False, include_block_trades=True, min_size=None, time_unit=None, config=None)"
+)]
+pub fn count_trades(
+    py: Python,
+    symbol: String,
+    start_time: i64,
+    end_time: i64,
+    limit: Option<i32>,
+    category: Option<String>,
+    api_key: Option<String>,
+    secret: Option<String>,
+    recv_window: Option<u64>,
+    max_retries: Option<u32>,
+    base_backoff_ms: Option<u64>,
+    max_backoff_ms: Option<u64>,
+    page_delay_ms: Option<u64>,
+    base_url: Option<String>,
+    testnet: Option<bool>,
+    proxy: Option<String>,
+    compress: Option<bool>,
+    connect_timeout_ms: Option<u64>,
+    timeout_ms: Option<u64>,
+    tcp_keepalive_ms: Option<u64>,
+    max_idle_connections: Option<usize>,
+    user_agent: Option<String>,
+    extra_headers: Option<HashMap<String, String>>,
+    cursor: Option<String>,
+    progress: Option<PyObject>,
+    strict_range: Option<bool>,
+    partial: Option<bool>,
+    include_block_trades: Option<bool>,
+    min_size: Option<f64>,
+    time_unit: Option<String>,
+    config: Option<FetchConfig>,
+) -> PyResult<usize> {
+    let mut config = config.unwrap_or_default();
+    override_config!(
+        config,
+        [
+            limit,
+            category,
+            api_key,
+            secret,
+            recv_window,
+            max_retries,
+            base_backoff_ms,
+            max_backoff_ms,
+            page_delay_ms,
+            base_url,
+            testnet,
+            compress,
+            timeout_ms,
+            strict_range,
+            include_block_trades,
+        ],
+        [
+            proxy,
+            connect_timeout_ms,
+            tcp_keepalive_ms,
+            max_idle_connections,
+            user_agent,
+            extra_headers,
+            min_size,
+            time_unit,
+        ]
+    );
+    let fetcher = BybitHistoryFetcher::from_config(&config)?;
+    count_trade_rows(
+        py,
+        &fetcher,
+        symbol,
+        start_time,
+        end_time,
+        &config,
+        cursor,
+        progress,
+        partial.unwrap_or(false),
+    )
+}
+
+/// Fetch a single page of Bybit trade history with one request, for pagination driven from Python.
+///
+/// Passing the returned cursor back with the same symbol and window fetches the next page, so a
+/// long pull can be persisted between runs and resumed from the last cursor. Unlike fetch_trades,
+/// nothing is kept between calls: the trades are neither sorted nor deduplicated across pages,
+/// so dedupe them by exec_id once all pages are in.
+///
+/// Args:
+///     symbol: Trading symbol (e.g., "BTCUSDT")
+///     start_time: Start timestamp in milliseconds, or in time_unit if given (inclusive)
+///     end_time: End timestamp in milliseconds, or in time_unit if given (exclusive)
+///     cursor: Cursor of the page to fetch, as returned by the previous call, or None for the first
+///             page of the window (default None)
+///     limit: Number of trades per request (default 1000, max 1000)
+///     category: Product type: "linear", "inverse", "spot", or "option" (default "linear")
+///     api_key: Bybit API key (optional for public endpoint)
+///     secret: Bybit API secret (optional for public endpoint)
+///     recv_window: Validity window of a signed request in milliseconds (default 5000)
+///     max_retries: Maximum number of retries on a 429, a 5xx or a network error (default 5)
+///     base_backoff_ms: Wait before the first retry in milliseconds, doubled on each retry (default 50)
+///     max_backoff_ms: Upper bound of the wait between retries in milliseconds (default 10000)
+///     base_url: Base URL for Bybit API (default "https://api.bybit.com")
+///     testnet: Use the Bybit testnet at "https://api-testnet.bybit.com" unless a custom base_url is
+///              given (default False)
+///     proxy: Proxy URL to send requests through, with an http, https, socks5 or socks5h scheme
+///            (default None)
+///     compress: Accept gzip and brotli compressed responses (default True)
+///     connect_timeout_ms: Timeout for establishing a connection in milliseconds (default None,
+///                         no timeout)
+///     timeout_ms: Timeout for each request in milliseconds (default 10000)
+///     include_block_trades: Keep block trades, which are negotiated off the order book. Set to
+///                           False to drop them (default True)
+///     min_size: Drop trades smaller than this size (default None, keep all)
+///     side_as_int: Return side as 1 for Buy and -1 for Sell instead of the string (default False)
+///     include_turnover: Add a turnover key with price * size, as in fetch_trades (default False)
+///     time_unit: Unit of start_time, end_time and the returned timestamps: "s", "ms", "us" or
+///                "ns", as in fetch_trades (default None)
+///     config: FetchConfig supplying the settings above that are not passed explicitly (default
+///             None)
+///
+/// Returns:
+///     Tuple of the list of trade dicts of the page, in the order Bybit returns them, newest
+///     first, and the cursor of the next page, or None if this was the last page
+///
+/// Raises:
+///     BybitError: If start_time is not before end_time
+///     RuntimeError: If the API request fails
+#[allow(clippy::too_many_arguments)]
+#[pyfunction]
+#[pyo3(
+    text_signature = "(symbol, start_time, end_time, cursor=None, *, limit=1000, category='linear', api_key='', secret='', recv_window=5000, max_retries=5, base_backoff_ms=50, max_backoff_ms=10000, base_url='https://api.bybit.com', testnet=False, proxy=None, compress=True, connect_timeout_ms=None, timeout_ms=10000, include_block_trades=True, min_size=None, side_as_int=False, include_turnover=False, time_unit=None, config=None)"
+)]
+pub fn fetch_trades_page(
+    py: Python,
+    symbol: String,
+    start_time: i64,
+    end_time: i64,
+    cursor: Option<String>,
+    limit: Option<i32>,
+    category: Option<String>,
+    api_key: Option<String>,
+    secret: Option<String>,
+    recv_window: Option<u64>,
+    max_retries: Option<u32>,
+    base_backoff_ms: Option<u64>,
+    max_backoff_ms: Option<u64>,
+    base_url: Option<String>,
+    testnet: Option<bool>,
+    proxy: Option<String>,
+    compress: Option<bool>,
+    connect_timeout_ms: Option<u64>,
+    timeout_ms: Option<u64>,
+    include_block_trades: Option<bool>,
+    min_size: Option<f64>,
+    side_as_int: Option<bool>,
+    include_turnover: Option<bool>,
+    time_unit: Option<String>,
+    config: Option<FetchConfig>,
+) -> PyResult<(PyObject, Option<String>)> {
+    let mut config = config.unwrap_or_default();
+    override_config!(
+        config,
+        [
+            limit,
+            category,
+            api_key,
+            secret,
+            recv_window,
+            max_retries,
+            base_backoff_ms,
+            max_backoff_ms,
+            base_url,
+            testnet,
+            compress,
+            timeout_ms,
+            include_block_trades,
+            side_as_int,
+            include_turnover
+        ],
+        [proxy, connect_timeout_ms, min_size, time_unit]
+    );
+    let time_unit = TimeUnit::resolve(config.time_unit.as_deref(), start_time)?;
+    let (start_time, end_time) = (
+        time_unit.unit_to_ms(start_time),
+        time_unit.unit_to_ms(end_time),
+    );
+    validate_range(start_time, end_time)?;
+    let fetcher = BybitHistoryFetcher::from_config(&config)?;
+    let rt = runtime()?;
+
+    let mut stats = FetchStats::default();
+    let (mut trades, next_cursor) = py.allow_threads(|| {
+        rt.block_on(fetcher.fetch_trade_page(
+            &config.category,
+            &symbol,
+            start_time,
+            end_time,
+            config.limit,
+            cursor.as_deref(),
+            &mut stats,
+        ))
+    })?;
+    record_stats(stats);
+    retain_before(&mut trades, end_time);
+    config.retain(&mut trades);
+    time_unit.convert_trades(&mut trades);
+
+    let result = trades
+        .iter()
+        .map(|t| t.to_dict(py, config.side_as_int, config.include_turnover))
+        .collect::<PyResult<Vec<_>>>()?;
+    Ok((PyList::new(py, result)?.into(), next_cursor))
+}
+
+/// Re-parse the trade pages of a raw dump written by fetch_trades with raw_dump_path, offline.
+///
+/// The pages go through the same parser and deduplication as a live fetch, which makes a dump
+/// useful both to audit a fetch and to debug a response that failed to parse.
+///
+/// Args:
+///     path: Dump file written with raw_dump_path
+///     side_as_int: Return side as 1 for Buy and -1 for Sell instead of the string (default False)
+///
+/// Returns:
+///     List of dicts in the same format and order as fetch_trades. Responses of other endpoints
+///     in the dump are skipped
+///
+/// Raises:
+///     BybitError: If the file cannot be read or a trade page fails to parse
+#[pyfunction]
+#[pyo3(text_signature = "(path, *, side_as_int=False)")]
+pub fn replay_raw_dump(py: Python, path: String, side_as_int: Option<bool>) -> PyResult<PyObject> {
+    let trades = py.allow_threads(|| replay_raw_dump_rows(&path))?;
+    let result = trades
+        .iter()
+        .map(|t| t.to_dict(py, side_as_int.unwrap_or(false), false))
+        .collect::<PyResult<Vec<_>>>()?;
+    Ok(PyList::new(py, result)?.into())
+}
+
+/// Fetch Bybit trade history between two timestamps without blocking the calling thread.
+///
+/// Returns an awaitable for use in asyncio code; the fetch runs on the background runtime shared
+/// with the blocking functions, so several fetches can be awaited concurrently. The window is
+/// half-open, as in fetch_trades.
+///
+/// Cancelling the awaitable, e.g. with Task.cancel() or a timeout, drops the fetch on the runtime:
+/// the request in flight is aborted, no further page is requested, and the trades fetched so far
+/// are discarded. The runtime stays usable for other fetches.
+///
+/// Args:
+///     symbol: Trading symbol (e.g., "BTCUSDT")
+///     start_time: Start timestamp in milliseconds (inclusive)
+///     end_time: End timestamp in milliseconds (exclusive)
+///     limit: Number of trades per request (default 1000, max 1000)
+///     category: Product type: "linear", "inverse", "spot", or "option" (default "linear")
+///     api_key: Bybit API key (optional for public endpoint)
+///     secret: Bybit API secret (optional for public endpoint)
+///     recv_window: Validity window of a signed request in milliseconds (default 5000)
+///     max_retries: Maximum number of retries on a 429, a 5xx or a network error (default 5)
+///     base_backoff_ms: Wait before the first retry in milliseconds, doubled on each retry (default 50)
+///     max_backoff_ms: Upper bound of the wait between retries in milliseconds (default 10000)
+///     page_delay_ms: Delay between page requests in milliseconds when Bybit sends no rate-limit
+///                    headers to pace them by. 0 removes it, which risks 429s on standard
+///                    accounts (default 50)
+///     base_url: Base URL for Bybit API (default "https://api.bybit.com")
+///     testnet: Use the Bybit testnet at "https://api-testnet.bybit.com" unless a custom base_url is
+///              given (default False)
+///     proxy: Proxy URL to send requests through, with an http, https, socks5 or socks5h scheme
+///            (default None)
+///     compress: Accept gzip and brotli compressed responses (default True)
+///     connect_timeout_ms: Timeout for establishing a connection in milliseconds (default None,
+///                         no timeout)
+///     timeout_ms: Timeout for each request in milliseconds (default 10000)
+///     tcp_keepalive_ms: Interval of TCP keepalive probes in milliseconds (default None, disabled)
+///     max_idle_connections: Maximum number of idle connections kept open (default None,
+///                           unlimited)
+///     user_agent: User-Agent header sent with every request, instead of reqwest's default
+///                 (default None)
+///     extra_headers: Dict of headers added to every request, such as those a gateway identifies
+///                    clients by. The X-BAPI-* signing headers can't be set (default None)
+///     strict_range: Raise instead of warning when start_time is older than the trade endpoint's
+///                   7-day retention (default False)
+///     strict: Raise instead of sorting when the pages arrive out of timestamp order, which
+///             indicates a data quality issue (default False)
+///     side_as_int: Return side as 1 for Buy and -1 for Sell instead of the string (default False)
+///     include_turnover: Add a turnover key with price * size, computed once in Rust. It is a
+///                       convenience derived from the f64 price and size, not a field reported by
+///                       Bybit, and carries a relative rounding error below 4e-16 (default False)
+///     config: FetchConfig supplying the settings above that are not passed explicitly, so they
+///             can be built once and reused across symbols (default None)
+///
+/// Returns:
+///     Awaitable resolving to a list of dicts with keys: exec_id, timestamp, symbol, side, size,
+///     price, is_block_trade, sorted by timestamp in ascending order as in fetch_trades
+///
+/// Raises:
+///     BybitError: If start_time is not before end_time, or if strict_range is set and start_time
+///                 is older than the retention. Raised by the call, before anything is awaited.
+///                 Also raised when awaited if strict is set and the trades arrived out of order
+///     RuntimeError: If the API request fails, raised when awaited
+#[allow(clippy::too_many_arguments)]
+#[pyfunction]
+#[pyo3(
+    text_signature = "(symbol, start_time, end_time, *, limit=1000, category='linear', api_key='', secret='', recv_window=5000, max_retries=5, base_backoff_ms=50, max_backoff_ms=10000, page_delay_ms=50, base_url='https://api.bybit.com', testnet=False, proxy=None, compress=True, connect_timeout_ms=None, timeout_ms=10000, tcp_keepalive_ms=None, max_idle_connections=None, user_agent=None, extra_headers=None, strict_range=False, strict=False, side_as_int=False, include_turnover=False, config=None)"
+)]
+pub fn fetch_trades_async<'py>(
+    py: Python<'py>,
+    symbol: String,
+    start_time: i64,
+    end_time: i64,
+    limit: Option<i32>,
+    category: Option<String>,
+    api_key: Option<String>,
+    secret: Option<String>,
+    recv_window: Option<u64>,
+    max_retries: Option<u32>,
+    base_backoff_ms: Option<u64>,
+    max_backoff_ms: Option<u64>,
+    page_delay_ms: Option<u64>,
+    base_url: Option<String>,
+    testnet: Option<bool>,
+    proxy: Option<String>,
+    compress: Option<bool>,
+    connect_timeout_ms: Option<u64>,
+    timeout_ms: Option<u64>,
+    tcp_keepalive_ms: Option<u64>,
+    max_idle_connections: Option<usize>,
+    user_agent: Option<String>,
+    extra_headers: Option<HashMap<String, String>>,
+    strict_range: Option<bool>,
+    strict: Option<bool>,
+    side_as_int: Option<bool>,
+    include_turnover: Option<bool>,
+    config: Option<FetchConfig>,
+) -> PyResult<Bound<'py, PyAny>> {
+    let mut config = config.unwrap_or_default();
+    override_config!(
+        config,
+        [
+            limit,
+            category,
+            api_key,
+            secret,
+            recv_window,
+            max_retries,
+            base_backoff_ms,
+            max_backoff_ms,
+            page_delay_ms,
+            base_url,
+            testnet,
+            compress,
+            timeout_ms,
+            strict_range,
+            strict,
+            side_as_int,
+            include_turnover
+        ],
+        [
+            proxy,
+            connect_timeout_ms,
+            tcp_keepalive_ms,
+            max_idle_connections,
+            user_agent,
+            extra_headers
+        ]
+    );
+    check_trade_range(py, start_time, end_time, Some(config.strict_range))?;
+    if config.min_size.is_some_and(f64::is_nan) {
+        return Err(BybitError::InvalidArg("min_size is NaN".to_string()).into());
+    }
+
+    let fetcher = BybitHistoryFetcher::from_config(&config)?;
+
+    // Fails if already set, which is the case after the first call.
+    let _ = pyo3_async_runtimes::tokio::init_with_runtime(runtime()?);
+    pyo3_async_runtimes::tokio::future_into_py(py, async move {
+        let (trades, stats) = fetcher
+            .fetch_trades(&symbol, start_time, end_time, &config)
+            .await?;
+        record_stats(stats);
+        Python::with_gil(|py| {
+            let result = trades
+                .iter()
+                .map(|t| t.to_dict(py, config.side_as_int, config.include_turnover))
+                .collect::<PyResult<Vec<_>>>()?;
+            Ok::<PyObject, PyErr>(PyList::new(py, result)?.into())
+        })
+    })
+}
+
+/// Fetch Bybit trade history between two timestamps as a NumPy structured array.
+///
+/// The array is filled directly from Rust without creating a Python object per trade, which makes
+/// it suitable for high-volume symbols and wide time windows.
+///
+/// Args:
+///     symbol: Trading symbol (e.g., "BTCUSDT")
+///     start_time: Start timestamp in milliseconds, or in time_unit if given (inclusive)
+///     end_time: End timestamp in milliseconds, or in time_unit if given (exclusive)
+///     limit: Number of trades per request (default 1000, max 1000)
+///     category: Product type: "linear", "inverse", "spot", or "option" (default "linear")
+///     api_key: Bybit API key (optional for public endpoint)
+///     secret: Bybit API secret (optional for public endpoint)
+///     recv_window: Validity window of a signed request in milliseconds (default 5000)
+///     max_retries: Maximum number of retries on a 429, a 5xx or a network error (default 5)
+///     base_backoff_ms: Wait before the first retry in milliseconds, doubled on each retry (default 50)
+///     max_backoff_ms: Upper bound of the wait between retries in milliseconds (default 10000)
+///     page_delay_ms: Delay between page requests in milliseconds when Bybit sends no rate-limit
+///                    headers to pace them by. 0 removes it, which risks 429s on standard
+///                    accounts (default 50)
+///     base_url: Base URL for Bybit API (default "https://api.bybit.com")
+///     testnet: Use the Bybit testnet at "https://api-testnet.bybit.com" unless a custom base_url is
+///              given (default False)
+///     proxy: Proxy URL to send requests through, with an http, https, socks5 or socks5h scheme
+///            (default None)
+///     compress: Accept gzip and brotli compressed responses (default True)
+///     cursor: Cursor to resume an interrupted fetch from, taken from FetchInterrupted.cursor. The
+///             other arguments must match the interrupted call (default None)
+///     progress: Optional callable invoked after each page with a dict of count (trades fetched so
+///               far), last_timestamp (of the last trade received, or None if none yet), cursor
+///               (of the next page, or None on the last page) and elapsed (seconds)
+///     strict_range: Raise instead of warning when start_time is older than the trade endpoint's
+///                   7-day retention (default False)
+///     strict: Raise instead of sorting when the pages arrive out of timestamp order, which
+///             indicates a data quality issue (default False)
+///     partial: On a transient error or a server-side API error after the first page, return the
+///              trades fetched so far with a UserWarning carrying the retCode, retMsg and the
+///              cursor to resume from, instead of raising (default False)
+///     include_block_trades: Keep block trades, which are negotiated off the order book. Set to
+///                           False to drop them (default True)
+///     min_size: Drop trades smaller than this size. Pages are still fetched in full; only the
+///               returned trades are filtered (default None, keep all)
+///     cache_dir: Directory caching each fetched window, keyed by symbol, category, start_time,
+///                end_time and limit. An identical fetch is then read from disk without any
+///                request. Only complete fetches are cached, not partial or resumed ones (default
+///                None)
+///     force_refresh: Fetch even if the window is cached, and replace the entry (default False)
+///     time_unit: Unit of start_time, end_time and the returned timestamps: "s", "ms", "us" or
+///                "ns". Without it, the timestamps are milliseconds and a start_time before 1973,
+///                which is most likely in seconds, raises ValueError (default None)
+///     config: FetchConfig supplying the settings above that are not passed explicitly, so they
+///             can be built once and reused across symbols (default None)
+///
+/// Returns:
+///     ndarray with dtype [('timestamp', 'i8'), ('price', 'f8'), ('size', 'f8'), ('side', 'u1'),
+///     ('is_block_trade', 'u1')], sorted by timestamp in ascending order. timestamp is in
+///     milliseconds, or in time_unit if given, side is 1 for a taker buy and 0 for a taker
+///     sell, and is_block_trade is 1 for a block trade.
+///
+/// Raises:
+///     FetchInterrupted: If the fetch stops on a transient error (dropped connection, exhausted
+///                       rate-limit retries, server error), with the trades fetched so far as
+///                       dicts in `partial` and the cursor to resume from in `cursor`, unless
+///                       partial is set and a page was already fetched
+///     BybitError: If start_time is not before end_time, if strict_range is set and start_time
+///                 is older than the retention, or if strict is set and the trades arrived out of
+///                 order
+///     RuntimeError: If the API request fails
+///     Any exception raised by the progress callback
+#[allow(clippy::too_many_arguments)]
+#[pyfunction]
+#[pyo3(
+    text_signature = "(symbol, start_time, end_time, *, limit=1000, category='linear', api_key='', secret='', recv_window=5000, max_retries=5, base_backoff_ms=50, max_backoff_ms=10000, page_delay_ms=50, base_url='https://api.bybit.com', testnet=False, proxy=None, compress=True, cursor=None, progress=None, strict_range=False, strict=False, partial=False, include_block_trades=True, min_size=None, cache_dir=None, force_refresh=False, time_unit=None, config=None)"
+)]
+pub fn fetch_trades_numpy<'py>(
+    py: Python<'py>,
+    symbol: String,
+    start_time: i64,
+    end_time: i64,
+    limit: Option<i32>,
+    category: Option<String>,
+    api_key: Option<String>,
+    secret: Option<String>,
+    recv_window: Option<u64>,
+    max_retries: Option<u32>,
+    base_backoff_ms: Option<u64>,
+    max_backoff_ms: Option<u64>,
+    page_delay_ms: Option<u64>,
+    base_url: Option<String>,
+    testnet: Option<bool>,
+    proxy: Option<String>,
+    compress: Option<bool>,
+    cursor: Option<String>,
+    progress: Option<PyObject>,
+    strict_range: Option<bool>,
+    strict: Option<bool>,
+    partial: Option<bool>,
+    include_block_trades: Option<bool>,
+    min_size: Option<f64>,
+    cache_dir: Option<String>,
+    force_refresh: Option<bool>,
+    time_unit: Option<String>,
+    config: Option<FetchConfig>,
+) -> PyResult<Bound<'py, PyArray1<TradeRecord>>> {
+    let mut config = config.unwrap_or_default();
+    override_config!(
+        config,
+        [
+            limit,
+            category,
+            api_key,
+            secret,
+            recv_window,
+            max_retries,
+            base_backoff_ms,
+            max_backoff_ms,
+            page_delay_ms,
+            base_url,
+            testnet,
+            compress,
+            strict_range,
+            strict,
+            include_block_trades,
+            force_refresh
+        ],
+        [proxy, min_size, cache_dir, time_unit]
+    );
+    let fetcher = BybitHistoryFetcher::from_config(&config)?;
+    let trades = fetch_trade_rows(
+        py,
+        &fetcher,
+        symbol,
+        start_time,
+        end_time,
+        &config,
+        cursor,
+        progress,
+        partial.unwrap_or(false),
+    )?;
+
+    let records = trades
+        .iter()
+        .map(TradeRow::to_record)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(PyArray1::from_vec(py, records))
+}
+
+/// Fetch Bybit trade history between two timestamps as a polars DataFrame.
+///
+/// It takes the same arguments as fetch_trades_numpy. The columns are built in Rust and handed
+/// to polars as a whole frame, so no Python object is created per trade.
+///
+/// Returns:
+///     polars DataFrame sorted by timestamp in ascending order, with the columns timestamp
+///     (Int64, in milliseconds, or in time_unit if given), symbol (String), side (String,
+///     "Buy" or "Sell"), size (Float64), price (Float64) and is_block_trade (Boolean). The schema
+///     is the same when no trade is returned.
+///
+/// Raises:
+///     Same as fetch_trades_numpy.
+#[allow(clippy::too_many_arguments)]
+#[pyfunction]
+#[pyo3(
+    text_signature = "(symbol, start_time, end_time, *, limit=1000, category='linear', api_key='', secret='', recv_window=5000, max_retries=5, base_backoff_ms=50, max_backoff_ms=10000, page_delay_ms=50, base_url='https://api.bybit.com', testnet=False, proxy=None, compress=True, cursor=None, progress=None, strict_range=False, strict=False, partial=False, include_block_trades=True, min_size=None, cache_dir=None, force_refresh=False, time_unit=None, config=None)"
+)]
+pub fn fetch_trades_polars(
+    py: Python,
+    symbol: String,
+    start_time: i64,
+    end_time: i64,
+    limit: Option<i32>,
+    category: Option<String>,
+    api_key: Option<String>,
+    secret: Option<String>,
+    recv_window: Option<u64>,
+    max_retries: Option<u32>,
+    base_backoff_ms: Option<u64>,
+    max_backoff_ms: Option<u64>,
+    page_delay_ms: Option<u64>,
+    base_url: Option<String>,
+    testnet: Option<bool>,
+    proxy: Option<String>,
+    compress: Option<bool>,
+    cursor: Option<String>,
+    progress: Option<PyObject>,
+    strict_range: Option<bool>,
+    strict: Option<bool>,
+    partial: Option<bool>,
+    include_block_trades: Option<bool>,
+    min_size: Option<f64>,
+    cache_dir: Option<String>,
+    force_refresh: Option<bool>,
+    time_unit: Option<String>,
+    config: Option<FetchConfig>,
+) -> PyResult<PyDataFrame> {
+    let mut config = config.unwrap_or_default();
+    override_config!(
+        config,
+        [
+            limit,
+            category,
+            api_key,
+            secret,
+            recv_window,
+            max_retries,
+            base_backoff_ms,
+            max_backoff_ms,
+            page_delay_ms,
+            base_url,
+            testnet,
+            compress,
+            strict_range,
+            strict,
+            include_block_trades,
+            force_refresh
+        ],
+        [proxy, min_size, cache_dir, time_unit]
+    );
+    let fetcher = BybitHistoryFetcher::from_config(&config)?;
+    let trades = fetch_trade_rows(
+        py,
+        &fetcher,
+        symbol.clone(),
+        start_time,
+        end_time,
+        &config,
+        cursor,
+        progress,
+        partial.unwrap_or(false),
+    )?;
+
+    trades_to_frame(&symbol, &trades)
+        .map(PyDataFrame)
+        .map_err(|e| PyRuntimeError::new_err(e.to_string()))
+}
+
+/// Builds the frame returned by [`fetch_trades_polars`], with a `symbol` column holding the
+/// requested symbol.
+fn trades_to_frame(symbol: &str, trades: &[TradeRow]) -> PolarsResult<DataFrame> {
+    DataFrame::new(vec![
+        Column::new(
+            "timestamp".into(),
+            trades
+                .iter()
+                .map(|trade| trade.timestamp)
+                .collect::<Vec<_>>(),
+        ),
+        Column::new("symbol".into(), vec![symbol; trades.len()]),
+        Column::new(
+            "side".into(),
+            trades
+                .iter()
+                .map(|trade| trade.side.as_str())
+                .collect::<Vec<_>>(),
+        ),
+        Column::new(
+            "size".into(),
+            trades.iter().map(|trade| trade.size).collect::<Vec<_>>(),
+        ),
+        Column::new(
+            "price".into(),
+            trades.iter().map(|trade| trade.price).collect::<Vec<_>>(),
+        ),
+        Column::new(
+            "is_block_trade".into(),
+            trades
+                .iter()
+                .map(|trade| trade.is_block_trade)
+                .collect::<Vec<_>>(),
+        ),
+    ])
+}
+
+/// Fetch Bybit trade history in fixed-size time windows, passing each window to a callback.
+///
+/// Only one window is held in memory at a time, which keeps memory bounded for multi-day pulls.
+/// Windows are half-open, so a trade exactly on a boundary millisecond is delivered once.
+///
+/// Args:
+///     symbol: Trading symbol (e.g., "BTCUSDT")
+///     start_time: Start timestamp in milliseconds, or in time_unit if given (inclusive)
+///     end_time: End timestamp in milliseconds, or in time_unit if given (exclusive)
+///     chunk_ms: Window length in milliseconds
+///     callback: Called with a list of trade dicts for every window, including empty ones, sorted
+///               as in fetch_trades
+///     limit: Number of trades per request (default 1000, max 1000)
+///     category: Product type: "linear", "inverse", "spot", or "option" (default "linear")
+///     api_key: Bybit API key (optional for public endpoint)
+///     secret: Bybit API secret (optional for public endpoint)
+///     recv_window: Validity window of a signed request in milliseconds (default 5000)
+///     max_retries: Maximum number of retries on a 429, a 5xx or a network error (default 5)
+///     base_backoff_ms: Wait before the first retry in milliseconds, doubled on each retry (default 50)
+///     max_backoff_ms: Upper bound of the wait between retries in milliseconds (default 10000)
+///     page_delay_ms: Delay between page requests in milliseconds when Bybit sends no rate-limit
+///                    headers to pace them by. 0 removes it, which risks 429s on standard
+///                    accounts (default 50)
+///     base_url: Base URL for Bybit API (default "https://api.bybit.com")
+///     testnet: Use the Bybit testnet at "https://api-testnet.bybit.com" unless a custom base_url is
+///              given (default False)
+///     proxy: Proxy URL to send requests through, with an http, https, socks5 or socks5h scheme
+///            (default None)
+///     compress: Accept gzip and brotli compressed responses (default True)
+///     side_as_int: Return side as 1 for Buy and -1 for Sell instead of the string (default False)
+///     time_unit: Unit of start_time, end_time and the returned timestamps: "s", "ms", "us" or
+///                "ns". Without it, the timestamps are milliseconds and a start_time before 1973,
+///                which is most likely in seconds, raises ValueError (default None)
+///     config: FetchConfig supplying the settings above that are not passed explicitly, and the
+///             others it holds, such as timeout_ms and user_agent (default None)
+///
+/// Returns:
+///     Total number of trades delivered to the callback
+///
+/// Raises:
+///     RuntimeError: If the API request fails or rate limit is exceeded
+///     Any exception raised by the callback
+#[allow(clippy::too_many_arguments)]
+#[pyfunction]
+#[pyo3(
+    text_signature = "(symbol, start_time, end_time, chunk_ms, callback, *, limit=1000, category='linear', api_key='', secret='', recv_window=5000, max_retries=5, base_backoff_ms=50, max_backoff_ms=10000, page_delay_ms=50, base_url='https://api.bybit.com', testnet=False, proxy=None, compress=True, side_as_int=False, time_unit=None, config=None)"
+)]
+pub fn fetch_trades_chunked(
+    py: Python,
+    symbol: String,
+    start_time: i64,
+    end_time: i64,
+    chunk_ms: i64,
+    callback: PyObject,
+    limit: Option<i32>,
+    category: Option<String>,
+    api_key: Option<String>,
+    secret: Option<String>,
+    recv_window: Option<u64>,
+    max_retries: Option<u32>,
+    base_backoff_ms: Option<u64>,
+    max_backoff_ms: Option<u64>,
+    page_delay_ms: Option<u64>,
+    base_url: Option<String>,
+    testnet: Option<bool>,
+    proxy: Option<String>,
+    compress: Option<bool>,
+    side_as_int: Option<bool>,
+    time_unit: Option<String>,
+    config: Option<FetchConfig>,
+) -> PyResult<usize> {
+    let mut config = config.unwrap_or_default();
+    override_config!(
+        config,
+        [
+            limit,
+            category,
+            api_key,
+            secret,
+            recv_window,
+            max_retries,
+            base_backoff_ms,
+            max_backoff_ms,
+            page_delay_ms,
+            base_url,
+            testnet,
+            compress,
+            side_as_int
+        ],
+        [proxy, time_unit]
+    );
+    let time_unit = TimeUnit::resolve(config.time_unit.as_deref(), start_time)?;
+    let (start_time, end_time) = (
+        time_unit.unit_to_ms(start_time),
+        time_unit.unit_to_ms(end_time),
+    );
+    let fetcher = BybitHistoryFetcher::from_config(&config)?;
+
+    let rt = runtime()?;
+
+    let mut callback_err = None;
+    let result = py.allow_threads(|| {
+        rt.block_on(fetcher.fetch_trades_chunked(
+            &config.category,
+            &symbol,
+            start_time,
+            end_time,
+            chunk_ms,
+            config.limit,
+            |mut chunk| {
+                time_unit.convert_trades(&mut chunk);
+                Python::with_gil(|py| {
+                    let chunk = chunk
+                        .iter()
+                        .map(|t| t.to_dict(py, config.side_as_int, false))
+                        .collect::<PyResult<Vec<_>>>()?;
+                    PyList::new(py, chunk).and_then(|chunk| callback.call1(py, (chunk,)))
+                })
+                .map(|_| ())
+                .map_err(|e| {
+                    callback_err = Some(e);
+                    BybitError::Callback
+                })
+            },
+        ))
+    });
+
+    match (result, callback_err) {
+        (_, Some(e)) => Err(e),
+        (result, None) => Ok(record_stats(result?).trades),
+    }
+}
+
+/// Fetch Bybit trade history between two timestamps by fetching sub-windows concurrently.
+///
+/// The range is split into `num_workers` equal sub-windows that are paginated concurrently, which
+/// is much faster than a single cursor walk for ranges spanning days. The merged trades are
+/// deduplicated by exec_id and sorted by timestamp.
+///
+/// Args:
+///     symbol: Trading symbol (e.g., "BTCUSDT")
+///     start_time: Start timestamp in milliseconds, or in time_unit if given
+///     end_time: End timestamp in milliseconds, or in time_unit if given
+///     num_workers: Number of sub-windows fetched concurrently, clamped to 1..=8 (default 4)
+///     limit: Number of trades per request (default 1000, max 1000)
+///     category: Product type: "linear", "inverse", "spot", or "option" (default "linear")
+///     api_key: Bybit API key (optional for public endpoint)
+///     secret: Bybit API secret (optional for public endpoint)
+///     recv_window: Validity window of a signed request in milliseconds (default 5000)
+///     max_retries: Maximum number of retries on a 429, a 5xx or a network error (default 5)
+///     base_backoff_ms: Wait before the first retry in milliseconds, doubled on each retry (default 50)
+///     max_backoff_ms: Upper bound of the wait between retries in milliseconds (default 10000)
+///     page_delay_ms: Delay between page requests in milliseconds when Bybit sends no rate-limit
+///                    headers to pace them by. 0 removes it, which risks 429s on standard
+///                    accounts (default 50)
+///     base_url: Base URL for Bybit API (default "https://api.bybit.com")
+///     testnet: Use the Bybit testnet at "https://api-testnet.bybit.com" unless a custom base_url is
+///              given (default False)
+///     proxy: Proxy URL to send requests through, with an http, https, socks5 or socks5h scheme
+///            (default None)
+///     compress: Accept gzip and brotli compressed responses (default True)
+///     side_as_int: Return side as 1 for Buy and -1 for Sell instead of the string (default False)
+///     deterministic: Sort the trades by (timestamp, exec_id), as in fetch_trades (default False)
+///     time_unit: Unit of start_time, end_time and the returned timestamps: "s", "ms", "us" or
+///                "ns". Without it, the timestamps are milliseconds and a start_time before 1973,
+///                which is most likely in seconds, raises ValueError (default None)
+///     config: FetchConfig supplying the settings above that are not passed explicitly, and the
+///             others it holds, such as timeout_ms and user_agent (default None)
+///
+/// Returns:
+///     List of trade dicts in the same format as fetch_trades, sorted by timestamp in ascending
+///     order
+///
+/// Raises:
+///     RuntimeError: If any sub-window fails or rate limit is exceeded
+#[allow(clippy::too_many_arguments)]
+#[pyfunction]
+#[pyo3(
+    text_signature = "(symbol, start_time, end_time, *, num_workers=4, limit=1000, category='linear', api_key='', secret='', recv_window=5000, max_retries=5, base_backoff_ms=50, max_backoff_ms=10000, page_delay_ms=50, base_url='https://api.bybit.com', testnet=False, proxy=None, compress=True, side_as_int=False, deterministic=False, time_unit=None, config=None)"
+)]
+pub fn fetch_trades_concurrent(
+    py: Python,
+    symbol: String,
+    start_time: i64,
+    end_time: i64,
+    num_workers: Option<usize>,
+    limit: Option<i32>,
+    category: Option<String>,
+    api_key: Option<String>,
+    secret: Option<String>,
+    recv_window: Option<u64>,
+    max_retries: Option<u32>,
+    base_backoff_ms: Option<u64>,
+    max_backoff_ms: Option<u64>,
+    page_delay_ms: Option<u64>,
+    base_url: Option<String>,
+    testnet: Option<bool>,
+    proxy: Option<String>,
+    compress: Option<bool>,
+    side_as_int: Option<bool>,
+    deterministic: Option<bool>,
+    time_unit: Option<String>,
+    config: Option<FetchConfig>,
+) -> PyResult<PyObject> {
+    let mut config = config.unwrap_or_default();
+    override_config!(
+        config,
+        [
+            limit,
+            category,
+            api_key,
+            secret,
+            recv_window,
+            max_retries,
+            base_backoff_ms,
+            max_backoff_ms,
+            page_delay_ms,
+            base_url,
+            testnet,
+            compress,
+            side_as_int,
+            deterministic
+        ],
+        [proxy, time_unit]
+    );
+    let time_unit = TimeUnit::resolve(config.time_unit.as_deref(), start_time)?;
+    let (start_time, end_time) = (
+        time_unit.unit_to_ms(start_time),
+        time_unit.unit_to_ms(end_time),
+    );
+    let num_workers = num_workers.unwrap_or(4);
+    let fetcher = BybitHistoryFetcher::from_config(&config)?;
+
+    let rt = runtime()?;
+
+    let (mut trades, stats) = py
+        .allow_threads(|| {
+            rt.block_on(fetcher.fetch_trades_concurrent(
+                &config.category,
+                &symbol,
+                start_time,
+                end_time,
+                config.limit,
+                num_workers,
+            ))
+        })
+        .map_err(PyErr::from)?;
+    record_stats(stats);
+    if config.deterministic {
+        sort_canonical(&mut trades);
+    }
+    time_unit.convert_trades(&mut trades);
+
+    let result = trades
+        .iter()
+        .map(|t| t.to_dict(py, config.side_as_int, false))
+        .collect::<PyResult<Vec<_>>>()?;
+
+    Ok(PyList::new(py, result)?.into())
+}
+
+/// Fetch the trade history of several Bybit symbols concurrently.
+///
+/// Up to `concurrency` symbols are fetched at a time on the shared runtime, and all of them draw
+/// from one token bucket so the aggregate request rate stays within `max_requests_per_sec`. A
+/// failing symbol does not abort the others.
+///
+/// Args:
+///     symbols: Trading symbols (e.g., ["BTCUSDT", "ETHUSDT"])
+///     start_time: Start timestamp in milliseconds, or in time_unit if given
+///     end_time: End timestamp in milliseconds, or in time_unit if given
+///     concurrency: Maximum number of symbols fetched at the same time (default 4)
+///     max_requests_per_sec: Aggregate request budget across all symbols (default 10.0)
+///     limit: Number of trades per request (default 1000, max 1000)
+///     category: Product type: "linear", "inverse", "spot", or "option" (default "linear")
+///     api_key: Bybit API key (optional for public endpoint)
+///     secret: Bybit API secret (optional for public endpoint)
+///     recv_window: Validity window of a signed request in milliseconds (default 5000)
+///     max_retries: Maximum number of retries on a 429, a 5xx or a network error (default 5)
+///     base_backoff_ms: Wait before the first retry in milliseconds, doubled on each retry (default 50)
+///     max_backoff_ms: Upper bound of the wait between retries in milliseconds (default 10000)
+///     page_delay_ms: Delay between page requests in milliseconds when Bybit sends no rate-limit
+///                    headers to pace them by. 0 removes it, which risks 429s on standard
+///                    accounts (default 50)
+///     base_url: Base URL for Bybit API (default "https://api.bybit.com")
+///     testnet: Use the Bybit testnet at "https://api-testnet.bybit.com" unless a custom base_url is
+///              given (default False)
+///     proxy: Proxy URL to send requests through, with an http, https, socks5 or socks5h scheme
+///            (default None)
+///     compress: Accept gzip and brotli compressed responses (default True)
+///     side_as_int: Return side as 1 for Buy and -1 for Sell instead of the string (default False)
+///     time_unit: Unit of start_time, end_time and the returned timestamps: "s", "ms", "us" or
+///                "ns". Without it, the timestamps are milliseconds and a start_time before 1973,
+///                which is most likely in seconds, raises ValueError (default None)
+///     config: FetchConfig supplying the settings above that are not passed explicitly, and the
+///             others it holds, such as timeout_ms and user_agent (default None)
+///
+/// Returns:
+///     Tuple of two dicts keyed by symbol: the trade dicts of every symbol that succeeded, and the
+///     exception of every symbol that failed
+///
+/// Raises:
+///     RuntimeError: If every symbol failed, with the error of the first one
+#[allow(clippy::too_many_arguments)]
+#[pyfunction]
+#[pyo3(
+    text_signature = "(symbols, start_time, end_time, *, concurrency=4, max_requests_per_sec=10.0, limit=1000, category='linear', api_key='', secret='', recv_window=5000, max_retries=5, base_backoff_ms=50, max_backoff_ms=10000, page_delay_ms=50, base_url='https://api.bybit.com', testnet=False, proxy=None, compress=True, side_as_int=False, time_unit=None, config=None)"
+)]
+pub fn fetch_trades_multi(
+    py: Python,
+    symbols: Vec<String>,
+    start_time: i64,
+    end_time: i64,
+    concurrency: Option<usize>,
+    max_requests_per_sec: Option<f64>,
+    limit: Option<i32>,
+    category: Option<String>,
+    api_key: Option<String>,
+    secret: Option<String>,
+    recv_window: Option<u64>,
+    max_retries: Option<u32>,
+    base_backoff_ms: Option<u64>,
+    max_backoff_ms: Option<u64>,
+    page_delay_ms: Option<u64>,
+    base_url: Option<String>,
+    testnet: Option<bool>,
+    proxy: Option<String>,
+    compress: Option<bool>,
+    side_as_int: Option<bool>,
+    time_unit: Option<String>,
+    config: Option<FetchConfig>,
+) -> PyResult<(PyObject, PyObject)> {
+    let mut config = config.unwrap_or_default();
+    override_config!(
+        config,
+        [
+            limit,
+            category,
+            api_key,
+            secret,
+            recv_window,
+            max_retries,
+            base_backoff_ms,
+            max_backoff_ms,
+            page_delay_ms,
+            base_url,
+            testnet,
+            compress,
+            side_as_int
+        ],
+        [proxy, time_unit]
+    );
+    let time_unit = TimeUnit::resolve(config.time_unit.as_deref(), start_time)?;
+    let (start_time, end_time) = (
+        time_unit.unit_to_ms(start_time),
+        time_unit.unit_to_ms(end_time),
+    );
+    let concurrency = concurrency.unwrap_or(4);
+    let max_requests_per_sec = max_requests_per_sec.unwrap_or(10.0);
+    if concurrency == 0 {
+        return Err(BybitError::InvalidArg("concurrency must be positive".to_string()).into());
+    }
+    if max_requests_per_sec.is_nan() || max_requests_per_sec <= 0.0 {
+        return Err(BybitError::InvalidArg(format!(
+            "max_requests_per_sec must be positive: {}",
+            max_requests_per_sec
+        ))
+        .into());
+    }
+    let fetcher = BybitHistoryFetcher::from_config(&config)?
+        .with_rate_limiter(Arc::new(TokenBucket::new(max_requests_per_sec)));
+
+    let rt = runtime()?;
+
+    let (results, stats) = py.allow_threads(|| {
+        rt.block_on(fetcher.fetch_trades_multi(
+            &config.category,
+            &symbols,
+            start_time,
+            end_time,
+            config.limit,
+            concurrency,
+        ))
+    });
+    record_stats(stats);
+
+    let mut succeeded = Vec::new();
+    let mut failed = Vec::new();
+    for (symbol, result) in results {
+        match result {
+            Ok(rows) => succeeded.push((symbol, rows)),
+            Err(e) => failed.push((symbol, e)),
+        }
+    }
+    if succeeded.is_empty() && !failed.is_empty() {
+        return Err(failed.swap_remove(0).1.into());
+    }
+
+    let trades = pyo3::types::PyDict::new(py);
+    for (symbol, mut rows) in succeeded {
+        time_unit.convert_trades(&mut rows);
+        let rows = rows
+            .iter()
+            .map(|t| t.to_dict(py, config.side_as_int, false))
+            .collect::<PyResult<Vec<_>>>()?;
+        trades.set_item(symbol, PyList::new(py, rows)?)?;
+    }
+    let errors = pyo3::types::PyDict::new(py);
+    for (symbol, e) in failed {
+        errors.set_item(symbol, PyErr::from(e).into_value(py))?;
+    }
+
+    Ok((trades.into(), errors.into()))
+}
+
+/// Fetch Bybit trade history between two timestamps and write it to a Parquet file.
+///
+/// Pages are streamed into the writer as they arrive and flushed every row_group_size trades, so
+/// peak memory stays bounded regardless of the length of the time range.
+///
+/// Args:
+///     symbol: Trading symbol (e.g., "BTCUSDT")
+///     start_time: Start timestamp in milliseconds, or in time_unit if given
+///     end_time: End timestamp in milliseconds, or in time_unit if given
+///     path: Output Parquet file path
+///     compression: Parquet compression codec: "snappy", "zstd", or "none" (default "snappy")
+///     row_group_size: Number of trades buffered before they are flushed as a row group
+///                     (default 100000)
+///     limit: Number of trades per request (default 1000, max 1000)
+///     category: Product type: "linear", "inverse", "spot", or "option" (default "linear")
+///     api_key: Bybit API key (optional for public endpoint)
+///     secret: Bybit API secret (optional for public endpoint)
+///     recv_window: Validity window of a signed request in milliseconds (default 5000)
+///     max_retries: Maximum number of retries on a 429, a 5xx or a network error (default 5)
+///     base_backoff_ms: Wait before the first retry in milliseconds, doubled on each retry (default 50)
+///     max_backoff_ms: Upper bound of the wait between retries in milliseconds (default 10000)
+///     page_delay_ms: Delay between page requests in milliseconds when Bybit sends no rate-limit
+///                    headers to pace them by. 0 removes it, which risks 429s on standard
+///                    accounts (default 50)
+///     base_url: Base URL for Bybit API (default "https://api.bybit.com")
+///     testnet: Use the Bybit testnet at "https://api-testnet.bybit.com" unless a custom base_url is
+///              given (default False)
+///     proxy: Proxy URL to send requests through, with an http, https, socks5 or socks5h scheme
+///            (default None)
+///     compress: Accept gzip and brotli compressed responses (default True)
+///     time_unit: Unit of start_time and end_time: "s", "ms", "us" or "ns". The file keeps its
+///                own timestamp unit regardless. Without it, the timestamps are milliseconds and a
+///                start_time before 1973, which is most likely in seconds, raises ValueError
+///                (default None)
+///     config: FetchConfig supplying the settings above that are not passed explicitly, and the
+///             others it holds, such as timeout_ms and user_agent (default None)
+///
+/// Returns:
+///     Tuple of (number of rows written, file size in bytes). The schema is timestamp (int64, ms),
+///     symbol (dictionary-encoded utf8), side (int8, +1 for a taker buy and -1 for a taker sell),
+///     price (float64), size (float64).
+///
+/// Raises:
+///     RuntimeError: If the API request fails, rate limit is exceeded, or the file cannot be written
+#[allow(clippy::too_many_arguments)]
+#[pyfunction]
+#[pyo3(
+    text_signature = "(symbol, start_time, end_time, path, *, compression='snappy', row_group_size=100000, limit=1000, category='linear', api_key='', secret='', recv_window=5000, max_retries=5, base_backoff_ms=50, max_backoff_ms=10000, page_delay_ms=50, base_url='https://api.bybit.com', testnet=False, proxy=None, compress=True, time_unit=None, config=None)"
+)]
+pub fn fetch_trades_to_parquet(
+    py: Python,
+    symbol: String,
+    start_time: i64,
+    end_time: i64,
+    path: String,
+    compression: Option<String>,
+    row_group_size: Option<usize>,
+    limit: Option<i32>,
+    category: Option<String>,
+    api_key: Option<String>,
+    secret: Option<String>,
+    recv_window: Option<u64>,
+    max_retries: Option<u32>,
+    base_backoff_ms: Option<u64>,
+    max_backoff_ms: Option<u64>,
+    page_delay_ms: Option<u64>,
+    base_url: Option<String>,
+    testnet: Option<bool>,
+    proxy: Option<String>,
+    compress: Option<bool>,
+    time_unit: Option<String>,
+    config: Option<FetchConfig>,
+) -> PyResult<(usize, u64)> {
+    let mut config = config.unwrap_or_default();
+    override_config!(
+        config,
+        [
+            limit,
+            category,
+            api_key,
+            secret,
+            recv_window,
+            max_retries,
+            base_backoff_ms,
+            max_backoff_ms,
+            page_delay_ms,
+            base_url,
+            testnet,
+            compress
+        ],
+        [proxy, time_unit]
+    );
+    let time_unit = TimeUnit::resolve(config.time_unit.as_deref(), start_time)?;
+    let (start_time, end_time) = (
+        time_unit.unit_to_ms(start_time),
+        time_unit.unit_to_ms(end_time),
+    );
+    let fetcher = BybitHistoryFetcher::from_config(&config)?;
+
+    let rt = runtime()?;
+
+    py.allow_threads(|| {
+        let mut writer = TradeParquetWriter::create(
+            &path,
+            compression.as_deref().unwrap_or("snappy"),
+            row_group_size.unwrap_or(PARQUET_ROW_GROUP_SIZE),
+        )?;
+        rt.block_on(fetcher.fetch_trade_pages(
+            &config.category,
+            &symbol,
+            start_time,
+            end_time,
+            config.limit,
+            |page, _| writer.write(&page),
+        ))
+        .map(record_stats)?;
+        writer.close()
+    })
+    .map_err(PyErr::from)
+}
+
+/// Fetch Bybit trade history between two timestamps and write it to a CSV file.
+///
+/// Each page is appended to the file as soon as it arrives. The columns are timestamp (ms),
+/// symbol, side, size, price, preceded by a header row.
+///
+/// Args:
+///     symbol: Trading symbol (e.g., "BTCUSDT")
+///     start_time: Start timestamp in milliseconds, or in time_unit if given
+///     end_time: End timestamp in milliseconds, or in time_unit if given
+///     path: Output CSV file path
+///     append: Append to an existing file instead of overwriting it, writing the header only if
+///             the file is new or empty, so several windows can be concatenated (default False)
+///     limit: Number of trades per request (default 1000, max 1000)
+///     category: Product type: "linear", "inverse", "spot", or "option" (default "linear")
+///     api_key: Bybit API key (optional for public endpoint)
+///     secret: Bybit API secret (optional for public endpoint)
+///     recv_window: Validity window of a signed request in milliseconds (default 5000)
+///     max_retries: Maximum number of retries on a 429, a 5xx or a network error (default 5)
+///     base_backoff_ms: Wait before the first retry in milliseconds, doubled on each retry (default 50)
+///     max_backoff_ms: Upper bound of the wait between retries in milliseconds (default 10000)
+///     page_delay_ms: Delay between page requests in milliseconds when Bybit sends no rate-limit
+///                    headers to pace them by. 0 removes it, which risks 429s on standard
+///                    accounts (default 50)
+///     base_url: Base URL for Bybit API (default "https://api.bybit.com")
+///     testnet: Use the Bybit testnet at "https://api-testnet.bybit.com" unless a custom base_url is
+///              given (default False)
+///     proxy: Proxy URL to send requests through, with an http, https, socks5 or socks5h scheme
+///            (default None)
+///     compress: Accept gzip and brotli compressed responses (default True)
+///     time_unit: Unit of start_time and end_time: "s", "ms", "us" or "ns". The file keeps its
+///                own timestamp unit regardless. Without it, the timestamps are milliseconds and a
+///                start_time before 1973, which is most likely in seconds, raises ValueError
+///                (default None)
+///     config: FetchConfig supplying the settings above that are not passed explicitly, and the
+///             others it holds, such as timeout_ms and user_agent (default None)
+///
+/// Returns:
+///     Number of rows written, excluding the header
+///
+/// Raises:
+///     RuntimeError: If the API request fails, rate limit is exceeded, or the file cannot be written
+#[allow(clippy::too_many_arguments)]
+#[pyfunction]
+#[pyo3(
+    text_signature = "(symbol, start_time, end_time, path, *, append=False, limit=1000, category='linear', api_key='', secret='', recv_window=5000, max_retries=5, base_backoff_ms=50, max_backoff_ms=10000, page_delay_ms=50, base_url='https://api.bybit.com', testnet=False, proxy=None, compress=True, time_unit=None, config=None)"
+)]
+pub fn fetch_trades_to_csv(
+    py: Python,
+    symbol: String,
+    start_time: i64,
+    end_time: i64,
+    path: String,
+    append: Option<bool>,
+    limit: Option<i32>,
+    category: Option<String>,
+    api_key: Option<String>,
+    secret: Option<String>,
+    recv_window: Option<u64>,
+    max_retries: Option<u32>,
+    base_backoff_ms: Option<u64>,
+    max_backoff_ms: Option<u64>,
+    page_delay_ms: Option<u64>,
+    base_url: Option<String>,
+    testnet: Option<bool>,
+    proxy: Option<String>,
+    compress: Option<bool>,
+    time_unit: Option<String>,
+    config: Option<FetchConfig>,
+) -> PyResult<usize> {
+    let mut config = config.unwrap_or_default();
+    override_config!(
+        config,
+        [
+            limit,
+            category,
+            api_key,
+            secret,
+            recv_window,
+            max_retries,
+            base_backoff_ms,
+            max_backoff_ms,
+            page_delay_ms,
+            base_url,
+            testnet,
+            compress
+        ],
+        [proxy, time_unit]
+    );
+    let time_unit = TimeUnit::resolve(config.time_unit.as_deref(), start_time)?;
+    let (start_time, end_time) = (
+        time_unit.unit_to_ms(start_time),
+        time_unit.unit_to_ms(end_time),
+    );
+    let fetcher = BybitHistoryFetcher::from_config(&config)?;
+
+    let rt = runtime()?;
+
+    py.allow_threads(|| {
+        let mut writer = TradeCsvWriter::create(&path, append.unwrap_or(false))?;
+        rt.block_on(fetcher.fetch_trade_pages(
+            &config.category,
+            &symbol,
+            start_time,
+            end_time,
+            config.limit,
+            |page, _| writer.write(&page),
+        ))
+        .map(record_stats)?;
+        writer.close()
+    })
+    .map_err(PyErr::from)
+}
+
+/// Fetch Bybit trade history and write it as hftbacktest trade events to an `.npz` file.
+///
+/// Each trade becomes an `Event` with `ev = EXCH_EVENT | LOCAL_EVENT | TRADE_EVENT` plus
+/// `BUY_EVENT` or `SELL_EVENT` for the taker side, and timestamps in nanoseconds. The file stores
+/// the events under the `data` key, sorted by timestamp, so it can be passed to
+/// `BacktestAsset.data` directly. Pages are streamed to disk as they arrive.
+///
+/// Args:
+///     symbol: Trading symbol (e.g., "BTCUSDT")
+///     start_time: Start timestamp in milliseconds, or in time_unit if given
+///     end_time: End timestamp in milliseconds, or in time_unit if given
+///     path: Output `.npz` file path
+///     feed_latency: Offset in nanoseconds added to the exchange timestamp to produce the local
+///                   timestamp (default 0)
+///     latency: LatencyInjector that produces the local timestamps instead of feed_latency, for a
+///              jittered or empirical feed latency (default None)
+///     tick_size: Count each price in ticks from its decimal string and store it as an exact
+///                multiple of tick_size (default None, store the parsed price)
+///     lot_size: Count each size in lots from its decimal string and store it as an exact multiple
+///               of lot_size (default None, store the parsed size)
+///     limit: Number of trades per request (default 1000, max 1000)
+///     category: Product type: "linear", "inverse", "spot", or "option" (default "linear")
+///     api_key: Bybit API key (optional for public endpoint)
+///     secret: Bybit API secret (optional for public endpoint)
+///     recv_window: Validity window of a signed request in milliseconds (default 5000)
+///     max_retries: Maximum number of retries on a 429, a 5xx or a network error (default 5)
+///     base_backoff_ms: Wait before the first retry in milliseconds, doubled on each retry (default 50)
+///     max_backoff_ms: Upper bound of the wait between retries in milliseconds (default 10000)
+///     page_delay_ms: Delay between page requests in milliseconds when Bybit sends no rate-limit
+///                    headers to pace them by. 0 removes it, which risks 429s on standard
+///                    accounts (default 50)
+///     base_url: Base URL for Bybit API (default "https://api.bybit.com")
+///     testnet: Use the Bybit testnet at "https://api-testnet.bybit.com" unless a custom base_url is
+///              given (default False)
+///     proxy: Proxy URL to send requests through, with an http, https, socks5 or socks5h scheme
+///            (default None)
+///     compress: Accept gzip and brotli compressed responses (default True)
+///     time_unit: Unit of start_time and end_time: "s", "ms", "us" or "ns". The file keeps its
+///                own timestamp unit regardless. Without it, the timestamps are milliseconds and a
+///                start_time before 1973, which is most likely in seconds, raises ValueError
+///                (default None)
+///     config: FetchConfig supplying the settings above that are not passed explicitly, and the
+///             others it holds, such as timeout_ms and user_agent (default None)
+///
+/// Returns:
+///     Number of events written
+///
+/// Raises:
+///     RuntimeError: If the API request fails, rate limit is exceeded, a price or size is not a
+///                   multiple of tick_size or lot_size, or the file cannot be written
+#[allow(clippy::too_many_arguments)]
+#[pyfunction]
+#[pyo3(
+    text_signature = "(symbol, start_time, end_time, path, *, feed_latency=0, latency=None, tick_size=None, lot_size=None, limit=1000, category='linear', api_key='', secret='', recv_window=5000, max_retries=5, base_backoff_ms=50, max_backoff_ms=10000, page_delay_ms=50, base_url='https://api.bybit.com', testnet=False, proxy=None, compress=True, time_unit=None, config=None)"
+)]
+pub fn fetch_trades_to_npz(
+    py: Python,
+    symbol: String,
+    start_time: i64,
+    end_time: i64,
+    path: String,
+    feed_latency: Option<i64>,
+    latency: Option<LatencyInjector>,
+    tick_size: Option<f64>,
+    lot_size: Option<f64>,
+    limit: Option<i32>,
+    category: Option<String>,
+    api_key: Option<String>,
+    secret: Option<String>,
+    recv_window: Option<u64>,
+    max_retries: Option<u32>,
+    base_backoff_ms: Option<u64>,
+    max_backoff_ms: Option<u64>,
+    page_delay_ms: Option<u64>,
+    base_url: Option<String>,
+    testnet: Option<bool>,
+    proxy: Option<String>,
+    compress: Option<bool>,
+    time_unit: Option<String>,
+    config: Option<FetchConfig>,
+) -> PyResult<usize> {
+    let mut config = config.unwrap_or_default();
+    override_config!(
+        config,
+        [
+            limit,
+            category,
+            api_key,
+            secret,
+            recv_window,
+            max_retries,
+            base_backoff_ms,
+            max_backoff_ms,
+            page_delay_ms,
+            base_url,
+            testnet,
+            compress
+        ],
+        [proxy, time_unit]
+    );
+    let time_unit = TimeUnit::resolve(config.time_unit.as_deref(), start_time)?;
+    let (start_time, end_time) = (
+        time_unit.unit_to_ms(start_time),
+        time_unit.unit_to_ms(end_time),
+    );
+    let latency = latency.unwrap_or_else(|| LatencyInjector::constant(feed_latency.unwrap_or(0)));
+    let grid = TickGrid::new(tick_size, lot_size)?;
+    let fetcher = BybitHistoryFetcher::from_config(&config)?;
+
+    let rt = runtime()?;
+
+    py.allow_threads(|| {
+        let mut writer = TradeNpzWriter::create(&path, latency)?.with_grid(grid);
+        rt.block_on(fetcher.fetch_trade_pages(
+            &config.category,
+            &symbol,
+            start_time,
+            end_time,
+            config.limit,
+            |page, _| writer.write(&page),
+        ))
+        .map(record_stats)?;
+        writer.finish()
+    })
+    .map_err(PyErr::from)
+}
+
+/// Fetch Bybit trade history one UTC day at a time, writing each day to its own file.
+///
+/// Each day from start_date to end_date, both inclusive, is fetched with the pagination of
+/// fetch_trades and written to "{symbol}_{YYYYMMDD}.npz" or ".parquet" under out_dir. Days are
+/// half-open: a trade at 23:59:59.999 belongs to its day and one at 00:00:00.000 to the next. A
+/// day whose file already exists is skipped, so an interrupted run can simply be repeated; each
+/// file is only given its final name once complete.
+///
+/// Args:
+///     symbol: Trading symbol (e.g., "BTCUSDT")
+///     start_date: First UTC day as "YYYY-MM-DD"
+///     end_date: Last UTC day as "YYYY-MM-DD", inclusive
+///     out_dir: Output directory, created if missing
+///     format: "npz" for trade events in the format of fetch_trades_to_npz, or "parquet" for
+///             trade rows in the format of fetch_trades_to_parquet (default "npz")
+///     overwrite: Fetch and rewrite the days whose file already exists (default False)
+///     feed_latency: Offset in nanoseconds added to the exchange timestamp to produce the local
+///                   timestamp of the npz events (default 0)
+///     config: FetchConfig with the client settings and the category, limit,
+///             include_block_trades and min_size of the fetches (default None)
+///
+/// Returns:
+///     List of DaySummary, one per day in order, with date, path, skipped, trades,
+///     first_timestamp and last_timestamp
+///
+/// Raises:
+///     BybitError: If a date is malformed, start_date is after end_date, the format is unknown,
+///                 the API request fails, or a file cannot be written. The days completed
+///                 before the error keep their files
+#[allow(clippy::too_many_arguments)]
+#[pyfunction]
+#[pyo3(
+    text_signature = "(symbol, start_date, end_date, out_dir, format='npz', *, overwrite=False, feed_latency=0, config=None)"
+)]
+pub fn fetch_trades_daily(
+    py: Python,
+    symbol: String,
+    start_date: String,
+    end_date: String,
+    out_dir: String,
+    format: Option<String>,
+    overwrite: Option<bool>,
+    feed_latency: Option<i64>,
+    config: Option<FetchConfig>,
+) -> PyResult<Vec<DaySummary>> {
+    let config = config.unwrap_or_default();
+    let start_date = parse_date(&start_date)?;
+    let end_date = parse_date(&end_date)?;
+    let format = DailyFormat::parse(format.as_deref().unwrap_or("npz"))?;
+    if config.min_size.is_some_and(f64::is_nan) {
+        return Err(BybitError::InvalidArg("min_size is NaN".to_string()).into());
+    }
+    if start_date <= end_date {
+        check_trade_range(
+            py,
+            day_range(start_date).0,
+            day_range(end_date).1,
+            Some(config.strict_range),
+        )?;
+    }
+
+    let fetcher = BybitHistoryFetcher::from_config(&config)?;
+    let rt = runtime()?;
+
+    let (summaries, stats) = py.allow_threads(|| {
+        rt.block_on(fetcher.fetch_trades_daily(
+            &symbol,
+            start_date,
+            end_date,
+            Path::new(&out_dir),
+            format,
+            overwrite.unwrap_or(false),
+            feed_latency.unwrap_or(0),
+            &config,
+        ))
+    })?;
+    record_stats(stats);
+    Ok(summaries)
+}
+
+/// Fetch Bybit trade history between two timestamps and aggregate it into fixed time buckets,
+/// for when the raw trades are too large to keep.
+///
+/// The trades are fetched with the pagination of fetch_trades and added to their bucket as each
+/// page arrives, so only the buckets are held in memory. Buckets are aligned to multiples of
+/// bucket_ms since the Unix epoch, and those without trades are left out.
+///
+/// Args:
+///     symbol: Trading symbol (e.g., "BTCUSDT")
+///     start_time: Start timestamp in milliseconds (inclusive)
+///     end_time: End timestamp in milliseconds (exclusive)
+///     bucket_ms: Length of a bucket in milliseconds
+///     config: FetchConfig with the client settings and the category, limit, strict_range,
+///             include_block_trades and min_size of the fetch (default None)
+///
+/// Returns:
+///     List of dicts with keys: start_ts, vwap, volume, buy_volume, sell_volume, trade_count,
+///     sorted by start_ts in ascending order
+///
+/// Raises:
+///     BybitError: If start_time is not before end_time, bucket_ms is not positive, strict_range
+///                 is set and start_time is older than the retention, or the API request fails
+#[pyfunction]
+#[pyo3(text_signature = "(symbol, start_time, end_time, bucket_ms, *, config=None)")]
+pub fn fetch_trades_aggregated(
+    py: Python,
+    symbol: String,
+    start_time: i64,
+    end_time: i64,
+    bucket_ms: i64,
+    config: Option<FetchConfig>,
+) -> PyResult<PyObject> {
+    let config = config.unwrap_or_default();
+    check_trade_range(py, start_time, end_time, Some(config.strict_range))?;
+    if config.min_size.is_some_and(f64::is_nan) {
+        return Err(BybitError::InvalidArg("min_size is NaN".to_string()).into());
+    }
+
+    let fetcher = BybitHistoryFetcher::from_config(&config)?;
+    let rt = runtime()?;
+
+    let (buckets, stats) = py.allow_threads(|| {
+        rt.block_on(
+            fetcher.fetch_trades_aggregated(&symbol, start_time, end_time, bucket_ms, &config),
+        )
+    })?;
+    record_stats(stats);
+    let result = buckets
+        .iter()
+        .map(|bucket| bucket.to_dict(py))
+        .collect::<PyResult<Vec<_>>>()?;
+    Ok(PyList::new(py, result)?.into())
+}
+
+/// Fetch Bybit klines (OHLCV candles) between two timestamps.
+///
+/// Args:
+///     symbol: Trading symbol (e.g., "BTCUSDT")
+///     interval: Kline interval: "1", "3", "5", "15", "30", "60", "120", "240", "360", "720" (minutes),
+///               "D" (day), "W" (week), or "M" (month)
+///     start_time: Start timestamp in milliseconds
+///     end_time: End timestamp in milliseconds
+///     limit: Number of klines per request (default 1000, max 1000)
+///     category: Product type: "linear", "inverse", or "spot" (default "linear")
+///     api_key: Bybit API key (optional for public endpoint)
+///     secret: Bybit API secret (optional for public endpoint)
+///     recv_window: Validity window of a signed request in milliseconds (default 5000)
+///     max_retries: Maximum number of retries on a 429, a 5xx or a network error (default 5)
+///     base_backoff_ms: Wait before the first retry in milliseconds, doubled on each retry (default 50)
+///     max_backoff_ms: Upper bound of the wait between retries in milliseconds (default 10000)
+///     page_delay_ms: Delay between page requests in milliseconds when Bybit sends no rate-limit
+///                    headers to pace them by. 0 removes it, which risks 429s on standard
+///                    accounts (default 50)
+///     base_url: Base URL for Bybit API (default "https://api.bybit.com")
+///     testnet: Use the Bybit testnet at "https://api-testnet.bybit.com" unless a custom base_url is
+///              given (default False)
+///     proxy: Proxy URL to send requests through, with an http, https, socks5 or socks5h scheme
+///            (default None)
+///     compress: Accept gzip and brotli compressed responses (default True)
+///     config: FetchConfig supplying the settings above that are not passed explicitly, and the
+///             others it holds, such as timeout_ms and user_agent (default None)
+///
+/// Returns:
+///     List of dicts with keys: start_time, open, high, low, close, volume, turnover,
+///     sorted by start_time in ascending order
+///
+/// Raises:
+///     RuntimeError: If the interval is invalid, the API request fails or rate limit is exceeded
+#[allow(clippy::too_many_arguments)]
+#[pyfunction]
+#[pyo3(
+    text_signature = "(symbol, interval, start_time, end_time, *, limit=1000, category='linear', api_key='', secret='', recv_window=5000, max_retries=5, base_backoff_ms=50, max_backoff_ms=10000, page_delay_ms=50, base_url='https://api.bybit.com', testnet=False, proxy=None, compress=True, config=None)"
+)]
+pub fn fetch_klines(
+    py: Python,
+    symbol: String,
+    interval: String,
+    start_time: i64,
+    end_time: i64,
+    limit: Option<i32>,
+    category: Option<String>,
+    api_key: Option<String>,
+    secret: Option<String>,
+    recv_window: Option<u64>,
+    max_retries: Option<u32>,
+    base_backoff_ms: Option<u64>,
+    max_backoff_ms: Option<u64>,
+    page_delay_ms: Option<u64>,
+    base_url: Option<String>,
+    testnet: Option<bool>,
+    proxy: Option<String>,
+    compress: Option<bool>,
+    config: Option<FetchConfig>,
+) -> PyResult<PyObject> {
+    let mut config = config.unwrap_or_default();
+    override_config!(
+        config,
+        [
+            limit,
+            category,
+            api_key,
+            secret,
+            recv_window,
+            max_retries,
+            base_backoff_ms,
+            max_backoff_ms,
+            page_delay_ms,
+            base_url,
+            testnet,
+            compress
+        ],
+        [proxy]
+    );
+    let klines = fetch_kline_rows(py, symbol, interval, start_time, end_time, &config)?;
+
+    let result = klines
+        .iter()
+        .map(|k| k.to_dict(py))
+        .collect::<PyResult<Vec<_>>>()?;
+
+    Ok(PyList::new(py, result)?.into())
+}
+
+/// Fetch Bybit klines between two timestamps as a NumPy structured array.
+///
+/// Args:
+///     symbol: Trading symbol (e.g., "BTCUSDT")
+///     interval: Kline interval: "1", "3", "5", "15", "30", "60", "120", "240", "360", "720" (minutes),
+///               "D" (day), "W" (week), or "M" (month)
+///     start_time: Start timestamp in milliseconds
+///     end_time: End timestamp in milliseconds
+///     limit: Number of klines per request (default 1000, max 1000)
+///     category: Product type: "linear", "inverse", or "spot" (default "linear")
+///     api_key: Bybit API key (optional for public endpoint)
+///     secret: Bybit API secret (optional for public endpoint)
+///     recv_window: Validity window of a signed request in milliseconds (default 5000)
+///     max_retries: Maximum number of retries on a 429, a 5xx or a network error (default 5)
+///     base_backoff_ms: Wait before the first retry in milliseconds, doubled on each retry (default 50)
+///     max_backoff_ms: Upper bound of the wait between retries in milliseconds (default 10000)
+///     page_delay_ms: Delay between page requests in milliseconds when Bybit sends no rate-limit
+///                    headers to pace them by. 0 removes it, which risks 429s on standard
+///                    accounts (default 50)
+///     base_url: Base URL for Bybit API (default "https://api.bybit.com")
+///     testnet: Use the Bybit testnet at "https://api-testnet.bybit.com" unless a custom base_url is
+///              given (default False)
+///     proxy: Proxy URL to send requests through, with an http, https, socks5 or socks5h scheme
+///            (default None)
+///     compress: Accept gzip and brotli compressed responses (default True)
+///     config: FetchConfig supplying the settings above that are not passed explicitly, and the
+///             others it holds, such as timeout_ms and user_agent (default None)
+///
+/// Returns:
+///     ndarray with dtype [('start_time', 'i8'), ('open', 'f8'), ('high', 'f8'), ('low', 'f8'),
+///     ('close', 'f8'), ('volume', 'f8'), ('turnover', 'f8')], sorted by start_time in ascending
+///     order
+///
+/// Raises:
+///     RuntimeError: If the interval is invalid, the API request fails or rate limit is exceeded
+#[allow(clippy::too_many_arguments)]
+#[pyfunction]
+#[pyo3(
+    text_signature = "(symbol, interval, start_time, end_time, *, limit=1000, category='linear', api_key='', secret='', recv_window=5000, max_retries=5, base_backoff_ms=50, max_backoff_ms=10000, page_delay_ms=50, base_url='https://api.bybit.com', testnet=False, proxy=None, compress=True, config=None)"
+)]
+pub fn fetch_klines_numpy<'py>(
+    py: Python<'py>,
+    symbol: String,
+    interval: String,
+    start_time: i64,
+    end_time: i64,
+    limit: Option<i32>,
+    category: Option<String>,
+    api_key: Option<String>,
+    secret: Option<String>,
+    recv_window: Option<u64>,
+    max_retries: Option<u32>,
+    base_backoff_ms: Option<u64>,
+    max_backoff_ms: Option<u64>,
+    page_delay_ms: Option<u64>,
+    base_url: Option<String>,
+    testnet: Option<bool>,
+    proxy: Option<String>,
+    compress: Option<bool>,
+    config: Option<FetchConfig>,
+) -> PyResult<Bound<'py, PyArray1<KlineRecord>>> {
+    let mut config = config.unwrap_or_default();
+    override_config!(
+        config,
+        [
+            limit,
+            category,
+            api_key,
+            secret,
+            recv_window,
+            max_retries,
+            base_backoff_ms,
+            max_backoff_ms,
+            page_delay_ms,
+            base_url,
+            testnet,
+            compress
+        ],
+        [proxy]
+    );
+    let klines = fetch_kline_rows(py, symbol, interval, start_time, end_time, &config)?;
+
+    let records = klines.iter().map(KlineRow::to_record).collect::<Vec<_>>();
+
+    Ok(PyArray1::from_vec(py, records))
+}
+
+/// Fetch the funding rate history of a Bybit perpetual contract between two timestamps.
+///
+/// Args:
+///     symbol: Trading symbol (e.g., "BTCUSDT")
+///     start_time: Start timestamp in milliseconds
+///     end_time: End timestamp in milliseconds
+///     category: Product type: "linear" or "inverse" (default "linear")
+///     api_key: Bybit API key (optional for public endpoint)
+///     secret: Bybit API secret (optional for public endpoint)
+///     recv_window: Validity window of a signed request in milliseconds (default 5000)
+///     max_retries: Maximum number of retries on a 429, a 5xx or a network error (default 5)
+///     base_backoff_ms: Wait before the first retry in milliseconds, doubled on each retry (default 50)
+///     max_backoff_ms: Upper bound of the wait between retries in milliseconds (default 10000)
+///     page_delay_ms: Delay between page requests in milliseconds when Bybit sends no rate-limit
+///                    headers to pace them by. 0 removes it, which risks 429s on standard
+///                    accounts (default 50)
+///     base_url: Base URL for Bybit API (default "https://api.bybit.com")
+///     testnet: Use the Bybit testnet at "https://api-testnet.bybit.com" unless a custom base_url is
+///              given (default False)
+///     proxy: Proxy URL to send requests through, with an http, https, socks5 or socks5h scheme
+///            (default None)
+///     compress: Accept gzip and brotli compressed responses (default True)
+///     config: FetchConfig supplying the settings above that are not passed explicitly, and the
+///             others it holds, such as timeout_ms and user_agent (default None)
+///
+/// Returns:
+///     List of dicts with keys: timestamp, symbol, funding_rate, sorted by timestamp in ascending
+///     order, with timestamps in milliseconds
+///
+/// Raises:
+///     RuntimeError: If the category is invalid, the API request fails or rate limit is exceeded
+#[allow(clippy::too_many_arguments)]
+#[pyfunction]
+#[pyo3(
+    text_signature = "(symbol, start_time, end_time, *, category='linear', api_key='', secret='', recv_window=5000, max_retries=5, base_backoff_ms=50, max_backoff_ms=10000, page_delay_ms=50, base_url='https://api.bybit.com', testnet=False, proxy=None, compress=True, config=None)"
+)]
+pub fn fetch_funding(
+    py: Python,
+    symbol: String,
+    start_time: i64,
+    end_time: i64,
+    category: Option<String>,
+    api_key: Option<String>,
+    secret: Option<String>,
+    recv_window: Option<u64>,
+    max_retries: Option<u32>,
+    base_backoff_ms: Option<u64>,
+    max_backoff_ms: Option<u64>,
+    page_delay_ms: Option<u64>,
+    base_url: Option<String>,
+    testnet: Option<bool>,
+    proxy: Option<String>,
+    compress: Option<bool>,
+    config: Option<FetchConfig>,
+) -> PyResult<PyObject> {
+    let mut config = config.unwrap_or_default();
+    override_config!(
+        config,
+        [
+            category,
+            api_key,
+            secret,
+            recv_window,
+            max_retries,
+            base_backoff_ms,
+            max_backoff_ms,
+            page_delay_ms,
+            base_url,
+            testnet,
+            compress
+        ],
+        [proxy]
+    );
+    let fetcher = BybitHistoryFetcher::from_config(&config)?;
+
+    let rt = runtime()?;
+
+    let funding = py
+        .allow_threads(|| {
+            rt.block_on(fetcher.fetch_funding(&config.category, &symbol, start_time, end_time))
+        })
+        .map_err(PyErr::from)?;
+
+    let result = funding
+        .iter()
+        .map(|f| f.to_dict(py))
+        .collect::<PyResult<Vec<_>>>()?;
+
+    Ok(PyList::new(py, result)?.into())
+}
+
+/// A `(timestamps, values)` pair of NumPy arrays.
+type SeriesArrays<'py> = (Bound<'py, PyArray1<i64>>, Bound<'py, PyArray1<f64>>);
+
+fn to_series_arrays(py: Python<'_>, series: Vec<(i64, f64)>) -> SeriesArrays<'_> {
+    let (timestamps, values): (Vec<_>, Vec<_>) = series.into_iter().unzip();
+    (
+        PyArray1::from_vec(py, timestamps),
+        PyArray1::from_vec(py, values),
+    )
+}
+
+/// Fetch the open interest history of a Bybit perpetual or futures contract between two timestamps.
+///
+/// Args:
+///     symbol: Trading symbol (e.g., "BTCUSDT")
+///     interval_time: Sampling interval: "5min", "15min", "30min", "1h", "4h", or "1d"
+///     start_time: Start timestamp in milliseconds
+///     end_time: End timestamp in milliseconds
+///     category: Product type: "linear" or "inverse" (default "linear")
+///     api_key: Bybit API key (optional for public endpoint)
+///     secret: Bybit API secret (optional for public endpoint)
+///     recv_window: Validity window of a signed request in milliseconds (default 5000)
+///     max_retries: Maximum number of retries on a 429, a 5xx or a network error (default 5)
+///     base_backoff_ms: Wait before the first retry in milliseconds, doubled on each retry (default 50)
+///     max_backoff_ms: Upper bound of the wait between retries in milliseconds (default 10000)
+///     page_delay_ms: Delay between page requests in milliseconds when Bybit sends no rate-limit
+///                    headers to pace them by. 0 removes it, which risks 429s on standard
+///                    accounts (default 50)
+///     base_url: Base URL for Bybit API (default "https://api.bybit.com")
+///     testnet: Use the Bybit testnet at "https://api-testnet.bybit.com" unless a custom base_url is
+///              given (default False)
+///     proxy: Proxy URL to send requests through, with an http, https, socks5 or socks5h scheme
+///            (default None)
+///     compress: Accept gzip and brotli compressed responses (default True)
+///     config: FetchConfig supplying the settings above that are not passed explicitly, and the
+///             others it holds, such as timeout_ms and user_agent (default None)
+///
+/// Returns:
+///     Tuple of (timestamps, open_interest) ndarrays of dtype int64 and float64, sorted by timestamp
+///     in ascending order, with timestamps in milliseconds
+///
+/// Raises:
+///     RuntimeError: If the interval_time or category is invalid, the API request fails or rate limit is
+///                   exceeded
+#[allow(clippy::too_many_arguments)]
+#[pyfunction]
+#[pyo3(
+    text_signature = "(symbol, interval_time, start_time, end_time, *, category='linear', api_key='', secret='', recv_window=5000, max_retries=5, base_backoff_ms=50, max_backoff_ms=10000, page_delay_ms=50, base_url='https://api.bybit.com', testnet=False, proxy=None, compress=True, config=None)"
+)]
+pub fn fetch_open_interest<'py>(
+    py: Python<'py>,
+    symbol: String,
+    interval_time: String,
+    start_time: i64,
+    end_time: i64,
+    category: Option<String>,
+    api_key: Option<String>,
+    secret: Option<String>,
+    recv_window: Option<u64>,
+    max_retries: Option<u32>,
+    base_backoff_ms: Option<u64>,
+    max_backoff_ms: Option<u64>,
+    page_delay_ms: Option<u64>,
+    base_url: Option<String>,
+    testnet: Option<bool>,
+    proxy: Option<String>,
+    compress: Option<bool>,
+    config: Option<FetchConfig>,
+) -> PyResult<SeriesArrays<'py>> {
+    let mut config = config.unwrap_or_default();
+    override_config!(
+        config,
+        [
+            category,
+            api_key,
+            secret,
+            recv_window,
+            max_retries,
+            base_backoff_ms,
+            max_backoff_ms,
+            page_delay_ms,
+            base_url,
+            testnet,
+            compress
+        ],
+        [proxy]
+    );
+    let fetcher = BybitHistoryFetcher::from_config(&config)?;
+
+    let rt = runtime()?;
+
+    let series = py
+        .allow_threads(|| {
+            rt.block_on(fetcher.fetch_open_interest(
+                &config.category,
+                &symbol,
+                &interval_time,
+                start_time,
+                end_time,
+            ))
+        })
+        .map_err(PyErr::from)?;
+
+    Ok(to_series_arrays(py, series))
+}
+
+/// Fetch the instruments listed in a Bybit product category.
+///
+/// Use it to enumerate the tradeable symbols before fetching their trades; the tick and lot sizes
+/// go directly into the backtest asset configuration.
+///
+/// Args:
+///     category: Product type: "linear", "inverse", "spot", or "option" (default "linear")
+///     api_key: Bybit API key (optional for public endpoint)
+///     secret: Bybit API secret (optional for public endpoint)
+///     recv_window: Validity window of a signed request in milliseconds (default 5000)
+///     max_retries: Maximum number of retries on a 429, a 5xx or a network error (default 5)
+///     base_backoff_ms: Wait before the first retry in milliseconds, doubled on each retry (default 50)
+///     max_backoff_ms: Upper bound of the wait between retries in milliseconds (default 10000)
+///     page_delay_ms: Delay between page requests in milliseconds when Bybit sends no rate-limit
+///                    headers to pace them by. 0 removes it, which risks 429s on standard
+///                    accounts (default 50)
+///     base_url: Base URL for Bybit API (default "https://api.bybit.com")
+///     testnet: Use the Bybit testnet at "https://api-testnet.bybit.com" unless a custom base_url is
+///              given (default False)
+///     proxy: Proxy URL to send requests through, with an http, https, socks5 or socks5h scheme
+///            (default None)
+///     compress: Accept gzip and brotli compressed responses (default True)
+///     config: FetchConfig supplying the settings above that are not passed explicitly, and the
+///             others it holds, such as timeout_ms and user_agent (default None)
+///
+/// Returns:
+///     List of dicts with keys: symbol, base_coin, quote_coin, tick_size, lot_size, status. lot_size
+///     is the quantity step: qtyStep for derivatives and basePrecision for spot
+///
+/// Raises:
+///     RuntimeError: If the category is invalid, the API request fails or rate limit is exceeded
+#[allow(clippy::too_many_arguments)]
+#[pyfunction]
+#[pyo3(
+    text_signature = "(category='linear', *, api_key='', secret='', recv_window=5000, max_retries=5, base_backoff_ms=50, max_backoff_ms=10000, page_delay_ms=50, base_url='https://api.bybit.com', testnet=False, proxy=None, compress=True, config=None)"
+)]
+pub fn fetch_instruments(
+    py: Python,
+    category: Option<String>,
+    api_key: Option<String>,
+    secret: Option<String>,
+    recv_window: Option<u64>,
+    max_retries: Option<u32>,
+    base_backoff_ms: Option<u64>,
+    max_backoff_ms: Option<u64>,
+    page_delay_ms: Option<u64>,
+    base_url: Option<String>,
+    testnet: Option<bool>,
+    proxy: Option<String>,
+    compress: Option<bool>,
+    config: Option<FetchConfig>,
+) -> PyResult<PyObject> {
+    let mut config = config.unwrap_or_default();
+    override_config!(
+        config,
+        [
+            category,
+            api_key,
+            secret,
+            recv_window,
+            max_retries,
+            base_backoff_ms,
+            max_backoff_ms,
+            page_delay_ms,
+            base_url,
+            testnet,
+            compress
+        ],
+        [proxy]
+    );
+    let fetcher = BybitHistoryFetcher::from_config(&config)?;
+
+    let rt = runtime()?;
+
+    let instruments = py
+        .allow_threads(|| rt.block_on(fetcher.fetch_instruments(&config.category)))
+        .map_err(PyErr::from)?;
+
+    let result = instruments
+        .iter()
+        .map(|instrument| instrument.to_dict(py))
+        .collect::<PyResult<Vec<_>>>()?;
+    Ok(PyList::new(py, result)?.into())
+}
+
+/// Fetch the history of the ratio of Bybit accounts holding long positions to accounts holding
+/// short positions between two timestamps.
+///
+/// Args:
+///     symbol: Trading symbol (e.g., "BTCUSDT")
+///     period: Sampling interval: "5min", "15min", "30min", "1h", "4h", or "1d"
+///     start_time: Start timestamp in milliseconds
+///     end_time: End timestamp in milliseconds
+///     category: Product type: "linear" or "inverse" (default "linear")
+///     api_key: Bybit API key (optional for public endpoint)
+///     secret: Bybit API secret (optional for public endpoint)
+///     recv_window: Validity window of a signed request in milliseconds (default 5000)
+///     max_retries: Maximum number of retries on a 429, a 5xx or a network error (default 5)
+///     base_backoff_ms: Wait before the first retry in milliseconds, doubled on each retry (default 50)
+///     max_backoff_ms: Upper bound of the wait between retries in milliseconds (default 10000)
+///     page_delay_ms: Delay between page requests in milliseconds when Bybit sends no rate-limit
+///                    headers to pace them by. 0 removes it, which risks 429s on standard
+///                    accounts (default 50)
+///     base_url: Base URL for Bybit API (default "https://api.bybit.com")
+///     testnet: Use the Bybit testnet at "https://api-testnet.bybit.com" unless a custom base_url is
+///              given (default False)
+///     proxy: Proxy URL to send requests through, with an http, https, socks5 or socks5h scheme
+///            (default None)
+///     compress: Accept gzip and brotli compressed responses (default True)
+///     config: FetchConfig supplying the settings above that are not passed explicitly, and the
+///             others it holds, such as timeout_ms and user_agent (default None)
+///
+/// Returns:
+///     Tuple of (timestamps, ratio) ndarrays of dtype int64 and float64, sorted by timestamp
+///     in ascending order, with timestamps in milliseconds
+///
+/// Raises:
+///     RuntimeError: If the period or category is invalid, the API request fails or rate limit is
+///                   exceeded
+#[allow(clippy::too_many_arguments)]
+#[pyfunction]
+#[pyo3(
+    text_signature = "(symbol, period, start_time, end_time, *, category='linear', api_key='', secret='', recv_window=5000, max_retries=5, base_backoff_ms=50, max_backoff_ms=10000, page_delay_ms=50, base_url='https://api.bybit.com', testnet=False, proxy=None, compress=True, config=None)"
+)]
+pub fn fetch_long_short_ratio<'py>(
+    py: Python<'py>,
+    symbol: String,
+    period: String,
+    start_time: i64,
+    end_time: i64,
+    category: Option<String>,
+    api_key: Option<String>,
+    secret: Option<String>,
+    recv_window: Option<u64>,
+    max_retries: Option<u32>,
+    base_backoff_ms: Option<u64>,
+    max_backoff_ms: Option<u64>,
+    page_delay_ms: Option<u64>,
+    base_url: Option<String>,
+    testnet: Option<bool>,
+    proxy: Option<String>,
+    compress: Option<bool>,
+    config: Option<FetchConfig>,
+) -> PyResult<SeriesArrays<'py>> {
+    let mut config = config.unwrap_or_default();
+    override_config!(
+        config,
+        [
+            category,
+            api_key,
+            secret,
+            recv_window,
+            max_retries,
+            base_backoff_ms,
+            max_backoff_ms,
+            page_delay_ms,
+            base_url,
+            testnet,
+            compress
+        ],
+        [proxy]
+    );
+    let fetcher = BybitHistoryFetcher::from_config(&config)?;
+
+    let rt = runtime()?;
+
+    let series = py
+        .allow_threads(|| {
+            rt.block_on(fetcher.fetch_long_short_ratio(
+                &config.category,
+                &symbol,
+                &period,
+                start_time,
+                end_time,
+            ))
+        })
+        .map_err(PyErr::from)?;
+
+    Ok(to_series_arrays(py, series))
+}
+
+/// Fetch an L2 orderbook snapshot of a Bybit symbol.
+///
+/// Args:
+///     symbol: Trading symbol (e.g., "BTCUSDT")
+///     depth_limit: Number of levels per side: up to 500 for linear and inverse, 200 for spot, and
+///                  25 for option. Larger values are clamped with a warning (default 50)
+///     category: Product type: "linear", "inverse", "spot", or "option" (default "linear")
+///     api_key: Bybit API key (optional for public endpoint)
+///     secret: Bybit API secret (optional for public endpoint)
+///     recv_window: Validity window of a signed request in milliseconds (default 5000)
+///     max_retries: Maximum number of retries on a 429, a 5xx or a network error (default 5)
+///     base_backoff_ms: Wait before the first retry in milliseconds, doubled on each retry (default 50)
+///     max_backoff_ms: Upper bound of the wait between retries in milliseconds (default 10000)
+///     base_url: Base URL for Bybit API (default "https://api.bybit.com")
+///     testnet: Use the Bybit testnet at "https://api-testnet.bybit.com" unless a custom base_url is
+///              given (default False)
+///     proxy: Proxy URL to send requests through, with an http, https, socks5 or socks5h scheme
+///            (default None)
+///     compress: Accept gzip and brotli compressed responses (default True)
+///
+/// Returns:
+///     Dict with keys: symbol, timestamp (milliseconds), update_id, bids and asks as lists of
+///     (price, size) tuples, and bid_px, bid_qty, ask_px, ask_qty as float64 ndarrays, all ordered
+///     from the best price
+///
+/// Raises:
+///     RuntimeError: If depth_limit is 0, the API request fails or rate limit is exceeded
+#[allow(clippy::too_many_arguments)]
+#[pyfunction]
+#[pyo3(
+    text_signature = "(symbol, depth_limit=50, *, category='linear', api_key='', secret='', recv_window=5000, max_retries=5, base_backoff_ms=50, max_backoff_ms=10000, base_url='https://api.bybit.com', testnet=False, proxy=None, compress=True)"
+)]
+pub fn fetch_orderbook(
+    py: Python,
+    symbol: String,
+    depth_limit: Option<usize>,
+    category: Option<String>,
+    api_key: Option<String>,
+    secret: Option<String>,
+    recv_window: Option<u64>,
+    max_retries: Option<u32>,
+    base_backoff_ms: Option<u64>,
+    max_backoff_ms: Option<u64>,
+    base_url: Option<String>,
+    testnet: Option<bool>,
+    proxy: Option<String>,
+    compress: Option<bool>,
+) -> PyResult<PyObject> {
+    let mut depth_limit = depth_limit.unwrap_or(50);
+    let category = category.unwrap_or_else(|| "linear".to_string());
+    let max_depth = max_orderbook_depth(&category);
+    if depth_limit > max_depth {
+        let message = format!(
+            "depth_limit {} exceeds the maximum of {} for {}, clamping to {}",
+            depth_limit, max_depth, category, max_depth
+        );
+        PyErr::warn(
+            py,
+            &py.get_type::<PyUserWarning>(),
+            &CString::new(message)?,
+            1,
+        )?;
+        depth_limit = max_depth;
+    }
+    let api_key = api_key.unwrap_or_default();
+    let secret = secret.unwrap_or_default();
+    let base_url = resolve_base_url(base_url, testnet);
+
+    let fetcher = BybitHistoryFetcher::new(base_url, api_key, secret)
+        .with_recv_window(recv_window.unwrap_or(DEFAULT_RECV_WINDOW))
+        .with_retry(RetryConfig::new(
+            max_retries,
+            base_backoff_ms,
+            max_backoff_ms,
+        ))
+        .with_proxy(proxy.as_deref())?
+        .with_compression(compress.unwrap_or(true))?;
+
+    let rt = runtime()?;
+
+    let snapshot = py
+        .allow_threads(|| rt.block_on(fetcher.fetch_orderbook(&category, &symbol, depth_limit)))
+        .map_err(PyErr::from)?;
+
+    let (bid_px, bid_qty): (Vec<f64>, Vec<f64>) = snapshot.bids.iter().copied().unzip();
+    let (ask_px, ask_qty): (Vec<f64>, Vec<f64>) = snapshot.asks.iter().copied().unzip();
+
+    let dict = pyo3::types::PyDict::new(py);
+    dict.set_item("symbol", &snapshot.symbol)?;
+    dict.set_item("timestamp", snapshot.timestamp)?;
+    dict.set_item("update_id", snapshot.update_id)?;
+    dict.set_item("bids", &snapshot.bids)?;
+    dict.set_item("asks", &snapshot.asks)?;
+    dict.set_item("bid_px", PyArray1::from_vec(py, bid_px))?;
+    dict.set_item("bid_qty", PyArray1::from_vec(py, bid_qty))?;
+    dict.set_item("ask_px", PyArray1::from_vec(py, ask_px))?;
+    dict.set_item("ask_qty", PyArray1::from_vec(py, ask_qty))?;
+    Ok(dict.into())
+}
+
+/// Fetch the most recent trades of a Bybit symbol.
+///
+/// No time range is sent, so Bybit starts from the latest trade; pages are followed backwards
+/// through the cursor until n trades are collected or there are no older trades.
+///
+/// Args:
+///     symbol: Trading symbol (e.g., "BTCUSDT")
+///     n: Number of trades to fetch
+///     category: Product type: "linear", "inverse", "spot", or "option" (default "linear")
+///     api_key: Bybit API key (optional for public endpoint)
+///     secret: Bybit API secret (optional for public endpoint)
+///     recv_window: Validity window of a signed request in milliseconds (default 5000)
+///     max_retries: Maximum number of retries on a 429, a 5xx or a network error (default 5)
+///     base_backoff_ms: Wait before the first retry in milliseconds, doubled on each retry (default 50)
+///     max_backoff_ms: Upper bound of the wait between retries in milliseconds (default 10000)
+///     page_delay_ms: Delay between page requests in milliseconds when Bybit sends no rate-limit
+///                    headers to pace them by. 0 removes it, which risks 429s on standard
+///                    accounts (default 50)
+///     base_url: Base URL for Bybit API (default "https://api.bybit.com")
+///     testnet: Use the Bybit testnet at "https://api-testnet.bybit.com" unless a custom base_url is
+///              given (default False)
+///     proxy: Proxy URL to send requests through, with an http, https, socks5 or socks5h scheme
+///            (default None)
+///     compress: Accept gzip and brotli compressed responses (default True)
+///     side_as_int: Return side as 1 for Buy and -1 for Sell instead of the string (default False)
+///     config: FetchConfig supplying the settings above that are not passed explicitly, and the
+///             others it holds, such as timeout_ms and user_agent (default None)
+///
+/// Returns:
+///     List of up to n trade dicts in the same format as fetch_trades, newest first
+///
+/// Raises:
+///     RuntimeError: If the API request fails or rate limit is exceeded
+#[allow(clippy::too_many_arguments)]
+#[pyfunction]
+#[pyo3(
+    text_signature = "(symbol, n, *, category='linear', api_key='', secret='', recv_window=5000, max_retries=5, base_backoff_ms=50, max_backoff_ms=10000, page_delay_ms=50, base_url='https://api.bybit.com', testnet=False, proxy=None, compress=True, side_as_int=False, config=None)"
+)]
+pub fn fetch_recent_trades(
+    py: Python,
+    symbol: String,
+    n: usize,
+    category: Option<String>,
+    api_key: Option<String>,
+    secret: Option<String>,
+    recv_window: Option<u64>,
+    max_retries: Option<u32>,
+    base_backoff_ms: Option<u64>,
+    max_backoff_ms: Option<u64>,
+    page_delay_ms: Option<u64>,
+    base_url: Option<String>,
+    testnet: Option<bool>,
+    proxy: Option<String>,
+    compress: Option<bool>,
+    side_as_int: Option<bool>,
+    config: Option<FetchConfig>,
+) -> PyResult<PyObject> {
+    let mut config = config.unwrap_or_default();
+    override_config!(
+        config,
+        [
+            category,
+            api_key,
+            secret,
+            recv_window,
+            max_retries,
+            base_backoff_ms,
+            max_backoff_ms,
+            page_delay_ms,
+            base_url,
+            testnet,
+            compress,
+            side_as_int
+        ],
+        [proxy]
+    );
+    let fetcher = BybitHistoryFetcher::from_config(&config)?;
+
+    let rt = runtime()?;
+
+    let (trades, stats) = py
+        .allow_threads(|| rt.block_on(fetcher.fetch_recent_trades(&config.category, &symbol, n)))
+        .map_err(PyErr::from)?;
+    record_stats(stats);
+
+    let result = trades
+        .iter()
+        .map(|t| t.to_dict(py, config.side_as_int, false))
+        .collect::<PyResult<Vec<_>>>()?;
+    Ok(PyList::new(py, result)?.into())
+}
+
+/// Check a fetched trade series for gaps and implausible values.
+///
+/// Exchanges occasionally drop history windows without reporting an error, so this flags any
+/// stretch longer than max_gap_ms without trades, along with timestamps out of order, zero or
+/// negative sizes and prices, and sides other than "Buy" and "Sell".
+///
+/// Args:
+///     trades: List of trade dicts as returned by fetch_trades, in either time order, with side as
+///             a string or as an integer from side_as_int
+///     max_gap_ms: Longest interval without trades, in milliseconds, that is not reported
+///                 (default 60000)
+///
+/// Returns:
+///     TradeReport with gaps as (start, end) timestamp pairs and non_monotonic, invalid_values
+///     and invalid_sides as indices into trades, plus is_clean()
+#[pyfunction]
+#[pyo3(text_signature = "(trades, max_gap_ms=60000)")]
+pub fn validate_trades(
+    trades: Vec<Bound<'_, pyo3::types::PyDict>>,
+    max_gap_ms: Option<i64>,
+) -> PyResult<TradeReport> {
+    fn field<'py, T: FromPyObject<'py>>(
+        trade: &Bound<'py, pyo3::types::PyDict>,
+        key: &str,
+    ) -> PyResult<T> {
+        trade
+            .get_item(key)?
+            .ok_or_else(|| BybitError::InvalidArg(format!("trade without {}", key)))?
+            .extract()
+    }
+
+    let rows = trades
+        .iter()
+        .map(|trade| {
+            Ok(TradeRow {
+                exec_id: String::new(),
+                timestamp: field(trade, "timestamp")?,
+                symbol: String::new(),
+                side: match field::<i8>(trade, "side") {
+                    Ok(1) => "Buy".to_string(),
+                    Ok(-1) => "Sell".to_string(),
+                    Ok(_) => String::new(),
+                    Err(_) => field(trade, "side")?,
+                },
+                size: field(trade, "size")?,
+                price: field(trade, "price")?,
+                is_block_trade: false,
+                mark_price: None,
+                index_price: None,
+                mark_iv: None,
+                iv: None,
+                raw_price: None,
+                raw_size: None,
+            })
+        })
+        .collect::<PyResult<Vec<_>>>()?;
+    Ok(validate_rows(
+        &rows,
+        max_gap_ms.unwrap_or(DEFAULT_MAX_GAP_MS),
+    ))
+}
+
+/// A reusable Bybit client. Unlike the free functions, which build a new HTTP client per call,
+/// it keeps one connection pool across fetches, so fetching many symbols in a row reuses
+/// connections.
+///
+/// Args:
+///     api_key: Bybit API key (optional for public endpoint)
+///     secret: Bybit API secret (optional for public endpoint)
+///     recv_window: Validity window of a signed request in milliseconds (default 5000)
+///     max_retries: Maximum number of retries on a 429, a 5xx or a network error (default 5)
+///     base_backoff_ms: Wait before the first retry in milliseconds, doubled on each retry (default 50)
+///     max_backoff_ms: Upper bound of the wait between retries in milliseconds (default 10000)
+///     page_delay_ms: Delay between page requests in milliseconds when Bybit sends no rate-limit
+///                    headers to pace them by. 0 removes it, which risks 429s on standard
+///                    accounts (default 50)
+///     base_url: Base URL for Bybit API (default "https://api.bybit.com")
+///     testnet: Use the Bybit testnet at "https://api-testnet.bybit.com" unless a custom base_url is
+///              given (default False)
+///     proxy: Proxy URL to send requests through, with an http, https, socks5 or socks5h scheme
+///            (default None)
+///     compress: Accept gzip and brotli compressed responses (default True)
+///     connect_timeout_ms: Timeout for establishing a connection in milliseconds (default None,
+///                         no timeout)
+///     timeout_ms: Timeout for each request in milliseconds (default 10000)
+///     tcp_keepalive_ms: Interval of TCP keepalive probes in milliseconds (default None, disabled)
+///     max_idle_connections: Maximum number of idle connections kept open (default None,
+///                           unlimited)
+///     user_agent: User-Agent header sent with every request, instead of reqwest's default
+///                 (default None)
+///     extra_headers: Dict of headers added to every request, such as those a gateway identifies
+///                    clients by. The X-BAPI-* signing headers can't be set (default None)
+///     jitter: Wait a random duration up to the backoff instead of the full backoff (default
+///             True)
+///     retry_on_5xx: Retry a 500, 502, 503 or 504 response instead of raising (default True)
+///     jitter_seed: Seed of the random jitter applied to retry waits, for reproducible runs
+///                  (default None, seeded from the OS)
+///     config: FetchConfig supplying the settings above that are not passed explicitly. Its
+///             per-fetch settings, such as category and limit, become the defaults of the fetch
+///             methods (default None)
+#[pyclass(module = "hftbacktest")]
+pub struct BybitFetcher {
+    fetcher: BybitHistoryFetcher,
+    config: FetchConfig,
+}
+
+#[pymethods]
+impl BybitFetcher {
+    #[allow(clippy::too_many_arguments)]
+    #[new]
+    #[pyo3(signature = (
+        *,
+        api_key = None,
+        secret = None,
+        recv_window = None,
+        max_retries = None,
+        base_backoff_ms = None,
+        max_backoff_ms = None,
+        page_delay_ms = None,
+        jitter = None,
+        retry_on_5xx = None,
+        base_url = None,
+        testnet = None,
+        proxy = None,
+        compress = None,
+        connect_timeout_ms = None,
+        timeout_ms = None,
+        tcp_keepalive_ms = None,
+        max_idle_connections = None,
+        user_agent = None,
+        extra_headers = None,
+        jitter_seed = None,
+        config = None,
+    ))]
+    fn new(
+        api_key: Option<String>,
+        secret: Option<String>,
+        recv_window: Option<u64>,
+        max_retries: Option<u32>,
+        base_backoff_ms: Option<u64>,
+        max_backoff_ms: Option<u64>,
+        page_delay_ms: Option<u64>,
+        jitter: Option<bool>,
+        retry_on_5xx: Option<bool>,
+        base_url: Option<String>,
+        testnet: Option<bool>,
+        proxy: Option<String>,
+        compress: Option<bool>,
+        connect_timeout_ms: Option<u64>,
+        timeout_ms: Option<u64>,
+        tcp_keepalive_ms: Option<u64>,
+        max_idle_connections: Option<usize>,
+        user_agent: Option<String>,
+        extra_headers: Option<HashMap<String, String>>,
+        jitter_seed: Option<u64>,
+        config: Option<FetchConfig>,
+    ) -> PyResult<Self> {
+        let mut config = config.unwrap_or_default();
+        override_config!(
+            config,
+            [
+                api_key,
+                secret,
+                recv_window,
+                max_retries,
+                base_backoff_ms,
+                max_backoff_ms,
+                page_delay_ms,
+                jitter,
+                retry_on_5xx,
+                base_url,
+                testnet,
+                compress,
+                timeout_ms,
+            ],
+            [
+                proxy,
+                connect_timeout_ms,
+                tcp_keepalive_ms,
+                max_idle_connections,
+                user_agent,
+                extra_headers,
+            ]
+        );
+        let mut fetcher = BybitHistoryFetcher::from_config(&config)?;
+        if let Some(seed) = jitter_seed.filter(|_| config.jitter) {
+            fetcher.retry = fetcher.retry.clone().with_jitter_seed(seed);
+        }
+        Ok(Self { fetcher, config })
+    }
+
+    /// Same as the free function fetch_trades, using this client's settings. Arguments left out
+    /// default to the client's config.
+    #[allow(clippy::too_many_arguments)]
+    #[pyo3(signature = (
+        symbol,
+        start_time,
+        end_time,
+        *,
+        limit = None,
+        category = None,
+        cursor = None,
+        progress = None,
+        strict_range = None,
+        strict = None,
+        deterministic = None,
+        partial = None,
+        include_block_trades = None,
+        min_size = None,
+        side_as_int = None,
+        include_turnover = None,
+        time_unit = None,
+    ))]
+    fn fetch_trades(
+        &self,
+        py: Python,
+        symbol: String,
+        start_time: i64,
+        end_time: i64,
+        limit: Option<i32>,
+        category: Option<String>,
+        cursor: Option<String>,
+        progress: Option<PyObject>,
+        strict_range: Option<bool>,
+        strict: Option<bool>,
+        deterministic: Option<bool>,
+        partial: Option<bool>,
+        include_block_trades: Option<bool>,
+        min_size: Option<f64>,
+        side_as_int: Option<bool>,
+        include_turnover: Option<bool>,
+        time_unit: Option<String>,
+    ) -> PyResult<PyObject> {
+        let mut config = self.config.clone();
+        override_config!(
+            config,
+            [
+                limit,
+                category,
+                strict_range,
+                strict,
+                deterministic,
+                include_block_trades,
+                side_as_int,
+                include_turnover,
+            ],
+            [min_size, time_unit]
+        );
+        let trades = fetch_trade_rows(
+            py,
+            &self.fetcher,
+            symbol,
+            start_time,
+            end_time,
+            &config,
+            cursor,
+            progress,
+            partial.unwrap_or(false),
+        )?;
+
+        let result = trades
+            .iter()
+            .map(|t| t.to_dict(py, config.side_as_int, config.include_turnover))
+            .collect::<PyResult<Vec<_>>>()?;
+
+        Ok(PyList::new(py, result)?.into())
+    }
+
+    /// Same as the free function fetch_trades_numpy, using this client's settings.
+    #[allow(clippy::too_many_arguments)]
+    #[pyo3(signature = (
+        symbol,
+        start_time,
+        end_time,
+        *,
+        limit = None,
+        category = None,
+        cursor = None,
+        progress = None,
+        strict_range = None,
+        strict = None,
+        partial = None,
+        include_block_trades = None,
+        min_size = None,
+        time_unit = None,
+    ))]
+    fn fetch_trades_numpy<'py>(
+        &self,
+        py: Python<'py>,
+        symbol: String,
+        start_time: i64,
+        end_time: i64,
+        limit: Option<i32>,
+        category: Option<String>,
+        cursor: Option<String>,
+        progress: Option<PyObject>,
+        strict_range: Option<bool>,
+        strict: Option<bool>,
+        partial: Option<bool>,
+        include_block_trades: Option<bool>,
+        min_size: Option<f64>,
+        time_unit: Option<String>,
+    ) -> PyResult<Bound<'py, PyArray1<TradeRecord>>> {
+        let mut config = self.config.clone();
+        override_config!(
+            config,
+            [limit, category, strict_range, strict, include_block_trades],
+            [min_size, time_unit]
+        );
+        let trades = fetch_trade_rows(
+            py,
+            &self.fetcher,
+            symbol,
+            start_time,
+            end_time,
+            &config,
+            cursor,
+            progress,
+            partial.unwrap_or(false),
+        )?;
+
+        let records = trades
+            .iter()
+            .map(TradeRow::to_record)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(PyArray1::from_vec(py, records))
+    }
+}
+
+/// Returns the request statistics of the last trade fetch made on the calling thread.
+///
+/// Returns:
+///     FetchStats with requests, rate_limited, retries, trades, elapsed, and request_rate(), or None
+///     if no fetch has completed yet
+#[pyfunction]
+pub fn last_fetch_stats() -> Option<FetchStats> {
+    LAST_FETCH_STATS.with(|stats| stats.borrow().clone())
+}
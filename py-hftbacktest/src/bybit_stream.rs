@@ -0,0 +1,1221 @@
+use std::{
+    collections::{HashMap, HashSet},
+    ffi::CString,
+    future::Future,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use futures_util::{SinkExt, StreamExt};
+use hftbacktest::types::{
+    BUY_EVENT, DEPTH_CLEAR_EVENT, DEPTH_EVENT, DEPTH_SNAPSHOT_EVENT, EXCH_EVENT, Event,
+    LOCAL_EVENT, SELL_EVENT,
+};
+use pyo3::{exceptions::PyUserWarning, prelude::*};
+use serde::Deserialize;
+use tokio::sync::mpsc::{UnboundedSender, unbounded_channel};
+use tokio_tungstenite::{connect_async, tungstenite::Message};
+
+use crate::bybit::{
+    BybitError, CATEGORIES, EventNpzWriter, RetryConfig, TradeCsvWriter, TradeRow, parse_levels,
+    runtime,
+};
+
+/// Bybit v5 public WebSocket hosts. The category is appended as the last path segment.
+pub const MAINNET_STREAM_URL: &str = "wss://stream.bybit.com/v5/public";
+pub const TESTNET_STREAM_URL: &str = "wss://stream-testnet.bybit.com/v5/public";
+
+/// How often a ping is sent. Bybit drops connections that stay silent for longer than 20 seconds.
+const PING_INTERVAL: Duration = Duration::from_secs(20);
+
+/// A connection that has not received anything for this long is considered dead and reconnected.
+const READ_TIMEOUT: Duration = Duration::from_secs(2 * PING_INTERVAL.as_secs());
+
+/// Wait between reconnection attempts, doubled on each consecutive failure.
+const RECONNECT_BACKOFF: RetryConfig = RetryConfig {
+    max_retries: u32::MAX,
+    base_backoff_ms: 500,
+    max_backoff_ms: 10_000,
+    jitter: None,
+    retry_on_5xx: true,
+};
+
+/// Orderbook depths Bybit publishes. Which ones are available depends on the category.
+pub const DEPTH_LEVELS: [u32; 7] = [1, 25, 50, 100, 200, 500, 1000];
+
+#[derive(Debug, Deserialize)]
+struct WsFrame {
+    topic: Option<String>,
+    #[serde(rename = "type")]
+    kind: Option<String>,
+    ts: Option<i64>,
+    op: Option<String>,
+    success: Option<bool>,
+    ret_msg: Option<String>,
+    data: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct WsTrade {
+    #[serde(rename = "i")]
+    exec_id: String,
+    #[serde(rename = "T")]
+    timestamp: i64,
+    #[serde(rename = "s")]
+    symbol: String,
+    #[serde(rename = "S")]
+    side: String,
+    #[serde(rename = "v")]
+    size: String,
+    #[serde(rename = "p")]
+    price: String,
+    #[serde(rename = "BT", default)]
+    is_block_trade: bool,
+    /// Option-specific fields.
+    #[serde(rename = "mP")]
+    mark_price: Option<String>,
+    #[serde(rename = "iP")]
+    index_price: Option<String>,
+    #[serde(rename = "mIv")]
+    mark_iv: Option<String>,
+    iv: Option<String>,
+}
+
+impl WsTrade {
+    fn into_row(self) -> Result<TradeRow, BybitError> {
+        let parse = |value: &str, name: &str| -> Result<f64, BybitError> {
+            value
+                .parse()
+                .map_err(|_| BybitError::Parse(format!("{}: {}", name, value)))
+        };
+        let parse_optional = |value: Option<String>, name: &str| {
+            value
+                .filter(|v| !v.is_empty())
+                .map(|v| parse(&v, name))
+                .transpose()
+        };
+        Ok(TradeRow {
+            size: parse(&self.size, "size")?,
+            price: parse(&self.price, "price")?,
+            mark_price: parse_optional(self.mark_price, "mark price")?,
+            index_price: parse_optional(self.index_price, "index price")?,
+            mark_iv: parse_optional(self.mark_iv, "mark iv")?,
+            iv: parse_optional(self.iv, "iv")?,
+            raw_price: Some(self.price),
+            raw_size: Some(self.size),
+            exec_id: self.exec_id,
+            timestamp: self.timestamp,
+            symbol: self.symbol,
+            side: self.side,
+            is_block_trade: self.is_block_trade,
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct WsLiquidation {
+    #[serde(rename = "T")]
+    timestamp: i64,
+    #[serde(rename = "s")]
+    symbol: String,
+    /// Side of the liquidated position: `Buy` for a long, `Sell` for a short.
+    #[serde(rename = "S")]
+    side: String,
+    #[serde(rename = "v")]
+    size: String,
+    #[serde(rename = "p")]
+    price: String,
+}
+
+impl WsLiquidation {
+    /// Converts the liquidation into a row of the trade layout. Liquidations have no execution id,
+    /// so `exec_id` is left empty.
+    fn into_row(self) -> Result<TradeRow, BybitError> {
+        let parse = |value: &str, name: &str| -> Result<f64, BybitError> {
+            value
+                .parse()
+                .map_err(|_| BybitError::Parse(format!("{}: {}", name, value)))
+        };
+        Ok(TradeRow {
+            exec_id: String::new(),
+            timestamp: self.timestamp,
+            size: parse(&self.size, "size")?,
+            price: parse(&self.price, "price")?,
+            symbol: self.symbol,
+            side: self.side,
+            is_block_trade: false,
+            mark_price: None,
+            index_price: None,
+            mark_iv: None,
+            iv: None,
+            raw_price: Some(self.price),
+            raw_size: Some(self.size),
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct WsDepth {
+    #[serde(rename = "b")]
+    bids: Vec<[String; 2]>,
+    #[serde(rename = "a")]
+    asks: Vec<[String; 2]>,
+    #[serde(rename = "u")]
+    update_id: u64,
+}
+
+/// An orderbook push. A snapshot replaces the whole book; a delta updates the listed levels, where
+/// a size of zero removes the level.
+pub struct DepthUpdate {
+    pub topic: String,
+    pub snapshot: bool,
+    /// Exchange time in milliseconds.
+    pub timestamp: i64,
+    /// When the message was read from the socket.
+    pub received: Instant,
+    pub update_id: u64,
+    pub bids: Vec<(f64, f64)>,
+    pub asks: Vec<(f64, f64)>,
+}
+
+impl DepthUpdate {
+    /// Converts the update into depth events. A snapshot is preceded by a [`DEPTH_CLEAR_EVENT`]
+    /// without a side, which clears the whole book and marks the discontinuity.
+    pub fn to_events(&self, local_ts: i64) -> Vec<Event> {
+        let exch_ts = self.timestamp * 1_000_000;
+        let event = |ev, px, qty| Event {
+            ev: EXCH_EVENT | LOCAL_EVENT | ev,
+            exch_ts,
+            local_ts,
+            px,
+            qty,
+            order_id: 0,
+            ival: 0,
+            fval: 0.0,
+        };
+        let kind = if self.snapshot {
+            DEPTH_SNAPSHOT_EVENT
+        } else {
+            DEPTH_EVENT
+        };
+        let mut events = Vec::with_capacity(self.bids.len() + self.asks.len() + 1);
+        if self.snapshot {
+            events.push(event(DEPTH_CLEAR_EVENT, 0.0, 0.0));
+        }
+        for &(px, qty) in &self.bids {
+            events.push(event(kind | BUY_EVENT, px, qty));
+        }
+        for &(px, qty) in &self.asks {
+            events.push(event(kind | SELL_EVENT, px, qty));
+        }
+        events
+    }
+}
+
+/// What a stream connection delivers to its consumer.
+pub enum StreamMessage {
+    Trades(Vec<TradeRow>),
+    /// Liquidations in the trade row layout, where `side` is the side of the liquidated position.
+    Liquidations(Vec<TradeRow>),
+    Depth(DepthUpdate),
+    /// The connection was re-established after a drop; messages sent during the outage are lost.
+    Reconnected,
+    /// An orderbook update was missed and the topic was resubscribed on the same connection. The
+    /// next update for it is a fresh snapshot.
+    Resync,
+}
+
+/// Parses a `publicTrade`, `allLiquidation` or `orderbook` push. Returns `None` for other frames.
+fn parse_frame(frame: WsFrame, received: Instant) -> Result<Option<StreamMessage>, BybitError> {
+    let (Some(topic), Some(data)) = (frame.topic, frame.data) else {
+        return Ok(None);
+    };
+    if topic.starts_with("publicTrade.") {
+        let trades: Vec<WsTrade> = serde_json::from_value(data)
+            .map_err(|e| BybitError::Parse(format!("trade message: {}", e)))?;
+        return trades
+            .into_iter()
+            .map(WsTrade::into_row)
+            .collect::<Result<Vec<_>, _>>()
+            .map(|trades| Some(StreamMessage::Trades(trades)));
+    }
+    if topic.starts_with("allLiquidation.") {
+        let liquidations: Vec<WsLiquidation> = serde_json::from_value(data)
+            .map_err(|e| BybitError::Parse(format!("liquidation message: {}", e)))?;
+        return liquidations
+            .into_iter()
+            .map(WsLiquidation::into_row)
+            .collect::<Result<Vec<_>, _>>()
+            .map(|liquidations| Some(StreamMessage::Liquidations(liquidations)));
+    }
+    if topic.starts_with("orderbook.") {
+        let depth: WsDepth = serde_json::from_value(data)
+            .map_err(|e| BybitError::Parse(format!("orderbook message: {}", e)))?;
+        return Ok(Some(StreamMessage::Depth(DepthUpdate {
+            snapshot: frame.kind.as_deref() == Some("snapshot"),
+            timestamp: frame.ts.unwrap_or_default(),
+            received,
+            update_id: depth.update_id,
+            bids: parse_levels(&depth.bids)?,
+            asks: parse_levels(&depth.asks)?,
+            topic,
+        })));
+    }
+    Ok(None)
+}
+
+/// Opens one connection to `url`, subscribes to `topics`, and forwards every trade, liquidation
+/// and orderbook push to `tx` until the connection drops. Once subscribed, sends [`StreamMessage::Reconnected`]
+/// first if `subscribed_before` is set, then sets it. A rejected subscription fails with
+/// [`BybitError::InvalidArg`]; everything else is a [`BybitError::Transport`] error.
+///
+/// Orderbook deltas must continue the update id of the previous message of their topic. On a
+/// gap, the topic is resubscribed to get a fresh snapshot, [`StreamMessage::Resync`] is sent, and
+/// deltas are dropped until the snapshot arrives.
+async fn connect(
+    url: &str,
+    topics: &[String],
+    tx: &UnboundedSender<StreamMessage>,
+    subscribed_before: &mut bool,
+) -> Result<(), BybitError> {
+    // rustls is built with both the ring and aws-lc-rs providers through other dependencies, so
+    // there is no implicit default and the TLS connector would panic without one.
+    let _ = rustls::crypto::ring::default_provider().install_default();
+    let (mut ws, _) = connect_async(url)
+        .await
+        .map_err(|e| BybitError::Transport(format!("{}: {}", url, e)))?;
+    let subscribe = serde_json::json!({"op": "subscribe", "args": topics});
+    ws.send(Message::Text(subscribe.to_string().into()))
+        .await
+        .map_err(|e| BybitError::Transport(e.to_string()))?;
+    if *subscribed_before && tx.send(StreamMessage::Reconnected).is_err() {
+        return Ok(());
+    }
+    *subscribed_before = true;
+
+    // The last update id of each orderbook topic, present once its snapshot has arrived.
+    let mut update_ids: HashMap<String, u64> = HashMap::new();
+    let mut ping_interval = tokio::time::interval(PING_INTERVAL);
+    let mut last_recv = Instant::now();
+    loop {
+        tokio::select! {
+            message = ws.next() => {
+                last_recv = Instant::now();
+                match message {
+                    Some(Ok(Message::Text(text))) => {
+                        let frame: WsFrame = serde_json::from_str(&text)
+                            .map_err(|e| BybitError::Parse(format!("{}: {}", e, text)))?;
+                        if frame.op.as_deref() == Some("subscribe") && frame.success == Some(false) {
+                            return Err(BybitError::InvalidArg(format!(
+                                "subscription rejected: {}",
+                                frame.ret_msg.unwrap_or_default()
+                            )));
+                        }
+                        let message = match parse_frame(frame, last_recv)? {
+                            Some(StreamMessage::Depth(update)) => {
+                                let last = update_ids.get(&update.topic).copied();
+                                if update.snapshot {
+                                    update_ids.insert(update.topic.clone(), update.update_id);
+                                    StreamMessage::Depth(update)
+                                } else if last.is_none() {
+                                    // Waiting for the snapshot after a resubscription.
+                                    continue;
+                                } else if last.map(|id| id + 1) == Some(update.update_id) {
+                                    update_ids.insert(update.topic.clone(), update.update_id);
+                                    StreamMessage::Depth(update)
+                                } else {
+                                    update_ids.remove(&update.topic);
+                                    for op in ["unsubscribe", "subscribe"] {
+                                        let request = serde_json::json!({"op": op, "args": [&update.topic]});
+                                        ws.send(Message::Text(request.to_string().into()))
+                                            .await
+                                            .map_err(|e| BybitError::Transport(e.to_string()))?;
+                                    }
+                                    StreamMessage::Resync
+                                }
+                            }
+                            Some(message) => message,
+                            None => continue,
+                        };
+                        if tx.send(message).is_err() {
+                            return Ok(());
+                        }
+                    }
+                    Some(Ok(Message::Ping(payload))) => {
+                        ws.send(Message::Pong(payload))
+                            .await
+                            .map_err(|e| BybitError::Transport(e.to_string()))?;
+                    }
+                    Some(Ok(Message::Close(frame))) => {
+                        return Err(BybitError::Transport(format!("closed: {:?}", frame)));
+                    }
+                    Some(Ok(_)) => {}
+                    Some(Err(e)) => return Err(BybitError::Transport(e.to_string())),
+                    None => return Err(BybitError::Transport("connection closed".to_string())),
+                }
+            }
+            _ = ping_interval.tick() => {
+                if last_recv.elapsed() > READ_TIMEOUT {
+                    return Err(BybitError::Transport(format!(
+                        "nothing received for {}s",
+                        READ_TIMEOUT.as_secs()
+                    )));
+                }
+                ws.send(Message::Text(r#"{"op":"ping"}"#.into()))
+                    .await
+                    .map_err(|e| BybitError::Transport(e.to_string()))?;
+            }
+        }
+    }
+}
+
+/// Keeps a subscription to `topics` alive, reconnecting with backoff whenever the connection
+/// drops and announcing each reconnection with [`StreamMessage::Reconnected`]. Only returns on a
+/// rejected subscription or once `tx` is closed.
+async fn keep_connection(
+    url: &str,
+    topics: &[String],
+    tx: UnboundedSender<StreamMessage>,
+) -> Result<(), BybitError> {
+    let mut failures = 0;
+    let mut subscribed_before = false;
+    loop {
+        let connected = Instant::now();
+        match connect(url, topics, &tx, &mut subscribed_before).await {
+            Ok(()) => return Ok(()),
+            Err(e @ BybitError::InvalidArg(_)) => return Err(e),
+            Err(_) => {
+                // A connection that stayed up for a while resets the backoff.
+                if connected.elapsed() > PING_INTERVAL {
+                    failures = 0;
+                }
+                failures += 1;
+                tokio::time::sleep(RECONNECT_BACKOFF.backoff(failures)).await;
+            },
+        }
+    }
+}
+
+/// Outcome of a recording session.
+#[pyclass(module = "hftbacktest", get_all)]
+#[derive(Debug, Clone, Default)]
+pub struct RecordSummary {
+    /// Number of rows written.
+    pub rows: usize,
+    /// Number of times the connection was re-established.
+    pub reconnects: usize,
+    /// Number of times the orderbook was resubscribed after a missed update.
+    pub resyncs: usize,
+    /// Number of re-sent liquidations that were dropped.
+    pub duplicates: usize,
+    /// `(last, first)` timestamps around each outage, in milliseconds: the last row received
+    /// before the connection dropped or an update was missed and the first one received after.
+    pub gaps: Vec<(i64, i64)>,
+}
+
+#[pymethods]
+impl RecordSummary {
+    fn __repr__(&self) -> String {
+        format!(
+            "RecordSummary(rows={}, reconnects={}, resyncs={}, duplicates={}, gaps={:?})",
+            self.rows, self.reconnects, self.resyncs, self.duplicates, self.gaps
+        )
+    }
+}
+
+/// Streams the trades or liquidations of `topics` into `writer` until `duration` elapses or `stop`
+/// completes, writing buffered rows every `flush_interval`. `on_gap` is called with the last and
+/// first row timestamps around each reconnection as soon as the first row after it arrives.
+///
+/// Bybit may re-send recent liquidations after a reconnection, so a liquidation with the same
+/// timestamp, price and size as one already recorded is dropped and counted in
+/// [`RecordSummary::duplicates`].
+pub async fn record_trade_stream<G, S>(
+    url: &str,
+    topics: &[String],
+    writer: &mut TradeCsvWriter,
+    duration: Duration,
+    flush_interval: Duration,
+    mut on_gap: G,
+    stop: S,
+) -> Result<RecordSummary, BybitError>
+where
+    G: FnMut(i64, i64) -> Result<(), BybitError>,
+    S: Future<Output = ()>,
+{
+    let (tx, mut rx) = unbounded_channel();
+    let stream = keep_connection(url, topics, tx);
+    let deadline = tokio::time::sleep(duration);
+    let mut flush_interval = tokio::time::interval(flush_interval);
+    tokio::pin!(stream, deadline, stop);
+
+    let mut summary = RecordSummary::default();
+    let mut buffer = Vec::new();
+    let mut liquidations_seen = HashSet::new();
+    let mut last_timestamp = None;
+    let mut outage_start = None;
+    loop {
+        tokio::select! {
+            result = &mut stream => {
+                result?;
+                break;
+            }
+            Some(message) = rx.recv() => {
+                let trades = match message {
+                    StreamMessage::Trades(trades) => trades,
+                    StreamMessage::Liquidations(mut liquidations) => {
+                        let received = liquidations.len();
+                        liquidations.retain(|l| {
+                            liquidations_seen.insert((l.timestamp, l.price.to_bits(), l.size.to_bits()))
+                        });
+                        summary.duplicates += received - liquidations.len();
+                        liquidations
+                    }
+                    StreamMessage::Reconnected => {
+                        summary.reconnects += 1;
+                        // Keep the start of the first outage if several reconnects happen in a row.
+                        outage_start = outage_start.or(last_timestamp);
+                        continue;
+                    }
+                    StreamMessage::Depth(_) | StreamMessage::Resync => continue,
+                };
+                if let (Some(last), Some(first)) =
+                    (outage_start, trades.iter().map(|t| t.timestamp).min())
+                {
+                    outage_start = None;
+                    summary.gaps.push((last, first));
+                    on_gap(last, first)?;
+                }
+                last_timestamp = trades.iter().map(|t| t.timestamp).max().or(last_timestamp);
+                buffer.extend(trades);
+            }
+            _ = flush_interval.tick() => {
+                writer.write(&buffer)?;
+                summary.rows += buffer.len();
+                buffer.clear();
+            }
+            _ = &mut deadline => break,
+            _ = &mut stop => break,
+        }
+    }
+    writer.write(&buffer)?;
+    summary.rows += buffer.len();
+    Ok(summary)
+}
+
+/// Maps monotonic [`Instant`]s to wall-clock nanoseconds since the epoch, so that receive times
+/// stay ordered even if the system clock is adjusted while recording.
+struct RecvClock {
+    wall_ns: i64,
+    start: Instant,
+}
+
+impl RecvClock {
+    fn new() -> Self {
+        let start = Instant::now();
+        let wall_ns = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos() as i64)
+            .unwrap_or_default();
+        Self { wall_ns, start }
+    }
+
+    fn timestamp(&self, instant: Instant) -> i64 {
+        self.wall_ns + instant.saturating_duration_since(self.start).as_nanos() as i64
+    }
+}
+
+/// Streams the orderbook of `topic` into `writer` as depth events until `duration` elapses or
+/// `stop` completes. The exchange timestamp of each event comes from the message and the local
+/// timestamp from when it was received. `on_gap` is called with the last and first update
+/// timestamps around each reconnection or resynchronization.
+pub async fn record_depth_stream<G, S>(
+    url: &str,
+    topic: &str,
+    writer: &mut EventNpzWriter,
+    duration: Duration,
+    mut on_gap: G,
+    stop: S,
+) -> Result<RecordSummary, BybitError>
+where
+    G: FnMut(i64, i64) -> Result<(), BybitError>,
+    S: Future<Output = ()>,
+{
+    let clock = RecvClock::new();
+    let topics = [topic.to_string()];
+    let (tx, mut rx) = unbounded_channel();
+    let stream = keep_connection(url, &topics, tx);
+    let deadline = tokio::time::sleep(duration);
+    tokio::pin!(stream, deadline, stop);
+
+    let mut summary = RecordSummary::default();
+    let mut last_timestamp = None;
+    let mut outage_start = None;
+    loop {
+        tokio::select! {
+            result = &mut stream => {
+                result?;
+                break;
+            }
+            Some(message) = rx.recv() => match message {
+                StreamMessage::Depth(update) => {
+                    if let Some(last) = outage_start.take() {
+                        summary.gaps.push((last, update.timestamp));
+                        on_gap(last, update.timestamp)?;
+                    }
+                    last_timestamp = Some(update.timestamp);
+                    let events = update.to_events(clock.timestamp(update.received));
+                    writer.write(&events)?;
+                    summary.rows += events.len();
+                }
+                StreamMessage::Reconnected => {
+                    summary.reconnects += 1;
+                    outage_start = outage_start.or(last_timestamp);
+                }
+                StreamMessage::Resync => {
+                    summary.resyncs += 1;
+                    outage_start = outage_start.or(last_timestamp);
+                }
+                StreamMessage::Trades(_) | StreamMessage::Liquidations(_) => {}
+            },
+            _ = &mut deadline => break,
+            _ = &mut stop => break,
+        }
+    }
+    Ok(summary)
+}
+
+/// Resolves once Python has a pending `KeyboardInterrupt`, consuming it. Must be polled on the
+/// main thread, which is where signal handlers run.
+async fn interrupted() {
+    let mut interval = tokio::time::interval(Duration::from_millis(100));
+    loop {
+        interval.tick().await;
+        if Python::with_gil(|py| py.check_signals()).is_err() {
+            return;
+        }
+    }
+}
+
+fn stream_url(category: &str, testnet: bool) -> Result<String, BybitError> {
+    if !CATEGORIES.contains(&category) {
+        return Err(BybitError::InvalidArg(format!(
+            "category {} (expected one of {})",
+            category,
+            CATEGORIES.join(", ")
+        )));
+    }
+    let host = if testnet {
+        TESTNET_STREAM_URL
+    } else {
+        MAINNET_STREAM_URL
+    };
+    Ok(format!("{}/{}", host, category))
+}
+
+/// Record the live Bybit trade stream of one or more symbols to a CSV file.
+///
+/// Subscribes to the `publicTrade.<symbol>` topics and appends every trade to output_path with the
+/// columns timestamp, symbol, side, size and price, the same layout as fetch_trades_to_csv. Dropped
+/// connections are re-established and resubscribed automatically; trades published during an
+/// outage are lost, and each such gap is reported with a UserWarning.
+///
+/// Args:
+///     symbols: Trading symbols (e.g., ["BTCUSDT", "ETHUSDT"])
+///     output_path: CSV file to append to, created with a header row if it doesn't exist
+///     duration_secs: How long to record, in seconds. Press Ctrl-C to stop earlier
+///     category: Product type: "linear", "inverse", "spot", or "option" (default "linear")
+///     testnet: Connect to the Bybit testnet (default False)
+///     flush_interval_ms: How often buffered trades are written to the file, in milliseconds
+///                        (default 1000)
+///
+/// Returns:
+///     RecordSummary with the number of rows written, the number of reconnects, and the
+///     (last, first) trade timestamps in milliseconds around each outage
+///
+/// Raises:
+///     BybitError: If the category is invalid, a symbol is rejected by the subscription, or the
+///                 file can't be written
+#[allow(clippy::too_many_arguments)]
+#[pyfunction]
+#[pyo3(
+    text_signature = "(symbols, output_path, duration_secs, *, category='linear', testnet=False, flush_interval_ms=1000)"
+)]
+pub fn record_trades(
+    py: Python,
+    symbols: Vec<String>,
+    output_path: String,
+    duration_secs: f64,
+    category: Option<String>,
+    testnet: Option<bool>,
+    flush_interval_ms: Option<u64>,
+) -> PyResult<RecordSummary> {
+    record_csv(
+        py,
+        "publicTrade",
+        "trade",
+        symbols,
+        output_path,
+        duration_secs,
+        category,
+        testnet,
+        flush_interval_ms,
+    )
+}
+
+/// Record the live Bybit liquidation stream of one or more symbols to a CSV file.
+///
+/// Subscribes to the `allLiquidation.<symbol>` topics and appends every liquidation to
+/// output_path with the columns timestamp, symbol, side, size and price, where side is the side
+/// of the liquidated position: Buy for a long, Sell for a short. Bybit may re-send liquidations
+/// after a reconnection; one with the same timestamp, price and size as a liquidation already
+/// recorded in this session is dropped. Dropped connections are re-established and resubscribed
+/// automatically; liquidations published during an outage are lost, and each such gap is
+/// reported with a UserWarning.
+///
+/// Args:
+///     symbols: Trading symbols (e.g., ["BTCUSDT", "ETHUSDT"])
+///     output_path: CSV file to append to, created with a header row if it doesn't exist
+///     duration_secs: How long to record, in seconds. Press Ctrl-C to stop earlier
+///     category: Product type: "linear" or "inverse" (default "linear")
+///     testnet: Connect to the Bybit testnet (default False)
+///     flush_interval_ms: How often buffered liquidations are written to the file, in
+///                        milliseconds (default 1000)
+///
+/// Returns:
+///     RecordSummary with the number of rows written, the number of reconnects and dropped
+///     duplicates, and the (last, first) liquidation timestamps in milliseconds around each outage
+///
+/// Raises:
+///     BybitError: If the category is invalid, a symbol is rejected by the subscription, or the
+///                 file can't be written
+#[allow(clippy::too_many_arguments)]
+#[pyfunction]
+#[pyo3(
+    text_signature = "(symbols, output_path, duration_secs, *, category='linear', testnet=False, flush_interval_ms=1000)"
+)]
+pub fn record_liquidations(
+    py: Python,
+    symbols: Vec<String>,
+    output_path: String,
+    duration_secs: f64,
+    category: Option<String>,
+    testnet: Option<bool>,
+    flush_interval_ms: Option<u64>,
+) -> PyResult<RecordSummary> {
+    record_csv(
+        py,
+        "allLiquidation",
+        "liquidation",
+        symbols,
+        output_path,
+        duration_secs,
+        category,
+        testnet,
+        flush_interval_ms,
+    )
+}
+
+/// Records the `<channel>.<symbol>` topics of `symbols` with [`record_trade_stream`], reporting
+/// each gap with a `UserWarning` that names the rows as `noun`s.
+#[allow(clippy::too_many_arguments)]
+fn record_csv(
+    py: Python,
+    channel: &str,
+    noun: &str,
+    symbols: Vec<String>,
+    output_path: String,
+    duration_secs: f64,
+    category: Option<String>,
+    testnet: Option<bool>,
+    flush_interval_ms: Option<u64>,
+) -> PyResult<RecordSummary> {
+    let url = stream_url(
+        category.as_deref().unwrap_or("linear"),
+        testnet.unwrap_or(false),
+    )?;
+    if symbols.is_empty() {
+        return Err(BybitError::InvalidArg("no symbols given".to_string()).into());
+    }
+    if !(duration_secs.is_finite() && duration_secs > 0.0) {
+        return Err(BybitError::InvalidArg(format!(
+            "duration_secs must be positive: {}",
+            duration_secs
+        ))
+        .into());
+    }
+    let topics = symbols
+        .iter()
+        .map(|symbol| format!("{}.{}", channel, symbol))
+        .collect::<Vec<_>>();
+    let mut writer = TradeCsvWriter::create(&output_path, true)?;
+
+    let rt = runtime()?;
+
+    let mut warn_err = None;
+    let result = py.allow_threads(|| {
+        rt.block_on(record_trade_stream(
+            &url,
+            &topics,
+            &mut writer,
+            Duration::from_secs_f64(duration_secs),
+            Duration::from_millis(flush_interval_ms.unwrap_or(1000).max(1)),
+            |last, first| {
+                Python::with_gil(|py| {
+                    let message = format!(
+                        "Bybit {noun} stream reconnected: no {noun}s between {last} and {first}"
+                    );
+                    PyErr::warn(
+                        py,
+                        &py.get_type::<PyUserWarning>(),
+                        &CString::new(message)?,
+                        1,
+                    )
+                })
+                .map_err(|e| {
+                    warn_err = Some(e);
+                    BybitError::Callback
+                })
+            },
+            interrupted(),
+        ))
+    });
+    writer.close()?;
+
+    match (result, warn_err) {
+        (_, Some(e)) => Err(e),
+        (result, None) => Ok(result?),
+    }
+}
+
+/// Record the live Bybit orderbook of a symbol as hftbacktest depth events.
+///
+/// Subscribes to the `orderbook.<level>.<symbol>` topic and writes every update to output_path as
+/// an `.npz` file that BacktestAsset loads directly. Each snapshot is written as a
+/// DEPTH_CLEAR_EVENT that clears the whole book followed by DEPTH_SNAPSHOT_EVENT rows, and each
+/// delta as DEPTH_EVENT rows, where a quantity of zero removes the level. The exchange timestamp
+/// comes from the message and the local timestamp from a monotonic clock anchored to the wall
+/// clock when recording starts.
+///
+/// A delta that doesn't continue the update id of the previous one means an update was missed;
+/// the topic is then resubscribed to get a fresh snapshot, so the book stays consistent. Dropped
+/// connections are re-established the same way. Both are reported with a UserWarning, and the
+/// clear event before the next snapshot marks the discontinuity in the file.
+///
+/// Args:
+///     symbol: Trading symbol (e.g., "BTCUSDT")
+///     level: Orderbook depth: 1, 25, 50, 100, 200, 500 or 1000. Which ones are available
+///            depends on the category
+///     output_path: .npz file to write
+///     duration_secs: How long to record, in seconds. Press Ctrl-C to stop earlier
+///     category: Product type: "linear", "inverse", "spot", or "option" (default "linear")
+///     testnet: Connect to the Bybit testnet (default False)
+///
+/// Returns:
+///     RecordSummary with the number of events written, the number of reconnects and
+///     resubscriptions, and the (last, first) update timestamps in milliseconds around each gap
+///
+/// Raises:
+///     BybitError: If the category or level is invalid, the subscription is rejected, or the
+///                 file can't be written
+#[pyfunction]
+#[pyo3(
+    text_signature = "(symbol, level, output_path, duration_secs, *, category='linear', testnet=False)"
+)]
+pub fn record_depth(
+    py: Python,
+    symbol: String,
+    level: u32,
+    output_path: String,
+    duration_secs: f64,
+    category: Option<String>,
+    testnet: Option<bool>,
+) -> PyResult<RecordSummary> {
+    let url = stream_url(
+        category.as_deref().unwrap_or("linear"),
+        testnet.unwrap_or(false),
+    )?;
+    if !DEPTH_LEVELS.contains(&level) {
+        return Err(BybitError::InvalidArg(format!(
+            "level {} (expected one of {:?})",
+            level, DEPTH_LEVELS
+        ))
+        .into());
+    }
+    if !(duration_secs.is_finite() && duration_secs > 0.0) {
+        return Err(BybitError::InvalidArg(format!(
+            "duration_secs must be positive: {}",
+            duration_secs
+        ))
+        .into());
+    }
+    let topic = format!("orderbook.{}.{}", level, symbol);
+    let mut writer = EventNpzWriter::create(&output_path, false)?;
+
+    let rt = runtime()?;
+
+    let mut warn_err = None;
+    let result = py.allow_threads(|| {
+        rt.block_on(record_depth_stream(
+            &url,
+            &topic,
+            &mut writer,
+            Duration::from_secs_f64(duration_secs),
+            |last, first| {
+                Python::with_gil(|py| {
+                    let message = format!(
+                        "Bybit orderbook stream resynchronized: updates between {} and {} are missing",
+                        last, first
+                    );
+                    PyErr::warn(
+                        py,
+                        &py.get_type::<PyUserWarning>(),
+                        &CString::new(message)?,
+                        1,
+                    )
+                })
+                .map_err(|e| {
+                    warn_err = Some(e);
+                    BybitError::Callback
+                })
+            },
+            interrupted(),
+        ))
+    });
+    writer.finish()?;
+
+    match (result, warn_err) {
+        (_, Some(e)) => Err(e),
+        (result, None) => Ok(result?),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::net::TcpListener;
+    use tokio_tungstenite::accept_async;
+
+    use super::*;
+
+    fn trade_push(exec_id: &str, timestamp: i64) -> Message {
+        Message::Text(
+            format!(
+                r#"{{"topic":"publicTrade.BTCUSDT","type":"snapshot","ts":{timestamp},"data":[
+                {{"T":{timestamp},"s":"BTCUSDT","S":"Buy","v":"0.001","p":"16578.50","L":"PlusTick",
+                "i":"{exec_id}","BT":false}}]}}"#
+            )
+            .into(),
+        )
+    }
+
+    #[test]
+    fn test_parse_trades() {
+        let frame: WsFrame = serde_json::from_str(
+            r#"{"topic":"publicTrade.BTC-26DEC25-100000-C","type":"snapshot","ts":1,"data":[
+                {"T":1672304486865,"s":"BTC-26DEC25-100000-C","S":"Sell","v":"0.1","p":"1500",
+                "i":"a1","BT":true,"mP":"1510.5","iP":"97000","mIv":"0.45","iv":""}]}"#,
+        )
+        .unwrap();
+        let Some(StreamMessage::Trades(trades)) = parse_frame(frame, Instant::now()).unwrap()
+        else {
+            panic!("expected trades");
+        };
+        assert_eq!(trades.len(), 1);
+        assert_eq!(trades[0].timestamp, 1672304486865);
+        assert_eq!(trades[0].side, "Sell");
+        assert!(trades[0].is_block_trade);
+        assert_eq!(trades[0].mark_price, Some(1510.5));
+        assert_eq!(trades[0].iv, None);
+
+        let pong: WsFrame =
+            serde_json::from_str(r#"{"success":true,"ret_msg":"pong","op":"ping"}"#).unwrap();
+        assert!(parse_frame(pong, Instant::now()).unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_record_trade_stream_reconnects() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let mut subscriptions = Vec::new();
+            // The first connection drops after one trade; the second one stays open.
+            for (exec_id, timestamp) in [("1", 1000), ("2", 5000)] {
+                let (socket, _) = listener.accept().await.unwrap();
+                let mut ws = accept_async(socket).await.unwrap();
+                let Some(Ok(Message::Text(subscribe))) = ws.next().await else {
+                    panic!("expected a subscription");
+                };
+                subscriptions.push(subscribe.to_string());
+                ws.send(trade_push(exec_id, timestamp)).await.unwrap();
+                if exec_id == "1" {
+                    ws.close(None).await.unwrap();
+                } else {
+                    tokio::time::sleep(Duration::from_secs(5)).await;
+                }
+            }
+            subscriptions
+        });
+
+        let path = std::env::temp_dir().join("test_record_trade_stream.csv");
+        let path = path.to_str().unwrap();
+        let _ = std::fs::remove_file(path);
+        let mut writer = TradeCsvWriter::create(path, true).unwrap();
+        let mut gaps = Vec::new();
+        let summary = record_trade_stream(
+            &url,
+            &["publicTrade.BTCUSDT".to_string()],
+            &mut writer,
+            Duration::from_secs(60),
+            Duration::from_millis(50),
+            |last, first| {
+                gaps.push((last, first));
+                Ok(())
+            },
+            tokio::time::sleep(Duration::from_millis(1500)),
+        )
+        .await
+        .unwrap();
+        assert_eq!(writer.close().unwrap(), 2);
+        server.abort();
+
+        assert_eq!(summary.rows, 2);
+        assert_eq!(summary.reconnects, 1);
+        assert_eq!(summary.gaps, vec![(1000, 5000)]);
+        assert_eq!(gaps, vec![(1000, 5000)]);
+        let csv = std::fs::read_to_string(path).unwrap();
+        assert_eq!(csv.lines().count(), 3);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    fn liquidation_push(timestamps: &[i64]) -> Message {
+        let data = timestamps
+            .iter()
+            .map(|ts| format!(r#"{{"T":{ts},"s":"BTCUSDT","S":"Sell","v":"0.5","p":"43511.70"}}"#))
+            .collect::<Vec<_>>();
+        Message::Text(
+            format!(
+                r#"{{"topic":"allLiquidation.BTCUSDT","type":"snapshot","ts":1,"data":[{}]}}"#,
+                data.join(",")
+            )
+            .into(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_record_liquidation_stream_drops_resent() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            // The liquidation at 1000 is re-sent after the reconnection.
+            for timestamps in [&[1000][..], &[1000, 5000]] {
+                let (socket, _) = listener.accept().await.unwrap();
+                let mut ws = accept_async(socket).await.unwrap();
+                ws.next().await;
+                ws.send(liquidation_push(timestamps)).await.unwrap();
+                if timestamps.len() == 1 {
+                    ws.close(None).await.unwrap();
+                } else {
+                    tokio::time::sleep(Duration::from_secs(5)).await;
+                }
+            }
+        });
+
+        let path = std::env::temp_dir().join("test_record_liquidation_stream.csv");
+        let path = path.to_str().unwrap();
+        let _ = std::fs::remove_file(path);
+        let mut writer = TradeCsvWriter::create(path, true).unwrap();
+        let summary = record_trade_stream(
+            &url,
+            &["allLiquidation.BTCUSDT".to_string()],
+            &mut writer,
+            Duration::from_secs(60),
+            Duration::from_millis(50),
+            |_, _| Ok(()),
+            tokio::time::sleep(Duration::from_millis(1500)),
+        )
+        .await
+        .unwrap();
+        assert_eq!(writer.close().unwrap(), 2);
+        server.abort();
+
+        assert_eq!(summary.rows, 2);
+        assert_eq!(summary.duplicates, 1);
+        assert_eq!(summary.reconnects, 1);
+        assert_eq!(summary.gaps, vec![(1000, 5000)]);
+        let csv = std::fs::read_to_string(path).unwrap();
+        assert_eq!(
+            csv.lines().collect::<Vec<_>>(),
+            [
+                "timestamp,symbol,side,size,price",
+                "1000,BTCUSDT,Sell,0.5,43511.7",
+                "5000,BTCUSDT,Sell,0.5,43511.7",
+            ]
+        );
+        std::fs::remove_file(path).unwrap();
+    }
+
+    fn depth_push(kind: &str, ts: i64, update_id: u64, bid: &str) -> Message {
+        Message::Text(
+            format!(
+                r#"{{"topic":"orderbook.50.BTCUSDT","type":"{kind}","ts":{ts},"data":{{
+                "s":"BTCUSDT","b":[["{bid}","1.5"]],"a":[["100.5","0"]],"u":{update_id},"seq":1}},
+                "cts":{ts}}}"#
+            )
+            .into(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_record_depth_stream_resyncs_on_gap() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        let (requests_tx, requests_rx) = tokio::sync::oneshot::channel();
+        let server = tokio::spawn(async move {
+            let (socket, _) = listener.accept().await.unwrap();
+            let mut ws = accept_async(socket).await.unwrap();
+            let mut requests = Vec::new();
+            let Some(Ok(Message::Text(subscribe))) = ws.next().await else {
+                panic!("expected a subscription");
+            };
+            requests.push(subscribe.to_string());
+            ws.send(depth_push("snapshot", 1000, 10, "100"))
+                .await
+                .unwrap();
+            ws.send(depth_push("delta", 1100, 11, "99.5"))
+                .await
+                .unwrap();
+            // Update 12 is missing.
+            ws.send(depth_push("delta", 1300, 13, "99")).await.unwrap();
+            while requests.len() < 3 {
+                let Some(Ok(Message::Text(request))) = ws.next().await else {
+                    panic!("expected a resubscription");
+                };
+                if !request.contains("ping") {
+                    requests.push(request.to_string());
+                }
+            }
+            // Dropped while waiting for the snapshot.
+            ws.send(depth_push("delta", 1400, 14, "98")).await.unwrap();
+            ws.send(depth_push("snapshot", 1500, 20, "101"))
+                .await
+                .unwrap();
+            ws.send(depth_push("delta", 1600, 21, "100.5"))
+                .await
+                .unwrap();
+            requests_tx.send(requests).unwrap();
+            tokio::time::sleep(Duration::from_secs(5)).await;
+        });
+
+        let path = std::env::temp_dir().join("test_record_depth_stream.npz");
+        let path = path.to_str().unwrap();
+        let mut writer = EventNpzWriter::create(path, false).unwrap();
+        let mut gaps = Vec::new();
+        let summary = record_depth_stream(
+            &url,
+            "orderbook.50.BTCUSDT",
+            &mut writer,
+            Duration::from_secs(60),
+            |last, first| {
+                gaps.push((last, first));
+                Ok(())
+            },
+            tokio::time::sleep(Duration::from_millis(500)),
+        )
+        .await
+        .unwrap();
+        assert_eq!(writer.finish().unwrap(), 10);
+        server.abort();
+
+        assert_eq!(summary.rows, 10);
+        assert_eq!(summary.reconnects, 0);
+        assert_eq!(summary.resyncs, 1);
+        assert_eq!(summary.gaps, vec![(1100, 1500)]);
+        assert_eq!(gaps, vec![(1100, 1500)]);
+
+        use hftbacktest::backtest::data::read_npz_file;
+        let data = read_npz_file::<Event>(path, "data").unwrap();
+        let events = (0..data.len()).map(|i| data[i].clone()).collect::<Vec<_>>();
+        let flags = events.iter().map(|e| e.ev).collect::<Vec<_>>();
+        let exch = EXCH_EVENT | LOCAL_EVENT;
+        assert_eq!(
+            flags,
+            vec![
+                exch | DEPTH_CLEAR_EVENT,
+                exch | DEPTH_SNAPSHOT_EVENT | BUY_EVENT,
+                exch | DEPTH_SNAPSHOT_EVENT | SELL_EVENT,
+                exch | DEPTH_EVENT | BUY_EVENT,
+                exch | DEPTH_EVENT | SELL_EVENT,
+                exch | DEPTH_CLEAR_EVENT,
+                exch | DEPTH_SNAPSHOT_EVENT | BUY_EVENT,
+                exch | DEPTH_SNAPSHOT_EVENT | SELL_EVENT,
+                exch | DEPTH_EVENT | BUY_EVENT,
+                exch | DEPTH_EVENT | SELL_EVENT,
+            ][..]
+        );
+        assert_eq!(events[9].qty, 0.0);
+        assert_eq!(events[6].px, 101.0);
+        assert_eq!(events[6].exch_ts, 1_500_000_000);
+        assert!(events.windows(2).all(|w| w[0].local_ts <= w[1].local_ts));
+        std::fs::remove_file(path).unwrap();
+
+        let requests = requests_rx.await.unwrap();
+        assert_eq!(
+            requests[1..],
+            [
+                r#"{"args":["orderbook.50.BTCUSDT"],"op":"unsubscribe"}"#,
+                r#"{"args":["orderbook.50.BTCUSDT"],"op":"subscribe"}"#,
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_tls_connector_has_crypto_provider() {
+        // Fails the TLS handshake against a plain TCP server instead of panicking.
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("wss://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            tokio::io::AsyncWriteExt::shutdown(&mut socket)
+                .await
+                .unwrap();
+        });
+
+        let (tx, _rx) = unbounded_channel();
+        let result = connect(&url, &[], &tx, &mut false).await;
+        assert!(matches!(result, Err(BybitError::Transport(_))));
+    }
+
+    #[tokio::test]
+    async fn test_rejected_subscription_is_fatal() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let (socket, _) = listener.accept().await.unwrap();
+            let mut ws = accept_async(socket).await.unwrap();
+            ws.next().await;
+            ws.send(Message::Text(
+                r#"{"success":false,"ret_msg":"error:handler not found,topic:publicTrade.NOPE","op":"subscribe"}"#
+                    .into(),
+            ))
+            .await
+            .unwrap();
+            tokio::time::sleep(Duration::from_secs(5)).await;
+        });
+
+        let (tx, _rx) = unbounded_channel();
+        let result = keep_connection(&url, &["publicTrade.NOPE".to_string()], tx).await;
+        assert!(matches!(result, Err(BybitError::InvalidArg(_))));
+    }
+
+    #[test]
+    fn test_stream_url() {
+        assert_eq!(
+            stream_url("linear", false).unwrap(),
+            "wss://stream.bybit.com/v5/public/linear"
+        );
+        assert_eq!(
+            stream_url("spot", true).unwrap(),
+            "wss://stream-testnet.bybit.com/v5/public/spot"
+        );
+        assert!(stream_url("futures", false).is_err());
+    }
+}
@@ -0,0 +1,634 @@
+use std::{
+    collections::HashSet,
+    time::{Duration, Instant},
+};
+
+use pyo3::{prelude::*, types::PyList};
+use reqwest::{Client, StatusCode};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use thiserror::Error;
+
+use crate::bybit::{FetchStats, RetryConfig, TradeRow, record_stats, runtime};
+
+pub mod exceptions {
+    use pyo3::{create_exception, exceptions::PyRuntimeError};
+
+    create_exception!(
+        hftbacktest,
+        DeribitError,
+        PyRuntimeError,
+        "Raised when fetching data from Deribit fails."
+    );
+}
+
+#[derive(Error, Debug)]
+pub enum DeribitError {
+    #[error("HTTP error: {0}")]
+    Http(StatusCode),
+    #[error("Rate limited: max retries exceeded")]
+    RateLimited,
+    #[error("API error: {code} - {msg}")]
+    Api { code: i64, msg: String },
+    #[error("Failed to parse {0}")]
+    Parse(String),
+    #[error("Request failed: {0}")]
+    Transport(String),
+    #[error("Invalid argument: {0}")]
+    InvalidArg(String),
+}
+
+impl From<DeribitError> for PyErr {
+    fn from(error: DeribitError) -> Self {
+        exceptions::DeribitError::new_err(error.to_string())
+    }
+}
+
+/// Deribit REST host.
+pub const BASE_URL: &str = "https://www.deribit.com";
+
+/// Maximum number of trades the trades-by-time endpoint returns per page.
+pub const TRADES_PAGE_LIMIT: usize = 1000;
+
+/// Deribit's error code for exceeding the request rate.
+const RATE_LIMIT_CODE: i64 = 10028;
+
+/// Instrument kinds accepted by `/api/v2/public/get_instruments`.
+pub const KINDS: [&str; 5] = ["future", "option", "spot", "future_combo", "option_combo"];
+
+/// Delay between consecutive page requests, keeping within the public rate limit.
+const PAGE_DELAY: Duration = Duration::from_millis(50);
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DeribitRpcError {
+    pub code: i64,
+    pub message: String,
+}
+
+/// A JSON-RPC response, carrying either `result` or `error`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DeribitResponse<T> {
+    pub result: Option<T>,
+    pub error: Option<DeribitRpcError>,
+}
+
+/// A trade as returned by `/api/v2/public/get_last_trades_by_instrument_and_time`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DeribitTrade {
+    /// Sequence number of the trade within the instrument.
+    pub trade_seq: i64,
+    pub trade_id: String,
+    pub timestamp: i64,
+    pub instrument_name: String,
+    pub price: f64,
+    /// Trade size: USD for inverse futures and perpetuals, the base currency otherwise.
+    pub amount: f64,
+    /// Taker side, `buy` or `sell`.
+    pub direction: String,
+    #[serde(default)]
+    pub mark_price: Option<f64>,
+    #[serde(default)]
+    pub index_price: Option<f64>,
+    /// Implied volatility in percent, options only.
+    #[serde(default)]
+    pub iv: Option<f64>,
+    #[serde(default)]
+    pub block_trade_id: Option<String>,
+}
+
+impl DeribitTrade {
+    /// Converts the trade into a [`TradeRow`], with the trade sequence number as `exec_id`.
+    pub fn to_row(&self) -> TradeRow {
+        TradeRow {
+            exec_id: self.trade_seq.to_string(),
+            timestamp: self.timestamp,
+            symbol: self.instrument_name.clone(),
+            side: match self.direction.as_str() {
+                "buy" => "Buy".to_string(),
+                "sell" => "Sell".to_string(),
+                side => side.to_string(),
+            },
+            size: self.amount,
+            price: self.price,
+            is_block_trade: self.block_trade_id.is_some(),
+            mark_price: self.mark_price,
+            index_price: self.index_price,
+            mark_iv: None,
+            iv: self.iv,
+            raw_price: None,
+            raw_size: None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DeribitTradePage {
+    /// Trades, newest first with `sorting=desc`.
+    pub trades: Vec<DeribitTrade>,
+    pub has_more: bool,
+}
+
+/// An instrument as returned by `/api/v2/public/get_instruments`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct DeribitInstrument {
+    pub instrument_name: String,
+    pub kind: String,
+    pub base_currency: String,
+    pub quote_currency: String,
+    pub settlement_currency: Option<String>,
+    pub tick_size: f64,
+    pub min_trade_amount: f64,
+    pub contract_size: f64,
+    /// Expiration time in milliseconds. Perpetuals report a far-future timestamp.
+    pub expiration_timestamp: i64,
+    /// Strike price, options only.
+    #[serde(default)]
+    pub strike: Option<f64>,
+    /// `call` or `put`, options only.
+    #[serde(default)]
+    pub option_type: Option<String>,
+    pub is_active: bool,
+}
+
+impl DeribitInstrument {
+    pub fn to_dict(&self, py: Python) -> PyResult<PyObject> {
+        let dict = pyo3::types::PyDict::new(py);
+        for (key, value) in [
+            ("instrument_name", &self.instrument_name),
+            ("kind", &self.kind),
+            ("base_currency", &self.base_currency),
+            ("quote_currency", &self.quote_currency),
+        ] {
+            dict.set_item(key, value)?;
+        }
+        for (key, value) in [
+            ("tick_size", self.tick_size),
+            ("min_trade_amount", self.min_trade_amount),
+            ("contract_size", self.contract_size),
+        ] {
+            dict.set_item(key, value)?;
+        }
+        for (key, value) in [
+            ("settlement_currency", &self.settlement_currency),
+            ("option_type", &self.option_type),
+        ] {
+            dict.set_item(key, value)?;
+        }
+        dict.set_item("expiration_timestamp", self.expiration_timestamp)?;
+        dict.set_item("strike", self.strike)?;
+        dict.set_item("is_active", self.is_active)?;
+        Ok(dict.into())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DeribitFetcher {
+    client: Client,
+    base_url: String,
+    retry: RetryConfig,
+}
+
+impl DeribitFetcher {
+    pub fn new(base_url: String) -> Self {
+        Self {
+            client: Client::new(),
+            base_url,
+            retry: RetryConfig::default(),
+        }
+    }
+
+    /// Sets the retry policy applied when requests are rate limited or fail transiently.
+    pub fn with_retry(mut self, retry: RetryConfig) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Sends a GET request to `path` and returns the `result` of the response. Rate-limited
+    /// requests (HTTP 429 or error code 10028), gateway and server errors, timeouts and dropped
+    /// connections are retried with exponential backoff, sharing the `max_retries` budget.
+    async fn get<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        query_string: &str,
+        stats: &mut FetchStats,
+    ) -> Result<T, DeribitError> {
+        let url = format!("{}{}?{}", self.base_url, path, query_string);
+        let mut retries = 0;
+
+        loop {
+            let response = self
+                .client
+                .get(&url)
+                .timeout(Duration::from_secs(10))
+                .send()
+                .await;
+            stats.requests += 1;
+            let response = match response {
+                Ok(response) => response,
+                Err(e) if self.retry.retries_error(&e) && retries < self.retry.max_retries => {
+                    retries += 1;
+                    stats.retries += 1;
+                    tokio::time::sleep(self.retry.backoff(retries)).await;
+                    continue;
+                },
+                Err(e) => return Err(DeribitError::Transport(e.to_string())),
+            };
+
+            let status = response.status();
+            if self.retry.retries_status(status) {
+                if retries >= self.retry.max_retries {
+                    return Err(DeribitError::Http(status));
+                }
+                retries += 1;
+                stats.retries += 1;
+                tokio::time::sleep(self.retry.backoff(retries)).await;
+                continue;
+            }
+
+            // Errors come with a JSON-RPC error body and a 4xx status.
+            let body = response.json::<DeribitResponse<T>>().await.ok();
+            let error = body.as_ref().and_then(|b| b.error.as_ref());
+            if status == StatusCode::TOO_MANY_REQUESTS
+                || error.is_some_and(|e| e.code == RATE_LIMIT_CODE)
+            {
+                stats.rate_limited += 1;
+                if retries >= self.retry.max_retries {
+                    return Err(DeribitError::RateLimited);
+                }
+                retries += 1;
+                stats.retries += 1;
+                tokio::time::sleep(self.retry.backoff(retries)).await;
+                continue;
+            }
+
+            return match body {
+                Some(DeribitResponse {
+                    error: Some(error), ..
+                }) => Err(DeribitError::Api {
+                    code: error.code,
+                    msg: error.message,
+                }),
+                _ if !status.is_success() => Err(DeribitError::Http(status)),
+                Some(DeribitResponse {
+                    result: Some(result),
+                    ..
+                }) => Ok(result),
+                _ => Err(DeribitError::Parse("response".to_string())),
+            };
+        }
+    }
+
+    /// Fetches the trades in `[start_time, end_time]`, passing each page to `on_page`, newest
+    /// first.
+    ///
+    /// Pages are requested newest first; while `has_more` is set, the next page ends at the oldest
+    /// timestamp received. That millisecond is requested again so none of its trades are missed,
+    /// and the repeats are dropped by trade sequence number. If a single millisecond holds more
+    /// than a full page of trades, the rest of that millisecond is skipped.
+    pub async fn fetch_trade_pages<F>(
+        &self,
+        instrument_name: &str,
+        start_time: i64,
+        end_time: i64,
+        mut on_page: F,
+    ) -> Result<FetchStats, DeribitError>
+    where
+        F: FnMut(Vec<TradeRow>) -> Result<(), DeribitError>,
+    {
+        let started = Instant::now();
+        let mut stats = FetchStats::default();
+        let mut seen = HashSet::new();
+        let mut page_end = end_time;
+
+        loop {
+            let query_string = format!(
+                "instrument_name={}&start_timestamp={}&end_timestamp={}&count={}&sorting=desc",
+                instrument_name, start_time, page_end, TRADES_PAGE_LIMIT
+            );
+            let page: DeribitTradePage = self
+                .get(
+                    "/api/v2/public/get_last_trades_by_instrument_and_time",
+                    &query_string,
+                    &mut stats,
+                )
+                .await?;
+            let Some(oldest) = page.trades.last().map(|t| t.timestamp) else {
+                break;
+            };
+
+            let rows = page
+                .trades
+                .iter()
+                .filter(|t| seen.insert(t.trade_seq))
+                .map(DeribitTrade::to_row)
+                .collect::<Vec<_>>();
+            stats.trades += rows.len();
+            on_page(rows)?;
+
+            if !page.has_more {
+                break;
+            }
+            page_end = if oldest < page_end {
+                oldest
+            } else {
+                oldest - 1
+            };
+            if page_end < start_time {
+                break;
+            }
+            tokio::time::sleep(PAGE_DELAY).await;
+        }
+
+        stats.elapsed = started.elapsed();
+        Ok(stats)
+    }
+
+    /// Fetches the instruments of `currency`, optionally of one `kind`, including expired ones if
+    /// `expired` is set.
+    pub async fn fetch_instruments(
+        &self,
+        currency: &str,
+        kind: Option<&str>,
+        expired: bool,
+    ) -> Result<Vec<DeribitInstrument>, DeribitError> {
+        let mut query_string = format!("currency={}&expired={}", currency, expired);
+        if let Some(kind) = kind {
+            if !KINDS.contains(&kind) {
+                return Err(DeribitError::InvalidArg(format!(
+                    "kind {} (expected one of {})",
+                    kind,
+                    KINDS.join(", ")
+                )));
+            }
+            query_string.push_str(&format!("&kind={}", kind));
+        }
+        self.get(
+            "/api/v2/public/get_instruments",
+            &query_string,
+            &mut FetchStats::default(),
+        )
+        .await
+    }
+}
+
+/// Fetch Deribit public trade history between two timestamps.
+///
+/// Trades are returned in the same shape as Bybit trades from fetch_trades, newest first, with the
+/// trade sequence number, unique within an instrument, as exec_id.
+///
+/// Args:
+///     instrument_name: Instrument name (e.g., "BTC-PERPETUAL" or "BTC-27DEC24-100000-C")
+///     start_time: Start timestamp in milliseconds
+///     end_time: End timestamp in milliseconds (inclusive)
+///     max_retries: Maximum number of retries on a rate limit, a 5xx or a network error (default 5)
+///     base_backoff_ms: Wait before the first retry in milliseconds, doubled on each retry (default 50)
+///     max_backoff_ms: Upper bound of the wait between retries in milliseconds (default 10000)
+///     base_url: Base URL for the Deribit API (default "https://www.deribit.com")
+///     side_as_int: Return side as 1 for Buy and -1 for Sell instead of the string (default False)
+///
+/// Returns:
+///     List of dicts with keys: exec_id, timestamp, symbol, side, size, price, is_block_trade, and
+///     mark_price, index_price and iv where Deribit reports them. size is in USD for inverse
+///     futures and perpetuals and in the base currency otherwise
+///
+/// Raises:
+///     DeribitError: If the API request fails or the rate limit is still exceeded after max retries
+#[allow(clippy::too_many_arguments)]
+#[pyfunction]
+#[pyo3(
+    text_signature = "(instrument_name, start_time, end_time, *, max_retries=5, base_backoff_ms=50, max_backoff_ms=10000, base_url='https://www.deribit.com', side_as_int=False)"
+)]
+pub fn fetch_deribit_trades(
+    py: Python,
+    instrument_name: String,
+    start_time: i64,
+    end_time: i64,
+    max_retries: Option<u32>,
+    base_backoff_ms: Option<u64>,
+    max_backoff_ms: Option<u64>,
+    base_url: Option<String>,
+    side_as_int: Option<bool>,
+) -> PyResult<PyObject> {
+    if start_time >= end_time {
+        return Err(DeribitError::InvalidArg(format!(
+            "start_time {} must be before end_time {}",
+            start_time, end_time
+        ))
+        .into());
+    }
+    let fetcher = DeribitFetcher::new(base_url.unwrap_or_else(|| BASE_URL.to_string())).with_retry(
+        RetryConfig::new(max_retries, base_backoff_ms, max_backoff_ms),
+    );
+
+    let rt = runtime()?;
+    let mut trades = Vec::new();
+    let stats = py.allow_threads(|| {
+        rt.block_on(
+            fetcher.fetch_trade_pages(&instrument_name, start_time, end_time, |page| {
+                trades.extend(page);
+                Ok(())
+            }),
+        )
+    })?;
+    record_stats(stats);
+
+    let result = trades
+        .iter()
+        .map(|t| t.to_dict(py, side_as_int.unwrap_or(false), false))
+        .collect::<PyResult<Vec<_>>>()?;
+    Ok(PyList::new(py, result)?.into())
+}
+
+/// Fetch the Deribit instruments of a currency, such as the listed option series.
+///
+/// Args:
+///     currency: Currency, e.g. "BTC", "ETH", "USDC" or "any"
+///     kind: Instrument kind: "future", "option", "spot", "future_combo" or "option_combo"
+///           (default None, all kinds)
+///     expired: List recently expired instruments instead of active ones (default False)
+///     max_retries: Maximum number of retries on a rate limit, a 5xx or a network error (default 5)
+///     base_backoff_ms: Wait before the first retry in milliseconds, doubled on each retry (default 50)
+///     max_backoff_ms: Upper bound of the wait between retries in milliseconds (default 10000)
+///     base_url: Base URL for the Deribit API (default "https://www.deribit.com")
+///
+/// Returns:
+///     List of dicts with keys: instrument_name, kind, base_currency, quote_currency,
+///     settlement_currency, tick_size, min_trade_amount, contract_size, expiration_timestamp,
+///     strike, option_type, is_active. strike and option_type are None except for options
+///
+/// Raises:
+///     DeribitError: If kind is invalid, the API request fails or the rate limit is still exceeded
+///                   after max retries
+#[allow(clippy::too_many_arguments)]
+#[pyfunction]
+#[pyo3(
+    text_signature = "(currency, kind=None, *, expired=False, max_retries=5, base_backoff_ms=50, max_backoff_ms=10000, base_url='https://www.deribit.com')"
+)]
+pub fn fetch_deribit_instruments(
+    py: Python,
+    currency: String,
+    kind: Option<String>,
+    expired: Option<bool>,
+    max_retries: Option<u32>,
+    base_backoff_ms: Option<u64>,
+    max_backoff_ms: Option<u64>,
+    base_url: Option<String>,
+) -> PyResult<PyObject> {
+    let fetcher = DeribitFetcher::new(base_url.unwrap_or_else(|| BASE_URL.to_string())).with_retry(
+        RetryConfig::new(max_retries, base_backoff_ms, max_backoff_ms),
+    );
+
+    let rt = runtime()?;
+    let instruments = py.allow_threads(|| {
+        rt.block_on(fetcher.fetch_instruments(&currency, kind.as_deref(), expired.unwrap_or(false)))
+    })?;
+
+    let result = instruments
+        .iter()
+        .map(|instrument| instrument.to_dict(py))
+        .collect::<PyResult<Vec<_>>>()?;
+    Ok(PyList::new(py, result)?.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    use super::*;
+
+    fn trade_json(trade_seq: i64, timestamp: i64) -> String {
+        format!(
+            r#"{{"trade_seq":{},"trade_id":"{}","timestamp":{},"tick_direction":0,"price":42000.5,"mark_price":42001.2,"index_price":41998.7,"instrument_name":"BTC-PERPETUAL","direction":"{}","amount":10.0}}"#,
+            trade_seq,
+            trade_seq + 1000,
+            timestamp,
+            if trade_seq % 2 == 0 { "buy" } else { "sell" },
+        )
+    }
+
+    /// Serves trades-by-time pages newest first from a fixed history of trade sequence numbers
+    /// `1..=25`, where trade `i` has timestamp `1000 + 10 * (i / 2)`, so pairs of trades share a
+    /// millisecond, with pages of `count` trades.
+    async fn serve_history(listener: TcpListener, count: usize) {
+        loop {
+            let Ok((mut socket, _)) = listener.accept().await else {
+                return;
+            };
+            let mut buf = vec![0; 4096];
+            let n = socket.read(&mut buf).await.unwrap();
+            let request = String::from_utf8_lossy(&buf[..n]).to_string();
+            let query = request
+                .split_whitespace()
+                .nth(1)
+                .and_then(|path| path.split_once('?'))
+                .map(|(_, query)| query.to_string())
+                .unwrap_or_default();
+            let param = |name: &str| -> i64 {
+                query
+                    .split('&')
+                    .find_map(|kv| kv.strip_prefix(&format!("{}=", name)))
+                    .unwrap()
+                    .parse()
+                    .unwrap()
+            };
+            let (start, end) = (param("start_timestamp"), param("end_timestamp"));
+            let matching = (1..=25)
+                .rev()
+                .map(|i| (i, 1000 + 10 * (i / 2)))
+                .filter(|(_, ts)| *ts >= start && *ts <= end)
+                .collect::<Vec<_>>();
+            let trades = matching
+                .iter()
+                .take(count)
+                .map(|(i, ts)| trade_json(*i, *ts))
+                .collect::<Vec<_>>();
+            let body = format!(
+                r#"{{"jsonrpc":"2.0","result":{{"trades":[{}],"has_more":{}}}}}"#,
+                trades.join(","),
+                matching.len() > count
+            );
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            socket.write_all(response.as_bytes()).await.unwrap();
+        }
+    }
+
+    #[test]
+    fn test_deribit_trade_to_row() {
+        let json = r#"{"jsonrpc":"2.0","result":{"trades":[
+            {"trade_seq":30289,"trade_id":"BTC-48217","timestamp":1704067200123,"tick_direction":1,
+             "price":0.0545,"mark_price":0.0541,"iv":55.3,"index_price":42250.1,
+             "instrument_name":"BTC-27DEC24-100000-C","direction":"sell","amount":1.5,
+             "block_trade_id":"BLOCK-1"}
+        ],"has_more":false}}"#;
+        let response: DeribitResponse<DeribitTradePage> = serde_json::from_str(json).unwrap();
+        let row = response.result.unwrap().trades[0].to_row();
+        assert_eq!(row.exec_id, "30289");
+        assert_eq!(row.timestamp, 1704067200123);
+        assert_eq!(row.symbol, "BTC-27DEC24-100000-C");
+        assert_eq!(row.side, "Sell");
+        assert_eq!(row.size, 1.5);
+        assert_eq!(row.iv, Some(55.3));
+        assert!(row.is_block_trade);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_trade_pages_shifts_end_timestamp() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(serve_history(listener, 5));
+
+        let fetcher = DeribitFetcher::new(base_url);
+        let mut seqs = Vec::new();
+        // Trades 4..=21 fall in [1020, 1100].
+        let stats = fetcher
+            .fetch_trade_pages("BTC-PERPETUAL", 1020, 1100, |page| {
+                seqs.extend(page.iter().map(|t| t.exec_id.parse::<i64>().unwrap()));
+                Ok(())
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(seqs, (4..=21).rev().collect::<Vec<_>>());
+        assert_eq!(stats.trades, seqs.len());
+        assert!(stats.requests > 1);
+    }
+
+    #[tokio::test]
+    async fn test_api_error() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = vec![0; 4096];
+            let _ = socket.read(&mut buf).await.unwrap();
+            let body = r#"{"jsonrpc":"2.0","error":{"message":"Invalid params","code":-32602}}"#;
+            let response = format!(
+                "HTTP/1.1 400 Bad Request\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            socket.write_all(response.as_bytes()).await.unwrap();
+        });
+
+        let fetcher = DeribitFetcher::new(base_url);
+        let result = fetcher
+            .fetch_instruments("BTC", Some("option"), false)
+            .await;
+        assert!(matches!(
+            result,
+            Err(DeribitError::Api { code: -32602, .. })
+        ));
+        assert!(matches!(
+            fetcher
+                .fetch_instruments("BTC", Some("perpetual"), false)
+                .await,
+            Err(DeribitError::InvalidArg(_))
+        ));
+    }
+}
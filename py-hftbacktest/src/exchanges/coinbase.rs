@@ -0,0 +1,230 @@
+use std::time::Duration;
+
+use chrono::DateTime;
+use reqwest::Client;
+use serde::Deserialize;
+
+use crate::{
+    bybit::TradeRow,
+    exchanges::{Cursor, FetchError, Page, TradeHistorySource, Window, send_json},
+};
+
+/// Coinbase Exchange REST host.
+pub const BASE_URL: &str = "https://api.exchange.coinbase.com";
+
+/// Maximum number of trades the trades endpoint returns per page.
+pub const TRADES_PAGE_LIMIT: usize = 1000;
+
+/// Delay between consecutive page requests, keeping within the 10 requests per second allowed
+/// for public endpoints.
+const PAGE_DELAY: Duration = Duration::from_millis(100);
+
+/// A trade as returned by `/products/{product_id}/trades`.
+#[derive(Debug, Deserialize, Clone)]
+pub struct CoinbaseTrade {
+    pub trade_id: i64,
+    pub price: String,
+    pub size: String,
+    /// Side of the maker order, `buy` for a down-tick and `sell` for an up-tick.
+    pub side: String,
+    /// RFC 3339 timestamp with microseconds, such as `2024-01-01T00:00:00.123456Z`.
+    pub time: String,
+}
+
+impl CoinbaseTrade {
+    pub fn to_row(&self, product_id: &str) -> Result<TradeRow, FetchError> {
+        let parse = |value: &str, name: &str| -> Result<f64, FetchError> {
+            value
+                .parse()
+                .map_err(|_| FetchError::Parse(format!("{}: {}", name, value)))
+        };
+        Ok(TradeRow {
+            exec_id: self.trade_id.to_string(),
+            timestamp: DateTime::parse_from_rfc3339(&self.time)
+                .map_err(|_| FetchError::Parse(format!("time: {}", self.time)))?
+                .timestamp_millis(),
+            symbol: product_id.to_string(),
+            // The taker is on the other side of the maker.
+            side: match self.side.as_str() {
+                "buy" => "Sell".to_string(),
+                "sell" => "Buy".to_string(),
+                side => side.to_string(),
+            },
+            size: parse(&self.size, "size")?,
+            price: parse(&self.price, "price")?,
+            is_block_trade: false,
+            mark_price: None,
+            index_price: None,
+            mark_iv: None,
+            iv: None,
+            raw_price: None,
+            raw_size: None,
+        })
+    }
+}
+
+/// Coinbase Exchange trade history. The endpoint can't be queried by time: pages run from the
+/// latest trade backwards, each pointing to the next, older one with the `CB-AFTER` header, so
+/// reaching an old window walks every trade after it.
+#[derive(Debug, Clone)]
+pub struct CoinbaseTradeSource {
+    client: Client,
+    base_url: String,
+}
+
+impl CoinbaseTradeSource {
+    pub fn new(base_url: String) -> Self {
+        Self {
+            client: Client::new(),
+            base_url,
+        }
+    }
+}
+
+impl TradeHistorySource for CoinbaseTradeSource {
+    async fn fetch_page(
+        &self,
+        product_id: &str,
+        window: Window,
+        cursor: Option<&Cursor>,
+    ) -> Result<Page, FetchError> {
+        let mut request = self
+            .client
+            .get(format!("{}/products/{}/trades", self.base_url, product_id))
+            .query(&[("limit", TRADES_PAGE_LIMIT.to_string())]);
+        if let Some(cursor) = cursor {
+            request = request.query(&[("after", cursor)]);
+        }
+        let (page, headers) = send_json::<Vec<CoinbaseTrade>>(request).await?;
+        let rows = page
+            .iter()
+            .map(|t| t.to_row(product_id))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let next = match rows.last() {
+            Some(oldest) if oldest.timestamp >= window.start_time => headers
+                .get("cb-after")
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string),
+            _ => None,
+        };
+        Ok((rows, next))
+    }
+
+    fn page_delay(&self) -> Duration {
+        PAGE_DELAY
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    use super::*;
+    use crate::{
+        bybit::RetryConfig,
+        exchanges::{Window, fetch_trades},
+    };
+
+    fn trade_json(trade_id: i64) -> String {
+        format!(
+            r#"{{"time":"2024-01-01T00:00:{:02}.500000Z","trade_id":{},"price":"42000.01","size":"0.0100","side":"{}"}}"#,
+            trade_id,
+            trade_id,
+            if trade_id % 2 == 0 { "buy" } else { "sell" }
+        )
+    }
+
+    /// Serves pages of two trades from a history of trade IDs `1..=5`, where trade `i` is at
+    /// second `i` of 2024, honouring `after` and pointing to the next page with `CB-AFTER`.
+    async fn serve_history(listener: TcpListener) -> Vec<String> {
+        let mut requests = Vec::new();
+        loop {
+            let Ok((mut socket, _)) = listener.accept().await else {
+                return requests;
+            };
+            let mut buf = vec![0; 4096];
+            let n = socket.read(&mut buf).await.unwrap();
+            let request = String::from_utf8_lossy(&buf[..n]).to_string();
+            let newest = request
+                .split_whitespace()
+                .nth(1)
+                .and_then(|path| path.split_once("after="))
+                .map(|(_, after)| after.parse::<i64>().unwrap() - 1)
+                .unwrap_or(5);
+            let last_page = request.contains("after=2");
+            requests.push(request);
+            let ids = (1..=newest).rev().take(2).collect::<Vec<_>>();
+            let body = format!(
+                "[{}]",
+                ids.iter()
+                    .map(|i| trade_json(*i))
+                    .collect::<Vec<_>>()
+                    .join(",")
+            );
+            let cb_after = match ids.last() {
+                Some(oldest) if *oldest > 1 => format!("CB-AFTER: {}\r\n", oldest),
+                _ => String::new(),
+            };
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n{}Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+                cb_after,
+                body.len(),
+                body
+            );
+            socket.write_all(response.as_bytes()).await.unwrap();
+            if last_page {
+                return requests;
+            }
+        }
+    }
+
+    #[test]
+    fn test_coinbase_trade_to_row() {
+        let trades: Vec<CoinbaseTrade> = serde_json::from_str(
+            r#"[{"time":"2024-01-01T00:00:01.123456Z","trade_id":74,"price":"42000.01","size":"0.5","side":"buy"}]"#,
+        )
+        .unwrap();
+        let row = trades[0].to_row("BTC-USD").unwrap();
+        assert_eq!(row.exec_id, "74");
+        assert_eq!(row.timestamp, 1704067201123);
+        assert_eq!(row.symbol, "BTC-USD");
+        // A maker buy means a taker sell.
+        assert_eq!(row.side, "Sell");
+        assert_eq!(row.price, 42000.01);
+        assert_eq!(row.size, 0.5);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_trades_follows_cb_after() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let server = tokio::spawn(serve_history(listener));
+
+        let source = CoinbaseTradeSource::new(base_url);
+        let window = Window {
+            start_time: 1704067202000,
+            end_time: 1704067204999,
+        };
+        let (trades, stats) = fetch_trades(&source, &RetryConfig::default(), "BTC-USD", window)
+            .await
+            .unwrap();
+        assert_eq!(
+            trades
+                .iter()
+                .map(|t| t.exec_id.as_str())
+                .collect::<Vec<_>>(),
+            vec!["2", "3", "4"]
+        );
+        // 5 and 4, 3 and 2, then 1, which reaches past the start.
+        assert_eq!(stats.requests, 3);
+
+        let requests = server.await.unwrap();
+        assert!(requests[0].starts_with("GET /products/BTC-USD/trades?limit=1000 "));
+        assert!(requests[1].contains("after=4"));
+        assert!(requests[2].contains("after=2"));
+    }
+}
@@ -0,0 +1,234 @@
+use std::time::Duration;
+
+use reqwest::Client;
+use serde::Deserialize;
+
+use crate::{
+    bybit::TradeRow,
+    exchanges::{Cursor, FetchError, Page, TradeHistorySource, Window, send_json},
+};
+
+/// Kraken Futures REST host.
+pub const BASE_URL: &str = "https://futures.kraken.com";
+
+/// Delay between consecutive page requests, keeping within the history endpoints' rate limit.
+const PAGE_DELAY: Duration = Duration::from_millis(200);
+
+/// A page of `/api/history/v3/market/{symbol}/executions`.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KrakenExecutionsPage {
+    pub elements: Vec<KrakenExecutionElement>,
+    /// Token of the next page, absent on the last one.
+    pub continuation_token: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct KrakenExecutionElement {
+    pub event: KrakenExecutionEvent,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct KrakenExecutionEvent {
+    #[serde(rename = "Execution")]
+    pub execution: KrakenExecutionDetail,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct KrakenExecutionDetail {
+    pub execution: KrakenExecution,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KrakenExecution {
+    pub uid: String,
+    /// Timestamp in milliseconds.
+    pub timestamp: i64,
+    pub quantity: String,
+    pub price: String,
+    pub taker_order: KrakenOrder,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct KrakenOrder {
+    /// `Buy` or `Sell`.
+    pub direction: String,
+    pub tradeable: String,
+}
+
+impl KrakenExecution {
+    pub fn to_row(&self) -> Result<TradeRow, FetchError> {
+        let parse = |value: &str, name: &str| -> Result<f64, FetchError> {
+            value
+                .parse()
+                .map_err(|_| FetchError::Parse(format!("{}: {}", name, value)))
+        };
+        Ok(TradeRow {
+            exec_id: self.uid.clone(),
+            timestamp: self.timestamp,
+            symbol: self.taker_order.tradeable.clone(),
+            side: self.taker_order.direction.clone(),
+            size: parse(&self.quantity, "quantity")?,
+            price: parse(&self.price, "price")?,
+            is_block_trade: false,
+            mark_price: None,
+            index_price: None,
+            mark_iv: None,
+            iv: None,
+            raw_price: None,
+            raw_size: None,
+        })
+    }
+}
+
+/// Kraken Futures public execution history, queried by time and walked oldest first with the
+/// continuation token of each page.
+#[derive(Debug, Clone)]
+pub struct KrakenFuturesTradeSource {
+    client: Client,
+    base_url: String,
+}
+
+impl KrakenFuturesTradeSource {
+    pub fn new(base_url: String) -> Self {
+        Self {
+            client: Client::new(),
+            base_url,
+        }
+    }
+}
+
+impl TradeHistorySource for KrakenFuturesTradeSource {
+    async fn fetch_page(
+        &self,
+        symbol: &str,
+        window: Window,
+        cursor: Option<&Cursor>,
+    ) -> Result<Page, FetchError> {
+        let mut request = self
+            .client
+            .get(format!(
+                "{}/api/history/v3/market/{}/executions",
+                self.base_url, symbol
+            ))
+            .query(&[
+                ("since", window.start_time.to_string()),
+                ("before", window.end_time.saturating_add(1).to_string()),
+                ("sort", "asc".to_string()),
+            ]);
+        if let Some(cursor) = cursor {
+            request = request.query(&[("continuationToken", cursor)]);
+        }
+        let (page, _) = send_json::<KrakenExecutionsPage>(request).await?;
+        let rows = page
+            .elements
+            .iter()
+            .map(|e| e.event.execution.execution.to_row())
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let next = match rows.last() {
+            Some(newest) if newest.timestamp <= window.end_time => page.continuation_token,
+            _ => None,
+        };
+        Ok((rows, next))
+    }
+
+    fn page_delay(&self) -> Duration {
+        PAGE_DELAY
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    use super::*;
+    use crate::{bybit::RetryConfig, exchanges::fetch_trades};
+
+    fn execution_json(uid: &str, timestamp: i64, direction: &str) -> String {
+        format!(
+            r#"{{"uid":"e-{uid}","timestamp":{timestamp},"event":{{"Execution":{{"execution":{{
+                "uid":"{uid}","makerOrder":{{"uid":"m","tradeable":"PF_XBTUSD","direction":"Sell","quantity":"0.01","timestamp":{timestamp},"limitPrice":"42000.5","orderType":"Post","reduceOnly":false,"lastUpdateTimestamp":{timestamp}}},
+                "takerOrder":{{"uid":"t","tradeable":"PF_XBTUSD","direction":"{direction}","quantity":"0.01","timestamp":{timestamp},"limitPrice":"42001","orderType":"IOC","reduceOnly":false,"lastUpdateTimestamp":{timestamp}}},
+                "timestamp":{timestamp},"quantity":"0.0100","price":"42000.5","markPrice":"42000.1","limitFilled":false,"usdValue":"420.01"}},
+                "takerReducedQuantity":""}}}}}}"#
+        )
+    }
+
+    #[test]
+    fn test_kraken_execution_to_row() {
+        let page: KrakenExecutionsPage = serde_json::from_str(&format!(
+            r#"{{"elements":[{}],"len":1}}"#,
+            execution_json("a1", 1704067200123, "Buy")
+        ))
+        .unwrap();
+        assert!(page.continuation_token.is_none());
+        let row = page.elements[0].event.execution.execution.to_row().unwrap();
+        assert_eq!(row.exec_id, "a1");
+        assert_eq!(row.timestamp, 1704067200123);
+        assert_eq!(row.symbol, "PF_XBTUSD");
+        assert_eq!(row.side, "Buy");
+        assert_eq!(row.price, 42000.5);
+        assert_eq!(row.size, 0.01);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_trades_follows_continuation_token() {
+        let pages = [
+            format!(
+                r#"{{"elements":[{},{}],"len":2,"continuationToken":"tok/en=="}}"#,
+                execution_json("a", 1000, "Buy"),
+                execution_json("b", 2000, "Sell")
+            ),
+            format!(
+                r#"{{"elements":[{}],"len":1}}"#,
+                execution_json("c", 3000, "Buy")
+            ),
+        ];
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let mut requests = Vec::new();
+            for body in pages {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut buf = vec![0; 4096];
+                let n = socket.read(&mut buf).await.unwrap();
+                requests.push(String::from_utf8_lossy(&buf[..n]).to_string());
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                socket.write_all(response.as_bytes()).await.unwrap();
+            }
+            requests
+        });
+
+        let source = KrakenFuturesTradeSource::new(base_url);
+        let window = Window {
+            start_time: 1000,
+            end_time: 2999,
+        };
+        let (trades, stats) = fetch_trades(&source, &RetryConfig::default(), "PF_XBTUSD", window)
+            .await
+            .unwrap();
+        assert_eq!(
+            trades
+                .iter()
+                .map(|t| t.exec_id.as_str())
+                .collect::<Vec<_>>(),
+            vec!["a", "b"]
+        );
+        assert_eq!(stats.requests, 2);
+
+        let requests = server.await.unwrap();
+        assert!(requests[0].starts_with(
+            "GET /api/history/v3/market/PF_XBTUSD/executions?since=1000&before=3000&sort=asc "
+        ));
+        assert!(requests[1].contains("&continuationToken=tok%2Fen%3D%3D "));
+    }
+}
@@ -0,0 +1,451 @@
+//! Trade history of exchanges whose public trade endpoint pages through the history with a
+//! cursor. Each exchange implements [`TradeHistorySource`] for a single page request, and
+//! [`fetch_trade_pages`] drives the pagination with the retries, pacing and deduplication they
+//! share.
+
+use std::{
+    collections::HashSet,
+    time::{Duration, Instant},
+};
+
+use pyo3::{prelude::*, types::PyList};
+use reqwest::{RequestBuilder, StatusCode, header::HeaderMap};
+use serde::de::DeserializeOwned;
+use thiserror::Error;
+
+use crate::bybit::{FetchStats, RetryConfig, TradeRow, body_snippet, record_stats, runtime};
+
+pub mod coinbase;
+pub mod kraken;
+
+pub mod exceptions {
+    use pyo3::{create_exception, exceptions::PyRuntimeError};
+
+    create_exception!(
+        hftbacktest,
+        ExchangeError,
+        PyRuntimeError,
+        "Raised when fetching trades from an exchange with fetch_exchange_trades fails."
+    );
+}
+
+/// Exchanges [`fetch_exchange_trades`] dispatches to.
+pub const EXCHANGES: [&str; 2] = ["coinbase", "kraken_futures"];
+
+/// Timeout for a whole request, from connecting until the response body is read.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Error, Debug)]
+pub enum FetchError {
+    #[error("HTTP error: {0}")]
+    Http(StatusCode),
+    #[error("Rate limited: max retries exceeded")]
+    RateLimited,
+    #[error("API error: {code} - {msg}")]
+    Api { code: String, msg: String },
+    #[error("Failed to parse {0}")]
+    Parse(String),
+    #[error("Request failed: {0}")]
+    Transport(String),
+    #[error("Invalid argument: {0}")]
+    InvalidArg(String),
+}
+
+impl From<FetchError> for PyErr {
+    fn from(error: FetchError) -> Self {
+        exceptions::ExchangeError::new_err(error.to_string())
+    }
+}
+
+/// Time range of a fetch in milliseconds, both ends inclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Window {
+    pub start_time: i64,
+    pub end_time: i64,
+}
+
+impl Window {
+    pub fn contains(&self, timestamp: i64) -> bool {
+        timestamp >= self.start_time && timestamp <= self.end_time
+    }
+}
+
+/// Position in the trade history that the next page is requested from, as handed out by the
+/// previous page.
+pub type Cursor = String;
+
+/// The trades of a page with the cursor of the next one.
+pub type Page = (Vec<TradeRow>, Option<Cursor>);
+
+/// The page request of an exchange's trade history endpoint.
+pub trait TradeHistorySource {
+    /// Fetches the page of trades at `cursor`, or the first page of `window` if `None`, and
+    /// returns it with the cursor of the next page, `None` once the page reaches past the window
+    /// or the history runs out. Trades outside the window may be returned; they are dropped.
+    ///
+    /// A rate-limited request fails with [`FetchError::RateLimited`] and is retried by the driver,
+    /// as are transport errors and the server errors of [`RetryConfig::retries_status`].
+    async fn fetch_page(
+        &self,
+        symbol: &str,
+        window: Window,
+        cursor: Option<&Cursor>,
+    ) -> Result<Page, FetchError>;
+
+    /// Delay between consecutive page requests, keeping within the endpoint's rate limit.
+    fn page_delay(&self) -> Duration;
+}
+
+/// Walks the pages of `source` in `window` from the first one, passing each page's trades in the
+/// window to `on_page` in the order the exchange returns them. A trade repeated from the previous
+/// page or earlier on the same page, by `exec_id`, is skipped, since exchanges may return
+/// overlapping pages around the cursor.
+pub async fn fetch_trade_pages<S, F, E>(
+    source: &S,
+    retry: &RetryConfig,
+    symbol: &str,
+    window: Window,
+    mut on_page: F,
+) -> Result<FetchStats, E>
+where
+    S: TradeHistorySource,
+    F: FnMut(Vec<TradeRow>) -> Result<(), E>,
+    E: From<FetchError>,
+{
+    if window.start_time > window.end_time {
+        return Err(FetchError::InvalidArg(format!(
+            "start_time {} is after end_time {}",
+            window.start_time, window.end_time
+        ))
+        .into());
+    }
+
+    let started = Instant::now();
+    let mut stats = FetchStats::default();
+    let mut cursor = None;
+    let mut prev_page_ids = HashSet::new();
+    loop {
+        let (page, next) =
+            fetch_page_with_retry(source, retry, symbol, window, cursor.as_ref(), &mut stats)
+                .await?;
+        let mut page_ids = HashSet::with_capacity(page.len());
+        let rows = page
+            .into_iter()
+            .filter(|t| {
+                page_ids.insert(t.exec_id.clone())
+                    && !prev_page_ids.contains(&t.exec_id)
+                    && window.contains(t.timestamp)
+            })
+            .collect::<Vec<_>>();
+        stats.trades += rows.len();
+        on_page(rows)?;
+        prev_page_ids = page_ids;
+
+        match next {
+            Some(next) if cursor.as_ref() == Some(&next) => {
+                return Err(
+                    FetchError::Parse(format!("next cursor {} did not advance", next)).into(),
+                );
+            },
+            Some(next) => cursor = Some(next),
+            None => break,
+        }
+        tokio::time::sleep(source.page_delay()).await;
+    }
+
+    stats.elapsed = started.elapsed();
+    Ok(stats)
+}
+
+/// Same as [`fetch_trade_pages`], returning all trades sorted by timestamp. Trades in the same
+/// millisecond keep the order of the exchange's pages.
+pub async fn fetch_trades<S: TradeHistorySource>(
+    source: &S,
+    retry: &RetryConfig,
+    symbol: &str,
+    window: Window,
+) -> Result<(Vec<TradeRow>, FetchStats), FetchError> {
+    let mut trades = Vec::new();
+    let stats = fetch_trade_pages(source, retry, symbol, window, |page| {
+        trades.extend(page);
+        Ok::<_, FetchError>(())
+    })
+    .await?;
+    trades.sort_by_key(|t| t.timestamp);
+    Ok((trades, stats))
+}
+
+async fn fetch_page_with_retry<S: TradeHistorySource>(
+    source: &S,
+    retry: &RetryConfig,
+    symbol: &str,
+    window: Window,
+    cursor: Option<&Cursor>,
+    stats: &mut FetchStats,
+) -> Result<Page, FetchError> {
+    let mut retries = 0;
+    loop {
+        stats.requests += 1;
+        let error = match source.fetch_page(symbol, window, cursor).await {
+            Ok(page) => return Ok(page),
+            Err(error) => error,
+        };
+        let retryable = match &error {
+            FetchError::RateLimited => {
+                stats.rate_limited += 1;
+                true
+            },
+            FetchError::Transport(_) => true,
+            FetchError::Http(status) => retry.retries_status(*status),
+            _ => false,
+        };
+        if !retryable || retries >= retry.max_retries {
+            return Err(error);
+        }
+        retries += 1;
+        stats.retries += 1;
+        tokio::time::sleep(retry.backoff(retries)).await;
+    }
+}
+
+/// Sends `request` and parses the JSON body of a successful response, returning it with the
+/// response headers. A 429 is [`FetchError::RateLimited`] and any other unsuccessful status
+/// [`FetchError::Http`]. A body that fails to parse is quoted in the error.
+pub async fn send_json<T: DeserializeOwned>(
+    request: RequestBuilder,
+) -> Result<(T, HeaderMap), FetchError> {
+    let response = request
+        .timeout(REQUEST_TIMEOUT)
+        .send()
+        .await
+        .map_err(|e| FetchError::Transport(e.to_string()))?;
+    let status = response.status();
+    if status == StatusCode::TOO_MANY_REQUESTS {
+        return Err(FetchError::RateLimited);
+    }
+    if !status.is_success() {
+        return Err(FetchError::Http(status));
+    }
+    let headers = response.headers().clone();
+    let body = response
+        .text()
+        .await
+        .map_err(|e| FetchError::Transport(e.to_string()))?;
+    let body = serde_json::from_str(&body).map_err(|e| {
+        FetchError::Parse(format!("response: {}, body: {}", e, body_snippet(&body)))
+    })?;
+    Ok((body, headers))
+}
+
+/// Fetch the public trade history of an exchange between two timestamps.
+///
+/// Every exchange goes through the same pagination, which retries rate-limited and failed
+/// requests, paces the pages to the endpoint's rate limit and drops trades repeated across pages.
+/// Trades are returned in the same shape as Bybit trades from fetch_trades, sorted by timestamp.
+///
+/// Args:
+///     exchange: "coinbase" for Coinbase Exchange spot products, or "kraken_futures" for Kraken
+///               Futures contracts
+///     symbol: Coinbase product ID (e.g., "BTC-USD") or Kraken Futures symbol (e.g., "PF_XBTUSD")
+///     start_time: Start timestamp in milliseconds
+///     end_time: End timestamp in milliseconds (inclusive)
+///     max_retries: Maximum number of retries on a 429, a 5xx or a network error (default 5)
+///     base_backoff_ms: Wait before the first retry in milliseconds, doubled on each retry (default 50)
+///     max_backoff_ms: Upper bound of the wait between retries in milliseconds (default 10000)
+///     base_url: Base URL of the exchange's API (default None, the exchange's public host)
+///     side_as_int: Return side as 1 for Buy and -1 for Sell instead of the string (default False)
+///
+/// Returns:
+///     List of dicts with keys: exec_id, timestamp, symbol, side, size, price, is_block_trade
+///
+/// Raises:
+///     ExchangeError: If the exchange is unknown, the API request fails, or the rate limit is
+///                    still exceeded after max retries
+#[allow(clippy::too_many_arguments)]
+#[pyfunction]
+#[pyo3(
+    text_signature = "(exchange, symbol, start_time, end_time, *, max_retries=5, base_backoff_ms=50, max_backoff_ms=10000, base_url=None, side_as_int=False)"
+)]
+pub fn fetch_exchange_trades(
+    py: Python,
+    exchange: String,
+    symbol: String,
+    start_time: i64,
+    end_time: i64,
+    max_retries: Option<u32>,
+    base_backoff_ms: Option<u64>,
+    max_backoff_ms: Option<u64>,
+    base_url: Option<String>,
+    side_as_int: Option<bool>,
+) -> PyResult<PyObject> {
+    let retry = RetryConfig::new(max_retries, base_backoff_ms, max_backoff_ms);
+    let window = Window {
+        start_time,
+        end_time,
+    };
+
+    let rt = runtime()?;
+    let (trades, stats) = py.allow_threads(|| {
+        rt.block_on(async {
+            match exchange.as_str() {
+                "coinbase" => {
+                    let base_url = base_url.unwrap_or_else(|| coinbase::BASE_URL.to_string());
+                    let source = coinbase::CoinbaseTradeSource::new(base_url);
+                    fetch_trades(&source, &retry, &symbol, window).await
+                },
+                "kraken_futures" => {
+                    let base_url = base_url.unwrap_or_else(|| kraken::BASE_URL.to_string());
+                    let source = kraken::KrakenFuturesTradeSource::new(base_url);
+                    fetch_trades(&source, &retry, &symbol, window).await
+                },
+                _ => Err(FetchError::InvalidArg(format!(
+                    "exchange {} (expected one of {})",
+                    exchange,
+                    EXCHANGES.join(", ")
+                ))),
+            }
+        })
+    })?;
+    record_stats(stats);
+
+    let result = trades
+        .iter()
+        .map(|t| t.to_dict(py, side_as_int.unwrap_or(false), false))
+        .collect::<PyResult<Vec<_>>>()?;
+    Ok(PyList::new(py, result)?.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+
+    fn trade(exec_id: &str, timestamp: i64) -> TradeRow {
+        TradeRow {
+            exec_id: exec_id.to_string(),
+            timestamp,
+            symbol: "BTC-USD".to_string(),
+            side: "Buy".to_string(),
+            size: 0.1,
+            price: 42000.0,
+            is_block_trade: false,
+            mark_price: None,
+            index_price: None,
+            mark_iv: None,
+            iv: None,
+            raw_price: None,
+            raw_size: None,
+        }
+    }
+
+    /// Serves the scripted results in order, recording the cursor of each request.
+    struct ScriptedSource {
+        results: Mutex<Vec<Result<Page, FetchError>>>,
+        cursors: Mutex<Vec<Option<Cursor>>>,
+    }
+
+    impl ScriptedSource {
+        fn new(mut results: Vec<Result<Page, FetchError>>) -> Self {
+            results.reverse();
+            Self {
+                results: Mutex::new(results),
+                cursors: Mutex::new(Vec::new()),
+            }
+        }
+    }
+
+    impl TradeHistorySource for ScriptedSource {
+        async fn fetch_page(
+            &self,
+            _symbol: &str,
+            _window: Window,
+            cursor: Option<&Cursor>,
+        ) -> Result<Page, FetchError> {
+            self.cursors.lock().unwrap().push(cursor.cloned());
+            self.results.lock().unwrap().pop().unwrap()
+        }
+
+        fn page_delay(&self) -> Duration {
+            Duration::ZERO
+        }
+    }
+
+    #[tokio::test]
+    async fn test_driver_retries_dedups_and_sorts() {
+        let source = ScriptedSource::new(vec![
+            Ok((
+                vec![trade("d", 400), trade("c", 300), trade("c", 300)],
+                Some("c".to_string()),
+            )),
+            Err(FetchError::RateLimited),
+            Err(FetchError::Http(StatusCode::BAD_GATEWAY)),
+            // Overlaps the previous page by one trade and reaches past the window.
+            Ok((
+                vec![trade("c", 300), trade("b", 200), trade("a", 100)],
+                None,
+            )),
+        ]);
+        let retry = RetryConfig::new(Some(2), Some(1), None);
+        let window = Window {
+            start_time: 150,
+            end_time: 1000,
+        };
+
+        let (trades, stats) = fetch_trades(&source, &retry, "BTC-USD", window)
+            .await
+            .unwrap();
+        assert_eq!(
+            trades
+                .iter()
+                .map(|t| t.exec_id.as_str())
+                .collect::<Vec<_>>(),
+            vec!["b", "c", "d"]
+        );
+        assert_eq!(stats.requests, 4);
+        assert_eq!(stats.retries, 2);
+        assert_eq!(stats.rate_limited, 1);
+        assert_eq!(stats.trades, 3);
+        assert_eq!(
+            *source.cursors.lock().unwrap(),
+            vec![
+                None,
+                Some("c".to_string()),
+                Some("c".to_string()),
+                Some("c".to_string())
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_driver_gives_up() {
+        let retry = RetryConfig::new(Some(1), Some(1), None);
+        let window = Window {
+            start_time: 0,
+            end_time: 1000,
+        };
+
+        let source = ScriptedSource::new(vec![
+            Err(FetchError::RateLimited),
+            Err(FetchError::RateLimited),
+        ]);
+        let result = fetch_trades(&source, &retry, "BTC-USD", window).await;
+        assert!(matches!(result, Err(FetchError::RateLimited)));
+
+        // A 4xx is not retried.
+        let source = ScriptedSource::new(vec![Err(FetchError::Http(StatusCode::NOT_FOUND))]);
+        let result = fetch_trades(&source, &retry, "BTC-USD", window).await;
+        assert!(matches!(
+            result,
+            Err(FetchError::Http(StatusCode::NOT_FOUND))
+        ));
+
+        // A cursor that does not advance would loop forever.
+        let source = ScriptedSource::new(vec![
+            Ok((vec![trade("a", 100)], Some("a".to_string()))),
+            Ok((vec![trade("a", 100)], Some("a".to_string()))),
+        ]);
+        let result = fetch_trades(&source, &retry, "BTC-USD", window).await;
+        assert!(matches!(result, Err(FetchError::Parse(_))));
+    }
+}
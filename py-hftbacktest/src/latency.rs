@@ -0,0 +1,262 @@
+use std::io::{Error, ErrorKind};
+
+use hftbacktest::{
+    backtest::{data::read_npz_file, models::OrderLatencyRow},
+    types::{Event, LOCAL_EVENT},
+};
+use numpy::{PyArray1, PyReadonlyArray1};
+use pyo3::{exceptions::PyValueError, prelude::*};
+use rand::{Rng, SeedableRng, rngs::StdRng};
+
+#[derive(Clone, Debug)]
+enum LatencyModel {
+    Constant(i64),
+    Uniform(i64, i64),
+    Empirical(Vec<i64>),
+}
+
+/// Produces the local timestamps of feed events that only carry an exchange timestamp, such as
+/// trades fetched over REST, by adding a feed latency drawn from a constant, a uniform range, or
+/// an empirical distribution.
+///
+/// Events must be passed in exchange timestamp order. A local timestamp is never earlier than the
+/// previous one, so jitter can't reorder the events as seen by the local processor.
+#[pyclass(module = "hftbacktest")]
+#[derive(Clone, Debug)]
+pub struct LatencyInjector {
+    model: LatencyModel,
+    rng: StdRng,
+    last_local_ts: i64,
+}
+
+impl LatencyInjector {
+    fn with_model(model: LatencyModel, seed: Option<u64>) -> Self {
+        let rng = match seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_os_rng(),
+        };
+        Self {
+            model,
+            rng,
+            last_local_ts: i64::MIN,
+        }
+    }
+
+    /// Adds the same latency to every event.
+    pub fn constant(latency: i64) -> Self {
+        Self::with_model(LatencyModel::Constant(latency), Some(0))
+    }
+
+    /// Adds a latency drawn uniformly from `[min_latency, max_latency]`.
+    pub fn uniform(min_latency: i64, max_latency: i64, seed: Option<u64>) -> Result<Self, Error> {
+        if min_latency > max_latency {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("min_latency {min_latency} is greater than max_latency {max_latency}"),
+            ));
+        }
+        Ok(Self::with_model(
+            LatencyModel::Uniform(min_latency, max_latency),
+            seed,
+        ))
+    }
+
+    /// Adds a latency sampled from `samples`.
+    pub fn empirical(samples: Vec<i64>, seed: Option<u64>) -> Result<Self, Error> {
+        if samples.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "no latency samples".to_string(),
+            ));
+        }
+        Ok(Self::with_model(LatencyModel::Empirical(samples), seed))
+    }
+
+    /// Samples the latency from an order latency `.npz` file, the format read by
+    /// `IntpOrderLatency`, taking `resp_ts - exch_ts` of each row as the exchange-to-local
+    /// latency. Rows with a non-positive timestamp or latency, which mark rejected requests, are
+    /// skipped.
+    pub fn empirical_from_npz(path: &str, seed: Option<u64>) -> Result<Self, Error> {
+        let data = read_npz_file::<OrderLatencyRow>(path, "data")?;
+        let samples = (0..data.len())
+            .map(|i| &data[i])
+            .filter(|row| row.exch_ts > 0 && row.resp_ts > row.exch_ts)
+            .map(|row| row.resp_ts - row.exch_ts)
+            .collect::<Vec<_>>();
+        Self::empirical(samples, seed).map_err(|_| {
+            Error::new(
+                ErrorKind::InvalidData,
+                format!("{path} has no valid latency rows"),
+            )
+        })
+    }
+
+    fn sample(&mut self) -> i64 {
+        match &self.model {
+            LatencyModel::Constant(latency) => *latency,
+            LatencyModel::Uniform(min, max) => self.rng.random_range(*min..=*max),
+            LatencyModel::Empirical(samples) => samples[self.rng.random_range(0..samples.len())],
+        }
+    }
+
+    /// Returns the local timestamp of the next event.
+    pub fn local_ts(&mut self, exch_ts: i64) -> i64 {
+        let local_ts = (exch_ts + self.sample()).max(self.last_local_ts);
+        self.last_local_ts = local_ts;
+        local_ts
+    }
+
+    /// Sets the local timestamp of the local events among `events`.
+    pub fn inject(&mut self, events: &mut [Event]) {
+        for event in events.iter_mut() {
+            if event.ev & LOCAL_EVENT == LOCAL_EVENT {
+                event.local_ts = self.local_ts(event.exch_ts);
+            }
+        }
+    }
+}
+
+fn map_err(e: Error) -> PyErr {
+    match e.kind() {
+        ErrorKind::InvalidInput | ErrorKind::InvalidData => PyValueError::new_err(e.to_string()),
+        _ => e.into(),
+    }
+}
+
+#[pymethods]
+impl LatencyInjector {
+    /// Create an injector that adds the same latency in nanoseconds to every event.
+    #[staticmethod]
+    #[pyo3(name = "constant")]
+    fn py_constant(latency: i64) -> Self {
+        Self::constant(latency)
+    }
+
+    /// Create an injector that adds a latency in nanoseconds drawn uniformly from
+    /// [min_latency, max_latency]. Pass a seed to make the draws reproducible.
+    #[staticmethod]
+    #[pyo3(name = "uniform", signature = (min_latency, max_latency, seed = None))]
+    fn py_uniform(min_latency: i64, max_latency: i64, seed: Option<u64>) -> PyResult<Self> {
+        Self::uniform(min_latency, max_latency, seed).map_err(map_err)
+    }
+
+    /// Create an injector that samples the latency from an order latency .npz file, such as
+    /// one used with IntpOrderLatency, taking resp_ts - exch_ts of each row. Pass a seed to make
+    /// the draws reproducible.
+    #[staticmethod]
+    #[pyo3(name = "empirical", signature = (path, seed = None))]
+    fn py_empirical(path: &str, seed: Option<u64>) -> PyResult<Self> {
+        Self::empirical_from_npz(path, seed).map_err(map_err)
+    }
+
+    /// Return the local timestamps of events with the given exchange timestamps, which must be
+    /// in ascending order. Each call starts from the injector's initial state, so the same seed
+    /// gives the same result.
+    #[pyo3(name = "local_ts")]
+    fn py_local_ts<'py>(
+        &self,
+        py: Python<'py>,
+        exch_ts: PyReadonlyArray1<'py, i64>,
+    ) -> PyResult<Bound<'py, PyArray1<i64>>> {
+        let mut injector = self.clone();
+        let local_ts = exch_ts
+            .as_slice()?
+            .iter()
+            .map(|exch_ts| injector.local_ts(*exch_ts))
+            .collect::<Vec<_>>();
+        Ok(PyArray1::from_vec(py, local_ts))
+    }
+
+    fn __repr__(&self) -> String {
+        match &self.model {
+            LatencyModel::Constant(latency) => format!("LatencyInjector.constant({latency})"),
+            LatencyModel::Uniform(min, max) => format!("LatencyInjector.uniform({min}, {max})"),
+            LatencyModel::Empirical(samples) => {
+                format!("LatencyInjector.empirical(<{} samples>)", samples.len())
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_uniform_latency_is_in_range_and_monotonic() {
+        let mut injector = LatencyInjector::uniform(1_000, 50_000, Some(7)).unwrap();
+        let local_ts = (0..1000)
+            .map(|i| {
+                let exch_ts = i * 100;
+                let local_ts = injector.local_ts(exch_ts);
+                assert!(local_ts >= exch_ts + 1_000);
+                local_ts
+            })
+            .collect::<Vec<_>>();
+        assert!(local_ts.windows(2).all(|w| w[0] <= w[1]));
+
+        let mut same_seed = LatencyInjector::uniform(1_000, 50_000, Some(7)).unwrap();
+        assert_eq!(
+            (0..1000)
+                .map(|i| same_seed.local_ts(i * 100))
+                .collect::<Vec<_>>(),
+            local_ts
+        );
+
+        assert!(LatencyInjector::uniform(2, 1, None).is_err());
+    }
+
+    #[test]
+    fn test_empirical_latency_from_npz() {
+        let path = std::env::temp_dir().join(format!("latency_{}.npz", std::process::id()));
+        let path = path.to_str().unwrap();
+        let rows = [(100, 200, 300), (1000, 1100, 1600), (2000, 0, -1)].map(
+            |(req_ts, exch_ts, resp_ts)| OrderLatencyRow {
+                req_ts,
+                exch_ts,
+                resp_ts,
+                _padding: 0,
+            },
+        );
+        {
+            use std::{fs::File, io::Write};
+
+            use hftbacktest::backtest::data::write_npy_header;
+            use zip::{ZipWriter, write::SimpleFileOptions};
+
+            let mut zip = ZipWriter::new(File::create(path).unwrap());
+            zip.start_file("data.npy", SimpleFileOptions::default())
+                .unwrap();
+            write_npy_header::<_, OrderLatencyRow>(&mut zip, rows.len()).unwrap();
+            for row in &rows {
+                zip.write_all(
+                    &[row.req_ts, row.exch_ts, row.resp_ts, row._padding]
+                        .map(i64::to_le_bytes)
+                        .concat(),
+                )
+                .unwrap();
+            }
+            zip.finish().unwrap();
+        }
+
+        let mut injector = LatencyInjector::empirical_from_npz(path, Some(1)).unwrap();
+        std::fs::remove_file(path).unwrap();
+
+        let mut events = (0..100)
+            .map(|i| Event {
+                ev: LOCAL_EVENT,
+                exch_ts: i * 1000,
+                local_ts: 0,
+                px: 0.0,
+                qty: 0.0,
+                order_id: 0,
+                ival: 0,
+                fval: 0.0,
+            })
+            .collect::<Vec<_>>();
+        injector.inject(&mut events);
+        for event in &events {
+            assert!([100, 500].contains(&(event.local_ts - event.exch_ts)));
+        }
+    }
+}
@@ -7,37 +7,22 @@ pub use fuse::*;
 use hftbacktest::live::{Instrument, LiveBotBuilder};
 use hftbacktest::{
     backtest::{
-        Asset,
-        Backtest,
-        DataSource,
+        Asset, Backtest, DataSource,
         assettype::{InverseAsset, LinearAsset},
         data::{Data, DataPtr, FeedLatencyAdjustment, Reader, read_npz_file},
         models::{
-            CommonFees,
-            ConstantLatency,
-            FlatPerTradeFeeModel,
-            IntpOrderLatency,
-            L3FIFOQueueModel,
-            LogProbQueueFunc,
-            LogProbQueueFunc2,
-            OrderLatencyRow,
-            PowerProbQueueFunc,
-            PowerProbQueueFunc2,
-            PowerProbQueueFunc3,
-            ProbQueueModel,
-            RiskAdverseQueueModel,
-            TradingQtyFeeModel,
-            TradingValueFeeModel,
+            CommonFees, ConstantLatency, EmpiricalQueueModel, FlatPerTradeFeeModel,
+            FundingFeeModel, IntpOrderLatency, L3FIFOQueueModel, LatencyDistribution,
+            LogProbQueueFunc, LogProbQueueFunc2, OrderLatencyRow, PowerProbQueueFunc,
+            PowerProbQueueFunc2, PowerProbQueueFunc3, ProbQueueModel, QueueProfile,
+            QueueProfileRow, RiskAdverseQueueModel, StochasticLatency, TieredFeeModel,
+            TradingQtyFeeModel, TradingValueFeeModel, estimate_queue_profile,
         },
         order::order_bus,
         proc::{
-            L3Local,
-            L3NoPartialFillExchange,
-            Local,
-            LocalProcessor,
-            NoPartialFillExchange,
-            PartialFillExchange,
-            Processor,
+            L3Local, L3NoPartialFillExchange, Local, LocalProcessor, NoPartialFillExchange,
+            PartialFillExchange, PostOnly, Processor, SelfTradePrevention, StopOrders,
+            TriggerSource,
         },
         state::State,
     },
@@ -56,11 +41,19 @@ use pyo3::{
 use crate::live::{HashMapMarketDepthLiveBot, ROIVectorMarketDepthLiveBot};
 
 mod backtest;
+mod binance;
 mod bybit;
+mod bybit_stream;
 mod depth;
+mod deribit;
+mod exchanges;
 mod fuse;
+mod latency;
 #[cfg(feature = "live")]
 mod live;
+mod logging;
+mod merge;
+mod okx;
 mod order;
 
 #[derive(Clone)]
@@ -79,6 +72,11 @@ pub enum LatencyModel {
         data: Vec<DataSource<OrderLatencyRow>>,
         latency_offset: i64,
     },
+    StochasticLatency {
+        entry: LatencyDistribution,
+        response: LatencyDistribution,
+        seed: u64,
+    },
 }
 
 #[derive(Clone)]
@@ -90,6 +88,7 @@ pub enum QueueModel {
     PowerProbQueueModel2 { n: f64 },
     PowerProbQueueModel3 { n: f64 },
     L3FIFOQueueModel {},
+    EmpiricalQueueModel { profile: QueueProfile },
 }
 
 #[derive(Clone)]
@@ -100,9 +99,19 @@ pub enum ExchangeKind {
 
 #[derive(Clone)]
 pub enum FeeModel {
-    TradingValueFeeModel { fees: CommonFees },
-    TradingQtyFeeModel { fees: CommonFees },
-    FlatPerTradeFeeModel { fees: CommonFees },
+    TradingValueFeeModel {
+        fees: CommonFees,
+    },
+    TradingQtyFeeModel {
+        fees: CommonFees,
+    },
+    FlatPerTradeFeeModel {
+        fees: CommonFees,
+    },
+    TieredFeeModel {
+        tiers: Vec<(f64, f64, f64)>,
+        window: i64,
+    },
 }
 
 /// Builds a backtesting asset.
@@ -122,6 +131,12 @@ pub struct BacktestAsset {
     fee_model: FeeModel,
     latency_offset: i64,
     parallel_load: bool,
+    trigger_source: TriggerSource,
+    stop_slippage_ticks: i64,
+    fill_ratio: f64,
+    post_only: PostOnly,
+    self_trade_prevention: SelfTradePrevention,
+    funding: FundingFeeModel,
 }
 
 unsafe impl Send for BacktestAsset {}
@@ -153,6 +168,12 @@ impl BacktestAsset {
             },
             latency_offset: 0,
             parallel_load: true,
+            trigger_source: TriggerSource::LastTrade,
+            stop_slippage_ticks: 0,
+            fill_ratio: 1.0,
+            post_only: PostOnly::Expire,
+            self_trade_prevention: SelfTradePrevention::None,
+            funding: FundingFeeModel::default(),
         }
     }
 
@@ -323,6 +344,61 @@ impl BacktestAsset {
         slf
     }
 
+    /// Uses `StochasticLatency <https://docs.rs/hftbacktest/latest/hftbacktest/backtest/models/struct.StochasticLatency.html>`_
+    /// for the order latency model, drawing the latencies from an analytic distribution when no
+    /// historical order latency data is available. Latencies are always positive, and the
+    /// responses for an order are received in the order the exchange sent them.
+    ///
+    /// Args:
+    ///     entry_mu: For ``lognormal``, the mean of the logarithm of the order entry latency, such
+    ///               as ``math.log(1_000_000)`` for around 1ms in nanoseconds. For ``gamma``, the
+    ///               shape.
+    ///     entry_sigma: For ``lognormal``, the standard deviation of the logarithm of the order
+    ///                  entry latency. For ``gamma``, the scale, in the timestamp unit.
+    ///     resp_mu: The same as ``entry_mu``, for the order response latency.
+    ///     resp_sigma: The same as ``entry_sigma``, for the order response latency.
+    ///     seed: The seed of the random number generator, to make a backtest reproducible. If not
+    ///           given, a random seed is used.
+    ///     distribution: ``lognormal`` or ``gamma``. The default is ``lognormal``.
+    ///
+    /// Raises:
+    ///     ValueError: If the distribution is unknown or its parameters are invalid.
+    #[pyo3(signature = (entry_mu, entry_sigma, resp_mu, resp_sigma, seed=None, distribution=None))]
+    pub fn stochastic_latency(
+        mut slf: PyRefMut<Self>,
+        entry_mu: f64,
+        entry_sigma: f64,
+        resp_mu: f64,
+        resp_sigma: f64,
+        seed: Option<u64>,
+        distribution: Option<String>,
+    ) -> PyResult<PyRefMut<Self>> {
+        let distribution = distribution.as_deref().unwrap_or("lognormal");
+        let dist = |mu, sigma| match distribution {
+            "lognormal" => Ok(LatencyDistribution::LogNormal { mu, sigma }),
+            "gamma" => Ok(LatencyDistribution::Gamma {
+                shape: mu,
+                scale: sigma,
+            }),
+            _ => Err(PyValueError::new_err(format!(
+                "unknown distribution: {distribution}"
+            ))),
+        };
+        let entry = dist(entry_mu, entry_sigma)?;
+        let response = dist(resp_mu, resp_sigma)?;
+        if !entry.is_valid() || !response.is_valid() {
+            return Err(PyValueError::new_err(format!(
+                "invalid {distribution} latency parameters"
+            )));
+        }
+        slf.latency_model = LatencyModel::StochasticLatency {
+            entry,
+            response,
+            seed: seed.unwrap_or_else(rand::random),
+        };
+        Ok(slf)
+    }
+
     /// Uses the `RiskAdverseQueueModel <https://docs.rs/hftbacktest/latest/hftbacktest/backtest/models/struct.RiskAdverseQueueModel.html>`_
     /// for the queue position model.
     ///
@@ -392,6 +468,41 @@ impl BacktestAsset {
         slf
     }
 
+    /// Uses the `EmpiricalQueueModel` for the queue position model, with a queue profile estimated
+    /// by :func:`estimate_queue_profile`.
+    ///
+    /// Please find the details below.
+    ///
+    /// * `Order Fill - ProbQueueModel <https://hftbacktest.readthedocs.io/en/latest/order_fill.html#probqueuemodel>`_
+    /// * `EmpiricalQueueModel <https://docs.rs/hftbacktest/latest/hftbacktest/backtest/models/struct.EmpiricalQueueModel.html>`_
+    ///
+    /// Args:
+    ///     profile: the file path of the queue profile in `npz`.
+    ///
+    /// Raises:
+    ///     ValueError: if the queue profile can't be loaded or is invalid.
+    pub fn empirical_queue_model(
+        mut slf: PyRefMut<Self>,
+        profile: String,
+    ) -> PyResult<PyRefMut<Self>> {
+        let profile = QueueProfile::from_file(&profile)
+            .map_err(|error| PyValueError::new_err(error.to_string()))?;
+        slf.queue_model = QueueModel::EmpiricalQueueModel { profile };
+        Ok(slf)
+    }
+
+    pub fn _empirical_queue_model_ndarray(
+        mut slf: PyRefMut<Self>,
+        data: usize,
+        len: usize,
+    ) -> PyResult<PyRefMut<Self>> {
+        let rows = unsafe { std::slice::from_raw_parts(data as *const QueueProfileRow, len) };
+        let profile =
+            QueueProfile::new(rows).map_err(|error| PyValueError::new_err(error.to_string()))?;
+        slf.queue_model = QueueModel::EmpiricalQueueModel { profile };
+        Ok(slf)
+    }
+
     /// Uses the `L3FIFOQueueModel` for the queue position model.
     ///
     /// Please find the details below.
@@ -446,6 +557,100 @@ impl BacktestAsset {
         slf
     }
 
+    /// Triggers stop orders by the last trade price. This is the default.
+    pub fn last_trade_trigger(mut slf: PyRefMut<Self>) -> PyRefMut<Self> {
+        slf.trigger_source = TriggerSource::LastTrade;
+        slf
+    }
+
+    /// Triggers stop orders by the mark price, which requires mark price events in the feed data.
+    pub fn mark_price_trigger(mut slf: PyRefMut<Self>) -> PyRefMut<Self> {
+        slf.trigger_source = TriggerSource::MarkPrice;
+        slf
+    }
+
+    /// Triggers stop orders by the index price, which requires index price events in the feed
+    /// data.
+    pub fn index_price_trigger(mut slf: PyRefMut<Self>) -> PyRefMut<Self> {
+        slf.trigger_source = TriggerSource::IndexPrice;
+        slf
+    }
+
+    /// Sets the slippage, in ticks, with which a triggered stop market order is filled beyond the
+    /// best price. The default value is `0`.
+    ///
+    /// Args:
+    ///     ticks: Slippage in ticks.
+    pub fn stop_slippage(mut slf: PyRefMut<Self>, ticks: i64) -> PyRefMut<Self> {
+        slf.stop_slippage_ticks = ticks;
+        slf
+    }
+
+    /// Sets the share of the traded quantity that a resting order gets once its queue position is
+    /// consumed, with the :meth:`partial_fill_exchange`. A trade of ``qty`` at the order's price
+    /// then fills it for ``min(leaves_qty, qty * fill_ratio)``, so it can be filled over several
+    /// trades. The default value is `1.0`.
+    ///
+    /// Args:
+    ///     fill_ratio: Share of the traded quantity, in `(0, 1]`.
+    pub fn fill_ratio(mut slf: PyRefMut<Self>, fill_ratio: f64) -> PyRefMut<Self> {
+        slf.fill_ratio = fill_ratio;
+        slf
+    }
+
+    /// Expires a post-only (:const:`GTX <hftbacktest.order.GTX>`) order that would cross the
+    /// opposite best at its arrival, as Binance does. This is the default.
+    pub fn post_only_expire(mut slf: PyRefMut<Self>) -> PyRefMut<Self> {
+        slf.post_only = PostOnly::Expire;
+        slf
+    }
+
+    /// Rejects a post-only (:const:`GTX <hftbacktest.order.GTX>`) order that would cross the
+    /// opposite best at its arrival with the :const:`REJECTED <hftbacktest.order.REJECTED>` status,
+    /// as Bybit does.
+    pub fn post_only_reject(mut slf: PyRefMut<Self>) -> PyRefMut<Self> {
+        slf.post_only = PostOnly::Reject;
+        slf
+    }
+
+    /// Reprices a post-only (:const:`GTX <hftbacktest.order.GTX>`) order that would cross the
+    /// opposite best at its arrival one tick away from the opposite best, so that it rests in the
+    /// book.
+    pub fn post_only_reprice(mut slf: PyRefMut<Self>) -> PyRefMut<Self> {
+        slf.post_only = PostOnly::Reprice;
+        slf
+    }
+
+    /// Executes an order that would take liquidity at its arrival regardless of our own resting
+    /// orders it would trade against. This is the default.
+    pub fn no_self_trade_prevention(mut slf: PyRefMut<Self>) -> PyRefMut<Self> {
+        slf.self_trade_prevention = SelfTradePrevention::None;
+        slf
+    }
+
+    /// Cancels our own resting orders that an order taking liquidity at its arrival would trade
+    /// against, and then executes the taking order, as ``CancelMaker`` on Bybit and
+    /// ``EXPIRE_MAKER`` on Binance do.
+    pub fn self_trade_prevention_cancel_maker(mut slf: PyRefMut<Self>) -> PyRefMut<Self> {
+        slf.self_trade_prevention = SelfTradePrevention::CancelMaker;
+        slf
+    }
+
+    /// Cancels an order that would take liquidity at its arrival and trade against our own
+    /// resting orders, leaving the resting orders in the book, as ``CancelTaker`` on Bybit and
+    /// ``EXPIRE_TAKER`` on Binance do.
+    pub fn self_trade_prevention_cancel_taker(mut slf: PyRefMut<Self>) -> PyRefMut<Self> {
+        slf.self_trade_prevention = SelfTradePrevention::CancelTaker;
+        slf
+    }
+
+    /// Cancels both an order that would take liquidity at its arrival and our own resting orders
+    /// it would trade against, as ``CancelBoth`` on Bybit and ``EXPIRE_BOTH`` on Binance do.
+    pub fn self_trade_prevention_cancel_both(mut slf: PyRefMut<Self>) -> PyRefMut<Self> {
+        slf.self_trade_prevention = SelfTradePrevention::CancelBoth;
+        slf
+    }
+
     /// Sets the initial capacity of the vector storing the last market trades.
     /// The default value is `0`, indicating that no last trades are stored.
     pub fn last_trades_capacity(mut slf: PyRefMut<Self>, capacity: usize) -> PyRefMut<Self> {
@@ -491,17 +696,140 @@ impl BacktestAsset {
         };
         slf
     }
+
+    /// Uses `TieredFeeModel <https://docs.rs/hftbacktest/latest/hftbacktest/backtest/models/struct.TieredFeeModel.html>`_,
+    /// whose maker and taker rates step down as the traded value over a rolling window grows, like
+    /// the VIP tiers of crypto exchanges. Once a fill takes the rolling volume over a threshold,
+    /// the fees change from the next fill onward. A negative fee represents rebates.
+    ///
+    /// Args:
+    ///     tiers: ``(volume_threshold, maker_bps, taker_bps)`` tuples, with the rates in basis
+    ///            points of the trading value. For example, ``[(0, 2, 5.5), (10_000_000, 1.8, 4)]``.
+    ///     window: The length of the rolling volume window in nanoseconds. The default is 30 days.
+    #[pyo3(signature = (tiers, window=30 * 24 * 60 * 60 * 1_000_000_000))]
+    pub fn tiered_fee_model(
+        mut slf: PyRefMut<Self>,
+        tiers: Vec<(f64, f64, f64)>,
+        window: i64,
+    ) -> PyRefMut<Self> {
+        slf.fee_model = FeeModel::TieredFeeModel { tiers, window };
+        slf
+    }
+
+    /// Charges the funding payments of a perpetual swap, using `FundingFeeModel <https://docs.rs/hftbacktest/latest/hftbacktest/backtest/models/struct.FundingFeeModel.html>`_.
+    /// At each funding timestamp, the position held pays ``position * mark_price * rate`` for a
+    /// linear asset, which is accumulated in :attr:`funding <hftbacktest.state.StateValues.funding>`
+    /// separately from trading fees. Funding timestamps outside the backtest data are not charged.
+    ///
+    /// Args:
+    ///     funding_rates: ``(timestamp, rate)`` pairs, with timestamps in nanoseconds. For example,
+    ///                    ``[(f["timestamp"] * 1_000_000, f["funding_rate"]) for f in funding]``
+    ///                    from the millisecond timestamps of Bybit's funding history.
+    ///     mark_prices: ``(timestamp, mark_price)`` pairs, with timestamps in nanoseconds, to value
+    ///                  the payments at. If not given, the mid-price at the funding timestamp is
+    ///                  used.
+    #[pyo3(signature = (funding_rates, mark_prices=None))]
+    pub fn funding_fee_model(
+        mut slf: PyRefMut<Self>,
+        funding_rates: Vec<(i64, f64)>,
+        mark_prices: Option<Vec<(i64, f64)>>,
+    ) -> PyRefMut<Self> {
+        let funding = FundingFeeModel::new(funding_rates);
+        slf.funding = match mark_prices {
+            Some(mark_prices) => funding.mark_prices(mark_prices),
+            None => funding,
+        };
+        slf
+    }
 }
 
 #[pymodule]
 fn _hftbacktest(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(build_hashmap_backtest, m)?)?;
     m.add_function(wrap_pyfunction!(build_roivec_backtest, m)?)?;
+    m.add_function(wrap_pyfunction!(_estimate_queue_profile, m)?)?;
     #[cfg(feature = "live")]
     m.add_function(wrap_pyfunction!(build_hashmap_livebot, m)?)?;
     #[cfg(feature = "live")]
     m.add_function(wrap_pyfunction!(build_roivec_livebot, m)?)?;
     m.add_function(wrap_pyfunction!(bybit::fetch_trades, m)?)?;
+    m.add_function(wrap_pyfunction!(bybit::count_trades, m)?)?;
+    m.add_function(wrap_pyfunction!(bybit::fetch_trades_page, m)?)?;
+    m.add_function(wrap_pyfunction!(bybit::replay_raw_dump, m)?)?;
+    m.add_function(wrap_pyfunction!(bybit::fetch_trades_async, m)?)?;
+    m.add_function(wrap_pyfunction!(bybit::fetch_trades_numpy, m)?)?;
+    m.add_function(wrap_pyfunction!(bybit::fetch_trades_polars, m)?)?;
+    m.add_function(wrap_pyfunction!(bybit::fetch_trades_chunked, m)?)?;
+    m.add_function(wrap_pyfunction!(bybit::fetch_trades_concurrent, m)?)?;
+    m.add_function(wrap_pyfunction!(bybit::fetch_trades_multi, m)?)?;
+    m.add_function(wrap_pyfunction!(bybit::fetch_trades_to_parquet, m)?)?;
+    m.add_function(wrap_pyfunction!(bybit::fetch_trades_to_csv, m)?)?;
+    m.add_function(wrap_pyfunction!(bybit::fetch_trades_to_npz, m)?)?;
+    m.add_function(wrap_pyfunction!(bybit::fetch_trades_daily, m)?)?;
+    m.add_function(wrap_pyfunction!(bybit::fetch_trades_aggregated, m)?)?;
+    m.add_class::<bybit::DaySummary>()?;
+    m.add_function(wrap_pyfunction!(bybit::fetch_klines, m)?)?;
+    m.add_function(wrap_pyfunction!(bybit::fetch_klines_numpy, m)?)?;
+    m.add_function(wrap_pyfunction!(bybit::fetch_funding, m)?)?;
+    m.add("fetch_funding_history", m.getattr("fetch_funding")?)?;
+    m.add_function(wrap_pyfunction!(bybit::fetch_open_interest, m)?)?;
+    m.add_function(wrap_pyfunction!(bybit::fetch_long_short_ratio, m)?)?;
+    m.add_function(wrap_pyfunction!(bybit::fetch_instruments, m)?)?;
+    m.add_function(wrap_pyfunction!(bybit::fetch_orderbook, m)?)?;
+    m.add_function(wrap_pyfunction!(bybit::fetch_recent_trades, m)?)?;
+    m.add_function(wrap_pyfunction!(bybit::last_fetch_stats, m)?)?;
+    m.add_function(wrap_pyfunction!(logging::init_logging, m)?)?;
+    m.add_class::<bybit::FetchStats>()?;
+    m.add_class::<bybit::BybitFetcher>()?;
+    m.add_class::<bybit::FetchConfig>()?;
+    m.add_function(wrap_pyfunction!(bybit::validate_trades, m)?)?;
+    m.add_class::<bybit::TradeReport>()?;
+    m.add_function(wrap_pyfunction!(bybit_stream::record_trades, m)?)?;
+    m.add_function(wrap_pyfunction!(bybit_stream::record_liquidations, m)?)?;
+    m.add_function(wrap_pyfunction!(bybit_stream::record_depth, m)?)?;
+    m.add_class::<bybit_stream::RecordSummary>()?;
+    m.add(
+        "BybitError",
+        m.py().get_type::<bybit::exceptions::BybitError>(),
+    )?;
+    m.add(
+        "BybitRateLimitError",
+        m.py().get_type::<bybit::exceptions::BybitRateLimitError>(),
+    )?;
+    m.add(
+        "BybitApiError",
+        m.py().get_type::<bybit::exceptions::BybitApiError>(),
+    )?;
+    m.add(
+        "BybitUnknownSymbolError",
+        m.py()
+            .get_type::<bybit::exceptions::BybitUnknownSymbolError>(),
+    )?;
+    m.add(
+        "FetchInterrupted",
+        m.py().get_type::<bybit::exceptions::FetchInterrupted>(),
+    )?;
+    m.add_function(wrap_pyfunction!(binance::fetch_binance_trades, m)?)?;
+    m.add(
+        "BinanceError",
+        m.py().get_type::<binance::exceptions::BinanceError>(),
+    )?;
+    m.add_function(wrap_pyfunction!(okx::fetch_okx_trades, m)?)?;
+    m.add("OkxError", m.py().get_type::<okx::exceptions::OkxError>())?;
+    m.add_function(wrap_pyfunction!(deribit::fetch_deribit_trades, m)?)?;
+    m.add_function(wrap_pyfunction!(deribit::fetch_deribit_instruments, m)?)?;
+    m.add(
+        "DeribitError",
+        m.py().get_type::<deribit::exceptions::DeribitError>(),
+    )?;
+    m.add_function(wrap_pyfunction!(exchanges::fetch_exchange_trades, m)?)?;
+    m.add(
+        "ExchangeError",
+        m.py().get_type::<exchanges::exceptions::ExchangeError>(),
+    )?;
+    m.add_function(wrap_pyfunction!(merge::merge_event_files, m)?)?;
+    m.add_class::<merge::MergeSummary>()?;
+    m.add_class::<latency::LatencyInjector>()?;
     m.add_class::<BacktestAsset>()?;
     m.add_class::<LiveInstrument>()?;
     Ok(())
@@ -513,6 +841,21 @@ type PowerProbQueueModelFunc = PowerProbQueueFunc;
 type PowerProbQueueModel2Func = PowerProbQueueFunc2;
 type PowerProbQueueModel3Func = PowerProbQueueFunc3;
 
+#[pyfunction]
+pub fn _estimate_queue_profile(
+    data: usize,
+    len: usize,
+    tick_size: f64,
+    levels: usize,
+    bins: usize,
+) -> Vec<(i64, f64, f64)> {
+    let events = unsafe { std::slice::from_raw_parts(data as *const Event, len) };
+    estimate_queue_profile(events, tick_size, levels, bins)
+        .into_iter()
+        .map(|row| (row.level, row.position, row.prob_ahead))
+        .collect()
+}
+
 #[pyfunction]
 pub fn build_hashmap_backtest(assets: Vec<PyRefMut<BacktestAsset>>) -> PyResult<usize> {
     let mut local = Vec::new();
@@ -526,6 +869,11 @@ pub fn build_hashmap_backtest(assets: Vec<PyRefMut<BacktestAsset>>) -> PyResult<
                 "L3PartialFillExchange is unsupported.",
             ));
         }
+        if !(asset.fill_ratio > 0.0 && asset.fill_ratio <= 1.0) {
+            return PyResult::Err(PyErr::new::<PyValueError, _>(
+                "fill_ratio must be in (0, 1].",
+            ));
+        }
 
         let asst = build_asset!(
             asset,
@@ -542,6 +890,11 @@ pub fn build_hashmap_backtest(assets: Vec<PyRefMut<BacktestAsset>>) -> PyResult<
                 IntpOrderLatency {
                     data,
                     latency_offset
+                },
+                StochasticLatency {
+                    entry,
+                    response,
+                    seed
                 }
             ],
             [
@@ -551,13 +904,15 @@ pub fn build_hashmap_backtest(assets: Vec<PyRefMut<BacktestAsset>>) -> PyResult<
                 PowerProbQueueModel { n },
                 PowerProbQueueModel2 { n },
                 PowerProbQueueModel3 { n },
-                L3FIFOQueueModel {}
+                L3FIFOQueueModel {},
+                EmpiricalQueueModel { profile }
             ],
             [NoPartialFillExchange {}, PartialFillExchange {}],
             [
                 TradingValueFeeModel { fees },
                 TradingQtyFeeModel { fees },
                 FlatPerTradeFeeModel { fees },
+                TieredFeeModel { tiers, window },
             ]
         );
         local.push(asst.local);
@@ -583,6 +938,11 @@ pub fn build_roivec_backtest(assets: Vec<PyRefMut<BacktestAsset>>) -> PyResult<u
                 "L3PartialFillExchange is unsupported.",
             ));
         }
+        if !(asset.fill_ratio > 0.0 && asset.fill_ratio <= 1.0) {
+            return PyResult::Err(PyErr::new::<PyValueError, _>(
+                "fill_ratio must be in (0, 1].",
+            ));
+        }
 
         let asst = build_asset!(
             asset,
@@ -599,6 +959,11 @@ pub fn build_roivec_backtest(assets: Vec<PyRefMut<BacktestAsset>>) -> PyResult<u
                 IntpOrderLatency {
                     data,
                     latency_offset
+                },
+                StochasticLatency {
+                    entry,
+                    response,
+                    seed
                 }
             ],
             [
@@ -608,13 +973,15 @@ pub fn build_roivec_backtest(assets: Vec<PyRefMut<BacktestAsset>>) -> PyResult<u
                 PowerProbQueueModel { n },
                 PowerProbQueueModel2 { n },
                 PowerProbQueueModel3 { n },
-                L3FIFOQueueModel {}
+                L3FIFOQueueModel {},
+                EmpiricalQueueModel { profile }
             ],
             [NoPartialFillExchange {}, PartialFillExchange {}],
             [
                 TradingValueFeeModel { fees },
                 TradingQtyFeeModel { fees },
                 FlatPerTradeFeeModel { fees },
+                TieredFeeModel { tiers, window },
             ]
         );
         local.push(asst.local);
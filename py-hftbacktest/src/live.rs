@@ -139,6 +139,8 @@ pub extern "C" fn hashmaplive_submit_buy_order(
     qty: f64,
     time_in_force: u8,
     order_type: u8,
+    trigger_price: f64,
+    display_qty: f64,
     wait: bool,
 ) -> i64 {
     let hbt = unsafe { &mut *hbt_ptr };
@@ -150,6 +152,8 @@ pub extern "C" fn hashmaplive_submit_buy_order(
         qty,
         tif,
         unsafe { mem::transmute::<u8, OrdType>(order_type) },
+        trigger_price,
+        display_qty,
         wait,
     ))
 }
@@ -163,6 +167,8 @@ pub extern "C" fn hashmaplive_submit_sell_order(
     qty: f64,
     time_in_force: u8,
     order_type: u8,
+    trigger_price: f64,
+    display_qty: f64,
     wait: bool,
 ) -> i64 {
     let hbt = unsafe { &mut *hbt_ptr };
@@ -173,6 +179,8 @@ pub extern "C" fn hashmaplive_submit_sell_order(
         qty,
         unsafe { mem::transmute::<u8, TimeInForce>(time_in_force) },
         unsafe { mem::transmute::<u8, OrdType>(order_type) },
+        trigger_price,
+        display_qty,
         wait,
     ))
 }
@@ -382,6 +390,8 @@ pub extern "C" fn roiveclive_submit_buy_order(
     qty: f64,
     time_in_force: u8,
     order_type: u8,
+    trigger_price: f64,
+    display_qty: f64,
     wait: bool,
 ) -> i64 {
     let hbt = unsafe { &mut *hbt_ptr };
@@ -393,6 +403,8 @@ pub extern "C" fn roiveclive_submit_buy_order(
         qty,
         tif,
         unsafe { mem::transmute::<u8, OrdType>(order_type) },
+        trigger_price,
+        display_qty,
         wait,
     ))
 }
@@ -406,6 +418,8 @@ pub extern "C" fn roiveclive_submit_sell_order(
     qty: f64,
     time_in_force: u8,
     order_type: u8,
+    trigger_price: f64,
+    display_qty: f64,
     wait: bool,
 ) -> i64 {
     let hbt = unsafe { &mut *hbt_ptr };
@@ -416,6 +430,8 @@ pub extern "C" fn roiveclive_submit_sell_order(
         qty,
         unsafe { mem::transmute::<u8, TimeInForce>(time_in_force) },
         unsafe { mem::transmute::<u8, OrdType>(order_type) },
+        trigger_price,
+        display_qty,
         wait,
     ))
 }
@@ -0,0 +1,50 @@
+use std::{str::FromStr, sync::OnceLock};
+
+use pyo3::{
+    exceptions::{PyRuntimeError, PyValueError},
+    prelude::*,
+};
+use tracing_subscriber::{
+    Registry, filter::LevelFilter, fmt, layer::SubscriberExt, reload, util::SubscriberInitExt,
+};
+
+/// Handle of the level filter installed by the first [`init_logging`] call, through which later
+/// calls change the level.
+static LEVEL: OnceLock<reload::Handle<LevelFilter, Registry>> = OnceLock::new();
+
+/// Writes the log events of the fetchers at `level` and above to stderr.
+///
+/// At debug level, every request is logged with its URL, response status and any backoff before
+/// it is retried, and every page with its size and the cursor of the next one. API keys and
+/// request signatures are never logged.
+///
+/// Calling it again changes the level; `"off"` disables logging.
+///
+/// Args:
+///     level: One of "trace", "debug", "info", "warn", "error" or "off". Default: "info".
+///
+/// Raises:
+///     ValueError: If `level` is not one of the above.
+///     RuntimeError: If another logger is already installed in the process.
+#[pyfunction]
+#[pyo3(signature = (level = None), text_signature = "(level='info')")]
+pub fn init_logging(level: Option<&str>) -> PyResult<()> {
+    let level = level.unwrap_or("info");
+    let filter = LevelFilter::from_str(level)
+        .map_err(|_| PyValueError::new_err(format!("invalid log level: {}", level)))?;
+
+    if let Some(handle) = LEVEL.get() {
+        return handle
+            .reload(filter)
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()));
+    }
+
+    let (filter, handle) = reload::Layer::new(filter);
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(fmt::layer().with_writer(std::io::stderr))
+        .try_init()
+        .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+    let _ = LEVEL.set(handle);
+    Ok(())
+}
@@ -0,0 +1,417 @@
+use std::{
+    cmp::Reverse,
+    collections::BinaryHeap,
+    fs::File,
+    io::{BufWriter, Error, ErrorKind, Read, Write},
+};
+
+use hftbacktest::{
+    backtest::data::{read_npy_header, write_npy_header},
+    types::{
+        DEPTH_BBO_EVENT, DEPTH_CLEAR_EVENT, DEPTH_EVENT, DEPTH_SNAPSHOT_EVENT, Event, LOCAL_EVENT,
+        TRADE_EVENT,
+    },
+};
+use pyo3::{exceptions::PyValueError, prelude::*};
+use zip::{ZipArchive, ZipWriter, read::ZipFile, write::SimpleFileOptions};
+
+/// Number of events read from each input, and written to the output, at a time.
+const BLOCK_EVENTS: usize = 16384;
+
+const EMPTY_EVENT: Event = Event {
+    ev: 0,
+    exch_ts: 0,
+    local_ts: 0,
+    px: 0.0,
+    qty: 0.0,
+    order_id: 0,
+    ival: 0,
+    fval: 0.0,
+};
+
+fn events_as_bytes(events: &[Event]) -> &[u8] {
+    // Event is a `repr(C)` plain old data struct without padding.
+    unsafe { std::slice::from_raw_parts(events.as_ptr() as *const u8, size_of_val(events)) }
+}
+
+fn events_as_bytes_mut(events: &mut [Event]) -> &mut [u8] {
+    unsafe { std::slice::from_raw_parts_mut(events.as_mut_ptr() as *mut u8, size_of_val(events)) }
+}
+
+/// Reads the `data` array of an `.npz` event file one block at a time, checking that it is sorted
+/// by exchange timestamp and that the local timestamps of its [`LOCAL_EVENT`]s don't decrease.
+struct EventBlocks<'a> {
+    path: &'a str,
+    npy: ZipFile<'a, File>,
+    remaining: usize,
+    block: Vec<Event>,
+    pos: usize,
+    last_exch_ts: i64,
+    last_local_ts: i64,
+}
+
+impl<'a> EventBlocks<'a> {
+    fn open(path: &'a str, archive: &'a mut ZipArchive<File>) -> std::io::Result<Self> {
+        let mut npy = archive.by_name("data.npy")?;
+        let remaining = read_npy_header::<_, Event>(&mut npy)
+            .map_err(|e| Error::new(e.kind(), format!("{}: {}", path, e)))?;
+        Ok(Self {
+            path,
+            npy,
+            remaining,
+            block: Vec::new(),
+            pos: 0,
+            last_exch_ts: i64::MIN,
+            last_local_ts: i64::MIN,
+        })
+    }
+
+    fn next(&mut self) -> std::io::Result<Option<Event>> {
+        if self.pos == self.block.len() {
+            if self.remaining == 0 {
+                return Ok(None);
+            }
+            let n = self.remaining.min(BLOCK_EVENTS);
+            self.remaining -= n;
+            self.block.clear();
+            self.block.resize(n, EMPTY_EVENT);
+            self.npy.read_exact(events_as_bytes_mut(&mut self.block))?;
+            self.pos = 0;
+        }
+        let event = self.block[self.pos].clone();
+        self.pos += 1;
+        if event.exch_ts < self.last_exch_ts {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "{} is not sorted by exch_ts: {} follows {}",
+                    self.path, event.exch_ts, self.last_exch_ts
+                ),
+            ));
+        }
+        self.last_exch_ts = event.exch_ts;
+        if event.ev & LOCAL_EVENT == LOCAL_EVENT {
+            if event.local_ts < self.last_local_ts {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    format!(
+                        "{} is not sorted by local_ts: {} follows {}",
+                        self.path, event.local_ts, self.last_local_ts
+                    ),
+                ));
+            }
+            self.last_local_ts = event.local_ts;
+        }
+        Ok(Some(event))
+    }
+}
+
+/// Number of events written by [`merge_files`], by event type.
+#[pyclass(module = "hftbacktest", get_all)]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MergeSummary {
+    pub events: usize,
+    pub depth: usize,
+    pub depth_clear: usize,
+    pub depth_snapshot: usize,
+    pub depth_bbo: usize,
+    pub trade: usize,
+    pub other: usize,
+    /// Number of [`LOCAL_EVENT`]s whose local timestamp is earlier than that of a local event
+    /// before them in the output, which happens when the inputs have different feed latencies.
+    pub local_ts_reversals: usize,
+}
+
+impl MergeSummary {
+    fn count(&mut self, event: &Event) {
+        self.events += 1;
+        match event.ev & 0xff {
+            DEPTH_EVENT => self.depth += 1,
+            DEPTH_CLEAR_EVENT => self.depth_clear += 1,
+            DEPTH_SNAPSHOT_EVENT => self.depth_snapshot += 1,
+            DEPTH_BBO_EVENT => self.depth_bbo += 1,
+            TRADE_EVENT => self.trade += 1,
+            _ => self.other += 1,
+        }
+    }
+}
+
+#[pymethods]
+impl MergeSummary {
+    fn __repr__(&self) -> String {
+        format!(
+            "MergeSummary(events={}, depth={}, depth_clear={}, depth_snapshot={}, depth_bbo={}, \
+             trade={}, other={}, local_ts_reversals={})",
+            self.events,
+            self.depth,
+            self.depth_clear,
+            self.depth_snapshot,
+            self.depth_bbo,
+            self.trade,
+            self.other,
+            self.local_ts_reversals
+        )
+    }
+}
+
+/// Orders events of different inputs that share an exchange timestamp: depth events come before
+/// trades, or after them if `trades_first` is set, and anything else comes last.
+fn tiebreak_rank(event: &Event, trades_first: bool) -> u8 {
+    match (event.ev & 0xff, trades_first) {
+        (TRADE_EVENT, false) => 1,
+        (TRADE_EVENT, true) => 0,
+        (DEPTH_EVENT | DEPTH_CLEAR_EVENT | DEPTH_SNAPSHOT_EVENT | DEPTH_BBO_EVENT, false) => 0,
+        (DEPTH_EVENT | DEPTH_CLEAR_EVENT | DEPTH_SNAPSHOT_EVENT | DEPTH_BBO_EVENT, true) => 1,
+        _ => 2,
+    }
+}
+
+/// Merges the `data` arrays of `.npz` event files, each sorted by `exch_ts`, into a single `.npz`
+/// file sorted by `exch_ts`. The inputs are read in blocks, so they don't need to fit in memory.
+///
+/// Events sharing an exchange timestamp keep their order within an input; across inputs they are
+/// ordered by [`tiebreak_rank`], then by the position of the input in `inputs`. On error, the
+/// output file is removed.
+pub fn merge_files(
+    inputs: &[String],
+    output: &str,
+    trades_first: bool,
+) -> std::io::Result<MergeSummary> {
+    let result = write_merged(inputs, output, trades_first);
+    if result.is_err() {
+        let _ = std::fs::remove_file(output);
+    }
+    result
+}
+
+fn write_merged(
+    inputs: &[String],
+    output: &str,
+    trades_first: bool,
+) -> std::io::Result<MergeSummary> {
+    let mut archives = inputs
+        .iter()
+        .map(|path| {
+            File::open(path)
+                .map_err(|e| Error::new(e.kind(), format!("{}: {}", path, e)))
+                .and_then(|file| Ok(ZipArchive::new(file)?))
+        })
+        .collect::<std::io::Result<Vec<_>>>()?;
+    let mut readers = inputs
+        .iter()
+        .zip(archives.iter_mut())
+        .map(|(path, archive)| EventBlocks::open(path, archive))
+        .collect::<std::io::Result<Vec<_>>>()?;
+    let total = readers.iter().map(|r| r.remaining).sum::<usize>();
+
+    let mut heads = Vec::with_capacity(readers.len());
+    let mut heap = BinaryHeap::with_capacity(readers.len());
+    for (i, reader) in readers.iter_mut().enumerate() {
+        let head = reader.next()?;
+        if let Some(event) = &head {
+            heap.push(Reverse((
+                event.exch_ts,
+                tiebreak_rank(event, trades_first),
+                i,
+            )));
+        }
+        heads.push(head);
+    }
+
+    let mut zip = ZipWriter::new(BufWriter::new(File::create(output)?));
+    let options = SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::DEFLATE)
+        .large_file(total * size_of::<Event>() > u32::MAX as usize);
+    zip.start_file("data.npy", options)?;
+    write_npy_header::<_, Event>(&mut zip, total)?;
+
+    let mut summary = MergeSummary::default();
+    let mut block = Vec::with_capacity(BLOCK_EVENTS);
+    let mut last_local_ts = i64::MIN;
+    while let Some(Reverse((_, _, i))) = heap.pop() {
+        let event = heads[i].take().unwrap();
+        heads[i] = readers[i].next()?;
+        if let Some(next) = &heads[i] {
+            heap.push(Reverse((
+                next.exch_ts,
+                tiebreak_rank(next, trades_first),
+                i,
+            )));
+        }
+
+        if event.ev & LOCAL_EVENT == LOCAL_EVENT {
+            if event.local_ts < last_local_ts {
+                summary.local_ts_reversals += 1;
+            }
+            last_local_ts = last_local_ts.max(event.local_ts);
+        }
+        summary.count(&event);
+        block.push(event);
+        if block.len() == BLOCK_EVENTS {
+            zip.write_all(events_as_bytes(&block))?;
+            block.clear();
+        }
+    }
+    zip.write_all(events_as_bytes(&block))?;
+    zip.finish()?.flush()?;
+    Ok(summary)
+}
+
+/// Merge hftbacktest event files into a single file sorted by exchange timestamp.
+///
+/// Each input is an .npz file with the events under the `data` key, sorted by exch_ts with the
+/// local_ts of its local events in order too, such as a trade file and a depth file of the same
+/// symbol and day. The inputs are merged in blocks, so they can be larger than memory, and the
+/// result is written to output as an .npz file.
+///
+/// Events sharing an exchange timestamp keep their order within a file. Across files, depth
+/// events come before trades unless trades_first is set, and ties that remain are broken by the
+/// order of inputs.
+///
+/// Inputs recorded with different feed latencies can interleave so that the local_ts of the
+/// merged local events goes backwards. The number of such events is reported as
+/// local_ts_reversals; if it isn't zero, pass the merged data through correct_event_order before
+/// backtesting.
+///
+/// Args:
+///     inputs: Paths of the .npz files to merge
+///     output: Path of the .npz file to write
+///     trades_first: Put trades before depth events that share their exchange timestamp
+///                   (default False)
+///
+/// Returns:
+///     MergeSummary with the total number of events, the number of depth, depth_clear,
+///     depth_snapshot, depth_bbo, trade and other events, and local_ts_reversals
+///
+/// Raises:
+///     ValueError: If an input isn't an event array sorted by exch_ts and local_ts. The output
+///                 file is removed
+///     OSError: If a file can't be read or written
+#[pyfunction]
+#[pyo3(text_signature = "(inputs, output, *, trades_first=False)")]
+pub fn merge_event_files(
+    py: Python,
+    inputs: Vec<String>,
+    output: String,
+    trades_first: Option<bool>,
+) -> PyResult<MergeSummary> {
+    py.allow_threads(|| merge_files(&inputs, &output, trades_first.unwrap_or(false)))
+        .map_err(|e| match e.kind() {
+            ErrorKind::InvalidData => PyValueError::new_err(e.to_string()),
+            _ => e.into(),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use hftbacktest::{
+        backtest::data::read_npz_file,
+        types::{BUY_EVENT, EXCH_EVENT, SELL_EVENT},
+    };
+
+    use super::*;
+
+    fn write_events(path: &str, events: &[Event]) {
+        let mut zip = ZipWriter::new(File::create(path).unwrap());
+        zip.start_file("data.npy", SimpleFileOptions::default())
+            .unwrap();
+        write_npy_header::<_, Event>(&mut zip, events.len()).unwrap();
+        zip.write_all(events_as_bytes(events)).unwrap();
+        zip.finish().unwrap();
+    }
+
+    fn event(ev: u64, exch_ts: i64, local_ts: i64) -> Event {
+        Event {
+            ev: EXCH_EVENT | LOCAL_EVENT | ev,
+            exch_ts,
+            local_ts,
+            ..EMPTY_EVENT
+        }
+    }
+
+    fn temp_path(name: &str) -> String {
+        std::env::temp_dir()
+            .join(name)
+            .to_str()
+            .unwrap()
+            .to_string()
+    }
+
+    #[test]
+    fn test_merge_event_files() {
+        let trades = temp_path("test_merge_trades.npz");
+        let depth = temp_path("test_merge_depth.npz");
+        let output = temp_path("test_merge_output.npz");
+        write_events(
+            &trades,
+            &[
+                event(TRADE_EVENT | BUY_EVENT, 10, 11),
+                event(TRADE_EVENT | SELL_EVENT, 20, 21),
+            ],
+        );
+        // More than one block, so block boundaries are crossed.
+        let mut depth_events = vec![event(DEPTH_CLEAR_EVENT, 0, 1)];
+        depth_events.extend(
+            (0..BLOCK_EVENTS + 10)
+                .map(|i| event(DEPTH_EVENT | BUY_EVENT, 20 + i as i64, 21 + i as i64)),
+        );
+        write_events(&depth, &depth_events);
+
+        let inputs = [trades.clone(), depth.clone()];
+        let summary = merge_files(&inputs, &output, false).unwrap();
+        assert_eq!(
+            summary,
+            MergeSummary {
+                events: BLOCK_EVENTS + 13,
+                depth: BLOCK_EVENTS + 10,
+                depth_clear: 1,
+                trade: 2,
+                ..Default::default()
+            }
+        );
+        let data = read_npz_file::<Event>(&output, "data").unwrap();
+        assert_eq!(data.len(), BLOCK_EVENTS + 13);
+        assert!(data[0].is(DEPTH_CLEAR_EVENT));
+        assert!(data[1].is(TRADE_EVENT));
+        // The depth event at 20 comes before the trade at 20.
+        assert!(data[2].is(DEPTH_EVENT) && data[2].exch_ts == 20);
+        assert!(data[3].is(TRADE_EVENT) && data[3].exch_ts == 20);
+        assert!((1..data.len()).all(|i| data[i - 1].exch_ts <= data[i].exch_ts));
+
+        merge_files(&inputs, &output, true).unwrap();
+        let data = read_npz_file::<Event>(&output, "data").unwrap();
+        assert!(data[2].is(TRADE_EVENT) && data[2].exch_ts == 20);
+
+        for path in [trades, depth, output] {
+            std::fs::remove_file(path).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_merge_event_files_checks_input_order() {
+        let unsorted = temp_path("test_merge_unsorted.npz");
+        let late = temp_path("test_merge_late.npz");
+        let sorted = temp_path("test_merge_sorted.npz");
+        let output = temp_path("test_merge_checked.npz");
+        write_events(
+            &unsorted,
+            &[event(DEPTH_EVENT, 10, 21), event(DEPTH_EVENT, 20, 11)],
+        );
+        // Sorted, but received much later than the other input's events.
+        write_events(&late, &[event(TRADE_EVENT, 5, 100)]);
+        write_events(&sorted, &[event(DEPTH_EVENT, 10, 11)]);
+
+        let err = merge_files(std::slice::from_ref(&unsorted), &output, false).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert!(err.to_string().contains("not sorted by local_ts"));
+        assert!(!std::path::Path::new(&output).exists());
+
+        let summary = merge_files(&[sorted.clone(), late.clone()], &output, false).unwrap();
+        assert_eq!(summary.events, 2);
+        assert_eq!(summary.local_ts_reversals, 1);
+
+        for path in [unsorted, late, sorted, output] {
+            std::fs::remove_file(path).unwrap();
+        }
+    }
+}
@@ -0,0 +1,401 @@
+use std::time::Duration;
+
+use pyo3::{prelude::*, types::PyList};
+use reqwest::{Client, StatusCode};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{
+    bybit::{FetchStats, RetryConfig, TradeRow, record_stats, runtime},
+    exchanges::{self, Cursor, FetchError, Page, TradeHistorySource, Window, send_json},
+};
+
+pub mod exceptions {
+    use pyo3::{create_exception, exceptions::PyRuntimeError};
+
+    create_exception!(
+        hftbacktest,
+        OkxError,
+        PyRuntimeError,
+        "Raised when fetching data from OKX fails."
+    );
+}
+
+#[derive(Error, Debug)]
+pub enum OkxError {
+    #[error("HTTP error: {0}")]
+    Http(StatusCode),
+    #[error("Rate limited: max retries exceeded")]
+    RateLimited,
+    #[error("API error: {code} - {msg}")]
+    Api { code: String, msg: String },
+    #[error("Failed to parse {0}")]
+    Parse(String),
+    #[error("Request failed: {0}")]
+    Transport(String),
+    #[error("Invalid argument: {0}")]
+    InvalidArg(String),
+}
+
+impl From<FetchError> for OkxError {
+    fn from(error: FetchError) -> Self {
+        match error {
+            FetchError::Http(status) => OkxError::Http(status),
+            FetchError::RateLimited => OkxError::RateLimited,
+            FetchError::Api { code, msg } => OkxError::Api { code, msg },
+            FetchError::Parse(msg) => OkxError::Parse(msg),
+            FetchError::Transport(msg) => OkxError::Transport(msg),
+            FetchError::InvalidArg(msg) => OkxError::InvalidArg(msg),
+        }
+    }
+}
+
+impl From<OkxError> for PyErr {
+    fn from(error: OkxError) -> Self {
+        exceptions::OkxError::new_err(error.to_string())
+    }
+}
+
+/// OKX REST host.
+pub const BASE_URL: &str = "https://www.okx.com";
+
+/// Maximum number of trades the history-trades endpoint returns per page.
+pub const HISTORY_TRADES_PAGE_LIMIT: usize = 100;
+
+/// OKX's error code for exceeding the request rate, sent with HTTP 429.
+const RATE_LIMIT_CODE: &str = "50011";
+
+/// Delay between consecutive page requests, keeping within the endpoint's 20 requests per 2
+/// seconds.
+const PAGE_DELAY: Duration = Duration::from_millis(100);
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OkxResponse<T> {
+    pub code: String,
+    pub msg: String,
+    pub data: T,
+}
+
+/// A trade as returned by `/api/v5/market/history-trades`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OkxTrade {
+    #[serde(rename = "instId")]
+    pub inst_id: String,
+    #[serde(rename = "tradeId")]
+    pub trade_id: String,
+    pub px: String,
+    pub sz: String,
+    /// Taker side, `buy` or `sell`.
+    pub side: String,
+    pub ts: String,
+}
+
+impl OkxTrade {
+    pub fn to_row(&self) -> Result<TradeRow, FetchError> {
+        Ok(TradeRow {
+            exec_id: self.trade_id.clone(),
+            timestamp: self
+                .ts
+                .parse()
+                .map_err(|_| FetchError::Parse(format!("ts: {}", self.ts)))?,
+            symbol: self.inst_id.clone(),
+            side: match self.side.as_str() {
+                "buy" => "Buy".to_string(),
+                "sell" => "Sell".to_string(),
+                side => side.to_string(),
+            },
+            size: self
+                .sz
+                .parse()
+                .map_err(|_| FetchError::Parse(format!("sz: {}", self.sz)))?,
+            price: self
+                .px
+                .parse()
+                .map_err(|_| FetchError::Parse(format!("px: {}", self.px)))?,
+            is_block_trade: false,
+            mark_price: None,
+            index_price: None,
+            mark_iv: None,
+            iv: None,
+            raw_price: None,
+            raw_size: None,
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct OkxTradeHistoryFetcher {
+    client: Client,
+    base_url: String,
+    retry: RetryConfig,
+}
+
+impl OkxTradeHistoryFetcher {
+    pub fn new(base_url: String) -> Self {
+        Self {
+            client: Client::new(),
+            base_url,
+            retry: RetryConfig::default(),
+        }
+    }
+
+    /// Sets the retry policy applied to rate-limited, failed and timed-out requests.
+    pub fn with_retry(mut self, retry: RetryConfig) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Fetches the trades in `[start_time, end_time]`, passing each page to `on_page`, newest
+    /// first.
+    ///
+    /// The first page is located by timestamp, ending at `end_time`; from there pages are walked
+    /// backwards by trade ID until a page reaches past `start_time` or the history runs out.
+    pub async fn fetch_trade_pages<F>(
+        &self,
+        inst_id: &str,
+        start_time: i64,
+        end_time: i64,
+        on_page: F,
+    ) -> Result<FetchStats, OkxError>
+    where
+        F: FnMut(Vec<TradeRow>) -> Result<(), OkxError>,
+    {
+        let window = Window {
+            start_time,
+            end_time,
+        };
+        exchanges::fetch_trade_pages(self, &self.retry, inst_id, window, on_page).await
+    }
+}
+
+impl TradeHistorySource for OkxTradeHistoryFetcher {
+    async fn fetch_page(
+        &self,
+        inst_id: &str,
+        window: Window,
+        cursor: Option<&Cursor>,
+    ) -> Result<Page, FetchError> {
+        // `after` returns records older than the given trade ID, or timestamp with type=2.
+        let query_string = match cursor {
+            Some(trade_id) => format!(
+                "instId={}&type=1&after={}&limit={}",
+                inst_id, trade_id, HISTORY_TRADES_PAGE_LIMIT
+            ),
+            None => format!(
+                "instId={}&type=2&after={}&limit={}",
+                inst_id,
+                window.end_time + 1,
+                HISTORY_TRADES_PAGE_LIMIT
+            ),
+        };
+        let request = self.client.get(format!(
+            "{}/api/v5/market/history-trades?{}",
+            self.base_url, query_string
+        ));
+        let (body, _) = send_json::<OkxResponse<Vec<OkxTrade>>>(request).await?;
+        if body.code == RATE_LIMIT_CODE {
+            return Err(FetchError::RateLimited);
+        }
+        if body.code != "0" {
+            return Err(FetchError::Api {
+                code: body.code,
+                msg: body.msg,
+            });
+        }
+        let rows = body
+            .data
+            .iter()
+            .map(OkxTrade::to_row)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let next = match rows.last() {
+            Some(oldest)
+                if oldest.timestamp >= window.start_time
+                    && rows.len() == HISTORY_TRADES_PAGE_LIMIT =>
+            {
+                Some(oldest.exec_id.clone())
+            },
+            _ => None,
+        };
+        Ok((rows, next))
+    }
+
+    fn page_delay(&self) -> Duration {
+        PAGE_DELAY
+    }
+}
+
+/// Fetch OKX public trade history between two timestamps.
+///
+/// Trades are returned in the same shape as Bybit trades from fetch_trades, newest first, with the
+/// trade ID as exec_id.
+///
+/// Args:
+///     inst_id: Instrument ID (e.g., "BTC-USDT-SWAP")
+///     start_time: Start timestamp in milliseconds
+///     end_time: End timestamp in milliseconds (inclusive)
+///     max_retries: Maximum number of retries on a 429, a 5xx or a network error (default 5)
+///     base_backoff_ms: Wait before the first retry in milliseconds, doubled on each retry (default 50)
+///     max_backoff_ms: Upper bound of the wait between retries in milliseconds (default 10000)
+///     base_url: Base URL for the OKX API (default "https://www.okx.com")
+///     side_as_int: Return side as 1 for Buy and -1 for Sell instead of the string (default False)
+///
+/// Returns:
+///     List of dicts with keys: exec_id, timestamp, symbol, side, size, price, is_block_trade
+///
+/// Raises:
+///     OkxError: If the API request fails or the rate limit is still exceeded after max retries
+#[allow(clippy::too_many_arguments)]
+#[pyfunction]
+#[pyo3(
+    text_signature = "(inst_id, start_time, end_time, *, max_retries=5, base_backoff_ms=50, max_backoff_ms=10000, base_url='https://www.okx.com', side_as_int=False)"
+)]
+pub fn fetch_okx_trades(
+    py: Python,
+    inst_id: String,
+    start_time: i64,
+    end_time: i64,
+    max_retries: Option<u32>,
+    base_backoff_ms: Option<u64>,
+    max_backoff_ms: Option<u64>,
+    base_url: Option<String>,
+    side_as_int: Option<bool>,
+) -> PyResult<PyObject> {
+    if start_time >= end_time {
+        return Err(OkxError::InvalidArg(format!(
+            "start_time {} must be before end_time {}",
+            start_time, end_time
+        ))
+        .into());
+    }
+    let fetcher =
+        OkxTradeHistoryFetcher::new(base_url.unwrap_or_else(|| BASE_URL.to_string())).with_retry(
+            RetryConfig::new(max_retries, base_backoff_ms, max_backoff_ms),
+        );
+
+    let rt = runtime()?;
+    let mut trades = Vec::new();
+    let stats = py.allow_threads(|| {
+        rt.block_on(
+            fetcher.fetch_trade_pages(&inst_id, start_time, end_time, |page| {
+                trades.extend(page);
+                Ok(())
+            }),
+        )
+    })?;
+    record_stats(stats);
+
+    let result = trades
+        .iter()
+        .map(|t| t.to_dict(py, side_as_int.unwrap_or(false), false))
+        .collect::<PyResult<Vec<_>>>()?;
+    Ok(PyList::new(py, result)?.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    use super::*;
+
+    fn trade_json(trade_id: i64, ts: i64) -> String {
+        format!(
+            r#"{{"instId":"BTC-USDT","tradeId":"{}","px":"42000.5","sz":"0.01","side":"{}","ts":"{}"}}"#,
+            trade_id,
+            if trade_id % 2 == 0 { "buy" } else { "sell" },
+            ts
+        )
+    }
+
+    /// Serves history-trades pages from a fixed history of trade IDs `1..=250`, where trade `i` has
+    /// timestamp `1000 + 10 * i`, honouring `after` as a trade ID or, with `type=2`, a timestamp.
+    async fn serve_history(listener: TcpListener) {
+        loop {
+            let Ok((mut socket, _)) = listener.accept().await else {
+                return;
+            };
+            let mut buf = vec![0; 4096];
+            let n = socket.read(&mut buf).await.unwrap();
+            let request = String::from_utf8_lossy(&buf[..n]).to_string();
+            let query = request
+                .split_whitespace()
+                .nth(1)
+                .and_then(|path| path.split_once('?'))
+                .map(|(_, query)| query.to_string())
+                .unwrap_or_default();
+            let param = |name: &str| {
+                query
+                    .split('&')
+                    .find_map(|kv| kv.strip_prefix(&format!("{}=", name)))
+                    .map(str::to_string)
+            };
+            let after: i64 = param("after").unwrap().parse().unwrap();
+            let newest_id = if param("type").as_deref() == Some("2") {
+                // Trades strictly older than the timestamp.
+                (after - 1000 - 1) / 10
+            } else {
+                after - 1
+            };
+            let trades = (1..=newest_id.min(250))
+                .rev()
+                .take(HISTORY_TRADES_PAGE_LIMIT)
+                .map(|i| trade_json(i, 1000 + 10 * i))
+                .collect::<Vec<_>>();
+            let body = format!(r#"{{"code":"0","msg":"","data":[{}]}}"#, trades.join(","));
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            socket.write_all(response.as_bytes()).await.unwrap();
+        }
+    }
+
+    #[test]
+    fn test_okx_trade_to_row() {
+        let json = r#"{"code":"0","msg":"","data":[
+            {"instId":"BTC-USDT-SWAP","tradeId":"130639474","px":"42219.9","sz":"0.12",
+             "side":"buy","ts":"1630048897897"}
+        ]}"#;
+        let response: OkxResponse<Vec<OkxTrade>> = serde_json::from_str(json).unwrap();
+        let row = response.data[0].to_row().unwrap();
+        assert_eq!(row.exec_id, "130639474");
+        assert_eq!(row.timestamp, 1630048897897);
+        assert_eq!(row.symbol, "BTC-USDT-SWAP");
+        assert_eq!(row.side, "Buy");
+        assert_eq!(row.price, 42219.9);
+        assert_eq!(row.size, 0.12);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_trade_pages_walks_backwards() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(serve_history(listener));
+
+        let fetcher = OkxTradeHistoryFetcher::new(base_url);
+        let mut pages = Vec::new();
+        // Trades 20..=230 fall in the range; the newest is found by timestamp and the rest by ID.
+        let stats = fetcher
+            .fetch_trade_pages("BTC-USDT", 1200, 3300, |page| {
+                pages.push(page);
+                Ok(())
+            })
+            .await
+            .unwrap();
+
+        let ids = pages
+            .iter()
+            .flatten()
+            .map(|t| t.exec_id.parse::<i64>().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(ids, (20..=230).rev().collect::<Vec<_>>());
+        // 230..=131, 130..=31, then 30..=1, which reaches past the start.
+        assert_eq!(stats.requests, 3);
+        assert_eq!(pages.len(), 3);
+        assert_eq!(stats.trades, ids.len());
+    }
+}
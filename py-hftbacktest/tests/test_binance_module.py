@@ -0,0 +1,47 @@
+"""Test Binance module API contract and structure."""
+
+import unittest
+from unittest.mock import patch
+
+
+class TestBinanceModuleStructure(unittest.TestCase):
+    """Test that the Binance module is properly structured."""
+
+    def test_exception_hierarchy(self):
+        """Test that BinanceError can be caught as RuntimeError."""
+        try:
+            from hftbacktest.binance import BinanceError
+
+            self.assertTrue(issubclass(BinanceError, RuntimeError))
+
+        except ImportError:
+            self.skipTest("hftbacktest not installed")
+
+    def test_fetch_binance_trades_parameter_passing(self):
+        """Test that all parameters are passed to the underlying function."""
+        try:
+            from hftbacktest.binance import fetch_binance_trades
+
+            with patch("hftbacktest.binance._hftbacktest") as mock_hftbacktest:
+                mock_hftbacktest.fetch_binance_trades.return_value = []
+
+                fetch_binance_trades("BTCUSDT", 1000, 2000, limit=500, max_retries=3)
+
+                mock_hftbacktest.fetch_binance_trades.assert_called_once_with(
+                    "BTCUSDT",
+                    1000,
+                    2000,
+                    limit=500,
+                    max_retries=3,
+                    base_backoff_ms=50,
+                    max_backoff_ms=10000,
+                    base_url="https://fapi.binance.com",
+                    side_as_int=False,
+                )
+
+        except ImportError:
+            self.skipTest("hftbacktest not installed")
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -1,7 +1,7 @@
 """Test Bybit module API contract and structure."""
 
 import unittest
-from unittest.mock import patch, MagicMock
+from unittest.mock import patch, AsyncMock, MagicMock, call
 
 
 class TestBybitModuleStructure(unittest.TestCase):
@@ -39,12 +39,14 @@ class TestBybitModuleStructure(unittest.TestCase):
 
             # Check keyword parameters
             self.assertIn("limit", params)
+            self.assertIn("category", params)
             self.assertIn("api_key", params)
             self.assertIn("secret", params)
             self.assertIn("base_url", params)
 
             # Check defaults
             self.assertEqual(sig.parameters["limit"].default, 1000)
+            self.assertEqual(sig.parameters["category"].default, "linear")
             self.assertEqual(sig.parameters["api_key"].default, "")
             self.assertEqual(sig.parameters["secret"].default, "")
             self.assertEqual(sig.parameters["base_url"].default, "https://api.bybit.com")
@@ -117,6 +119,38 @@ class TestBybitModuleStructure(unittest.TestCase):
         except ImportError:
             self.skipTest("hftbacktest not installed")
 
+    def test_exception_hierarchy(self):
+        """Test that Bybit exceptions can be caught specifically or as RuntimeError."""
+        try:
+            from hftbacktest.bybit import (
+                BybitApiError,
+                BybitError,
+                BybitRateLimitError,
+                BybitUnknownSymbolError,
+                FetchInterrupted,
+            )
+
+            self.assertTrue(issubclass(BybitError, RuntimeError))
+            self.assertTrue(issubclass(BybitRateLimitError, BybitError))
+            self.assertTrue(issubclass(BybitApiError, BybitError))
+            self.assertTrue(issubclass(BybitUnknownSymbolError, BybitApiError))
+            self.assertTrue(issubclass(FetchInterrupted, BybitError))
+
+        except ImportError:
+            self.skipTest("hftbacktest not installed")
+
+    def test_bybit_fetcher_is_exported(self):
+        """Test that the reusable client is exported."""
+        try:
+            import hftbacktest.bybit
+            from hftbacktest.bybit import BybitFetcher
+
+            self.assertIn("BybitFetcher", hftbacktest.bybit.__all__)
+            self.assertTrue(callable(BybitFetcher))
+
+        except ImportError:
+            self.skipTest("hftbacktest not installed")
+
     def test_fetch_trades_parameter_passing(self):
         """Test that all parameters are passed to the underlying function."""
         try:
@@ -130,25 +164,952 @@ class TestBybitModuleStructure(unittest.TestCase):
                     1000,
                     2000,
                     limit=500,
+                    category="inverse",
                     api_key="test_key",
                     secret="test_secret",
+                    recv_window=5000,
+                    max_retries=5,
+                    base_backoff_ms=50,
+                    max_backoff_ms=10000,
+                    page_delay_ms=50,
                     base_url="https://testnet.bybit.com",
+                    testnet=False,
+                    proxy="socks5://127.0.0.1:1080",
+                    compress=True,
+                    connect_timeout_ms=3000,
+                    timeout_ms=30000,
+                    tcp_keepalive_ms=60000,
+                    max_idle_connections=4,
+                    user_agent="research/1.0",
+                    extra_headers={"X-Client-Id": "research-1"},
+                    cursor="abc%3D%3D",
+                    progress=None,
+                    strict_range=True,
+                    strict=True,
+                    deterministic=True,
+                    partial=True,
+                    include_block_trades=False,
+                    min_size=0.5,
+                    side_as_int=True,
+                    include_turnover=True,
+                    raw_dump_path="/tmp/raw.ndjson.zst",
+                    cache_dir="/tmp/trades",
+                    force_refresh=True,
+                    time_unit="us",
                 )
 
+                mock_hftbacktest.validate_trades.assert_not_called()
+
                 # Verify the underlying function was called with correct parameters
                 mock_hftbacktest.fetch_trades.assert_called_once_with(
                     "ETHUSDT",
                     1000,
                     2000,
                     limit=500,
+                    category="inverse",
                     api_key="test_key",
                     secret="test_secret",
+                    recv_window=5000,
+                    max_retries=5,
+                    base_backoff_ms=50,
+                    max_backoff_ms=10000,
+                    page_delay_ms=50,
                     base_url="https://testnet.bybit.com",
+                    testnet=False,
+                    proxy="socks5://127.0.0.1:1080",
+                    compress=True,
+                    connect_timeout_ms=3000,
+                    timeout_ms=30000,
+                    tcp_keepalive_ms=60000,
+                    max_idle_connections=4,
+                    user_agent="research/1.0",
+                    extra_headers={"X-Client-Id": "research-1"},
+                    cursor="abc%3D%3D",
+                    progress=None,
+                    strict_range=True,
+                    strict=True,
+                    deterministic=True,
+                    partial=True,
+                    include_block_trades=False,
+                    min_size=0.5,
+                    side_as_int=True,
+                    include_turnover=True,
+                    raw_dump_path="/tmp/raw.ndjson.zst",
+                    cache_dir="/tmp/trades",
+                    force_refresh=True,
+                    time_unit="us",
+                    config=None,
+                )
+
+        except ImportError:
+            self.skipTest("hftbacktest not installed")
+
+    def test_count_trades(self):
+        """Test that count_trades forwards its arguments and returns the count as is."""
+        try:
+            from hftbacktest.bybit import count_trades
+
+            with patch("hftbacktest.bybit._hftbacktest") as mock_hftbacktest:
+                mock_hftbacktest.count_trades.return_value = 1234
+                config = MagicMock()
+
+                count = count_trades(
+                    "BTCUSDT", 1000, 2000, min_size=0.5, time_unit="s", config=config
+                )
+
+                self.assertEqual(count, 1234)
+                mock_hftbacktest.count_trades.assert_called_once_with(
+                    "BTCUSDT",
+                    1000,
+                    2000,
+                    cursor=None,
+                    progress=None,
+                    partial=False,
+                    config=config,
+                    min_size=0.5,
+                    time_unit="s",
+                )
+
+        except ImportError:
+            self.skipTest("hftbacktest not installed")
+
+    def test_fetch_trades_config(self):
+        """Test that only the arguments changed from their default are passed with a config."""
+        try:
+            from hftbacktest.bybit import fetch_trades
+
+            with patch("hftbacktest.bybit._hftbacktest") as mock_hftbacktest:
+                mock_hftbacktest.fetch_trades.return_value = []
+                config = MagicMock()
+
+                fetch_trades("ETHUSDT", 1000, 2000, category="spot", config=config)
+
+                mock_hftbacktest.fetch_trades.assert_called_once_with(
+                    "ETHUSDT",
+                    1000,
+                    2000,
+                    cursor=None,
+                    progress=None,
+                    partial=False,
+                    raw_dump_path=None,
+                    config=config,
+                    category="spot",
+                )
+
+        except ImportError:
+            self.skipTest("hftbacktest not installed")
+
+    def test_replay_raw_dump_parameter_passing(self):
+        """Test that replay_raw_dump forwards the dump path."""
+        try:
+            from hftbacktest.bybit import replay_raw_dump
+
+            with patch("hftbacktest.bybit._hftbacktest") as mock_hftbacktest:
+                replay_raw_dump("/tmp/raw.ndjson.zst")
+
+                mock_hftbacktest.replay_raw_dump.assert_called_once_with(
+                    "/tmp/raw.ndjson.zst", side_as_int=False
+                )
+
+        except ImportError:
+            self.skipTest("hftbacktest not installed")
+
+    def test_fetch_trades_validate(self):
+        """Test that validate=True attaches the validation report to the result."""
+        try:
+            from hftbacktest.bybit import ValidatedTrades, fetch_trades
+
+            trades = [{"timestamp": 1000, "side": "Buy", "size": 0.1, "price": 42000.0}]
+            with patch("hftbacktest.bybit._hftbacktest") as mock_hftbacktest:
+                mock_hftbacktest.fetch_trades.return_value = trades
+                report = MagicMock()
+                mock_hftbacktest.validate_trades.return_value = report
+
+                result = fetch_trades("BTCUSDT", 1000, 2000, validate=True)
+
+                self.assertIsInstance(result, ValidatedTrades)
+                self.assertEqual(list(result), trades)
+                self.assertIs(result.report, report)
+                mock_hftbacktest.validate_trades.assert_called_once_with(trades)
+
+        except ImportError:
+            self.skipTest("hftbacktest not installed")
+
+    def test_fetch_trades_async(self):
+        """Test that fetch_trades_async awaits the extension and returns its trades."""
+        try:
+            import asyncio
+            from hftbacktest.bybit import fetch_trades_async
+
+            with patch("hftbacktest.bybit._hftbacktest") as mock_hftbacktest:
+                trades = [{"timestamp": 1500, "price": 42000.0}]
+                mock_hftbacktest.fetch_trades_async = AsyncMock(return_value=trades)
+
+                result = asyncio.run(fetch_trades_async("BTCUSDT", 1000, 2000, timeout_ms=5000))
+
+                self.assertEqual(result, trades)
+                mock_hftbacktest.fetch_trades_async.assert_awaited_once_with(
+                    "BTCUSDT",
+                    1000,
+                    2000,
+                    limit=1000,
+                    category="linear",
+                    api_key="",
+                    secret="",
+                    recv_window=5000,
+                    max_retries=5,
+                    base_backoff_ms=50,
+                    max_backoff_ms=10000,
+                    page_delay_ms=50,
+                    base_url="https://api.bybit.com",
+                    testnet=False,
+                    proxy=None,
+                    compress=True,
+                    connect_timeout_ms=None,
+                    timeout_ms=5000,
+                    tcp_keepalive_ms=None,
+                    max_idle_connections=None,
+                    user_agent=None,
+                    extra_headers=None,
+                    strict_range=False,
+                    strict=False,
+                    side_as_int=False,
+                    include_turnover=False,
+                    config=None,
+                )
+
+        except ImportError:
+            self.skipTest("hftbacktest not installed")
+
+    def test_fetch_trades_async_cancellation(self):
+        """Test that cancelling fetch_trades_async cancels the awaited extension call."""
+        try:
+            import asyncio
+            from hftbacktest.bybit import fetch_trades_async
+
+            cancelled = []
+
+            async def fetch(*args, **kwargs):
+                try:
+                    await asyncio.sleep(10)
+                except asyncio.CancelledError:
+                    cancelled.append(True)
+                    raise
+
+            async def main():
+                task = asyncio.ensure_future(fetch_trades_async("BTCUSDT", 1000, 2000))
+                await asyncio.sleep(0)
+                task.cancel()
+                with self.assertRaises(asyncio.CancelledError):
+                    await task
+
+            with patch("hftbacktest.bybit._hftbacktest") as mock_hftbacktest:
+                mock_hftbacktest.fetch_trades_async = fetch
+                asyncio.run(main())
+
+            self.assertEqual(cancelled, [True])
+
+        except ImportError:
+            self.skipTest("hftbacktest not installed")
+
+    def test_fetch_trades_numpy_parameter_passing(self):
+        """Test that fetch_trades_numpy forwards all parameters."""
+        try:
+            from hftbacktest.bybit import fetch_trades_numpy
+
+            with patch("hftbacktest.bybit._hftbacktest") as mock_hftbacktest:
+                fetch_trades_numpy("ETHUSDT", 1000, 2000, limit=500)
+
+                mock_hftbacktest.fetch_trades_numpy.assert_called_once_with(
+                    "ETHUSDT",
+                    1000,
+                    2000,
+                    limit=500,
+                    category="linear",
+                    api_key="",
+                    secret="",
+                    recv_window=5000,
+                    max_retries=5,
+                    base_backoff_ms=50,
+                    max_backoff_ms=10000,
+                    page_delay_ms=50,
+                    base_url="https://api.bybit.com",
+                    testnet=False,
+                    proxy=None,
+                    compress=True,
+                    cursor=None,
+                    progress=None,
+                    strict_range=False,
+                    strict=False,
+                    partial=False,
+                    include_block_trades=True,
+                    min_size=None,
+                    cache_dir=None,
+                    force_refresh=False,
+                    time_unit=None,
+                    config=None,
+                )
+
+        except ImportError:
+            self.skipTest("hftbacktest not installed")
+
+    def test_fetch_trades_polars(self):
+        """Test that fetch_trades_polars forwards its arguments to the Rust function."""
+        try:
+            from hftbacktest.bybit import fetch_trades_polars
+
+            with patch("hftbacktest.bybit._hftbacktest") as mock_hftbacktest:
+                mock_hftbacktest.fetch_trades_polars.return_value = "frame"
+                df = fetch_trades_polars("BTCUSDT", 1000, 2000, limit=500, time_unit="us")
+
+                self.assertEqual(df, "frame")
+                mock_hftbacktest.fetch_trades_polars.assert_called_once_with(
+                    "BTCUSDT",
+                    1000,
+                    2000,
+                    cursor=None,
+                    progress=None,
+                    partial=False,
+                    time_unit="us",
+                    config=None,
+                    limit=500,
+                    category="linear",
+                    api_key="",
+                    secret="",
+                    recv_window=5000,
+                    max_retries=5,
+                    base_backoff_ms=50,
+                    max_backoff_ms=10000,
+                    page_delay_ms=50,
+                    base_url="https://api.bybit.com",
+                    testnet=False,
+                    proxy=None,
+                    compress=True,
+                    strict_range=False,
+                    strict=False,
+                    include_block_trades=True,
+                    min_size=None,
+                    cache_dir=None,
+                    force_refresh=False,
+                )
+
+        except ImportError:
+            self.skipTest("hftbacktest not installed")
+
+    def test_fetch_trades_chunked_parameter_passing(self):
+        """Test that fetch_trades_chunked forwards the callback and window size."""
+        try:
+            from hftbacktest.bybit import fetch_trades_chunked
+
+            with patch("hftbacktest.bybit._hftbacktest") as mock_hftbacktest:
+                mock_hftbacktest.fetch_trades_chunked.return_value = 0
+                callback = MagicMock()
+
+                count = fetch_trades_chunked("BTCUSDT", 1000, 2000, 100, callback)
+
+                self.assertEqual(count, 0)
+                mock_hftbacktest.fetch_trades_chunked.assert_called_once_with(
+                    "BTCUSDT",
+                    1000,
+                    2000,
+                    100,
+                    callback,
+                    limit=1000,
+                    category="linear",
+                    api_key="",
+                    secret="",
+                    recv_window=5000,
+                    max_retries=5,
+                    base_backoff_ms=50,
+                    max_backoff_ms=10000,
+                    page_delay_ms=50,
+                    base_url="https://api.bybit.com",
+                    testnet=False,
+                    proxy=None,
+                    compress=True,
+                    side_as_int=False,
+                    time_unit=None,
+                    config=None,
+                )
+
+        except ImportError:
+            self.skipTest("hftbacktest not installed")
+
+    def test_fetch_trades_to_parquet_parameter_passing(self):
+        """Test that fetch_trades_to_parquet forwards the writer options and returns the tuple."""
+        try:
+            from hftbacktest.bybit import fetch_trades_to_parquet
+
+            with patch("hftbacktest.bybit._hftbacktest") as mock_hftbacktest:
+                mock_hftbacktest.fetch_trades_to_parquet.return_value = (3, 1024)
+
+                rows, size = fetch_trades_to_parquet(
+                    "BTCUSDT", 1000, 2000, "out.parquet", compression="zstd", row_group_size=500
+                )
+
+                self.assertEqual((rows, size), (3, 1024))
+                call = mock_hftbacktest.fetch_trades_to_parquet.call_args
+                self.assertEqual(call.args, ("BTCUSDT", 1000, 2000, "out.parquet"))
+                self.assertEqual(call.kwargs["compression"], "zstd")
+                self.assertEqual(call.kwargs["row_group_size"], 500)
+
+        except ImportError:
+            self.skipTest("hftbacktest not installed")
+
+    def test_fetch_trades_to_csv_parameter_passing(self):
+        """Test that fetch_trades_to_csv forwards the append flag."""
+        try:
+            from hftbacktest.bybit import fetch_trades_to_csv
+
+            with patch("hftbacktest.bybit._hftbacktest") as mock_hftbacktest:
+                mock_hftbacktest.fetch_trades_to_csv.return_value = 3
+
+                count = fetch_trades_to_csv("BTCUSDT", 1000, 2000, "out.csv", append=True)
+
+                self.assertEqual(count, 3)
+                call = mock_hftbacktest.fetch_trades_to_csv.call_args
+                self.assertEqual(call.args, ("BTCUSDT", 1000, 2000, "out.csv"))
+                self.assertTrue(call.kwargs["append"])
+
+        except ImportError:
+            self.skipTest("hftbacktest not installed")
+
+    def test_fetch_trades_daily_parameter_passing(self):
+        """Test that fetch_trades_daily passes dates as ISO strings and paths as str."""
+        try:
+            import datetime
+            import pathlib
+            from hftbacktest.bybit import fetch_trades_daily
+
+            with patch("hftbacktest.bybit._hftbacktest") as mock_hftbacktest:
+                fetch_trades_daily(
+                    "BTCUSDT",
+                    datetime.date(2024, 1, 1),
+                    "2024-01-07",
+                    pathlib.Path("data"),
+                    "parquet",
+                    overwrite=True,
+                )
+
+                mock_hftbacktest.fetch_trades_daily.assert_called_once_with(
+                    "BTCUSDT",
+                    "2024-01-01",
+                    "2024-01-07",
+                    "data",
+                    "parquet",
+                    overwrite=True,
+                    feed_latency=0,
+                    config=None,
+                )
+
+        except ImportError:
+            self.skipTest("hftbacktest not installed")
+
+    def test_fetch_trades_to_npz_parameter_passing(self):
+        """Test that fetch_trades_to_npz forwards the latency injector and tick grid."""
+        try:
+            from hftbacktest.bybit import LatencyInjector, fetch_trades_to_npz
+
+            latency = LatencyInjector.uniform(1_000_000, 5_000_000, seed=1)
+            with patch("hftbacktest.bybit._hftbacktest") as mock_hftbacktest:
+                fetch_trades_to_npz(
+                    "BTCUSDT", 1000, 2000, "trades.npz", latency=latency, tick_size=0.1
+                )
+
+                mock_hftbacktest.fetch_trades_to_npz.assert_called_once_with(
+                    "BTCUSDT",
+                    1000,
+                    2000,
+                    "trades.npz",
+                    feed_latency=0,
+                    latency=latency,
+                    tick_size=0.1,
+                    lot_size=None,
+                    limit=1000,
+                    category="linear",
+                    api_key="",
+                    secret="",
+                    recv_window=5000,
+                    max_retries=5,
+                    base_backoff_ms=50,
+                    max_backoff_ms=10000,
+                    page_delay_ms=50,
+                    base_url="https://api.bybit.com",
+                    testnet=False,
+                    proxy=None,
+                    compress=True,
+                    time_unit=None,
+                )
+
+        except ImportError:
+            self.skipTest("hftbacktest not installed")
+
+    def test_fetch_klines_numpy_parameter_passing(self):
+        """Test that fetch_klines_numpy forwards the interval and category."""
+        try:
+            from hftbacktest.bybit import fetch_klines_numpy
+
+            with patch("hftbacktest.bybit._hftbacktest") as mock_hftbacktest:
+                fetch_klines_numpy("BTCUSD", "60", 1000, 2000, category="inverse")
+
+                mock_hftbacktest.fetch_klines_numpy.assert_called_once_with(
+                    "BTCUSD",
+                    "60",
+                    1000,
+                    2000,
+                    limit=1000,
+                    category="inverse",
+                    api_key="",
+                    secret="",
+                    recv_window=5000,
+                    max_retries=5,
+                    base_backoff_ms=50,
+                    max_backoff_ms=10000,
+                    page_delay_ms=50,
+                    base_url="https://api.bybit.com",
+                    testnet=False,
+                    proxy=None,
+                    compress=True,
+                    config=None,
+                )
+
+        except ImportError:
+            self.skipTest("hftbacktest not installed")
+
+    def test_fetch_funding_parameter_passing(self):
+        """Test that fetch_funding forwards the category and keeps its former name as an alias."""
+        try:
+            from hftbacktest.bybit import fetch_funding, fetch_funding_history
+
+            self.assertIs(fetch_funding_history, fetch_funding)
+
+            with patch("hftbacktest.bybit._hftbacktest") as mock_hftbacktest:
+                fetch_funding("BTCUSD", 1000, 2000, category="inverse")
+
+                mock_hftbacktest.fetch_funding.assert_called_once_with(
+                    "BTCUSD",
+                    1000,
+                    2000,
+                    category="inverse",
+                    api_key="",
+                    secret="",
+                    recv_window=5000,
+                    max_retries=5,
+                    base_backoff_ms=50,
+                    max_backoff_ms=10000,
+                    page_delay_ms=50,
+                    base_url="https://api.bybit.com",
+                    testnet=False,
+                    proxy=None,
+                    compress=True,
+                    config=None,
+                )
+
+        except ImportError:
+            self.skipTest("hftbacktest not installed")
+
+    def test_fetch_open_interest_parameter_passing(self):
+        """Test that fetch_open_interest forwards the interval and category."""
+        try:
+            from hftbacktest.bybit import fetch_open_interest
+
+            with patch("hftbacktest.bybit._hftbacktest") as mock_hftbacktest:
+                fetch_open_interest("BTCUSD", "1h", 1000, 2000, category="inverse")
+
+                mock_hftbacktest.fetch_open_interest.assert_called_once_with(
+                    "BTCUSD",
+                    "1h",
+                    1000,
+                    2000,
+                    category="inverse",
+                    api_key="",
+                    secret="",
+                    recv_window=5000,
+                    max_retries=5,
+                    base_backoff_ms=50,
+                    max_backoff_ms=10000,
+                    page_delay_ms=50,
+                    base_url="https://api.bybit.com",
+                    testnet=False,
+                    proxy=None,
+                    compress=True,
+                    config=None,
+                )
+
+        except ImportError:
+            self.skipTest("hftbacktest not installed")
+
+    def test_fetch_long_short_ratio_parameter_passing(self):
+        """Test that fetch_long_short_ratio forwards the period."""
+        try:
+            from hftbacktest.bybit import fetch_long_short_ratio
+
+            with patch("hftbacktest.bybit._hftbacktest") as mock_hftbacktest:
+                fetch_long_short_ratio("BTCUSDT", "5min", 1000, 2000, testnet=True)
+
+                mock_hftbacktest.fetch_long_short_ratio.assert_called_once_with(
+                    "BTCUSDT",
+                    "5min",
+                    1000,
+                    2000,
+                    category="linear",
+                    api_key="",
+                    secret="",
+                    recv_window=5000,
+                    max_retries=5,
+                    base_backoff_ms=50,
+                    max_backoff_ms=10000,
+                    page_delay_ms=50,
+                    base_url="https://api.bybit.com",
+                    testnet=True,
+                    proxy=None,
+                    compress=True,
+                    config=None,
+                )
+
+        except ImportError:
+            self.skipTest("hftbacktest not installed")
+
+    def test_fetch_recent_trades_parameter_passing(self):
+        """Test that fetch_recent_trades forwards the trade count."""
+        try:
+            from hftbacktest.bybit import fetch_recent_trades
+
+            with patch("hftbacktest.bybit._hftbacktest") as mock_hftbacktest:
+                fetch_recent_trades("BTCUSDT", 500, category="spot")
+
+                mock_hftbacktest.fetch_recent_trades.assert_called_once_with(
+                    "BTCUSDT",
+                    500,
+                    category="spot",
+                    api_key="",
+                    secret="",
+                    recv_window=5000,
+                    max_retries=5,
+                    base_backoff_ms=50,
+                    max_backoff_ms=10000,
+                    page_delay_ms=50,
+                    base_url="https://api.bybit.com",
+                    testnet=False,
+                    proxy=None,
+                    compress=True,
+                    side_as_int=False,
+                    config=None,
+                )
+
+        except ImportError:
+            self.skipTest("hftbacktest not installed")
+
+    def test_fetch_trades_page_parameter_passing(self):
+        """Test that fetch_trades_page forwards the cursor and returns the page as is."""
+        try:
+            from hftbacktest.bybit import fetch_trades_page
+
+            with patch("hftbacktest.bybit._hftbacktest") as mock_hftbacktest:
+                mock_hftbacktest.fetch_trades_page.return_value = ([], "page2")
+                config = MagicMock()
+
+                result = fetch_trades_page(
+                    "BTCUSDT", 1000, 2000, "page1", limit=500, config=config
+                )
+
+                self.assertEqual(result, ([], "page2"))
+                mock_hftbacktest.fetch_trades_page.assert_called_once_with(
+                    "BTCUSDT",
+                    1000,
+                    2000,
+                    "page1",
+                    config=config,
+                    limit=500,
                 )
 
         except ImportError:
             self.skipTest("hftbacktest not installed")
 
+    def test_fetch_instruments_parameter_passing(self):
+        """Test that fetch_instruments forwards the category."""
+        try:
+            from hftbacktest.bybit import fetch_instruments
+
+            with patch("hftbacktest.bybit._hftbacktest") as mock_hftbacktest:
+                fetch_instruments("spot", proxy="http://127.0.0.1:8080")
+
+                mock_hftbacktest.fetch_instruments.assert_called_once_with(
+                    "spot",
+                    api_key="",
+                    secret="",
+                    recv_window=5000,
+                    max_retries=5,
+                    base_backoff_ms=50,
+                    max_backoff_ms=10000,
+                    page_delay_ms=50,
+                    base_url="https://api.bybit.com",
+                    testnet=False,
+                    proxy="http://127.0.0.1:8080",
+                    compress=True,
+                    config=None,
+                )
+
+        except ImportError:
+            self.skipTest("hftbacktest not installed")
+
+    def test_record_trades_parameter_passing(self):
+        """Test that record_trades forwards the symbols as a list."""
+        try:
+            from hftbacktest.bybit import record_trades
+
+            with patch("hftbacktest.bybit._hftbacktest") as mock_hftbacktest:
+                record_trades(("BTCUSDT", "ETHUSDT"), "trades.csv", 60, category="spot")
+
+                mock_hftbacktest.record_trades.assert_called_once_with(
+                    ["BTCUSDT", "ETHUSDT"],
+                    "trades.csv",
+                    60,
+                    category="spot",
+                    testnet=False,
+                    flush_interval_ms=1000,
+                )
+
+        except ImportError:
+            self.skipTest("hftbacktest not installed")
+
+    def test_record_depth_parameter_passing(self):
+        """Test that record_depth forwards its parameters."""
+        try:
+            from hftbacktest.bybit import record_depth
+
+            with patch("hftbacktest.bybit._hftbacktest") as mock_hftbacktest:
+                record_depth("BTCUSDT", 50, "depth.npz", 60, testnet=True)
+
+                mock_hftbacktest.record_depth.assert_called_once_with(
+                    "BTCUSDT",
+                    50,
+                    "depth.npz",
+                    60,
+                    category="linear",
+                    testnet=True,
+                )
+
+        except ImportError:
+            self.skipTest("hftbacktest not installed")
+
+    def test_record_liquidations_parameter_passing(self):
+        """Test that record_liquidations forwards the symbols as a list."""
+        try:
+            from hftbacktest.bybit import record_liquidations
+
+            with patch("hftbacktest.bybit._hftbacktest") as mock_hftbacktest:
+                record_liquidations(("BTCUSDT",), "liquidations.csv", 60, testnet=True)
+
+                mock_hftbacktest.record_liquidations.assert_called_once_with(
+                    ["BTCUSDT"],
+                    "liquidations.csv",
+                    60,
+                    category="linear",
+                    testnet=True,
+                    flush_interval_ms=1000,
+                )
+
+        except ImportError:
+            self.skipTest("hftbacktest not installed")
+
+    def test_liquidations_to_events(self):
+        """Test that recorded liquidations become flagged local trade events in time order."""
+        try:
+            import os
+            import tempfile
+
+            from hftbacktest.bybit import LIQUIDATION_EVENT, liquidations_to_events
+            from hftbacktest.types import (
+                BUY_EVENT,
+                EXCH_EVENT,
+                LOCAL_EVENT,
+                SELL_EVENT,
+                TRADE_EVENT,
+            )
+
+            with tempfile.TemporaryDirectory() as tmp:
+                path = os.path.join(tmp, "liquidations.csv")
+                with open(path, "w") as f:
+                    f.write(
+                        "timestamp,symbol,side,size,price\n"
+                        "1704067200500,BTCUSDT,Sell,0.5,42000.5\n"
+                        "1704067200100,ETHUSDT,Buy,2,2300\n"
+                        "1704067200000,BTCUSDT,Buy,0.1,42100\n"
+                    )
+                events = liquidations_to_events(path, feed_latency=1000, symbol="BTCUSDT")
+
+            self.assertEqual(len(events), 2)
+            flags = LOCAL_EVENT | TRADE_EVENT | LIQUIDATION_EVENT
+            self.assertEqual(events["ev"][0], flags | BUY_EVENT)
+            self.assertEqual(events["ev"][1], flags | SELL_EVENT)
+            self.assertFalse((events["ev"] & EXCH_EVENT).any())
+            self.assertEqual(events["exch_ts"][1], 1704067200500000000)
+            self.assertEqual(events["local_ts"][1], 1704067200500001000)
+            self.assertAlmostEqual(events["px"][1], 42000.5)
+            self.assertAlmostEqual(events["qty"][1], 0.5)
+
+        except ImportError:
+            self.skipTest("hftbacktest not installed")
+
+    def test_funding_to_events(self):
+        """Test that funding rates become events with nanosecond timestamps."""
+        try:
+            from hftbacktest.bybit import FUNDING_RATE_EVENT, funding_to_events
+
+            events = funding_to_events(
+                [
+                    {"timestamp": 1704067200000, "symbol": "BTCUSDT", "funding_rate": 0.0001},
+                    {"timestamp": 1704096000000, "symbol": "BTCUSDT", "funding_rate": -0.000072},
+                ],
+                feed_latency=1000,
+            )
+
+            self.assertEqual(len(events), 2)
+            self.assertTrue((events["ev"] == FUNDING_RATE_EVENT).all())
+            self.assertEqual(events["exch_ts"][0], 1704067200000000000)
+            self.assertEqual(events["local_ts"][0], 1704067200000001000)
+            self.assertAlmostEqual(events["px"][1], -0.000072)
+
+        except ImportError:
+            self.skipTest("hftbacktest not installed")
+
+    def test_funding_to_events_latency_injector(self):
+        """Test that an injected jitter keeps the local timestamps in order."""
+        try:
+            import numpy as np
+
+            from hftbacktest.bybit import LatencyInjector, funding_to_events
+
+            funding = [
+                {"timestamp": 1704067200000 + i, "symbol": "BTCUSDT", "funding_rate": 0.0001}
+                for i in range(100)
+            ]
+            latency = LatencyInjector.uniform(0, 10_000_000, seed=3)
+            events = funding_to_events(funding, feed_latency=latency)
+
+            self.assertTrue((events["local_ts"] >= events["exch_ts"]).all())
+            self.assertTrue((np.diff(events["local_ts"]) >= 0).all())
+            np.testing.assert_array_equal(
+                events["local_ts"], funding_to_events(funding, feed_latency=latency)["local_ts"]
+            )
+
+        except ImportError:
+            self.skipTest("hftbacktest not installed")
+
+    def test_snapshot_to_events(self):
+        """Test that a snapshot becomes clear events followed by snapshot levels per side."""
+        try:
+            import numpy as np
+
+            from hftbacktest.bybit import snapshot_to_events
+            from hftbacktest.types import (
+                BUY_EVENT,
+                DEPTH_CLEAR_EVENT,
+                DEPTH_SNAPSHOT_EVENT,
+                SELL_EVENT,
+            )
+
+            snapshot = {
+                "symbol": "BTCUSDT",
+                "timestamp": 1716863719031,
+                "update_id": 230704,
+                "bid_px": np.array([65485.47, 65485.0]),
+                "bid_qty": np.array([47.081829, 0.001]),
+                "ask_px": np.array([65557.7]),
+                "ask_qty": np.array([16.606555]),
+            }
+
+            events = snapshot_to_events(snapshot, feed_latency=1000)
+
+            self.assertEqual(len(events), 5)
+            self.assertTrue(events[0]["ev"] & DEPTH_CLEAR_EVENT == DEPTH_CLEAR_EVENT)
+            self.assertTrue(events[0]["ev"] & BUY_EVENT)
+            self.assertEqual(events[0]["px"], 65485.0)
+            self.assertTrue(events[1]["ev"] & DEPTH_SNAPSHOT_EVENT == DEPTH_SNAPSHOT_EVENT)
+            self.assertEqual(events[1]["qty"], 47.081829)
+            self.assertTrue(events[3]["ev"] & SELL_EVENT)
+            self.assertEqual(events[4]["exch_ts"], 1716863719031000000)
+            self.assertEqual(events[4]["local_ts"], 1716863719031001000)
+
+        except ImportError:
+            self.skipTest("hftbacktest not installed")
+
+    def test_last_fetch_stats(self):
+        """Test that last_fetch_stats returns the extension's stats object."""
+        try:
+            from hftbacktest.bybit import last_fetch_stats
+
+            with patch("hftbacktest.bybit._hftbacktest") as mock_hftbacktest:
+                stats = MagicMock(requests=3, rate_limited=1, trades=2500)
+                mock_hftbacktest.last_fetch_stats.return_value = stats
+
+                self.assertIs(last_fetch_stats(), stats)
+                mock_hftbacktest.last_fetch_stats.assert_called_once_with()
+
+        except ImportError:
+            self.skipTest("hftbacktest not installed")
+
+    def test_init_logging(self):
+        """Test that init_logging passes the level, defaulting to info."""
+        try:
+            from hftbacktest.bybit import init_logging
+
+            with patch("hftbacktest.bybit._hftbacktest") as mock_hftbacktest:
+                init_logging()
+                init_logging("debug")
+
+                self.assertEqual(
+                    mock_hftbacktest.init_logging.call_args_list,
+                    [call("info"), call("debug")],
+                )
+
+        except ImportError:
+            self.skipTest("hftbacktest not installed")
+
+    def test_fetch_trades_concurrent_parameter_passing(self):
+        """Test that fetch_trades_concurrent forwards num_workers."""
+        try:
+            from hftbacktest.bybit import fetch_trades_concurrent
+
+            with patch("hftbacktest.bybit._hftbacktest") as mock_hftbacktest:
+                mock_hftbacktest.fetch_trades_concurrent.return_value = []
+
+                fetch_trades_concurrent("BTCUSDT", 1000, 2000, num_workers=6, time_unit="s")
+
+                args, kwargs = mock_hftbacktest.fetch_trades_concurrent.call_args
+                self.assertEqual(args, ("BTCUSDT", 1000, 2000))
+                self.assertEqual(kwargs["num_workers"], 6)
+                self.assertEqual(kwargs["time_unit"], "s")
+                self.assertEqual(kwargs["category"], "linear")
+
+        except ImportError:
+            self.skipTest("hftbacktest not installed")
+
+    def test_fetch_trades_multi_partial_failure(self):
+        """Test that fetch_trades_multi returns successes and attaches per-symbol errors."""
+        try:
+            from hftbacktest.bybit import BybitError, fetch_trades_multi
+
+            with patch("hftbacktest.bybit._hftbacktest") as mock_hftbacktest:
+                error = BybitError("HTTP error: 404 Not Found")
+                mock_hftbacktest.fetch_trades_multi.return_value = (
+                    {"BTCUSDT": []},
+                    {"NOPEUSDT": error},
+                )
+
+                result = fetch_trades_multi(("BTCUSDT", "NOPEUSDT"), 1000, 2000, concurrency=2)
+
+                self.assertEqual(dict(result), {"BTCUSDT": []})
+                self.assertEqual(result.errors, {"NOPEUSDT": error})
+                args, kwargs = mock_hftbacktest.fetch_trades_multi.call_args
+                self.assertEqual(args, (["BTCUSDT", "NOPEUSDT"], 1000, 2000))
+                self.assertEqual(kwargs["concurrency"], 2)
+                self.assertEqual(kwargs["max_requests_per_sec"], 10.0)
+
+        except ImportError:
+            self.skipTest("hftbacktest not installed")
+
     def test_fetch_trades_missing_extension(self):
         """Test that fetch_trades raises error when extension is missing."""
         try:
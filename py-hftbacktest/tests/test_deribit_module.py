@@ -0,0 +1,69 @@
+"""Test Deribit module API contract and structure."""
+
+import unittest
+from unittest.mock import patch
+
+
+class TestDeribitModuleStructure(unittest.TestCase):
+    """Test that the Deribit module is properly structured."""
+
+    def test_exception_hierarchy(self):
+        """Test that DeribitError can be caught as RuntimeError."""
+        try:
+            from hftbacktest.deribit import DeribitError
+
+            self.assertTrue(issubclass(DeribitError, RuntimeError))
+
+        except ImportError:
+            self.skipTest("hftbacktest not installed")
+
+    def test_fetch_deribit_trades_parameter_passing(self):
+        """Test that all parameters are passed to the underlying function."""
+        try:
+            from hftbacktest.deribit import fetch_deribit_trades
+
+            with patch("hftbacktest.deribit._hftbacktest") as mock_hftbacktest:
+                mock_hftbacktest.fetch_deribit_trades.return_value = []
+
+                fetch_deribit_trades("BTC-PERPETUAL", 1000, 2000, max_retries=3)
+
+                mock_hftbacktest.fetch_deribit_trades.assert_called_once_with(
+                    "BTC-PERPETUAL",
+                    1000,
+                    2000,
+                    max_retries=3,
+                    base_backoff_ms=50,
+                    max_backoff_ms=10000,
+                    base_url="https://www.deribit.com",
+                    side_as_int=False,
+                )
+
+        except ImportError:
+            self.skipTest("hftbacktest not installed")
+
+    def test_fetch_deribit_instruments_parameter_passing(self):
+        """Test that all parameters are passed to the underlying function."""
+        try:
+            from hftbacktest.deribit import fetch_deribit_instruments
+
+            with patch("hftbacktest.deribit._hftbacktest") as mock_hftbacktest:
+                mock_hftbacktest.fetch_deribit_instruments.return_value = []
+
+                fetch_deribit_instruments("BTC", "option", expired=True)
+
+                mock_hftbacktest.fetch_deribit_instruments.assert_called_once_with(
+                    "BTC",
+                    "option",
+                    expired=True,
+                    max_retries=5,
+                    base_backoff_ms=50,
+                    max_backoff_ms=10000,
+                    base_url="https://www.deribit.com",
+                )
+
+        except ImportError:
+            self.skipTest("hftbacktest not installed")
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,48 @@
+"""Test exchanges module API contract and structure."""
+
+import unittest
+from unittest.mock import patch
+
+
+class TestExchangesModuleStructure(unittest.TestCase):
+    """Test that the exchanges module is properly structured."""
+
+    def test_exception_hierarchy(self):
+        """Test that ExchangeError can be caught as RuntimeError."""
+        try:
+            from hftbacktest.exchanges import ExchangeError
+
+            self.assertTrue(issubclass(ExchangeError, RuntimeError))
+
+        except ImportError:
+            self.skipTest("hftbacktest not installed")
+
+    def test_fetch_trades_parameter_passing(self):
+        """Test that all parameters are passed to the underlying function."""
+        try:
+            from hftbacktest.exchanges import EXCHANGES, fetch_trades
+
+            self.assertEqual(EXCHANGES, ("coinbase", "kraken_futures"))
+            with patch("hftbacktest.exchanges._hftbacktest") as mock_hftbacktest:
+                mock_hftbacktest.fetch_exchange_trades.return_value = []
+
+                fetch_trades("coinbase", "BTC-USD", 1000, 2000, max_retries=3)
+
+                mock_hftbacktest.fetch_exchange_trades.assert_called_once_with(
+                    "coinbase",
+                    "BTC-USD",
+                    1000,
+                    2000,
+                    max_retries=3,
+                    base_backoff_ms=50,
+                    max_backoff_ms=10000,
+                    base_url=None,
+                    side_as_int=False,
+                )
+
+        except ImportError:
+            self.skipTest("hftbacktest not installed")
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,46 @@
+"""Test OKX module API contract and structure."""
+
+import unittest
+from unittest.mock import patch
+
+
+class TestOkxModuleStructure(unittest.TestCase):
+    """Test that the OKX module is properly structured."""
+
+    def test_exception_hierarchy(self):
+        """Test that OkxError can be caught as RuntimeError."""
+        try:
+            from hftbacktest.okx import OkxError
+
+            self.assertTrue(issubclass(OkxError, RuntimeError))
+
+        except ImportError:
+            self.skipTest("hftbacktest not installed")
+
+    def test_fetch_okx_trades_parameter_passing(self):
+        """Test that all parameters are passed to the underlying function."""
+        try:
+            from hftbacktest.okx import fetch_okx_trades
+
+            with patch("hftbacktest.okx._hftbacktest") as mock_hftbacktest:
+                mock_hftbacktest.fetch_okx_trades.return_value = []
+
+                fetch_okx_trades("BTC-USDT-SWAP", 1000, 2000, max_retries=3)
+
+                mock_hftbacktest.fetch_okx_trades.assert_called_once_with(
+                    "BTC-USDT-SWAP",
+                    1000,
+                    2000,
+                    max_retries=3,
+                    base_backoff_ms=50,
+                    max_backoff_ms=10000,
+                    base_url="https://www.okx.com",
+                    side_as_int=False,
+                )
+
+        except ImportError:
+            self.skipTest("hftbacktest not installed")
+
+
+if __name__ == "__main__":
+    unittest.main()